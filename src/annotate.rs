@@ -0,0 +1,277 @@
+//! 配置注释
+//!
+//! EZ_ANNOTATE_CONFIG 开启时，写入的服务端 / 客户端配置在各段前带 `//` 注释（JSONC），
+//! 说明该段由哪些环境变量生成、其中的密码 / 密钥用于什么，便于手工排查。
+//! 注释只按配置内容推断（入站类型、传输层、是否 REALITY），不依赖生成时的中间结果
+
+use serde_json::Value;
+
+use crate::config::protocol_env_suffix;
+use ezsingbox::autoconfig::Protocol;
+use ezsingbox::singboxconfig::jsonc::Annotations;
+
+/// 文件开头的说明
+const HEADER: &str = "由 ezsingbox 生成（EZ_ANNOTATE_CONFIG）：注释说明各段来自哪些环境变量\n\
+重新生成时整个文件会被覆盖，持久的修改请通过环境变量或配置文件（EZ_FILE）完成";
+
+/// 生成配置的注释表（label 为 server 或 client）
+pub fn config_annotations(label: &str, config: &Value) -> Annotations {
+    let client = label == "client";
+    let mut notes = Annotations::new();
+    notes.insert(String::new(), HEADER.to_string());
+    let mut note = |pointer: String, text: String| {
+        notes.insert(pointer, text);
+    };
+
+    if config.get("log").is_some() {
+        note("/log".into(), "日志：级别来自 EZ_LOG_LEVEL".into());
+    }
+    if config.get("dns").is_some() {
+        let text = if client {
+            "DNS：分流方式来自 EZ_CLIENT_DNS_MODE，广告拦截规则来自 EZ_CLIENT_ADBLOCK"
+        } else {
+            "DNS：上游来自 EZ_DNS_UPSTREAM，解析策略来自 EZ_DNS_STRATEGY，\
+             屏蔽域名来自 EZ_DNS_BLOCK_DOMAINS / EZ_DNS_BLOCK_ACTION"
+        };
+        note("/dns".into(), text.into());
+    }
+    for (i, inbound) in items(config, "inbounds") {
+        let pointer = format!("/inbounds/{}", i);
+        if client {
+            if let Some(text) = client_inbound_note(inbound) {
+                note(pointer, text.into());
+            }
+            continue;
+        }
+        let Some(protocol) = protocol_of(inbound) else {
+            continue;
+        };
+        let suffix = protocol_env_suffix(protocol);
+        note(
+            pointer.clone(),
+            format!(
+                "{} 入站：由 EZ_ENABLE_{} 启用，端口来自 EZ_{}_PORT，监听地址来自 EZ_{}_LISTEN",
+                protocol, suffix, suffix, suffix
+            ),
+        );
+        if inbound.get("users").is_some() {
+            note(format!("{}/users", pointer), users_note(protocol));
+        }
+        if let Some(tls) = inbound.get("tls") {
+            note(format!("{}/tls", pointer), tls_note(tls).into());
+        }
+        if inbound.get("obfs").is_some() {
+            note(
+                format!("{}/obfs", pointer),
+                "Salamander 混淆：密码来自 EZ_HY2_OBFS，客户端须使用相同的混淆密码".into(),
+            );
+        }
+        if inbound.get("masquerade").is_some() {
+            note(
+                format!("{}/masquerade", pointer),
+                "伪装：来自 EZ_HY2_MASQUERADE_TYPE / EZ_HY2_MASQUERADE_URL / \
+                 EZ_HY2_MASQUERADE_DIR / EZ_HY2_MASQUERADE_STATUS"
+                    .into(),
+            );
+        }
+        if inbound.get("transport").is_some() {
+            let text = match protocol {
+                Protocol::VlessGrpc => "gRPC 传输：服务名来自 EZ_VLESS_GRPC_SERVICE",
+                _ => "WebSocket 传输：路径来自 EZ_VLESS_WS_PATH，Host 来自 EZ_VLESS_WS_HOST",
+            };
+            note(format!("{}/transport", pointer), text.into());
+        }
+    }
+    for (i, outbound) in items(config, "outbounds") {
+        let pointer = format!("/outbounds/{}", i);
+        if let Some(text) = outbound_note(outbound, client) {
+            note(pointer.clone(), text);
+        }
+        if client && protocol_of(outbound).is_some() {
+            for key in ["password", "uuid"] {
+                if outbound.get(key).is_some() {
+                    note(
+                        format!("{}/{}", pointer, key),
+                        "连接凭证：与服务端入站 users 中该用户的条目相同，泄露后请更换该用户的凭证"
+                            .into(),
+                    );
+                }
+            }
+        }
+    }
+    for (i, endpoint) in items(config, "endpoints") {
+        if endpoint["type"] == "wireguard" {
+            let pointer = format!("/endpoints/{}", i);
+            note(
+                pointer.clone(),
+                "Cloudflare WARP：按 EZ_WARP_DOMAINS / EZ_WARP_SERVICES / EZ_WARP_ALL 分流到此端点"
+                    .into(),
+            );
+            if endpoint.get("private_key").is_some() {
+                note(
+                    format!("{}/private_key", pointer),
+                    "WARP 账户私钥（EZ_WARP_PRIVATE_KEY），地址与对端来自 EZ_WARP_ADDRESS / EZ_WARP_PEER"
+                        .into(),
+                );
+            }
+        }
+    }
+    if config.get("route").is_some() {
+        let text = if client {
+            "路由：决定哪些流量经代理出站，规则集镜像来自 EZ_RULE_SET_MIRROR"
+        } else {
+            "路由：GeoIP 分流来自 EZ_GEOIP_DIRECT / EZ_GEOIP_BLOCK，\
+             出站限制来自 EZ_LIMIT_*，规则集镜像来自 EZ_RULE_SET_MIRROR"
+        };
+        note("/route".into(), text.into());
+    }
+    if config.get("experimental").is_some() {
+        note(
+            "/experimental".into(),
+            "Clash API：监听地址来自 EZ_CLASH_API，访问密钥来自 EZ_CLASH_API_SECRET".into(),
+        );
+    }
+    notes
+}
+
+/// 数组字段中的元素及其下标
+fn items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = (usize, &'a Value)> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+}
+
+/// 按类型与传输层推断入站 / 出站的协议
+fn protocol_of(item: &Value) -> Option<Protocol> {
+    match item["type"].as_str()? {
+        "anytls" => Some(Protocol::AnyTls),
+        "hysteria2" => Some(Protocol::Hysteria2),
+        "tuic" => Some(Protocol::Tuic),
+        "vless" => Some(match item["transport"]["type"].as_str() {
+            Some("ws") => Protocol::VlessWs,
+            Some("grpc") => Protocol::VlessGrpc,
+            _ => Protocol::VlessReality,
+        }),
+        _ => None,
+    }
+}
+
+/// 入站用户凭证的说明
+fn users_note(protocol: Protocol) -> String {
+    let secret = match protocol {
+        Protocol::AnyTls | Protocol::Hysteria2 => "password",
+        Protocol::Tuic => "uuid + password",
+        _ => "uuid",
+    };
+    format!(
+        "用户：来自 EZ_USERS / EZ_USERS_JSON（单用户为 EZ_USER / EZ_PASSWORD），\
+         未指定的凭证自动生成并保存在状态文件（EZ_STATE_PATH）\n\
+         {} 是客户端连接此入站的凭证，与分享链接 / 订阅中的相同",
+        secret
+    )
+}
+
+/// 入站 TLS 的说明
+fn tls_note(tls: &Value) -> &'static str {
+    if tls["reality"]["enabled"] == true {
+        "REALITY：private_key 为服务端私钥（EZ_REALITY_PRIVATE_KEY，客户端使用对应的公钥），\n\
+         short_id 来自 EZ_REALITY_SHORT_IDS，握手目标来自 EZ_VLESS_HANDSHAKE_SERVER / EZ_VLESS_HANDSHAKE_PORT"
+    } else if tls.get("acme").is_some() {
+        "TLS：EZ_DOMAIN 的 ACME 证书（EZ_ACME_PROVIDER / EZ_ACME_EMAIL），\
+         证书与账户私钥保存在 EZ_ACME_DATA_DIR"
+    } else {
+        "TLS：证书对应的域名来自 EZ_DOMAIN"
+    }
+}
+
+/// 客户端入站的说明
+fn client_inbound_note(inbound: &Value) -> Option<&'static str> {
+    match inbound["type"].as_str()? {
+        "mixed" => {
+            Some("本地 HTTP / SOCKS 代理：来自 EZ_CLIENT_MIXED_LISTEN / EZ_CLIENT_MIXED_PORT")
+        }
+        "tun" => Some(
+            "TUN 入站：按 EZ_CLIENT_PLATFORM 生成，分应用代理来自 \
+             EZ_CLIENT_INCLUDE_PACKAGES / EZ_CLIENT_EXCLUDE_PACKAGES",
+        ),
+        _ => None,
+    }
+}
+
+/// 出站的说明
+fn outbound_note(outbound: &Value, client: bool) -> Option<String> {
+    if let Some(protocol) = protocol_of(outbound).filter(|_| client) {
+        let suffix = protocol_env_suffix(protocol);
+        return Some(format!(
+            "连接服务器的 {} 出站：协议来自 EZ_CLIENT_PROTOCOL，用户来自 EZ_CLIENT_USER，\
+             服务器地址可由 EZ_CLIENT_SERVER_OVERRIDE_{} 覆盖",
+            protocol, suffix
+        ));
+    }
+    let text = match (outbound["type"].as_str()?, outbound["tag"].as_str()) {
+        (_, Some("relay")) => "中继出站：流量经 EZ_RELAY_UPSTREAM 转发",
+        (_, Some("chain-front")) => "链式代理的前置节点：来自 EZ_CLIENT_CHAIN_FRONT",
+        ("direct", _) => "直连出站",
+        ("block", _) => "拦截出站：EZ_GEOIP_BLOCK、EZ_LIMIT_* 等规则命中的流量",
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezsingbox::singboxconfig::jsonc::{from_jsonc_str, to_jsonc_string};
+    use serde_json::json;
+
+    #[test]
+    fn test_config_annotations() {
+        let config = json!({
+            "log": { "level": "info" },
+            "inbounds": [{
+                "type": "vless",
+                "tag": "vless-ws-in",
+                "users": [{ "name": "u", "uuid": "x" }],
+                "transport": { "type": "ws", "path": "/ws" },
+                "tls": { "enabled": true, "acme": { "domain": ["ex.com"] } }
+            }, {
+                "type": "vless",
+                "tag": "vless-reality-in",
+                "tls": { "enabled": true, "reality": { "enabled": true, "private_key": "k" } }
+            }],
+            "outbounds": [{ "type": "direct", "tag": "direct" }],
+            "route": {}
+        });
+        let notes = config_annotations("server", &config);
+        assert!(notes[""].contains("EZ_ANNOTATE_CONFIG"));
+        assert!(notes["/inbounds/0"].contains("EZ_VLESS_WS_PORT"));
+        assert!(notes["/inbounds/0/users"].starts_with("用户"));
+        assert!(notes["/inbounds/0/transport"].contains("EZ_VLESS_WS_PATH"));
+        assert!(notes["/inbounds/0/tls"].contains("ACME"));
+        assert!(notes["/inbounds/1"].contains("EZ_ENABLE_VLESS_REALITY"));
+        assert!(notes["/inbounds/1/tls"].contains("EZ_REALITY_PRIVATE_KEY"));
+        assert!(notes.contains_key("/outbounds/0"));
+        assert!(!notes.contains_key("/dns"));
+
+        // 注释不影响内容
+        let text = to_jsonc_string(&config, &notes);
+        assert_eq!(from_jsonc_str::<Value>(&text).unwrap(), config);
+
+        let client = json!({
+            "inbounds": [{ "type": "mixed", "tag": "mixed-in" }],
+            "outbounds": [{ "type": "tuic", "tag": "proxy", "uuid": "x", "password": "p" }]
+        });
+        let notes = config_annotations("client", &client);
+        assert!(notes["/inbounds/0"].contains("EZ_CLIENT_MIXED_PORT"));
+        assert!(notes["/outbounds/0"].contains("EZ_CLIENT_SERVER_OVERRIDE_TUIC"));
+        assert!(notes.contains_key("/outbounds/0/uuid"));
+        assert!(notes.contains_key("/outbounds/0/password"));
+    }
+}
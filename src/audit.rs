@@ -0,0 +1,218 @@
+//! 订阅访问审计
+//!
+//! 订阅服务把每次请求（时间、来源 IP、User-Agent、认证方式与用户、请求格式、状态码）
+//! 追加到审计日志（EZ_AUDIT_LOG，每行一个 JSON），`ezsingbox audit` 按用户与时间筛选后输出，
+//! 便于查看谁在何时拉取了配置。日志只追加，不记录令牌、签名等凭据本身
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write as _};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::cert::format_utc;
+use crate::env::env_string;
+use crate::utils::ensure_parent_dir;
+
+/// 审计日志默认路径
+pub const DEFAULT_AUDIT_LOG: &str = "./ezsingbox-audit.log";
+
+/// 审计日志路径（EZ_AUDIT_LOG）
+pub fn audit_path_from_env() -> String {
+    env_string("EZ_AUDIT_LOG").unwrap_or_else(|| DEFAULT_AUDIT_LOG.to_string())
+}
+
+/// 请求通过的认证方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthMethod {
+    /// 未认证（未要求认证或认证失败）
+    #[default]
+    None,
+    /// Basic 认证
+    Basic,
+    /// 访问令牌
+    Token,
+    /// 签名链接
+    Signed,
+}
+
+impl fmt::Display for AuthMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthMethod::None => write!(f, "-"),
+            AuthMethod::Basic => write!(f, "basic"),
+            AuthMethod::Token => write!(f, "token"),
+            AuthMethod::Signed => write!(f, "signed"),
+        }
+    }
+}
+
+/// 一次订阅请求的审计记录
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// 请求时间（Unix 时间戳）
+    pub at: i64,
+    /// 来源 IP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    /// User-Agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// 认证方式
+    #[serde(default)]
+    pub auth: AuthMethod,
+    /// 请求的用户（令牌 / 签名链接所属用户或 `?user=`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// 请求格式（sing-box 或 `?format=` 的取值）
+    pub format: String,
+    /// HTTP 状态码
+    pub status: u16,
+}
+
+/// 追加一条审计记录
+pub fn append_audit(path: &str, entry: &AuditEntry) -> io::Result<()> {
+    ensure_parent_dir(path)?;
+    let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// 读取审计日志（文件不存在时为空，无法解析的行跳过）
+pub fn load_audit(path: &str) -> io::Result<Vec<AuditEntry>> {
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!(path, line = i + 1, error = %e, "跳过无法解析的审计记录"),
+        }
+    }
+    Ok(entries)
+}
+
+/// 审计记录筛选条件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditFilter {
+    /// 只保留该用户的记录
+    pub user: Option<String>,
+    /// 只保留该时间（Unix 时间戳）之后的记录
+    pub since: Option<i64>,
+    /// 只保留最近的若干条
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    /// 按条件筛选记录（保持时间顺序）
+    pub fn apply<'a>(&self, entries: &'a [AuditEntry]) -> Vec<&'a AuditEntry> {
+        let matched: Vec<&AuditEntry> = entries
+            .iter()
+            .filter(|e| self.user.is_none() || e.user == self.user)
+            .filter(|e| self.since.is_none_or(|t| e.at >= t))
+            .collect();
+        let skip = self.limit.map_or(0, |n| matched.len().saturating_sub(n));
+        matched.into_iter().skip(skip).collect()
+    }
+}
+
+/// 渲染审计记录表
+pub fn render_audit(entries: &[&AuditEntry]) -> String {
+    if entries.is_empty() {
+        return "（无审计记录）\n".to_string();
+    }
+    let mut out = format!(
+        "{:<24} {:<40} {:<7} {:<16} {:<13} {:<4} {}\n",
+        "时间", "来源", "认证", "用户", "格式", "状态", "User-Agent"
+    );
+    for e in entries {
+        out.push_str(&format!(
+            "{:<24} {:<40} {:<7} {:<16} {:<13} {:<4} {}\n",
+            format_utc(e.at),
+            e.ip.as_deref().unwrap_or("-"),
+            e.auth,
+            e.user.as_deref().unwrap_or("-"),
+            e.format,
+            e.status,
+            e.user_agent.as_deref().unwrap_or("-")
+        ));
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log() {
+        let dir = std::env::temp_dir().join(format!("ezsingbox-audit-{}", std::process::id()));
+        let path = dir.join("audit.log").to_string_lossy().into_owned();
+        assert!(load_audit(&path).unwrap().is_empty());
+
+        for (at, user, auth) in [
+            (1000, Some("alice"), AuthMethod::Token),
+            (2000, None, AuthMethod::Basic),
+            (3000, Some("alice"), AuthMethod::Signed),
+        ] {
+            let entry = AuditEntry {
+                at,
+                ip: Some("203.0.113.7".to_string()),
+                user_agent: Some("sing-box 1.12.0".to_string()),
+                auth,
+                user: user.map(str::to_string),
+                format: "sing-box".to_string(),
+                status: 200,
+            };
+            append_audit(&path, &entry).unwrap();
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let entries = load_audit(&path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].auth, AuthMethod::Token);
+
+        let alice = AuditFilter {
+            user: Some("alice".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(alice.apply(&entries).len(), 2);
+        let recent = AuditFilter {
+            since: Some(1500),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let recent = recent.apply(&entries);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].at, 3000);
+        let table = render_audit(&recent);
+        assert!(table.contains("signed"));
+        assert!(table.contains("203.0.113.7"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
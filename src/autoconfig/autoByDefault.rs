@@ -12,17 +12,23 @@
 use std::net::IpAddr;
 
 use crate::singboxconfig::inbound::{
-    AnyTlsInbound, CongestionControl, Hysteria2Inbound, TuicInbound, VlessFlow, VlessInbound,
-    VlessUser,
+    AnyTlsInbound, CongestionControl, Hysteria2Inbound, Hysteria2Masquerade, TuicInbound,
+    UdpRelayMode, VlessFlow, VlessInbound, VlessUser,
 };
 use crate::singboxconfig::shared::{
-    AcmeConfig, InboundTlsConfig, RealityHandshake, RealityInboundConfig,
+    AcmeConfig, AcmeExternalAccount, AcmeProvider, Dns01Challenge, GrpcTransport, InboundTlsConfig,
+    ListenFields, MultiplexInbound, MultiplexOutbound, RealityHandshake, RealityInboundConfig,
+    TcpBrutal, V2RayTransport, WebSocketTransport,
 };
-use crate::singboxconfig::types::TuicUser;
+use crate::singboxconfig::types::{Duration, RoutingMark, TuicUser};
 
+use super::domainprovider::DomainProvider;
+use super::ipdetect::IpDetector;
+use super::preset::Preset;
+use super::protocol::{Protocol, Transport};
 use super::tools::{
-    PublicIpError, generate_hex_string, generate_password, generate_sslip_domain, generate_uuid,
-    get_public_ip,
+    DEFAULT_ACME_DATA_DIR, PublicIpError, generate_hex_string, generate_password,
+    generate_sslip_domain, generate_uuid, get_public_ip, get_public_ip_with, pick_ephemeral_port,
 };
 
 //============================================================================
@@ -49,35 +55,6 @@ fn generate_random_email() -> String {
     format!("{}@acme.invalid", random_part)
 }
 
-//============================================================================
-// 协议类型
-//============================================================================
-
-/// 支持的协议类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Protocol {
-    /// AnyTLS 协议
-    AnyTls,
-    /// Hysteria2 协议
-    Hysteria2,
-    /// TUIC 协议
-    Tuic,
-    /// VLESS-Vision-uTLS-REALITY 协议
-    VlessReality,
-}
-
-impl Protocol {
-    /// 获取协议的默认标签
-    pub fn default_tag(&self) -> &'static str {
-        match self {
-            Protocol::AnyTls => "anytls-in",
-            Protocol::Hysteria2 => "hy2-in",
-            Protocol::Tuic => "tuic-in",
-            Protocol::VlessReality => "vless-reality-in",
-        }
-    }
-}
-
 //============================================================================
 // 用户信息
 //============================================================================
@@ -91,6 +68,8 @@ pub struct GeneratedUser {
     pub password: String,
     /// UUID（仅 TUIC 使用）
     pub uuid: Option<String>,
+    /// 过期时间（Unix 时间戳，None 表示永不过期）
+    pub expires_at: Option<i64>,
 }
 
 impl GeneratedUser {
@@ -100,6 +79,7 @@ impl GeneratedUser {
             name: name.into(),
             password: generate_password(),
             uuid: None,
+            expires_at: None,
         }
     }
 
@@ -109,6 +89,7 @@ impl GeneratedUser {
             name: name.into(),
             password: generate_password(),
             uuid: Some(generate_uuid()),
+            expires_at: None,
         }
     }
 
@@ -118,6 +99,7 @@ impl GeneratedUser {
             name: name.into(),
             password: password.into(),
             uuid: None,
+            expires_at: None,
         }
     }
 
@@ -131,8 +113,20 @@ impl GeneratedUser {
             name: name.into(),
             password: password.into(),
             uuid: Some(uuid.into()),
+            expires_at: None,
         }
     }
+
+    /// 设置过期时间（Unix 时间戳）
+    pub fn with_expires_at(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// 在给定时间（Unix 时间戳）是否已过期
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|t| now >= t)
+    }
 }
 
 //============================================================================
@@ -148,6 +142,8 @@ pub struct AutoDefaultResult {
     pub domain: String,
     /// 使用的端口
     pub port: u16,
+    /// 端口是否由系统临时分配（请求端口为 0）
+    pub ephemeral_port: bool,
     /// 生成的用户列表
     pub users: Vec<GeneratedUser>,
 }
@@ -179,6 +175,8 @@ pub struct TuicAutoResult {
     pub info: AutoDefaultResult,
     /// 生成的入站配置
     pub inbound: TuicInbound,
+    /// 客户端 UDP 中继模式
+    pub udp_relay_mode: UdpRelayMode,
 }
 
 /// VLESS-Vision-uTLS-REALITY 自动配置结果
@@ -192,14 +190,33 @@ pub struct VlessRealityAutoResult {
     pub private_key: String,
     /// REALITY 公钥（客户端使用）
     pub public_key: String,
-    /// REALITY 短 ID
+    /// REALITY 短 ID（客户端使用）
     pub short_id: String,
+    /// 入站接受的全部 REALITY 短 ID
+    pub short_ids: Vec<String>,
     /// 握手服务器地址
     pub handshake_server: String,
     /// 握手服务器端口
     pub handshake_port: u16,
 }
 
+/// VLESS over WebSocket / gRPC 自动配置结果
+#[derive(Debug)]
+pub struct VlessTransportAutoResult {
+    /// 基础信息
+    pub info: AutoDefaultResult,
+    /// 生成的入站配置
+    pub inbound: VlessInbound,
+    /// WebSocket 路径或 gRPC 服务名
+    pub path: String,
+    /// 客户端多路复用设置（服务端已接受多路复用时存在）
+    pub multiplex: Option<MultiplexOutbound>,
+    /// 客户端连接的 CDN 地址（经 CDN 转发时存在，SNI 仍为源站域名）
+    pub cdn_host: Option<String>,
+    /// WebSocket Host 请求头（未设置时客户端使用 SNI）
+    pub host: Option<String>,
+}
+
 /// REALITY 密钥对
 #[derive(Debug, Clone)]
 pub struct RealityKeyPair {
@@ -209,8 +226,28 @@ pub struct RealityKeyPair {
     pub public_key: String,
 }
 
-/// 生成 REALITY 密钥对
-/// 注意：这是一个简化实现，生产环境建议使用 `sing-box generate reality-keypair`
+impl RealityKeyPair {
+    /// 从已有私钥（Base64 URL 编码）恢复密钥对，用于重新生成配置时保持客户端公钥不变
+    pub fn from_private_key(private_key: &str) -> Result<Self, String> {
+        use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+        let bytes = URL_SAFE_NO_PAD
+            .decode(private_key.trim().trim_end_matches('='))
+            .map_err(|e| format!("REALITY 私钥不是有效的 Base64: {}", e))?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|b: Vec<u8>| format!("REALITY 私钥应为 32 字节，实际为 {} 字节", b.len()))?;
+
+        let secret = x25519_dalek::StaticSecret::from(bytes);
+        let public_key = x25519_dalek::PublicKey::from(&secret);
+        Ok(Self {
+            private_key: URL_SAFE_NO_PAD.encode(bytes),
+            public_key: URL_SAFE_NO_PAD.encode(public_key.as_bytes()),
+        })
+    }
+}
+
+/// 使用内置 X25519 实现生成 REALITY 密钥对
 pub fn generate_reality_keypair() -> RealityKeyPair {
     use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
     use rand::RngCore;
@@ -235,11 +272,79 @@ pub fn generate_reality_keypair() -> RealityKeyPair {
     }
 }
 
+/// 调用 `sing-box generate reality-keypair` 生成密钥对
+/// sing-box 不可用或输出无法解析时回退到内置实现
+pub fn generate_reality_keypair_with_sing_box(sing_box_bin: &str) -> RealityKeyPair {
+    std::process::Command::new(sing_box_bin)
+        .args(["generate", "reality-keypair"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_reality_keypair_output(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_else(generate_reality_keypair)
+}
+
+/// 解析 `sing-box generate reality-keypair` 的输出
+/// 格式为 `PrivateKey: ...` 与 `PublicKey: ...` 两行，公钥需与私钥匹配
+fn parse_reality_keypair_output(output: &str) -> Option<RealityKeyPair> {
+    let field = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .map(|v| v.trim().to_string())
+    };
+    let keypair = RealityKeyPair::from_private_key(&field("PrivateKey:")?).ok()?;
+    (keypair.public_key == field("PublicKey:")?).then_some(keypair)
+}
+
 /// 生成 REALITY 短 ID（8位十六进制）
 pub fn generate_short_id() -> String {
     generate_hex_string(4) // 4 bytes = 8 hex chars
 }
 
+/// REALITY 短 ID 最大字节数
+pub const MAX_SHORT_ID_BYTES: usize = 8;
+
+/// 生成 `count` 个互不相同、长度为 `len` 字节的 REALITY 短 ID
+pub fn generate_short_ids(count: usize, len: usize) -> Result<Vec<String>, String> {
+    if len > MAX_SHORT_ID_BYTES {
+        return Err(format!(
+            "REALITY 短 ID 长度不能超过 {} 字节",
+            MAX_SHORT_ID_BYTES
+        ));
+    }
+    if count == 0 {
+        return Err("REALITY 短 ID 数量至少为 1".to_string());
+    }
+    // len 字节最多能组成 256^len 个不同的短 ID
+    if len < MAX_SHORT_ID_BYTES && count as u64 > 1u64 << (8 * len) {
+        return Err(format!("{} 字节的短 ID 无法生成 {} 个不同的值", len, count));
+    }
+
+    let mut ids: Vec<String> = Vec::with_capacity(count);
+    while ids.len() < count {
+        let id = generate_hex_string(len);
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// 校验 REALITY 短 ID：0–16 位偶数长度的十六进制字符串
+pub fn validate_short_id(id: &str) -> Result<(), String> {
+    if id.len() > MAX_SHORT_ID_BYTES * 2
+        || !id.len().is_multiple_of(2)
+        || !id.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(format!(
+            "无效的 REALITY 短 ID: {}（应为 0–16 位偶数长度的十六进制字符串）",
+            id
+        ));
+    }
+    Ok(())
+}
+
 /// 多协议自动配置结果
 #[derive(Debug)]
 pub struct MultiProtocolResult {
@@ -255,6 +360,32 @@ pub struct MultiProtocolResult {
     pub tuic: Option<TuicAutoResult>,
     /// VLESS-Reality 配置（如果启用）
     pub vless_reality: Option<VlessRealityAutoResult>,
+    /// VLESS-WebSocket 配置（如果启用）
+    pub vless_ws: Option<VlessTransportAutoResult>,
+    /// VLESS-gRPC 配置（如果启用）
+    pub vless_grpc: Option<VlessTransportAutoResult>,
+}
+
+impl MultiProtocolResult {
+    /// 获取协议的监听端口（协议未启用时返回 None）
+    pub fn port(&self, protocol: Protocol) -> Option<u16> {
+        match protocol {
+            Protocol::AnyTls => self.anytls.as_ref().map(|r| r.info.port),
+            Protocol::Hysteria2 => self.hysteria2.as_ref().map(|r| r.info.port),
+            Protocol::Tuic => self.tuic.as_ref().map(|r| r.info.port),
+            Protocol::VlessReality => self.vless_reality.as_ref().map(|r| r.info.port),
+            Protocol::VlessWs => self.vless_ws.as_ref().map(|r| r.info.port),
+            Protocol::VlessGrpc => self.vless_grpc.as_ref().map(|r| r.info.port),
+        }
+    }
+
+    /// 已启用的协议（按默认优先级排序）
+    pub fn enabled_protocols(&self) -> Vec<Protocol> {
+        Protocol::ALL
+            .into_iter()
+            .filter(|p| self.port(*p).is_some())
+            .collect()
+    }
 }
 
 //============================================================================
@@ -270,6 +401,13 @@ pub enum AutoDefaultError {
     NoAvailablePort,
     /// 配置生成失败
     ConfigError(String),
+    /// 两个协议使用了同一传输层的同一端口
+    PortConflict {
+        port: u16,
+        transport: Transport,
+        first: Protocol,
+        second: Protocol,
+    },
 }
 
 impl std::fmt::Display for AutoDefaultError {
@@ -278,6 +416,16 @@ impl std::fmt::Display for AutoDefaultError {
             AutoDefaultError::PublicIpError(msg) => write!(f, "获取公网 IP 失败: {}", msg),
             AutoDefaultError::NoAvailablePort => write!(f, "无可用端口"),
             AutoDefaultError::ConfigError(msg) => write!(f, "配置生成失败: {}", msg),
+            AutoDefaultError::PortConflict {
+                port,
+                transport,
+                first,
+                second,
+            } => write!(
+                f,
+                "端口冲突: {} 与 {} 都使用 {} 端口 {}",
+                first, second, transport, port
+            ),
         }
     }
 }
@@ -333,18 +481,72 @@ pub struct AutoDefault {
     down_mbps: Option<u32>,
     /// Hysteria2 特有：启用混淆
     enable_obfs: bool,
-    /// Hysteria2 特有：伪装 URL
-    masquerade_url: Option<String>,
+    /// Hysteria2 特有：伪装
+    masquerade: Option<Hysteria2Masquerade>,
+    /// Hysteria2 特有：忽略客户端带宽
+    ignore_client_bandwidth: Option<bool>,
     /// TUIC 特有：拥塞控制算法
     congestion_control: Option<CongestionControl>,
+    /// TUIC 特有：认证超时
+    tuic_auth_timeout: Option<Duration>,
+    /// TUIC 特有：0-RTT 握手
+    tuic_zero_rtt: Option<bool>,
+    /// TUIC 特有：心跳间隔
+    tuic_heartbeat: Option<Duration>,
+    /// TUIC 特有：客户端 UDP 中继模式
+    tuic_udp_relay_mode: Option<UdpRelayMode>,
     /// VLESS Reality 特有：握手服务器
     reality_handshake_server: Option<String>,
     /// VLESS Reality 特有：握手服务器端口
     reality_handshake_port: Option<u16>,
     /// VLESS Reality 特有：服务器名称（SNI）
     reality_server_name: Option<String>,
+    /// VLESS Reality 特有：自动生成的短 ID 数量与字节长度
+    reality_short_id_spec: Option<(usize, usize)>,
+    /// VLESS Reality 特有：用户指定的短 ID
+    reality_custom_short_ids: Vec<String>,
+    /// VLESS Reality 特有：复用的私钥
+    reality_private_key: Option<String>,
+    /// VLESS Reality 特有：用于生成密钥对的 sing-box 路径
+    reality_keygen_bin: Option<String>,
+    /// VLESS-WebSocket 特有：路径
+    ws_path: Option<String>,
+    /// VLESS-WebSocket 特有：Host 请求头
+    ws_host: Option<String>,
+    /// VLESS-gRPC 特有：服务名
+    grpc_service_name: Option<String>,
+    /// VLESS WebSocket / gRPC 特有：客户端连接的 CDN 地址
+    cdn_host: Option<String>,
+    /// VLESS WebSocket / gRPC 特有：TCP Brutal 上下行带宽
+    tcp_brutal: Option<(u32, u32)>,
+    /// VLESS WebSocket / gRPC 特有：客户端多路复用设置
+    multiplex: Option<MultiplexOutbound>,
     /// ACME 邮箱地址
     acme_email: Option<String>,
+    /// ACME DNS-01 挑战（设置后禁用 HTTP 与 TLS-ALPN 挑战）
+    acme_dns01: Option<Dns01Challenge>,
+    /// ACME CA 提供商（默认 Let's Encrypt）
+    acme_provider: Option<AcmeProvider>,
+    /// ACME 数据目录（默认 ./acme）
+    acme_data_directory: Option<String>,
+    /// ACME 外部账户绑定（EAB）
+    acme_external_account: Option<AcmeExternalAccount>,
+    /// 监听选项：TCP 快速打开
+    tcp_fast_open: Option<bool>,
+    /// 监听选项：UDP NAT 过期时间
+    udp_timeout: Option<Duration>,
+    /// 监听选项：协议嗅探（默认开启）
+    sniff: bool,
+    /// 监听选项：使用嗅探到的域名覆盖目标地址（默认开启）
+    sniff_override_destination: bool,
+    /// 监听选项：监听地址（不设置则使用协议的默认地址）
+    listen: Option<String>,
+    /// 监听选项：绑定的网络接口
+    bind_interface: Option<String>,
+    /// 监听选项：netfilter 路由标记
+    routing_mark: Option<RoutingMark>,
+    /// 监听选项：网络命名空间
+    netns: Option<String>,
 }
 
 impl AutoDefault {
@@ -368,6 +570,16 @@ impl AutoDefault {
         Self::new(Protocol::VlessReality)
     }
 
+    /// 创建 VLESS over WebSocket 自动配置
+    pub fn vless_ws() -> Self {
+        Self::new(Protocol::VlessWs)
+    }
+
+    /// 创建 VLESS over gRPC 自动配置
+    pub fn vless_grpc() -> Self {
+        Self::new(Protocol::VlessGrpc)
+    }
+
     /// 创建指定协议的自动配置
     fn new(protocol: Protocol) -> Self {
         Self {
@@ -380,12 +592,39 @@ impl AutoDefault {
             up_mbps: None,
             down_mbps: None,
             enable_obfs: false,
-            masquerade_url: None,
+            masquerade: None,
             congestion_control: None,
+            tuic_auth_timeout: None,
+            tuic_zero_rtt: None,
+            tuic_heartbeat: None,
+            tuic_udp_relay_mode: None,
             reality_handshake_server: None,
             reality_handshake_port: None,
             reality_server_name: None,
+            reality_short_id_spec: None,
+            reality_custom_short_ids: Vec::new(),
+            reality_private_key: None,
+            reality_keygen_bin: None,
             acme_email: None,
+            acme_dns01: None,
+            acme_provider: None,
+            acme_data_directory: None,
+            acme_external_account: None,
+            ws_path: None,
+            tcp_brutal: None,
+            multiplex: None,
+            ignore_client_bandwidth: None,
+            ws_host: None,
+            grpc_service_name: None,
+            cdn_host: None,
+            tcp_fast_open: None,
+            udp_timeout: None,
+            sniff: true,
+            sniff_override_destination: true,
+            listen: None,
+            bind_interface: None,
+            routing_mark: None,
+            netns: None,
         }
     }
 
@@ -408,6 +647,7 @@ impl AutoDefault {
     }
 
     /// 设置端口
+    /// 端口为 0 时由系统分配临时端口，并在结果中记录实际端口
     pub fn port(mut self, port: u16) -> Self {
         self.port = Some(port);
         self
@@ -427,7 +667,7 @@ impl AutoDefault {
 
     /// 添加用户（自动生成密码/UUID）
     pub fn add_user(mut self, name: impl Into<String>) -> Self {
-        let user = if self.protocol == Protocol::Tuic || self.protocol == Protocol::VlessReality {
+        let user = if self.protocol.requires_uuid() {
             GeneratedUser::with_uuid(name)
         } else {
             GeneratedUser::new(name)
@@ -443,7 +683,7 @@ impl AutoDefault {
         password: impl Into<String>,
     ) -> Self {
         let mut user = GeneratedUser::with_password(name, password);
-        if self.protocol == Protocol::Tuic || self.protocol == Protocol::VlessReality {
+        if self.protocol.requires_uuid() {
             user.uuid = Some(generate_uuid());
         }
         self.users.push(user);
@@ -468,6 +708,44 @@ impl AutoDefault {
         self
     }
 
+    /// 使用 DNS-01 挑战申请证书
+    pub fn acme_dns01(mut self, challenge: Dns01Challenge) -> Self {
+        self.acme_dns01 = Some(challenge);
+        self
+    }
+
+    /// 使用 Cloudflare DNS-01 挑战申请证书
+    pub fn acme_dns01_cloudflare(self, api_token: impl Into<String>) -> Self {
+        self.acme_dns01(Dns01Challenge::cloudflare(api_token))
+    }
+
+    /// 使用阿里云 DNS-01 挑战申请证书
+    pub fn acme_dns01_alidns(
+        self,
+        access_key_id: impl Into<String>,
+        access_key_secret: impl Into<String>,
+    ) -> Self {
+        self.acme_dns01(Dns01Challenge::alidns(access_key_id, access_key_secret))
+    }
+
+    /// 设置 ACME CA 提供商（letsencrypt / zerossl / 自定义 URL）
+    pub fn acme_provider(mut self, provider: AcmeProvider) -> Self {
+        self.acme_provider = Some(provider);
+        self
+    }
+
+    /// 设置 ACME 数据目录（所有入站共享同一目录以复用证书）
+    pub fn acme_data_directory(mut self, dir: impl Into<String>) -> Self {
+        self.acme_data_directory = Some(dir.into());
+        self
+    }
+
+    /// 设置 ACME 外部账户绑定（EAB，ZeroSSL 等 CA 需要）
+    pub fn acme_eab(mut self, key_id: impl Into<String>, mac_key: impl Into<String>) -> Self {
+        self.acme_external_account = Some(AcmeExternalAccount::new(key_id, mac_key));
+        self
+    }
+
     //========== Hysteria2 特有方法 ==========
 
     /// 设置带宽限制（Hysteria2）
@@ -484,8 +762,19 @@ impl AutoDefault {
     }
 
     /// 设置伪装 URL（Hysteria2）
-    pub fn masquerade(mut self, url: impl Into<String>) -> Self {
-        self.masquerade_url = Some(url.into());
+    pub fn masquerade(self, url: impl Into<String>) -> Self {
+        self.masquerade_config(Hysteria2Masquerade::Url(url.into()))
+    }
+
+    /// 设置伪装：反向代理、文件服务器或固定响应（Hysteria2）
+    pub fn masquerade_config(mut self, masquerade: Hysteria2Masquerade) -> Self {
+        self.masquerade = Some(masquerade);
+        self
+    }
+
+    /// 忽略客户端带宽，由服务端使用 BBR 拥塞控制（Hysteria2）
+    pub fn ignore_client_bandwidth(mut self, ignore: bool) -> Self {
+        self.ignore_client_bandwidth = Some(ignore);
         self
     }
 
@@ -509,6 +798,31 @@ impl AutoDefault {
         self
     }
 
+    /// 设置认证超时（TUIC，默认 3s）
+    pub fn auth_timeout(mut self, timeout: impl Into<Duration>) -> Self {
+        self.tuic_auth_timeout = Some(timeout.into());
+        self
+    }
+
+    /// 启用/禁用 0-RTT 握手（TUIC）
+    /// 0-RTT 容易受到重放攻击，默认禁用
+    pub fn zero_rtt_handshake(mut self, enabled: bool) -> Self {
+        self.tuic_zero_rtt = Some(enabled);
+        self
+    }
+
+    /// 设置心跳间隔（TUIC，默认 10s）
+    pub fn heartbeat(mut self, interval: impl Into<Duration>) -> Self {
+        self.tuic_heartbeat = Some(interval.into());
+        self
+    }
+
+    /// 设置客户端 UDP 中继模式（TUIC，默认 native）
+    pub fn udp_relay_mode(mut self, mode: UdpRelayMode) -> Self {
+        self.tuic_udp_relay_mode = Some(mode);
+        self
+    }
+
     // ========== VLESS Reality 特有方法 ==========
 
     /// 设置 REALITY 握手服务器（VLESS Reality）
@@ -526,6 +840,130 @@ impl AutoDefault {
         self
     }
 
+    /// 生成多个 REALITY 短 ID（VLESS Reality）
+    /// `len` 为每个短 ID 的字节数（0–8），默认生成 1 个 4 字节短 ID
+    pub fn short_ids(mut self, count: usize, len: usize) -> Self {
+        self.reality_short_id_spec = Some((count, len));
+        self
+    }
+
+    /// 使用指定的 REALITY 短 ID（VLESS Reality）
+    /// 第一个短 ID 用于客户端配置
+    pub fn custom_short_ids<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.reality_custom_short_ids = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 复用已有的 REALITY 私钥（VLESS Reality）
+    /// 不设置则每次生成新的密钥对
+    pub fn reality_private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.reality_private_key = Some(private_key.into());
+        self
+    }
+
+    /// 使用 sing-box 生成 REALITY 密钥对（VLESS Reality）
+    /// sing-box 不可用时回退到内置实现
+    pub fn reality_keygen_sing_box(mut self, sing_box_bin: impl Into<String>) -> Self {
+        self.reality_keygen_bin = Some(sing_box_bin.into());
+        self
+    }
+
+    // ========== VLESS WebSocket / gRPC 特有方法 ==========
+
+    /// 设置 WebSocket 路径（VLESS-WS）
+    /// 不设置则随机生成
+    pub fn ws_path(mut self, path: impl Into<String>) -> Self {
+        self.ws_path = Some(path.into());
+        self
+    }
+
+    /// 设置客户端 WebSocket Host 请求头（VLESS-WS）
+    /// 不设置则与 SNI 相同
+    pub fn ws_host(mut self, host: impl Into<String>) -> Self {
+        self.ws_host = Some(host.into());
+        self
+    }
+
+    /// 设置 gRPC 服务名（VLESS-gRPC）
+    /// 不设置则随机生成
+    pub fn grpc_service_name(mut self, name: impl Into<String>) -> Self {
+        self.grpc_service_name = Some(name.into());
+        self
+    }
+
+    /// 设置客户端连接的 CDN 地址（VLESS-WS / gRPC）
+    /// 客户端连接该地址，SNI 仍为源站域名
+    pub fn cdn_host(mut self, host: impl Into<String>) -> Self {
+        self.cdn_host = Some(host.into());
+        self
+    }
+
+    /// 启用多路复用与 TCP Brutal 拥塞控制（VLESS-WS / gRPC）
+    /// 服务端需为 Linux 并加载 brutal 内核模块
+    pub fn tcp_brutal(mut self, up_mbps: u32, down_mbps: u32) -> Self {
+        self.tcp_brutal = Some((up_mbps, down_mbps));
+        self
+    }
+
+    /// 启用多路复用（VLESS-WS / gRPC），入站接受多路复用，客户端使用给定设置
+    /// 与 TCP Brutal 同时设置时，客户端沿用此处的协议、流数与填充
+    pub fn multiplex(mut self, multiplex: MultiplexOutbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 启用/禁用 TCP 快速打开
+    pub fn tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.tcp_fast_open = Some(enabled);
+        self
+    }
+
+    /// 设置 UDP NAT 过期时间
+    pub fn udp_timeout(mut self, timeout: impl Into<Duration>) -> Self {
+        self.udp_timeout = Some(timeout.into());
+        self
+    }
+
+    /// 启用/禁用入站协议嗅探（默认开启）
+    pub fn sniff(mut self, enabled: bool) -> Self {
+        self.sniff = enabled;
+        self
+    }
+
+    /// 启用/禁用使用嗅探到的域名覆盖目标地址（默认开启，仅 sing-box 1.11 以下生效）
+    pub fn sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.sniff_override_destination = enabled;
+        self
+    }
+
+    /// 设置入站监听地址（默认 Hysteria2 为 "0.0.0.0"，其他协议为 "::"）
+    pub fn listen(mut self, listen: impl Into<String>) -> Self {
+        self.listen = Some(listen.into());
+        self
+    }
+
+    /// 设置入站绑定的网络接口（如 "eth1"，多出口服务器固定使用某条线路）
+    pub fn bind_interface(mut self, interface: impl Into<String>) -> Self {
+        self.bind_interface = Some(interface.into());
+        self
+    }
+
+    /// 设置入站的 netfilter 路由标记（仅 Linux）
+    pub fn routing_mark(mut self, mark: impl Into<RoutingMark>) -> Self {
+        self.routing_mark = Some(mark.into());
+        self
+    }
+
+    /// 设置入站所在的网络命名空间（名称或路径，仅 Linux）
+    pub fn netns(mut self, netns: impl Into<String>) -> Self {
+        self.netns = Some(netns.into());
+        self
+    }
+
     // ========== 构建方法 ==========
 
     /// 获取或自动检测公网 IP
@@ -537,13 +975,76 @@ impl AutoDefault {
         }
     }
 
+    /// 解析最终使用的端口
+    /// 返回 (端口, 是否为系统临时分配)
+    fn resolve_port(&self) -> Result<(u16, bool), AutoDefaultError> {
+        match self.port {
+            Some(0) => pick_ephemeral_port(self.protocol.transport())
+                .map(|port| (port, true))
+                .map_err(|_| AutoDefaultError::NoAvailablePort),
+            Some(port) => Ok((port, false)),
+            None => Ok((default_port(), false)),
+        }
+    }
+
+    /// 确定 REALITY 短 ID：优先使用指定值，否则按数量与长度生成
+    fn resolve_short_ids(&self) -> Result<Vec<String>, AutoDefaultError> {
+        if !self.reality_custom_short_ids.is_empty() {
+            for id in &self.reality_custom_short_ids {
+                validate_short_id(id).map_err(AutoDefaultError::ConfigError)?;
+            }
+            return Ok(self.reality_custom_short_ids.clone());
+        }
+        match self.reality_short_id_spec {
+            Some((count, len)) => {
+                generate_short_ids(count, len).map_err(AutoDefaultError::ConfigError)
+            }
+            None => Ok(vec![generate_short_id()]),
+        }
+    }
+
+    /// 生成监听字段（附带已设置的监听选项，未设置监听地址时使用 default_listen）
+    fn listen_fields(&self, default_listen: &str, port: u16) -> ListenFields {
+        let listen = self.listen.as_deref().unwrap_or(default_listen);
+        let mut fields = ListenFields::new(listen).with_listen_port(port);
+        if let Some(enabled) = self.tcp_fast_open {
+            fields = fields.with_tcp_fast_open(enabled);
+        }
+        if let Some(ref timeout) = self.udp_timeout {
+            fields = fields.with_udp_timeout(timeout.clone());
+        }
+        if self.sniff {
+            fields = fields.with_sniff(true);
+            if self.sniff_override_destination {
+                fields = fields.with_sniff_override_destination(true);
+            }
+        }
+        if let Some(ref interface) = self.bind_interface {
+            fields = fields.with_bind_interface(interface);
+        }
+        if let Some(ref mark) = self.routing_mark {
+            fields = fields.with_routing_mark(mark.clone());
+        }
+        if let Some(ref netns) = self.netns {
+            fields = fields.with_netns(netns);
+        }
+        fields
+    }
+
     /// 生成 TLS 配置
     fn generate_tls_config(&self, domain: &str, email: Option<String>) -> InboundTlsConfig {
         let acme = AcmeConfig {
             domain: Some(vec![domain.to_string()]),
             email: Some(email.unwrap_or_else(generate_random_email)),
             // 设置共享的数据目录，让所有入站共享同一个证书
-            data_directory: Some("./acme".to_string()),
+            data_directory: Some(
+                self.acme_data_directory
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_ACME_DATA_DIR.to_string()),
+            ),
+            provider: self.acme_provider.clone(),
+            external_account: self.acme_external_account.clone(),
+            dns01_challenge: self.acme_dns01.clone(),
             ..Default::default()
         };
 
@@ -558,8 +1059,7 @@ impl AutoDefault {
     /// 生成用户列表（如果为空则生成默认用户）
     fn generate_users(&self) -> Vec<GeneratedUser> {
         if self.users.is_empty() {
-            let user = if self.protocol == Protocol::Tuic || self.protocol == Protocol::VlessReality
-            {
+            let user = if self.protocol.requires_uuid() {
                 GeneratedUser::with_uuid("default")
             } else {
                 GeneratedUser::new("default")
@@ -577,7 +1077,7 @@ impl AutoDefault {
             .domain
             .clone()
             .unwrap_or_else(|| generate_sslip_domain(&public_ip));
-        let port = self.port.unwrap_or_else(default_port);
+        let (port, ephemeral_port) = self.resolve_port()?;
         let tag = self
             .tag
             .clone()
@@ -586,8 +1086,7 @@ impl AutoDefault {
         let tls = self.generate_tls_config(&domain, self.acme_email.clone());
 
         let mut inbound = AnyTlsInbound::new(&tag)
-            .with_listen("::")
-            .with_listen_port(port)
+            .with_listen_fields(self.listen_fields("::", port))
             .with_tls(tls);
 
         for user in &users {
@@ -599,6 +1098,7 @@ impl AutoDefault {
                 public_ip,
                 domain,
                 port,
+                ephemeral_port,
                 users,
             },
             inbound,
@@ -612,7 +1112,7 @@ impl AutoDefault {
             .domain
             .clone()
             .unwrap_or_else(|| generate_sslip_domain(&public_ip));
-        let port = self.port.unwrap_or_else(default_port);
+        let (port, ephemeral_port) = self.resolve_port()?;
         let tag = self
             .tag
             .clone()
@@ -622,15 +1122,15 @@ impl AutoDefault {
         // hy2 建议使用 HTTP/3 的 ALPN
         tls.alpn = Some(vec!["h3".to_string()]);
         // 若非 443 端口，尽量设置 ACME 的备用 TLS 端口（需系统将 443 转发到该端口）
+        // 使用 DNS-01 挑战时无需 TLS-ALPN 挑战
         if let Some(ref mut acme) = tls.acme {
-            if port != 443 {
+            if port != 443 && acme.dns01_challenge.is_none() {
                 acme.alternative_tls_port = Some(port);
             }
         }
 
         let mut inbound = Hysteria2Inbound::new(&tag)
-            .with_listen("0.0.0.0")
-            .with_listen_port(port)
+            .with_listen_fields(self.listen_fields("0.0.0.0", port))
             .with_tls(tls);
 
         for user in &users {
@@ -652,8 +1152,13 @@ impl AutoDefault {
         };
 
         // 伪装
-        if let Some(ref url) = self.masquerade_url {
-            inbound = inbound.with_masquerade_url(url);
+        if let Some(ref masquerade) = self.masquerade {
+            inbound = inbound.with_masquerade(masquerade.clone());
+        }
+
+        // 忽略客户端带宽
+        if let Some(ignore) = self.ignore_client_bandwidth {
+            inbound = inbound.with_ignore_client_bandwidth(ignore);
         }
 
         Ok(Hysteria2AutoResult {
@@ -661,6 +1166,7 @@ impl AutoDefault {
                 public_ip,
                 domain,
                 port,
+                ephemeral_port,
                 users,
             },
             inbound,
@@ -675,7 +1181,7 @@ impl AutoDefault {
             .domain
             .clone()
             .unwrap_or_else(|| generate_sslip_domain(&public_ip));
-        let port = self.port.unwrap_or_else(default_port);
+        let (port, ephemeral_port) = self.resolve_port()?;
         let tag = self
             .tag
             .clone()
@@ -683,11 +1189,13 @@ impl AutoDefault {
         let users = self.generate_users();
         let tls = self.generate_tls_config(&domain, self.acme_email.clone());
 
-        let cc = self.congestion_control.unwrap_or(CongestionControl::Cubic);
+        let cc = self
+            .congestion_control
+            .clone()
+            .unwrap_or(CongestionControl::Cubic);
 
         let mut inbound = TuicInbound::new(&tag)
-            .with_listen("::")
-            .with_listen_port(port)
+            .with_listen_fields(self.listen_fields("::", port))
             .with_tls(tls)
             .with_congestion_control(cc);
 
@@ -700,21 +1208,33 @@ impl AutoDefault {
             inbound = inbound.add_user(tuic_user);
         }
 
+        if let Some(ref timeout) = self.tuic_auth_timeout {
+            inbound = inbound.with_auth_timeout(timeout.clone());
+        }
+        if let Some(enabled) = self.tuic_zero_rtt {
+            inbound = inbound.with_zero_rtt_handshake(enabled);
+        }
+        if let Some(ref interval) = self.tuic_heartbeat {
+            inbound = inbound.with_heartbeat(interval.clone());
+        }
+
         Ok(TuicAutoResult {
             info: AutoDefaultResult {
                 public_ip,
                 domain,
                 port,
+                ephemeral_port,
                 users,
             },
             inbound,
+            udp_relay_mode: self.tuic_udp_relay_mode.unwrap_or_default(),
         })
     }
 
     /// 构建 VLESS-Vision-uTLS-REALITY 配置
     pub fn build_vless_reality(self) -> Result<VlessRealityAutoResult, AutoDefaultError> {
         let public_ip = self.get_public_ip()?;
-        let port = self.port.unwrap_or_else(default_port);
+        let (port, ephemeral_port) = self.resolve_port()?;
         let tag = self
             .tag
             .clone()
@@ -733,8 +1253,15 @@ impl AutoDefault {
             .unwrap_or_else(|| handshake_server.clone());
 
         // 生成 REALITY 密钥对
-        let keypair = generate_reality_keypair();
-        let short_id = generate_short_id();
+        let keypair = match (&self.reality_private_key, &self.reality_keygen_bin) {
+            (Some(key), _) => {
+                RealityKeyPair::from_private_key(key).map_err(AutoDefaultError::ConfigError)?
+            }
+            (None, Some(bin)) => generate_reality_keypair_with_sing_box(bin),
+            (None, None) => generate_reality_keypair(),
+        };
+        let short_ids = self.resolve_short_ids()?;
+        let short_id = short_ids[0].clone();
 
         // 构建 REALITY TLS 配置
         let reality_config = RealityInboundConfig {
@@ -756,7 +1283,7 @@ impl AutoDefault {
                 fallback_delay: None,
             }),
             private_key: Some(keypair.private_key.clone()),
-            short_id: Some(vec![short_id.clone()]),
+            short_id: Some(short_ids.clone()),
             max_time_difference: None,
         };
 
@@ -769,8 +1296,7 @@ impl AutoDefault {
 
         // 构建入站配置
         let mut inbound = VlessInbound::new(&tag)
-            .with_listen("::")
-            .with_listen_port(port)
+            .with_listen_fields(self.listen_fields("::", port))
             .with_tls(tls_config);
 
         // 添加用户（带XTLS Vision flow）
@@ -788,17 +1314,114 @@ impl AutoDefault {
                 public_ip,
                 domain,
                 port,
+                ephemeral_port,
                 users,
             },
             inbound,
             private_key: keypair.private_key,
             public_key: keypair.public_key,
             short_id,
+            short_ids,
             handshake_server,
             handshake_port,
         })
     }
 
+    /// 构建 VLESS over WebSocket 配置
+    pub fn build_vless_ws(self) -> Result<VlessTransportAutoResult, AutoDefaultError> {
+        let path = match self.ws_path.as_deref().map(str::trim) {
+            Some(p) if p.starts_with('/') => p.to_string(),
+            Some(p) if !p.is_empty() => format!("/{}", p),
+            _ => format!("/{}", generate_hex_string(8)),
+        };
+        let transport = V2RayTransport::Ws(WebSocketTransport::new().with_path(&path));
+        self.build_vless_transport(transport, path)
+    }
+
+    /// 构建 VLESS over gRPC 配置
+    pub fn build_vless_grpc(self) -> Result<VlessTransportAutoResult, AutoDefaultError> {
+        let service_name = self
+            .grpc_service_name
+            .as_deref()
+            .map(|s| s.trim().trim_matches('/'))
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| generate_hex_string(8));
+        let transport = V2RayTransport::Grpc(GrpcTransport::new().with_service_name(&service_name));
+        self.build_vless_transport(transport, service_name)
+    }
+
+    /// 构建使用 ACME TLS 与 V2Ray 传输层的 VLESS 入站
+    /// XTLS Vision 仅支持裸 TCP，这里的用户不设置 flow
+    fn build_vless_transport(
+        self,
+        transport: V2RayTransport,
+        path: String,
+    ) -> Result<VlessTransportAutoResult, AutoDefaultError> {
+        let public_ip = self.get_public_ip()?;
+        let domain = self
+            .domain
+            .clone()
+            .unwrap_or_else(|| generate_sslip_domain(&public_ip));
+        let (port, ephemeral_port) = self.resolve_port()?;
+        let tag = self
+            .tag
+            .clone()
+            .unwrap_or_else(|| self.protocol.default_tag().to_string());
+        let users = self.generate_users();
+        let tls = self.generate_tls_config(&domain, self.acme_email.clone());
+
+        let mut inbound = VlessInbound::new(&tag)
+            .with_listen_fields(self.listen_fields("::", port))
+            .with_tls(tls)
+            .with_transport(transport);
+        for user in &users {
+            let uuid = user.uuid.clone().unwrap_or_else(generate_uuid);
+            inbound = inbound.add_user(VlessUser::new(&user.name, &uuid));
+        }
+        let multiplex = self.client_multiplex();
+        if let Some(ref mux) = multiplex {
+            let mut accept = MultiplexInbound::new().enabled();
+            // 服务端开启填充后会拒绝未填充的连接，仅在客户端同样填充时开启
+            if mux.padding == Some(true) {
+                accept = accept.with_padding(true);
+            }
+            if let Some(ref brutal) = mux.brutal {
+                accept = accept.with_brutal(brutal.clone());
+            }
+            inbound = inbound.with_multiplex(accept);
+        }
+
+        Ok(VlessTransportAutoResult {
+            info: AutoDefaultResult {
+                public_ip,
+                domain,
+                port,
+                ephemeral_port,
+                users,
+            },
+            inbound,
+            path,
+            multiplex,
+            cdn_host: self.cdn_host.clone(),
+            host: self.ws_host.clone(),
+        })
+    }
+
+    /// 合并多路复用与 TCP Brutal 设置，得到客户端的多路复用配置
+    fn client_multiplex(&self) -> Option<MultiplexOutbound> {
+        let mut mux = match (&self.multiplex, self.tcp_brutal) {
+            (Some(mux), _) => mux.clone(),
+            (None, Some(_)) => MultiplexOutbound::new().h2mux(),
+            (None, None) => return None,
+        };
+        mux.enabled = Some(true);
+        if let Some((up, down)) = self.tcp_brutal {
+            mux.brutal = Some(TcpBrutal::new(up, down));
+        }
+        Some(mux)
+    }
+
     /// 根据协议类型自动构建
     pub fn build(self) -> Result<AutoBuildResult, AutoDefaultError> {
         match self.protocol {
@@ -808,6 +1431,8 @@ impl AutoDefault {
             Protocol::VlessReality => {
                 Ok(AutoBuildResult::VlessReality(self.build_vless_reality()?))
             }
+            Protocol::VlessWs => Ok(AutoBuildResult::VlessWs(self.build_vless_ws()?)),
+            Protocol::VlessGrpc => Ok(AutoBuildResult::VlessGrpc(self.build_vless_grpc()?)),
         }
     }
 }
@@ -823,6 +1448,10 @@ pub enum AutoBuildResult {
     Tuic(TuicAutoResult),
     /// VLESS-Reality 结果
     VlessReality(VlessRealityAutoResult),
+    /// VLESS-WebSocket 结果
+    VlessWs(VlessTransportAutoResult),
+    /// VLESS-gRPC 结果
+    VlessGrpc(VlessTransportAutoResult),
 }
 
 //============================================================================
@@ -846,8 +1475,14 @@ pub enum AutoBuildResult {
 pub struct MultiProtocolBuilder {
     /// 公网 IP
     public_ip: Option<IpAddr>,
-    /// 指定域名（不指定则使用基于公网 IP 的 sslip.io）
+    /// 公网 IP 探测方式（为空时使用默认顺序）
+    ip_detectors: Vec<IpDetector>,
+    /// HTTP 公网 IP 服务列表（为空时使用默认列表）
+    ip_services: Vec<String>,
+    /// 指定域名（不指定则由 domain_provider 按公网 IP 生成）
     domain: Option<String>,
+    /// 未指定域名时使用的域名提供方
+    domain_provider: DomainProvider,
     /// 用户列表
     users: Vec<GeneratedUser>,
     /// AnyTLS 端口（None表示不启用）
@@ -862,12 +1497,72 @@ pub struct MultiProtocolBuilder {
     hy2_bandwidth: Option<(u32, u32)>,
     /// Hysteria2 混淆
     hy2_obfs: bool,
+    /// Hysteria2 伪装
+    hy2_masquerade: Option<Hysteria2Masquerade>,
+    /// Hysteria2 忽略客户端带宽
+    hy2_ignore_client_bandwidth: Option<bool>,
+    /// VLESS WebSocket / gRPC 的 TCP Brutal 带宽
+    tcp_brutal: Option<(u32, u32)>,
+    /// VLESS WebSocket / gRPC 的客户端多路复用设置
+    multiplex: Option<MultiplexOutbound>,
     /// TUIC 拥塞控制
     tuic_cc: Option<CongestionControl>,
+    /// TUIC 认证超时
+    tuic_auth_timeout: Option<Duration>,
+    /// TUIC 0-RTT 握手
+    tuic_zero_rtt: Option<bool>,
+    /// TUIC 心跳间隔
+    tuic_heartbeat: Option<Duration>,
+    /// TUIC 客户端 UDP 中继模式
+    tuic_udp_relay_mode: Option<UdpRelayMode>,
     /// VLESS Reality 握手服务器
     vless_handshake: Option<(String, u16)>,
+    /// VLESS-WebSocket 端口与路径
+    vless_ws: Option<(u16, String)>,
+    /// VLESS-gRPC 端口与服务名
+    vless_grpc: Option<(u16, String)>,
+    /// VLESS-WebSocket 客户端 Host 请求头
+    vless_ws_host: Option<String>,
+    /// VLESS WebSocket / gRPC 客户端连接的 CDN 地址
+    cdn_host: Option<String>,
+    /// 已应用的预设
+    preset: Option<Preset>,
+    /// VLESS Reality 短 ID 数量与字节长度
+    reality_short_id_spec: Option<(usize, usize)>,
+    /// VLESS Reality 指定的短 ID
+    reality_custom_short_ids: Vec<String>,
+    /// VLESS Reality 复用的私钥
+    reality_private_key: Option<String>,
+    /// 用于生成 REALITY 密钥对的 sing-box 路径
+    reality_keygen_bin: Option<String>,
     /// ACME 邮箱地址
     acme_email: Option<String>,
+    /// ACME DNS-01 挑战
+    acme_dns01: Option<Dns01Challenge>,
+    /// ACME CA 提供商
+    acme_provider: Option<AcmeProvider>,
+    /// ACME 数据目录
+    acme_data_directory: Option<String>,
+    /// ACME 外部账户绑定（EAB）
+    acme_external_account: Option<AcmeExternalAccount>,
+    /// 所有入站的 TCP 快速打开
+    tcp_fast_open: Option<bool>,
+    /// 所有入站的 UDP NAT 过期时间
+    udp_timeout: Option<Duration>,
+    /// 所有入站的协议嗅探（默认开启）
+    sniff: bool,
+    /// 所有入站使用嗅探到的域名覆盖目标地址（默认开启）
+    sniff_override_destination: bool,
+    /// 按协议覆盖的协议嗅探开关
+    protocol_sniff: Vec<(Protocol, bool)>,
+    /// 按协议设置的监听地址
+    protocol_listen: Vec<(Protocol, String)>,
+    /// 所有入站绑定的网络接口
+    bind_interface: Option<String>,
+    /// 所有入站的 netfilter 路由标记
+    routing_mark: Option<RoutingMark>,
+    /// 所有入站的网络命名空间
+    netns: Option<String>,
 }
 
 impl MultiProtocolBuilder {
@@ -875,7 +1570,10 @@ impl MultiProtocolBuilder {
     pub fn new() -> Self {
         Self {
             public_ip: None,
+            ip_detectors: Vec::new(),
+            ip_services: Vec::new(),
             domain: None,
+            domain_provider: DomainProvider::default(),
             users: Vec::new(),
             anytls_port: None,
             hysteria2_port: None,
@@ -883,9 +1581,39 @@ impl MultiProtocolBuilder {
             vless_reality_port: None,
             hy2_bandwidth: None,
             hy2_obfs: false,
+            hy2_masquerade: None,
+            hy2_ignore_client_bandwidth: None,
+            tcp_brutal: None,
+            multiplex: None,
             tuic_cc: None,
+            tuic_auth_timeout: None,
+            tuic_zero_rtt: None,
+            tuic_heartbeat: None,
+            tuic_udp_relay_mode: None,
             vless_handshake: None,
+            vless_ws: None,
+            vless_grpc: None,
+            vless_ws_host: None,
+            cdn_host: None,
+            preset: None,
+            reality_short_id_spec: None,
+            reality_custom_short_ids: Vec::new(),
+            reality_private_key: None,
+            reality_keygen_bin: None,
             acme_email: None,
+            acme_dns01: None,
+            acme_provider: None,
+            acme_data_directory: None,
+            acme_external_account: None,
+            tcp_fast_open: None,
+            udp_timeout: None,
+            sniff: true,
+            sniff_override_destination: true,
+            protocol_sniff: Vec::new(),
+            protocol_listen: Vec::new(),
+            bind_interface: None,
+            routing_mark: None,
+            netns: None,
         }
     }
 
@@ -895,12 +1623,30 @@ impl MultiProtocolBuilder {
         self
     }
 
-    /// 设置域名（不指定则使用基于公网 IP 的 sslip.io）
+    /// 设置公网 IP 探测方式（按顺序尝试）
+    pub fn ip_detectors(mut self, detectors: Vec<IpDetector>) -> Self {
+        self.ip_detectors = detectors;
+        self
+    }
+
+    /// 设置 HTTP 公网 IP 服务列表
+    pub fn ip_services(mut self, services: Vec<String>) -> Self {
+        self.ip_services = services;
+        self
+    }
+
+    /// 设置域名（不指定则由域名提供方按公网 IP 生成）
     pub fn domain(mut self, domain: impl Into<String>) -> Self {
         self.domain = Some(domain.into());
         self
     }
 
+    /// 设置未指定域名时使用的域名提供方（默认 sslip.io）
+    pub fn domain_provider(mut self, provider: DomainProvider) -> Self {
+        self.domain_provider = provider;
+        self
+    }
+
     /// 启用 AnyTLS
     pub fn enable_anytls(mut self, port: u16) -> Self {
         self.anytls_port = Some(port);
@@ -925,90 +1671,431 @@ impl MultiProtocolBuilder {
         self
     }
 
-    /// 设置 VLESS Reality 握手服务器
-    pub fn vless_handshake(mut self, server: impl Into<String>, port: u16) -> Self {
-        self.vless_handshake = Some((server.into(), port));
+    /// 启用 VLESS over WebSocket（ACME TLS，可经 CDN 转发）
+    /// 路径为空时随机生成
+    pub fn enable_vless_ws(mut self, port: u16, path: impl Into<String>) -> Self {
+        self.vless_ws = Some((port, path.into()));
         self
     }
 
-    /// 设置 ACME 邮箱地址
-    pub fn acme_email(mut self, email: impl Into<String>) -> Self {
-        self.acme_email = Some(email.into());
+    /// 启用 VLESS over gRPC（ACME TLS，可经 CDN 转发）
+    /// 服务名为空时随机生成
+    pub fn enable_vless_grpc(mut self, port: u16, service_name: impl Into<String>) -> Self {
+        self.vless_grpc = Some((port, service_name.into()));
         self
     }
 
-    /// 启用所有协议（使用默认端口）
-    pub fn enable_all(mut self) -> Self {
-        self.anytls_port = Some(DEFAULT_PORTS[0]); // 443
-        self.hysteria2_port = Some(DEFAULT_PORTS[1]); // 2053
-        self.tuic_port = Some(DEFAULT_PORTS[2]); // 2083
-        self.vless_reality_port = Some(DEFAULT_PORTS[3]); // 2096
+    /// 设置 VLESS-WebSocket 客户端的 Host 请求头（默认与 SNI 相同）
+    pub fn vless_ws_host(mut self, host: impl Into<String>) -> Self {
+        self.vless_ws_host = Some(host.into());
         self
     }
 
-    /// 添加用户
-    pub fn add_user(mut self, name: impl Into<String>) -> Self {
-        self.users.push(GeneratedUser::with_uuid(name));
+    /// 设置 VLESS WebSocket / gRPC 客户端连接的 CDN 地址（如优选 IP 或 CDN 主机名）
+    /// 客户端以源站域名作为 SNI，经 CDN 回源
+    pub fn cdn_host(mut self, host: impl Into<String>) -> Self {
+        self.cdn_host = Some(host.into());
         self
     }
 
-    /// 添加用户（指定密码）
-    pub fn add_user_with_password(
-        mut self,
-        name: impl Into<String>,
-        password: impl Into<String>,
-    ) -> Self {
-        let mut user = GeneratedUser::with_password(name, password);
-        user.uuid = Some(generate_uuid());
-        self.users.push(user);
+    /// 应用协议预设：清空已启用的协议，改为预设中的协议组合（隐蔽预设同时启用 Hysteria2 混淆）
+    /// 之后仍可继续启用、禁用协议或调整选项
+    pub fn preset(mut self, preset: Preset) -> Self {
+        for protocol in Protocol::ALL {
+            self = self.disable(protocol);
+        }
+        for &(protocol, port) in preset.protocols() {
+            self = match protocol {
+                Protocol::AnyTls => self.enable_anytls(port),
+                Protocol::Hysteria2 => self.enable_hysteria2(port),
+                Protocol::Tuic => self.enable_tuic(port),
+                Protocol::VlessReality => self.enable_vless_reality(port),
+                Protocol::VlessWs => self.enable_vless_ws(port, ""),
+                Protocol::VlessGrpc => self.enable_vless_grpc(port, ""),
+            };
+        }
+        if preset == Preset::Stealth {
+            self.hy2_obfs = true;
+        }
+        self.preset = Some(preset);
         self
     }
 
-    /// 设置 Hysteria2 带宽
-    pub fn hy2_bandwidth(mut self, up_mbps: u32, down_mbps: u32) -> Self {
-        self.hy2_bandwidth = Some((up_mbps, down_mbps));
+    /// 禁用协议
+    pub fn disable(mut self, protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::AnyTls => self.anytls_port = None,
+            Protocol::Hysteria2 => self.hysteria2_port = None,
+            Protocol::Tuic => self.tuic_port = None,
+            Protocol::VlessReality => self.vless_reality_port = None,
+            Protocol::VlessWs => self.vless_ws = None,
+            Protocol::VlessGrpc => self.vless_grpc = None,
+        }
         self
     }
 
-    /// 启用 Hysteria2 混淆
-    pub fn hy2_obfs(mut self) -> Self {
-        self.hy2_obfs = true;
+    /// 设置 VLESS Reality 握手服务器
+    pub fn vless_handshake(mut self, server: impl Into<String>, port: u16) -> Self {
+        self.vless_handshake = Some((server.into(), port));
         self
     }
 
-    /// 设置 TUIC 拥塞控制
-    pub fn tuic_congestion(mut self, cc: CongestionControl) -> Self {
-        self.tuic_cc = Some(cc);
+    /// 生成多个 REALITY 短 ID（每个 `len` 字节，0–8）
+    pub fn reality_short_ids(mut self, count: usize, len: usize) -> Self {
+        self.reality_short_id_spec = Some((count, len));
         self
     }
 
-    /// 构建多协议配置
-    pub fn build(self) -> Result<MultiProtocolResult, AutoDefaultError> {
+    /// 使用指定的 REALITY 短 ID
+    pub fn reality_custom_short_ids<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.reality_custom_short_ids = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 复用已有的 REALITY 私钥
+    pub fn reality_private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.reality_private_key = Some(private_key.into());
+        self
+    }
+
+    /// 使用 sing-box 生成 REALITY 密钥对（不可用时回退到内置实现）
+    pub fn reality_keygen_sing_box(mut self, sing_box_bin: impl Into<String>) -> Self {
+        self.reality_keygen_bin = Some(sing_box_bin.into());
+        self
+    }
+
+    /// 设置 ACME 邮箱地址
+    pub fn acme_email(mut self, email: impl Into<String>) -> Self {
+        self.acme_email = Some(email.into());
+        self
+    }
+
+    /// 使用 DNS-01 挑战申请证书（适用于 80/443 端口无法用于验证的环境）
+    pub fn acme_dns01(mut self, challenge: Dns01Challenge) -> Self {
+        self.acme_dns01 = Some(challenge);
+        self
+    }
+
+    /// 使用 Cloudflare DNS-01 挑战申请证书
+    pub fn acme_dns01_cloudflare(self, api_token: impl Into<String>) -> Self {
+        self.acme_dns01(Dns01Challenge::cloudflare(api_token))
+    }
+
+    /// 使用阿里云 DNS-01 挑战申请证书
+    pub fn acme_dns01_alidns(
+        self,
+        access_key_id: impl Into<String>,
+        access_key_secret: impl Into<String>,
+    ) -> Self {
+        self.acme_dns01(Dns01Challenge::alidns(access_key_id, access_key_secret))
+    }
+
+    /// 设置 ACME CA 提供商（letsencrypt / zerossl / 自定义 URL）
+    pub fn acme_provider(mut self, provider: AcmeProvider) -> Self {
+        self.acme_provider = Some(provider);
+        self
+    }
+
+    /// 设置 ACME 数据目录（所有入站共享同一目录以复用证书）
+    pub fn acme_data_directory(mut self, dir: impl Into<String>) -> Self {
+        self.acme_data_directory = Some(dir.into());
+        self
+    }
+
+    /// 设置 ACME 外部账户绑定（EAB，ZeroSSL 等 CA 需要）
+    pub fn acme_eab(mut self, key_id: impl Into<String>, mac_key: impl Into<String>) -> Self {
+        self.acme_external_account = Some(AcmeExternalAccount::new(key_id, mac_key));
+        self
+    }
+
+    /// 启用所有协议（使用默认端口）
+    pub fn enable_all(mut self) -> Self {
+        self.anytls_port = Some(Protocol::AnyTls.default_port());
+        self.hysteria2_port = Some(Protocol::Hysteria2.default_port());
+        self.tuic_port = Some(Protocol::Tuic.default_port());
+        self.vless_reality_port = Some(Protocol::VlessReality.default_port());
+        self
+    }
+
+    /// 添加用户
+    pub fn add_user(mut self, name: impl Into<String>) -> Self {
+        self.users.push(GeneratedUser::with_uuid(name));
+        self
+    }
+
+    /// 添加用户（指定密码）
+    pub fn add_user_with_password(
+        mut self,
+        name: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        let mut user = GeneratedUser::with_password(name, password);
+        user.uuid = Some(generate_uuid());
+        self.users.push(user);
+        self
+    }
+
+    /// 添加已构造的用户（保留密码、UUID 与过期时间）
+    pub fn add_generated_user(mut self, user: GeneratedUser) -> Self {
+        self.users.push(user);
+        self
+    }
+
+    /// 添加用户（指定密码与 UUID，用于复用已保存的凭证）
+    pub fn add_user_with_credentials(
+        mut self,
+        name: impl Into<String>,
+        password: impl Into<String>,
+        uuid: impl Into<String>,
+    ) -> Self {
+        self.users
+            .push(GeneratedUser::with_credentials(name, password, uuid));
+        self
+    }
+
+    /// 设置 Hysteria2 带宽
+    pub fn hy2_bandwidth(mut self, up_mbps: u32, down_mbps: u32) -> Self {
+        self.hy2_bandwidth = Some((up_mbps, down_mbps));
+        self
+    }
+
+    /// 启用 Hysteria2 混淆
+    pub fn hy2_obfs(mut self) -> Self {
+        self.hy2_obfs = true;
+        self
+    }
+
+    /// 设置 Hysteria2 伪装（反向代理、文件服务器或固定响应）
+    pub fn hy2_masquerade(mut self, masquerade: Hysteria2Masquerade) -> Self {
+        self.hy2_masquerade = Some(masquerade);
+        self
+    }
+
+    /// 设置 Hysteria2 是否忽略客户端带宽（服务端改用 BBR）
+    pub fn hy2_ignore_client_bandwidth(mut self, ignore: bool) -> Self {
+        self.hy2_ignore_client_bandwidth = Some(ignore);
+        self
+    }
+
+    /// 为 VLESS WebSocket / gRPC 启用多路复用与 TCP Brutal
+    /// AnyTLS 自带会话复用，VLESS Reality 的 Vision 流控不支持多路复用，均不受影响
+    pub fn tcp_brutal(mut self, up_mbps: u32, down_mbps: u32) -> Self {
+        self.tcp_brutal = Some((up_mbps, down_mbps));
+        self
+    }
+
+    /// 为 VLESS WebSocket / gRPC 启用多路复用（smux / yamux / h2mux、流数、填充）
+    /// AnyTLS 出站不支持多路复用设置，VLESS Reality 的 Vision 流控与多路复用不兼容，均不受影响
+    pub fn multiplex(mut self, multiplex: MultiplexOutbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置 TUIC 拥塞控制
+    pub fn tuic_congestion(mut self, cc: CongestionControl) -> Self {
+        self.tuic_cc = Some(cc);
+        self
+    }
+
+    /// 设置 TUIC 认证超时
+    pub fn tuic_auth_timeout(mut self, timeout: impl Into<Duration>) -> Self {
+        self.tuic_auth_timeout = Some(timeout.into());
+        self
+    }
+
+    /// 启用/禁用 TUIC 0-RTT 握手
+    pub fn tuic_zero_rtt(mut self, enabled: bool) -> Self {
+        self.tuic_zero_rtt = Some(enabled);
+        self
+    }
+
+    /// 设置 TUIC 心跳间隔
+    pub fn tuic_heartbeat(mut self, interval: impl Into<Duration>) -> Self {
+        self.tuic_heartbeat = Some(interval.into());
+        self
+    }
+
+    /// 设置 TUIC 客户端 UDP 中继模式
+    pub fn tuic_udp_relay_mode(mut self, mode: UdpRelayMode) -> Self {
+        self.tuic_udp_relay_mode = Some(mode);
+        self
+    }
+
+    /// 为所有入站启用/禁用 TCP 快速打开
+    pub fn tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.tcp_fast_open = Some(enabled);
+        self
+    }
+
+    /// 设置所有入站的 UDP NAT 过期时间
+    pub fn udp_timeout(mut self, timeout: impl Into<Duration>) -> Self {
+        self.udp_timeout = Some(timeout.into());
+        self
+    }
+
+    /// 为所有入站启用/禁用协议嗅探（默认开启）
+    pub fn sniff(mut self, enabled: bool) -> Self {
+        self.sniff = enabled;
+        self
+    }
+
+    /// 为所有入站启用/禁用使用嗅探到的域名覆盖目标地址（默认开启，仅 sing-box 1.11 以下生效）
+    pub fn sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.sniff_override_destination = enabled;
+        self
+    }
+
+    /// 单独设置某个协议的协议嗅探开关（优先于 `sniff`）
+    pub fn protocol_sniff(mut self, protocol: Protocol, enabled: bool) -> Self {
+        self.protocol_sniff.retain(|(p, _)| *p != protocol);
+        self.protocol_sniff.push((protocol, enabled));
+        self
+    }
+
+    /// 单独设置某个协议入站的监听地址（如只在指定网卡地址上监听 VLESS-Reality）
+    pub fn protocol_listen(mut self, protocol: Protocol, listen: impl Into<String>) -> Self {
+        self.protocol_listen.retain(|(p, _)| *p != protocol);
+        self.protocol_listen.push((protocol, listen.into()));
+        self
+    }
+
+    /// 设置所有入站绑定的网络接口（如 "eth1"）
+    pub fn bind_interface(mut self, interface: impl Into<String>) -> Self {
+        self.bind_interface = Some(interface.into());
+        self
+    }
+
+    /// 设置所有入站的 netfilter 路由标记（仅 Linux）
+    pub fn routing_mark(mut self, mark: impl Into<RoutingMark>) -> Self {
+        self.routing_mark = Some(mark.into());
+        self
+    }
+
+    /// 设置所有入站所在的网络命名空间（仅 Linux）
+    pub fn netns(mut self, netns: impl Into<String>) -> Self {
+        self.netns = Some(netns.into());
+        self
+    }
+
+    /// 检查已启用协议之间的端口冲突
+    /// TCP 与 UDP 协议可以共用同一端口（如 AnyTLS 与 Hysteria2 共用 443），端口 0 表示自动分配，不参与检查
+    fn check_port_conflicts(&self) -> Result<(), AutoDefaultError> {
+        let enabled = [
+            (Protocol::AnyTls, self.anytls_port),
+            (Protocol::Hysteria2, self.hysteria2_port),
+            (Protocol::Tuic, self.tuic_port),
+            (Protocol::VlessReality, self.vless_reality_port),
+            (
+                Protocol::VlessWs,
+                self.vless_ws.as_ref().map(|(port, _)| *port),
+            ),
+            (
+                Protocol::VlessGrpc,
+                self.vless_grpc.as_ref().map(|(port, _)| *port),
+            ),
+        ];
+        let mut used: Vec<(u16, Transport, Protocol)> = Vec::new();
+        for (protocol, port) in enabled {
+            let Some(port) = port.filter(|&p| p != 0) else {
+                continue;
+            };
+            let transport = protocol.transport();
+            if let Some(&(_, _, first)) =
+                used.iter().find(|(p, t, _)| *p == port && *t == transport)
+            {
+                return Err(AutoDefaultError::PortConflict {
+                    port,
+                    transport,
+                    first,
+                    second: protocol,
+                });
+            }
+            used.push((port, transport, protocol));
+        }
+        Ok(())
+    }
+
+    /// 将 ACME 相关设置应用到单协议构建器，保证各入站申请证书的方式一致
+    fn apply_acme(&self, mut builder: AutoDefault) -> AutoDefault {
+        if let Some(ref email) = self.acme_email {
+            builder = builder.acme_email(email);
+        }
+        if let Some(ref challenge) = self.acme_dns01 {
+            builder = builder.acme_dns01(challenge.clone());
+        }
+        if let Some(ref provider) = self.acme_provider {
+            builder = builder.acme_provider(provider.clone());
+        }
+        if let Some(ref dir) = self.acme_data_directory {
+            builder = builder.acme_data_directory(dir);
+        }
+        builder.acme_external_account = self.acme_external_account.clone();
+        builder
+    }
+
+    /// 将监听选项应用到单协议构建器
+    fn apply_listen_options(&self, mut builder: AutoDefault) -> AutoDefault {
+        builder.tcp_fast_open = self.tcp_fast_open;
+        builder.udp_timeout = self.udp_timeout.clone();
+        builder.sniff = self
+            .protocol_sniff
+            .iter()
+            .find(|(p, _)| *p == builder.protocol)
+            .map_or(self.sniff, |(_, enabled)| *enabled);
+        builder.sniff_override_destination = self.sniff_override_destination;
+        builder.listen = self
+            .protocol_listen
+            .iter()
+            .find(|(p, _)| *p == builder.protocol)
+            .map(|(_, listen)| listen.clone());
+        builder.bind_interface = self.bind_interface.clone();
+        builder.routing_mark = self.routing_mark.clone();
+        builder.netns = self.netns.clone();
+        builder
+    }
+
+    /// 构建多协议配置
+    pub fn build(self) -> Result<MultiProtocolResult, AutoDefaultError> {
+        self.check_port_conflicts()?;
+        // CDN 只能按域名回源，sslip.io 域名无法接入 CDN
+        if self.preset == Some(Preset::Cdn) && self.domain.is_none() {
+            return Err(AutoDefaultError::ConfigError(
+                "CDN 预设需要设置已接入 CDN 的域名".to_string(),
+            ));
+        }
+
         let public_ip = if let Some(ip) = self.public_ip {
             ip
+        } else if self.ip_detectors.is_empty() {
+            get_public_ip_with(&IpDetector::default_order(), &self.ip_services)?
         } else {
-            get_public_ip()?
+            get_public_ip_with(&self.ip_detectors, &self.ip_services)?
         };
 
         let domain = self
             .domain
             .clone()
-            .unwrap_or_else(|| generate_sslip_domain(&public_ip));
+            .unwrap_or_else(|| self.domain_provider.domain_for(&public_ip));
         let users = if self.users.is_empty() {
             vec![GeneratedUser::with_uuid("default")]
         } else {
-            self.users
+            self.users.clone()
         };
 
         //构建 AnyTLS
         let anytls = if let Some(port) = self.anytls_port {
-            let mut builder = AutoDefault::anytls()
-                .public_ip(public_ip)
-                .domain(domain.clone())
-                .port(port);
-            if let Some(ref email) = self.acme_email {
-                builder = builder.acme_email(email);
-            }
+            let mut builder = self.apply_listen_options(
+                self.apply_acme(
+                    AutoDefault::anytls()
+                        .public_ip(public_ip)
+                        .domain(domain.clone())
+                        .port(port),
+                ),
+            );
             for user in &users {
                 builder = builder.add_user_with_password(&user.name, &user.password);
             }
@@ -1019,10 +2106,14 @@ impl MultiProtocolBuilder {
 
         // 构建 Hysteria2
         let hysteria2 = if let Some(port) = self.hysteria2_port {
-            let mut builder = AutoDefault::hysteria2()
-                .public_ip(public_ip)
-                .domain(domain.clone())
-                .port(port);
+            let mut builder = self.apply_listen_options(
+                self.apply_acme(
+                    AutoDefault::hysteria2()
+                        .public_ip(public_ip)
+                        .domain(domain.clone())
+                        .port(port),
+                ),
+            );
             for user in &users {
                 builder = builder.add_user_with_password(&user.name, &user.password);
             }
@@ -1032,6 +2123,12 @@ impl MultiProtocolBuilder {
             if self.hy2_obfs {
                 builder = builder.with_obfs();
             }
+            if let Some(ref masquerade) = self.hy2_masquerade {
+                builder = builder.masquerade_config(masquerade.clone());
+            }
+            if let Some(ignore) = self.hy2_ignore_client_bandwidth {
+                builder = builder.ignore_client_bandwidth(ignore);
+            }
             Some(builder.build_hysteria2()?)
         } else {
             None
@@ -1039,13 +2136,14 @@ impl MultiProtocolBuilder {
 
         // 构建 TUIC
         let tuic = if let Some(port) = self.tuic_port {
-            let mut builder = AutoDefault::tuic()
-                .public_ip(public_ip)
-                .domain(domain.clone())
-                .port(port);
-            if let Some(ref email) = self.acme_email {
-                builder = builder.acme_email(email);
-            }
+            let mut builder = self.apply_listen_options(
+                self.apply_acme(
+                    AutoDefault::tuic()
+                        .public_ip(public_ip)
+                        .domain(domain.clone())
+                        .port(port),
+                ),
+            );
             for user in &users {
                 if let Some(ref uuid) = user.uuid {
                     builder = builder.add_tuic_user(&user.name, uuid, &user.password);
@@ -1053,13 +2151,25 @@ impl MultiProtocolBuilder {
                     builder = builder.add_user_with_password(&user.name, &user.password);
                 }
             }
-            if let Some(cc) = self.tuic_cc {
+            if let Some(cc) = &self.tuic_cc {
                 builder = match cc {
                     CongestionControl::Bbr => builder.bbr(),
                     CongestionControl::Cubic => builder.cubic(),
                     CongestionControl::NewReno => builder.new_reno(),
                 };
             }
+            if let Some(ref timeout) = self.tuic_auth_timeout {
+                builder = builder.auth_timeout(timeout.clone());
+            }
+            if let Some(enabled) = self.tuic_zero_rtt {
+                builder = builder.zero_rtt_handshake(enabled);
+            }
+            if let Some(ref interval) = self.tuic_heartbeat {
+                builder = builder.heartbeat(interval.clone());
+            }
+            if let Some(mode) = self.tuic_udp_relay_mode {
+                builder = builder.udp_relay_mode(mode);
+            }
             Some(builder.build_tuic()?)
         } else {
             None
@@ -1067,7 +2177,8 @@ impl MultiProtocolBuilder {
 
         // 构建 VLESS Reality
         let vless_reality = if let Some(port) = self.vless_reality_port {
-            let mut builder = AutoDefault::vless_reality().public_ip(public_ip).port(port);
+            let mut builder = self
+                .apply_listen_options(AutoDefault::vless_reality().public_ip(public_ip).port(port));
             for user in &users {
                 if let Some(ref uuid) = user.uuid {
                     builder = builder.add_tuic_user(&user.name, uuid, &user.password);
@@ -1078,19 +2189,96 @@ impl MultiProtocolBuilder {
             if let Some((server, hs_port)) = &self.vless_handshake {
                 builder = builder.handshake_server(server, *hs_port);
             }
+            if let Some((count, len)) = self.reality_short_id_spec {
+                builder = builder.short_ids(count, len);
+            }
+            builder = builder.custom_short_ids(self.reality_custom_short_ids.iter().cloned());
+            if let Some(ref key) = self.reality_private_key {
+                builder = builder.reality_private_key(key);
+            }
+            if let Some(ref bin) = self.reality_keygen_bin {
+                builder = builder.reality_keygen_sing_box(bin);
+            }
             Some(builder.build_vless_reality()?)
         } else {
             None
         };
 
-        Ok(MultiProtocolResult {
+        // 构建 VLESS WebSocket / gRPC
+        let vless_transport_builder = |protocol: Protocol, port: u16| {
+            let mut builder = self.apply_listen_options(
+                self.apply_acme(
+                    AutoDefault::new(protocol)
+                        .public_ip(public_ip)
+                        .domain(domain.clone())
+                        .port(port),
+                ),
+            );
+            for user in &users {
+                if let Some(ref uuid) = user.uuid {
+                    builder = builder.add_tuic_user(&user.name, uuid, &user.password);
+                } else {
+                    builder = builder.add_user(&user.name);
+                }
+            }
+            if let Some((up, down)) = self.tcp_brutal {
+                builder = builder.tcp_brutal(up, down);
+            }
+            if let Some(ref mux) = self.multiplex {
+                builder = builder.multiplex(mux.clone());
+            }
+            if let Some(ref host) = self.cdn_host {
+                builder = builder.cdn_host(host);
+            }
+            builder
+        };
+        let vless_ws = match &self.vless_ws {
+            Some((port, path)) => {
+                let mut builder = vless_transport_builder(Protocol::VlessWs, *port).ws_path(path);
+                if let Some(ref host) = self.vless_ws_host {
+                    builder = builder.ws_host(host);
+                }
+                Some(builder.build_vless_ws()?)
+            }
+            None => None,
+        };
+        let vless_grpc = match &self.vless_grpc {
+            Some((port, service_name)) => Some(
+                vless_transport_builder(Protocol::VlessGrpc, *port)
+                    .grpc_service_name(service_name)
+                    .build_vless_grpc()?,
+            ),
+            None => None,
+        };
+
+        let mut result = MultiProtocolResult {
             public_ip,
             domain,
             anytls,
             hysteria2,
             tuic,
             vless_reality,
-        })
+            vless_ws,
+            vless_grpc,
+        };
+        // 各协议构建器只复制凭证，按用户名回填过期时间
+        let infos = [
+            result.anytls.as_mut().map(|r| &mut r.info),
+            result.hysteria2.as_mut().map(|r| &mut r.info),
+            result.tuic.as_mut().map(|r| &mut r.info),
+            result.vless_reality.as_mut().map(|r| &mut r.info),
+            result.vless_ws.as_mut().map(|r| &mut r.info),
+            result.vless_grpc.as_mut().map(|r| &mut r.info),
+        ];
+        for info in infos.into_iter().flatten() {
+            for user in &mut info.users {
+                user.expires_at = users
+                    .iter()
+                    .find(|u| u.name == user.name)
+                    .and_then(|u| u.expires_at);
+            }
+        }
+        Ok(result)
     }
 }
 
@@ -1238,6 +2426,12 @@ mod tests {
         assert_eq!(result.anytls.as_ref().unwrap().info.port, 443);
         assert_eq!(result.hysteria2.as_ref().unwrap().info.port, 2053);
         assert_eq!(result.tuic.as_ref().unwrap().info.port, 2083);
+        assert_eq!(result.port(Protocol::Hysteria2), Some(2053));
+        assert_eq!(result.port(Protocol::VlessReality), None);
+        assert_eq!(
+            result.enabled_protocols(),
+            vec![Protocol::AnyTls, Protocol::Hysteria2, Protocol::Tuic]
+        );
     }
 
     #[test]
@@ -1251,6 +2445,59 @@ mod tests {
         assert!(result.anytls.is_some());
         assert!(result.hysteria2.is_some());
         assert!(result.tuic.is_some());
+        assert_eq!(result.domain, "1-2-3-4.sslip.io");
+
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .domain_provider(DomainProvider::Nip)
+            .enable_anytls(443)
+            .build()
+            .unwrap();
+        assert_eq!(result.domain, "1.2.3.4.nip.io");
+    }
+
+    #[test]
+    fn test_port_conflict() {
+        // 同为 TCP 的协议不能共用端口
+        let err = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_anytls(443)
+            .enable_vless_reality(443)
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AutoDefaultError::PortConflict {
+                port: 443,
+                transport: Transport::Tcp,
+                first: Protocol::AnyTls,
+                second: Protocol::VlessReality,
+            }
+        ));
+
+        // 同为 UDP 的协议不能共用端口
+        let err = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_hysteria2(8443)
+            .enable_tuic(8443)
+            .build()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("hysteria2 与 tuic 都使用 udp 端口 8443")
+        );
+
+        // TCP 与 UDP 可以共用端口，端口 0 表示自动分配
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_anytls(443)
+            .enable_hysteria2(443)
+            .enable_tuic(0)
+            .enable_vless_reality(0)
+            .build()
+            .unwrap();
+        assert_eq!(result.anytls.unwrap().info.port, 443);
+        assert_eq!(result.hysteria2.unwrap().info.port, 443);
     }
 
     #[test]
@@ -1280,6 +2527,69 @@ mod tests {
         assert!(json.contains("1-2-3-4.sslip.io"));
     }
 
+    #[test]
+    fn test_acme_dns01() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .domain("example.com")
+            .enable_anytls(443)
+            .enable_hysteria2(8443)
+            .acme_dns01_cloudflare("cf-token")
+            .build()
+            .unwrap();
+
+        let anytls = serde_json::to_value(&result.anytls.unwrap().inbound).unwrap();
+        let dns01 = &anytls["tls"]["acme"]["dns01_challenge"];
+        assert_eq!(dns01["provider"], "cloudflare");
+        assert_eq!(dns01["api_token"], "cf-token");
+
+        // 使用 DNS-01 时不设置 TLS-ALPN 备用端口
+        let hy2 = serde_json::to_value(&result.hysteria2.unwrap().inbound).unwrap();
+        assert_eq!(
+            hy2["tls"]["acme"]["dns01_challenge"]["provider"],
+            "cloudflare"
+        );
+        assert!(hy2["tls"]["acme"].get("alternative_tls_port").is_none());
+    }
+
+    #[test]
+    fn test_acme_provider_and_eab() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .domain("example.com")
+            .enable_anytls(443)
+            .enable_tuic(2083)
+            .acme_email("admin@example.com")
+            .acme_provider("zerossl".parse().unwrap())
+            .acme_data_directory("/var/lib/ezsingbox/acme")
+            .acme_eab("kid", "hmac")
+            .build()
+            .unwrap();
+
+        for inbound in [
+            serde_json::to_value(&result.anytls.unwrap().inbound).unwrap(),
+            serde_json::to_value(&result.tuic.unwrap().inbound).unwrap(),
+        ] {
+            let acme = &inbound["tls"]["acme"];
+            assert_eq!(acme["provider"], "zerossl");
+            assert_eq!(acme["email"], "admin@example.com");
+            assert_eq!(acme["data_directory"], "/var/lib/ezsingbox/acme");
+            assert_eq!(acme["external_account"]["key_id"], "kid");
+            assert_eq!(acme["external_account"]["mac_key"], "hmac");
+        }
+
+        // 未设置时保持默认值
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_anytls(443)
+            .build()
+            .unwrap();
+        let anytls = serde_json::to_value(&result.anytls.unwrap().inbound).unwrap();
+        assert_eq!(anytls["tls"]["acme"]["data_directory"], "./acme");
+        assert!(anytls["tls"]["acme"].get("provider").is_none());
+        assert!(anytls["tls"]["acme"].get("external_account").is_none());
+    }
+
     #[test]
     fn test_protocol_default_tags() {
         assert_eq!(Protocol::AnyTls.default_tag(), "anytls-in");
@@ -1338,6 +2648,43 @@ mod tests {
         assert!(!keypair1.public_key.is_empty());
     }
 
+    #[test]
+    fn test_reality_keypair_from_private_key() {
+        let keypair = generate_reality_keypair();
+        let restored = RealityKeyPair::from_private_key(&keypair.private_key).unwrap();
+        assert_eq!(restored.private_key, keypair.private_key);
+        assert_eq!(restored.public_key, keypair.public_key);
+
+        assert!(RealityKeyPair::from_private_key("not base64!").is_err());
+        assert!(RealityKeyPair::from_private_key("AAAA").is_err());
+
+        // 解析 sing-box 输出，公钥不匹配时视为无效
+        let output = format!(
+            "PrivateKey: {}\nPublicKey: {}\n",
+            keypair.private_key, keypair.public_key
+        );
+        let parsed = parse_reality_keypair_output(&output).unwrap();
+        assert_eq!(parsed.public_key, keypair.public_key);
+        let output = format!(
+            "PrivateKey: {}\nPublicKey: {}\n",
+            keypair.private_key,
+            generate_reality_keypair().public_key
+        );
+        assert!(parse_reality_keypair_output(&output).is_none());
+
+        // 复用私钥生成的配置保持公钥不变
+        let result = AutoDefault::vless_reality()
+            .public_ip(test_ip())
+            .reality_private_key(&keypair.private_key)
+            .build_vless_reality()
+            .unwrap();
+        assert_eq!(result.public_key, keypair.public_key);
+
+        // sing-box 不可用时回退到内置实现
+        let fallback = generate_reality_keypair_with_sing_box("/nonexistent/sing-box");
+        assert!(RealityKeyPair::from_private_key(&fallback.private_key).is_ok());
+    }
+
     #[test]
     fn test_short_id_generation() {
         let short_id = generate_short_id();
@@ -1346,6 +2693,300 @@ mod tests {
         assert!(short_id.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[test]
+    fn test_reality_short_ids() {
+        let result = AutoDefault::vless_reality()
+            .public_ip(test_ip())
+            .short_ids(3, 2)
+            .build_vless_reality()
+            .unwrap();
+        assert_eq!(result.short_ids.len(), 3);
+        assert!(result.short_ids.iter().all(|id| id.len() == 4));
+        assert_eq!(result.short_id, result.short_ids[0]);
+        let inbound = serde_json::to_value(&result.inbound).unwrap();
+        assert_eq!(
+            inbound["tls"]["reality"]["short_id"],
+            serde_json::json!(result.short_ids)
+        );
+
+        // 指定的短 ID 优先，空字符串也是合法的短 ID
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_vless_reality(2096)
+            .reality_short_ids(5, 8)
+            .reality_custom_short_ids(["0123abcd", ""])
+            .build()
+            .unwrap()
+            .vless_reality
+            .unwrap();
+        assert_eq!(result.short_ids, vec!["0123abcd", ""]);
+        assert_eq!(result.short_id, "0123abcd");
+
+        assert_eq!(generate_short_ids(1, 0).unwrap(), vec![""]);
+        assert_eq!(generate_short_ids(1, 8).unwrap()[0].len(), 16);
+        assert!(generate_short_ids(1, 9).is_err());
+        assert!(generate_short_ids(2, 0).is_err());
+        assert!(generate_short_ids(0, 4).is_err());
+        assert!(validate_short_id("abc").is_err());
+        assert!(validate_short_id("zz").is_err());
+        assert!(validate_short_id("0123456789abcdef01").is_err());
+    }
+
+    #[test]
+    fn test_multi_protocol_with_vless_transports() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .domain("cdn.example.com")
+            .enable_vless_ws(8443, "ray")
+            .enable_vless_grpc(2087, "")
+            .acme_dns01_cloudflare("cf-token")
+            .build()
+            .unwrap();
+        assert!(result.anytls.is_none());
+
+        let ws = result.vless_ws.unwrap();
+        assert_eq!(ws.path, "/ray");
+        let inbound = serde_json::to_value(&ws.inbound).unwrap();
+        assert_eq!(inbound["tag"], "vless-ws-in");
+        assert_eq!(inbound["listen_port"], 8443);
+        assert_eq!(inbound["transport"]["type"], "ws");
+        assert_eq!(inbound["transport"]["path"], "/ray");
+        assert_eq!(inbound["tls"]["server_name"], "cdn.example.com");
+        assert_eq!(
+            inbound["tls"]["acme"]["dns01_challenge"]["provider"],
+            "cloudflare"
+        );
+        // XTLS Vision 不能与 V2Ray 传输层同时使用
+        assert!(inbound["users"][0].get("flow").is_none());
+        assert!(inbound.get("multiplex").is_none());
+        assert!(inbound["users"][0]["uuid"].is_string());
+
+        let grpc = result.vless_grpc.unwrap();
+        assert_eq!(grpc.path.len(), 16);
+        let inbound = serde_json::to_value(&grpc.inbound).unwrap();
+        assert_eq!(inbound["transport"]["type"], "grpc");
+        assert_eq!(inbound["transport"]["service_name"], grpc.path.as_str());
+
+        let err = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_anytls(443)
+            .enable_vless_ws(443, "/ws")
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AutoDefaultError::PortConflict { port: 443, .. }
+        ));
+    }
+
+    #[test]
+    fn test_multi_protocol_tuic_options() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_tuic(2083)
+            .tuic_auth_timeout(Duration::from_secs(5))
+            .tuic_zero_rtt(true)
+            .tuic_heartbeat(Duration::from_secs(15))
+            .tuic_udp_relay_mode(UdpRelayMode::Quic)
+            .build()
+            .unwrap();
+
+        let tuic = result.tuic.unwrap();
+        assert_eq!(tuic.udp_relay_mode, UdpRelayMode::Quic);
+        let inbound = serde_json::to_value(&tuic.inbound).unwrap();
+        assert_eq!(inbound["auth_timeout"], "5s");
+        assert_eq!(inbound["zero_rtt_handshake"], true);
+        assert_eq!(inbound["heartbeat"], "15s");
+    }
+
+    #[test]
+    fn test_multi_protocol_user_credentials() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_tuic(2083)
+            .enable_vless_reality(2096)
+            .add_user_with_credentials("alice", "pwd", "uuid-1")
+            .add_generated_user(GeneratedUser::with_uuid("bob").with_expires_at(1000))
+            .build()
+            .unwrap();
+        for users in [
+            &result.tuic.unwrap().info.users,
+            &result.vless_reality.unwrap().info.users,
+        ] {
+            assert_eq!(users.len(), 2);
+            assert_eq!(users[0].password, "pwd");
+            assert_eq!(users[0].uuid.as_deref(), Some("uuid-1"));
+            assert_eq!(users[0].expires_at, None);
+            assert_eq!(users[1].expires_at, Some(1000));
+            assert!(users[1].is_expired(1000));
+            assert!(!users[1].is_expired(999));
+        }
+    }
+
+    #[test]
+    fn test_multi_protocol_hy2_bandwidth_and_brutal() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_hysteria2(2053)
+            .enable_vless_ws(8443, "/ws")
+            .hy2_ignore_client_bandwidth(true)
+            .tcp_brutal(50, 200)
+            .build()
+            .unwrap();
+
+        let hy2 = serde_json::to_value(&result.hysteria2.unwrap().inbound).unwrap();
+        assert_eq!(hy2["ignore_client_bandwidth"], true);
+
+        let ws = serde_json::to_value(&result.vless_ws.unwrap().inbound).unwrap();
+        assert_eq!(ws["multiplex"]["enabled"], true);
+        assert_eq!(ws["multiplex"]["brutal"]["up_mbps"], 50);
+        assert_eq!(ws["multiplex"]["brutal"]["down_mbps"], 200);
+    }
+
+    #[test]
+    fn test_multi_protocol_multiplex() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_vless_grpc(2087, "grpc")
+            .multiplex(
+                MultiplexOutbound::new()
+                    .smux()
+                    .with_max_streams(8)
+                    .with_padding(true),
+            )
+            .tcp_brutal(50, 200)
+            .build()
+            .unwrap();
+
+        let grpc = result.vless_grpc.unwrap();
+        let inbound = serde_json::to_value(&grpc.inbound).unwrap();
+        assert_eq!(inbound["multiplex"]["enabled"], true);
+        assert_eq!(inbound["multiplex"]["padding"], true);
+        assert_eq!(inbound["multiplex"]["brutal"]["down_mbps"], 200);
+
+        let client = serde_json::to_value(grpc.multiplex.unwrap()).unwrap();
+        assert_eq!(client["enabled"], true);
+        assert_eq!(client["protocol"], "smux");
+        assert_eq!(client["max_streams"], 8);
+        assert_eq!(client["brutal"]["up_mbps"], 50);
+    }
+
+    #[test]
+    fn test_multi_protocol_presets() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .preset(Preset::Minimal)
+            .build()
+            .unwrap();
+        assert_eq!(result.anytls.unwrap().info.port, 443);
+        assert!(result.hysteria2.is_none() && result.tuic.is_none());
+
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .preset(Preset::Stealth)
+            .build()
+            .unwrap();
+        assert!(result.anytls.is_none());
+        assert_eq!(result.vless_reality.unwrap().info.port, 443);
+        assert!(result.hysteria2.unwrap().obfs_password.is_some());
+    }
+
+    #[test]
+    fn test_multi_protocol_cdn_preset() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .domain("example.com")
+            .enable_all()
+            .preset(Preset::Cdn)
+            .cdn_host("cdn.example.net")
+            .vless_ws_host("ws.example.com")
+            .build()
+            .unwrap();
+        assert!(result.anytls.is_none() && result.hysteria2.is_none());
+        assert!(result.vless_reality.is_none() && result.vless_grpc.is_none());
+
+        let ws = result.vless_ws.unwrap();
+        assert_eq!(ws.info.port, 443);
+        assert_eq!(ws.cdn_host.as_deref(), Some("cdn.example.net"));
+        assert_eq!(ws.host.as_deref(), Some("ws.example.com"));
+        let inbound = serde_json::to_value(&ws.inbound).unwrap();
+        assert_eq!(inbound["tls"]["server_name"], "example.com");
+
+        // CDN 需要已接入的域名
+        let err = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .preset(Preset::Cdn)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, AutoDefaultError::ConfigError(_)));
+    }
+
+    #[test]
+    fn test_multi_protocol_listen_options() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_hysteria2(2053)
+            .enable_vless_reality(2096)
+            .tcp_fast_open(true)
+            .udp_timeout(Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        for inbound in [
+            serde_json::to_value(&result.hysteria2.unwrap().inbound).unwrap(),
+            serde_json::to_value(&result.vless_reality.unwrap().inbound).unwrap(),
+        ] {
+            assert_eq!(inbound["tcp_fast_open"], true);
+            assert_eq!(inbound["udp_timeout"], "1m");
+            assert_eq!(inbound["sniff"], true);
+            assert_eq!(inbound["sniff_override_destination"], true);
+        }
+
+        // 按协议关闭嗅探
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_anytls(443)
+            .enable_tuic(2083)
+            .sniff_override_destination(false)
+            .protocol_sniff(Protocol::Tuic, false)
+            .build()
+            .unwrap();
+        let anytls = serde_json::to_value(&result.anytls.unwrap().inbound).unwrap();
+        assert_eq!(anytls["sniff"], true);
+        assert!(anytls.get("sniff_override_destination").is_none());
+        let tuic = serde_json::to_value(&result.tuic.unwrap().inbound).unwrap();
+        assert!(tuic.get("sniff").is_none());
+
+        // 按协议设置监听地址，其余协议保持默认
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_vless_reality(2096)
+            .enable_hysteria2(2053)
+            .enable_tuic(2083)
+            .protocol_listen(Protocol::VlessReality, "10.0.0.5")
+            .build()
+            .unwrap();
+        let vless = serde_json::to_value(&result.vless_reality.unwrap().inbound).unwrap();
+        assert_eq!(vless["listen"], "10.0.0.5");
+        let hy2 = serde_json::to_value(&result.hysteria2.unwrap().inbound).unwrap();
+        assert_eq!(hy2["listen"], "0.0.0.0");
+        let tuic = serde_json::to_value(&result.tuic.unwrap().inbound).unwrap();
+        assert_eq!(tuic["listen"], "::");
+
+        // 绑定网络接口与路由标记
+        let result = MultiProtocolBuilder::new()
+            .public_ip(test_ip())
+            .enable_anytls(443)
+            .bind_interface("eth1")
+            .routing_mark(0x1234)
+            .build()
+            .unwrap();
+        let anytls = serde_json::to_value(&result.anytls.unwrap().inbound).unwrap();
+        assert_eq!(anytls["bind_interface"], "eth1");
+        assert_eq!(anytls["routing_mark"], 0x1234);
+        assert!(anytls.get("netns").is_none());
+    }
+
     #[test]
     fn test_multi_protocol_with_vless_reality() {
         let result = MultiProtocolBuilder::new()
@@ -1371,6 +3012,19 @@ mod tests {
         assert_eq!(result.inbound.tag, "my-custom-tag");
     }
 
+    #[test]
+    fn test_ephemeral_port() {
+        let result = AutoDefault::hysteria2()
+            .public_ip(test_ip())
+            .port(0)
+            .build_hysteria2()
+            .unwrap();
+
+        assert!(result.info.ephemeral_port);
+        assert_ne!(result.info.port, 0);
+        assert_eq!(result.inbound.listen.listen_port, Some(result.info.port));
+    }
+
     #[test]
     fn test_fallback_port_usage() {
         let result = AutoDefault::anytls()
@@ -6,7 +6,8 @@ use crate::singboxconfig::types::UserWithPassword;
 
 // 从 tools 模块导入通用功能
 use super::tools::{
-    PublicIpError, TlsMode, UserConfig, generate_password, generate_sslip_domain, get_public_ip,
+    DEFAULT_ACME_DATA_DIR, PublicIpError, TlsMode, UserConfig, generate_password,
+    generate_sslip_domain, get_public_ip,
 };
 
 //============================================================================
@@ -318,6 +319,7 @@ impl AutoAnyTlsConfig {
                 let acme = AcmeConfig {
                     domain: Some(vec![actual_domain.clone()]),
                     email: email.clone(),
+                    data_directory: Some(DEFAULT_ACME_DATA_DIR.to_string()),
                     ..Default::default()
                 };
 
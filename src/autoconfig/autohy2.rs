@@ -1,6 +1,6 @@
 use std::net::IpAddr;
 
-use crate::singboxconfig::inbound::{Hysteria2Inbound};
+use crate::singboxconfig::inbound::{Hysteria2Inbound, Hysteria2Masquerade};
 use crate::singboxconfig::shared::{AcmeConfig, InboundTlsConfig};
 use crate::singboxconfig::types::UserWithPassword;
 
@@ -34,8 +34,8 @@ pub struct AutoHysteria2Config {
     pub down_mbps: Option<u32>,
     /// 混淆密码（如果设置则启用 salamander 混淆）
     pub obfs_password: Option<String>,
-    /// 伪装 URL
-    pub masquerade_url: Option<String>,
+    /// 伪装（反向代理、文件服务器或固定响应）
+    pub masquerade: Option<Hysteria2Masquerade>,
     /// 忽略客户端带宽设置
     pub ignore_client_bandwidth: Option<bool>,
 }
@@ -52,7 +52,7 @@ impl Default for AutoHysteria2Config {
             up_mbps: None,
             down_mbps: None,
             obfs_password: None,
-            masquerade_url: None,
+            masquerade: None,
             ignore_client_bandwidth: None,
         }
     }
@@ -155,9 +155,29 @@ impl AutoHysteria2Builder {
         self
     }
 
-    /// 设置伪装 URL
-    pub fn with_masquerade(mut self, url: impl Into<String>) -> Self {
-        self.config.masquerade_url = Some(url.into());
+    /// 设置伪装 URL（file:// 为文件服务器，http(s):// 为反向代理）
+    pub fn with_masquerade(self, url: impl Into<String>) -> Self {
+        self.with_masquerade_config(Hysteria2Masquerade::Url(url.into()))
+    }
+
+    /// 伪装为反向代理，转发到指定 URL 并重写 Host 头
+    pub fn with_masquerade_proxy(self, url: impl Into<String>) -> Self {
+        self.with_masquerade_config(Hysteria2Masquerade::proxy(url, true))
+    }
+
+    /// 伪装为文件服务器，提供指定目录下的文件
+    pub fn with_masquerade_file(self, directory: impl Into<String>) -> Self {
+        self.with_masquerade_config(Hysteria2Masquerade::file(directory))
+    }
+
+    /// 伪装为固定响应（如 404）
+    pub fn with_masquerade_string(self, status_code: u16, content: Option<String>) -> Self {
+        self.with_masquerade_config(Hysteria2Masquerade::string(status_code, content))
+    }
+
+    /// 设置伪装配置
+    pub fn with_masquerade_config(mut self, masquerade: Hysteria2Masquerade) -> Self {
+        self.config.masquerade = Some(masquerade);
         self
     }
 
@@ -313,8 +333,8 @@ impl AutoHysteria2Config {
         }
 
         // 添加伪装
-        if let Some(ref url) = self.masquerade_url {
-            inbound = inbound.with_masquerade_url(url);
+        if let Some(ref masquerade) = self.masquerade {
+            inbound = inbound.with_masquerade(masquerade.clone());
         }
 
         // 忽略客户端带宽
@@ -484,6 +504,15 @@ mod tests {
             .unwrap();
 
         assert!(result.inbound.masquerade.is_some());
+
+        let result = AutoHysteria2Builder::new()
+            .public_ip(ip)
+            .with_masquerade_file("/var/www")
+            .build()
+            .unwrap();
+        let v = serde_json::to_value(&result.inbound).unwrap();
+        assert_eq!(v["masquerade"]["type"], "file");
+        assert_eq!(v["masquerade"]["directory"], "/var/www");
     }
 
     #[test]
@@ -227,8 +227,8 @@ impl AutoTuicBuilder {
     }
 
     /// 设置认证超时时间
-    pub fn auth_timeout(mut self, timeout: Duration) -> Self {
-        self.config.auth_timeout = Some(timeout);
+    pub fn auth_timeout(mut self, timeout: impl Into<Duration>) -> Self {
+        self.config.auth_timeout = Some(timeout.into());
         self
     }
 
@@ -246,8 +246,8 @@ impl AutoTuicBuilder {
     }
 
     /// 设置心跳间隔
-    pub fn heartbeat(mut self, interval: Duration) -> Self {
-        self.config.heartbeat = Some(interval);
+    pub fn heartbeat(mut self, interval: impl Into<Duration>) -> Self {
+        self.config.heartbeat = Some(interval.into());
         self
     }
 
@@ -6,7 +6,10 @@ use crate::singboxconfig::shared::{
 };
 
 //从 tools 模块导入通用功能
-use super::tools::{PublicIpError, TlsMode, generate_sslip_domain, generate_uuid, get_public_ip};
+use super::tools::{
+    DEFAULT_ACME_DATA_DIR, PublicIpError, TlsMode, generate_sslip_domain, generate_uuid,
+    get_public_ip,
+};
 
 //============================================================================
 // VLESS 用户配置
@@ -479,6 +482,7 @@ impl AutoVlessConfig {
                 let acme = AcmeConfig {
                     domain: Some(vec![actual_domain.clone()]),
                     email: email.clone(),
+                    data_directory: Some(DEFAULT_ACME_DATA_DIR.to_string()),
                     ..Default::default()
                 };
 
@@ -0,0 +1,308 @@
+//! 域名提供方
+//!
+//! 未指定域名时按公网 IP 生成域名：泛解析服务（sslip.io / nip.io）、
+//! 自定义泛解析模式，或 DuckDNS 动态域名（写入配置前更新解析记录）
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration as StdDuration;
+
+use super::tools::{generate_nip_domain, generate_sslip_domain};
+
+/// DuckDNS 域名后缀
+pub const DUCKDNS_SUFFIX: &str = ".duckdns.org";
+
+/// DuckDNS 更新接口
+#[cfg(feature = "probe")]
+const DUCKDNS_UPDATE_URL: &str = "https://www.duckdns.org/update";
+
+//============================================================================
+// 提供方
+//============================================================================
+
+/// 域名提供方
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DomainProvider {
+    /// sslip.io 泛解析（1.2.3.4 -> 1-2-3-4.sslip.io）
+    #[default]
+    Sslip,
+    /// nip.io 泛解析（1.2.3.4 -> 1.2.3.4.nip.io）
+    Nip,
+    /// DuckDNS 动态域名，需要更新解析记录
+    DuckDns {
+        /// 子域名（不含 .duckdns.org）
+        subdomain: String,
+        /// 账户 token
+        token: String,
+    },
+    /// 自定义泛解析模式，`{ip}` 替换为点分 IP，`{ip_dashed}` 替换为以 - 分隔的 IP
+    Custom(String),
+}
+
+impl DomainProvider {
+    /// 创建 DuckDNS 提供方，`domain` 可带 .duckdns.org 后缀
+    pub fn duckdns(domain: &str, token: impl Into<String>) -> Result<Self, DomainProviderError> {
+        let subdomain = domain.trim().trim_end_matches(DUCKDNS_SUFFIX);
+        let valid = !subdomain.is_empty()
+            && subdomain
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-');
+        if !valid {
+            return Err(DomainProviderError::Invalid(format!(
+                "无效的 DuckDNS 子域名: {}",
+                domain
+            )));
+        }
+        let token = token.into();
+        if token.trim().is_empty() {
+            return Err(DomainProviderError::Invalid(
+                "DuckDNS 需要设置 token".to_string(),
+            ));
+        }
+        Ok(DomainProvider::DuckDns {
+            subdomain: subdomain.to_ascii_lowercase(),
+            token: token.trim().to_string(),
+        })
+    }
+
+    /// 创建自定义泛解析提供方，模式中必须包含 `{ip}` 或 `{ip_dashed}`
+    pub fn custom(pattern: impl Into<String>) -> Result<Self, DomainProviderError> {
+        let pattern = pattern.into();
+        if !pattern.contains("{ip}") && !pattern.contains("{ip_dashed}") {
+            return Err(DomainProviderError::Invalid(format!(
+                "域名模式必须包含 {{ip}} 或 {{ip_dashed}}: {}",
+                pattern
+            )));
+        }
+        Ok(DomainProvider::Custom(pattern.trim().to_string()))
+    }
+
+    /// 按公网 IP 生成域名
+    pub fn domain_for(&self, ip: &IpAddr) -> String {
+        match self {
+            DomainProvider::Sslip => generate_sslip_domain(ip),
+            DomainProvider::Nip => generate_nip_domain(ip),
+            DomainProvider::DuckDns { subdomain, .. } => {
+                format!("{}{}", subdomain, DUCKDNS_SUFFIX)
+            }
+            DomainProvider::Custom(pattern) => {
+                // IPv6 地址中的 ':' 不能出现在域名中，{ip} 同样使用 - 分隔
+                let dashed = generate_sslip_domain(ip)
+                    .trim_end_matches(".sslip.io")
+                    .to_string();
+                let dotted = match ip {
+                    IpAddr::V4(v4) => v4.to_string(),
+                    IpAddr::V6(_) => dashed.clone(),
+                };
+                pattern
+                    .replace("{ip_dashed}", &dashed)
+                    .replace("{ip}", &dotted)
+            }
+        }
+    }
+
+    /// 生成的域名是否总是解析到对应 IP（无需检查或更新解析记录）
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, DomainProvider::Sslip | DomainProvider::Nip)
+    }
+
+    /// 是否为需要更新解析记录的动态域名
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, DomainProvider::DuckDns { .. })
+    }
+
+    /// 将动态域名的解析记录更新为指定 IP（泛解析提供方无需更新，直接返回）
+    pub fn update_record(
+        &self,
+        ip: &IpAddr,
+        timeout: StdDuration,
+    ) -> Result<(), DomainProviderError> {
+        match self {
+            DomainProvider::DuckDns { subdomain, token } => {
+                update_duckdns(subdomain, token, ip, timeout)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for DomainProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainProvider::Sslip => f.write_str("sslip"),
+            DomainProvider::Nip => f.write_str("nip"),
+            DomainProvider::DuckDns { subdomain, .. } => {
+                write!(f, "duckdns ({}{})", subdomain, DUCKDNS_SUFFIX)
+            }
+            DomainProvider::Custom(pattern) => write!(f, "custom ({})", pattern),
+        }
+    }
+}
+
+/// 解析不需要额外参数的提供方（sslip / nip），以及直接给出的自定义模式
+impl FromStr for DomainProvider {
+    type Err = DomainProviderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "sslip" | "sslip.io" => Ok(DomainProvider::Sslip),
+            "nip" | "nip.io" => Ok(DomainProvider::Nip),
+            _ if s.contains('{') => DomainProvider::custom(s),
+            _ => Err(DomainProviderError::Invalid(format!(
+                "未知的域名提供方: {}（可选 sslip / nip / duckdns / custom）",
+                s
+            ))),
+        }
+    }
+}
+
+//============================================================================
+// 错误
+//============================================================================
+
+/// 域名提供方错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainProviderError {
+    /// 参数无效
+    Invalid(String),
+    /// 网络请求失败
+    Network(String),
+    /// 服务拒绝更新（如 token 错误）
+    Rejected(String),
+    /// 编译时未启用 probe 特性
+    #[cfg_attr(feature = "probe", allow(dead_code))]
+    ProbeDisabled,
+}
+
+impl fmt::Display for DomainProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainProviderError::Invalid(msg) => f.write_str(msg),
+            DomainProviderError::Network(msg) => write!(f, "更新动态域名失败: {}", msg),
+            DomainProviderError::Rejected(msg) => write!(f, "动态域名服务拒绝更新: {}", msg),
+            DomainProviderError::ProbeDisabled => {
+                write!(f, "未启用 HTTP 客户端（probe 特性），无法更新动态域名")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DomainProviderError {}
+
+//============================================================================
+// DuckDNS
+//============================================================================
+
+/// 构造 DuckDNS 更新请求的查询参数（IPv6 地址使用 ipv6 参数）
+fn duckdns_query(subdomain: &str, token: &str, ip: &IpAddr) -> Vec<(&'static str, String)> {
+    let ip_param = if ip.is_ipv6() { "ipv6" } else { "ip" };
+    vec![
+        ("domains", subdomain.to_string()),
+        ("token", token.to_string()),
+        (ip_param, ip.to_string()),
+    ]
+}
+
+/// 检查 DuckDNS 更新响应（成功时为 OK，失败时为 KO）
+fn check_duckdns_response(body: &str) -> Result<(), DomainProviderError> {
+    match body.lines().next().map(str::trim) {
+        Some("OK") => Ok(()),
+        Some("KO") => Err(DomainProviderError::Rejected(
+            "DuckDNS 返回 KO，请检查子域名与 token".to_string(),
+        )),
+        _ => Err(DomainProviderError::Rejected(format!(
+            "DuckDNS 返回意外响应: {}",
+            body.trim()
+        ))),
+    }
+}
+
+/// 调用 DuckDNS 接口更新解析记录
+#[cfg(feature = "probe")]
+fn update_duckdns(
+    subdomain: &str,
+    token: &str,
+    ip: &IpAddr,
+    timeout: StdDuration,
+) -> Result<(), DomainProviderError> {
+    use super::tools::http_agent;
+
+    let body = http_agent(timeout)
+        .get(DUCKDNS_UPDATE_URL)
+        .query_pairs(duckdns_query(subdomain, token, ip))
+        .call()
+        .map_err(|e| DomainProviderError::Network(e.to_string()))?
+        .into_body()
+        .read_to_string()
+        .map_err(|e| DomainProviderError::Network(e.to_string()))?;
+    check_duckdns_response(&body)
+}
+
+/// 未启用 probe 特性时的占位实现
+#[cfg(not(feature = "probe"))]
+fn update_duckdns(
+    _subdomain: &str,
+    _token: &str,
+    _ip: &IpAddr,
+    _timeout: StdDuration,
+) -> Result<(), DomainProviderError> {
+    Err(DomainProviderError::ProbeDisabled)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_for() {
+        let v4: IpAddr = "1.2.3.4".parse().unwrap();
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        assert_eq!(DomainProvider::Sslip.domain_for(&v4), "1-2-3-4.sslip.io");
+        assert_eq!(DomainProvider::Nip.domain_for(&v4), "1.2.3.4.nip.io");
+
+        let custom: DomainProvider = "{ip_dashed}.wild.example.com".parse().unwrap();
+        assert_eq!(custom.domain_for(&v4), "1-2-3-4.wild.example.com");
+        let custom = DomainProvider::custom("{ip}.wild.example.com").unwrap();
+        assert_eq!(custom.domain_for(&v4), "1.2.3.4.wild.example.com");
+        assert_eq!(
+            custom.domain_for(&v6),
+            "2001-db8-0-0-0-0-0-1.wild.example.com"
+        );
+        assert!(DomainProvider::custom("wild.example.com").is_err());
+
+        let duck = DomainProvider::duckdns("MyHost.duckdns.org", "token").unwrap();
+        assert_eq!(duck.domain_for(&v4), "myhost.duckdns.org");
+        assert!(duck.is_dynamic() && !duck.is_wildcard());
+        assert!(DomainProvider::duckdns("my.host", "token").is_err());
+        assert!(DomainProvider::duckdns("myhost", " ").is_err());
+
+        assert!("nip.io".parse::<DomainProvider>().unwrap().is_wildcard());
+        assert!("duckdns".parse::<DomainProvider>().is_err());
+    }
+
+    #[test]
+    fn test_duckdns_update() {
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        let query = duckdns_query("myhost", "t", &v6);
+        assert_eq!(query[2], ("ipv6", "2001:db8::1".to_string()));
+        assert!(check_duckdns_response("OK\n").is_ok());
+        assert!(matches!(
+            check_duckdns_response("KO"),
+            Err(DomainProviderError::Rejected(_))
+        ));
+        assert!(check_duckdns_response("<html>").is_err());
+        // 泛解析提供方不需要更新
+        let v4: IpAddr = "1.2.3.4".parse().unwrap();
+        assert!(
+            DomainProvider::Sslip
+                .update_record(&v4, StdDuration::from_secs(1))
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! 公网 IP 探测后端
+//!
+//! 除 HTTP 查询服务外，支持 DNS 查询、读取网卡地址与云厂商元数据服务，
+//! 按指定顺序依次尝试，直到某一方式成功
+
+use std::fmt;
+use std::net::{IpAddr, UdpSocket};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration as StdDuration;
+
+use super::tools::{PublicIpError, get_ip_from_services};
+
+//============================================================================
+// 探测方式
+//============================================================================
+
+/// 公网 IP 探测方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpDetector {
+    /// HTTP 查询公网 IP 服务
+    Http,
+    /// DNS 查询（OpenDNS myip / Cloudflare whoami）
+    Dns,
+    /// 读取指定网卡的全局地址
+    Interface(String),
+    /// 云厂商元数据服务（EC2 / GCP / Azure）
+    /// OCI 元数据不包含公网 IP，无法通过此方式获取
+    Metadata,
+}
+
+impl IpDetector {
+    /// 默认探测顺序
+    pub fn default_order() -> Vec<IpDetector> {
+        vec![IpDetector::Http, IpDetector::Dns, IpDetector::Metadata]
+    }
+
+    /// 执行探测，`services` 为 HTTP 探测使用的服务列表（为空时使用默认列表）
+    pub fn detect(
+        &self,
+        services: &[String],
+        timeout: StdDuration,
+    ) -> Result<IpAddr, PublicIpError> {
+        match self {
+            IpDetector::Http => get_ip_from_services(services, timeout),
+            IpDetector::Dns => detect_dns(timeout),
+            IpDetector::Interface(name) => detect_interface(name),
+            IpDetector::Metadata => detect_metadata(timeout),
+        }
+    }
+}
+
+impl fmt::Display for IpDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpDetector::Http => f.write_str("http"),
+            IpDetector::Dns => f.write_str("dns"),
+            IpDetector::Interface(name) => write!(f, "interface:{}", name),
+            IpDetector::Metadata => f.write_str("metadata"),
+        }
+    }
+}
+
+impl FromStr for IpDetector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(name) = s.strip_prefix("interface:") {
+            if name.is_empty() {
+                return Err("interface 需要指定网卡名，如 interface:eth0".to_string());
+            }
+            return Ok(IpDetector::Interface(name.to_string()));
+        }
+        match s.to_ascii_lowercase().as_str() {
+            "http" => Ok(IpDetector::Http),
+            "dns" => Ok(IpDetector::Dns),
+            "metadata" => Ok(IpDetector::Metadata),
+            _ => Err(format!("未知的公网 IP 探测方式: {}", s)),
+        }
+    }
+}
+
+/// 依次尝试各探测方式，返回第一个成功的结果
+/// 只指定了一种方式时返回其具体错误
+pub fn detect_public_ip(
+    detectors: &[IpDetector],
+    services: &[String],
+    timeout: StdDuration,
+) -> Result<IpAddr, PublicIpError> {
+    let mut last_error = PublicIpError::AllServicesFailed;
+    for detector in detectors {
+        match detector.detect(services, timeout) {
+            Ok(ip) => return Ok(ip),
+            Err(e) => last_error = e,
+        }
+    }
+    if detectors.len() == 1 {
+        Err(last_error)
+    } else {
+        Err(PublicIpError::AllServicesFailed)
+    }
+}
+
+//============================================================================
+// DNS 探测
+//============================================================================
+
+/// OpenDNS 解析器（myip.opendns.com 返回查询方地址）
+const OPENDNS_RESOLVER: &str = "208.67.222.222:53";
+/// Cloudflare 解析器（CHAOS 类 whoami.cloudflare 返回查询方地址）
+const CLOUDFLARE_RESOLVER: &str = "1.1.1.1:53";
+
+const DNS_TYPE_A: u16 = 1;
+const DNS_TYPE_TXT: u16 = 16;
+const DNS_CLASS_IN: u16 = 1;
+const DNS_CLASS_CH: u16 = 3;
+
+/// 通过 DNS 查询公网 IP
+fn detect_dns(timeout: StdDuration) -> Result<IpAddr, PublicIpError> {
+    let opendns = dns_query(
+        OPENDNS_RESOLVER,
+        "myip.opendns.com",
+        DNS_TYPE_A,
+        DNS_CLASS_IN,
+        timeout,
+    )
+    .and_then(|answers| {
+        answers
+            .iter()
+            .find_map(|rdata| <[u8; 4]>::try_from(rdata.as_slice()).ok())
+            .map(IpAddr::from)
+            .ok_or_else(|| PublicIpError::ParseError("OpenDNS 未返回 A 记录".to_string()))
+    });
+    if opendns.is_ok() {
+        return opendns;
+    }
+
+    let answers = dns_query(
+        CLOUDFLARE_RESOLVER,
+        "whoami.cloudflare",
+        DNS_TYPE_TXT,
+        DNS_CLASS_CH,
+        timeout,
+    )?;
+    answers
+        .iter()
+        .find_map(|rdata| parse_txt(rdata).and_then(|s| s.parse().ok()))
+        .ok_or_else(|| PublicIpError::ParseError("Cloudflare 未返回 TXT 记录".to_string()))
+}
+
+/// 发送单个 DNS 查询并返回匹配类型的应答数据
+fn dns_query(
+    resolver: &str,
+    name: &str,
+    qtype: u16,
+    qclass: u16,
+    timeout: StdDuration,
+) -> Result<Vec<Vec<u8>>, PublicIpError> {
+    let net_err = |e: std::io::Error| PublicIpError::NetworkError(format!("{}: {}", resolver, e));
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(net_err)?;
+    socket.set_read_timeout(Some(timeout)).map_err(net_err)?;
+    socket.connect(resolver).map_err(net_err)?;
+
+    let id: u16 = rand::random();
+    socket
+        .send(&build_dns_query(id, name, qtype, qclass))
+        .map_err(net_err)?;
+    let mut buf = [0u8; 512];
+    let len = socket.recv(&mut buf).map_err(net_err)?;
+    parse_dns_answers(&buf[..len], id, qtype)
+}
+
+/// 构造 DNS 查询报文（递归查询，单个问题）
+fn build_dns_query(id: u16, name: &str, qtype: u16, qclass: u16) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(32 + name.len());
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&0x0100u16.to_be_bytes());
+    msg.extend_from_slice(&1u16.to_be_bytes());
+    msg.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        msg.push(label.len() as u8);
+        msg.extend_from_slice(label.as_bytes());
+    }
+    msg.push(0);
+    msg.extend_from_slice(&qtype.to_be_bytes());
+    msg.extend_from_slice(&qclass.to_be_bytes());
+    msg
+}
+
+/// 解析 DNS 应答，返回指定类型记录的数据
+fn parse_dns_answers(msg: &[u8], id: u16, qtype: u16) -> Result<Vec<Vec<u8>>, PublicIpError> {
+    let malformed = || PublicIpError::ParseError("DNS 应答格式错误".to_string());
+    let read_u16 = |pos: usize| -> Option<u16> {
+        msg.get(pos..pos + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+
+    if read_u16(0).ok_or_else(malformed)? != id {
+        return Err(malformed());
+    }
+    let flags = read_u16(2).ok_or_else(malformed)?;
+    let rcode = flags & 0x000f;
+    if rcode != 0 {
+        return Err(PublicIpError::NetworkError(format!(
+            "DNS 查询失败 (rcode {})",
+            rcode
+        )));
+    }
+    let qdcount = read_u16(4).ok_or_else(malformed)?;
+    let ancount = read_u16(6).ok_or_else(malformed)?;
+
+    let mut pos = 12;
+    for _ in 0..qdcount {
+        pos = skip_dns_name(msg, pos).ok_or_else(malformed)? + 4;
+    }
+
+    let mut answers = Vec::new();
+    for _ in 0..ancount {
+        pos = skip_dns_name(msg, pos).ok_or_else(malformed)?;
+        let rtype = read_u16(pos).ok_or_else(malformed)?;
+        let rdlen = read_u16(pos + 8).ok_or_else(malformed)? as usize;
+        let start = pos + 10;
+        let rdata = msg.get(start..start + rdlen).ok_or_else(malformed)?;
+        if rtype == qtype {
+            answers.push(rdata.to_vec());
+        }
+        pos = start + rdlen;
+    }
+    Ok(answers)
+}
+
+/// 跳过域名字段（支持压缩指针），返回其后的位置
+fn skip_dns_name(msg: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *msg.get(pos)?;
+        if len == 0 {
+            return Some(pos + 1);
+        }
+        if len & 0xc0 == 0xc0 {
+            return Some(pos + 2);
+        }
+        pos += 1 + len as usize;
+    }
+}
+
+/// 读取 TXT 记录的第一个字符串
+fn parse_txt(rdata: &[u8]) -> Option<String> {
+    let len = *rdata.first()? as usize;
+    let text = rdata.get(1..1 + len)?;
+    std::str::from_utf8(text).ok().map(str::to_string)
+}
+
+//============================================================================
+// 网卡地址
+//============================================================================
+
+/// 读取网卡的全局地址（优先 IPv4）
+fn detect_interface(name: &str) -> Result<IpAddr, PublicIpError> {
+    let output = Command::new("ip")
+        .args(["-o", "addr", "show", "dev", name])
+        .output()
+        .map_err(|e| PublicIpError::InterfaceError(format!("执行 ip 命令失败: {}", e)))?;
+    if !output.status.success() {
+        return Err(PublicIpError::InterfaceError(format!(
+            "{}: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    parse_interface_addr(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| PublicIpError::InterfaceError(format!("{} 没有全局地址", name)))
+}
+
+/// 从 `ip -o addr` 输出中提取全局地址（优先 IPv4）
+fn parse_interface_addr(output: &str) -> Option<IpAddr> {
+    let mut addrs: Vec<IpAddr> = output
+        .lines()
+        .filter(|line| line.contains("scope global"))
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            tokens.find(|t| *t == "inet" || *t == "inet6")?;
+            tokens.next()?.split('/').next()?.parse().ok()
+        })
+        .collect();
+    addrs.sort_by_key(|ip| ip.is_ipv6());
+    addrs.into_iter().next()
+}
+
+//============================================================================
+// 云厂商元数据
+//============================================================================
+
+/// 元数据服务超时（非云主机上请求会一直挂起，需尽快放弃）
+#[cfg(feature = "probe")]
+const METADATA_TIMEOUT: StdDuration = StdDuration::from_secs(2);
+
+#[cfg(feature = "probe")]
+const EC2_TOKEN_URL: &str = "http://169.254.169.254/latest/api/token";
+#[cfg(feature = "probe")]
+const EC2_IP_URL: &str = "http://169.254.169.254/latest/meta-data/public-ipv4";
+#[cfg(feature = "probe")]
+const GCP_IP_URL: &str = "http://metadata.google.internal/computeMetadata/v1/instance/network-interfaces/0/access-configs/0/external-ip";
+#[cfg(feature = "probe")]
+const AZURE_IP_URL: &str = "http://169.254.169.254/metadata/instance/network/interface/0/ipv4/ipAddress/0/publicIpAddress?api-version=2021-02-01&format=text";
+
+/// 通过云厂商元数据服务获取公网 IP
+#[cfg(feature = "probe")]
+fn detect_metadata(timeout: StdDuration) -> Result<IpAddr, PublicIpError> {
+    use super::tools::http_agent;
+
+    let agent = http_agent(timeout.min(METADATA_TIMEOUT));
+    let read_ip = |resp: Result<ureq::http::Response<ureq::Body>, ureq::Error>| {
+        let body = resp
+            .map_err(|e| PublicIpError::NetworkError(e.to_string()))?
+            .into_body()
+            .read_to_string()
+            .map_err(|e| PublicIpError::NetworkError(e.to_string()))?;
+        body.trim()
+            .parse::<IpAddr>()
+            .map_err(|e| PublicIpError::ParseError(format!("{}: {}", body.trim(), e)))
+    };
+
+    // EC2 (IMDSv2)
+    let ec2 = agent
+        .put(EC2_TOKEN_URL)
+        .header("X-aws-ec2-metadata-token-ttl-seconds", "60")
+        .send_empty()
+        .map_err(|e| PublicIpError::NetworkError(e.to_string()))
+        .and_then(|resp| {
+            resp.into_body()
+                .read_to_string()
+                .map_err(|e| PublicIpError::NetworkError(e.to_string()))
+        })
+        .and_then(|token| {
+            read_ip(
+                agent
+                    .get(EC2_IP_URL)
+                    .header("X-aws-ec2-metadata-token", token.trim())
+                    .call(),
+            )
+        });
+    if ec2.is_ok() {
+        return ec2;
+    }
+
+    // GCP
+    let gcp = read_ip(
+        agent
+            .get(GCP_IP_URL)
+            .header("Metadata-Flavor", "Google")
+            .call(),
+    );
+    if gcp.is_ok() {
+        return gcp;
+    }
+
+    // Azure
+    read_ip(agent.get(AZURE_IP_URL).header("Metadata", "true").call())
+}
+
+/// 未启用 probe 特性时的占位实现
+#[cfg(not(feature = "probe"))]
+fn detect_metadata(_timeout: StdDuration) -> Result<IpAddr, PublicIpError> {
+    Err(PublicIpError::ProbeDisabled)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_detector() {
+        assert_eq!("http".parse::<IpDetector>(), Ok(IpDetector::Http));
+        assert_eq!(" DNS ".parse::<IpDetector>(), Ok(IpDetector::Dns));
+        assert_eq!(
+            "interface:eth0".parse::<IpDetector>(),
+            Ok(IpDetector::Interface("eth0".to_string()))
+        );
+        assert!("interface:".parse::<IpDetector>().is_err());
+        assert!("stun".parse::<IpDetector>().is_err());
+        assert_eq!(
+            IpDetector::Interface("wan".to_string()).to_string(),
+            "interface:wan"
+        );
+    }
+
+    #[test]
+    fn test_dns_query_roundtrip() {
+        let query = build_dns_query(0x1234, "myip.opendns.com", DNS_TYPE_A, DNS_CLASS_IN);
+        assert_eq!(&query[..2], &[0x12, 0x34]);
+        assert_eq!(&query[12..17], b"\x04myip");
+
+        // 应答 = 查询 + 一条使用压缩指针的 A 记录
+        let mut resp = query.clone();
+        resp[2] = 0x81;
+        resp[3] = 0x80;
+        resp[7] = 1;
+        resp.extend_from_slice(&[0xc0, 0x0c]);
+        resp.extend_from_slice(&DNS_TYPE_A.to_be_bytes());
+        resp.extend_from_slice(&DNS_CLASS_IN.to_be_bytes());
+        resp.extend_from_slice(&[0, 0, 0, 0]);
+        resp.extend_from_slice(&4u16.to_be_bytes());
+        resp.extend_from_slice(&[203, 0, 113, 7]);
+
+        let answers = parse_dns_answers(&resp, 0x1234, DNS_TYPE_A).unwrap();
+        assert_eq!(answers, vec![vec![203, 0, 113, 7]]);
+        assert!(parse_dns_answers(&resp, 0x9999, DNS_TYPE_A).is_err());
+        assert!(parse_dns_answers(&resp[..resp.len() - 2], 0x1234, DNS_TYPE_A).is_err());
+
+        // rcode 非 0
+        resp[3] = 0x83;
+        assert!(parse_dns_answers(&resp, 0x1234, DNS_TYPE_A).is_err());
+    }
+
+    #[test]
+    fn test_parse_txt() {
+        assert_eq!(parse_txt(b"\x07203.0.1"), Some("203.0.1".to_string()));
+        assert_eq!(parse_txt(b"\x05ab"), None);
+        assert_eq!(parse_txt(b""), None);
+    }
+
+    #[test]
+    fn test_parse_interface_addr() {
+        let output = "\
+1: lo    inet 127.0.0.1/8 scope host lo\\       valid_lft forever preferred_lft forever
+2: eth0    inet6 2001:db8::5/64 scope global dynamic \\       valid_lft 86000sec
+2: eth0    inet6 fe80::1/64 scope link \\       valid_lft forever preferred_lft forever
+2: eth0    inet 203.0.113.5/24 brd 203.0.113.255 scope global eth0\\       valid_lft forever
+";
+        assert_eq!(
+            parse_interface_addr(output),
+            Some("203.0.113.5".parse().unwrap())
+        );
+
+        let v6_only = "2: eth0    inet6 2001:db8::5/64 scope global \\       valid_lft forever\n";
+        assert_eq!(
+            parse_interface_addr(v6_only),
+            Some("2001:db8::5".parse().unwrap())
+        );
+        assert_eq!(
+            parse_interface_addr("1: lo    inet 127.0.0.1/8 scope host lo"),
+            None
+        );
+    }
+}
@@ -8,6 +8,12 @@ mod autoanytls;
 mod autohy2;
 mod autotuic;
 mod autovless;
+mod domainprovider;
+mod ipdetect;
+mod platform;
+mod preset;
+mod protocol;
+mod realitytarget;
 pub mod tools;
 
 // 从 autoanytls 模块导出
@@ -49,10 +55,11 @@ pub use autoByDefault::{
     Hysteria2AutoResult,
     MultiProtocolBuilder,
     MultiProtocolResult,
-    Protocol,
     TuicAutoResult,
     default_port,
     fallback_port,
+    generate_reality_keypair,
+    generate_short_ids,
     // 便捷函数
     quick_all,
     quick_anytls,
@@ -60,9 +67,32 @@ pub use autoByDefault::{
     quick_tuic,
 };
 
+// 从 domainprovider 模块导出
+pub use domainprovider::{DUCKDNS_SUFFIX, DomainProvider, DomainProviderError};
+
+// 从 ipdetect 模块导出
+pub use ipdetect::IpDetector;
+
+// 从 platform 模块导出
+pub use platform::{ClientPlatform, TUN_INBOUND_TAG};
+
+// 从 preset 模块导出
+pub use preset::{ParsePresetError, Preset};
+
+// 从 protocol 模块导出
+pub use protocol::{ParseProtocolError, Protocol, Transport};
+
+// 从 realitytarget 模块导出
+pub use realitytarget::{
+    DEFAULT_TARGET_TIMEOUT, REALITY_TARGET_CANDIDATES, check_reality_target, pick_reality_target,
+};
+
 // 从 tools 模块重新导出常用功能
+#[cfg(feature = "probe")]
+pub use tools::http_agent;
 pub use tools::{
-    PublicIpError, TlsMode, UserConfig, generate_hex_string, generate_nip_domain,
-    generate_password, generate_password_with_length, generate_random_bytes, generate_sslip_domain,
-    generate_uuid, generate_uuid_simple, get_public_ip, get_public_ip_with_timeout,
+    DEFAULT_ACME_DATA_DIR, PublicIpError, TlsMode, UserConfig, generate_hex_string,
+    generate_nip_domain, generate_password, generate_password_with_length, generate_random_bytes,
+    generate_sslip_domain, generate_uuid, generate_uuid_simple, get_public_ip, get_public_ip_with,
+    get_public_ip_with_timeout,
 };
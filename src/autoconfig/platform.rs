@@ -0,0 +1,142 @@
+//! 客户端平台
+//!
+//! 导出的客户端配置按目标平台调整：桌面端使用本地 mixed 代理，
+//! 移动端只使用 TUN 入站，路由器使用 TUN + nftables 自动重定向
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Value, json};
+
+use crate::singboxconfig::inbound::TunInbound;
+
+/// TUN 入站标签
+pub const TUN_INBOUND_TAG: &str = "tun-in";
+
+//============================================================================
+// 平台类型
+//============================================================================
+
+/// 客户端平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ClientPlatform {
+    /// 桌面端：仅本地 mixed 代理，由系统代理或应用设置使用
+    #[default]
+    Desktop,
+    /// iOS：仅 TUN 入站（网络扩展内存受限，使用 system 协议栈）
+    Ios,
+    /// Android：仅 TUN 入站，可按应用包名分流
+    Android,
+    /// 路由器（OpenWrt 等 Linux）：TUN + auto_redirect 透明代理局域网，保留 mixed 代理
+    Router,
+}
+
+impl ClientPlatform {
+    /// 所有平台
+    pub const ALL: [ClientPlatform; 4] = [
+        ClientPlatform::Desktop,
+        ClientPlatform::Ios,
+        ClientPlatform::Android,
+        ClientPlatform::Router,
+    ];
+
+    /// 获取平台名称字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientPlatform::Desktop => "desktop",
+            ClientPlatform::Ios => "ios",
+            ClientPlatform::Android => "android",
+            ClientPlatform::Router => "router",
+        }
+    }
+
+    /// 是否包含本地 mixed 入站
+    pub fn uses_mixed_inbound(&self) -> bool {
+        matches!(self, ClientPlatform::Desktop | ClientPlatform::Router)
+    }
+
+    /// 平台使用的 TUN 入站（桌面端不使用）
+    pub fn tun_inbound(&self) -> Option<TunInbound> {
+        let tun = TunInbound::new(TUN_INBOUND_TAG);
+        match self {
+            ClientPlatform::Desktop => None,
+            ClientPlatform::Ios => Some(tun.with_auto_route(true).with_stack("system")),
+            ClientPlatform::Android => Some(tun.with_auto_route(true).with_stack("mixed")),
+            ClientPlatform::Router => Some(tun.with_auto_redirect()),
+        }
+    }
+
+    /// 是否需要 `route.auto_detect_interface`（使用 TUN 时避免出站流量回环）
+    pub fn auto_detect_interface(&self) -> bool {
+        self.tun_inbound().is_some()
+    }
+
+    /// 平台的 experimental 设置：使用 TUN 的平台启用缓存文件，重启后保留 FakeIP 映射
+    pub fn experimental(&self) -> Option<Value> {
+        self.tun_inbound().map(|_| {
+            json!({
+                "cache_file": {
+                    "enabled": true,
+                    "store_fakeip": true
+                }
+            })
+        })
+    }
+}
+
+impl fmt::Display for ClientPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ClientPlatform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "desktop" => Ok(ClientPlatform::Desktop),
+            "ios" => Ok(ClientPlatform::Ios),
+            "android" => Ok(ClientPlatform::Android),
+            "router" | "openwrt" => Ok(ClientPlatform::Router),
+            other => Err(format!(
+                "未知的客户端平台: {}（可选 desktop / ios / android / router）",
+                other
+            )),
+        }
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_platform() {
+        for p in ClientPlatform::ALL {
+            assert_eq!(p.to_string().parse::<ClientPlatform>().unwrap(), p);
+        }
+        assert_eq!("OpenWrt".parse(), Ok(ClientPlatform::Router));
+        assert!("windows".parse::<ClientPlatform>().is_err());
+
+        let desktop = ClientPlatform::Desktop;
+        assert!(desktop.uses_mixed_inbound() && desktop.tun_inbound().is_none());
+        assert!(desktop.experimental().is_none());
+
+        let ios = ClientPlatform::Ios;
+        assert!(!ios.uses_mixed_inbound() && ios.auto_detect_interface());
+        assert_eq!(ios.tun_inbound().unwrap().stack.as_deref(), Some("system"));
+
+        let router = ClientPlatform::Router.tun_inbound().unwrap();
+        assert_eq!(router.auto_redirect, Some(true));
+        assert_eq!(router.auto_route, Some(true));
+        assert_eq!(
+            ClientPlatform::Router.experimental().unwrap()["cache_file"]["enabled"],
+            true
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! 协议预设模块
+//!
+//! 预设是经过挑选的协议组合及选项，由 `MultiProtocolBuilder::preset` 应用，
+//! 使用者无需了解每个协议的细节
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::protocol::Protocol;
+
+//============================================================================
+// 预设类型
+//============================================================================
+
+/// 协议预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// 最简：仅 AnyTLS
+    Minimal,
+    /// 均衡：AnyTLS + Hysteria2 + VLESS Reality
+    Balanced,
+    /// 隐蔽：443 端口的 VLESS Reality + 启用 Salamander 混淆的 Hysteria2
+    Stealth,
+    /// 全部：所有协议（VLESS WebSocket / gRPC 使用 ACME 证书）
+    Full,
+    /// 经 CDN（如 Cloudflare）转发：仅启用 443 端口的 VLESS over WebSocket，
+    /// 客户端连接 CDN 地址，以源站域名作为 SNI 与 Host
+    Cdn,
+}
+
+impl Preset {
+    /// 所有预设
+    pub const ALL: [Preset; 5] = [
+        Preset::Minimal,
+        Preset::Balanced,
+        Preset::Stealth,
+        Preset::Full,
+        Preset::Cdn,
+    ];
+
+    /// 获取预设名称字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Preset::Minimal => "minimal",
+            Preset::Balanced => "balanced",
+            Preset::Stealth => "stealth",
+            Preset::Full => "full",
+            Preset::Cdn => "cdn",
+        }
+    }
+
+    /// 预设启用的协议及端口
+    pub fn protocols(&self) -> &'static [(Protocol, u16)] {
+        match self {
+            Preset::Minimal => &[(Protocol::AnyTls, 443)],
+            Preset::Balanced => &[
+                (Protocol::AnyTls, 443),
+                (Protocol::Hysteria2, 2053),
+                (Protocol::VlessReality, 2096),
+            ],
+            Preset::Stealth => &[(Protocol::VlessReality, 443), (Protocol::Hysteria2, 2053)],
+            Preset::Full => &[
+                (Protocol::AnyTls, 443),
+                (Protocol::Hysteria2, 2053),
+                (Protocol::Tuic, 2083),
+                (Protocol::VlessReality, 2096),
+                (Protocol::VlessWs, 8443),
+                (Protocol::VlessGrpc, 2087),
+            ],
+            Preset::Cdn => &[(Protocol::VlessWs, 443)],
+        }
+    }
+
+    /// 预设中协议的端口（未启用时为 None）
+    pub fn port(&self, protocol: Protocol) -> Option<u16> {
+        self.protocols()
+            .iter()
+            .find(|(p, _)| *p == protocol)
+            .map(|(_, port)| *port)
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 预设解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePresetError(pub String);
+
+impl fmt::Display for ParsePresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = Preset::ALL.iter().map(Preset::as_str).collect();
+        write!(f, "未知的预设: {}（可选 {}）", self.0, names.join("/"))
+    }
+}
+
+impl std::error::Error for ParsePresetError {}
+
+impl FromStr for Preset {
+    type Err = ParsePresetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        Preset::ALL
+            .into_iter()
+            .find(|p| p.as_str() == lower)
+            .ok_or_else(|| ParsePresetError(s.to_string()))
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_preset() {
+        for p in Preset::ALL {
+            assert_eq!(p.to_string().parse::<Preset>().unwrap(), p);
+        }
+        assert_eq!(" CDN ".parse::<Preset>().unwrap(), Preset::Cdn);
+        assert!("turbo".parse::<Preset>().is_err());
+        assert_eq!(Preset::Cdn.port(Protocol::VlessWs), Some(443));
+        assert_eq!(Preset::Cdn.port(Protocol::AnyTls), None);
+        assert_eq!(Preset::Stealth.port(Protocol::VlessReality), Some(443));
+        assert_eq!(Preset::Full.protocols().len(), Protocol::ALL.len());
+    }
+}
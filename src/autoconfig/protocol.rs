@@ -0,0 +1,207 @@
+//! 协议类型定义模块
+//!
+//! 此模块提供统一的协议枚举，服务端生成、客户端导出和命令行解析共用同一套定义
+
+use std::fmt;
+use std::str::FromStr;
+
+//============================================================================
+// 传输层类型
+//============================================================================
+
+/// 协议使用的传输层
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transport {
+    /// 基于 TCP
+    Tcp,
+    /// 基于 UDP（QUIC）
+    Udp,
+}
+
+impl Transport {
+    /// 获取传输层名称字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transport::Tcp => "tcp",
+            Transport::Udp => "udp",
+        }
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+//============================================================================
+// 协议类型
+//============================================================================
+
+/// 支持的协议类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    /// AnyTLS 协议
+    AnyTls,
+    /// Hysteria2 协议
+    Hysteria2,
+    /// TUIC 协议
+    Tuic,
+    /// VLESS-Vision-uTLS-REALITY 协议
+    VlessReality,
+    /// VLESS over WebSocket（ACME TLS，可经 CDN 转发）
+    VlessWs,
+    /// VLESS over gRPC（ACME TLS，可经 CDN 转发）
+    VlessGrpc,
+}
+
+impl Protocol {
+    /// 所有协议（按默认优先级排序）
+    pub const ALL: [Protocol; 6] = [
+        Protocol::AnyTls,
+        Protocol::Hysteria2,
+        Protocol::Tuic,
+        Protocol::VlessReality,
+        Protocol::VlessWs,
+        Protocol::VlessGrpc,
+    ];
+
+    /// 获取协议名称字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::AnyTls => "anytls",
+            Protocol::Hysteria2 => "hysteria2",
+            Protocol::Tuic => "tuic",
+            Protocol::VlessReality => "vless-reality",
+            Protocol::VlessWs => "vless-ws",
+            Protocol::VlessGrpc => "vless-grpc",
+        }
+    }
+
+    /// 获取协议的默认标签
+    pub fn default_tag(&self) -> &'static str {
+        match self {
+            Protocol::AnyTls => "anytls-in",
+            Protocol::Hysteria2 => "hy2-in",
+            Protocol::Tuic => "tuic-in",
+            Protocol::VlessReality => "vless-reality-in",
+            Protocol::VlessWs => "vless-ws-in",
+            Protocol::VlessGrpc => "vless-grpc-in",
+        }
+    }
+
+    /// 获取协议的默认端口
+    /// VLESS-WS / gRPC 使用 Cloudflare 支持回源的 HTTPS 端口
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Protocol::AnyTls => 443,
+            Protocol::Hysteria2 => 2053,
+            Protocol::Tuic => 2083,
+            Protocol::VlessReality => 2096,
+            Protocol::VlessWs => 8443,
+            Protocol::VlessGrpc => 2087,
+        }
+    }
+
+    /// 获取协议使用的传输层
+    pub fn transport(&self) -> Transport {
+        match self {
+            Protocol::AnyTls | Protocol::VlessReality | Protocol::VlessWs | Protocol::VlessGrpc => {
+                Transport::Tcp
+            }
+            Protocol::Hysteria2 | Protocol::Tuic => Transport::Udp,
+        }
+    }
+
+    /// 用户凭证是否需要 UUID
+    pub fn requires_uuid(&self) -> bool {
+        matches!(
+            self,
+            Protocol::Tuic | Protocol::VlessReality | Protocol::VlessWs | Protocol::VlessGrpc
+        )
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 协议解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseProtocolError(pub String);
+
+impl fmt::Display for ParseProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "未知的协议类型: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseProtocolError {}
+
+impl FromStr for Protocol {
+    type Err = ParseProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "anytls" => Ok(Protocol::AnyTls),
+            "hysteria2" | "hy2" => Ok(Protocol::Hysteria2),
+            "tuic" => Ok(Protocol::Tuic),
+            "vless" | "vless-reality" | "vlessreality" | "reality" => Ok(Protocol::VlessReality),
+            "vless-ws" | "vlessws" | "ws" => Ok(Protocol::VlessWs),
+            "vless-grpc" | "vlessgrpc" | "grpc" => Ok(Protocol::VlessGrpc),
+            _ => Err(ParseProtocolError(s.to_string())),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Protocol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aliases() {
+        assert_eq!("anytls".parse::<Protocol>().unwrap(), Protocol::AnyTls);
+        assert_eq!("HY2".parse::<Protocol>().unwrap(), Protocol::Hysteria2);
+        assert_eq!(" tuic ".parse::<Protocol>().unwrap(), Protocol::Tuic);
+        assert_eq!(
+            "reality".parse::<Protocol>().unwrap(),
+            Protocol::VlessReality
+        );
+        assert_eq!("ws".parse::<Protocol>().unwrap(), Protocol::VlessWs);
+        assert_eq!(
+            "vless-grpc".parse::<Protocol>().unwrap(),
+            Protocol::VlessGrpc
+        );
+        assert!("trojan".parse::<Protocol>().is_err());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for p in Protocol::ALL {
+            assert_eq!(p.to_string().parse::<Protocol>().unwrap(), p);
+        }
+    }
+
+    #[test]
+    fn test_metadata() {
+        assert_eq!(Protocol::AnyTls.default_port(), 443);
+        assert_eq!(Protocol::VlessReality.default_port(), 2096);
+        assert_eq!(Protocol::Hysteria2.transport(), Transport::Udp);
+        assert_eq!(Protocol::AnyTls.transport(), Transport::Tcp);
+        assert!(Protocol::Tuic.requires_uuid());
+        assert!(!Protocol::Hysteria2.requires_uuid());
+    }
+}
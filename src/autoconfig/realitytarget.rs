@@ -0,0 +1,232 @@
+//! REALITY 握手目标检测
+//!
+//! REALITY 借用握手目标的证书完成 TLS 握手，目标必须可以从服务器访问，
+//! 并支持 TLS 1.3 与 HTTP/2，否则客户端握手失败或流量特征异常
+
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration as StdDuration, Instant};
+
+/// 内置握手目标候选（均支持 TLS 1.3 与 HTTP/2）
+pub const REALITY_TARGET_CANDIDATES: &[&str] = &[
+    "www.microsoft.com",
+    "www.apple.com",
+    "www.amazon.com",
+    "addons.mozilla.org",
+    "www.nvidia.com",
+    "www.samsung.com",
+    "dl.google.com",
+    "www.cloudflare.com",
+];
+
+/// 默认检测超时
+pub const DEFAULT_TARGET_TIMEOUT: StdDuration = StdDuration::from_secs(5);
+
+//============================================================================
+// 检测结果
+//============================================================================
+
+/// 握手目标检测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetHealth {
+    /// 目标地址
+    pub server: String,
+    /// 目标端口
+    pub port: u16,
+    /// 是否协商到 TLS 1.3
+    pub tls13: bool,
+    /// 是否协商到 HTTP/2 (ALPN h2)
+    pub h2: bool,
+    /// 建立连接并完成握手的耗时
+    pub latency: StdDuration,
+}
+
+impl TargetHealth {
+    /// 是否适合作为 REALITY 握手目标
+    pub fn is_suitable(&self) -> bool {
+        self.tls13 && self.h2
+    }
+}
+
+impl fmt::Display for TargetHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |v: bool| if v { "是" } else { "否" };
+        write!(
+            f,
+            "{}:{} (TLS 1.3: {}, HTTP/2: {}, 耗时 {} ms)",
+            self.server,
+            self.port,
+            yes_no(self.tls13),
+            yes_no(self.h2),
+            self.latency.as_millis()
+        )
+    }
+}
+
+/// 握手目标检测错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetCheckError {
+    /// 未启用 rustls 特性，无法检测
+    #[cfg_attr(feature = "rustls", allow(dead_code))]
+    Unsupported,
+    /// 无法建立 TCP 连接
+    Connect(String),
+    /// TLS 握手失败
+    Handshake(String),
+}
+
+impl fmt::Display for TargetCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetCheckError::Unsupported => write!(f, "未启用 rustls 特性，无法检测握手目标"),
+            TargetCheckError::Connect(msg) => write!(f, "无法连接: {}", msg),
+            TargetCheckError::Handshake(msg) => write!(f, "TLS 握手失败: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TargetCheckError {}
+
+//============================================================================
+// 检测
+//============================================================================
+
+/// 检测握手目标：建立 TLS 连接并记录协商到的协议版本与 ALPN
+#[cfg(feature = "rustls")]
+pub fn check_reality_target(
+    server: &str,
+    port: u16,
+    timeout: StdDuration,
+) -> Result<TargetHealth, TargetCheckError> {
+    use std::net::{TcpStream, ToSocketAddrs};
+    use std::sync::Arc;
+
+    use rustls::pki_types::ServerName;
+    use rustls::{ClientConfig, ClientConnection, ProtocolVersion, RootCertStore};
+
+    let start = Instant::now();
+    let addrs = (server, port)
+        .to_socket_addrs()
+        .map_err(|e| TargetCheckError::Connect(e.to_string()))?;
+    let mut last_err = format!("{} 没有可用地址", server);
+    let mut stream = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(e) => last_err = e.to_string(),
+        }
+    }
+    let mut stream = stream.ok_or(TargetCheckError::Connect(last_err))?;
+    let io_err = |e: std::io::Error| TargetCheckError::Handshake(e.to_string());
+    stream.set_read_timeout(Some(timeout)).map_err(io_err)?;
+    stream.set_write_timeout(Some(timeout)).map_err(io_err)?;
+
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let mut config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| TargetCheckError::Handshake(e.to_string()))?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    let name = ServerName::try_from(server.to_string())
+        .map_err(|e| TargetCheckError::Handshake(e.to_string()))?;
+    let mut conn = ClientConnection::new(Arc::new(config), name)
+        .map_err(|e| TargetCheckError::Handshake(e.to_string()))?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut stream).map_err(io_err)?;
+    }
+
+    Ok(TargetHealth {
+        server: server.to_string(),
+        port,
+        tls13: conn.protocol_version() == Some(ProtocolVersion::TLSv1_3),
+        h2: conn.alpn_protocol() == Some(b"h2".as_slice()),
+        latency: start.elapsed(),
+    })
+}
+
+/// 检测握手目标（未启用 rustls 特性）
+#[cfg(not(feature = "rustls"))]
+pub fn check_reality_target(
+    _server: &str,
+    _port: u16,
+    _timeout: StdDuration,
+) -> Result<TargetHealth, TargetCheckError> {
+    Err(TargetCheckError::Unsupported)
+}
+
+/// 并行检测候选目标（端口 443），返回耗时最短的合适目标
+pub fn pick_reality_target(candidates: &[&str], timeout: StdDuration) -> Option<TargetHealth> {
+    let (tx, rx) = mpsc::channel();
+    for &server in candidates {
+        let tx = tx.clone();
+        let server = server.to_string();
+        thread::spawn(move || {
+            let _ = tx.send(check_reality_target(&server, 443, timeout));
+        });
+    }
+    drop(tx);
+
+    // 每个检测自身受 timeout 限制，这里再留出 DNS 解析的余量
+    let deadline = Instant::now() + timeout * 2;
+    let mut results = Vec::new();
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match rx.recv_timeout(remaining) {
+            Ok(Ok(health)) => results.push(health),
+            Ok(Err(_)) => {}
+            Err(_) => break,
+        }
+    }
+    best_target(results)
+}
+
+/// 从检测结果中选出耗时最短的合适目标
+fn best_target(results: Vec<TargetHealth>) -> Option<TargetHealth> {
+    results
+        .into_iter()
+        .filter(TargetHealth::is_suitable)
+        .min_by_key(|h| h.latency)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(server: &str, tls13: bool, h2: bool, ms: u64) -> TargetHealth {
+        TargetHealth {
+            server: server.to_string(),
+            port: 443,
+            tls13,
+            h2,
+            latency: StdDuration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn test_best_target() {
+        let results = vec![
+            health("a.example", true, true, 80),
+            health("b.example", true, false, 10),
+            health("c.example", false, true, 5),
+            health("d.example", true, true, 30),
+        ];
+        assert_eq!(best_target(results).unwrap().server, "d.example");
+        assert!(best_target(vec![health("b.example", true, false, 10)]).is_none());
+        assert_eq!(
+            health("a.example", true, false, 12).to_string(),
+            "a.example:443 (TLS 1.3: 是, HTTP/2: 否, 耗时 12 ms)"
+        );
+    }
+}
@@ -1,7 +1,12 @@
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
 use rand::RngCore;
 use std::net::IpAddr;
-use std::time::Duration as StdDuration;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration as StdDuration, Instant};
+
+use super::ipdetect::{IpDetector, detect_public_ip};
+use super::protocol::Transport;
 
 //============================================================================
 // 公网 IP 获取
@@ -16,6 +21,10 @@ pub enum PublicIpError {
     ParseError(String),
     /// 所有服务都不可用
     AllServicesFailed,
+    /// 编译时未启用 probe 特性
+    ProbeDisabled,
+    /// 读取网卡地址失败
+    InterfaceError(String),
 }
 
 impl std::fmt::Display for PublicIpError {
@@ -24,14 +33,18 @@ impl std::fmt::Display for PublicIpError {
             PublicIpError::NetworkError(msg) => write!(f, "网络请求失败: {}", msg),
             PublicIpError::ParseError(msg) => write!(f, "解析 IP 失败: {}", msg),
             PublicIpError::AllServicesFailed => write!(f, "所有公网 IP 服务都不可用"),
+            PublicIpError::ProbeDisabled => {
+                write!(f, "未启用公网 IP 探测（probe 特性），请设置 EZ_PUBLIC_IP")
+            }
+            PublicIpError::InterfaceError(msg) => write!(f, "读取网卡地址失败: {}", msg),
         }
     }
 }
 
 impl std::error::Error for PublicIpError {}
 
-/// 获取公网 IP 的服务列表
-const PUBLIC_IP_SERVICES: &[&str] = &[
+/// 获取公网 IP 的默认服务列表
+pub const PUBLIC_IP_SERVICES: &[&str] = &[
     "https://api.ipify.org",
     "https://ifconfig.me/ip",
     "https://icanhazip.com",
@@ -39,29 +52,96 @@ const PUBLIC_IP_SERVICES: &[&str] = &[
     "https://api.ip.sb/ip",
 ];
 
+/// 单个探测方式的默认超时时间
+const DEFAULT_PROBE_TIMEOUT: StdDuration = StdDuration::from_secs(5);
+
 /// 获取公网 IP
-/// 依次尝试多个服务，直到成功获取
+/// 按默认顺序依次尝试 HTTP 服务、DNS 查询、云厂商元数据，直到成功获取
 pub fn get_public_ip() -> Result<IpAddr, PublicIpError> {
-    get_public_ip_with_timeout(StdDuration::from_secs(5))
+    get_public_ip_with_timeout(DEFAULT_PROBE_TIMEOUT)
 }
 
 /// 获取公网 IP（指定超时时间）
 pub fn get_public_ip_with_timeout(timeout: StdDuration) -> Result<IpAddr, PublicIpError> {
-    for service in PUBLIC_IP_SERVICES {
-        match try_get_ip_from_service(service, timeout) {
-            Ok(ip) => return Ok(ip),
-            Err(_) => continue,
+    detect_public_ip(&IpDetector::default_order(), &[], timeout)
+}
+
+/// 按指定的探测方式顺序获取公网 IP
+/// `services` 为 HTTP 探测使用的服务列表，为空时使用默认列表
+pub fn get_public_ip_with(
+    detectors: &[IpDetector],
+    services: &[String],
+) -> Result<IpAddr, PublicIpError> {
+    detect_public_ip(detectors, services, DEFAULT_PROBE_TIMEOUT)
+}
+
+/// 并发查询 HTTP 公网 IP 服务
+/// 两个服务给出相同结果时立即返回；超时或全部结束时返回最先得到的结果
+pub(crate) fn get_ip_from_services(
+    services: &[String],
+    timeout: StdDuration,
+) -> Result<IpAddr, PublicIpError> {
+    if !cfg!(feature = "probe") {
+        return Err(PublicIpError::ProbeDisabled);
+    }
+    let services: Vec<String> = if services.is_empty() {
+        PUBLIC_IP_SERVICES.iter().map(|s| s.to_string()).collect()
+    } else {
+        services.to_vec()
+    };
+
+    let (tx, rx) = mpsc::channel();
+    for service in &services {
+        let tx = tx.clone();
+        let url = service.clone();
+        thread::spawn(move || {
+            let _ = tx.send(try_get_ip_from_service(&url, timeout));
+        });
+    }
+    drop(tx);
+
+    let deadline = Instant::now() + timeout;
+    let results = std::iter::from_fn(|| {
+        rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            .ok()
+    });
+    first_consistent(results, services.len()).ok_or(PublicIpError::AllServicesFailed)
+}
+
+/// 从陆续到达的查询结果中选出公网 IP
+/// 只有一个服务时直接采用其结果，否则等待两个服务结果一致
+fn first_consistent(
+    results: impl Iterator<Item = Result<IpAddr, PublicIpError>>,
+    total: usize,
+) -> Option<IpAddr> {
+    let mut seen: Vec<IpAddr> = Vec::new();
+    for ip in results.flatten() {
+        if total <= 1 || seen.contains(&ip) {
+            return Some(ip);
         }
+        seen.push(ip);
     }
-    Err(PublicIpError::AllServicesFailed)
+    seen.first().copied()
+}
+
+/// 创建 HTTP 客户端
+#[cfg(feature = "probe")]
+pub fn http_agent(timeout: StdDuration) -> ureq::Agent {
+    let builder = ureq::Agent::config_builder().timeout_global(Some(timeout));
+    // 仅启用 native-tls 时需显式指定 TLS 实现（ureq 默认使用 rustls）
+    #[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+    let builder = builder.tls_config(
+        ureq::tls::TlsConfig::builder()
+            .provider(ureq::tls::TlsProvider::NativeTls)
+            .build(),
+    );
+    ureq::Agent::new_with_config(builder.build())
 }
 
 /// 从指定服务获取 IP
+#[cfg(feature = "probe")]
 fn try_get_ip_from_service(url: &str, timeout: StdDuration) -> Result<IpAddr, PublicIpError> {
-    let config = ureq::Agent::config_builder()
-        .timeout_global(Some(timeout))
-        .build();
-    let agent = ureq::Agent::new_with_config(config);
+    let agent = http_agent(timeout);
 
     let response = agent
         .get(url)
@@ -79,6 +159,27 @@ fn try_get_ip_from_service(url: &str, timeout: StdDuration) -> Result<IpAddr, Pu
         .map_err(|e| PublicIpError::ParseError(format!("{}: {}", ip_str, e)))
 }
 
+/// 未启用 probe 特性时的占位实现
+#[cfg(not(feature = "probe"))]
+fn try_get_ip_from_service(_url: &str, _timeout: StdDuration) -> Result<IpAddr, PublicIpError> {
+    Err(PublicIpError::ProbeDisabled)
+}
+
+//============================================================================
+// 端口分配
+//============================================================================
+
+/// 向系统申请一个临时端口
+/// 绑定端口 0 后读取系统实际分配的端口，随即释放供 sing-box 使用
+pub fn pick_ephemeral_port(transport: Transport) -> std::io::Result<u16> {
+    let addr = (std::net::Ipv4Addr::UNSPECIFIED, 0);
+    let port = match transport {
+        Transport::Tcp => std::net::TcpListener::bind(addr)?.local_addr()?.port(),
+        Transport::Udp => std::net::UdpSocket::bind(addr)?.local_addr()?.port(),
+    };
+    Ok(port)
+}
+
 //============================================================================
 // 密码生成
 //============================================================================
@@ -193,6 +294,9 @@ impl UserConfig {
 // TLS 配置模式
 //============================================================================
 
+/// 默认 ACME 数据目录，所有入站共享同一目录以复用证书
+pub const DEFAULT_ACME_DATA_DIR: &str = "./acme";
+
 /// TLS 配置模式
 #[derive(Debug, Clone)]
 pub enum TlsMode {
@@ -283,6 +387,23 @@ impl TlsMode {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_first_consistent() {
+        let a: IpAddr = "203.0.113.1".parse().unwrap();
+        let b: IpAddr = "203.0.113.2".parse().unwrap();
+        let err = || Err(PublicIpError::AllServicesFailed);
+
+        // 两个服务结果一致时采用该结果
+        let results = vec![Ok(b), err(), Ok(a), Ok(a), Ok(b)];
+        assert_eq!(first_consistent(results.into_iter(), 5), Some(a));
+        // 结果均不一致时采用最先到达的
+        let results = vec![err(), Ok(b), Ok(a)];
+        assert_eq!(first_consistent(results.into_iter(), 3), Some(b));
+        // 只有一个服务时直接采用
+        assert_eq!(first_consistent(vec![Ok(a)].into_iter(), 1), Some(a));
+        assert_eq!(first_consistent(vec![err(), err()].into_iter(), 2), None);
+    }
+
     #[test]
     fn test_generate_password() {
         let password = generate_password();
@@ -325,6 +446,12 @@ mod tests {
         assert_eq!(domain, "1.2.3.4.nip.io");
     }
 
+    #[test]
+    fn test_pick_ephemeral_port() {
+        assert_ne!(pick_ephemeral_port(Transport::Tcp).unwrap(), 0);
+        assert_ne!(pick_ephemeral_port(Transport::Udp).unwrap(), 0);
+    }
+
     #[test]
     fn test_generate_uuid() {
         let uuid = generate_uuid();
@@ -0,0 +1,124 @@
+//! 配置备份模块
+//!
+//! 覆盖服务端配置前将旧文件保存为 `<文件名>.<UTC 时间>.bak`，只保留最近 N 份；
+//! `rollback` 使用最新的一份备份恢复配置
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cert::format_utc;
+
+/// 默认保留的备份数量
+pub const DEFAULT_CONFIG_BACKUPS: usize = 5;
+
+/// 备份文件名前缀（`config.json.`）与所在目录
+fn backup_prefix(path: &Path) -> (PathBuf, String) {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    (dir, format!("{}.", name))
+}
+
+/// 列出配置文件的全部备份（从旧到新）
+pub fn list_backups(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let (dir, prefix) = backup_prefix(path);
+    let mut backups: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .map(|n| n.to_string_lossy())
+                    .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".bak"))
+            })
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    // 时间戳定长，按文件名排序即按时间排序
+    backups.sort();
+    Ok(backups)
+}
+
+/// 备份已有的配置文件并清理多余的旧备份
+/// keep 为 0 或配置文件不存在时不做任何操作
+pub fn backup_config(path: &Path, keep: usize, now: i64) -> io::Result<Option<PathBuf>> {
+    if keep == 0 || !path.is_file() {
+        return Ok(None);
+    }
+    let (dir, prefix) = backup_prefix(path);
+    let stamp: String = format_utc(now)
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+    let backup = dir.join(format!("{}{}-{}.bak", prefix, &stamp[..8], &stamp[8..]));
+    std::fs::copy(path, &backup)?;
+
+    let backups = list_backups(path)?;
+    for old in backups.iter().take(backups.len().saturating_sub(keep)) {
+        std::fs::remove_file(old)?;
+    }
+    Ok(Some(backup))
+}
+
+/// 使用最新的备份恢复配置文件（该备份随之移除），没有备份时返回 None
+pub fn restore_latest(path: &Path) -> io::Result<Option<PathBuf>> {
+    let Some(latest) = list_backups(path)?.pop() else {
+        return Ok(None);
+    };
+    std::fs::rename(&latest, path)?;
+    Ok(Some(latest))
+}
+
+/// 读取最新备份的内容（用于恢复前检查）
+pub fn read_latest(path: &Path) -> io::Result<Option<(PathBuf, String)>> {
+    match list_backups(path)?.pop() {
+        Some(latest) => Ok(Some((latest.clone(), std::fs::read_to_string(latest)?))),
+        None => Ok(None),
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = std::env::temp_dir().join(format!("ezsingbox-backup-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+
+        // 配置文件不存在时不备份
+        assert_eq!(backup_config(&path, 2, 0).unwrap(), None);
+
+        for (i, now) in [1_700_000_000, 1_700_000_060, 1_700_000_120]
+            .into_iter()
+            .enumerate()
+        {
+            std::fs::write(&path, format!("v{}", i)).unwrap();
+            backup_config(&path, 2, now).unwrap();
+        }
+        let backups = list_backups(&path).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups[1].ends_with("config.json.20231114-221520.bak"));
+
+        std::fs::write(&path, "broken").unwrap();
+        restore_latest(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "v2");
+        restore_latest(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "v1");
+        assert_eq!(restore_latest(&path).unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,410 @@
+//! ACME 证书目录管理模块
+//!
+//! sing-box 使用 certmagic 管理 ACME 证书，证书保存在
+//! `<数据目录>/certificates/<CA>/<域名>/<域名>.crt`；
+//! 此模块负责创建数据目录并读取已签发证书的到期时间
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use serde_json::Value;
+
+/// 证书即将到期的提醒阈值（天）
+pub const EXPIRY_WARN_DAYS: i64 = 30;
+
+//============================================================================
+// 数据目录
+//============================================================================
+
+/// 收集配置中所有 ACME 数据目录（去重，保持出现顺序）
+pub fn acme_data_dirs(config: &Value) -> Vec<String> {
+    let mut dirs: Vec<String> = Vec::new();
+    let inbounds = config["inbounds"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    for inbound in inbounds {
+        let dir = inbound["tls"]["acme"]["data_directory"].as_str();
+        if let Some(dir) = dir.filter(|dir| !dirs.iter().any(|d| d == dir)) {
+            dirs.push(dir.to_string());
+        }
+    }
+    dirs
+}
+
+/// 创建 ACME 数据目录
+/// 目录中保存账户与证书私钥，Unix 上权限设为 0700
+pub fn prepare_data_dir(path: &str) -> std::io::Result<()> {
+    std::fs::create_dir_all(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+//============================================================================
+// 证书状态
+//============================================================================
+
+/// 已签发证书信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertStatus {
+    /// 域名（certmagic 目录名，通配符证书为 `wildcard_.example.com`）
+    pub domain: String,
+    /// 签发 CA（certmagic 目录名）
+    pub issuer: String,
+    /// 证书文件路径
+    pub path: PathBuf,
+    /// 到期时间（Unix 时间戳，秒）
+    pub not_after: i64,
+}
+
+impl CertStatus {
+    /// 距到期的剩余天数（已过期时为负数）
+    pub fn days_left(&self, now: i64) -> i64 {
+        (self.not_after - now).div_euclid(86400)
+    }
+}
+
+/// 扫描数据目录中的证书，按域名排序
+/// 目录不存在时返回空列表
+pub fn scan_certificates(data_dir: &Path) -> Result<Vec<CertStatus>, String> {
+    let root = data_dir.join("certificates");
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let read_dir = |p: &Path| {
+        std::fs::read_dir(p).map_err(|e| format!("读取目录失败({}): {}", p.display(), e))
+    };
+    let mut certs = Vec::new();
+    for issuer in read_dir(&root)?.flatten() {
+        if !issuer.path().is_dir() {
+            continue;
+        }
+        for domain in read_dir(&issuer.path())?.flatten() {
+            let domain_name = domain.file_name().to_string_lossy().into_owned();
+            let path = domain.path().join(format!("{}.crt", domain_name));
+            if !path.is_file() {
+                continue;
+            }
+            let pem = std::fs::read_to_string(&path)
+                .map_err(|e| format!("读取证书失败({}): {}", path.display(), e))?;
+            let not_after = pem_not_after(&pem)
+                .map_err(|e| format!("解析证书失败({}): {}", path.display(), e))?;
+            certs.push(CertStatus {
+                domain: domain_name,
+                issuer: issuer.file_name().to_string_lossy().into_owned(),
+                path,
+                not_after,
+            });
+        }
+    }
+    certs.sort_by(|a, b| a.domain.cmp(&b.domain).then(a.issuer.cmp(&b.issuer)));
+    Ok(certs)
+}
+
+/// run 模式检查证书续期的间隔（秒）
+const CERT_WATCH_INTERVAL: i64 = 60 * 60;
+
+/// 证书续期检测
+/// run 模式定期扫描 ACME 数据目录，同一 CA 与域名的证书到期时间延后即视为已续期（首次签发不计）
+#[derive(Debug)]
+pub struct CertWatcher {
+    /// ACME 数据目录
+    dirs: Vec<PathBuf>,
+    /// 已知证书的到期时间（(CA, 域名) → 到期时间）
+    known: HashMap<(String, String), i64>,
+    /// 下次检查时间（Unix 时间戳）
+    next_check: i64,
+}
+
+impl CertWatcher {
+    /// 创建检测器并记录当前证书
+    pub fn new(dirs: &[String], now: i64) -> Self {
+        let mut watcher = Self {
+            dirs: dirs.iter().map(PathBuf::from).collect(),
+            known: HashMap::new(),
+            next_check: now + CERT_WATCH_INTERVAL,
+        };
+        let certs = watcher.scan();
+        watcher.renewed(certs);
+        watcher
+    }
+
+    /// 到达检查时间时扫描证书，返回已续期的证书
+    pub fn poll(&mut self, now: i64) -> Vec<CertStatus> {
+        if now < self.next_check {
+            return Vec::new();
+        }
+        self.next_check = now + CERT_WATCH_INTERVAL;
+        let certs = self.scan();
+        self.renewed(certs)
+    }
+
+    /// 扫描全部数据目录（读取失败的目录跳过）
+    fn scan(&self) -> Vec<CertStatus> {
+        self.dirs
+            .iter()
+            .filter_map(|dir| scan_certificates(dir).ok())
+            .flatten()
+            .collect()
+    }
+
+    /// 更新已知证书，返回到期时间延后的证书
+    fn renewed(&mut self, certs: Vec<CertStatus>) -> Vec<CertStatus> {
+        certs
+            .into_iter()
+            .filter(|cert| {
+                let key = (cert.issuer.clone(), cert.domain.clone());
+                let prev = self.known.insert(key, cert.not_after);
+                prev.is_some_and(|t| cert.not_after > t)
+            })
+            .collect()
+    }
+}
+
+/// 渲染证书状态报告
+pub fn render_status(data_dir: &str, certs: &[CertStatus], now: i64) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "ACME 数据目录: {}", data_dir);
+    if certs.is_empty() {
+        let _ = writeln!(out, "尚未签发任何证书（sing-box 首次启动后自动申请）");
+        return out;
+    }
+    for cert in certs {
+        let days = cert.days_left(now);
+        let (mark, remark) = if days < 0 {
+            ("❌", format!("已过期 {} 天", -days))
+        } else if days < EXPIRY_WARN_DAYS {
+            ("⚠️", format!("剩余 {} 天", days))
+        } else {
+            ("✅", format!("剩余 {} 天", days))
+        };
+        let _ = writeln!(
+            out,
+            "{} {} 到期: {}（{}，{}）",
+            mark,
+            cert.domain,
+            format_utc(cert.not_after),
+            remark,
+            cert.issuer
+        );
+    }
+    out
+}
+
+/// 当前 Unix 时间戳（秒）
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+//============================================================================
+// X.509 解析
+//============================================================================
+
+/// 读取一个 DER TLV，返回 (标签, 内容, 剩余数据)
+fn read_tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8]), String> {
+    let truncated = || "DER 数据不完整".to_string();
+    let (&tag, rest) = data.split_first().ok_or_else(truncated)?;
+    let (&first, rest) = rest.split_first().ok_or_else(truncated)?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 4 || rest.len() < n {
+            return Err("不支持的 DER 长度编码".to_string());
+        }
+        let len = rest[..n]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &rest[n..])
+    };
+    if rest.len() < len {
+        return Err(truncated());
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+/// 读取 PEM 中第一张证书的到期时间
+pub fn pem_not_after(pem: &str) -> Result<i64, String> {
+    const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+    const END: &str = "-----END CERTIFICATE-----";
+    let start = pem.find(BEGIN).ok_or("未找到 PEM 证书")? + BEGIN.len();
+    let end = pem[start..].find(END).ok_or("PEM 证书不完整")? + start;
+    let body: String = pem[start..end].split_whitespace().collect();
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(body)
+        .map_err(|e| format!("PEM 解码失败: {}", e))?;
+    der_not_after(&der)
+}
+
+/// 读取 DER 证书的到期时间
+/// Certificate ::= SEQUENCE { tbsCertificate, ... }
+/// TBSCertificate ::= SEQUENCE { [0] version, serialNumber, signature, issuer, validity, ... }
+fn der_not_after(der: &[u8]) -> Result<i64, String> {
+    let (_, cert, _) = read_tlv(der)?;
+    let (_, tbs, _) = read_tlv(cert)?;
+    let (tag, _, mut rest) = read_tlv(tbs)?;
+    // 跳过可选的 version，再跳过 serialNumber、signature、issuer
+    let skip = if tag == 0xa0 { 3 } else { 2 };
+    for _ in 0..skip {
+        rest = read_tlv(rest)?.2;
+    }
+    let (_, validity, _) = read_tlv(rest)?;
+    let (_, _, validity) = read_tlv(validity)?;
+    let (tag, time, _) = read_tlv(validity)?;
+    let time = std::str::from_utf8(time).map_err(|e| e.to_string())?;
+    parse_der_time(tag, time)
+}
+
+/// 解析 UTCTime (`YYMMDDHHMMSSZ`) 或 GeneralizedTime (`YYYYMMDDHHMMSSZ`)
+fn parse_der_time(tag: u8, s: &str) -> Result<i64, String> {
+    let invalid = || format!("无效的证书时间: {}", s);
+    let digits = s.strip_suffix('Z').ok_or_else(invalid)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let num = |r: std::ops::Range<usize>| digits.get(r).and_then(|v| v.parse::<i64>().ok());
+    let (year, rest) = match (tag, digits.len()) {
+        // UTCTime: 50-99 表示 19xx，00-49 表示 20xx
+        (0x17, 12) => {
+            let yy = num(0..2).ok_or_else(invalid)?;
+            (if yy >= 50 { 1900 + yy } else { 2000 + yy }, 2)
+        }
+        (0x18, 14) => (num(0..4).ok_or_else(invalid)?, 4),
+        _ => return Err(invalid()),
+    };
+    let field = |i: usize| num(rest + i * 2..rest + i * 2 + 2).ok_or_else(invalid);
+    let (month, day) = (field(0)?, field(1)?);
+    let (hour, minute, second) = (field(2)?, field(3)?, field(4)?);
+    Ok(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// 公历日期转换为自 1970-01-01 起的天数
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// 将 Unix 时间戳格式化为 `YYYY-MM-DD HH:MM:SS UTC`
+pub fn format_utc(ts: i64) -> String {
+    let days = ts.div_euclid(86400);
+    let secs = ts.rem_euclid(86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 自签名 P-256 证书，有效期至 2035-06-01 12:00:00 UTC
+    const SAMPLE_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBgjCCASegAwIBAgIUPzNrk9OxvJKrMbzeoExAfNQB++AwCgYIKoZIzj0EAwIw
+FjEUMBIGA1UEAwwLZXhhbXBsZS5jb20wHhcNMjUwMTAxMDAwMDAwWhcNMzUwNjAx
+MTIwMDAwWjAWMRQwEgYDVQQDDAtleGFtcGxlLmNvbTBZMBMGByqGSM49AgEGCCqG
+SM49AwEHA0IABAYPvs4GhoPs9w8Pw5WiaCoQXu1QbufBUrIqCBRC0WbOF/Hg8Hlr
+YN6Ouw9Utx3/63o9rmJTFLAjKRycWYYaCzyjUzBRMB0GA1UdDgQWBBS1t9rmRSVa
+Z//3nmPG3b/J/9TZ+zAfBgNVHSMEGDAWgBS1t9rmRSVaZ//3nmPG3b/J/9TZ+zAP
+BgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0kAMEYCIQDe6wQODfeioscmgUVT
+s/eRYW3sPEPQHx/vLJGyEp2eigIhAIoutJCH+6EYNhApKulnSC1flZRRJb6X4/OF
+z1lQl3Js
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_pem_not_after() {
+        let ts = pem_not_after(SAMPLE_PEM).unwrap();
+        assert_eq!(format_utc(ts), "2035-06-01 12:00:00 UTC");
+        assert_eq!(
+            parse_der_time(0x18, "20500101000000Z").map(format_utc),
+            Ok("2050-01-01 00:00:00 UTC".to_string())
+        );
+        assert_eq!(parse_der_time(0x17, "700101000000Z"), Ok(0));
+        assert!(parse_der_time(0x17, "7001010000Z").is_err());
+        assert!(pem_not_after("not a cert").is_err());
+    }
+
+    #[test]
+    fn test_scan_and_render() {
+        let dir = std::env::temp_dir().join(format!("ezsingbox-cert-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(scan_certificates(&dir).unwrap().is_empty());
+
+        let domain_dir =
+            dir.join("certificates/acme-v02.api.letsencrypt.org-directory/example.com");
+        std::fs::create_dir_all(&domain_dir).unwrap();
+        std::fs::write(domain_dir.join("example.com.crt"), SAMPLE_PEM).unwrap();
+        std::fs::write(domain_dir.join("example.com.key"), "").unwrap();
+
+        let certs = scan_certificates(&dir).unwrap();
+        assert_eq!(certs.len(), 1);
+        assert_eq!(certs[0].domain, "example.com");
+        assert_eq!(certs[0].issuer, "acme-v02.api.letsencrypt.org-directory");
+
+        let not_after = certs[0].not_after;
+        let report = render_status("./acme", &certs, not_after - 10 * 86400);
+        assert!(report.contains("⚠️ example.com 到期: 2035-06-01 12:00:00 UTC（剩余 10 天"));
+        let report = render_status("./acme", &certs, not_after + 86400);
+        assert!(report.contains("❌ example.com"));
+        assert!(render_status("./acme", &[], 0).contains("尚未签发"));
+
+        // 续期检测：同一证书到期时间延后才视为续期
+        let mut watcher = CertWatcher::new(&[dir.to_string_lossy().into_owned()], 0);
+        assert!(watcher.poll(0).is_empty());
+        assert!(watcher.poll(CERT_WATCH_INTERVAL).is_empty());
+        let mut renewed = certs[0].clone();
+        renewed.not_after += 60 * 86400;
+        assert_eq!(watcher.renewed(vec![renewed.clone()]), vec![renewed]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_acme_data_dirs() {
+        let config = json!({
+            "inbounds": [
+                { "type": "anytls", "tls": { "acme": { "data_directory": "/var/lib/acme" } } },
+                { "type": "vless", "tls": { "reality": { "enabled": true } } },
+                { "type": "tuic", "tls": { "acme": { "data_directory": "/var/lib/acme" } } }
+            ]
+        });
+        assert_eq!(acme_data_dirs(&config), vec!["/var/lib/acme".to_string()]);
+        assert!(acme_data_dirs(&json!({})).is_empty());
+    }
+}
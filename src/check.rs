@@ -0,0 +1,221 @@
+//! sing-box 配置检查模块
+//!
+//! 调用 `sing-box check -c <path>` 校验生成的配置
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tracing::warn;
+
+use crate::install::INSTALL_HINT;
+
+/// sing-box 配置检查错误
+#[derive(Debug, Clone)]
+pub enum CheckError {
+    /// 找不到 sing-box 二进制文件
+    BinaryNotFound(String),
+    /// 执行 sing-box 失败
+    Io(String),
+    /// 配置未通过 sing-box 检查
+    Invalid {
+        /// 被检查的配置（路径或名称）
+        target: String,
+        /// sing-box 退出码
+        code: Option<i32>,
+        /// sing-box 标准错误输出
+        stderr: String,
+    },
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::BinaryNotFound(bin) => write!(f, "找不到 sing-box 二进制文件: {}", bin),
+            CheckError::Io(msg) => write!(f, "执行 sing-box check 失败: {}", msg),
+            CheckError::Invalid {
+                target,
+                code,
+                stderr,
+            } => {
+                let code = code.map_or_else(|| "无".to_string(), |c| c.to_string());
+                write!(
+                    f,
+                    "sing-box check 未通过 ({}, 退出码 {}):\n{}",
+                    target,
+                    code,
+                    stderr.trim()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+/// 对指定路径的配置文件执行 `sing-box check`
+pub fn sing_box_check(sing_box: &str, config_path: &str) -> Result<(), CheckError> {
+    let output = Command::new(sing_box)
+        .arg("check")
+        .arg("-c")
+        .arg(config_path)
+        .output()
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                CheckError::BinaryNotFound(sing_box.to_string())
+            } else {
+                CheckError::Io(e.to_string())
+            }
+        })?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(CheckError::Invalid {
+        target: config_path.to_string(),
+        code: output.status.code(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// 私有临时目录中的配置文件，释放时连同目录一起删除
+pub struct TempConfig {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempConfig {
+    /// 新建只有当前用户可访问的临时目录（0700），在其中独占创建配置文件（0600）并写入内容
+    /// 目录名带随机后缀，避免其他用户预先创建同名文件或符号链接
+    pub fn create(prefix: &str, name: &str, json: &str) -> io::Result<Self> {
+        let dir = private_temp_dir(prefix)?;
+        let config = TempConfig {
+            path: dir.join(name),
+            dir,
+        };
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&config.path)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+        Ok(config)
+    }
+
+    /// 配置文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempConfig {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// 在系统临时目录下新建私有目录（已存在时换一个随机名重试）
+fn private_temp_dir(prefix: &str) -> io::Result<PathBuf> {
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    let mut attempts = 0;
+    loop {
+        let dir = std::env::temp_dir().join(format!(
+            "{}-{}-{:016x}",
+            prefix,
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 8 => attempts += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 将配置内容写入私有临时文件并执行 `sing-box check`
+pub fn sing_box_check_json(sing_box: &str, label: &str, json: &str) -> Result<(), CheckError> {
+    let config = TempConfig::create("ezsingbox-check", &format!("{}.json", label), json)
+        .map_err(|e| CheckError::Io(e.to_string()))?;
+    let result = sing_box_check(sing_box, &config.path().to_string_lossy());
+    drop(config);
+    result.map_err(|e| match e {
+        CheckError::Invalid { code, stderr, .. } => CheckError::Invalid {
+            target: label.to_string(),
+            code,
+            stderr,
+        },
+        other => other,
+    })
+}
+
+/// 检查指定路径的配置文件：sing-box 不可用时仅给出警告并跳过
+pub fn precheck_file(sing_box: &str, label: &str, path: &str) -> Result<(), String> {
+    match sing_box_check(sing_box, path) {
+        Ok(()) => Ok(()),
+        Err(CheckError::BinaryNotFound(bin)) => {
+            warn!(%bin, hint = INSTALL_HINT, "未找到 sing-box，跳过配置检查");
+            Ok(())
+        }
+        Err(CheckError::Invalid { code, stderr, .. }) => Err(CheckError::Invalid {
+            target: label.to_string(),
+            code,
+            stderr,
+        }
+        .to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 运行前检查：sing-box 不可用时仅给出警告并跳过
+pub fn precheck(sing_box: &str, label: &str, json: &str) -> Result<(), String> {
+    match sing_box_check_json(sing_box, label, json) {
+        Ok(()) => Ok(()),
+        Err(CheckError::BinaryNotFound(bin)) => {
+            warn!(%bin, hint = INSTALL_HINT, "未找到 sing-box，跳过配置检查");
+            Ok(())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_config() {
+        let config = TempConfig::create("ezsingbox-test", "client.json", "{}").unwrap();
+        let path = config.path().to_path_buf();
+        let dir = path.parent().unwrap().to_path_buf();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&dir), 0o700);
+            assert_eq!(mode(&path), 0o600);
+        }
+
+        // 每次都使用新的目录
+        let other = TempConfig::create("ezsingbox-test", "client.json", "{}").unwrap();
+        assert_ne!(other.path(), path);
+
+        drop(config);
+        assert!(!dir.exists());
+        assert!(other.path().exists());
+    }
+}
@@ -0,0 +1,1672 @@
+//! 命令行参数解析模块
+//!
+//! 每个参数都对应一个环境变量，命令行参数优先于环境变量
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use clap::{Args, Command, CommandFactory, Parser, Subcommand};
+
+use crate::error::ErrorFormat;
+use crate::firewall::FirewallFormat;
+use crate::i18n::{Lang, Msg, fill};
+use crate::links::LinkFormat;
+use crate::logging::LogFormat;
+use crate::summary::OutputFormat;
+use ezsingbox::autoconfig::{IpDetector, Protocol};
+use ezsingbox::convert::ConvertSource;
+use ezsingbox::singboxconfig::types::Duration;
+
+//============================================================================
+// 命令定义
+//============================================================================
+
+/// 简易 sing-box 配置生成器和运行器
+#[derive(Debug, Parser)]
+#[command(name = "ezsingbox", version)]
+pub struct Cli {
+    /// 子命令（默认为 generate）
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// 覆盖环境变量的参数
+    #[command(flatten)]
+    pub options: Options,
+}
+
+/// 子命令
+#[derive(Debug, Clone, Subcommand)]
+pub enum Commands {
+    /// 生成服务端与客户端配置
+    Generate,
+    /// 生成配置并启动 sing-box
+    Run,
+    /// 启动 HTTP 订阅服务
+    Serve,
+    /// 使用最新的备份恢复服务端配置，并通知运行中的 sing-box 重新加载
+    Rollback,
+    /// 使用结构校验与 sing-box check 检查生成的配置
+    Check,
+    /// 按最佳实践检查任意 sing-box 配置（不安全的 TLS、CDN 握手目标、未启用嗅探、弃用字段），有错误时以退出码 1 退出
+    Lint {
+        /// 要检查的配置文件（可带注释）
+        #[arg(value_name = "CONFIG")]
+        path: String,
+    },
+    /// 把配置中的弃用字段改写为 sing-box 1.12 的等价写法（geosite / geoip → rule_set、旧版 DNS 服务器 → 类型化服务器等），输出到标准输出或 EZ_EXPORT_PATH
+    Migrate {
+        /// 要迁移的配置文件（可带注释）
+        #[arg(value_name = "CONFIG")]
+        source: String,
+    },
+    /// 把其他代理工具的配置转换为 sing-box 配置（Xray 服务端的 VLESS / VMess / Trojan 入站，或由 Clash.Meta 的 proxies 生成客户端配置），输出到标准输出或 EZ_EXPORT_PATH
+    Convert {
+        /// 导入来源（xray / clash）
+        #[arg(long, value_name = "SOURCE")]
+        from: ConvertSource,
+        /// 要转换的配置文件（Xray 可带注释，Clash 为 YAML）
+        #[arg(value_name = "CONFIG")]
+        input: String,
+    },
+    /// 生成故障排查包（已脱敏的配置、检查结果、日志与环境摘要）
+    SupportBundle,
+    /// 从本机连接生成配置中的各入站端口，任一失败时以退出码 1 退出
+    Health,
+    /// 通过临时 sing-box 实例测试各协议出站的延迟，推荐最快的客户端协议
+    Test,
+    /// 生成只发布已启用端口、内嵌当前变量的 docker-compose.yaml
+    Compose,
+    /// 按服务器列表（EZ_FLEET）为每台服务器生成服务端配置，并生成包含全部节点的客户端配置
+    Fleet,
+    /// 从 GitHub 下载与当前系统匹配的 sing-box，校验 SHA-256 后安装到 <EZ_INSTALL_PREFIX>/bin
+    InstallSingbox {
+        /// sing-box 版本（如 1.12.8，默认最新正式版）
+        #[arg(long, value_name = "VERSION")]
+        version: Option<String>,
+    },
+    /// 通过 SSH 部署服务端配置、安装 systemd 单元并重启服务，逐台报告结果
+    Deploy {
+        /// SSH 目标（如 root@1.2.3.4，可重复；不指定时部署服务器列表中设置了 ssh 的节点）
+        #[arg(long = "host", value_name = "TARGET")]
+        hosts: Vec<String>,
+    },
+    /// 输出已启用入站与订阅服务的防火墙放行规则
+    Firewall {
+        /// 规则格式（nft / iptables / ufw）
+        #[arg(long, default_value_t = FirewallFormat::Nft)]
+        format: FirewallFormat,
+    },
+    /// 输出代理列表（分享链接、Base64 订阅，或 Surge / Quantumult X / Loon 代理行）
+    Links {
+        /// 列表格式（uri / surge / quantumult-x / loon / base64）
+        #[arg(long, default_value_t = LinkFormat::Uri)]
+        format: LinkFormat,
+        /// 改为输出各用户的签名订阅链接及其有效期（如 24h，需要 EZ_SUBSCRIBE_SIGNING_KEY）
+        #[arg(long, value_name = "DURATION")]
+        expire: Option<Duration>,
+    },
+    /// ACME 证书管理
+    Cert {
+        /// 操作
+        #[command(subcommand)]
+        action: CertAction,
+    },
+    /// 导出配置片段
+    Export {
+        /// 导出类型
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+    /// 查看 run 模式通过 Clash API 累计的各入站与各用户流量
+    Stats,
+    /// 查看订阅服务的访问审计记录（EZ_AUDIT_LOG）
+    Audit {
+        /// 只显示该用户的记录
+        #[arg(long, value_name = "NAME")]
+        user: Option<String>,
+        /// 只显示最近一段时间内的记录（如 24h、7d）
+        #[arg(long, value_name = "DURATION")]
+        since: Option<Duration>,
+        /// 只显示最近的若干条
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+    /// 用户管理（用户保存在状态文件中）
+    User {
+        /// 操作
+        #[command(subcommand)]
+        action: UserAction,
+    },
+}
+
+/// 导出类型
+#[derive(Debug, Clone, Subcommand)]
+pub enum ExportTarget {
+    /// 导出可供其他 sing-box 实例使用的对等出站
+    PeerOutbound,
+    /// 导出交给最终用户的连接说明文档（Markdown）
+    Readme,
+    /// 使用自定义 Handlebars 模板（EZ_CLIENT_TEMPLATE_PATH）渲染客户端配置
+    Template,
+}
+
+/// 用户操作
+#[derive(Debug, Clone, Subcommand)]
+pub enum UserAction {
+    /// 添加用户（凭证随机生成），重新生成配置后生效；再次添加同名用户时更新过期时间
+    Add {
+        /// 用户名
+        name: String,
+        /// 过期时间（UTC，如 2025-12-31 表示当天结束时过期）
+        #[arg(long, value_name = "DATE")]
+        expires: Option<String>,
+    },
+    /// 为用户签发订阅服务访问令牌（Bearer 或 ?token=），再次签发时更换令牌
+    Token {
+        /// 用户名
+        name: String,
+        /// 吊销该用户的令牌
+        #[arg(long)]
+        revoke: bool,
+    },
+}
+
+/// 证书操作
+#[derive(Debug, Clone, Subcommand)]
+pub enum CertAction {
+    /// 查看 ACME 数据目录中各域名证书的到期时间
+    Status,
+}
+
+//============================================================================
+// 参数定义
+//============================================================================
+
+/// 与环境变量一一对应的参数
+#[derive(Debug, Clone, Default, Args)]
+pub struct Options {
+    /// 声明式配置文件（ezsingbox.toml / yaml） [EZ_FILE]
+    #[arg(long = "config", global = true, value_name = "FILE")]
+    pub file: Option<String>,
+
+    /// 覆盖服务端配置前保留的备份数量（默认 5，0 为不备份） [EZ_CONFIG_BACKUPS]
+    #[arg(long, global = true, value_name = "N")]
+    pub config_backups: Option<u16>,
+
+    /// 回滚后是否通知运行中的 sing-box 重新加载 [EZ_ROLLBACK_RELOAD]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub rollback_reload: Option<bool>,
+
+    /// 服务端配置输出路径 [EZ_CONFIG_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config_path: Option<String>,
+
+    /// 服务器列表（JSON 数组，通常来自配置文件 [[servers]]） [EZ_FLEET]
+    #[arg(long, global = true, value_name = "JSON")]
+    pub fleet: Option<String>,
+
+    /// fleet 模式下服务端配置的输出目录（默认 ./fleet） [EZ_FLEET_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub fleet_dir: Option<String>,
+
+    /// 协议预设: minimal, balanced, stealth, full, cdn（见文档） [EZ_PRESET]
+    #[arg(long, global = true, value_name = "PRESET")]
+    pub preset: Option<String>,
+
+    /// 公网 IP（不指定时自动检测） [EZ_PUBLIC_IP]
+    #[arg(long, global = true, value_name = "IP")]
+    pub public_ip: Option<IpAddr>,
+
+    /// 公网 IP 探测方式，按顺序尝试（http、dns、metadata、interface:<网卡>） [EZ_IP_DETECTOR]
+    #[arg(long, global = true, value_delimiter = ',', value_name = "DETECTOR")]
+    pub ip_detector: Option<Vec<IpDetector>>,
+
+    /// HTTP 公网 IP 服务列表（逗号分隔，并发查询） [EZ_IP_SERVICES]
+    #[arg(long, global = true, value_delimiter = ',', value_name = "URLS")]
+    pub ip_services: Option<Vec<String>>,
+
+    /// 域名（不指定时由域名提供方生成） [EZ_DOMAIN]
+    #[arg(long, global = true)]
+    pub domain: Option<String>,
+
+    /// 未指定域名时的域名提供方（sslip / nip / duckdns / custom，默认 sslip） [EZ_DOMAIN_PROVIDER]
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    pub domain_provider: Option<String>,
+
+    /// 自定义泛解析域名模式（支持 {ip} / {ip_dashed}） [EZ_DOMAIN_PATTERN]
+    #[arg(long, global = true, value_name = "PATTERN")]
+    pub domain_pattern: Option<String>,
+
+    /// DuckDNS 子域名 [EZ_DUCKDNS_DOMAIN]
+    #[arg(long, global = true, value_name = "NAME")]
+    pub duckdns_domain: Option<String>,
+
+    /// DuckDNS token [EZ_DUCKDNS_TOKEN]
+    #[arg(long, global = true, value_name = "TOKEN")]
+    pub duckdns_token: Option<String>,
+
+    /// ACME 邮箱 [EZ_ACME_EMAIL]
+    #[arg(long, global = true, value_name = "EMAIL")]
+    pub acme_email: Option<String>,
+
+    /// ACME CA 提供商（letsencrypt / zerossl / 自定义 URL） [EZ_ACME_PROVIDER]
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    pub acme_provider: Option<String>,
+
+    /// ACME 数据目录（默认 ./acme） [EZ_ACME_DATA_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub acme_data_dir: Option<String>,
+
+    /// 写入 ACME 配置前检查域名解析与验证端口占用（默认启用） [EZ_ACME_PREFLIGHT]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub acme_preflight: Option<bool>,
+
+    /// ACME 外部账户绑定 Key ID [EZ_ACME_EAB_KEY_ID]
+    #[arg(long, global = true, value_name = "ID")]
+    pub acme_eab_key_id: Option<String>,
+
+    /// ACME 外部账户绑定 MAC Key [EZ_ACME_EAB_MAC_KEY]
+    #[arg(long, global = true, value_name = "KEY")]
+    pub acme_eab_mac_key: Option<String>,
+
+    /// ACME DNS-01 挑战提供商（cloudflare / alidns） [EZ_ACME_DNS01_PROVIDER]
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    pub acme_dns01_provider: Option<String>,
+
+    /// Cloudflare API Token（DNS-01） [EZ_CF_API_TOKEN]
+    #[arg(long, global = true, value_name = "TOKEN")]
+    pub cf_api_token: Option<String>,
+
+    /// 阿里云 AccessKey ID（DNS-01） [EZ_ALIDNS_ACCESS_KEY_ID]
+    #[arg(long, global = true, value_name = "ID")]
+    pub alidns_access_key_id: Option<String>,
+
+    /// 阿里云 AccessKey Secret（DNS-01） [EZ_ALIDNS_ACCESS_KEY_SECRET]
+    #[arg(long, global = true, value_name = "SECRET")]
+    pub alidns_access_key_secret: Option<String>,
+
+    /// 阿里云区域 ID（DNS-01，可选） [EZ_ALIDNS_REGION_ID]
+    #[arg(long, global = true, value_name = "REGION")]
+    pub alidns_region_id: Option<String>,
+
+    /// 启用的协议（逗号分隔，未列出的协议将被禁用） [EZ_ENABLE_*]
+    #[arg(long, global = true, value_delimiter = ',', value_name = "PROTOCOLS")]
+    pub enable: Option<Vec<Protocol>>,
+
+    /// AnyTLS 端口 [EZ_ANYTLS_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub port_anytls: Option<u16>,
+
+    /// Hysteria2 端口 [EZ_HYSTERIA2_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub port_hy2: Option<u16>,
+
+    /// TUIC 端口 [EZ_TUIC_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub port_tuic: Option<u16>,
+
+    /// VLESS-Reality 端口 [EZ_VLESS_REALITY_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub port_vless: Option<u16>,
+
+    /// VLESS-WebSocket 端口 [EZ_VLESS_WS_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub port_vless_ws: Option<u16>,
+
+    /// VLESS-gRPC 端口 [EZ_VLESS_GRPC_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub port_vless_grpc: Option<u16>,
+
+    /// 用户名 [EZ_USER]
+    #[arg(long, global = true)]
+    pub user: Option<String>,
+
+    /// 多用户列表（name[:password]，逗号分隔） [EZ_USERS]
+    #[arg(long, global = true, value_name = "USERS")]
+    pub users: Option<String>,
+
+    /// JSON 用户列表（可为用户设置过期时间） [EZ_USERS_JSON]
+    #[arg(long, global = true, value_name = "JSON")]
+    pub users_json: Option<String>,
+
+    /// 密码（不指定时随机生成） [EZ_PASSWORD]
+    #[arg(long, global = true)]
+    pub password: Option<String>,
+
+    /// 凭证轮换间隔（如 30d），凭证保存在状态文件中 [EZ_ROTATE_INTERVAL]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub rotate_interval: Option<String>,
+
+    /// 凭证状态文件路径（默认 ./ezsingbox-state.json） [EZ_STATE_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub state_path: Option<String>,
+
+    /// 轮换日志路径，记录被替换的旧凭证（默认 ./ezsingbox-rotation.log） [EZ_ROTATION_LOG]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub rotation_log: Option<String>,
+
+    /// 轮换凭证时同时更换 REALITY 短 ID [EZ_ROTATE_SHORT_IDS]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub rotate_short_ids: Option<bool>,
+
+    /// 状态文件加密密钥（age 口令或 AGE-SECRET-KEY 私钥） [EZ_STATE_ENCRYPTION_KEY]
+    #[arg(long, global = true, value_name = "KEY")]
+    pub state_encryption_key: Option<String>,
+
+    /// 启用 Hysteria2 Salamander 混淆 [EZ_HY2_OBFS]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub hy2_obfs: Option<bool>,
+
+    /// Hysteria2 伪装类型（proxy / file / string） [EZ_HY2_MASQUERADE_TYPE]
+    #[arg(long, global = true, value_name = "TYPE")]
+    pub hy2_masquerade_type: Option<String>,
+
+    /// Hysteria2 伪装反向代理目标 URL [EZ_HY2_MASQUERADE_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub hy2_masquerade_url: Option<String>,
+
+    /// Hysteria2 伪装文件服务器目录 [EZ_HY2_MASQUERADE_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub hy2_masquerade_dir: Option<String>,
+
+    /// Hysteria2 伪装固定响应状态码（默认 404） [EZ_HY2_MASQUERADE_STATUS]
+    #[arg(long, global = true, value_name = "CODE")]
+    pub hy2_masquerade_status: Option<u16>,
+
+    /// Hysteria2 上行带宽 (Mbps) [EZ_HY2_UP_MBPS]
+    #[arg(long, global = true, value_name = "MBPS")]
+    pub hy2_up_mbps: Option<u32>,
+
+    /// Hysteria2 下行带宽 (Mbps) [EZ_HY2_DOWN_MBPS]
+    #[arg(long, global = true, value_name = "MBPS")]
+    pub hy2_down_mbps: Option<u32>,
+
+    /// Hysteria2 忽略客户端带宽，服务端改用 BBR [EZ_HY2_IGNORE_CLIENT_BW]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub hy2_ignore_client_bw: Option<bool>,
+
+    /// VLESS WS / gRPC 的 TCP Brutal 上行带宽 (Mbps) [EZ_BRUTAL_UP_MBPS]
+    #[arg(long, global = true, value_name = "MBPS")]
+    pub brutal_up_mbps: Option<u32>,
+
+    /// VLESS WS / gRPC 的 TCP Brutal 下行带宽 (Mbps) [EZ_BRUTAL_DOWN_MBPS]
+    #[arg(long, global = true, value_name = "MBPS")]
+    pub brutal_down_mbps: Option<u32>,
+
+    /// VLESS WS / gRPC 的多路复用协议（smux / yamux / h2mux） [EZ_MUX_PROTOCOL]
+    #[arg(long, global = true, value_name = "PROTOCOL")]
+    pub mux_protocol: Option<String>,
+
+    /// 多路复用单个连接的最大流数 [EZ_MUX_MAX_STREAMS]
+    #[arg(long, global = true, value_name = "N")]
+    pub mux_max_streams: Option<u32>,
+
+    /// 多路复用启用填充 [EZ_MUX_PADDING]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub mux_padding: Option<bool>,
+
+    /// TUIC 拥塞控制算法 [EZ_TUIC_CC]
+    #[arg(long, global = true, value_name = "ALGO")]
+    pub tuic_cc: Option<String>,
+
+    /// TUIC 认证超时（如 3s） [EZ_TUIC_AUTH_TIMEOUT]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub tuic_auth_timeout: Option<String>,
+
+    /// TUIC 启用 0-RTT 握手（易受重放攻击） [EZ_TUIC_ZERO_RTT]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub tuic_zero_rtt: Option<bool>,
+
+    /// TUIC 心跳间隔（如 10s） [EZ_TUIC_HEARTBEAT]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub tuic_heartbeat: Option<String>,
+
+    /// TUIC 客户端 UDP 中继模式（native / quic） [EZ_TUIC_UDP_RELAY_MODE]
+    #[arg(long, global = true, value_name = "MODE")]
+    pub tuic_udp_relay_mode: Option<String>,
+
+    /// 所有入站启用 TCP 快速打开 [EZ_TCP_FAST_OPEN]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub tcp_fast_open: Option<bool>,
+
+    /// 所有入站的 UDP NAT 过期时间（如 5m） [EZ_UDP_TIMEOUT]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub udp_timeout: Option<String>,
+
+    /// 所有入站启用协议嗅探（默认开启） [EZ_SNIFF]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff: Option<bool>,
+
+    /// 使用嗅探到的域名覆盖目标地址（默认开启，仅 sing-box 1.11 以下生效） [EZ_SNIFF_OVERRIDE_DESTINATION]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_override_destination: Option<bool>,
+
+    /// 入站与出站绑定的网络接口（如 eth1） [EZ_BIND_INTERFACE]
+    #[arg(long, global = true, value_name = "IFACE")]
+    pub bind_interface: Option<String>,
+
+    /// 入站与出站的 netfilter 路由标记（整数或 0x 开头的十六进制，仅 Linux） [EZ_ROUTING_MARK]
+    #[arg(long, global = true, value_name = "MARK")]
+    pub routing_mark: Option<String>,
+
+    /// 入站与出站所在的网络命名空间（仅 Linux） [EZ_NETNS]
+    #[arg(long, global = true, value_name = "NAME")]
+    pub netns: Option<String>,
+
+    /// AnyTLS 入站的协议嗅探开关（覆盖 --sniff） [EZ_SNIFF_ANYTLS]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_anytls: Option<bool>,
+
+    /// Hysteria2 入站的协议嗅探开关（覆盖 --sniff） [EZ_SNIFF_HYSTERIA2]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_hysteria2: Option<bool>,
+
+    /// TUIC 入站的协议嗅探开关（覆盖 --sniff） [EZ_SNIFF_TUIC]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_tuic: Option<bool>,
+
+    /// VLESS-Reality 入站的协议嗅探开关（覆盖 --sniff） [EZ_SNIFF_VLESS_REALITY]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_vless_reality: Option<bool>,
+
+    /// VLESS-WebSocket 入站的协议嗅探开关（覆盖 --sniff） [EZ_SNIFF_VLESS_WS]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_vless_ws: Option<bool>,
+
+    /// VLESS-gRPC 入站的协议嗅探开关（覆盖 --sniff） [EZ_SNIFF_VLESS_GRPC]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub sniff_vless_grpc: Option<bool>,
+
+    /// AnyTLS 入站的监听地址（默认 ::） [EZ_ANYTLS_LISTEN]
+    #[arg(long, global = true, value_name = "IP")]
+    pub anytls_listen: Option<String>,
+
+    /// Hysteria2 入站的监听地址（默认 0.0.0.0） [EZ_HYSTERIA2_LISTEN]
+    #[arg(long, global = true, value_name = "IP")]
+    pub hysteria2_listen: Option<String>,
+
+    /// TUIC 入站的监听地址（默认 ::） [EZ_TUIC_LISTEN]
+    #[arg(long, global = true, value_name = "IP")]
+    pub tuic_listen: Option<String>,
+
+    /// VLESS-Reality 入站的监听地址（默认 ::） [EZ_VLESS_REALITY_LISTEN]
+    #[arg(long, global = true, value_name = "IP")]
+    pub vless_reality_listen: Option<String>,
+
+    /// VLESS-WebSocket 入站的监听地址（默认 ::） [EZ_VLESS_WS_LISTEN]
+    #[arg(long, global = true, value_name = "IP")]
+    pub vless_ws_listen: Option<String>,
+
+    /// VLESS-gRPC 入站的监听地址（默认 ::） [EZ_VLESS_GRPC_LISTEN]
+    #[arg(long, global = true, value_name = "IP")]
+    pub vless_grpc_listen: Option<String>,
+
+    /// VLESS-Reality 握手服务器 [EZ_VLESS_HANDSHAKE_SERVER]
+    #[arg(long, global = true, value_name = "HOST")]
+    pub vless_handshake_server: Option<String>,
+
+    /// VLESS-Reality 握手端口 [EZ_VLESS_HANDSHAKE_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub vless_handshake_port: Option<u16>,
+
+    /// VLESS-WebSocket 路径（不指定时随机生成） [EZ_VLESS_WS_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub vless_ws_path: Option<String>,
+
+    /// VLESS-WebSocket 客户端 Host 请求头（不指定时与 SNI 相同） [EZ_VLESS_WS_HOST]
+    #[arg(long, global = true, value_name = "HOST")]
+    pub vless_ws_host: Option<String>,
+
+    /// VLESS-WebSocket / gRPC 客户端连接的 CDN 地址（优选 IP 或 CDN 主机名，SNI 仍为域名） [EZ_CDN_HOST]
+    #[arg(long, global = true, value_name = "HOST")]
+    pub cdn_host: Option<String>,
+
+    /// VLESS-gRPC 服务名（不指定时随机生成） [EZ_VLESS_GRPC_SERVICE]
+    #[arg(long, global = true, value_name = "NAME")]
+    pub vless_grpc_service: Option<String>,
+
+    /// 检测 REALITY 握手目标是否支持 TLS 1.3 与 HTTP/2 [EZ_REALITY_CHECK_TARGET]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub reality_check_target: Option<bool>,
+
+    /// 目标不可用时自动从内置候选中选择握手目标 [EZ_REALITY_AUTO_TARGET]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub reality_auto_target: Option<bool>,
+
+    /// 指定的 REALITY 短 ID（逗号分隔，第一个用于客户端） [EZ_REALITY_SHORT_IDS]
+    #[arg(long, global = true, value_name = "IDS")]
+    pub reality_short_ids: Option<String>,
+
+    /// 自动生成的 REALITY 短 ID 数量 [EZ_REALITY_SHORT_ID_COUNT]
+    #[arg(long, global = true, value_name = "N")]
+    pub reality_short_id_count: Option<u16>,
+
+    /// 自动生成的 REALITY 短 ID 字节长度（0-8，默认 4） [EZ_REALITY_SHORT_ID_LEN]
+    #[arg(long, global = true, value_name = "BYTES")]
+    pub reality_short_id_len: Option<u16>,
+
+    /// 复用的 REALITY 私钥（不设置则每次生成新密钥对） [EZ_REALITY_PRIVATE_KEY]
+    #[arg(long, global = true, value_name = "KEY")]
+    pub reality_private_key: Option<String>,
+
+    /// REALITY 密钥对生成方式: internal, sing-box [EZ_REALITY_KEYGEN]
+    #[arg(long, global = true, value_name = "MODE")]
+    pub reality_keygen: Option<String>,
+
+    /// 服务端 DNS 上游（cloudflare / google / quad9 / alidns 或 https://、tls:// 等 URL） [EZ_DNS_UPSTREAM]
+    #[arg(long, global = true, value_name = "UPSTREAM")]
+    pub dns_upstream: Option<String>,
+
+    /// 服务端域名解析策略: prefer_ipv4, prefer_ipv6, ipv4_only, ipv6_only [EZ_DNS_STRATEGY]
+    #[arg(long, global = true, value_name = "STRATEGY")]
+    pub dns_strategy: Option<String>,
+
+    /// 在 DNS 层屏蔽的域名（逗号分隔，包含子域名） [EZ_DNS_BLOCK_DOMAINS]
+    #[arg(long, global = true, value_name = "DOMAINS")]
+    pub dns_block_domains: Option<String>,
+
+    /// DNS 屏蔽动作: reject, drop, nxdomain, refused [EZ_DNS_BLOCK_ACTION]
+    #[arg(long, global = true, value_name = "ACTION")]
+    pub dns_block_action: Option<String>,
+
+    /// 中转模式：流量转发到的上游（分享链接或 JSON 格式的 sing-box 出站） [EZ_RELAY_UPSTREAM]
+    #[arg(long, global = true, value_name = "LINK|JSON")]
+    pub relay_upstream: Option<String>,
+
+    /// 拒绝访问的目标国家（GeoIP 国家代码，逗号分隔） [EZ_GEOIP_BLOCK]
+    #[arg(long, global = true, value_name = "COUNTRIES")]
+    pub geoip_block: Option<String>,
+
+    /// 直连的目标国家（不经中转 / WARP，逗号分隔） [EZ_GEOIP_DIRECT]
+    #[arg(long, global = true, value_name = "COUNTRIES")]
+    pub geoip_direct: Option<String>,
+
+    /// 拒绝访问中国大陆的 IP 与域名（GeoIP + Geosite） [EZ_BLOCK_CN_INBOUND]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub block_cn_inbound: Option<bool>,
+
+    /// 规则集下载地址前缀（GitHub 加速镜像） [EZ_RULE_SET_MIRROR]
+    #[arg(long, global = true, value_name = "URL")]
+    pub rule_set_mirror: Option<String>,
+
+    /// WARP 出口的 WireGuard 私钥（设置后添加 WARP 端点） [EZ_WARP_PRIVATE_KEY]
+    #[arg(long, global = true, value_name = "KEY")]
+    pub warp_private_key: Option<String>,
+
+    /// WARP 分配的本地地址（逗号分隔，默认 172.16.0.2/32） [EZ_WARP_ADDRESS]
+    #[arg(long, global = true, value_name = "CIDRS")]
+    pub warp_address: Option<String>,
+
+    /// WARP 对端地址（默认 engage.cloudflareclient.com:2408） [EZ_WARP_PEER]
+    #[arg(long, global = true, value_name = "HOST:PORT")]
+    pub warp_peer: Option<String>,
+
+    /// WARP 对端公钥（默认 Cloudflare 公钥） [EZ_WARP_PEER_PUBLIC_KEY]
+    #[arg(long, global = true, value_name = "KEY")]
+    pub warp_peer_public_key: Option<String>,
+
+    /// WireGuard 保留字节（1,2,3 或 base64 client_id） [EZ_WARP_RESERVED]
+    #[arg(long, global = true, value_name = "BYTES")]
+    pub warp_reserved: Option<String>,
+
+    /// WARP 端点 MTU（默认 1280） [EZ_WARP_MTU]
+    #[arg(long, global = true, value_name = "MTU")]
+    pub warp_mtu: Option<u16>,
+
+    /// 经 WARP 转发的域名后缀（逗号分隔） [EZ_WARP_DOMAINS]
+    #[arg(long, global = true, value_name = "DOMAINS")]
+    pub warp_domains: Option<String>,
+
+    /// 经 WARP 转发的内置服务（openai / claude / gemini / netflix / disney，逗号分隔） [EZ_WARP_SERVICES]
+    #[arg(long, global = true, value_name = "SERVICES")]
+    pub warp_services: Option<String>,
+
+    /// 全部流量经 WARP 转发 [EZ_WARP_ALL]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub warp_all: Option<bool>,
+
+    /// 滥用防护规则作用的协议（逗号分隔，默认全部） [EZ_LIMIT_PROTOCOLS]
+    #[arg(long, global = true, value_name = "PROTOCOLS")]
+    pub limit_protocols: Option<String>,
+
+    /// 拒绝的来源 IP / CIDR（逗号分隔） [EZ_LIMIT_BLOCKED_SOURCES]
+    #[arg(long, global = true, value_name = "CIDRS")]
+    pub limit_blocked_sources: Option<String>,
+
+    /// 嗅探并拒绝 BitTorrent 流量 [EZ_LIMIT_BLOCK_BITTORRENT]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub limit_block_bittorrent: Option<bool>,
+
+    /// 拒绝 SMTP 端口 25/465/587 [EZ_LIMIT_BLOCK_SMTP]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub limit_block_smtp: Option<bool>,
+
+    /// 拒绝访问私有地址 [EZ_LIMIT_BLOCK_PRIVATE]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub limit_block_private: Option<bool>,
+
+    /// 日志级别 [EZ_LOG_LEVEL]
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    /// ezsingbox 自身日志格式（text / json） [EZ_LOG_FORMAT]
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub log_format: Option<LogFormat>,
+
+    /// 是否打印配置内容 [EZ_PRINT_CONFIG]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub print_config: Option<bool>,
+
+    /// 试运行：生成配置但不写入文件 [EZ_DRY_RUN]
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub dry_run: Option<bool>,
+
+    /// 写入前输出与已有配置文件的差异 [EZ_DIFF]
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub diff: Option<bool>,
+
+    /// 配置字段按固定顺序输出（顶层按 sing-box 文档顺序，其余 type、tag 在前并按字母排序） [EZ_CANONICAL_OUTPUT]
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub canonical_output: Option<bool>,
+
+    /// 去掉与 sing-box 默认值相同的字段，输出等价的最小配置 [EZ_MINIMAL_CONFIG]
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub minimal_config: Option<bool>,
+
+    /// 写入带注释的配置（JSONC），说明各段来自哪些环境变量、凭证的用途 [EZ_ANNOTATE_CONFIG]
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub annotate_config: Option<bool>,
+
+    /// 是否打印连接详情 [EZ_PRINT_DETAILS]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub print_details: Option<bool>,
+
+    /// 控制台输出中脱敏密码、UUID 与私钥（写入的文件保持完整） [EZ_REDACT_SECRETS]
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub redact_secrets: Option<bool>,
+
+    /// 错误输出格式（text / json） [EZ_ERROR_FORMAT]
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub error_format: Option<ErrorFormat>,
+
+    /// generate 结果输出格式（text / json，json 时标准输出只包含一个 JSON 文档） [EZ_OUTPUT]
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub output: Option<OutputFormat>,
+
+    /// 输出语言（zh / en，默认按 LANG 判断） [EZ_LANG]
+    #[arg(long, global = true, value_name = "LANG")]
+    pub lang: Option<Lang>,
+
+    /// 客户端配置输出路径（目录或含 {protocol} / {user} 的模式时每个组合一个文件） [EZ_CLIENT_CONFIG_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub client_config_path: Option<String>,
+
+    /// 客户端使用的协议 [EZ_CLIENT_PROTOCOL]
+    #[arg(long, global = true, value_name = "PROTOCOL")]
+    pub client_protocol: Option<Protocol>,
+
+    /// 客户端使用的用户 [EZ_CLIENT_USER]
+    #[arg(long, global = true, value_name = "USER")]
+    pub client_user: Option<String>,
+
+    /// 客户端 mixed 入站监听地址 [EZ_CLIENT_MIXED_LISTEN]
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub client_mixed_listen: Option<String>,
+
+    /// 客户端 mixed 入站端口 [EZ_CLIENT_MIXED_PORT]
+    #[arg(long, global = true, value_name = "PORT")]
+    pub client_mixed_port: Option<u16>,
+
+    /// 客户端链式代理的前置节点（socks:// 或分享链接） [EZ_CLIENT_CHAIN_FRONT]
+    #[arg(long, global = true, value_name = "LINK")]
+    pub client_chain_front: Option<String>,
+
+    /// 客户端 DNS 方案: simple, split, fakeip [EZ_CLIENT_DNS_MODE]
+    #[arg(long, global = true, value_name = "MODE")]
+    pub client_dns_mode: Option<String>,
+
+    /// 客户端屏蔽广告与跟踪域名（DNS 拒绝 + 连接拒绝） [EZ_CLIENT_ADBLOCK]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub client_adblock: Option<bool>,
+
+    /// 客户端平台: desktop, ios, android, router [EZ_CLIENT_PLATFORM]
+    #[arg(long, global = true, value_name = "PLATFORM")]
+    pub client_platform: Option<String>,
+
+    /// 只代理的 Android 应用包名（逗号分隔，仅 android 平台） [EZ_CLIENT_INCLUDE_PACKAGES]
+    #[arg(long, global = true, value_name = "PACKAGES")]
+    pub client_include_packages: Option<String>,
+
+    /// 不代理的 Android 应用包名（逗号分隔，仅 android 平台） [EZ_CLIENT_EXCLUDE_PACKAGES]
+    #[arg(long, global = true, value_name = "PACKAGES")]
+    pub client_exclude_packages: Option<String>,
+
+    /// 客户端 uTLS 指纹（AnyTLS / VLESS）: chrome, firefox, safari, ios, random ... [EZ_CLIENT_UTLS_FINGERPRINT]
+    #[arg(long, global = true, value_name = "FINGERPRINT")]
+    pub client_utls_fingerprint: Option<String>,
+
+    /// 客户端连接的服务器地址（如 CDN 地址或其他入口 IP，按协议覆盖见 EZ_CLIENT_SERVER_OVERRIDE_<协议>） [EZ_CLIENT_SERVER_OVERRIDE]
+    #[arg(long, global = true, value_name = "HOST")]
+    pub client_server_override: Option<String>,
+
+    /// 客户端使用的 SNI（VLESS Reality 除外，按协议覆盖见 EZ_SNI_OVERRIDE_<协议>） [EZ_SNI_OVERRIDE]
+    #[arg(long, global = true, value_name = "DOMAIN")]
+    pub sni_override: Option<String>,
+
+    /// 导出文件路径（不指定时输出到标准输出） [EZ_EXPORT_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub export_path: Option<String>,
+
+    /// export template 使用的 Handlebars 模板路径 [EZ_CLIENT_TEMPLATE_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub client_template_path: Option<String>,
+
+    /// 远程配置订阅地址 [EZ_REMOTE_PROFILE_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub remote_profile_url: Option<String>,
+
+    /// 远程配置名称 [EZ_REMOTE_PROFILE_NAME]
+    #[arg(long, global = true, value_name = "NAME")]
+    pub remote_profile_name: Option<String>,
+
+    /// 订阅服务监听地址（逗号分隔多个，dual 同时监听 IPv4 与 IPv6，unix://<路径> 为 Unix 套接字） [EZ_SUBSCRIBE_LISTEN]
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub subscribe_listen: Option<String>,
+
+    /// 订阅服务路径 [EZ_SUBSCRIBE_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub subscribe_path: Option<String>,
+
+    /// 订阅服务公开地址 [EZ_SUBSCRIBE_PUBLIC_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub subscribe_public_url: Option<String>,
+
+    /// 订阅名称 [EZ_SUBSCRIBE_NAME]
+    #[arg(long, global = true, value_name = "NAME")]
+    pub subscribe_name: Option<String>,
+
+    /// 订阅服务 Basic 认证用户名 [EZ_SUBSCRIBE_BASIC_USER]
+    #[arg(long, global = true, value_name = "USER")]
+    pub subscribe_basic_user: Option<String>,
+
+    /// 订阅服务 Basic 认证密码 [EZ_SUBSCRIBE_BASIC_PASS]
+    #[arg(long, global = true, value_name = "PASS")]
+    pub subscribe_basic_pass: Option<String>,
+
+    /// 允许访问订阅服务的来源地址段（逗号分隔，如 10.0.0.0/8） [EZ_SUBSCRIBE_ALLOW_CIDRS]
+    #[arg(long, global = true, value_name = "CIDRS")]
+    pub subscribe_allow_cidrs: Option<String>,
+
+    /// 受信任的反向代理地址段（信任其 X-Forwarded-* 请求头） [EZ_SUBSCRIBE_TRUSTED_PROXIES]
+    #[arg(long, global = true, value_name = "CIDRS")]
+    pub subscribe_trusted_proxies: Option<String>,
+
+    /// 路由匹配前从请求路径中去除的前缀（如 /ezsub） [EZ_SUBSCRIBE_PATH_PREFIX]
+    #[arg(long, global = true, value_name = "PREFIX")]
+    pub subscribe_path_prefix: Option<String>,
+
+    /// 订阅服务每个来源每分钟的请求数（默认 30，0 为不限流） [EZ_SUBSCRIBE_RATE_LIMIT]
+    #[arg(long, global = true, value_name = "N")]
+    pub subscribe_rate_limit: Option<u32>,
+
+    /// 订阅服务每个来源的突发请求数（默认 10） [EZ_SUBSCRIBE_RATE_BURST]
+    #[arg(long, global = true, value_name = "N")]
+    pub subscribe_rate_burst: Option<u32>,
+
+    /// 签名订阅链接的服务端密钥（启用 /sub/<用户> 签名链接） [EZ_SUBSCRIBE_SIGNING_KEY]
+    #[arg(long, global = true, value_name = "KEY")]
+    pub subscribe_signing_key: Option<String>,
+
+    /// 订阅服务审计日志路径（默认 ./ezsingbox-audit.log） [EZ_AUDIT_LOG]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub audit_log: Option<String>,
+
+    /// 故障排查包输出路径 [EZ_BUNDLE_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub bundle_path: Option<String>,
+
+    /// 故障排查包使用的 sing-box 日志文件 [EZ_SUPPORT_LOG]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub support_log: Option<String>,
+
+    /// 健康检查连接的地址（默认 127.0.0.1） [EZ_HEALTH_HOST]
+    #[arg(long, global = true, value_name = "IP")]
+    pub health_host: Option<IpAddr>,
+
+    /// 健康检查单个入站的超时（默认 3s） [EZ_HEALTH_TIMEOUT]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub health_timeout: Option<String>,
+
+    /// 检查 Hysteria2 / TUIC 的 UDP 端口是否可达（generate 给出提示，run 启动后自检，默认启用） [EZ_UDP_SELFTEST]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub udp_selftest: Option<bool>,
+
+    /// 外部 UDP 探测服务地址模板（含 {host} {port}，返回 {"reachable": bool}） [EZ_UDP_PROBE_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub udp_probe_url: Option<String>,
+
+    /// install-singbox 的安装前缀（默认 /usr/local，安装到 <前缀>/bin/sing-box） [EZ_INSTALL_PREFIX]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub install_prefix: Option<String>,
+
+    /// docker-compose.yaml 使用的镜像 [EZ_COMPOSE_IMAGE]
+    #[arg(long, global = true, value_name = "IMAGE")]
+    pub compose_image: Option<String>,
+
+    /// deploy 远程配置目录（默认 /etc/sing-box） [EZ_DEPLOY_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub deploy_dir: Option<String>,
+
+    /// deploy 远程 sing-box 二进制文件路径（默认 /usr/local/bin/sing-box） [EZ_DEPLOY_REMOTE_BIN]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub deploy_remote_bin: Option<String>,
+
+    /// deploy 时上传的本地 sing-box 二进制文件（不指定时使用远程已安装的版本） [EZ_DEPLOY_BINARY]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub deploy_binary: Option<String>,
+
+    /// deploy 安装的 systemd 服务名（默认 sing-box） [EZ_DEPLOY_SERVICE]
+    #[arg(long, global = true, value_name = "NAME")]
+    pub deploy_service: Option<String>,
+
+    /// deploy 使用的 SSH 私钥文件 [EZ_DEPLOY_SSH_KEY]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub deploy_ssh_key: Option<String>,
+
+    /// 延迟测试地址（只支持 http://） [EZ_URLTEST_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub urltest_url: Option<String>,
+
+    /// 延迟测试单个协议的超时（默认 5s） [EZ_URLTEST_TIMEOUT]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub urltest_timeout: Option<String>,
+
+    /// sing-box 二进制文件路径 [SING_BOX_BIN]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub sing_box_bin: Option<String>,
+
+    /// 运行前是否执行 sing-box check [EZ_SINGBOX_CHECK]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub singbox_check: Option<bool>,
+
+    /// 目标 sing-box 版本，低于 1.12 时输出旧版 DNS / 路由格式 [EZ_SINGBOX_VERSION]
+    #[arg(long, global = true, value_name = "VERSION")]
+    pub singbox_version: Option<String>,
+
+    /// run 模式下定期检查 sing-box 新版本 [EZ_AUTO_UPGRADE_CHECK]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub auto_upgrade_check: Option<bool>,
+
+    /// sing-box 新版本检查间隔（默认 24h） [EZ_UPGRADE_CHECK_INTERVAL]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub upgrade_check_interval: Option<String>,
+
+    /// 发现新版本时自动升级并平滑重启 sing-box [EZ_AUTO_UPGRADE]
+    #[arg(long, global = true, value_name = "BOOL")]
+    pub auto_upgrade: Option<bool>,
+
+    /// 自动升级的维护时间窗口（UTC，如 03:00-05:00） [EZ_UPGRADE_WINDOW]
+    #[arg(long, global = true, value_name = "HH:MM-HH:MM")]
+    pub upgrade_window: Option<String>,
+
+    /// 启用 sing-box Clash API 的监听地址（如 127.0.0.1:9090），run 模式据此统计流量 [EZ_CLASH_API]
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub clash_api: Option<String>,
+
+    /// Clash API 密钥 [EZ_CLASH_API_SECRET]
+    #[arg(long, global = true, value_name = "SECRET")]
+    pub clash_api_secret: Option<String>,
+
+    /// 流量统计的轮询间隔（默认 1m） [EZ_STATS_INTERVAL]
+    #[arg(long, global = true, value_name = "DURATION")]
+    pub stats_interval: Option<String>,
+
+    /// 流量统计文件路径（默认 ./ezsingbox-stats.json） [EZ_STATS_PATH]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub stats_path: Option<String>,
+
+    /// run 模式下 /metrics 指标服务的监听地址 [EZ_METRICS_LISTEN]
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub metrics_listen: Option<String>,
+
+    /// Telegram 机器人令牌（启用 Telegram 通知） [EZ_TELEGRAM_BOT_TOKEN]
+    #[arg(long, global = true, value_name = "TOKEN")]
+    pub telegram_bot_token: Option<String>,
+
+    /// 接收通知的 Telegram 会话 ID [EZ_TELEGRAM_CHAT_ID]
+    #[arg(long, global = true, value_name = "ID")]
+    pub telegram_chat_id: Option<String>,
+
+    /// 接收事件通知的 Webhook 地址 [EZ_WEBHOOK_URL]
+    #[arg(long, global = true, value_name = "URL")]
+    pub webhook_url: Option<String>,
+
+    /// Webhook 负载的 HMAC-SHA256 签名密钥 [EZ_WEBHOOK_SECRET]
+    #[arg(long, global = true, value_name = "SECRET")]
+    pub webhook_secret: Option<String>,
+
+    /// 只发送这些事件（逗号分隔） [EZ_WEBHOOK_EVENTS]
+    #[arg(long, global = true, value_name = "EVENTS")]
+    pub webhook_events: Option<String>,
+}
+
+impl Options {
+    /// 转换为环境变量覆盖表（仅包含命令行中指定的参数）
+    pub fn to_overrides(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        let mut put = |key: &str, value: Option<String>| {
+            if let Some(v) = value {
+                map.insert(key.to_string(), v);
+            }
+        };
+
+        put("EZ_FILE", self.file.clone());
+        put("EZ_CONFIG_PATH", self.config_path.clone());
+        put(
+            "EZ_CONFIG_BACKUPS",
+            self.config_backups.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_ROLLBACK_RELOAD",
+            self.rollback_reload.map(|v| v.to_string()),
+        );
+        put("EZ_FLEET", self.fleet.clone());
+        put("EZ_FLEET_DIR", self.fleet_dir.clone());
+        put("EZ_PRESET", self.preset.clone());
+        put("EZ_PUBLIC_IP", self.public_ip.map(|v| v.to_string()));
+        put(
+            "EZ_IP_DETECTOR",
+            self.ip_detector.as_ref().map(|detectors| {
+                let names: Vec<String> = detectors.iter().map(|d| d.to_string()).collect();
+                names.join(",")
+            }),
+        );
+        put(
+            "EZ_IP_SERVICES",
+            self.ip_services.as_ref().map(|v| v.join(",")),
+        );
+        put("EZ_DOMAIN", self.domain.clone());
+        put("EZ_DOMAIN_PROVIDER", self.domain_provider.clone());
+        put("EZ_DOMAIN_PATTERN", self.domain_pattern.clone());
+        put("EZ_DUCKDNS_DOMAIN", self.duckdns_domain.clone());
+        put("EZ_DUCKDNS_TOKEN", self.duckdns_token.clone());
+        put("EZ_ACME_EMAIL", self.acme_email.clone());
+        put("EZ_ACME_PROVIDER", self.acme_provider.clone());
+        put("EZ_ACME_DATA_DIR", self.acme_data_dir.clone());
+        put(
+            "EZ_ACME_PREFLIGHT",
+            self.acme_preflight.map(|v| v.to_string()),
+        );
+        put("EZ_ACME_EAB_KEY_ID", self.acme_eab_key_id.clone());
+        put("EZ_ACME_EAB_MAC_KEY", self.acme_eab_mac_key.clone());
+        put("EZ_ACME_DNS01_PROVIDER", self.acme_dns01_provider.clone());
+        put("EZ_CF_API_TOKEN", self.cf_api_token.clone());
+        put("EZ_ALIDNS_ACCESS_KEY_ID", self.alidns_access_key_id.clone());
+        put(
+            "EZ_ALIDNS_ACCESS_KEY_SECRET",
+            self.alidns_access_key_secret.clone(),
+        );
+        put("EZ_ALIDNS_REGION_ID", self.alidns_region_id.clone());
+        if let Some(enabled) = &self.enable {
+            for p in Protocol::ALL {
+                put(enable_key(p), Some(enabled.contains(&p).to_string()));
+            }
+        }
+        put("EZ_ANYTLS_PORT", self.port_anytls.map(|v| v.to_string()));
+        put("EZ_HYSTERIA2_PORT", self.port_hy2.map(|v| v.to_string()));
+        put("EZ_TUIC_PORT", self.port_tuic.map(|v| v.to_string()));
+        put(
+            "EZ_VLESS_REALITY_PORT",
+            self.port_vless.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_VLESS_WS_PORT",
+            self.port_vless_ws.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_VLESS_GRPC_PORT",
+            self.port_vless_grpc.map(|v| v.to_string()),
+        );
+        put("EZ_USER", self.user.clone());
+        put("EZ_USERS", self.users.clone());
+        put("EZ_USERS_JSON", self.users_json.clone());
+        put("EZ_PASSWORD", self.password.clone());
+        put("EZ_ROTATE_INTERVAL", self.rotate_interval.clone());
+        put("EZ_STATE_PATH", self.state_path.clone());
+        put("EZ_ROTATION_LOG", self.rotation_log.clone());
+        put(
+            "EZ_ROTATE_SHORT_IDS",
+            self.rotate_short_ids.map(|v| v.to_string()),
+        );
+        put("EZ_STATE_ENCRYPTION_KEY", self.state_encryption_key.clone());
+        put("EZ_HY2_OBFS", self.hy2_obfs.map(|v| v.to_string()));
+        put("EZ_HY2_MASQUERADE_TYPE", self.hy2_masquerade_type.clone());
+        put("EZ_HY2_MASQUERADE_URL", self.hy2_masquerade_url.clone());
+        put("EZ_HY2_MASQUERADE_DIR", self.hy2_masquerade_dir.clone());
+        put(
+            "EZ_HY2_MASQUERADE_STATUS",
+            self.hy2_masquerade_status.map(|v| v.to_string()),
+        );
+        put("EZ_HY2_UP_MBPS", self.hy2_up_mbps.map(|v| v.to_string()));
+        put(
+            "EZ_HY2_DOWN_MBPS",
+            self.hy2_down_mbps.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_HY2_IGNORE_CLIENT_BW",
+            self.hy2_ignore_client_bw.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_BRUTAL_UP_MBPS",
+            self.brutal_up_mbps.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_BRUTAL_DOWN_MBPS",
+            self.brutal_down_mbps.map(|v| v.to_string()),
+        );
+        put("EZ_MUX_PROTOCOL", self.mux_protocol.clone());
+        put(
+            "EZ_MUX_MAX_STREAMS",
+            self.mux_max_streams.map(|v| v.to_string()),
+        );
+        put("EZ_MUX_PADDING", self.mux_padding.map(|v| v.to_string()));
+        put("EZ_TUIC_CC", self.tuic_cc.clone());
+        put("EZ_TUIC_AUTH_TIMEOUT", self.tuic_auth_timeout.clone());
+        put(
+            "EZ_TUIC_ZERO_RTT",
+            self.tuic_zero_rtt.map(|v| v.to_string()),
+        );
+        put("EZ_TUIC_HEARTBEAT", self.tuic_heartbeat.clone());
+        put("EZ_TUIC_UDP_RELAY_MODE", self.tuic_udp_relay_mode.clone());
+        put(
+            "EZ_TCP_FAST_OPEN",
+            self.tcp_fast_open.map(|v| v.to_string()),
+        );
+        put("EZ_UDP_TIMEOUT", self.udp_timeout.clone());
+        put("EZ_SNIFF", self.sniff.map(|v| v.to_string()));
+        put(
+            "EZ_SNIFF_OVERRIDE_DESTINATION",
+            self.sniff_override_destination.map(|v| v.to_string()),
+        );
+        put("EZ_BIND_INTERFACE", self.bind_interface.clone());
+        put("EZ_ROUTING_MARK", self.routing_mark.clone());
+        put("EZ_NETNS", self.netns.clone());
+        put("EZ_SNIFF_ANYTLS", self.sniff_anytls.map(|v| v.to_string()));
+        put(
+            "EZ_SNIFF_HYSTERIA2",
+            self.sniff_hysteria2.map(|v| v.to_string()),
+        );
+        put("EZ_SNIFF_TUIC", self.sniff_tuic.map(|v| v.to_string()));
+        put(
+            "EZ_SNIFF_VLESS_REALITY",
+            self.sniff_vless_reality.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_SNIFF_VLESS_WS",
+            self.sniff_vless_ws.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_SNIFF_VLESS_GRPC",
+            self.sniff_vless_grpc.map(|v| v.to_string()),
+        );
+        put("EZ_ANYTLS_LISTEN", self.anytls_listen.clone());
+        put("EZ_HYSTERIA2_LISTEN", self.hysteria2_listen.clone());
+        put("EZ_TUIC_LISTEN", self.tuic_listen.clone());
+        put("EZ_VLESS_REALITY_LISTEN", self.vless_reality_listen.clone());
+        put("EZ_VLESS_WS_LISTEN", self.vless_ws_listen.clone());
+        put("EZ_VLESS_GRPC_LISTEN", self.vless_grpc_listen.clone());
+        put(
+            "EZ_VLESS_HANDSHAKE_SERVER",
+            self.vless_handshake_server.clone(),
+        );
+        put(
+            "EZ_VLESS_HANDSHAKE_PORT",
+            self.vless_handshake_port.map(|v| v.to_string()),
+        );
+        put("EZ_VLESS_WS_PATH", self.vless_ws_path.clone());
+        put("EZ_VLESS_WS_HOST", self.vless_ws_host.clone());
+        put("EZ_CDN_HOST", self.cdn_host.clone());
+        put("EZ_VLESS_GRPC_SERVICE", self.vless_grpc_service.clone());
+        put(
+            "EZ_REALITY_CHECK_TARGET",
+            self.reality_check_target.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_REALITY_AUTO_TARGET",
+            self.reality_auto_target.map(|v| v.to_string()),
+        );
+        put("EZ_REALITY_SHORT_IDS", self.reality_short_ids.clone());
+        put(
+            "EZ_REALITY_SHORT_ID_COUNT",
+            self.reality_short_id_count.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_REALITY_SHORT_ID_LEN",
+            self.reality_short_id_len.map(|v| v.to_string()),
+        );
+        put("EZ_REALITY_PRIVATE_KEY", self.reality_private_key.clone());
+        put("EZ_REALITY_KEYGEN", self.reality_keygen.clone());
+        put("EZ_DNS_UPSTREAM", self.dns_upstream.clone());
+        put("EZ_DNS_STRATEGY", self.dns_strategy.clone());
+        put("EZ_DNS_BLOCK_DOMAINS", self.dns_block_domains.clone());
+        put("EZ_DNS_BLOCK_ACTION", self.dns_block_action.clone());
+        put("EZ_RELAY_UPSTREAM", self.relay_upstream.clone());
+        put("EZ_GEOIP_BLOCK", self.geoip_block.clone());
+        put("EZ_GEOIP_DIRECT", self.geoip_direct.clone());
+        put(
+            "EZ_BLOCK_CN_INBOUND",
+            self.block_cn_inbound.map(|v| v.to_string()),
+        );
+        put("EZ_RULE_SET_MIRROR", self.rule_set_mirror.clone());
+        put("EZ_WARP_PRIVATE_KEY", self.warp_private_key.clone());
+        put("EZ_WARP_ADDRESS", self.warp_address.clone());
+        put("EZ_WARP_PEER", self.warp_peer.clone());
+        put("EZ_WARP_PEER_PUBLIC_KEY", self.warp_peer_public_key.clone());
+        put("EZ_WARP_RESERVED", self.warp_reserved.clone());
+        put("EZ_WARP_MTU", self.warp_mtu.map(|v| v.to_string()));
+        put("EZ_WARP_DOMAINS", self.warp_domains.clone());
+        put("EZ_WARP_SERVICES", self.warp_services.clone());
+        put("EZ_WARP_ALL", self.warp_all.map(|v| v.to_string()));
+        put("EZ_LIMIT_PROTOCOLS", self.limit_protocols.clone());
+        put(
+            "EZ_LIMIT_BLOCKED_SOURCES",
+            self.limit_blocked_sources.clone(),
+        );
+        put(
+            "EZ_LIMIT_BLOCK_BITTORRENT",
+            self.limit_block_bittorrent.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_LIMIT_BLOCK_SMTP",
+            self.limit_block_smtp.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_LIMIT_BLOCK_PRIVATE",
+            self.limit_block_private.map(|v| v.to_string()),
+        );
+        put("EZ_LOG_LEVEL", self.log_level.clone());
+        put("EZ_LOG_FORMAT", self.log_format.map(|v| v.to_string()));
+        put("EZ_PRINT_CONFIG", self.print_config.map(|v| v.to_string()));
+        put("EZ_DRY_RUN", self.dry_run.map(|v| v.to_string()));
+        put("EZ_DIFF", self.diff.map(|v| v.to_string()));
+        put(
+            "EZ_CANONICAL_OUTPUT",
+            self.canonical_output.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_MINIMAL_CONFIG",
+            self.minimal_config.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_ANNOTATE_CONFIG",
+            self.annotate_config.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_PRINT_DETAILS",
+            self.print_details.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_REDACT_SECRETS",
+            self.redact_secrets.map(|v| v.to_string()),
+        );
+        put("EZ_ERROR_FORMAT", self.error_format.map(|v| v.to_string()));
+        put("EZ_OUTPUT", self.output.map(|v| v.to_string()));
+        put("EZ_LANG", self.lang.map(|v| v.to_string()));
+        put("EZ_CLIENT_CONFIG_PATH", self.client_config_path.clone());
+        put(
+            "EZ_CLIENT_PROTOCOL",
+            self.client_protocol.map(|v| v.to_string()),
+        );
+        put("EZ_CLIENT_USER", self.client_user.clone());
+        put("EZ_CLIENT_MIXED_LISTEN", self.client_mixed_listen.clone());
+        put(
+            "EZ_CLIENT_MIXED_PORT",
+            self.client_mixed_port.map(|v| v.to_string()),
+        );
+        put("EZ_CLIENT_CHAIN_FRONT", self.client_chain_front.clone());
+        put("EZ_CLIENT_DNS_MODE", self.client_dns_mode.clone());
+        put(
+            "EZ_CLIENT_ADBLOCK",
+            self.client_adblock.map(|v| v.to_string()),
+        );
+        put("EZ_CLIENT_PLATFORM", self.client_platform.clone());
+        put(
+            "EZ_CLIENT_INCLUDE_PACKAGES",
+            self.client_include_packages.clone(),
+        );
+        put(
+            "EZ_CLIENT_EXCLUDE_PACKAGES",
+            self.client_exclude_packages.clone(),
+        );
+        put(
+            "EZ_CLIENT_UTLS_FINGERPRINT",
+            self.client_utls_fingerprint.clone(),
+        );
+        put(
+            "EZ_CLIENT_SERVER_OVERRIDE",
+            self.client_server_override.clone(),
+        );
+        put("EZ_SNI_OVERRIDE", self.sni_override.clone());
+        put("EZ_EXPORT_PATH", self.export_path.clone());
+        put("EZ_CLIENT_TEMPLATE_PATH", self.client_template_path.clone());
+        put("EZ_REMOTE_PROFILE_URL", self.remote_profile_url.clone());
+        put("EZ_REMOTE_PROFILE_NAME", self.remote_profile_name.clone());
+        put("EZ_SUBSCRIBE_LISTEN", self.subscribe_listen.clone());
+        put("EZ_SUBSCRIBE_PATH", self.subscribe_path.clone());
+        put("EZ_SUBSCRIBE_PUBLIC_URL", self.subscribe_public_url.clone());
+        put("EZ_SUBSCRIBE_NAME", self.subscribe_name.clone());
+        put("EZ_SUBSCRIBE_BASIC_USER", self.subscribe_basic_user.clone());
+        put("EZ_SUBSCRIBE_BASIC_PASS", self.subscribe_basic_pass.clone());
+        put(
+            "EZ_SUBSCRIBE_ALLOW_CIDRS",
+            self.subscribe_allow_cidrs.clone(),
+        );
+        put(
+            "EZ_SUBSCRIBE_TRUSTED_PROXIES",
+            self.subscribe_trusted_proxies.clone(),
+        );
+        put(
+            "EZ_SUBSCRIBE_PATH_PREFIX",
+            self.subscribe_path_prefix.clone(),
+        );
+        put(
+            "EZ_SUBSCRIBE_RATE_LIMIT",
+            self.subscribe_rate_limit.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_SUBSCRIBE_RATE_BURST",
+            self.subscribe_rate_burst.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_SUBSCRIBE_SIGNING_KEY",
+            self.subscribe_signing_key.clone(),
+        );
+        put("EZ_AUDIT_LOG", self.audit_log.clone());
+        put("EZ_BUNDLE_PATH", self.bundle_path.clone());
+        put("EZ_SUPPORT_LOG", self.support_log.clone());
+        put("EZ_HEALTH_HOST", self.health_host.map(|v| v.to_string()));
+        put("EZ_HEALTH_TIMEOUT", self.health_timeout.clone());
+        put("EZ_UDP_SELFTEST", self.udp_selftest.map(|v| v.to_string()));
+        put("EZ_UDP_PROBE_URL", self.udp_probe_url.clone());
+        put("EZ_INSTALL_PREFIX", self.install_prefix.clone());
+        put("EZ_COMPOSE_IMAGE", self.compose_image.clone());
+        put("EZ_DEPLOY_DIR", self.deploy_dir.clone());
+        put("EZ_DEPLOY_REMOTE_BIN", self.deploy_remote_bin.clone());
+        put("EZ_DEPLOY_BINARY", self.deploy_binary.clone());
+        put("EZ_DEPLOY_SERVICE", self.deploy_service.clone());
+        put("EZ_DEPLOY_SSH_KEY", self.deploy_ssh_key.clone());
+        put("EZ_URLTEST_URL", self.urltest_url.clone());
+        put("EZ_URLTEST_TIMEOUT", self.urltest_timeout.clone());
+        put("SING_BOX_BIN", self.sing_box_bin.clone());
+        put(
+            "EZ_SINGBOX_CHECK",
+            self.singbox_check.map(|v| v.to_string()),
+        );
+        put("EZ_SINGBOX_VERSION", self.singbox_version.clone());
+        put(
+            "EZ_AUTO_UPGRADE_CHECK",
+            self.auto_upgrade_check.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_UPGRADE_CHECK_INTERVAL",
+            self.upgrade_check_interval.clone(),
+        );
+        put("EZ_AUTO_UPGRADE", self.auto_upgrade.map(|v| v.to_string()));
+        put("EZ_UPGRADE_WINDOW", self.upgrade_window.clone());
+        put("EZ_CLASH_API", self.clash_api.clone());
+        put("EZ_CLASH_API_SECRET", self.clash_api_secret.clone());
+        put("EZ_STATS_INTERVAL", self.stats_interval.clone());
+        put("EZ_STATS_PATH", self.stats_path.clone());
+        put("EZ_METRICS_LISTEN", self.metrics_listen.clone());
+        put("EZ_TELEGRAM_BOT_TOKEN", self.telegram_bot_token.clone());
+        put("EZ_TELEGRAM_CHAT_ID", self.telegram_chat_id.clone());
+        put("EZ_WEBHOOK_URL", self.webhook_url.clone());
+        put("EZ_WEBHOOK_SECRET", self.webhook_secret.clone());
+        put("EZ_WEBHOOK_EVENTS", self.webhook_events.clone());
+
+        map
+    }
+}
+
+/// 协议对应的启用开关环境变量名
+fn enable_key(protocol: Protocol) -> &'static str {
+    match protocol {
+        Protocol::AnyTls => "EZ_ENABLE_ANYTLS",
+        Protocol::Hysteria2 => "EZ_ENABLE_HYSTERIA2",
+        Protocol::Tuic => "EZ_ENABLE_TUIC",
+        Protocol::VlessReality => "EZ_ENABLE_VLESS_REALITY",
+        Protocol::VlessWs => "EZ_ENABLE_VLESS_WS",
+        Protocol::VlessGrpc => "EZ_ENABLE_VLESS_GRPC",
+    }
+}
+
+//============================================================================
+// 帮助信息本地化
+//============================================================================
+
+/// 英文帮助中的子命令说明（嵌套子命令以空格分隔）
+const COMMAND_ABOUT_EN: &[(&str, &str)] = &[
+    ("generate", "Generate server and client configs"),
+    ("run", "Generate configs and start sing-box"),
+    ("serve", "Start the HTTP subscription server"),
+    (
+        "rollback",
+        "Restore the latest server config backup and reload the running sing-box",
+    ),
+    (
+        "check",
+        "Check generated configs with schema validation and sing-box check",
+    ),
+    (
+        "lint",
+        "Lint any sing-box config for best practices (insecure TLS, CDN handshake targets, missing sniff, deprecated fields); exit with code 1 on errors",
+    ),
+    (
+        "migrate",
+        "Rewrite deprecated fields into their sing-box 1.12 equivalents (geosite / geoip to rule_set, legacy DNS servers to typed servers, ...); print to stdout or EZ_EXPORT_PATH",
+    ),
+    (
+        "convert",
+        "Convert another proxy tool's config into a sing-box config (VLESS / VMess / Trojan inbounds of an Xray server, or a client profile from Clash.Meta proxies); print to stdout or EZ_EXPORT_PATH",
+    ),
+    (
+        "support-bundle",
+        "Create a redacted support bundle (configs, check results, logs, environment)",
+    ),
+    (
+        "health",
+        "Connect to every inbound port locally; exit with code 1 if any fails",
+    ),
+    (
+        "test",
+        "Measure outbound latency per protocol through a temporary sing-box and recommend the fastest",
+    ),
+    (
+        "compose",
+        "Generate a docker-compose.yaml that publishes only enabled ports",
+    ),
+    (
+        "fleet",
+        "Generate a server config per server in EZ_FLEET and one client config with all nodes",
+    ),
+    (
+        "install-singbox",
+        "Download sing-box for this system from GitHub, verify SHA-256 and install it",
+    ),
+    (
+        "deploy",
+        "Deploy server configs over SSH, install the systemd unit and restart the service",
+    ),
+    ("firewall", "Print firewall rules for enabled inbounds"),
+    (
+        "links",
+        "Print proxy lists (share links, Base64 subscription, or Surge / Quantumult X / Loon lines)",
+    ),
+    ("cert", "ACME certificate management"),
+    (
+        "cert status",
+        "Show certificate expiry per domain in the ACME data directory",
+    ),
+    ("export", "Export config fragments"),
+    (
+        "export peer-outbound",
+        "Export outbounds usable by other sing-box instances",
+    ),
+    (
+        "export readme",
+        "Export end-user connection instructions (Markdown)",
+    ),
+    (
+        "export template",
+        "Render the client config with a custom Handlebars template (EZ_CLIENT_TEMPLATE_PATH)",
+    ),
+    (
+        "stats",
+        "Show per-inbound and per-user traffic collected by run via the Clash API",
+    ),
+    (
+        "audit",
+        "Show the subscription server access audit log (EZ_AUDIT_LOG)",
+    ),
+    (
+        "user",
+        "User management (users are stored in the state file)",
+    ),
+    (
+        "user add",
+        "Add a user with random credentials; takes effect after regenerating configs",
+    ),
+    (
+        "user token",
+        "Issue a subscription access token for a user (Bearer or ?token=); reissuing replaces it",
+    ),
+];
+
+/// 英文帮助中常用参数的说明，其余参数显示对应的环境变量
+const ARG_HELP_EN: &[(&str, &str)] = &[
+    ("file", "Declarative config file (ezsingbox.toml / yaml)"),
+    ("config_path", "Server config output path"),
+    (
+        "preset",
+        "Protocol preset (minimal / balanced / stealth / full / cdn)",
+    ),
+    ("public_ip", "Public IP (detected automatically by default)"),
+    ("domain", "Domain (generated from the public IP by default)"),
+    ("enable", "Protocols to enable (comma-separated)"),
+    ("users", "Users (name[:password], comma-separated)"),
+    ("log_level", "Log level"),
+    ("print_config", "Print the generated config"),
+    ("print_details", "Print connection details"),
+    (
+        "redact_secrets",
+        "Mask passwords, UUIDs and private keys in console output (files are written in full)",
+    ),
+    ("dry_run", "Dry run: generate configs without writing files"),
+    (
+        "diff",
+        "Show the diff against existing config files before writing",
+    ),
+    ("error_format", "Error output format (text / json)"),
+    (
+        "output",
+        "Output format of generate results (text / json; json prints one JSON document to stdout)",
+    ),
+    (
+        "lang",
+        "Output language (zh / en, derived from LANG by default)",
+    ),
+    ("client_config_path", "Client config output path"),
+    ("client_protocol", "Protocol used by the client config"),
+    ("client_user", "User used by the client config"),
+    (
+        "version",
+        "sing-box version (e.g. 1.12.8, latest release by default)",
+    ),
+    ("hosts", "SSH target (e.g. root@1.2.3.4, repeatable)"),
+    ("path", "sing-box config file to lint (comments allowed)"),
+    (
+        "source",
+        "sing-box config file to migrate (comments allowed)",
+    ),
+    ("from", "Source format (xray / clash)"),
+    (
+        "input",
+        "Config file to convert (Xray JSON with comments allowed, or Clash YAML)",
+    ),
+    ("format", "Output format"),
+    ("name", "User name"),
+    ("expires", "Expiry date (UTC, e.g. 2025-12-31)"),
+    ("revoke", "Revoke the user's token"),
+    ("user", "Only show entries for this user"),
+    (
+        "since",
+        "Only show entries from this recent period (e.g. 24h, 7d)",
+    ),
+    ("limit", "Only show the latest N entries"),
+    (
+        "expire",
+        "Print signed subscription links per user valid for this long instead (e.g. 24h)",
+    ),
+];
+
+/// 按语言构建命令行定义（英文时替换子命令与参数说明）
+pub fn localized_command(lang: Lang) -> Command {
+    let cmd = Cli::command();
+    if lang == Lang::Zh {
+        return cmd;
+    }
+    localize(cmd.about(Msg::AppAbout.text(lang)), lang, "")
+}
+
+/// 递归替换命令及其子命令的说明
+fn localize(cmd: Command, lang: Lang, path: &str) -> Command {
+    let cmd = cmd.mut_args(|arg| {
+        let id = arg.get_id().as_str();
+        if let Some((_, help)) = ARG_HELP_EN.iter().find(|(name, _)| *name == id) {
+            return arg.help(*help);
+        }
+        // 中文说明以 [EZ_*] 结尾，替换为对应的环境变量
+        let key = arg.get_help().map(|h| h.to_string()).and_then(|h| {
+            let start = h.rfind("[EZ_")?;
+            Some(h[start + 1..].trim_end_matches(']').to_string())
+        });
+        match key {
+            Some(key) => arg.help(fill(Msg::ArgFallback.text(lang), &[&key])),
+            None => arg,
+        }
+    });
+    cmd.mut_subcommands(|sub| {
+        let sub_path = if path.is_empty() {
+            sub.get_name().to_string()
+        } else {
+            format!("{} {}", path, sub.get_name())
+        };
+        let sub = match COMMAND_ABOUT_EN.iter().find(|(name, _)| *name == sub_path) {
+            Some((_, about)) => sub.about(*about),
+            None => sub,
+        };
+        localize(sub, lang, &sub_path)
+    })
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_cli() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+        localized_command(Lang::En).debug_assert();
+    }
+
+    #[test]
+    fn test_localized_command() {
+        let cmd = localized_command(Lang::En);
+        let generate = cmd.find_subcommand("generate").unwrap();
+        assert_eq!(
+            generate.get_about().unwrap().to_string(),
+            "Generate server and client configs"
+        );
+        let help = |id: &str| {
+            cmd.get_arguments()
+                .find(|a| a.get_id() == id)
+                .and_then(|a| a.get_help())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            help("lang"),
+            "Output language (zh / en, derived from LANG by default)"
+        );
+        assert_eq!(help("port_tuic"), "Sets EZ_TUIC_PORT (see IFLOW.md)");
+    }
+
+    #[test]
+    fn test_flags_documented_in_schema() {
+        use clap::CommandFactory;
+        for arg in Cli::command().get_arguments() {
+            let Some(help) = arg.get_help().map(|h| h.to_string()) else {
+                continue;
+            };
+            let Some(key) = help.rsplit_once('[').map(|(_, k)| k.trim_end_matches(']')) else {
+                continue;
+            };
+            if key == "EZ_ENABLE_*" {
+                continue;
+            }
+            assert!(
+                crate::envschema::lookup_kind(key).is_some(),
+                "{} 未在变量定义中声明",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_subcommand() {
+        let cli = Cli::try_parse_from(["ezsingbox"]).unwrap();
+        assert!(cli.command.is_none());
+        assert!(cli.options.to_overrides().is_empty());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let cli = Cli::try_parse_from([
+            "ezsingbox",
+            "generate",
+            "--domain",
+            "example.com",
+            "--enable",
+            "tuic,hy2",
+            "--port-tuic",
+            "2083",
+            "--print-config",
+            "false",
+            "--ip-detector",
+            "interface:eth0,dns",
+            "--output",
+            "json",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Some(Commands::Generate)));
+
+        let map = cli.options.to_overrides();
+        assert_eq!(map["EZ_DOMAIN"], "example.com");
+        assert_eq!(map["EZ_TUIC_PORT"], "2083");
+        assert_eq!(map["EZ_PRINT_CONFIG"], "false");
+        assert_eq!(map["EZ_OUTPUT"], "json");
+        assert!(!map.contains_key("EZ_LANG"));
+        assert_eq!(map["EZ_ENABLE_TUIC"], "true");
+        assert_eq!(map["EZ_ENABLE_HYSTERIA2"], "true");
+        assert_eq!(map["EZ_ENABLE_ANYTLS"], "false");
+        assert_eq!(map["EZ_ENABLE_VLESS_REALITY"], "false");
+        assert_eq!(map["EZ_IP_DETECTOR"], "interface:eth0,dns");
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(Cli::try_parse_from(["ezsingbox", "--enable", "trojan"]).is_err());
+        assert!(Cli::try_parse_from(["ezsingbox", "--port-tuic", "70000"]).is_err());
+        assert!(Cli::try_parse_from(["ezsingbox", "--ip-detector", "stun"]).is_err());
+        assert!(Cli::try_parse_from(["ezsingbox", "export"]).is_err());
+        let cli = Cli::try_parse_from(["ezsingbox", "export", "peer-outbound"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Export {
+                target: ExportTarget::PeerOutbound
+            })
+        ));
+        let cli = Cli::try_parse_from(["ezsingbox", "export", "readme"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Export {
+                target: ExportTarget::Readme
+            })
+        ));
+    }
+}
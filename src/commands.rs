@@ -1,121 +1,726 @@
 //! 命令处理模块
 
-use std::net::SocketAddr;
-use std::process::{Command, ExitCode};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::process::{Command, ExitCode, Stdio};
+use std::time::Instant;
 
 use base64::Engine;
 use tiny_http::{Header, Method, Response, StatusCode};
 
+use crate::annotate::config_annotations;
+use crate::audit::{
+    AuditEntry, AuditFilter, AuthMethod, append_audit, audit_path_from_env, load_audit,
+    render_audit,
+};
+use crate::backup::{DEFAULT_CONFIG_BACKUPS, backup_config, read_latest, restore_latest};
+use crate::bundle::{build_bundle, collect_bundle};
+use crate::cert::{
+    CertWatcher, acme_data_dirs, format_utc, prepare_data_dir, render_status, scan_certificates,
+    unix_now,
+};
+use crate::check::{TempConfig, precheck, precheck_file, sing_box_check_json};
+use crate::compose::{
+    DEFAULT_COMPOSE_IMAGE, container_env, port_mappings, render_compose, uses_acme,
+};
+use crate::compress::compress_body;
 use crate::config::{
-    build_from_env, generate_client_config_json, generate_config_json, print_details,
+    CLIENT_RESERVED_TAGS, ClientOutput, acme_data_dir_from_env, build_from_env,
+    build_node_from_env, client_output_from_env, config_path_from_env, domain_provider_from_env,
+    generate_client_config_json, generate_client_config_variants, generate_config_json,
+    generate_fleet_client_config_json, generate_imported_client_config_json,
+    generate_peer_outbounds_json, generate_urltest_config_json, generate_user_client_config_json,
+    health_options_from_env, pick_client_protocol, print_details, protocol_users,
+    rule_set_mirror_from_env, share_links, state_options_from_env, stats_options_from_env,
+    stats_path_from_env, upgrade_options_from_env, urltest_options_from_env, users_from_env,
+};
+use crate::deploy::{
+    DeployOptions, DeployReport, deploy_steps, render_report as render_deploy_report, run_step,
 };
-use crate::env::{env_bool, env_string};
-use crate::sharelink::sing_box_import_remote_profile_uri;
-use crate::utils::{ensure_parent_dir, pick_sing_box_bin};
+use crate::diff::{json_diff, render_diff};
+use crate::env::{env_bool, env_snapshot, env_string, env_u16};
+use crate::error::EzError;
+use crate::firewall::{FirewallFormat, firewall_rules, render_rules};
+use crate::fleet::{fleet_dir_from_env, fleet_from_env};
+use crate::health::{
+    DEFAULT_HEALTH_TIMEOUT, ProbeTransport, probe, probe_external, probe_targets, render_report,
+};
+use crate::i18n::{Msg, t, tf};
+use crate::install::{DEFAULT_INSTALL_PREFIX, INSTALL_HINT, install_singbox};
+use crate::links::{LinkFormat, render_links};
+use crate::listen::{ListenAddr, bind_all, first_tcp_port, listen_addrs_from_env, merge_incoming};
+use crate::notify::{Event, Notifier, generate_message, notify_event};
+use crate::preflight::acme_preflight;
+use crate::proxy::{ProxyTrust, path_prefix_from_env, strip_path_prefix};
+use crate::ratelimit::{Rejection, access_guard_from_env};
+use crate::readme::{Subscription, render_readme, render_serve_banner};
+use crate::redact::ConsoleRedactor;
+use crate::signedlink::{LinkSigner, SIGNED_PATH_PREFIX};
+use crate::state::{load_rotated_state, load_state, save_state};
+use crate::stats::{TrafficPoller, load_stats, render_stats, spawn_metrics_server};
+use crate::subauth::{SubscriptionAuth, Unauthorized, generate_token};
+use crate::summary::{OutputFormat, WrittenFile, generate_summary, output_format_from_env};
+use crate::template::{render_template, template_context};
+use crate::upgrade::UpgradeChecker;
+use crate::urltest::{LatencyResult, free_port, render_latency, url_test, wait_for_port};
+use crate::users::{UserSpec, parse_expiry, validate_user_name};
+use crate::utils::{ensure_parent_dir, pick_sing_box_bin, temp_sibling, write_synced};
+use ezsingbox::autoconfig::{MultiProtocolResult, Protocol};
+use ezsingbox::convert::ConvertSource;
+use ezsingbox::convert::clash::convert_clash;
+use ezsingbox::convert::xray::convert_xray;
+use ezsingbox::sharelink::sing_box_import_remote_profile_uri;
+use ezsingbox::singboxconfig::jsonc::{from_jsonc_str, to_jsonc_string};
+use ezsingbox::singboxconfig::lint::{RULE_DEPRECATED, lint_config};
+use ezsingbox::singboxconfig::migrate::migrate_config;
+use ezsingbox::singboxconfig::types::Duration;
+use ezsingbox::singboxconfig::validate::{Severity, has_errors, validate_config};
+use tracing::{error, info, info_span, warn};
+
+/// 更新动态域名解析记录的超时
+const DDNS_UPDATE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// 对生成的配置执行结构校验并打印诊断信息
+/// 存在错误级别的诊断时返回错误
+fn report_diagnostics(label: &str, json: &str) -> Result<(), EzError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| EzError::Build(e.to_string()))?;
+    let diagnostics = validate_config(&value);
+    for d in &diagnostics {
+        if d.is_error() {
+            error!(label, path = %d.path, "{}", d.message);
+        } else {
+            warn!(label, path = %d.path, "{}", d.message);
+        }
+    }
+    if has_errors(&diagnostics) {
+        return Err(EzError::Build(format!("{} 配置未通过结构校验", label)));
+    }
+    Ok(())
+}
+
+/// 由域名提供方生成的域名：泛解析域名直接返回，动态域名先将解析记录更新为公网 IP
+/// 返回已知解析到公网 IP 的域名（指定了 EZ_DOMAIN、自定义模式或试运行时为 None）
+fn resolve_provider_domain(result: &MultiProtocolResult) -> Result<Option<String>, EzError> {
+    let provider = domain_provider_from_env().map_err(EzError::Env)?;
+    if result.domain != provider.domain_for(&result.public_ip) {
+        return Ok(None);
+    }
+    if provider.is_wildcard() {
+        return Ok(Some(result.domain.clone()));
+    }
+    if !provider.is_dynamic() {
+        return Ok(None);
+    }
+    if env_bool("EZ_DRY_RUN", false) {
+        info!(domain = %result.domain, "试运行，未更新动态域名");
+        return Ok(None);
+    }
+    provider
+        .update_record(&result.public_ip, DDNS_UPDATE_TIMEOUT)
+        .map_err(|e| EzError::Build(e.to_string()))?;
+    info!(domain = %result.domain, ip = %result.public_ip, "动态域名解析记录已更新");
+    Ok(Some(result.domain.clone()))
+}
+
+/// ACME 预检：域名解析到公网 IP、验证端口未被其他进程占用（EZ_ACME_PREFLIGHT=false 时跳过）
+fn run_acme_preflight(
+    json: &str,
+    public_ip: IpAddr,
+    trusted_domain: Option<&str>,
+) -> Result<(), EzError> {
+    if !env_bool("EZ_ACME_PREFLIGHT", true) {
+        return Ok(());
+    }
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| EzError::Build(e.to_string()))?;
+    let issues = acme_preflight(&value, public_ip, trusted_domain);
+    if issues.is_empty() {
+        return Ok(());
+    }
+    Err(EzError::Build(format!(
+        "ACME 预检未通过（设置 EZ_ACME_PREFLIGHT=false 跳过）:\n{}",
+        issues.join("\n")
+    )))
+}
+
+/// 创建配置中引用的 ACME 数据目录
+fn prepare_acme_dirs(json: &str) -> Result<(), EzError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| EzError::Build(e.to_string()))?;
+    for dir in acme_data_dirs(&value) {
+        prepare_data_dir(&dir).map_err(EzError::io(&dir))?;
+    }
+    Ok(())
+}
+
+/// 写入配置：临时文件通过 sing-box check（EZ_SINGBOX_CHECK）后才替换目标文件
+/// 服务端配置（label 为 server）替换前按 EZ_CONFIG_BACKUPS 备份旧文件；
+/// EZ_ANNOTATE_CONFIG 开启时写入带注释的 JSONC
+fn write_config(label: &str, path: &str, json: &str) -> Result<(), EzError> {
+    let annotated;
+    let data = if env_bool("EZ_ANNOTATE_CONFIG", false) {
+        annotated = annotate_config(label, json)?;
+        &annotated
+    } else {
+        json
+    };
+    write_checked(path, data.as_bytes(), |tmp| {
+        if env_bool("EZ_SINGBOX_CHECK", true) {
+            precheck_file(&pick_sing_box_bin(), label, tmp).map_err(EzError::Build)?;
+        }
+        if label == "server" {
+            let keep = env_u16("EZ_CONFIG_BACKUPS").map_or(DEFAULT_CONFIG_BACKUPS, usize::from);
+            if let Some(backup) =
+                backup_config(Path::new(path), keep, unix_now()).map_err(EzError::io(path))?
+            {
+                info!(backup = %backup.display(), "已备份旧配置");
+            }
+        }
+        Ok(())
+    })
+}
+
+/// 在配置各段前加上说明来源与凭证用途的注释（字段按规范化顺序输出）
+fn annotate_config(label: &str, json: &str) -> Result<String, EzError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| EzError::Build(e.to_string()))?;
+    Ok(to_jsonc_string(&value, &config_annotations(label, &value)))
+}
+
+/// 写入输出文件（自动创建父目录）
+fn write_output(path: &str, data: impl AsRef<[u8]>) -> Result<(), EzError> {
+    write_checked(path, data.as_ref(), |_| Ok(()))
+}
+
+/// 原子写入：先写入同目录临时文件，check 通过后重命名覆盖目标文件
+/// 任一步骤失败时删除临时文件，目标文件保持不变
+fn write_checked(
+    path: &str,
+    data: &[u8],
+    check: impl FnOnce(&str) -> Result<(), EzError>,
+) -> Result<(), EzError> {
+    ensure_parent_dir(path).map_err(EzError::io(path))?;
+    let tmp = temp_sibling(path);
+    let result = write_synced(&tmp, data, path)
+        .map_err(EzError::io(&tmp))
+        .and_then(|_| check(&tmp))
+        .and_then(|_| std::fs::rename(&tmp, path).map_err(EzError::io(path)));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// 比较已有配置文件与新生成的配置并输出差异（文件不存在时视为空配置，已有文件可带注释）
+fn print_config_diff(path: &str, json: &str) -> Result<(), EzError> {
+    let parse = |data: &str, what: &str| {
+        from_jsonc_str::<serde_json::Value>(data)
+            .map_err(|e| EzError::Build(format!("解析{}失败: {}", what, e)))
+    };
+    let old = match std::fs::read_to_string(path) {
+        Ok(data) => parse(&data, &format!("已有配置 {} ", path))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(e) => return Err(EzError::io(path)(e)),
+    };
+    let entries = json_diff(&old, &parse(json, "新配置")?);
+    if entries.is_empty() {
+        info!(%path, "配置无变化");
+    } else {
+        print!("{}", render_diff(path, "新生成的配置", &entries));
+    }
+    Ok(())
+}
 
 /// 生成配置命令
-pub fn cmd_generate() -> Result<(), String> {
+pub fn cmd_generate() -> Result<(), EzError> {
+    let _span = info_span!("generate").entered();
     let build_result = build_from_env()?;
     let result = &build_result.result;
     let config_path = &build_result.config_path;
     let print_config = build_result.print_config;
     let log_level = &build_result.log_level;
+    let notifier = Notifier::from_env().map_err(EzError::Env)?;
 
-    let json = generate_config_json(result, log_level)?;
+    let json = generate_config_json(result, log_level).map_err(EzError::Build)?;
+    report_diagnostics("server", &json)?;
+    let trusted_domain = resolve_provider_domain(result)?;
+    run_acme_preflight(&json, result.public_ip, trusted_domain.as_deref())?;
 
-    ensure_parent_dir(config_path).map_err(|e| e.to_string())?;
-    std::fs::write(config_path, &json).map_err(|e| e.to_string())?;
+    if env_bool("EZ_DIFF", false) {
+        print_config_diff(config_path, &json)?;
+    }
+    let output = output_format_from_env();
+    let redactor = ConsoleRedactor::from_env(&json);
+    if env_bool("EZ_DRY_RUN", false) {
+        info!(path = %config_path, "试运行，未写入配置");
+        if output == OutputFormat::Json {
+            print_summary(result, &[], true, &redactor)?;
+        }
+        return Ok(());
+    }
 
-    println!("✅ sing-box 配置已生成: {}", config_path);
-    println!("公网 IP: {}", result.public_ip);
-    println!("域名: {}", result.domain);
+    write_config("server", config_path, &json)?;
+    prepare_acme_dirs(&json)?;
+    let mut written = vec![WrittenFile::new("server", config_path.as_str())];
 
+    info!(
+        path = %config_path,
+        public_ip = %result.public_ip,
+        domain = %result.domain,
+        "sing-box 配置已生成"
+    );
     if let Some(ref anytls) = result.anytls {
-        println!(
-            "AnyTLS 端口: {} (tag={})",
-            anytls.info.port, anytls.inbound.tag
+        info!(
+            protocol = "anytls",
+            port = anytls.info.port,
+            tag = %anytls.inbound.tag,
+            ephemeral = anytls.info.ephemeral_port,
+            "入站已启用"
         );
     }
     if let Some(ref hy2) = result.hysteria2 {
-        println!(
-            "Hysteria2 端口: {} (tag={})",
-            hy2.info.port, hy2.inbound.tag
+        info!(
+            protocol = "hysteria2",
+            port = hy2.info.port,
+            tag = %hy2.inbound.tag,
+            ephemeral = hy2.info.ephemeral_port,
+            "入站已启用"
         );
     }
     if let Some(ref tuic) = result.tuic {
-        println!("TUIC 端口: {} (tag={})", tuic.info.port, tuic.inbound.tag);
+        info!(
+            protocol = "tuic",
+            port = tuic.info.port,
+            tag = %tuic.inbound.tag,
+            ephemeral = tuic.info.ephemeral_port,
+            "入站已启用"
+        );
     }
     if let Some(ref vless) = result.vless_reality {
-        println!(
-            "VLESS-Reality 端口: {} (tag={})",
-            vless.info.port, vless.inbound.tag
+        info!(
+            protocol = "vless-reality",
+            port = vless.info.port,
+            tag = %vless.inbound.tag,
+            ephemeral = vless.info.ephemeral_port,
+            "入站已启用"
+        );
+    }
+    if let Some(ref vless) = result.vless_ws {
+        info!(
+            protocol = "vless-ws",
+            port = vless.info.port,
+            tag = %vless.inbound.tag,
+            path = %vless.path,
+            ephemeral = vless.info.ephemeral_port,
+            "入站已启用"
         );
     }
+    if let Some(ref vless) = result.vless_grpc {
+        info!(
+            protocol = "vless-grpc",
+            port = vless.info.port,
+            tag = %vless.inbound.tag,
+            service_name = %vless.path,
+            ephemeral = vless.info.ephemeral_port,
+            "入站已启用"
+        );
+    }
+    if env_bool("EZ_UDP_SELFTEST", true) {
+        let udp_ports = quic_ports(&json);
+        if !udp_ports.is_empty() {
+            info!(
+                ports = %udp_ports,
+                "Hysteria2 / TUIC 依赖 UDP，部分主机默认拦截 UDP：请确认防火墙与安全组已放行；\
+                 sing-box 运行后可执行 ezsingbox health --health-host {} 自检（run 模式启动后自动自检）",
+                result.public_ip
+            );
+        }
+    }
 
-    if print_config {
-        println!("\n{}", json);
+    // JSON 输出时标准输出只包含摘要文档
+    if print_config && output == OutputFormat::Text {
+        println!("\n{}", redactor.text(&json));
     }
 
-    if env_bool("EZ_PRINT_DETAILS", true) {
-        print_details(result);
+    if env_bool("EZ_PRINT_DETAILS", true) && output == OutputFormat::Text {
+        print_details(result, &redactor);
     }
 
-    if let Some(client_path) = env_string("EZ_CLIENT_CONFIG_PATH") {
-        let (client_json, _name) = generate_client_config_json(result, log_level)?;
-        ensure_parent_dir(&client_path).map_err(|e| e.to_string())?;
-        std::fs::write(&client_path, &client_json).map_err(|e| e.to_string())?;
-        println!("✅ client配置已生成: {}", client_path);
+    match client_output_from_env() {
+        Some(ClientOutput::Single(client_path)) => {
+            let (client_json, _name) =
+                generate_client_config_json(result, log_level).map_err(EzError::Build)?;
+            report_diagnostics("client", &client_json)?;
+            write_config("client", &client_path, &client_json)?;
+            info!(path = %client_path, "客户端配置已生成");
+            written.push(WrittenFile::new("client", client_path));
+        }
+        Some(ClientOutput::Pattern(pattern)) => {
+            let variants = generate_client_config_variants(result, log_level, &pattern)
+                .map_err(EzError::Build)?;
+            for (client_path, client_json) in &variants {
+                report_diagnostics("client", client_json)?;
+                write_config("client", client_path, client_json)?;
+                info!(path = %client_path, "客户端配置已生成");
+                written.push(WrittenFile::new("client", client_path.as_str()));
+            }
+        }
+        None => {}
     }
 
+    if output == OutputFormat::Json {
+        print_summary(result, &written, false, &redactor)?;
+    }
+
+    let public_url = env_string("EZ_SUBSCRIBE_PUBLIC_URL");
+    let name = env_string("EZ_SUBSCRIBE_NAME").unwrap_or_else(|| "ezsingbox".to_string());
+    let subscription = public_url.as_deref().map(|url| (url, name.as_str()));
+    let files: Vec<_> = written
+        .iter()
+        .map(|f| serde_json::json!({ "kind": f.kind, "path": f.path }))
+        .collect();
+    notifier.emit(
+        Event::ConfigGenerated,
+        Some(&generate_message(
+            result,
+            subscription,
+            &share_links(result),
+        )),
+        serde_json::json!({
+            "public_ip": result.public_ip,
+            "domain": result.domain,
+            "files": files,
+        }),
+    );
+
+    Ok(())
+}
+
+/// 向标准输出写入 generate 结果摘要（EZ_OUTPUT=json）
+fn print_summary(
+    result: &MultiProtocolResult,
+    files: &[WrittenFile],
+    dry_run: bool,
+    redactor: &ConsoleRedactor,
+) -> Result<(), EzError> {
+    let summary = generate_summary(result, files, dry_run).map_err(EzError::Build)?;
+    let text = serde_json::to_string_pretty(&summary).map_err(|e| EzError::Build(e.to_string()))?;
+    println!("{}", redactor.text(&text));
     Ok(())
 }
 
 /// 运行 sing-box 命令
-pub fn cmd_run() -> Result<ExitCode, String> {
+/// 启用 EZ_AUTO_UPGRADE_CHECK 时定期检查 sing-box 新版本，自动升级后平滑重启；
+/// 凭证轮换到期（EZ_ROTATE_INTERVAL）或有用户过期时重写配置并通知 sing-box 重新加载
+pub fn cmd_run() -> Result<ExitCode, EzError> {
+    let upgrade = upgrade_options_from_env().map_err(EzError::Env)?;
+    let stats = stats_options_from_env().map_err(EzError::Env)?;
+    let notifier = Notifier::from_env().map_err(EzError::Env)?;
     let build_result = build_from_env()?;
     let result = &build_result.result;
     let config_path = &build_result.config_path;
     let print_config = build_result.print_config;
     let log_level = &build_result.log_level;
 
-    let json = generate_config_json(result, log_level)?;
+    let json = generate_config_json(result, log_level).map_err(EzError::Build)?;
+    report_diagnostics("server", &json)?;
+    let trusted_domain = resolve_provider_domain(result)?;
+    run_acme_preflight(&json, result.public_ip, trusted_domain.as_deref())?;
 
-    ensure_parent_dir(config_path).map_err(|e| e.to_string())?;
-    std::fs::write(config_path, &json).map_err(|e| e.to_string())?;
+    write_config("server", config_path, &json)?;
+    prepare_acme_dirs(&json)?;
+    notifier.emit(
+        Event::ConfigGenerated,
+        None,
+        serde_json::json!({
+            "public_ip": result.public_ip,
+            "domain": result.domain,
+            "files": [{ "kind": "server", "path": config_path }],
+        }),
+    );
 
+    let redactor = ConsoleRedactor::from_env(&json);
     if print_config {
-        println!("\n{}", json);
+        println!("\n{}", redactor.text(&json));
     }
 
     if env_bool("EZ_PRINT_DETAILS", true) {
-        print_details(result);
+        print_details(result, &redactor);
     }
 
     let sing_box = pick_sing_box_bin();
-    let status = Command::new(&sing_box)
-        .arg("run")
-        .arg("-c")
-        .arg(config_path)
-        .status()
-        .map_err(|e| format!("启动 sing-box 失败({}): {}", sing_box, e))?;
+    let _span = info_span!("sing-box", bin = %sing_box).entered();
+    let child = spawn_sing_box(&sing_box, config_path)?;
+    if env_bool("EZ_UDP_SELFTEST", true) && !quic_ports(&json).is_empty() {
+        spawn_udp_self_test(&json, result.public_ip);
+    }
+    let poller = match stats {
+        Some(opts) => {
+            if let Some(listen) = opts.metrics {
+                spawn_metrics_server(listen, opts.path.clone()).map_err(EzError::Build)?;
+            }
+            Some(TrafficPoller::new(opts))
+        }
+        None => None,
+    };
+    // 只有配置了 Webhook 时才需要检测证书续期
+    let acme_dirs = serde_json::from_str(&json)
+        .map(|config| acme_data_dirs(&config))
+        .unwrap_or_default();
+    let certs = (notifier.webhook.is_some() && !acme_dirs.is_empty())
+        .then(|| CertWatcher::new(&acme_dirs, unix_now()));
+    let tasks = RunTasks {
+        checker: upgrade.map(UpgradeChecker::new),
+        poller,
+        regenerate_at: build_result.regenerate_at,
+        certs,
+    };
+    let status = wait_sing_box(child, sing_box, config_path, tasks, &notifier)?;
 
     let code: u8 = status
         .code()
         .and_then(|c| u8::try_from(c).ok())
         .unwrap_or(1);
+    if status.success() {
+        info!(code, "sing-box 已退出");
+    } else {
+        warn!(code, %status, "sing-box 异常退出");
+        notifier.emit(
+            Event::SingBoxExited,
+            Some(&format!(
+                "⚠️ {} ({}) 上的 sing-box 异常退出: {}",
+                result.domain, result.public_ip, status
+            )),
+            serde_json::json!({ "code": status.code(), "status": status.to_string() }),
+        );
+    }
     Ok(ExitCode::from(code))
 }
 
+/// 启动 sing-box 实例
+fn spawn_sing_box(sing_box: &str, config_path: &str) -> Result<std::process::Child, EzError> {
+    let child = Command::new(sing_box)
+        .arg("run")
+        .arg("-c")
+        .arg(config_path)
+        .spawn()
+        .map_err(|source| {
+            if source.kind() == std::io::ErrorKind::NotFound {
+                warn!("未找到 sing-box，{}", INSTALL_HINT);
+            }
+            EzError::SingBoxSpawn {
+                bin: sing_box.to_string(),
+                source,
+            }
+        })?;
+    info!(pid = child.id(), bin = %sing_box, config = %config_path, "sing-box 已启动");
+    Ok(child)
+}
+
+/// 配置中 QUIC 入站（Hysteria2 / TUIC）的端口列表（逗号分隔）
+fn quic_ports(json: &str) -> String {
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(json) else {
+        return String::new();
+    };
+    probe_targets(&config)
+        .iter()
+        .filter(|t| t.transport == ProbeTransport::Quic)
+        .map(|t| t.port.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 在后台线程执行 UDP 可达性自检（等待 sing-box 开始监听后探测）
+/// 设置 EZ_UDP_PROBE_URL 时由外部探测服务从外网探测；否则经公网 IP 从本机探测，无法发现云服务商安全组的拦截
+fn spawn_udp_self_test(json: &str, public_ip: IpAddr) {
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(json) else {
+        return;
+    };
+    let helper = env_string("EZ_UDP_PROBE_URL");
+    let timeout = health_options_from_env()
+        .map(|(_, timeout)| timeout)
+        .unwrap_or(DEFAULT_HEALTH_TIMEOUT);
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_secs(2));
+        let targets = probe_targets(&config);
+        for target in targets
+            .iter()
+            .filter(|t| t.transport == ProbeTransport::Quic)
+        {
+            let external = helper.as_deref().and_then(|template| {
+                probe_external(template, public_ip, target.port, timeout)
+                    .inspect_err(|e| warn!("外部探测服务不可用，改为本机探测: {}", e))
+                    .ok()
+            });
+            let (protocol, port) = (&target.protocol, target.port);
+            match external {
+                Some((true, detail)) => {
+                    info!(protocol = %protocol, port, detail = %detail, "UDP 端口从外网可达")
+                }
+                Some((false, detail)) => warn!(
+                    protocol = %protocol,
+                    port,
+                    detail = %detail,
+                    "UDP 端口从外网不可达，客户端将无法连接：请检查防火墙 / 云服务商安全组是否放行 UDP {}，或改用 AnyTLS / VLESS 等 TCP 协议",
+                    port
+                ),
+                None => match probe(target, public_ip, timeout).result {
+                    Ok(detail) => info!(
+                        protocol = %protocol,
+                        port,
+                        detail = %detail,
+                        "UDP 端口经公网 IP 从本机可达；外网可达性未验证，请确认安全组放行 UDP {}（或设置 EZ_UDP_PROBE_URL）",
+                        port
+                    ),
+                    Err(e) => warn!(
+                        protocol = %protocol,
+                        port,
+                        "UDP 端口经公网 IP {} 探测失败（{}），Hysteria2 / TUIC 可能无法连接：请检查 sing-box 日志与本机防火墙",
+                        public_ip,
+                        e
+                    ),
+                },
+            }
+        }
+    });
+}
+
+/// run 模式运行期间的定时任务
+struct RunTasks {
+    /// sing-box 升级检查
+    checker: Option<UpgradeChecker>,
+    /// 流量统计
+    poller: Option<TrafficPoller>,
+    /// 下次重新生成配置的时间（Unix 时间戳）
+    regenerate_at: Option<i64>,
+    /// 证书续期检测
+    certs: Option<CertWatcher>,
+}
+
+impl RunTasks {
+    /// 是否没有任何定时任务（此时直接阻塞等待 sing-box 退出）
+    fn is_empty(&self) -> bool {
+        self.checker.is_none()
+            && self.poller.is_none()
+            && self.regenerate_at.is_none()
+            && self.certs.is_none()
+    }
+}
+
+/// 等待 sing-box 退出
+/// 有定时任务时每秒轮询一次，升级后的新版本通过配置检查才替换运行中的实例
+fn wait_sing_box(
+    mut child: std::process::Child,
+    mut sing_box: String,
+    config_path: &str,
+    mut tasks: RunTasks,
+    notifier: &Notifier,
+) -> Result<std::process::ExitStatus, EzError> {
+    loop {
+        let wait_error = |source| EzError::SingBoxSpawn {
+            bin: sing_box.clone(),
+            source,
+        };
+        if tasks.is_empty() {
+            return child.wait().map_err(wait_error);
+        }
+        if let Some(status) = child.try_wait().map_err(wait_error)? {
+            return Ok(status);
+        }
+        if tasks.regenerate_at.is_some_and(|at| unix_now() >= at) {
+            tasks.regenerate_at = match regenerate_server_config() {
+                Ok(next) => {
+                    let _ = Command::new("kill")
+                        .args(["-HUP", &child.id().to_string()])
+                        .status();
+                    info!("已通知 sing-box 重新加载配置");
+                    notifier.emit(
+                        Event::SingBoxRestarted,
+                        None,
+                        serde_json::json!({ "reason": "reload", "bin": sing_box }),
+                    );
+                    next
+                }
+                Err(e) => {
+                    warn!("重新生成配置失败，1 小时后重试: {}", e);
+                    Some(unix_now() + 60 * 60)
+                }
+            };
+        }
+        if let Some(poller) = tasks.poller.as_mut() {
+            poller.poll();
+        }
+        if let Some(certs) = tasks.certs.as_mut() {
+            for cert in certs.poll(unix_now()) {
+                info!(domain = %cert.domain, not_after = %format_utc(cert.not_after), "证书已续期");
+                notifier.emit(
+                    Event::CertificateRenewed,
+                    None,
+                    serde_json::json!({
+                        "domain": cert.domain,
+                        "issuer": cert.issuer,
+                        "not_after": cert.not_after,
+                    }),
+                );
+            }
+        }
+        if let Some(upgraded) = tasks.checker.as_mut().and_then(|c| c.poll(&sing_box)) {
+            match precheck_file(&upgraded, "server", config_path) {
+                Ok(()) => {
+                    stop_sing_box(&mut child);
+                    sing_box = upgraded;
+                    child = spawn_sing_box(&sing_box, config_path)?;
+                    notifier.emit(
+                        Event::SingBoxRestarted,
+                        Some(&format!("🔄 sing-box 已升级并重启: {}", sing_box)),
+                        serde_json::json!({ "reason": "upgrade", "bin": sing_box }),
+                    );
+                }
+                Err(e) => warn!("新版本 sing-box 未通过配置检查，继续运行旧版本: {}", e),
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+
+/// 重新生成服务端配置（轮换到期的凭证、移除已过期的用户），返回下次需要重新生成的时间
+fn regenerate_server_config() -> Result<Option<i64>, EzError> {
+    let build_result = build_from_env()?;
+    let json = generate_config_json(&build_result.result, &build_result.log_level)
+        .map_err(EzError::Build)?;
+    report_diagnostics("server", &json)?;
+    // 公网 IP 可能已变化，动态域名更新失败不影响重新生成
+    if let Err(e) = resolve_provider_domain(&build_result.result) {
+        warn!("{}", e);
+    }
+    write_config("server", &build_result.config_path, &json)?;
+    Ok(build_result.regenerate_at)
+}
+
+/// 平滑停止 sing-box：先发送 SIGTERM，超时后强制结束
+fn stop_sing_box(child: &mut std::process::Child) {
+    let pid = child.id().to_string();
+    let _ = Command::new("kill").args(["-TERM", &pid]).status();
+    let deadline = Instant::now() + std::time::Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if let Ok(Some(_)) = child.try_wait() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    warn!(pid = %pid, "sing-box 未在 10 秒内退出，强制结束");
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
 /// 订阅服务命令
-pub fn cmd_serve() -> Result<ExitCode, String> {
+pub fn cmd_serve() -> Result<ExitCode, EzError> {
     let build_result = build_from_env()?;
     let result = &build_result.result;
     let log_level = &build_result.log_level;
 
-    let listen = env_string("EZ_SUBSCRIBE_LISTEN").unwrap_or_else(|| "0.0.0.0:8080".to_string());
-    let listen_addr: SocketAddr = listen
-        .parse()
-        .map_err(|_| format!("EZ_SUBSCRIBE_LISTEN 无效: {}", listen))?;
+    let listen_addrs = listen_addrs_from_env().map_err(EzError::Env)?;
 
     let path = env_string("EZ_SUBSCRIBE_PATH").unwrap_or_else(|| "/config.json".to_string());
     let path = if path.starts_with('/') {
@@ -123,24 +728,48 @@ pub fn cmd_serve() -> Result<ExitCode, String> {
     } else {
         format!("/{}", path)
     };
+    let prefix = path_prefix_from_env();
+    let trust = ProxyTrust::from_env().map_err(EzError::Env)?;
 
-    let (client_json, profile_name) = generate_client_config_json(result, log_level)?;
+    let (client_json, profile_name) =
+        generate_client_config_json(result, log_level).map_err(EzError::Build)?;
+    if env_bool("EZ_SINGBOX_CHECK", true) {
+        precheck(&pick_sing_box_bin(), "client", &client_json).map_err(EzError::Build)?;
+    }
 
-    let public_url = env_string("EZ_SUBSCRIBE_PUBLIC_URL")
-        .unwrap_or_else(|| format!("http://{}:{}{}", result.public_ip, listen_addr.port(), path));
+    let public_url = match env_string("EZ_SUBSCRIBE_PUBLIC_URL") {
+        Some(url) => url,
+        None => {
+            let port = first_tcp_port(&listen_addrs).ok_or_else(|| {
+                EzError::Env("只监听 Unix 套接字时需要设置 EZ_SUBSCRIBE_PUBLIC_URL".to_string())
+            })?;
+            format!("http://{}:{}{}{}", result.public_ip, port, prefix, path)
+        }
+    };
     let import_name = env_string("EZ_SUBSCRIBE_NAME").unwrap_or(profile_name);
 
-    println!("✅ 订阅服务已启动");
-    println!("监听: {}", listen_addr);
-    println!("路径: {}", path);
+    let user = env_string("EZ_CLIENT_USER");
+    let auth_user = env_string("EZ_SUBSCRIBE_BASIC_USER");
+    let auth_pass = env_string("EZ_SUBSCRIBE_BASIC_PASS");
+
+    let servers = bind_all(&listen_addrs).map_err(EzError::Serve)?;
+    for (addr, _) in &servers {
+        info!(listen = %addr, %path, %prefix, "订阅服务已启动");
+    }
     println!("订阅链接: {}", public_url);
     println!(
         "URI 链接: {}",
         sing_box_import_remote_profile_uri(&public_url, &import_name)
     );
-
-    let auth_user = env_string("EZ_SUBSCRIBE_BASIC_USER");
-    let auth_pass = env_string("EZ_SUBSCRIBE_BASIC_PASS");
+    let subscription = Subscription {
+        name: &import_name,
+        url: &public_url,
+    };
+    let basic_auth = auth_user.as_deref().zip(auth_pass.as_deref());
+    println!(
+        "\n{}",
+        render_serve_banner(&subscription, basic_auth, &share_links(result))
+    );
     let expected_auth = match (auth_user.as_deref(), auth_pass.as_deref()) {
         (Some(u), Some(p)) => {
             let token = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", u, p));
@@ -149,61 +778,1182 @@ pub fn cmd_serve() -> Result<ExitCode, String> {
         _ => None,
     };
 
-    let server = tiny_http::Server::http(listen_addr)
-        .map_err(|e| format!("启动订阅 HTTP 服务失败: {}", e))?;
-    for req in server.incoming_requests() {
-        if req.method() != &Method::Get && req.method() != &Method::Head {
-            let _ = req.respond(Response::empty(StatusCode(405)));
-            continue;
+    let mut guard = access_guard_from_env().map_err(EzError::Env)?;
+    let signer = LinkSigner::from_env();
+    if signer.is_some() {
+        info!(prefix = SIGNED_PATH_PREFIX, "已启用签名订阅链接");
+    }
+    let state_opts = state_options_from_env().map_err(EzError::Env)?;
+    let tokens = load_state(&state_opts.state_path, state_opts.encryption_key.as_ref())
+        .map_err(EzError::io(&state_opts.state_path))?
+        .map(|s| s.subscribe_tokens)
+        .unwrap_or_default();
+    if !tokens.is_empty() {
+        info!(users = tokens.len(), "已启用订阅令牌认证");
+    }
+    let auth = SubscriptionAuth {
+        basic: expected_auth.as_deref(),
+        tokens: &tokens,
+        signer: signer.as_ref(),
+    };
+
+    let audit_path = audit_path_from_env();
+    info!(path = %audit_path, "订阅请求记录到审计日志");
+
+    let route = SubscriptionRoute {
+        prefix: &prefix,
+        path: &path,
+    };
+    // 已设置 EZ_SUBSCRIBE_PUBLIC_URL 时不再提示代理推断出的外部地址
+    let mut external_reported = env_string("EZ_SUBSCRIBE_PUBLIC_URL").is_some();
+    let incoming = merge_incoming(servers.into_iter().map(|(_, server)| server).collect());
+    for req in incoming {
+        let started = Instant::now();
+        let peer = req.remote_addr().map(|a| a.ip());
+        let client_ip = trust.client_ip(
+            peer,
+            header_value(&req, "X-Forwarded-For"),
+            header_value(&req, "X-Real-IP"),
+        );
+        let remote = client_ip.map_or_else(|| "-".to_string(), |ip| ip.to_string());
+        let _span =
+            info_span!("request", method = %req.method(), url = %req.url(), %remote).entered();
+        let mut audit = AuditEntry {
+            at: unix_now(),
+            ip: client_ip.map(|ip| ip.to_string()),
+            user_agent: header_value(&req, "User-Agent").map(str::to_string),
+            format: query_param(req.url(), "format")
+                .unwrap_or("sing-box")
+                .to_string(),
+            ..Default::default()
+        };
+        if !external_reported
+            && trust.trusts(peer)
+            && let Some(host) =
+                header_value(&req, "X-Forwarded-Host").or_else(|| header_value(&req, "Host"))
+        {
+            let scheme = trust.scheme(peer, header_value(&req, "X-Forwarded-Proto"));
+            let external = format!("{}://{}{}{}", scheme, host, prefix, path);
+            if external != public_url {
+                warn!(
+                    %external,
+                    "经反向代理访问的外部地址与订阅链接不同，建议设置 EZ_SUBSCRIBE_PUBLIC_URL={}",
+                    external
+                );
+            }
+            external_reported = true;
         }
-        if req.url() != path {
-            let _ = req.respond(Response::empty(StatusCode(404)));
+        if let Err(rejection) = guard.check(client_ip, started) {
+            let status = rejection.status();
+            let mut resp = Response::empty(StatusCode(status));
+            if let Rejection::TooManyRequests(retry_after) = rejection {
+                resp.add_header(
+                    Header::from_bytes(&b"Retry-After"[..], retry_after.to_string().as_bytes())
+                        .unwrap(),
+                );
+            }
+            let _ = req.respond(resp);
+            warn!(status, "订阅请求已拒绝");
+            audit.status = status;
+            record_audit(&audit_path, &audit);
             continue;
         }
+        let status = respond_subscription(
+            req,
+            &route,
+            &auth,
+            &mut audit,
+            |signed| match signed {
+                Some(name) => generate_user_client_config_json(result, log_level, name),
+                None => Ok(client_json.clone()),
+            },
+            |format, requested| render_links(result, format, requested.or(user.as_deref())),
+            |requested| {
+                let name = requested.or(user.as_deref());
+                let now = unix_now();
+                build_result
+                    .user_expiry
+                    .iter()
+                    .any(|(n, t)| Some(n.as_str()) == name && now >= *t)
+            },
+        );
+        info!(
+            status,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "access"
+        );
+        audit.status = status;
+        record_audit(&audit_path, &audit);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 写入审计记录（失败时只记录警告，不影响订阅服务）
+fn record_audit(path: &str, entry: &AuditEntry) {
+    if let Err(e) = append_audit(path, entry) {
+        warn!(%path, error = %e, "写入审计日志失败");
+    }
+}
+
+/// 读取请求 URL 中的查询参数
+fn query_param<'a>(url: &'a str, key: &str) -> Option<&'a str> {
+    let query = url.split_once('?').map_or("", |(_, q)| q);
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
+/// 读取请求头
+fn header_value<'a>(req: &'a tiny_http::Request, name: &'static str) -> Option<&'a str> {
+    req.headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+/// 订阅服务的路由
+struct SubscriptionRoute<'a> {
+    /// 路由匹配前去除的路径前缀（EZ_SUBSCRIBE_PATH_PREFIX，为空时不去除）
+    prefix: &'a str,
+    /// 订阅路径
+    path: &'a str,
+}
 
-        if let Some(ref expected) = expected_auth {
-            let provided = req
-                .headers()
-                .iter()
-                .find(|h| h.field.equiv("Authorization"))
-                .map(|h| h.value.as_str());
-            if provided != Some(expected.as_str()) {
+/// 订阅请求的 `?format=` 参数（缺省或 sing-box 时为 None，即返回 sing-box 配置）
+fn query_format(url: &str) -> Result<Option<LinkFormat>, String> {
+    match query_param(url, "format") {
+        None | Some("sing-box" | "singbox" | "json") => Ok(None),
+        Some(v) => v.parse().map(Some),
+    }
+}
+
+/// 响应一次订阅请求，返回 HTTP 状态码
+/// `?format=` 指定代理列表格式时由 render_links 渲染（`?user=` 只包含该用户），否则返回 sing-box 配置；
+/// 请求的用户已过期（is_expired）时返回 410；认证失败时返回 401，`/sub/<用户>` 签名链接校验失败或过期时返回 403
+/// 客户端接受压缩时按 Accept-Encoding 以 gzip / deflate 返回，HEAD 请求只返回头部（含完整响应的 Content-Length）
+fn respond_subscription(
+    req: tiny_http::Request,
+    route: &SubscriptionRoute<'_>,
+    auth: &SubscriptionAuth<'_>,
+    audit: &mut AuditEntry,
+    render_config: impl Fn(Option<&str>) -> Result<String, String>,
+    render_links: impl Fn(LinkFormat, Option<&str>) -> Result<String, String>,
+    is_expired: impl Fn(Option<&str>) -> bool,
+) -> u16 {
+    if req.method() != &Method::Get && req.method() != &Method::Head {
+        let _ = req.respond(Response::empty(StatusCode(405)));
+        return 405;
+    }
+    let Some(url) = strip_path_prefix(route.prefix, req.url()) else {
+        let _ = req.respond(Response::empty(StatusCode(404)));
+        return 404;
+    };
+    let url = url.to_string();
+    let request_path = url.split('?').next().unwrap_or_default();
+
+    // 签名链接以签名代替其他认证；签名与令牌认证的请求固定为对应的用户
+    let auth_user = if request_path == route.path {
+        match auth.check(
+            header_value(&req, "Authorization"),
+            query_param(&url, "token"),
+        ) {
+            Ok(user) => {
+                audit.auth = match (&user, auth.required()) {
+                    (Some(_), _) => AuthMethod::Token,
+                    (None, true) => AuthMethod::Basic,
+                    (None, false) => AuthMethod::None,
+                };
+                user
+            }
+            Err(Unauthorized) => {
                 let mut resp = Response::empty(StatusCode(401));
-                let _ = resp.add_header(
-                    Header::from_bytes(&b"WWW-Authenticate"[..], &b"Basic realm=\"ezsingbox\""[..])
+                if auth.basic.is_some() {
+                    resp.add_header(
+                        Header::from_bytes(
+                            &b"WWW-Authenticate"[..],
+                            &b"Basic realm=\"ezsingbox\""[..],
+                        )
                         .unwrap(),
-                );
+                    );
+                }
+                if !auth.tokens.is_empty() {
+                    resp.add_header(
+                        Header::from_bytes(
+                            &b"WWW-Authenticate"[..],
+                            &b"Bearer realm=\"ezsingbox\""[..],
+                        )
+                        .unwrap(),
+                    );
+                }
                 let _ = req.respond(resp);
-                continue;
+                return 401;
+            }
+        }
+    } else {
+        match (request_path.strip_prefix(SIGNED_PATH_PREFIX), auth.signer) {
+            (Some(segment), Some(signer)) => match signer.verify(
+                segment,
+                query_param(&url, "exp"),
+                query_param(&url, "sig"),
+                unix_now(),
+            ) {
+                Ok(user) => {
+                    audit.auth = AuthMethod::Signed;
+                    Some(user)
+                }
+                Err(e) => {
+                    let _ = req.respond(Response::from_string(e.to_string()).with_status_code(403));
+                    return 403;
+                }
+            },
+            _ => {
+                let _ = req.respond(Response::empty(StatusCode(404)));
+                return 404;
             }
         }
+    };
 
-        let mut resp = Response::from_string(client_json.clone());
-        resp.add_header(
-            Header::from_bytes(
-                &b"Content-Type"[..],
-                &b"application/json; charset=utf-8"[..],
-            )
-            .unwrap(),
+    let user = auth_user.as_deref().or_else(|| query_param(&url, "user"));
+    audit.user = user.map(str::to_string);
+    if is_expired(user) {
+        let _ = req.respond(Response::from_string("该用户已过期").with_status_code(410));
+        return 410;
+    }
+
+    let (body, content_type) = match query_format(&url) {
+        Ok(None) => match render_config(auth_user.as_deref()) {
+            Ok(config) => (config, "application/json; charset=utf-8"),
+            Err(e) => {
+                warn!(error = %e, "渲染客户端配置失败");
+                let _ = req.respond(Response::from_string(e).with_status_code(404));
+                return 404;
+            }
+        },
+        Ok(Some(format)) => match render_links(format, user) {
+            Ok(links) => (links, "text/plain; charset=utf-8"),
+            Err(e) => {
+                warn!(%format, error = %e, "渲染代理列表失败");
+                let _ = req.respond(Response::from_string(e).with_status_code(404));
+                return 404;
+            }
+        },
+        Err(e) => {
+            let _ = req.respond(Response::from_string(e).with_status_code(400));
+            return 400;
+        }
+    };
+
+    let (body, encoding) = compress_body(body.into_bytes(), header_value(&req, "Accept-Encoding"));
+    let mut headers = vec![
+        Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()).unwrap(),
+        Header::from_bytes(&b"Vary"[..], &b"Accept-Encoding"[..]).unwrap(),
+    ];
+    if let Some(encoding) = encoding {
+        headers.push(
+            Header::from_bytes(&b"Content-Encoding"[..], encoding.as_str().as_bytes()).unwrap(),
         );
+    }
+    // 响应体已完整生成：始终发送 Content-Length 而不是分块传输，HEAD 请求只返回与 GET 相同的头部
+    if req.method() == &Method::Head {
+        let resp = Response::new(
+            StatusCode(200),
+            headers,
+            std::io::empty(),
+            Some(body.len()),
+            None,
+        )
+        .with_chunked_threshold(usize::MAX);
+        let _ = req.respond(resp);
+    } else {
+        let mut resp = Response::from_data(body).with_chunked_threshold(usize::MAX);
+        for header in headers {
+            resp.add_header(header);
+        }
         let _ = req.respond(resp);
     }
+    200
+}
 
-    Ok(ExitCode::SUCCESS)
+/// 配置检查命令
+/// 使用 sing-box 校验服务端与客户端配置
+pub fn cmd_check() -> Result<(), EzError> {
+    let build_result = build_from_env()?;
+    let result = &build_result.result;
+    let log_level = &build_result.log_level;
+    let sing_box = pick_sing_box_bin();
+
+    let server_json = generate_config_json(result, log_level).map_err(EzError::Build)?;
+    let (client_json, _name) =
+        generate_client_config_json(result, log_level).map_err(EzError::Build)?;
+
+    let mut errors = Vec::new();
+    for (label, json) in [("server", &server_json), ("client", &client_json)] {
+        if let Err(e) = report_diagnostics(label, json) {
+            errors.push(e.to_string());
+            continue;
+        }
+        match sing_box_check_json(&sing_box, label, json) {
+            Ok(()) => info!(label, "配置检查通过"),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(EzError::Build(errors.join("\n")))
+    }
+}
+
+/// 配置检查命令
+/// 检查任意 sing-box 配置（不限于本工具生成的），存在错误级别的结果时返回退出码 1；
+/// EZ_OUTPUT=json 时输出包含全部结果的 JSON 文档
+pub fn cmd_lint(path: &str) -> Result<ExitCode, EzError> {
+    let data = std::fs::read_to_string(path).map_err(EzError::io(path))?;
+    let config: serde_json::Value = from_jsonc_str(&data)
+        .map_err(|e| EzError::Build(format!("解析配置 {} 失败: {}", path, e)))?;
+    let findings = lint_config(&config);
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    match output_format_from_env() {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "path": path,
+                "errors": errors,
+                "warnings": warnings,
+                "findings": findings,
+            }))
+            .map_err(|e| EzError::Build(format!("序列化检查结果失败: {}", e)))?
+        ),
+        OutputFormat::Text => {
+            for finding in &findings {
+                println!("{}", finding);
+            }
+            println!("{}", tf(Msg::LintSummary, &[&path, &errors, &warnings]));
+            if findings.iter().any(|f| f.rule == RULE_DEPRECATED) {
+                println!("{}", tf(Msg::LintMigrateHint, &[&path]));
+            }
+        }
+    }
+    if errors > 0 {
+        Ok(ExitCode::from(1))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// 把配置中的弃用字段改写为新写法，结果输出到 EZ_EXPORT_PATH 或标准输出
+/// 新增的规则集使用 EZ_RULE_SET_MIRROR 作为下载地址前缀
+pub fn cmd_migrate(path: &str) -> Result<(), EzError> {
+    let data = std::fs::read_to_string(path).map_err(EzError::io(path))?;
+    let config: serde_json::Value = from_jsonc_str(&data)
+        .map_err(|e| EzError::Build(format!("解析配置 {} 失败: {}", path, e)))?;
+    let mirror = rule_set_mirror_from_env().map_err(EzError::Env)?;
+    let migration = migrate_config(&config, &mirror)
+        .map_err(|e| EzError::Build(format!("迁移配置 {} 失败: {}", path, e)))?;
+    let serialize = |value: &serde_json::Value| {
+        serde_json::to_string_pretty(value)
+            .map_err(|e| EzError::Build(format!("序列化配置失败: {}", e)))
+    };
+    let output = match output_format_from_env() {
+        OutputFormat::Json => serialize(&serde_json::json!({
+            "path": path,
+            "config": migration.config,
+            "changes": migration.changes,
+            "manual": migration.manual,
+        }))?,
+        OutputFormat::Text => {
+            for change in &migration.changes {
+                info!(path = %change.path, "{}", change.message);
+            }
+            for item in &migration.manual {
+                warn!(path = %item.path, "需要手动处理: {}", item.message);
+            }
+            serialize(&migration.config)?
+        }
+    };
+    if let Some(export) = env_string("EZ_EXPORT_PATH") {
+        write_output(&export, format!("{}\n", output))?;
+        info!(
+            path = %export,
+            changes = migration.changes.len(),
+            manual = migration.manual.len(),
+            "迁移后的配置已写入"
+        );
+    } else {
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+/// 把其他代理工具的配置转换为 sing-box 配置
+pub fn cmd_convert(from: ConvertSource, path: &str) -> Result<(), EzError> {
+    let data = std::fs::read_to_string(path).map_err(EzError::io(path))?;
+    let parse_error = |e: String| EzError::Build(format!("解析配置 {} 失败: {}", path, e));
+    let convert_error = |e: String| EzError::Build(format!("转换配置 {} 失败: {}", path, e));
+    let serialize = |value: &serde_json::Value| {
+        serde_json::to_string_pretty(value)
+            .map_err(|e| EzError::Build(format!("序列化配置失败: {}", e)))
+    };
+    // (配置, 输出文本, 转换的入站 / 节点数, 跳过清单)
+    let (config, rendered, converted, skipped) = match from {
+        ConvertSource::Xray => {
+            let source: serde_json::Value =
+                from_jsonc_str(&data).map_err(|e| parse_error(e.to_string()))?;
+            let conversion = convert_xray(&source).map_err(convert_error)?;
+            let config = serde_json::to_value(&conversion.config)
+                .map_err(|e| EzError::Build(format!("序列化配置失败: {}", e)))?;
+            let rendered = serialize(&config)?;
+            let converted = conversion.config.inbounds.len();
+            (config, rendered, converted, conversion.skipped)
+        }
+        ConvertSource::Clash => {
+            let source: serde_json::Value =
+                serde_yaml::from_str(&data).map_err(|e| parse_error(e.to_string()))?;
+            let conversion =
+                convert_clash(&source, &CLIENT_RESERVED_TAGS).map_err(convert_error)?;
+            let converted = conversion.outbounds.len();
+            let log_level = env_string("EZ_LOG_LEVEL").unwrap_or_else(|| "info".to_string());
+            let rendered = generate_imported_client_config_json(conversion.outbounds, &log_level)
+                .map_err(convert_error)?;
+            let config = serde_json::from_str(&rendered)
+                .map_err(|e| EzError::Build(format!("序列化配置失败: {}", e)))?;
+            (config, rendered, converted, conversion.skipped)
+        }
+    };
+    let output = match output_format_from_env() {
+        OutputFormat::Json => serialize(&serde_json::json!({
+            "path": path,
+            "from": from.to_string(),
+            "config": config,
+            "skipped": skipped,
+        }))?,
+        OutputFormat::Text => {
+            for item in &skipped {
+                warn!(path = %item.path, "未转换: {}", item.message);
+            }
+            rendered
+        }
+    };
+    if let Some(export) = env_string("EZ_EXPORT_PATH") {
+        write_output(&export, format!("{}\n", output))?;
+        info!(
+            path = %export,
+            converted,
+            skipped = skipped.len(),
+            "转换后的配置已写入"
+        );
+    } else {
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+/// 导出对等出站
+pub fn cmd_export_peer_outbound() -> Result<(), EzError> {
+    let build_result = build_from_env()?;
+    let json = generate_peer_outbounds_json(&build_result.result).map_err(EzError::Build)?;
+    if let Some(path) = env_string("EZ_EXPORT_PATH") {
+        write_output(&path, &json)?;
+        info!(%path, "对等出站已导出");
+    } else {
+        println!("{}", json);
+    }
+    Ok(())
+}
+
+/// 导出连接说明文档
+pub fn cmd_export_readme() -> Result<(), EzError> {
+    let build_result = build_from_env()?;
+    let markdown = render_readme(&build_result.result).map_err(EzError::Build)?;
+    if let Some(path) = env_string("EZ_EXPORT_PATH") {
+        write_output(&path, &markdown)?;
+        info!(%path, "连接说明已导出");
+    } else {
+        print!("{}", markdown);
+    }
+    Ok(())
+}
+
+/// 使用自定义模板渲染客户端配置
+/// 设置了 EZ_CLIENT_USER 时只向模板提供该用户
+pub fn cmd_export_template() -> Result<(), EzError> {
+    let template_path = env_string("EZ_CLIENT_TEMPLATE_PATH")
+        .ok_or_else(|| EzError::Env("未设置 EZ_CLIENT_TEMPLATE_PATH".to_string()))?;
+    let template = std::fs::read_to_string(&template_path).map_err(EzError::io(&template_path))?;
+    let build_result = build_from_env()?;
+    let user = env_string("EZ_CLIENT_USER");
+    let context =
+        template_context(&build_result.result, user.as_deref()).map_err(EzError::Build)?;
+    let output = render_template(&template, &context).map_err(EzError::Build)?;
+    if let Some(path) = env_string("EZ_EXPORT_PATH") {
+        write_output(&path, &output)?;
+        info!(%path, template = %template_path, "模板已渲染");
+    } else {
+        print!("{}", output);
+    }
+    Ok(())
+}
+
+/// 输出代理列表（分享链接或 Surge / Quantumult X / Loon 代理行）
+/// 设置了 EZ_CLIENT_USER 时只输出该用户；指定 expire 时改为输出各用户的签名订阅链接
+pub fn cmd_links(format: LinkFormat, expire: Option<&Duration>) -> Result<(), EzError> {
+    let build_result = build_from_env()?;
+    let user = env_string("EZ_CLIENT_USER");
+    let links = match expire {
+        Some(expire) => signed_links(&build_result.result, expire, user.as_deref())?,
+        None => {
+            render_links(&build_result.result, format, user.as_deref()).map_err(EzError::Build)?
+        }
+    };
+    if let Some(path) = env_string("EZ_EXPORT_PATH") {
+        write_output(&path, &links)?;
+        info!(%path, %format, "代理列表已导出");
+    } else {
+        print!("{}", links);
+    }
+    Ok(())
+}
+
+/// 为各用户生成签名订阅链接，每行 `用户名 链接`
+/// 链接前缀取 EZ_SUBSCRIBE_PUBLIC_URL 的站点部分，未设置时为 `http://<公网 IP>:<订阅端口>`，
+/// 设置了 EZ_SUBSCRIBE_PATH_PREFIX 时再加上该前缀
+fn signed_links(
+    result: &MultiProtocolResult,
+    expire: &Duration,
+    only_user: Option<&str>,
+) -> Result<String, EzError> {
+    let signer = LinkSigner::from_env().ok_or_else(|| {
+        EzError::Env("生成签名订阅链接需要设置 EZ_SUBSCRIBE_SIGNING_KEY".to_string())
+    })?;
+    let base = match env_string("EZ_SUBSCRIBE_PUBLIC_URL") {
+        Some(url) => format!("{}{}", site_origin(&url), path_prefix_from_env()),
+        None => {
+            let addrs = listen_addrs_from_env().map_err(EzError::Env)?;
+            let port = first_tcp_port(&addrs).ok_or_else(|| {
+                EzError::Env("只监听 Unix 套接字时需要设置 EZ_SUBSCRIBE_PUBLIC_URL".to_string())
+            })?;
+            format!(
+                "http://{}:{}{}",
+                result.public_ip,
+                port,
+                path_prefix_from_env()
+            )
+        }
+    };
+    let protocol = pick_client_protocol(result)
+        .ok_or_else(|| EzError::Build("没有可用协议用于生成订阅链接".to_string()))?;
+    let expires_at = unix_now() + expire.to_std().as_secs() as i64;
+    let mut out = String::new();
+    for user in protocol_users(result, protocol)
+        .iter()
+        .filter(|u| only_user.is_none_or(|name| u.name == name))
+    {
+        out.push_str(&format!(
+            "{} {}{}\n",
+            user.name,
+            base,
+            signer.signed_path(&user.name, expires_at)
+        ));
+    }
+    if out.is_empty() {
+        return Err(EzError::Build("没有可用用户用于生成订阅链接".to_string()));
+    }
+    info!(expires = %format_utc(expires_at), "签名订阅链接到期时间");
+    Ok(out)
+}
+
+/// URL 的站点部分（`scheme://host[:port]`）
+fn site_origin(url: &str) -> &str {
+    let start = url.find("://").map_or(0, |i| i + 3);
+    match url[start..].find('/') {
+        Some(i) => &url[..start + i],
+        None => url,
+    }
+}
+
+/// 按服务器列表批量生成配置
+/// 每台服务器的服务端配置写入各自路径，客户端配置包含全部节点并由 selector 选择
+pub fn cmd_fleet() -> Result<(), EzError> {
+    let _span = info_span!("fleet").entered();
+    let nodes = fleet_from_env().map_err(EzError::Env)?;
+    let dir = fleet_dir_from_env();
+    let log_level = env_string("EZ_LOG_LEVEL").unwrap_or_else(|| "info".to_string());
+    let dry_run = env_bool("EZ_DRY_RUN", false);
+
+    let mut results = Vec::with_capacity(nodes.len());
+    for node in &nodes {
+        let _node_span = info_span!("node", name = %node.name).entered();
+        let result = build_node_from_env(node.public_ip, node.domain.as_deref())?;
+        let json = generate_config_json(&result, &log_level).map_err(EzError::Build)?;
+        report_diagnostics("server", &json)?;
+        let path = node.config_path(&dir);
+        if dry_run {
+            info!(path = %path, "试运行，未写入配置");
+        } else {
+            write_config("server", &path, &json)?;
+            info!(
+                path = %path,
+                public_ip = %result.public_ip,
+                domain = %result.domain,
+                "服务端配置已生成"
+            );
+        }
+        results.push((node.name.clone(), result));
+    }
+
+    let client_json =
+        generate_fleet_client_config_json(&results, &log_level).map_err(EzError::Build)?;
+    report_diagnostics("client", &client_json)?;
+    let client_path = env_string("EZ_CLIENT_CONFIG_PATH").unwrap_or_else(|| {
+        Path::new(&dir)
+            .join("client.json")
+            .to_string_lossy()
+            .into_owned()
+    });
+    if dry_run {
+        info!(path = %client_path, "试运行，未写入配置");
+        return Ok(());
+    }
+    write_config("client", &client_path, &client_json)?;
+    info!(path = %client_path, nodes = results.len(), "客户端配置已生成");
+    Ok(())
+}
+
+/// 安装 sing-box 命令
+pub fn cmd_install_singbox(version: Option<&str>) -> Result<(), EzError> {
+    let _span = info_span!("install-singbox").entered();
+    let prefix =
+        env_string("EZ_INSTALL_PREFIX").unwrap_or_else(|| DEFAULT_INSTALL_PREFIX.to_string());
+    let (version, path) = install_singbox(version, &prefix).map_err(EzError::Build)?;
+    info!(version = %version, path = %path, "sing-box 已安装");
+    Ok(())
+}
+
+/// 远程部署命令
+/// 指定 --host 时部署 EZ_CONFIG_PATH 指向的配置；否则部署服务器列表中设置了 ssh 的节点（fleet 生成的配置）
+/// 任一主机失败时返回退出码 1
+pub fn cmd_deploy(hosts: &[String]) -> Result<ExitCode, EzError> {
+    let _span = info_span!("deploy").entered();
+    let opts = DeployOptions::from_env();
+    let targets: Vec<(String, String, String)> = if hosts.is_empty() {
+        let dir = fleet_dir_from_env();
+        let mut targets = Vec::new();
+        for node in fleet_from_env().map_err(EzError::Env)? {
+            match node.ssh {
+                Some(ref ssh) => {
+                    targets.push((node.name.clone(), ssh.clone(), node.config_path(&dir)))
+                }
+                None => warn!(name = %node.name, "节点未设置 ssh，跳过"),
+            }
+        }
+        targets
+    } else {
+        let path = config_path_from_env();
+        hosts
+            .iter()
+            .map(|host| (host.clone(), host.clone(), path.clone()))
+            .collect()
+    };
+    if targets.is_empty() {
+        return Err(EzError::Env(
+            "未指定部署主机（--host，或服务器列表中节点的 ssh）".to_string(),
+        ));
+    }
+    if let Some(ref bin) = opts.upload_bin {
+        std::fs::metadata(bin).map_err(EzError::io(bin))?;
+    }
+
+    let dry_run = env_bool("EZ_DRY_RUN", false);
+    let mut reports = Vec::with_capacity(targets.len());
+    for (name, host, local) in targets {
+        std::fs::metadata(&local).map_err(EzError::io(&local))?;
+        let steps = deploy_steps(&host, &local, &opts);
+        if dry_run {
+            println!("# {}", name);
+            for step in &steps {
+                println!("{}", step.display());
+            }
+            continue;
+        }
+        let _host_span = info_span!("host", name = %name, host = %host).entered();
+        let result = steps.iter().try_for_each(|step| {
+            info!(step = step.label, "执行");
+            run_step(step)
+        });
+        match result {
+            Ok(()) => info!("部署成功"),
+            Err(ref reason) => error!("{}", reason),
+        }
+        reports.push(DeployReport { name, host, result });
+    }
+    print!("{}", render_deploy_report(&reports));
+
+    if reports.iter().all(|r| r.result.is_ok()) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(1))
+    }
+}
+
+/// 生成 docker-compose.yaml
+/// 只发布已启用入站的端口，并写入当前生效的变量
+pub fn cmd_compose() -> Result<(), EzError> {
+    let build_result = build_from_env()?;
+    let json = generate_config_json(&build_result.result, &build_result.log_level)
+        .map_err(EzError::Build)?;
+    let config: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| EzError::Build(e.to_string()))?;
+
+    let acme = uses_acme(&config);
+    let acme_dir = acme.then(acme_data_dir_from_env);
+    let image = env_string("EZ_COMPOSE_IMAGE").unwrap_or_else(|| DEFAULT_COMPOSE_IMAGE.to_string());
+    let yaml = render_compose(
+        &image,
+        &port_mappings(&config),
+        &container_env(&env_snapshot(), acme),
+        acme_dir.as_deref(),
+    );
+    if let Some(path) = env_string("EZ_EXPORT_PATH") {
+        write_output(&path, &yaml)?;
+        info!(%path, "docker-compose.yaml 已导出");
+    } else {
+        print!("{}", yaml);
+    }
+    Ok(())
 }
 
-/// 打印帮助信息
-pub fn print_usage() {
-    eprintln!(
-        "用法: ezsingbox [generate|run|serve]\n\n\
-        环境变量(服务端生成): EZ_CONFIG_PATH, EZ_PUBLIC_IP, EZ_DOMAIN, EZ_ENABLE_ANYTLS, \
-        EZ_ENABLE_HYSTERIA2, EZ_ENABLE_TUIC, EZ_ANYTLS_PORT, EZ_HYSTERIA2_PORT, EZ_TUIC_PORT, \
-        EZ_USER, EZ_PASSWORD, EZ_HY2_OBFS, EZ_HY2_UP_MBPS, EZ_HY2_DOWN_MBPS, EZ_TUIC_CC, \
-        EZ_LOG_LEVEL, EZ_PRINT_CONFIG, EZ_PRINT_DETAILS\n\n\
-        环境变量(客户端导出): EZ_CLIENT_CONFIG_PATH, EZ_CLIENT_PROTOCOL, EZ_CLIENT_USER, \
-        EZ_CLIENT_MIXED_LISTEN, EZ_CLIENT_MIXED_PORT\n\n\
-        订阅/URI: EZ_REMOTE_PROFILE_URL, EZ_REMOTE_PROFILE_NAME\n\n\
-        HTTP订阅服务(serve): EZ_SUBSCRIBE_LISTEN, EZ_SUBSCRIBE_PATH, EZ_SUBSCRIBE_PUBLIC_URL, \
-        EZ_SUBSCRIBE_NAME, EZ_SUBSCRIBE_BASIC_USER, EZ_SUBSCRIBE_BASIC_PASS"
+/// 输出已启用入站与订阅服务的防火墙放行规则
+pub fn cmd_firewall(format: FirewallFormat) -> Result<(), EzError> {
+    let build_result = build_from_env()?;
+    let mut subscribe_ports = Vec::new();
+    if env_string("EZ_SUBSCRIBE_LISTEN").is_some() {
+        for port in listen_addrs_from_env()
+            .map_err(EzError::Env)?
+            .iter()
+            .filter_map(ListenAddr::port)
+        {
+            if !subscribe_ports.contains(&port) {
+                subscribe_ports.push(port);
+            }
+        }
+    }
+    let rules = firewall_rules(&build_result.result, &subscribe_ports);
+    print!("{}", render_rules(&rules, format));
+    Ok(())
+}
+
+/// 回滚命令
+/// 使用最新的备份恢复服务端配置，并通知运行中的 sing-box 重新加载
+pub fn cmd_rollback() -> Result<(), EzError> {
+    let path = config_path_from_env();
+    let (backup, json) = read_latest(Path::new(&path))
+        .map_err(EzError::io(&path))?
+        .ok_or_else(|| EzError::Build(format!("{} 没有可用的备份", path)))?;
+    let sing_box = pick_sing_box_bin();
+    if env_bool("EZ_SINGBOX_CHECK", true) {
+        precheck(&sing_box, "backup", &json).map_err(EzError::Build)?;
+    }
+    restore_latest(Path::new(&path)).map_err(EzError::io(&path))?;
+    info!(%path, backup = %backup.display(), "已恢复上一版本配置");
+
+    if env_bool("EZ_ROLLBACK_RELOAD", true) {
+        reload_sing_box(&sing_box);
+    }
+    Ok(())
+}
+
+/// 向运行中的 sing-box 发送 SIGHUP 使其重新加载配置
+fn reload_sing_box(sing_box: &str) {
+    let name = Path::new(sing_box).file_name().map_or_else(
+        || sing_box.to_string(),
+        |n| n.to_string_lossy().into_owned(),
     );
+    match Command::new("pkill").args(["-HUP", "-x", &name]).status() {
+        Ok(status) if status.success() => info!(process = %name, "已通知 sing-box 重新加载配置"),
+        Ok(_) => warn!(process = %name, "未找到运行中的 sing-box，请手动重启"),
+        Err(e) => warn!(error = %e, "无法执行 pkill，请手动重启 sing-box"),
+    }
+}
+
+/// 生成故障排查包
+pub fn cmd_support_bundle() -> Result<(), EzError> {
+    let path = env_string("EZ_BUNDLE_PATH").unwrap_or_else(|| {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        format!("./ezsingbox-support-{}.tar", ts)
+    });
+
+    let entries = collect_bundle();
+    let data = build_bundle(&entries).map_err(EzError::Build)?;
+    write_output(&path, data)?;
+
+    let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+    info!(%path, entries = %names.join(", "), "故障排查包已生成");
+    warn!("敏感信息已脱敏，提交前仍建议检查内容");
+    Ok(())
+}
+
+/// 查看 ACME 证书状态
+pub fn cmd_cert_status() -> Result<(), EzError> {
+    let dir = acme_data_dir_from_env();
+    let certs = scan_certificates(std::path::Path::new(&dir)).map_err(EzError::Build)?;
+    print!("{}", render_status(&dir, &certs, unix_now()));
+    Ok(())
+}
+
+/// 流量统计命令：输出 run 模式累计的各入站与各用户流量
+pub fn cmd_stats() -> Result<(), EzError> {
+    let path = stats_path_from_env();
+    let stats = load_stats(&path)
+        .map_err(EzError::io(&path))?
+        .ok_or_else(|| {
+            EzError::Env(format!(
+                "{} 不存在（run 模式设置 EZ_CLASH_API 后开始统计流量）",
+                path
+            ))
+        })?;
+    print!("{}", render_stats(&stats));
+    Ok(())
+}
+
+/// 审计日志命令
+/// 按用户与时间筛选订阅服务的访问记录；EZ_OUTPUT=json 时输出 JSON 数组
+pub fn cmd_audit(
+    user: Option<&str>,
+    since: Option<&Duration>,
+    limit: Option<usize>,
+) -> Result<(), EzError> {
+    let path = audit_path_from_env();
+    let entries = load_audit(&path).map_err(EzError::io(&path))?;
+    let filter = AuditFilter {
+        user: user.map(str::to_string),
+        since: since.map(|d| unix_now() - d.to_std().as_secs() as i64),
+        limit,
+    };
+    let matched = filter.apply(&entries);
+    match output_format_from_env() {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&matched)
+                .map_err(|e| EzError::Build(format!("序列化审计记录失败: {}", e)))?
+        ),
+        OutputFormat::Text => print!("{}", render_audit(&matched)),
+    }
+    Ok(())
+}
+
+/// 添加用户命令
+/// 用户写入状态文件（与 EZ_USER / EZ_USERS 合并），重新执行 generate / run 后生效
+pub fn cmd_user_add(name: &str, expires: Option<&str>) -> Result<(), EzError> {
+    validate_user_name(name).map_err(EzError::Env)?;
+    let expires_at = expires
+        .map(parse_expiry)
+        .transpose()
+        .map_err(EzError::Env)?;
+    let users = users_from_env().map_err(EzError::Env)?;
+    if users.iter().any(|u| u.name == name) {
+        return Err(EzError::Env(format!(
+            "用户 {} 已由 EZ_USER / EZ_USERS 配置",
+            name
+        )));
+    }
+    let opts = state_options_from_env().map_err(EzError::Env)?;
+    let spec = UserSpec {
+        name: name.to_string(),
+        password: None,
+        expires_at,
+    };
+    let state = load_rotated_state(&opts, users, Some(spec))?;
+    if let Some(user) = state.user(name) {
+        let expires_at = match user.expires_at {
+            Some(at) => format_utc(at),
+            None => t(Msg::NeverExpires).to_string(),
+        };
+        println!("{}", tf(Msg::UserName, &[&user.name]));
+        println!("{}", tf(Msg::UserPassword, &[&user.password]));
+        println!("UUID: {}", user.uuid);
+        println!("{}", tf(Msg::UserExpiresAt, &[&expires_at]));
+    }
+    info!(path = %opts.state_path, "用户已写入状态文件，重新生成配置后生效");
+    Ok(())
+}
+
+/// 订阅令牌命令
+/// 为用户签发（或更换）订阅服务访问令牌并写入状态文件，revoke 时吊销该用户的令牌；重启订阅服务后生效
+pub fn cmd_user_token(name: &str, revoke: bool) -> Result<(), EzError> {
+    let users = users_from_env().map_err(EzError::Env)?;
+    let opts = state_options_from_env().map_err(EzError::Env)?;
+    let mut state = load_rotated_state(&opts, users, None)?;
+    if state.user(name).is_none() {
+        return Err(EzError::Env(format!("用户不存在: {}", name)));
+    }
+    if revoke {
+        if state.subscribe_tokens.remove(name).is_none() {
+            warn!(user = name, "该用户没有订阅令牌");
+            return Ok(());
+        }
+        info!(user = name, "订阅令牌已吊销");
+    } else {
+        let token = generate_token();
+        println!("{}", tf(Msg::UserName, &[&name]));
+        println!("{}", tf(Msg::UserToken, &[&token]));
+        state.subscribe_tokens.insert(name.to_string(), token);
+    }
+    save_state(&opts.state_path, &state, opts.encryption_key.as_ref())
+        .map_err(EzError::io(&opts.state_path))?;
+    info!(path = %opts.state_path, "订阅令牌已写入状态文件，重启订阅服务后生效");
+    Ok(())
+}
+
+/// 健康检查命令
+/// 从本机连接已生成配置中的各入站端口，任一入站失败时返回退出码 1（Docker HEALTHCHECK 约定）
+pub fn cmd_health() -> Result<ExitCode, EzError> {
+    let (host, timeout) = health_options_from_env().map_err(EzError::Env)?;
+    let path = config_path_from_env();
+    let data = std::fs::read_to_string(&path).map_err(EzError::io(&path))?;
+    let config: serde_json::Value = from_jsonc_str(&data)
+        .map_err(|e| EzError::Build(format!("解析配置 {} 失败: {}", path, e)))?;
+
+    let targets = probe_targets(&config);
+    if targets.is_empty() {
+        return Err(EzError::Build(format!(
+            "配置 {} 中没有监听端口的入站",
+            path
+        )));
+    }
+    let reports: Vec<_> = targets.iter().map(|t| probe(t, host, timeout)).collect();
+    print!("{}", render_report(&reports));
+
+    if reports.iter().all(|r| r.is_ok()) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        let failures: Vec<_> = reports
+            .iter()
+            .filter_map(|r| {
+                let error = r.result.as_ref().err()?;
+                Some(serde_json::json!({
+                    "protocol": r.target.protocol,
+                    "tag": r.target.tag,
+                    "port": r.target.port,
+                    "error": error,
+                }))
+            })
+            .collect();
+        notify_event(
+            Event::HealthCheckFailed,
+            None,
+            serde_json::json!({ "host": host, "failures": failures }),
+        );
+        Ok(ExitCode::from(1))
+    }
+}
+
+/// 延迟测试命令
+/// 启动临时 sing-box 实例，经每个协议的出站请求测试地址并输出延迟，全部失败时返回退出码 1
+pub fn cmd_test() -> Result<ExitCode, EzError> {
+    let (target, timeout) = urltest_options_from_env().map_err(EzError::Env)?;
+    let build_result = build_from_env()?;
+    let result = &build_result.result;
+
+    let mut listeners = Vec::new();
+    for protocol in Protocol::ALL {
+        if !protocol_users(result, protocol).is_empty() {
+            let port =
+                free_port().map_err(|e| EzError::Build(format!("无法分配本地端口: {}", e)))?;
+            listeners.push((protocol, port));
+        }
+    }
+    let json = generate_urltest_config_json(result, &listeners).map_err(EzError::Build)?;
+    let temp_dir = std::env::temp_dir().to_string_lossy().into_owned();
+    let config = TempConfig::create("ezsingbox-urltest", "config.json", &json)
+        .map_err(EzError::io(&temp_dir))?;
+    let path = config.path().to_string_lossy().into_owned();
+
+    let sing_box = pick_sing_box_bin();
+    let _span = info_span!("sing-box", bin = %sing_box).entered();
+    let spawned = Command::new(&sing_box)
+        .arg("run")
+        .arg("-c")
+        .arg(&path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(source) => {
+            if source.kind() == std::io::ErrorKind::NotFound {
+                warn!("未找到 sing-box，{}", INSTALL_HINT);
+            }
+            return Err(EzError::SingBoxSpawn {
+                bin: sing_box.clone(),
+                source,
+            });
+        }
+    };
+    info!(pid = child.id(), target = %target.host, "临时 sing-box 已启动");
+
+    let results: Vec<LatencyResult> = listeners
+        .iter()
+        .map(|&(protocol, port)| {
+            let proxy = SocketAddr::from(([127, 0, 0, 1], port));
+            let result = if wait_for_port(proxy, timeout) {
+                url_test(proxy, &target, timeout)
+            } else {
+                Err("临时 sing-box 未能启动本地入站".to_string())
+            };
+            LatencyResult { protocol, result }
+        })
+        .collect();
+    let _ = child.kill();
+    let _ = child.wait();
+    drop(config);
+
+    print!("{}", render_latency(&results));
+    if results.iter().any(|r| r.result.is_ok()) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(1))
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 修改环境变量的测试须串行执行
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// 配置中 VLESS-Reality 入站的私钥与短 ID
+    fn reality_keys(path: &str) -> serde_json::Value {
+        let config: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let inbound = config["inbounds"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|i| i["type"] == "vless" && i["tls"]["reality"].is_object())
+            .unwrap();
+        let reality = &inbound["tls"]["reality"];
+        serde_json::json!([reality["private_key"], reality["short_id"]])
+    }
+
+    #[test]
+    fn test_regenerate_keeps_reality_key() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("ezsingbox-regen-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let file = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        let (state_path, config_path) = (file("state.json"), file("config.json"));
+        let vars = [
+            ("EZ_PUBLIC_IP", "127.0.0.1".to_string()),
+            ("EZ_ROTATE_INTERVAL", "30d".to_string()),
+            ("EZ_STATE_PATH", state_path.clone()),
+            ("EZ_ROTATION_LOG", file("rotation.log")),
+            ("EZ_CONFIG_PATH", config_path.clone()),
+            ("EZ_SINGBOX_CHECK", "false".to_string()),
+            ("EZ_CONFIG_BACKUPS", "0".to_string()),
+        ];
+        // 其他单元测试不读取这些环境变量
+        for (key, value) in &vars {
+            unsafe { std::env::set_var(key, value) };
+        }
+        let regenerate = || {
+            regenerate_server_config().unwrap();
+            reality_keys(&config_path)
+        };
+
+        // 未到期：重新生成后私钥与短 ID 不变
+        let first = regenerate();
+        assert!(first[0].is_string() && first[1].is_array(), "{}", first);
+        assert_eq!(regenerate(), first);
+
+        // 到期：私钥随凭证轮换，旧私钥记入轮换日志
+        let mut state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+        state["rotated_at"] = 0.into();
+        std::fs::write(&state_path, state.to_string()).unwrap();
+        let rotated = regenerate();
+        let log = std::fs::read_to_string(file("rotation.log")).unwrap();
+
+        for (key, _) in &vars {
+            unsafe { std::env::remove_var(key) };
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_ne!(rotated[0], first[0]);
+        assert!(log.contains(first[0].as_str().unwrap()));
+    }
+
+    #[test]
+    fn test_generated_config_has_no_deprecations() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let vars = [
+            ("EZ_LIMIT_BLOCKED_SOURCES", "203.0.113.0/24"),
+            ("EZ_LIMIT_BLOCK_PRIVATE", "true"),
+            ("EZ_LIMIT_BLOCK_SMTP", "true"),
+            ("EZ_LIMIT_BLOCK_BITTORRENT", "true"),
+        ];
+        for (key, value) in vars {
+            unsafe { std::env::set_var(key, value) };
+        }
+        let ip = "127.0.0.1".parse().unwrap();
+        let json = build_node_from_env(ip, None)
+            .map_err(|e| e.to_string())
+            .and_then(|result| generate_config_json(&result, "info"));
+        for (key, _) in vars {
+            unsafe { std::env::remove_var(key) };
+        }
+
+        // 拦截规则使用 reject 动作，不再生成已弃用的 block 出站
+        let config: serde_json::Value = serde_json::from_str(&json.unwrap()).unwrap();
+        let deprecated: Vec<_> = lint_config(&config)
+            .into_iter()
+            .filter(|f| f.rule == RULE_DEPRECATED)
+            .collect();
+        assert!(deprecated.is_empty(), "{:?}", deprecated);
+        let rules = config["route"]["rules"].as_array().unwrap();
+        assert!(rules.iter().any(|r| r["action"] == "reject"));
+    }
+
+    #[test]
+    fn test_peer_outbounds_match_models() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let vars = [
+            ("EZ_ENABLE_VLESS_WS", "true"),
+            ("EZ_ENABLE_VLESS_GRPC", "true"),
+            ("EZ_HY2_OBFS", "true"),
+            ("EZ_TUIC_CC", "bbr"),
+        ];
+        for (key, value) in vars {
+            unsafe { std::env::set_var(key, value) };
+        }
+        let ip = "127.0.0.1".parse().unwrap();
+        let json = build_node_from_env(ip, Some("ex.example.com"))
+            .map_err(|e| e.to_string())
+            .and_then(|result| generate_peer_outbounds_json(&result));
+        for (key, _) in vars {
+            unsafe { std::env::remove_var(key) };
+        }
+
+        // 导出的出站能按强类型模型严格解析，且没有检查问题
+        let outbounds: serde_json::Value = serde_json::from_str(&json.unwrap()).unwrap();
+        assert_eq!(outbounds.as_array().unwrap().len(), 6);
+        let findings = lint_config(&serde_json::json!({ "outbounds": outbounds }));
+        assert!(findings.is_empty(), "{:?}", findings);
+        assert_eq!(outbounds[2]["congestion_control"], "bbr");
+    }
 }
@@ -0,0 +1,194 @@
+//! Docker Compose 生成模块
+//!
+//! 根据生成的服务端配置映射入站端口（QUIC 入站映射为 udp），
+//! 并将当前生效的变量写入 environment，输出可直接使用的 docker-compose.yaml
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::health::{ProbeTransport, probe_targets};
+
+/// 默认镜像
+pub const DEFAULT_COMPOSE_IMAGE: &str = "ghcr.io/laomeifun/ezsingbox:latest";
+
+/// 容器内的 ACME 数据目录（挂载为数据卷，重建容器后保留证书）
+pub const CONTAINER_ACME_DIR: &str = "/var/lib/ezsingbox/acme";
+
+/// 只对宿主机有意义、不写入容器环境的变量（路径类变量由镜像与数据卷决定）
+const HOST_ONLY_VARS: &[&str] = &[
+    "EZ_FILE",
+    "EZ_CONFIG_PATH",
+    "EZ_CLIENT_CONFIG_PATH",
+    "EZ_EXPORT_PATH",
+    "EZ_CLIENT_TEMPLATE_PATH",
+    "EZ_BUNDLE_PATH",
+    "EZ_SUPPORT_LOG",
+    "EZ_ACME_DATA_DIR",
+    "EZ_STATE_PATH",
+    "EZ_ROTATION_LOG",
+    "EZ_STATS_PATH",
+    "EZ_COMPOSE_IMAGE",
+    "EZ_INSTALL_PREFIX",
+    "EZ_FLEET",
+    "EZ_FLEET_DIR",
+    "EZ_DEPLOY_DIR",
+    "EZ_DEPLOY_REMOTE_BIN",
+    "EZ_DEPLOY_BINARY",
+    "EZ_DEPLOY_SERVICE",
+    "EZ_DEPLOY_SSH_KEY",
+    "SING_BOX_BIN",
+];
+
+//============================================================================
+// 端口与环境
+//============================================================================
+
+/// 需要发布的端口（按端口、协议排序去重）
+/// 使用 ACME 且未禁用 HTTP 验证、也未使用 DNS-01 时额外发布 80/tcp
+pub fn port_mappings(config: &Value) -> Vec<(u16, &'static str)> {
+    let mut ports: BTreeSet<(u16, &'static str)> = probe_targets(config)
+        .iter()
+        .map(|t| match t.transport {
+            ProbeTransport::Tcp => (t.port, "tcp"),
+            ProbeTransport::Quic => (t.port, "udp"),
+        })
+        .collect();
+    if acme_configs(config)
+        .any(|acme| acme["disable_http_challenge"] != true && acme["dns01_challenge"].is_null())
+    {
+        ports.insert((80, "tcp"));
+    }
+    ports.into_iter().collect()
+}
+
+/// 是否有入站使用 ACME 证书
+pub fn uses_acme(config: &Value) -> bool {
+    acme_configs(config).next().is_some()
+}
+
+/// 所有入站的 ACME 配置
+fn acme_configs(config: &Value) -> impl Iterator<Item = &Value> {
+    config["inbounds"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|inbound| &inbound["tls"]["acme"])
+        .filter(|acme| acme.is_object())
+}
+
+/// 写入容器的变量：去掉只对宿主机有意义的变量，使用 ACME 时指向数据卷
+pub fn container_env(vars: &BTreeMap<String, String>, acme: bool) -> BTreeMap<String, String> {
+    let mut env: BTreeMap<String, String> = vars
+        .iter()
+        .filter(|(k, _)| !HOST_ONLY_VARS.contains(&k.as_str()))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if acme {
+        env.insert(
+            "EZ_ACME_DATA_DIR".to_string(),
+            CONTAINER_ACME_DIR.to_string(),
+        );
+    }
+    env
+}
+
+//============================================================================
+// 渲染
+//============================================================================
+
+/// 将字符串渲染为 YAML 双引号标量，`$` 转义为 `$$` 避免被 Compose 插值
+fn quote(value: &str) -> String {
+    serde_json::Value::from(value)
+        .to_string()
+        .replace('$', "$$")
+}
+
+/// 渲染 docker-compose.yaml
+/// acme_volume 为宿主机上的 ACME 数据目录（未使用 ACME 时为 None）
+pub fn render_compose(
+    image: &str,
+    ports: &[(u16, &str)],
+    env: &BTreeMap<String, String>,
+    acme_volume: Option<&str>,
+) -> String {
+    let mut out = String::new();
+    out.push_str("# 由 ezsingbox compose 生成\n");
+    out.push_str("services:\n  ezsingbox:\n");
+    let _ = writeln!(out, "    image: {}", quote(image));
+    out.push_str("    container_name: ezsingbox\n");
+    out.push_str("    restart: unless-stopped\n");
+    out.push_str("    command: [\"run\"]\n");
+    if !ports.is_empty() {
+        out.push_str("    ports:\n");
+        for (port, protocol) in ports {
+            let _ = writeln!(out, "      - \"{port}:{port}/{protocol}\"");
+        }
+    }
+    if !env.is_empty() {
+        out.push_str("    environment:\n");
+        for (key, value) in env {
+            let _ = writeln!(out, "      {}: {}", key, quote(value));
+        }
+    }
+    if let Some(dir) = acme_volume {
+        out.push_str("    volumes:\n");
+        let _ = writeln!(
+            out,
+            "      - {}",
+            quote(&format!("{}:{}", dir, CONTAINER_ACME_DIR))
+        );
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_compose() {
+        let config = json!({
+            "inbounds": [
+                { "type": "anytls", "tag": "anytls-in", "listen_port": 443,
+                  "tls": { "enabled": true, "server_name": "example.com",
+                           "acme": { "domain": ["example.com"] } } },
+                { "type": "hysteria2", "tag": "hy2-in", "listen_port": 443 },
+                { "type": "tuic", "tag": "tuic-in", "listen_port": 2083 }
+            ]
+        });
+        let ports = port_mappings(&config);
+        assert_eq!(
+            ports,
+            vec![(80, "tcp"), (443, "tcp"), (443, "udp"), (2083, "udp")]
+        );
+        assert!(uses_acme(&config));
+
+        let vars = BTreeMap::from([
+            ("EZ_DOMAIN".to_string(), "example.com".to_string()),
+            ("EZ_PASSWORD".to_string(), "pa$$\"word".to_string()),
+            ("EZ_CONFIG_PATH".to_string(), "./config.json".to_string()),
+        ]);
+        let env = container_env(&vars, true);
+        assert!(!env.contains_key("EZ_CONFIG_PATH"));
+
+        let yaml = render_compose(DEFAULT_COMPOSE_IMAGE, &ports, &env, Some("./acme"));
+        assert!(yaml.contains("      - \"443:443/udp\"\n"));
+        assert!(yaml.contains("      EZ_PASSWORD: \"pa$$$$\\\"word\"\n"));
+        assert!(yaml.contains("      EZ_ACME_DATA_DIR: \"/var/lib/ezsingbox/acme\"\n"));
+        assert!(yaml.contains("      - \"./acme:/var/lib/ezsingbox/acme\"\n"));
+
+        // 生成的文件必须是合法 YAML
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            parsed["services"]["ezsingbox"]["environment"]["EZ_DOMAIN"],
+            "example.com"
+        );
+    }
+}
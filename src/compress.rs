@@ -0,0 +1,167 @@
+//! 订阅响应压缩
+//!
+//! 订阅服务按请求的 Accept-Encoding 协商 gzip / deflate，只压缩不小于 COMPRESS_MIN_BYTES 的响应体
+//! （小响应压缩后反而更大）。未启用 compress 特性时始终返回原始内容
+
+use std::fmt;
+
+/// 压缩的最小响应体大小（字节）
+pub const COMPRESS_MIN_BYTES: usize = 1024;
+
+/// 响应内容编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// gzip
+    Gzip,
+    /// deflate（zlib 格式，RFC 9110）
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Content-Encoding 头中的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Deflate => "deflate",
+        }
+    }
+}
+
+impl fmt::Display for ContentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 解析 Accept-Encoding，返回客户端接受且权重最高的编码（权重相同时优先 gzip，q=0 表示拒绝）
+pub fn negotiate(accept_encoding: &str) -> Option<ContentEncoding> {
+    let mut gzip = None;
+    let mut deflate = None;
+    let mut wildcard = None;
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let q = parts
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match name.as_str() {
+            "gzip" | "x-gzip" => gzip = Some(q),
+            "deflate" => deflate = Some(q),
+            "*" => wildcard = Some(q),
+            _ => {}
+        }
+    }
+    let gzip = gzip.or(wildcard).unwrap_or(0.0);
+    let deflate = deflate.or(wildcard).unwrap_or(0.0);
+    if gzip <= 0.0 && deflate <= 0.0 {
+        None
+    } else if gzip >= deflate {
+        Some(ContentEncoding::Gzip)
+    } else {
+        Some(ContentEncoding::Deflate)
+    }
+}
+
+/// 按 Accept-Encoding 压缩响应体，返回（响应体，使用的编码）
+/// 响应体过小、客户端不接受压缩或压缩失败时返回原始内容
+#[cfg(feature = "compress")]
+pub fn compress_body(
+    body: Vec<u8>,
+    accept_encoding: Option<&str>,
+) -> (Vec<u8>, Option<ContentEncoding>) {
+    use std::io::Write as _;
+
+    use flate2::Compression;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+
+    let Some(encoding) = accept_encoding.and_then(negotiate) else {
+        return (body, None);
+    };
+    if body.len() < COMPRESS_MIN_BYTES {
+        return (body, None);
+    }
+    let compressed = match encoding {
+        ContentEncoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&body).and_then(|()| encoder.finish())
+        }
+        ContentEncoding::Deflate => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&body).and_then(|()| encoder.finish())
+        }
+    };
+    match compressed {
+        Ok(data) => (data, Some(encoding)),
+        Err(e) => {
+            tracing::warn!(%encoding, error = %e, "压缩响应失败，返回原始内容");
+            (body, None)
+        }
+    }
+}
+
+/// 未启用 compress 特性时不压缩
+#[cfg(not(feature = "compress"))]
+pub fn compress_body(
+    body: Vec<u8>,
+    _accept_encoding: Option<&str>,
+) -> (Vec<u8>, Option<ContentEncoding>) {
+    (body, None)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("gzip, deflate, br"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("deflate"), Some(ContentEncoding::Deflate));
+        assert_eq!(
+            negotiate("gzip;q=0.5, deflate;q=0.8"),
+            Some(ContentEncoding::Deflate)
+        );
+        assert_eq!(negotiate("GZIP"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("*"), Some(ContentEncoding::Gzip));
+        assert_eq!(
+            negotiate("*;q=0.1, gzip;q=0"),
+            Some(ContentEncoding::Deflate)
+        );
+        assert_eq!(negotiate("gzip;q=0"), None);
+        assert_eq!(negotiate("identity, br"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_compress_body() {
+        use std::io::Read as _;
+
+        let body = "{\"outbounds\": []}\n".repeat(200).into_bytes();
+        let (small, encoding) = compress_body(b"{}".to_vec(), Some("gzip"));
+        assert_eq!((small.as_slice(), encoding), (&b"{}"[..], None));
+        let (plain, encoding) = compress_body(body.clone(), None);
+        assert_eq!((plain.len(), encoding), (body.len(), None));
+
+        let (gzip, encoding) = compress_body(body.clone(), Some("gzip, deflate"));
+        assert_eq!(encoding, Some(ContentEncoding::Gzip));
+        assert!(gzip.len() < body.len());
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(gzip.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+
+        let (deflate, encoding) = compress_body(body.clone(), Some("deflate"));
+        assert_eq!(encoding, Some(ContentEncoding::Deflate));
+        let mut decoded = Vec::new();
+        flate2::read::ZlibDecoder::new(deflate.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+    }
+}
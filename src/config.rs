@@ -1,14 +1,65 @@
 //! 配置构建和生成模块
 
-use crate::autoconfig::{GeneratedUser, MultiProtocolBuilder, MultiProtocolResult};
+use std::fmt::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::Path;
+
+use base64::Engine;
+
+use crate::cert::{format_utc, unix_now};
 use crate::env::{env_bool, env_ip, env_string, env_u16, env_u32};
-use crate::protocol::ClientProtocol;
-use crate::sharelink::{
-    generate_anytls_share_link, generate_hysteria2_share_link, generate_tuic_share_link,
-    generate_vless_reality_share_link, sing_box_import_remote_profile_uri,
+use crate::error::EzError;
+use crate::health::DEFAULT_HEALTH_TIMEOUT;
+use crate::i18n::{Msg, t, tf};
+use crate::install::DEFAULT_INSTALL_PREFIX;
+use crate::redact::ConsoleRedactor;
+use crate::state::{
+    DEFAULT_ROTATION_LOG, DEFAULT_STATE_PATH, StateKey, StateOptions, StoredUser,
+    load_rotated_state,
+};
+use crate::stats::{DEFAULT_STATS_INTERVAL, DEFAULT_STATS_PATH, StatsOptions};
+use crate::upgrade::{DEFAULT_UPGRADE_CHECK_INTERVAL, MaintenanceWindow, UpgradeOptions};
+use crate::urltest::{DEFAULT_URLTEST_TIMEOUT, DEFAULT_URLTEST_URL, HttpTarget};
+use crate::users::{UserSpec, merge_users, parse_users_json};
+use crate::utils::pick_sing_box_bin;
+use ezsingbox::autoconfig::{
+    ClientPlatform, DEFAULT_ACME_DATA_DIR, DEFAULT_TARGET_TIMEOUT, DomainProvider,
+    DomainProviderError, GeneratedUser, IpDetector, MultiProtocolBuilder, MultiProtocolResult,
+    Preset, Protocol, REALITY_TARGET_CANDIDATES, check_reality_target, generate_sslip_domain,
+    pick_reality_target,
+};
+use ezsingbox::dns::{
+    ClientDnsBuilder, ClientDnsMode, ClientDnsProfile, DefaultDnsRule, Dns, DnsRejectMethod,
+    DnsRule, DnsRuleAction, DnsServer, DnsUpstream, LOCAL_DNS_TAG, LocalDnsServer, REMOTE_DNS_TAG,
+    ServerDnsBuilder,
+};
+use ezsingbox::sharelink::{
+    RealityLinkParams, TuicLinkOptions, WsLinkParams, generate_anytls_share_link,
+    generate_hysteria2_share_link, generate_tuic_share_link, generate_vless_grpc_share_link,
+    generate_vless_reality_share_link, generate_vless_ws_share_link, parse_share_link,
+    sing_box_import_remote_profile_uri,
+};
+use ezsingbox::singboxconfig::canonical::to_canonical_string;
+use ezsingbox::singboxconfig::endpoint::WireGuardEndpoint;
+use ezsingbox::singboxconfig::full::SingBoxConfig;
+use ezsingbox::singboxconfig::inbound::{
+    CongestionControl, Hysteria2Masquerade, MasqueradeType, TunInbound, UdpRelayMode,
+};
+use ezsingbox::singboxconfig::minimal::minimize_config;
+use ezsingbox::singboxconfig::outbound::{
+    AnyTlsOutbound, BlockOutbound, DirectOutbound, Hysteria2Outbound, TuicOutbound, VlessOutbound,
+};
+use ezsingbox::singboxconfig::route::{
+    RejectAction, RejectMethod, RouteRule, RuleAction, RuleSet, SING_GEOIP_URL, SING_GEOSITE_URL,
 };
-use crate::singboxconfig::full::SingBoxConfig;
-use crate::singboxconfig::inbound::CongestionControl;
+use ezsingbox::singboxconfig::shared::{
+    AcmeProvider, AliDnsConfig, DialFields, Dns01Challenge, GrpcTransport, MultiplexOutbound,
+    MultiplexProtocol, OutboundTlsConfig, RealityOutboundConfig, UtlsConfig, UtlsFingerprint,
+    V2RayTransport, WebSocketTransport,
+};
+use ezsingbox::singboxconfig::types::{DomainStrategy, Duration, RoutingMark, SingBoxVersion};
+use ezsingbox::singboxconfig::validate::Diagnostic;
+use tracing::{info, info_span, warn};
 
 /// 配置构建结果
 pub struct BuildResult {
@@ -16,37 +67,261 @@ pub struct BuildResult {
     pub config_path: String,
     pub print_config: bool,
     pub log_level: String,
+    /// 下次需要重新生成配置的时间（凭证轮换或用户过期，Unix 时间戳）
+    pub regenerate_at: Option<i64>,
+    /// 设置了过期时间的用户（含已过期、未写入配置的用户）
+    pub user_expiry: Vec<(String, i64)>,
+}
+
+/// 构建配置使用的凭证
+struct Credentials {
+    users: Vec<GeneratedUser>,
+    reality: StoredReality,
+    next_rotation: Option<i64>,
+}
+
+/// 状态文件中保存的 REALITY 私钥与短 ID（未使用状态文件时为空）
+#[derive(Debug, Clone, Default)]
+struct StoredReality {
+    private_key: Option<String>,
+    short_ids: Vec<String>,
+}
+
+/// 读取用户凭证：使用状态文件时取自状态文件（到期时先轮换），否则按用户列表随机生成
+fn credentials_from_env() -> Result<Credentials, EzError> {
+    let opts = state_options_from_env().map_err(EzError::Env)?;
+    let users = users_from_env().map_err(EzError::Env)?;
+    if !opts.enabled() {
+        return Ok(Credentials {
+            users: merge_users(users, &[])
+                .iter()
+                .map(UserSpec::generate)
+                .collect(),
+            reality: StoredReality::default(),
+            next_rotation: None,
+        });
+    }
+    let state = load_rotated_state(&opts, users, None)?;
+    Ok(Credentials {
+        users: state.users.iter().map(StoredUser::to_generated).collect(),
+        next_rotation: opts.interval.map(|i| state.next_rotation(i)),
+        reality: StoredReality {
+            private_key: state.reality_private_key,
+            short_ids: state.reality_short_ids,
+        },
+    })
+}
+
+/// 移除已过期的用户
+fn remove_expired(users: Vec<GeneratedUser>, now: i64) -> Vec<GeneratedUser> {
+    let total = users.len();
+    let (active, expired): (Vec<_>, Vec<_>) = users.into_iter().partition(|u| !u.is_expired(now));
+    for user in &expired {
+        info!(user = %user.name, "用户已过期，不写入配置");
+    }
+    if active.is_empty() && total > 0 {
+        warn!("全部用户均已过期，配置中只有随机生成的 default 用户");
+    }
+    active
 }
 
 /// 从环境变量构建配置
-pub fn build_from_env() -> Result<BuildResult, String> {
-    let config_path = env_string("EZ_CONFIG_PATH").unwrap_or_else(|| "./config.json".to_string());
-    let print_config = env_bool("EZ_PRINT_CONFIG", true);
-    let log_level = env_string("EZ_LOG_LEVEL").unwrap_or_else(|| "info".to_string());
+/// 使用状态文件（EZ_ROTATE_INTERVAL 或已执行 `user add`）时凭证取自状态文件，到期时先轮换
+pub fn build_from_env() -> Result<BuildResult, EzError> {
+    let _span = info_span!("build").entered();
+    let credentials = credentials_from_env()?;
+    let user_expiry = credentials
+        .users
+        .iter()
+        .filter_map(|u| Some((u.name.clone(), u.expires_at?)))
+        .collect();
+    let users = remove_expired(credentials.users, unix_now());
+    let regenerate_at = users
+        .iter()
+        .filter_map(|u| u.expires_at)
+        .chain(credentials.next_rotation)
+        .min();
+    let builder = multi_builder_from_env(users, &credentials.reality).map_err(EzError::Env)?;
+    let result = builder.build().map_err(|e| EzError::Build(e.to_string()))?;
+    Ok(BuildResult {
+        result,
+        config_path: config_path_from_env(),
+        print_config: env_bool("EZ_PRINT_CONFIG", true),
+        log_level: env_string("EZ_LOG_LEVEL").unwrap_or_else(|| "info".to_string()),
+        regenerate_at,
+        user_expiry,
+    })
+}
+
+/// 为服务器列表中的单个节点构建配置
+/// 公网 IP 与域名取自节点，其余选项与单机模式共用环境变量；未指定域名时按 EZ_DOMAIN_PROVIDER 生成，
+/// DuckDNS 域名只能对应一个 IP，节点改用 sslip.io
+pub fn build_node_from_env(
+    public_ip: IpAddr,
+    domain: Option<&str>,
+) -> Result<MultiProtocolResult, EzError> {
+    let users = users_from_env().map_err(EzError::Env)?;
+    let users = merge_users(users, &[])
+        .iter()
+        .map(UserSpec::generate)
+        .collect();
+    let provider = domain_provider_from_env().map_err(EzError::Env)?;
+    let domain = match domain {
+        Some(domain) => domain.to_string(),
+        None if provider.is_dynamic() => generate_sslip_domain(&public_ip),
+        None => provider.domain_for(&public_ip),
+    };
+    let builder =
+        multi_builder_from_env(remove_expired(users, unix_now()), &StoredReality::default())
+            .map_err(EzError::Env)?
+            .public_ip(public_ip)
+            .domain(domain);
+    builder.build().map_err(|e| EzError::Build(e.to_string()))
+}
+
+/// 按协议覆盖协议嗅探开关的环境变量
+const SNIFF_PROTOCOL_VARS: [(&str, Protocol); 6] = [
+    ("EZ_SNIFF_ANYTLS", Protocol::AnyTls),
+    ("EZ_SNIFF_HYSTERIA2", Protocol::Hysteria2),
+    ("EZ_SNIFF_TUIC", Protocol::Tuic),
+    ("EZ_SNIFF_VLESS_REALITY", Protocol::VlessReality),
+    ("EZ_SNIFF_VLESS_WS", Protocol::VlessWs),
+    ("EZ_SNIFF_VLESS_GRPC", Protocol::VlessGrpc),
+];
+
+/// 按协议设置入站监听地址的环境变量
+const LISTEN_PROTOCOL_VARS: [(&str, Protocol); 6] = [
+    ("EZ_ANYTLS_LISTEN", Protocol::AnyTls),
+    ("EZ_HYSTERIA2_LISTEN", Protocol::Hysteria2),
+    ("EZ_TUIC_LISTEN", Protocol::Tuic),
+    ("EZ_VLESS_REALITY_LISTEN", Protocol::VlessReality),
+    ("EZ_VLESS_WS_LISTEN", Protocol::VlessWs),
+    ("EZ_VLESS_GRPC_LISTEN", Protocol::VlessGrpc),
+];
+
+/// 环境变量中的用户列表（可能为空，由 merge_users 补充 default 用户）
+/// 优先级: EZ_USER > EZ_USERS_JSON > EZ_USERS（后两者通常来自配置文件）
+pub fn users_from_env() -> Result<Vec<UserSpec>, String> {
+    if let Some(user) = env_string("EZ_USER") {
+        return Ok(vec![UserSpec::new(user, env_string("EZ_PASSWORD"))]);
+    }
+    if let Some(raw) = env_string("EZ_USERS_JSON") {
+        return parse_users_json(&raw);
+    }
+    Ok(env_string("EZ_USERS")
+        .map(|raw| parse_users(&raw))
+        .unwrap_or_default())
+}
 
-    let enable_anytls = env_bool("EZ_ENABLE_ANYTLS", true);
-    let enable_hy2 = env_bool("EZ_ENABLE_HYSTERIA2", true);
-    let enable_tuic = env_bool("EZ_ENABLE_TUIC", true);
-    let enable_vless_reality = env_bool("EZ_ENABLE_VLESS_REALITY", true);
+/// 根据环境变量创建多协议构建器
+/// reality 中的私钥与短 ID（取自状态文件）覆盖随机生成的密钥对与短 ID 设置
+fn multi_builder_from_env(
+    users: Vec<GeneratedUser>,
+    reality: &StoredReality,
+) -> Result<MultiProtocolBuilder, String> {
+    // 使用预设时，协议默认启用与否及端口由预设决定，仍可被 EZ_ENABLE_* / 端口变量覆盖
+    let preset = env_string("EZ_PRESET")
+        .map(|raw| raw.parse::<Preset>().map_err(|e| e.to_string()))
+        .transpose()?;
+    let enabled_by_default =
+        |protocol: Protocol, default: bool| preset.map_or(default, |p| p.port(protocol).is_some());
+    let port_for = |key: &str, protocol: Protocol| {
+        env_u16(key)
+            .or_else(|| preset.and_then(|p| p.port(protocol)))
+            .unwrap_or(protocol.default_port())
+    };
 
-    let anytls_port = env_u16("EZ_ANYTLS_PORT").unwrap_or(443);
-    let hy2_port = env_u16("EZ_HYSTERIA2_PORT").unwrap_or(2053);
-    let tuic_port = env_u16("EZ_TUIC_PORT").unwrap_or(2083);
-    let vless_reality_port = env_u16("EZ_VLESS_REALITY_PORT").unwrap_or(2096);
+    let enable_anytls = env_bool(
+        "EZ_ENABLE_ANYTLS",
+        enabled_by_default(Protocol::AnyTls, true),
+    );
+    let enable_hy2 = env_bool(
+        "EZ_ENABLE_HYSTERIA2",
+        enabled_by_default(Protocol::Hysteria2, true),
+    );
+    let enable_tuic = env_bool("EZ_ENABLE_TUIC", enabled_by_default(Protocol::Tuic, true));
+    let enable_vless_reality = env_bool(
+        "EZ_ENABLE_VLESS_REALITY",
+        enabled_by_default(Protocol::VlessReality, true),
+    );
+    // WebSocket / gRPC 需要可用于 ACME 的域名，默认不启用
+    let enable_vless_ws = env_bool(
+        "EZ_ENABLE_VLESS_WS",
+        enabled_by_default(Protocol::VlessWs, false),
+    );
+    let enable_vless_grpc = env_bool(
+        "EZ_ENABLE_VLESS_GRPC",
+        enabled_by_default(Protocol::VlessGrpc, false),
+    );
 
-    let user = env_string("EZ_USER").unwrap_or_else(|| "default".to_string());
-    let password = env_string("EZ_PASSWORD");
+    let anytls_port = port_for("EZ_ANYTLS_PORT", Protocol::AnyTls);
+    let hy2_port = port_for("EZ_HYSTERIA2_PORT", Protocol::Hysteria2);
+    let tuic_port = port_for("EZ_TUIC_PORT", Protocol::Tuic);
+    let vless_reality_port = port_for("EZ_VLESS_REALITY_PORT", Protocol::VlessReality);
+    let vless_ws_port = port_for("EZ_VLESS_WS_PORT", Protocol::VlessWs);
+    let vless_grpc_port = port_for("EZ_VLESS_GRPC_PORT", Protocol::VlessGrpc);
 
     let mut builder = MultiProtocolBuilder::new();
+    if let Some(preset) = preset {
+        // 预设的协议组合在下方按 EZ_ENABLE_* 重新启用，这里只保留预设本身的选项
+        builder = builder.preset(preset);
+        for protocol in Protocol::ALL {
+            builder = builder.disable(protocol);
+        }
+    }
+    if let Some(raw) = env_string("EZ_IP_DETECTOR") {
+        let detectors = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse::<IpDetector>)
+            .collect::<Result<Vec<_>, _>>()?;
+        builder = builder.ip_detectors(detectors);
+    }
+    if let Some(raw) = env_string("EZ_IP_SERVICES") {
+        let services: Vec<String> = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        if let Some(bad) = services
+            .iter()
+            .find(|s| !s.starts_with("http://") && !s.starts_with("https://"))
+        {
+            return Err(format!("EZ_IP_SERVICES 中的地址无效: {}", bad));
+        }
+        builder = builder.ip_services(services);
+    }
     if let Some(ip) = env_ip("EZ_PUBLIC_IP") {
         builder = builder.public_ip(ip);
     }
     if let Some(domain) = env_string("EZ_DOMAIN") {
         builder = builder.domain(domain);
     }
+    builder = builder.domain_provider(domain_provider_from_env()?);
     if let Some(email) = env_string("EZ_ACME_EMAIL") {
         builder = builder.acme_email(email);
     }
+    if let Some(provider) = env_string("EZ_ACME_DNS01_PROVIDER") {
+        builder = builder.acme_dns01(dns01_challenge_from_env(&provider)?);
+    }
+    if let Some(provider) = env_string("EZ_ACME_PROVIDER") {
+        builder = builder.acme_provider(provider.parse::<AcmeProvider>()?);
+    }
+    if let Some(dir) = env_string("EZ_ACME_DATA_DIR") {
+        builder = builder.acme_data_directory(dir);
+    }
+    match (
+        env_string("EZ_ACME_EAB_KEY_ID"),
+        env_string("EZ_ACME_EAB_MAC_KEY"),
+    ) {
+        (Some(key_id), Some(mac_key)) => builder = builder.acme_eab(key_id, mac_key),
+        (None, None) => {}
+        _ => {
+            return Err("EZ_ACME_EAB_KEY_ID 与 EZ_ACME_EAB_MAC_KEY 需要同时设置".to_string());
+        }
+    }
 
     if enable_anytls {
         builder = builder.enable_anytls(anytls_port);
@@ -60,21 +335,67 @@ pub fn build_from_env() -> Result<BuildResult, String> {
     if enable_vless_reality {
         builder = builder.enable_vless_reality(vless_reality_port);
         // 设置 VLESS Reality 握手服务器
-        let handshake_server = env_string("EZ_VLESS_HANDSHAKE_SERVER")
-            .unwrap_or_else(|| "www.microsoft.com".to_string());
-        let handshake_port = env_u16("EZ_VLESS_HANDSHAKE_PORT").unwrap_or(443);
+        let (handshake_server, handshake_port) = resolve_reality_target(
+            env_string("EZ_VLESS_HANDSHAKE_SERVER"),
+            env_u16("EZ_VLESS_HANDSHAKE_PORT").unwrap_or(443),
+        );
         builder = builder.vless_handshake(handshake_server, handshake_port);
+        if let Some(ids) = env_string("EZ_REALITY_SHORT_IDS") {
+            builder = builder
+                .reality_custom_short_ids(ids.split(',').map(str::trim).filter(|s| !s.is_empty()));
+        }
+        if let Some(count) = env_u16("EZ_REALITY_SHORT_ID_COUNT") {
+            let len = env_u16("EZ_REALITY_SHORT_ID_LEN").unwrap_or(4);
+            builder = builder.reality_short_ids(count as usize, len as usize);
+        } else if let Some(len) = env_u16("EZ_REALITY_SHORT_ID_LEN") {
+            builder = builder.reality_short_ids(1, len as usize);
+        }
+        if !reality.short_ids.is_empty() {
+            builder = builder.reality_custom_short_ids(reality.short_ids.iter().cloned());
+        }
+        // 复用已有私钥（环境变量优先于状态文件），或调用 sing-box 生成密钥对
+        if let Some(key) =
+            env_string("EZ_REALITY_PRIVATE_KEY").or_else(|| reality.private_key.clone())
+        {
+            builder = builder.reality_private_key(key);
+        }
+        if env_string("EZ_REALITY_KEYGEN").is_some_and(|v| v.eq_ignore_ascii_case("sing-box")) {
+            builder = builder.reality_keygen_sing_box(pick_sing_box_bin());
+        }
+    }
+
+    if enable_vless_ws {
+        builder = builder.enable_vless_ws(
+            vless_ws_port,
+            env_string("EZ_VLESS_WS_PATH").unwrap_or_default(),
+        );
+    }
+    if enable_vless_grpc {
+        builder = builder.enable_vless_grpc(
+            vless_grpc_port,
+            env_string("EZ_VLESS_GRPC_SERVICE").unwrap_or_default(),
+        );
+    }
+    if let Some(host) = env_string("EZ_VLESS_WS_HOST") {
+        builder = builder.vless_ws_host(host);
+    }
+    if let Some(host) = env_string("EZ_CDN_HOST") {
+        builder = builder.cdn_host(host);
     }
 
-    if !enable_anytls && !enable_hy2 && !enable_tuic && !enable_vless_reality {
+    if !enable_anytls
+        && !enable_hy2
+        && !enable_tuic
+        && !enable_vless_reality
+        && !enable_vless_ws
+        && !enable_vless_grpc
+    {
         builder = builder.enable_all();
     }
 
-    builder = if let Some(pwd) = password {
-        builder.add_user_with_password(user, pwd)
-    } else {
-        builder.add_user(user)
-    };
+    for user in users {
+        builder = builder.add_generated_user(user);
+    }
 
     if env_bool("EZ_HY2_OBFS", false) {
         builder = builder.hy2_obfs();
@@ -82,6 +403,18 @@ pub fn build_from_env() -> Result<BuildResult, String> {
     if let (Some(up), Some(down)) = (env_u32("EZ_HY2_UP_MBPS"), env_u32("EZ_HY2_DOWN_MBPS")) {
         builder = builder.hy2_bandwidth(up, down);
     }
+    if env_bool("EZ_HY2_IGNORE_CLIENT_BW", false) {
+        builder = builder.hy2_ignore_client_bandwidth(true);
+    }
+    if let (Some(up), Some(down)) = (env_u32("EZ_BRUTAL_UP_MBPS"), env_u32("EZ_BRUTAL_DOWN_MBPS")) {
+        builder = builder.tcp_brutal(up, down);
+    }
+    if let Some(masquerade) = hy2_masquerade_from_env()? {
+        builder = builder.hy2_masquerade(masquerade);
+    }
+    if let Some(multiplex) = multiplex_from_env()? {
+        builder = builder.multiplex(multiplex);
+    }
 
     if let Some(cc) = env_string("EZ_TUIC_CC") {
         builder = match cc.trim().to_ascii_lowercase().as_str() {
@@ -91,35 +424,129 @@ pub fn build_from_env() -> Result<BuildResult, String> {
             _ => builder,
         };
     }
+    if let Some(timeout) = env_duration("EZ_TUIC_AUTH_TIMEOUT")? {
+        builder = builder.tuic_auth_timeout(timeout);
+    }
+    if env_bool("EZ_TUIC_ZERO_RTT", false) {
+        builder = builder.tuic_zero_rtt(true);
+    }
+    if let Some(interval) = env_duration("EZ_TUIC_HEARTBEAT")? {
+        builder = builder.tuic_heartbeat(interval);
+    }
+    if let Some(mode) = env_string("EZ_TUIC_UDP_RELAY_MODE") {
+        builder = builder.tuic_udp_relay_mode(mode.parse::<UdpRelayMode>()?);
+    }
 
-    let result = builder.build().map_err(|e| e.to_string())?;
-    Ok(BuildResult {
-        result,
-        config_path,
-        print_config,
-        log_level,
+    if env_bool("EZ_TCP_FAST_OPEN", false) {
+        builder = builder.tcp_fast_open(true);
+    }
+    if let Some(timeout) = env_duration("EZ_UDP_TIMEOUT")? {
+        builder = builder.udp_timeout(timeout);
+    }
+    builder = builder
+        .sniff(env_bool("EZ_SNIFF", true))
+        .sniff_override_destination(env_bool("EZ_SNIFF_OVERRIDE_DESTINATION", true));
+    for (key, protocol) in SNIFF_PROTOCOL_VARS {
+        if env_string(key).is_some() {
+            builder = builder.protocol_sniff(protocol, env_bool(key, true));
+        }
+    }
+    for (key, protocol) in LISTEN_PROTOCOL_VARS {
+        if let Some(listen) = env_string(key) {
+            let ip: IpAddr = listen
+                .parse()
+                .map_err(|_| format!("{} 不是有效的 IP 地址: {}", key, listen))?;
+            builder = builder.protocol_listen(protocol, ip.to_string());
+        }
+    }
+    let dial = dial_fields_from_env()?;
+    if let Some(interface) = dial.bind_interface {
+        builder = builder.bind_interface(interface);
+    }
+    if let Some(mark) = dial.routing_mark {
+        builder = builder.routing_mark(mark);
+    }
+    if let Some(netns) = dial.netns {
+        builder = builder.netns(netns);
+    }
+
+    Ok(builder)
+}
+
+/// 解析路由标记（十进制整数或 0x 开头的十六进制）
+fn parse_routing_mark(raw: &str) -> Result<RoutingMark, String> {
+    let raw = raw.trim();
+    let mark = if raw.starts_with("0x") || raw.starts_with("0X") {
+        RoutingMark::from_hex(raw)
+    } else {
+        RoutingMark::from_int(
+            raw.parse()
+                .map_err(|_| format!("无效的路由标记: {}", raw))?,
+        )
+    };
+    if mark.as_u32().is_none() {
+        return Err(format!("无效的路由标记: {}", raw));
+    }
+    Ok(mark)
+}
+
+/// 入站与出站共用的绑定选项（EZ_BIND_INTERFACE、EZ_ROUTING_MARK、EZ_NETNS）
+/// 多出口服务器用于把协议流量固定在某条线路上
+fn dial_fields_from_env() -> Result<DialFields, String> {
+    let routing_mark = env_string("EZ_ROUTING_MARK")
+        .map(|raw| parse_routing_mark(&raw))
+        .transpose()
+        .map_err(|e| format!("EZ_ROUTING_MARK: {}", e))?;
+    Ok(DialFields {
+        bind_interface: env_string("EZ_BIND_INTERFACE"),
+        routing_mark,
+        netns: env_string("EZ_NETNS"),
+        ..Default::default()
     })
 }
 
+/// 解析 EZ_USERS（格式: `name[:password],name2[:password]`）
+fn parse_users(raw: &str) -> Vec<UserSpec> {
+    raw.split(',')
+        .filter_map(|entry| {
+            let (name, password) = match entry.split_once(':') {
+                Some((name, pwd)) => (name.trim(), Some(pwd.trim())),
+                None => (entry.trim(), None),
+            };
+            if name.is_empty() {
+                return None;
+            }
+            let password = password.filter(|p| !p.is_empty()).map(str::to_string);
+            Some(UserSpec::new(name, password))
+        })
+        .collect()
+}
+
 /// 选择客户端协议
-pub fn pick_client_protocol(result: &MultiProtocolResult) -> Option<ClientProtocol> {
+pub fn pick_client_protocol(result: &MultiProtocolResult) -> Option<Protocol> {
     if let Some(raw) = env_string("EZ_CLIENT_PROTOCOL") {
-        if let Some(p) = ClientProtocol::parse(&raw) {
+        if let Ok(p) = raw.parse::<Protocol>() {
             return Some(p);
         }
     }
 
     if result.anytls.is_some() {
-        return Some(ClientProtocol::AnyTls);
+        return Some(Protocol::AnyTls);
     }
     if result.hysteria2.is_some() {
-        return Some(ClientProtocol::Hysteria2);
+        return Some(Protocol::Hysteria2);
     }
     if result.tuic.is_some() {
-        return Some(ClientProtocol::Tuic);
+        return Some(Protocol::Tuic);
     }
     if result.vless_reality.is_some() {
-        return Some(ClientProtocol::VlessReality);
+        return Some(Protocol::VlessReality);
+    }
+    if result.vless_ws.is_some() {
+        return Some(Protocol::VlessWs);
+    }
+    if result.vless_grpc.is_some() {
+        return Some(Protocol::VlessGrpc);
     }
     None
 }
@@ -134,136 +561,407 @@ pub fn pick_user(users: &[GeneratedUser]) -> Option<&GeneratedUser> {
     users.first()
 }
 
+/// 获取指定协议的用户列表（协议未启用时返回空列表）
+pub fn protocol_users(result: &MultiProtocolResult, protocol: Protocol) -> Vec<GeneratedUser> {
+    match protocol {
+        Protocol::AnyTls => result
+            .anytls
+            .as_ref()
+            .map(|r| r.info.users.clone())
+            .unwrap_or_default(),
+        Protocol::Hysteria2 => result
+            .hysteria2
+            .as_ref()
+            .map(|r| r.info.users.clone())
+            .unwrap_or_default(),
+        Protocol::Tuic => result
+            .tuic
+            .as_ref()
+            .map(|r| r.info.users.clone())
+            .unwrap_or_default(),
+        Protocol::VlessReality => result
+            .vless_reality
+            .as_ref()
+            .map(|r| r.info.users.clone())
+            .unwrap_or_default(),
+        Protocol::VlessWs => result
+            .vless_ws
+            .as_ref()
+            .map(|r| r.info.users.clone())
+            .unwrap_or_default(),
+        Protocol::VlessGrpc => result
+            .vless_grpc
+            .as_ref()
+            .map(|r| r.info.users.clone())
+            .unwrap_or_default(),
+    }
+}
+
+/// 获取指定协议入站的标签（协议未启用时返回 None）
+pub fn protocol_tag(result: &MultiProtocolResult, protocol: Protocol) -> Option<String> {
+    match protocol {
+        Protocol::AnyTls => result.anytls.as_ref().map(|r| r.inbound.tag.clone()),
+        Protocol::Hysteria2 => result.hysteria2.as_ref().map(|r| r.inbound.tag.clone()),
+        Protocol::Tuic => result.tuic.as_ref().map(|r| r.inbound.tag.clone()),
+        Protocol::VlessReality => result.vless_reality.as_ref().map(|r| r.inbound.tag.clone()),
+        Protocol::VlessWs => result.vless_ws.as_ref().map(|r| r.inbound.tag.clone()),
+        Protocol::VlessGrpc => result.vless_grpc.as_ref().map(|r| r.inbound.tag.clone()),
+    }
+}
+
+/// 获取指定协议入站的监听端口（协议未启用时返回 None）
+pub fn protocol_port(result: &MultiProtocolResult, protocol: Protocol) -> Option<u16> {
+    match protocol {
+        Protocol::AnyTls => result.anytls.as_ref().map(|r| r.info.port),
+        Protocol::Hysteria2 => result.hysteria2.as_ref().map(|r| r.info.port),
+        Protocol::Tuic => result.tuic.as_ref().map(|r| r.info.port),
+        Protocol::VlessReality => result.vless_reality.as_ref().map(|r| r.info.port),
+        Protocol::VlessWs => result.vless_ws.as_ref().map(|r| r.info.port),
+        Protocol::VlessGrpc => result.vless_grpc.as_ref().map(|r| r.info.port),
+    }
+}
+
+/// 按需检测 REALITY 握手目标
+/// EZ_REALITY_CHECK_TARGET=true 时仅在目标不合适时告警；
+/// EZ_REALITY_AUTO_TARGET=true 时未指定目标或目标不合适则从内置候选中选择耗时最短的一个
+fn resolve_reality_target(server: Option<String>, port: u16) -> (String, u16) {
+    let auto = env_bool("EZ_REALITY_AUTO_TARGET", false);
+    let check = auto || env_bool("EZ_REALITY_CHECK_TARGET", false);
+    let fallback = (
+        server
+            .clone()
+            .unwrap_or_else(|| "www.microsoft.com".to_string()),
+        port,
+    );
+    if !check {
+        return fallback;
+    }
+
+    if let Some(ref server) = server {
+        match check_reality_target(server, port, DEFAULT_TARGET_TIMEOUT) {
+            Ok(health) if health.is_suitable() => return fallback,
+            Ok(health) => warn!(%health, "REALITY 握手目标不支持 TLS 1.3 或 HTTP/2"),
+            Err(e) => warn!(server, port, error = %e, "REALITY 握手目标检测失败"),
+        }
+    }
+    if !auto {
+        return fallback;
+    }
+
+    match pick_reality_target(REALITY_TARGET_CANDIDATES, DEFAULT_TARGET_TIMEOUT) {
+        Some(health) => {
+            info!(%health, "自动选择 REALITY 握手目标");
+            (health.server, health.port)
+        }
+        None => {
+            warn!(
+                server = %fallback.0,
+                port = fallback.1,
+                "内置候选中没有可用的 REALITY 握手目标，使用默认目标"
+            );
+            fallback
+        }
+    }
+}
+
+/// 从环境变量构建 ACME DNS-01 挑战配置
+fn dns01_challenge_from_env(provider: &str) -> Result<Dns01Challenge, String> {
+    let require = |key: &str| {
+        env_string(key)
+            .ok_or_else(|| format!("EZ_ACME_DNS01_PROVIDER={} 需要设置 {}", provider, key))
+    };
+    match provider.trim().to_ascii_lowercase().as_str() {
+        "cloudflare" => Ok(Dns01Challenge::cloudflare(require("EZ_CF_API_TOKEN")?)),
+        "alidns" => {
+            let mut config = AliDnsConfig::new(
+                require("EZ_ALIDNS_ACCESS_KEY_ID")?,
+                require("EZ_ALIDNS_ACCESS_KEY_SECRET")?,
+            );
+            if let Some(region) = env_string("EZ_ALIDNS_REGION_ID") {
+                config = config.with_region(region);
+            }
+            Ok(Dns01Challenge::AliDns(config))
+        }
+        other => Err(format!("不支持的 DNS-01 提供商: {}", other)),
+    }
+}
+
+/// 读取时间间隔类型的环境变量（如 3s、1m30s、1.5h，纯数字为秒）
+fn env_duration(key: &str) -> Result<Option<Duration>, String> {
+    env_string(key)
+        .map(|raw| {
+            raw.parse::<Duration>()
+                .map_err(|e| format!("{} 取值无效: {}", key, e))
+        })
+        .transpose()
+}
+
+/// 读取监听地址类型的环境变量（ip:port）
+fn env_socket_addr(key: &str) -> Result<Option<SocketAddr>, String> {
+    env_string(key)
+        .map(|raw| {
+            raw.parse::<SocketAddr>()
+                .map_err(|_| format!("{} 取值无效: {}（格式 ip:port）", key, raw))
+        })
+        .transpose()
+}
+
+/// 从环境变量构建客户端多路复用设置（EZ_MUX_PROTOCOL / EZ_MUX_MAX_STREAMS / EZ_MUX_PADDING）
+/// 均未设置时不启用
+fn multiplex_from_env() -> Result<Option<MultiplexOutbound>, String> {
+    let protocol = env_string("EZ_MUX_PROTOCOL")
+        .map(|raw| raw.parse::<MultiplexProtocol>())
+        .transpose()?;
+    let max_streams = env_u32("EZ_MUX_MAX_STREAMS");
+    let padding = env_bool("EZ_MUX_PADDING", false);
+    if protocol.is_none() && max_streams.is_none() && !padding {
+        return Ok(None);
+    }
+
+    let mut multiplex = MultiplexOutbound::new()
+        .enabled()
+        .with_protocol(protocol.unwrap_or_default());
+    if let Some(max) = max_streams {
+        multiplex = multiplex.with_max_streams(max);
+    }
+    if padding {
+        multiplex = multiplex.with_padding(true);
+    }
+    Ok(Some(multiplex))
+}
+
+/// 读取客户端 uTLS 指纹（EZ_CLIENT_UTLS_FINGERPRINT，默认 chrome）
+fn client_utls_fingerprint() -> Result<UtlsFingerprint, String> {
+    env_string("EZ_CLIENT_UTLS_FINGERPRINT")
+        .map(|raw| {
+            raw.parse::<UtlsFingerprint>()
+                .map_err(|e| format!("EZ_CLIENT_UTLS_FINGERPRINT 取值无效: {}", e))
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// 从环境变量构建 Hysteria2 伪装配置
+/// 未设置 EZ_HY2_MASQUERADE_TYPE 时根据 URL / DIR / STATUS 推断类型
+fn hy2_masquerade_from_env() -> Result<Option<Hysteria2Masquerade>, String> {
+    let url = env_string("EZ_HY2_MASQUERADE_URL");
+    let dir = env_string("EZ_HY2_MASQUERADE_DIR");
+    let status = env_u16("EZ_HY2_MASQUERADE_STATUS");
+
+    let kind = match env_string("EZ_HY2_MASQUERADE_TYPE") {
+        Some(raw) => raw.parse::<MasqueradeType>()?,
+        None if url.is_some() => MasqueradeType::Proxy,
+        None if dir.is_some() => MasqueradeType::File,
+        None if status.is_some() => MasqueradeType::String,
+        None => return Ok(None),
+    };
+    let require = |value: Option<String>, key: &str, kind: &str| {
+        value.ok_or_else(|| format!("EZ_HY2_MASQUERADE_TYPE={} 需要设置 {}", kind, key))
+    };
+    let masquerade = match kind {
+        MasqueradeType::Proxy => {
+            Hysteria2Masquerade::proxy(require(url, "EZ_HY2_MASQUERADE_URL", "proxy")?, true)
+        }
+        MasqueradeType::File => {
+            Hysteria2Masquerade::file(require(dir, "EZ_HY2_MASQUERADE_DIR", "file")?)
+        }
+        MasqueradeType::String => Hysteria2Masquerade::string(status.unwrap_or(404), None),
+    };
+    Ok(Some(masquerade))
+}
+
+/// 协议对应的环境变量后缀（如 vless-ws → VLESS_WS）
+pub fn protocol_env_suffix(protocol: Protocol) -> String {
+    protocol.as_str().replace('-', "_").to_ascii_uppercase()
+}
+
+/// 读取按协议覆盖的客户端参数：优先 `<KEY>_<协议>`，其次 `<KEY>`
+fn client_override(key: &str, protocol: Protocol) -> Option<String> {
+    env_string(&format!("{}_{}", key, protocol_env_suffix(protocol))).or_else(|| env_string(key))
+}
+
+/// 客户端连接的服务器地址
+/// 默认 VLESS Reality 使用公网 IP、VLESS WebSocket / gRPC 使用 CDN 地址（若有）、其余协议使用域名，
+/// 可由 EZ_CLIENT_SERVER_OVERRIDE[_<协议>] 覆盖（如服务器位于 CDN 之后或入口 IP 与检测到的不同）
+pub fn client_server(result: &MultiProtocolResult, protocol: Protocol) -> String {
+    let cdn_host = match protocol {
+        Protocol::VlessWs => result.vless_ws.as_ref(),
+        Protocol::VlessGrpc => result.vless_grpc.as_ref(),
+        _ => None,
+    }
+    .and_then(|vless| vless.cdn_host.clone());
+    client_override("EZ_CLIENT_SERVER_OVERRIDE", protocol)
+        .or(cdn_host)
+        .unwrap_or_else(|| {
+            if protocol == Protocol::VlessReality {
+                result.public_ip.to_string()
+            } else {
+                result.domain.clone()
+            }
+        })
+}
+
+/// 客户端使用的 SNI（WebSocket 未指定 Host 时同此），默认域名，可由 EZ_SNI_OVERRIDE[_<协议>] 覆盖
+/// VLESS Reality 的 SNI 必须是握手服务器，不使用此值
+pub fn client_sni(result: &MultiProtocolResult, protocol: Protocol) -> String {
+    client_override("EZ_SNI_OVERRIDE", protocol).unwrap_or_else(|| result.domain.clone())
+}
+
 /// 构建代理出站 JSON
+/// 由 singboxconfig::outbound 中的出站结构序列化，字段与模型保持一致
 pub fn build_proxy_outbound_json(
     result: &MultiProtocolResult,
-    protocol: ClientProtocol,
+    protocol: Protocol,
     user: &GeneratedUser,
 ) -> Result<serde_json::Value, String> {
-    let domain = &result.domain;
-    let tls = serde_json::json!({
-        "enabled": true,
-        "server_name": domain
-    });
+    let server = client_server(result, protocol);
+    let sni = client_sni(result, protocol);
+    let tls = OutboundTlsConfig {
+        enabled: Some(true),
+        server_name: Some(sni.clone()),
+        ..Default::default()
+    };
+    // uTLS 只作用于基于 TCP 的 TLS（AnyTLS、VLESS），QUIC 协议（Hysteria2、TUIC）不支持
+    let utls_tls = OutboundTlsConfig {
+        utls: Some(UtlsConfig::new(client_utls_fingerprint()?)),
+        ..tls.clone()
+    };
+    let uuid = || {
+        user.uuid
+            .clone()
+            .ok_or_else(|| format!("{} 用户缺少 UUID", protocol_label(protocol)))
+    };
 
-    match protocol {
-        ClientProtocol::AnyTls => {
+    let outbound = match protocol {
+        Protocol::AnyTls => {
             let anytls = result
                 .anytls
                 .as_ref()
                 .ok_or_else(|| "AnyTLS 未启用".to_string())?;
-            Ok(serde_json::json!({
-                "type": "anytls",
-                "tag": "proxy",
-                "server": domain,
-                "server_port": anytls.info.port,
-                "password": user.password,
-                "tls": tls
-            }))
+            let outbound = AnyTlsOutbound::new("proxy", server, anytls.info.port, &user.password)
+                .with_tls(utls_tls);
+            serde_json::to_value(outbound)
         }
-        ClientProtocol::Hysteria2 => {
+        Protocol::Hysteria2 => {
             let hy2 = result
                 .hysteria2
                 .as_ref()
                 .ok_or_else(|| "Hysteria2 未启用".to_string())?;
-            let mut v = serde_json::json!({
-                "type": "hysteria2",
-                "tag": "proxy",
-                "server": domain,
-                "server_port": hy2.info.port,
-                "password": user.password,
-                "tls": {
-                    "enabled": true,
-                    "server_name": domain,
-                    "alpn": ["h3"]
-                }
-            });
-
-            if env_bool("EZ_HY2_OBFS", false) {
-                if let Some(ref pwd) = hy2.obfs_password {
-                    v["obfs"] = serde_json::json!({
-                        "type": "salamander",
-                        "password": pwd
-                    });
-                }
+            let mut outbound =
+                Hysteria2Outbound::new("proxy", server, hy2.info.port, &user.password).with_tls(
+                    OutboundTlsConfig {
+                        alpn: Some(vec!["h3".to_string()]),
+                        ..tls
+                    },
+                );
+
+            // 服务端启用混淆（EZ_HY2_OBFS 或预设）时才有混淆密码
+            if let Some(ref pwd) = hy2.obfs_password {
+                outbound = outbound.with_obfs(pwd);
             }
             if let (Some(up), Some(down)) = (env_u32("EZ_HY2_UP_MBPS"), env_u32("EZ_HY2_DOWN_MBPS"))
             {
-                v["up_mbps"] = serde_json::json!(up);
-                v["down_mbps"] = serde_json::json!(down);
+                outbound = outbound.with_bandwidth(up, down);
             }
-            Ok(v)
+            serde_json::to_value(outbound)
         }
-        ClientProtocol::Tuic => {
+        Protocol::Tuic => {
             let tuic = result
                 .tuic
                 .as_ref()
                 .ok_or_else(|| "TUIC 未启用".to_string())?;
-            let uuid = user
-                .uuid
-                .as_ref()
-                .ok_or_else(|| "TUIC 用户缺少 UUID".to_string())?;
-
-            let mut v = serde_json::json!({
-                "type": "tuic",
-                "tag": "proxy",
-                "server": domain,
-                "server_port": tuic.info.port,
-                "uuid": uuid,
-                "password": user.password,
-                "tls": tls
-            });
+            let mut outbound =
+                TuicOutbound::new("proxy", server, tuic.info.port, uuid()?, &user.password)
+                    .with_tls(tls)
+                    .with_udp_relay_mode(tuic.udp_relay_mode);
 
+            // 无法识别的拥塞控制算法忽略，使用 sing-box 默认值
             if let Some(cc) = env_string("EZ_TUIC_CC") {
-                let cc = cc.trim().to_ascii_lowercase();
-                if matches!(cc.as_str(), "bbr" | "cubic" | "new_reno" | "newreno") {
-                    let normalized = if cc == "newreno" {
-                        "new_reno"
-                    } else {
-                        cc.as_str()
-                    };
-                    v["congestion_control"] = serde_json::json!(normalized);
+                let cc = cc
+                    .trim()
+                    .to_ascii_lowercase()
+                    .replace("newreno", "new_reno");
+                if let Ok(cc) = serde_json::from_value::<CongestionControl>(cc.into()) {
+                    outbound = outbound.with_congestion_control(cc);
                 }
             }
-            Ok(v)
+            if tuic.inbound.zero_rtt_handshake == Some(true) {
+                outbound = outbound.with_zero_rtt_handshake(true);
+            }
+            if let Some(heartbeat) = tuic.inbound.heartbeat.clone() {
+                outbound = outbound.with_heartbeat(heartbeat);
+            }
+            serde_json::to_value(outbound)
         }
-        ClientProtocol::VlessReality => {
+        Protocol::VlessReality => {
             let vless = result
                 .vless_reality
                 .as_ref()
                 .ok_or_else(|| "VLESS Reality 未启用".to_string())?;
-            let uuid = user
-                .uuid
-                .as_ref()
-                .ok_or_else(|| "VLESS用户缺少 UUID".to_string())?;
-
-            // VLESS Reality 客户端配置
-            Ok(serde_json::json!({
-                "type": "vless",
-                "tag": "proxy",
-                "server": result.public_ip.to_string(),
-                "server_port": vless.info.port,
-                "uuid": uuid,
-                "flow": "xtls-rprx-vision",
-                "tls": {
-                    "enabled": true,
-                    "server_name": vless.handshake_server,
-                    "utls": {
-                        "enabled": true,
-                        "fingerprint": "chrome"
-                    },
-                    "reality": {
-                        "enabled": true,
-                        "public_key": vless.public_key,
-                        "short_id": vless.short_id
-                    }
-                }
-            }))
+
+            // VLESS Reality 的 SNI 为握手服务器
+            let outbound = VlessOutbound::new("proxy", server, vless.info.port, uuid()?)
+                .with_xtls_vision()
+                .with_tls(OutboundTlsConfig {
+                    server_name: Some(vless.handshake_server.clone()),
+                    reality: Some(RealityOutboundConfig {
+                        enabled: Some(true),
+                        public_key: Some(vless.public_key.clone()),
+                        short_id: Some(vless.short_id.clone()),
+                    }),
+                    ..utls_tls
+                });
+            serde_json::to_value(outbound)
         }
-    }
+        Protocol::VlessWs | Protocol::VlessGrpc => {
+            let (vless, transport) = if protocol == Protocol::VlessWs {
+                let vless = result
+                    .vless_ws
+                    .as_ref()
+                    .ok_or_else(|| "VLESS WebSocket 未启用".to_string())?;
+                let transport = WebSocketTransport::new()
+                    .with_path(&vless.path)
+                    .add_header("Host", vless.host.as_deref().unwrap_or(&sni));
+                (vless, V2RayTransport::Ws(transport))
+            } else {
+                let vless = result
+                    .vless_grpc
+                    .as_ref()
+                    .ok_or_else(|| "VLESS gRPC 未启用".to_string())?;
+                let transport = GrpcTransport::new().with_service_name(&vless.path);
+                (vless, V2RayTransport::Grpc(transport))
+            };
+            let mut outbound = VlessOutbound::new("proxy", server, vless.info.port, uuid()?)
+                .with_tls(utls_tls)
+                .with_transport(transport);
+
+            // 服务端接受多路复用时（含 TCP Brutal），客户端使用对应的多路复用设置
+            if let Some(ref multiplex) = vless.multiplex {
+                outbound = outbound.with_multiplex(multiplex.clone());
+            }
+            serde_json::to_value(outbound)
+        }
+    };
+    outbound.map_err(|e| e.to_string())
+}
+
+/// 链式代理前置节点的出站标签
+const CHAIN_FRONT_TAG: &str = "chain-front";
+
+/// 读取 EZ_CLIENT_CHAIN_FRONT 指定的前置节点（socks:// 或其他分享链接）
+/// 代理出站经该节点连接服务器（proxy → chain-front → 服务器）
+fn client_chain_front_from_env() -> Result<Option<serde_json::Value>, String> {
+    let Some(link) = env_string("EZ_CLIENT_CHAIN_FRONT") else {
+        return Ok(None);
+    };
+    let mut front =
+        parse_share_link(&link).map_err(|e| format!("EZ_CLIENT_CHAIN_FRONT 无效: {}", e))?;
+    front["tag"] = serde_json::json!(CHAIN_FRONT_TAG);
+    Ok(Some(front))
 }
 
 /// 生成客户端配置 JSON
+/// 设置了 EZ_CLIENT_CHAIN_FRONT 时代理出站经前置节点连接（detour）
 pub fn generate_client_config_json(
     result: &MultiProtocolResult,
     log_level: &str,
@@ -271,37 +969,10 @@ pub fn generate_client_config_json(
     let protocol =
         pick_client_protocol(result).ok_or_else(|| "没有可用协议用于生成客户端配置".to_string())?;
 
-    let users: Vec<GeneratedUser> = match protocol {
-        ClientProtocol::AnyTls => result
-            .anytls
-            .as_ref()
-            .map(|r| r.info.users.clone())
-            .unwrap_or_default(),
-        ClientProtocol::Hysteria2 => result
-            .hysteria2
-            .as_ref()
-            .map(|r| r.info.users.clone())
-            .unwrap_or_default(),
-        ClientProtocol::Tuic => result
-            .tuic
-            .as_ref()
-            .map(|r| r.info.users.clone())
-            .unwrap_or_default(),
-        ClientProtocol::VlessReality => result
-            .vless_reality
-            .as_ref()
-            .map(|r| r.info.users.clone())
-            .unwrap_or_default(),
-    };
+    let users = protocol_users(result, protocol);
     let user = pick_user(&users).ok_or_else(|| "没有可用用户用于生成客户端配置".to_string())?;
 
-    let proxy = build_proxy_outbound_json(result, protocol, user)?;
-    let mixed_listen =
-        env_string("EZ_CLIENT_MIXED_LISTEN").unwrap_or_else(|| "127.0.0.1".to_string());
-    let mixed_port = env_u16("EZ_CLIENT_MIXED_PORT").unwrap_or(7890);
-
-    let cfg = SingBoxConfig::client_default(proxy, log_level, &mixed_listen, mixed_port);
-    let json = cfg.to_pretty_json_string().map_err(|e| e.to_string())?;
+    let json = client_config_json_for(result, log_level, protocol, user)?;
     let profile_name = format!(
         "ezsingbox-{}-{}@{}",
         protocol.as_str(),
@@ -311,38 +982,1123 @@ pub fn generate_client_config_json(
     Ok((json, profile_name))
 }
 
-/// 生成服务端配置 JSON
-pub fn generate_config_json(
+/// 生成指定用户的客户端配置 JSON（协议同 generate_client_config_json）
+pub fn generate_user_client_config_json(
     result: &MultiProtocolResult,
     log_level: &str,
+    user_name: &str,
 ) -> Result<String, String> {
-    let mut inbounds = Vec::new();
-    if let Some(ref anytls) = result.anytls {
-        inbounds.push(serde_json::to_value(&anytls.inbound).map_err(|e| e.to_string())?);
-    }
-    if let Some(ref hy2) = result.hysteria2 {
-        inbounds.push(serde_json::to_value(&hy2.inbound).map_err(|e| e.to_string())?);
-    }
-    if let Some(ref tuic) = result.tuic {
-        inbounds.push(serde_json::to_value(&tuic.inbound).map_err(|e| e.to_string())?);
-    }
-    if let Some(ref vless) = result.vless_reality {
-        inbounds.push(serde_json::to_value(&vless.inbound).map_err(|e| e.to_string())?);
-    }
+    let protocol =
+        pick_client_protocol(result).ok_or_else(|| "没有可用协议用于生成客户端配置".to_string())?;
+    let users = protocol_users(result, protocol);
+    let user = users
+        .iter()
+        .find(|u| u.name == user_name)
+        .ok_or_else(|| format!("用户不存在: {}", user_name))?;
+    client_config_json_for(result, log_level, protocol, user)
+}
 
-    let cfg = SingBoxConfig::server_default(inbounds, log_level);
-    cfg.to_pretty_json_string().map_err(|e| e.to_string())
+/// 生成指定协议与用户的客户端配置 JSON
+fn client_config_json_for(
+    result: &MultiProtocolResult,
+    log_level: &str,
+    protocol: Protocol,
+    user: &GeneratedUser,
+) -> Result<String, String> {
+    let mut proxy = build_proxy_outbound_json(result, protocol, user)?;
+    let front = client_chain_front_from_env()?;
+    if front.is_some() {
+        proxy["detour"] = serde_json::json!(CHAIN_FRONT_TAG);
+    }
+    assemble_client_config(log_level, vec![proxy], front)
 }
 
-/// 打印详细信息
-pub fn print_details(result: &MultiProtocolResult) {
-    println!("\n==== 详细信息 (包含敏感信息) ====");
-    println!("公网 IP: {}", result.public_ip);
-    println!("域名: {}", result.domain);
+/// EZ_CLIENT_CONFIG_PATH 为目录时使用的文件名模式
+pub const CLIENT_DIR_PATTERN: &str = "client-{protocol}-{user}.json";
 
-    let domain = &result.domain;
-    let public_ip_str = result.public_ip.to_string();
+/// 客户端配置输出位置（EZ_CLIENT_CONFIG_PATH）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientOutput {
+    /// 单个文件，协议与用户由 EZ_CLIENT_PROTOCOL / EZ_CLIENT_USER 选择
+    Single(String),
+    /// 路径模式，`{protocol}` / `{user}` 按启用的协议与用户展开，每个组合一个文件
+    Pattern(String),
+}
 
+/// 从环境变量读取客户端配置输出位置
+/// 路径包含 `{protocol}` 或 `{user}` 时为模式；以 `/` 结尾或为已有目录时
+/// 在目录下按 [`CLIENT_DIR_PATTERN`] 输出所有协议与用户的组合
+pub fn client_output_from_env() -> Option<ClientOutput> {
+    let path = env_string("EZ_CLIENT_CONFIG_PATH")?;
+    if path.contains("{protocol}") || path.contains("{user}") {
+        return Some(ClientOutput::Pattern(path));
+    }
+    if path.ends_with('/') || Path::new(&path).is_dir() {
+        let pattern = Path::new(&path).join(CLIENT_DIR_PATTERN);
+        return Some(ClientOutput::Pattern(
+            pattern.to_string_lossy().into_owned(),
+        ));
+    }
+    Some(ClientOutput::Single(path))
+}
+
+/// 按路径模式生成客户端配置，返回（路径, JSON）列表
+/// 模式中不含 `{protocol}` 时只使用选定的协议，不含 `{user}` 时只使用选定的用户
+pub fn generate_client_config_variants(
+    result: &MultiProtocolResult,
+    log_level: &str,
+    pattern: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let protocols: Vec<Protocol> = if pattern.contains("{protocol}") {
+        Protocol::ALL
+            .into_iter()
+            .filter(|p| !protocol_users(result, *p).is_empty())
+            .collect()
+    } else {
+        pick_client_protocol(result).into_iter().collect()
+    };
+
+    let mut variants = Vec::new();
+    for protocol in protocols {
+        let users = protocol_users(result, protocol);
+        let selected: Vec<&GeneratedUser> = if pattern.contains("{user}") {
+            users.iter().collect()
+        } else {
+            pick_user(&users).into_iter().collect()
+        };
+        for user in selected {
+            // 用户名可能包含路径分隔符等字符，写入文件名前替换
+            let file_user: String = user
+                .name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let path = pattern
+                .replace("{protocol}", protocol.as_str())
+                .replace("{user}", &file_user);
+            if variants.iter().any(|(p, _)| *p == path) {
+                return Err(format!("客户端配置路径重复: {}（用户名替换后相同）", path));
+            }
+            let json = client_config_json_for(result, log_level, protocol, user)?;
+            variants.push((path, json));
+        }
+    }
+    if variants.is_empty() {
+        return Err("没有可用协议用于生成客户端配置".to_string());
+    }
+    Ok(variants)
+}
+
+/// 生成多服务器客户端配置 JSON
+/// 每个节点一个出站（标签为节点名），由标签为 proxy 的 selector 出站选择，默认第一个节点
+pub fn generate_fleet_client_config_json(
+    nodes: &[(String, MultiProtocolResult)],
+    log_level: &str,
+) -> Result<String, String> {
+    if nodes.is_empty() {
+        return Err("服务器列表为空".to_string());
+    }
+    let mut outbounds = Vec::new();
+    for (name, result) in nodes {
+        let protocol = pick_client_protocol(result)
+            .ok_or_else(|| format!("节点 {} 没有可用协议用于生成客户端配置", name))?;
+        let users = protocol_users(result, protocol);
+        let user = pick_user(&users)
+            .ok_or_else(|| format!("节点 {} 没有可用用户用于生成客户端配置", name))?;
+        let mut outbound = build_proxy_outbound_json(result, protocol, user)?;
+        outbound["tag"] = serde_json::json!(name);
+        outbounds.push(outbound);
+    }
+    selector_client_config(log_level, outbounds)
+}
+
+/// 导入的节点出站在客户端配置中不可使用的标签
+pub const CLIENT_RESERVED_TAGS: [&str; 4] = [
+    "proxy",
+    CHAIN_FRONT_TAG,
+    DirectOutbound::DEFAULT_TAG,
+    BlockOutbound::DEFAULT_TAG,
+];
+
+/// 用导入的节点出站生成客户端配置 JSON（节点选择方式同 generate_fleet_client_config_json）
+/// 出站标签须互不重复且不在 [`CLIENT_RESERVED_TAGS`] 中
+pub fn generate_imported_client_config_json(
+    outbounds: Vec<serde_json::Value>,
+    log_level: &str,
+) -> Result<String, String> {
+    if outbounds.is_empty() {
+        return Err("没有可用的节点出站".to_string());
+    }
+    selector_client_config(log_level, outbounds)
+}
+
+/// 节点出站前插入标签为 proxy 的 selector（默认第一个节点）后组装客户端配置
+/// 设置了 EZ_CLIENT_CHAIN_FRONT 时每个节点都经前置节点连接
+fn selector_client_config(
+    log_level: &str,
+    mut outbounds: Vec<serde_json::Value>,
+) -> Result<String, String> {
+    let front = client_chain_front_from_env()?;
+    if front.is_some() {
+        for outbound in &mut outbounds {
+            outbound["detour"] = serde_json::json!(CHAIN_FRONT_TAG);
+        }
+    }
+    let names: Vec<serde_json::Value> = outbounds.iter().map(|o| o["tag"].clone()).collect();
+    let selector = serde_json::json!({
+        "type": "selector",
+        "tag": "proxy",
+        "outbounds": names,
+        "default": names[0]
+    });
+    outbounds.insert(0, selector);
+    assemble_client_config(log_level, outbounds, front)
+}
+
+/// 组装客户端配置：mixed 入站、代理出站（第一个标签须为 proxy）、前置节点、direct 出站与客户端 DNS
+/// 拦截统一使用 reject 路由动作，仅在转换为 1.11 之前的格式时补充 block 出站
+fn assemble_client_config(
+    log_level: &str,
+    proxies: Vec<serde_json::Value>,
+    front: Option<serde_json::Value>,
+) -> Result<String, String> {
+    let mixed_listen =
+        env_string("EZ_CLIENT_MIXED_LISTEN").unwrap_or_else(|| "127.0.0.1".to_string());
+    let mixed_port = env_u16("EZ_CLIENT_MIXED_PORT").unwrap_or(7890);
+
+    let platform = client_platform_from_env()?;
+    let tun = client_tun_from_env(platform)?;
+    let client_dns = client_dns_from_env()?;
+    let route_rules = client_dns
+        .route_rules
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let rule_sets = client_dns
+        .rule_sets
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // 远程上游经代理查询，代理服务器地址必须由本地解析器解析
+    let mut builder = SingBoxConfig::builder()
+        .log_level(log_level)
+        .dns(&client_dns.dns);
+    if let Some(tun) = tun {
+        builder = builder.add_inbound(tun);
+    }
+    if platform.uses_mixed_inbound() {
+        builder = builder.add_inbound(serde_json::json!({
+            "type": "mixed",
+            "tag": "mixed-in",
+            "listen": mixed_listen,
+            "listen_port": mixed_port
+        }));
+    }
+    if let Some(experimental) = platform.experimental() {
+        builder = builder.experimental(experimental);
+    }
+    let mut route = serde_json::json!({
+        "rules": [],
+        "default_domain_resolver": LOCAL_DNS_TAG,
+        "final": "proxy"
+    });
+    if platform.auto_detect_interface() {
+        route["auto_detect_interface"] = true.into();
+    }
+    for proxy in proxies {
+        builder = builder.add_outbound(proxy);
+    }
+    if let Some(front) = front {
+        builder = builder.add_outbound(front);
+    }
+    let mut cfg = builder
+        .add_outbound(DirectOutbound::default())
+        .route(route)
+        .build()
+        .map_err(diagnostics_error)?
+        .with_route_rules(route_rules)
+        .with_rule_sets(rule_sets);
+    if let Some(version) = singbox_version_from_env()? {
+        cfg = cfg.for_version(version)?;
+    }
+    render_config(&cfg)
+}
+
+/// 生成延迟测试使用的临时客户端配置
+/// 每个协议一个出站，并通过各自的本地 mixed 入站（127.0.0.1:端口）路由到该出站
+pub fn generate_urltest_config_json(
+    result: &MultiProtocolResult,
+    listeners: &[(Protocol, u16)],
+) -> Result<String, String> {
+    let mut builder = SingBoxConfig::builder()
+        .log_level("error")
+        .dns(Dns::new().add_server(DnsServer::Local(LocalDnsServer::new().tag(LOCAL_DNS_TAG))));
+    let mut rules = Vec::new();
+    for &(protocol, port) in listeners {
+        let users = protocol_users(result, protocol);
+        let user = pick_user(&users).ok_or_else(|| format!("{} 没有可用用户", protocol))?;
+        let mut outbound = build_proxy_outbound_json(result, protocol, user)?;
+        outbound["tag"] = serde_json::json!(protocol.as_str());
+        let inbound_tag = format!("test-{}", protocol.as_str());
+        builder = builder
+            .add_inbound(serde_json::json!({
+                "type": "mixed",
+                "tag": inbound_tag,
+                "listen": "127.0.0.1",
+                "listen_port": port
+            }))
+            .add_outbound(outbound);
+        rules.push(serde_json::json!({
+            "inbound": [inbound_tag],
+            "action": "route",
+            "outbound": protocol.as_str()
+        }));
+    }
+    let first = listeners
+        .first()
+        .ok_or_else(|| "没有可用协议用于延迟测试".to_string())?;
+
+    let mut cfg = builder
+        .route(serde_json::json!({
+            "rules": rules,
+            "default_domain_resolver": LOCAL_DNS_TAG,
+            "final": first.0.as_str()
+        }))
+        .build()
+        .map_err(diagnostics_error)?;
+    if let Some(version) = singbox_version_from_env()? {
+        cfg = cfg.for_version(version)?;
+    }
+    render_config(&cfg)
+}
+
+/// 生成对等节点出站 JSON
+/// 为每个已启用协议的每个用户输出一个出站对象，可直接放入另一台 sing-box 服务端的 outbounds 中
+pub fn generate_peer_outbounds_json(result: &MultiProtocolResult) -> Result<String, String> {
+    let mut outbounds = Vec::new();
+    for protocol in Protocol::ALL {
+        for user in protocol_users(result, protocol) {
+            let mut outbound = build_proxy_outbound_json(result, protocol, &user)?;
+            outbound["tag"] =
+                serde_json::json!(format!("{}-{}@{}", protocol, user.name, result.domain));
+            outbounds.push(outbound);
+        }
+    }
+    if outbounds.is_empty() {
+        return Err("没有可用协议用于导出对等出站".to_string());
+    }
+    let mut outbounds = serde_json::Value::Array(outbounds);
+    if minimal_config() {
+        let mut wrapper = serde_json::json!({ "outbounds": outbounds });
+        minimize_config(&mut wrapper);
+        outbounds = wrapper["outbounds"].take();
+    }
+    if canonical_output() {
+        return Ok(to_canonical_string(&outbounds));
+    }
+    serde_json::to_string_pretty(&outbounds).map_err(|e| e.to_string())
+}
+
+/// 是否使用规范化字段顺序输出（EZ_CANONICAL_OUTPUT）
+fn canonical_output() -> bool {
+    env_bool("EZ_CANONICAL_OUTPUT", false)
+}
+
+/// 是否去掉与 sing-box 默认值相同的字段（EZ_MINIMAL_CONFIG）
+fn minimal_config() -> bool {
+    env_bool("EZ_MINIMAL_CONFIG", false)
+}
+
+/// 序列化完整配置
+fn render_config(cfg: &SingBoxConfig) -> Result<String, String> {
+    let minimized;
+    let cfg = if minimal_config() {
+        minimized = cfg.minimized().map_err(|e| e.to_string())?;
+        &minimized
+    } else {
+        cfg
+    };
+    let json = if canonical_output() {
+        cfg.to_canonical_json_string()
+    } else {
+        cfg.to_pretty_json_string()
+    };
+    json.map_err(|e| e.to_string())
+}
+
+/// 中转模式的上游出站标签
+const RELAY_TAG: &str = "relay";
+
+/// 读取 EZ_RELAY_UPSTREAM 指定的中转上游（分享链接，或 JSON 格式的 sing-box 出站）
+fn relay_upstream_from_env() -> Result<Option<serde_json::Value>, String> {
+    let Some(raw) = env_string("EZ_RELAY_UPSTREAM") else {
+        return Ok(None);
+    };
+    let mut upstream = if raw.trim_start().starts_with('{') {
+        serde_json::from_str::<serde_json::Value>(&raw)
+            .map_err(|e| format!("EZ_RELAY_UPSTREAM 不是有效的 JSON: {}", e))?
+    } else {
+        parse_share_link(&raw).map_err(|e| format!("EZ_RELAY_UPSTREAM 无效: {}", e))?
+    };
+    if !upstream["type"].is_string() {
+        return Err("EZ_RELAY_UPSTREAM 出站缺少 type 字段".to_string());
+    }
+    upstream["tag"] = serde_json::json!(RELAY_TAG);
+    // 绑定选项只补充上游未设置的字段
+    if let (Some(fields), serde_json::Value::Object(dial)) = (
+        upstream.as_object_mut(),
+        serde_json::to_value(dial_fields_from_env()?).map_err(|e| e.to_string())?,
+    ) {
+        for (key, value) in dial {
+            fields.entry(key).or_insert(value);
+        }
+    }
+    Ok(Some(upstream))
+}
+
+/// 生成服务端配置 JSON
+/// 设置了 EZ_RELAY_UPSTREAM 时作为中转节点，流量默认转发到上游出站；
+/// 设置了 EZ_WARP_PRIVATE_KEY 时添加 WARP 端点，按规则分流
+pub fn generate_config_json(
+    result: &MultiProtocolResult,
+    log_level: &str,
+) -> Result<String, String> {
+    let mut builder = SingBoxConfig::builder().log_level(log_level);
+    if let Some(ref anytls) = result.anytls {
+        builder = builder.add_inbound(&anytls.inbound);
+    }
+    if let Some(ref hy2) = result.hysteria2 {
+        builder = builder.add_inbound(&hy2.inbound);
+    }
+    if let Some(ref tuic) = result.tuic {
+        builder = builder.add_inbound(&tuic.inbound);
+    }
+    if let Some(ref vless) = result.vless_reality {
+        builder = builder.add_inbound(&vless.inbound);
+    }
+    if let Some(ref vless) = result.vless_ws {
+        builder = builder.add_inbound(&vless.inbound);
+    }
+    if let Some(ref vless) = result.vless_grpc {
+        builder = builder.add_inbound(&vless.inbound);
+    }
+
+    let dns_rules = dns_block_rules_from_env()?
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // 滥用防护规则在前，其次按国家屏蔽 / 直连，WARP 分流规则在后
+    let warp = warp_from_env()?;
+    let (geo_rules, rule_sets) = geo_rules_from_env()?;
+    let route_rules = limit_rules_from_env(result)?
+        .iter()
+        .chain(geo_rules.iter())
+        .chain(warp.iter().flat_map(|w| w.rules.iter()))
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let rule_sets = rule_sets
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // 中转模式下流量默认转发到上游，滥用防护规则仍在本机生效
+    let relay = relay_upstream_from_env()?;
+    let final_tag = match (&relay, &warp) {
+        (Some(_), Some(w)) if w.all => {
+            return Err("EZ_WARP_ALL 不能与 EZ_RELAY_UPSTREAM 同时使用".to_string());
+        }
+        (Some(_), _) => RELAY_TAG,
+        (None, Some(w)) if w.all => WARP_TAG,
+        (None, _) => DirectOutbound::DEFAULT_TAG,
+    };
+    if let Some(relay) = relay {
+        builder = builder.add_outbound(relay);
+    }
+    if let Some(warp) = warp {
+        builder = builder.add_endpoint(warp.endpoint);
+    }
+
+    // 启用 Clash API 供 run 模式统计流量
+    if let Some(stats) = stats_options_from_env()? {
+        if !stats.api.ip().is_loopback() && stats.secret.is_none() {
+            warn!(api = %stats.api, "Clash API 监听在非本机地址且未设置 EZ_CLASH_API_SECRET");
+        }
+        let mut clash_api = serde_json::json!({ "external_controller": stats.api.to_string() });
+        if let Some(secret) = stats.secret {
+            clash_api["secret"] = secret.into();
+        }
+        builder = builder.experimental(serde_json::json!({ "clash_api": clash_api }));
+    }
+
+    let mut cfg = builder
+        .dns(server_dns_from_env()?)
+        .add_outbound(DirectOutbound::default().with_dial(dial_fields_from_env()?))
+        .route(serde_json::json!({
+            "rules": [],
+            "default_domain_resolver": REMOTE_DNS_TAG,
+            "final": final_tag
+        }))
+        .build()
+        .map_err(diagnostics_error)?
+        .with_dns_rules(dns_rules)
+        .with_route_rules(route_rules)
+        .with_rule_sets(rule_sets);
+    cfg = match singbox_version_from_env()? {
+        Some(version) => cfg.for_version(version)?,
+        None => cfg.with_inbound_sniff_rules(),
+    };
+    render_config(&cfg)
+}
+
+/// 将构建器的诊断信息合并为错误消息
+fn diagnostics_error(diagnostics: Vec<Diagnostic>) -> String {
+    let lines: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    format!("配置未通过结构校验:\n{}", lines.join("\n"))
+}
+
+/// 读取 ACME 数据目录（EZ_ACME_DATA_DIR，默认 ./acme）
+pub fn acme_data_dir_from_env() -> String {
+    env_string("EZ_ACME_DATA_DIR").unwrap_or_else(|| DEFAULT_ACME_DATA_DIR.to_string())
+}
+
+/// 读取服务端配置路径（EZ_CONFIG_PATH，默认 ./config.json）
+pub fn config_path_from_env() -> String {
+    env_string("EZ_CONFIG_PATH").unwrap_or_else(|| "./config.json".to_string())
+}
+
+/// 读取健康检查设置（EZ_HEALTH_HOST 默认 127.0.0.1，EZ_HEALTH_TIMEOUT 默认 3s）
+pub fn health_options_from_env() -> Result<(IpAddr, std::time::Duration), String> {
+    let host = env_ip("EZ_HEALTH_HOST").unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let timeout = env_duration("EZ_HEALTH_TIMEOUT")?
+        .map(|d| d.to_std())
+        .unwrap_or(DEFAULT_HEALTH_TIMEOUT);
+    Ok((host, timeout))
+}
+
+/// 读取 run 模式的 sing-box 升级检查设置（EZ_AUTO_UPGRADE_CHECK 未启用时为 None）
+pub fn upgrade_options_from_env() -> Result<Option<UpgradeOptions>, String> {
+    if !env_bool("EZ_AUTO_UPGRADE_CHECK", false) {
+        return Ok(None);
+    }
+    let window = env_string("EZ_UPGRADE_WINDOW")
+        .map(|raw| raw.parse::<MaintenanceWindow>())
+        .transpose()?;
+    Ok(Some(UpgradeOptions {
+        interval: env_duration("EZ_UPGRADE_CHECK_INTERVAL")?
+            .map(|d| d.to_std())
+            .unwrap_or(DEFAULT_UPGRADE_CHECK_INTERVAL),
+        auto_upgrade: env_bool("EZ_AUTO_UPGRADE", false),
+        window,
+        prefix: env_string("EZ_INSTALL_PREFIX")
+            .unwrap_or_else(|| DEFAULT_INSTALL_PREFIX.to_string()),
+    }))
+}
+
+/// 读取未指定 EZ_DOMAIN 时使用的域名提供方（EZ_DOMAIN_PROVIDER，默认 sslip）
+/// duckdns 需要 EZ_DUCKDNS_DOMAIN 与 EZ_DUCKDNS_TOKEN，custom 需要 EZ_DOMAIN_PATTERN
+pub fn domain_provider_from_env() -> Result<DomainProvider, String> {
+    let Some(provider) = env_string("EZ_DOMAIN_PROVIDER") else {
+        return Ok(DomainProvider::default());
+    };
+    match provider.to_ascii_lowercase().as_str() {
+        "duckdns" => {
+            let (Some(domain), Some(token)) = (
+                env_string("EZ_DUCKDNS_DOMAIN"),
+                env_string("EZ_DUCKDNS_TOKEN"),
+            ) else {
+                return Err(
+                    "EZ_DOMAIN_PROVIDER=duckdns 需要设置 EZ_DUCKDNS_DOMAIN 与 EZ_DUCKDNS_TOKEN"
+                        .to_string(),
+                );
+            };
+            DomainProvider::duckdns(&domain, token).map_err(|e| e.to_string())
+        }
+        "custom" => {
+            let pattern = env_string("EZ_DOMAIN_PATTERN").ok_or_else(|| {
+                "EZ_DOMAIN_PROVIDER=custom 需要设置 EZ_DOMAIN_PATTERN（如 {ip_dashed}.example.com）"
+                    .to_string()
+            })?;
+            DomainProvider::custom(pattern).map_err(|e| e.to_string())
+        }
+        _ => provider
+            .parse()
+            .map_err(|e: DomainProviderError| e.to_string()),
+    }
+}
+
+/// 读取状态文件与凭证轮换设置（EZ_ROTATE_INTERVAL 未设置时不轮换）
+/// 未设置 EZ_REALITY_SHORT_IDS 时按 EZ_REALITY_SHORT_ID_COUNT / EZ_REALITY_SHORT_ID_LEN 生成短 ID 并保存，
+/// EZ_ROTATE_SHORT_IDS 启用时随凭证轮换；未设置 EZ_REALITY_PRIVATE_KEY 时 REALITY 私钥同样保存在状态文件中
+pub fn state_options_from_env() -> Result<StateOptions, String> {
+    let interval = env_duration("EZ_ROTATE_INTERVAL")?;
+    if interval.as_ref().is_some_and(|i| i.as_secs() == 0) {
+        return Err("EZ_ROTATE_INTERVAL 至少为 1s".to_string());
+    }
+    let fixed_short_ids = env_string("EZ_REALITY_SHORT_IDS").is_some();
+    let rotate_short_ids = env_bool("EZ_ROTATE_SHORT_IDS", false);
+    if rotate_short_ids && fixed_short_ids {
+        return Err("EZ_ROTATE_SHORT_IDS 不能与固定的 EZ_REALITY_SHORT_IDS 同时使用".to_string());
+    }
+    let short_ids = (!fixed_short_ids).then(|| {
+        let count = env_u16("EZ_REALITY_SHORT_ID_COUNT").unwrap_or(1);
+        let len = env_u16("EZ_REALITY_SHORT_ID_LEN").unwrap_or(4);
+        (count as usize, len as usize)
+    });
+    Ok(StateOptions {
+        interval: interval.map(|i| i.to_std()),
+        state_path: env_string("EZ_STATE_PATH").unwrap_or_else(|| DEFAULT_STATE_PATH.to_string()),
+        log_path: env_string("EZ_ROTATION_LOG").unwrap_or_else(|| DEFAULT_ROTATION_LOG.to_string()),
+        short_ids,
+        rotate_short_ids,
+        reality_private_key: env_string("EZ_REALITY_PRIVATE_KEY").is_none(),
+        encryption_key: env_string("EZ_STATE_ENCRYPTION_KEY")
+            .map(|raw| StateKey::parse(&raw))
+            .transpose()?,
+    })
+}
+
+/// 读取流量统计设置（EZ_CLASH_API 未设置时不启用）
+pub fn stats_options_from_env() -> Result<Option<StatsOptions>, String> {
+    let metrics = env_socket_addr("EZ_METRICS_LISTEN")?;
+    let Some(api) = env_socket_addr("EZ_CLASH_API")? else {
+        if metrics.is_some() {
+            return Err("EZ_METRICS_LISTEN 需要同时设置 EZ_CLASH_API".to_string());
+        }
+        return Ok(None);
+    };
+    let interval = env_duration("EZ_STATS_INTERVAL")?
+        .map(|d| d.to_std())
+        .unwrap_or(DEFAULT_STATS_INTERVAL);
+    if interval.as_secs() == 0 {
+        return Err("EZ_STATS_INTERVAL 至少为 1s".to_string());
+    }
+    Ok(Some(StatsOptions {
+        api,
+        secret: env_string("EZ_CLASH_API_SECRET"),
+        interval,
+        path: stats_path_from_env(),
+        metrics,
+    }))
+}
+
+/// 流量统计文件路径（EZ_STATS_PATH，默认 ./ezsingbox-stats.json）
+pub fn stats_path_from_env() -> String {
+    env_string("EZ_STATS_PATH").unwrap_or_else(|| DEFAULT_STATS_PATH.to_string())
+}
+
+/// 读取延迟测试设置（EZ_URLTEST_URL 默认 gstatic generate_204，EZ_URLTEST_TIMEOUT 默认 5s）
+pub fn urltest_options_from_env() -> Result<(HttpTarget, std::time::Duration), String> {
+    let target = env_string("EZ_URLTEST_URL")
+        .as_deref()
+        .unwrap_or(DEFAULT_URLTEST_URL)
+        .parse::<HttpTarget>()?;
+    let timeout = env_duration("EZ_URLTEST_TIMEOUT")?
+        .map(|d| d.to_std())
+        .unwrap_or(DEFAULT_URLTEST_TIMEOUT);
+    Ok((target, timeout))
+}
+
+/// 读取目标 sing-box 版本（EZ_SINGBOX_VERSION），未设置时使用最新格式
+pub fn singbox_version_from_env() -> Result<Option<SingBoxVersion>, String> {
+    env_string("EZ_SINGBOX_VERSION")
+        .map(|raw| raw.parse::<SingBoxVersion>())
+        .transpose()
+}
+
+/// 从环境变量构建服务端 DNS 配置（EZ_DNS_UPSTREAM, EZ_DNS_STRATEGY）
+pub fn server_dns_from_env() -> Result<Dns, String> {
+    let mut builder = ServerDnsBuilder::new();
+    if let Some(raw) = env_string("EZ_DNS_UPSTREAM") {
+        builder = builder.upstream(raw.parse::<DnsUpstream>()?);
+    }
+    if let Some(raw) = env_string("EZ_DNS_STRATEGY") {
+        builder = builder.strategy(raw.parse::<DomainStrategy>()?);
+    }
+    Ok(builder.build())
+}
+
+/// 从环境变量构建客户端 DNS 配置（EZ_CLIENT_DNS_MODE）
+pub fn client_dns_from_env() -> Result<ClientDnsProfile, String> {
+    let mode = env_string("EZ_CLIENT_DNS_MODE")
+        .map(|raw| raw.parse::<ClientDnsMode>())
+        .transpose()?
+        .unwrap_or_default();
+    Ok(ClientDnsBuilder::new()
+        .mode(mode)
+        .block_rule_sets(client_adblock_from_env()?)
+        .build())
+}
+
+/// 从环境变量读取客户端平台（EZ_CLIENT_PLATFORM）
+pub fn client_platform_from_env() -> Result<ClientPlatform, String> {
+    Ok(env_string("EZ_CLIENT_PLATFORM")
+        .map(|raw| raw.parse::<ClientPlatform>())
+        .transpose()?
+        .unwrap_or_default())
+}
+
+/// 按平台构建客户端 TUN 入站（EZ_CLIENT_INCLUDE_PACKAGES, EZ_CLIENT_EXCLUDE_PACKAGES）
+fn client_tun_from_env(platform: ClientPlatform) -> Result<Option<TunInbound>, String> {
+    let packages = |key: &str| -> Vec<String> {
+        env_string(key)
+            .unwrap_or_default()
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect()
+    };
+    let include = packages("EZ_CLIENT_INCLUDE_PACKAGES");
+    let exclude = packages("EZ_CLIENT_EXCLUDE_PACKAGES");
+    if platform != ClientPlatform::Android && !(include.is_empty() && exclude.is_empty()) {
+        return Err(format!(
+            "EZ_CLIENT_INCLUDE_PACKAGES / EZ_CLIENT_EXCLUDE_PACKAGES 只适用于 android 平台（当前 {}）",
+            platform
+        ));
+    }
+    Ok(platform.tun_inbound().map(|tun| {
+        tun.with_include_packages(include)
+            .with_exclude_packages(exclude)
+    }))
+}
+
+/// 客户端广告屏蔽使用的 geosite 规则集（含常见广告与跟踪域名）
+const CLIENT_ADBLOCK_GEOSITES: &[&str] = &["category-ads-all"];
+
+/// 从环境变量构建客户端广告屏蔽规则集（EZ_CLIENT_ADBLOCK）
+fn client_adblock_from_env() -> Result<Vec<RuleSet>, String> {
+    if !env_bool("EZ_CLIENT_ADBLOCK", false) {
+        return Ok(Vec::new());
+    }
+    // 客户端所在网络可能无法直连 GitHub，规则集经代理下载
+    let mirror = rule_set_mirror_from_env()?;
+    Ok(CLIENT_ADBLOCK_GEOSITES
+        .iter()
+        .map(|name| {
+            mirror_rule_set(RuleSet::geosite(name, SING_GEOSITE_URL), &mirror)
+                .with_download_detour("proxy")
+        })
+        .collect())
+}
+
+/// 从环境变量构建 DNS 屏蔽规则（EZ_DNS_BLOCK_DOMAINS, EZ_DNS_BLOCK_ACTION）
+pub fn dns_block_rules_from_env() -> Result<Vec<DnsRule>, String> {
+    let domains: Vec<String> = env_string("EZ_DNS_BLOCK_DOMAINS")
+        .map(|raw| {
+            raw.split(',')
+                .map(|d| d.trim().trim_start_matches('.').to_ascii_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    if domains.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let action = match env_string("EZ_DNS_BLOCK_ACTION")
+        .map(|s| s.to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("reject") => DnsRuleAction::reject(DnsRejectMethod::Default),
+        Some("drop") => DnsRuleAction::reject(DnsRejectMethod::Drop),
+        Some("nxdomain") => DnsRuleAction::predefined_rcode("NXDOMAIN"),
+        Some("refused") => DnsRuleAction::predefined_rcode("REFUSED"),
+        Some(other) => {
+            return Err(format!(
+                "未知的 DNS 屏蔽动作: {}（可用: reject, drop, nxdomain, refused）",
+                other
+            ));
+        }
+    };
+
+    // 使用后缀匹配，同时屏蔽子域名
+    Ok(vec![DnsRule::Default(
+        DefaultDnsRule::new()
+            .domain_suffixes(domains)
+            .action(action),
+    )])
+}
+
+/// 从环境变量构建滥用防护路由规则
+/// （EZ_LIMIT_PROTOCOLS, EZ_LIMIT_BLOCKED_SOURCES, EZ_LIMIT_BLOCK_BITTORRENT,
+/// EZ_LIMIT_BLOCK_SMTP, EZ_LIMIT_BLOCK_PRIVATE）
+pub fn limit_rules_from_env(result: &MultiProtocolResult) -> Result<Vec<RouteRule>, String> {
+    let split = |raw: String| -> Vec<String> {
+        raw.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    };
+
+    // 未指定协议时规则作用于全部入站
+    let mut inbounds = Vec::new();
+    if let Some(raw) = env_string("EZ_LIMIT_PROTOCOLS") {
+        for name in split(raw) {
+            let protocol = name.parse::<Protocol>().map_err(|e| e.to_string())?;
+            if let Some(tag) = protocol_tag(result, protocol) {
+                inbounds.push(tag);
+            }
+        }
+        if inbounds.is_empty() {
+            return Ok(Vec::new());
+        }
+    }
+    let scoped = |rule: RouteRule| {
+        if inbounds.is_empty() {
+            rule
+        } else {
+            rule.with_inbounds(inbounds.clone())
+        }
+    };
+
+    let mut rules = Vec::new();
+
+    let sources = env_string("EZ_LIMIT_BLOCKED_SOURCES")
+        .map(split)
+        .unwrap_or_default();
+    for cidr in &sources {
+        if !is_valid_cidr(cidr) {
+            return Err(format!("EZ_LIMIT_BLOCKED_SOURCES 中的地址无效: {}", cidr));
+        }
+    }
+    if !sources.is_empty() {
+        rules.push(scoped(
+            RouteRule::new()
+                .with_source_ip_cidrs(sources)
+                .with_action(RuleAction::Reject(RejectAction {
+                    method: Some(RejectMethod::Drop),
+                    no_drop: None,
+                })),
+        ));
+    }
+
+    if env_bool("EZ_LIMIT_BLOCK_PRIVATE", false) {
+        rules.push(scoped(
+            RouteRule::new()
+                .with_ip_is_private()
+                .with_action(RuleAction::reject()),
+        ));
+    }
+
+    if env_bool("EZ_LIMIT_BLOCK_SMTP", false) {
+        rules.push(scoped(
+            RouteRule::new()
+                .with_ports(vec![25, 465, 587])
+                .with_action(RuleAction::reject()),
+        ));
+    }
+
+    // 协议匹配依赖嗅探结果，需先插入 sniff 动作
+    if env_bool("EZ_LIMIT_BLOCK_BITTORRENT", false) {
+        rules.push(scoped(RouteRule::new().with_action(RuleAction::sniff())));
+        rules.push(scoped(
+            RouteRule::new()
+                .with_protocols(["bittorrent"])
+                .with_action(RuleAction::reject()),
+        ));
+    }
+
+    Ok(rules)
+}
+
+/// 读取规则集下载地址前缀（EZ_RULE_SET_MIRROR）
+pub fn rule_set_mirror_from_env() -> Result<String, String> {
+    let mirror = env_string("EZ_RULE_SET_MIRROR").unwrap_or_default();
+    if !mirror.is_empty() && !mirror.starts_with("http://") && !mirror.starts_with("https://") {
+        return Err(format!(
+            "EZ_RULE_SET_MIRROR 必须是 http(s) 地址: {}",
+            mirror
+        ));
+    }
+    Ok(mirror)
+}
+
+/// 为规则集下载地址加上镜像前缀
+fn mirror_rule_set(rule_set: RuleSet, mirror: &str) -> RuleSet {
+    RuleSet {
+        url: format!("{}{}", mirror, rule_set.url),
+        ..rule_set
+    }
+}
+
+/// 按目标国家分流的路由规则及其引用的规则集
+/// （EZ_GEOIP_BLOCK, EZ_GEOIP_DIRECT, EZ_BLOCK_CN_INBOUND, EZ_RULE_SET_MIRROR）
+/// GeoIP 规则集只匹配 IP 目标，域名目标需要 Geosite 规则集（EZ_BLOCK_CN_INBOUND 同时使用两者）
+fn geo_rules_from_env() -> Result<(Vec<RouteRule>, Vec<RuleSet>), String> {
+    let countries = |key: &str| -> Result<Vec<String>, String> {
+        let list: Vec<String> = env_string(key)
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if let Some(bad) = list
+            .iter()
+            .find(|c| c.len() != 2 || !c.bytes().all(|b| b.is_ascii_lowercase()))
+        {
+            return Err(format!("{} 中的国家代码无效: {}（如 cn,ir）", key, bad));
+        }
+        Ok(list)
+    };
+    let mirror = rule_set_mirror_from_env()?;
+    // 规则集经 direct 出站下载，不受中转 / WARP 默认出站影响
+    let mirrored = |rule_set: RuleSet| {
+        mirror_rule_set(rule_set, &mirror).with_download_detour(DirectOutbound::DEFAULT_TAG)
+    };
+
+    let mut block: Vec<RuleSet> = countries("EZ_GEOIP_BLOCK")?
+        .iter()
+        .map(|c| RuleSet::geoip(c, SING_GEOIP_URL))
+        .collect();
+    if env_bool("EZ_BLOCK_CN_INBOUND", false) {
+        block.push(RuleSet::geoip("cn", SING_GEOIP_URL));
+        block.push(RuleSet::geosite("cn", SING_GEOSITE_URL));
+    }
+    let direct: Vec<RuleSet> = countries("EZ_GEOIP_DIRECT")?
+        .iter()
+        .map(|c| RuleSet::geoip(c, SING_GEOIP_URL))
+        .collect();
+    if let Some(both) = direct.iter().find(|d| block.contains(d)) {
+        return Err(format!("{} 不能同时屏蔽与直连", both.tag));
+    }
+
+    let mut rules = Vec::new();
+    if !block.is_empty() {
+        rules.push(
+            RouteRule::new()
+                .with_rule_sets(block.iter().map(|r| r.tag.clone()))
+                .with_action(RuleAction::reject()),
+        );
+    }
+    if !direct.is_empty() {
+        rules.push(
+            RouteRule::new()
+                .with_rule_sets(direct.iter().map(|r| r.tag.clone()))
+                .with_action(RuleAction::route(DirectOutbound::DEFAULT_TAG)),
+        );
+    }
+    let mut rule_sets: Vec<RuleSet> = Vec::new();
+    for rule_set in block.into_iter().chain(direct) {
+        if !rule_sets.iter().any(|r| r.tag == rule_set.tag) {
+            rule_sets.push(mirrored(rule_set));
+        }
+    }
+    Ok((rules, rule_sets))
+}
+
+/// WARP 出口的端点标签
+const WARP_TAG: &str = "warp";
+
+/// EZ_WARP_SERVICES 可选的内置服务及其域名后缀
+const WARP_SERVICES: &[(&str, &[&str])] = &[
+    (
+        "openai",
+        &[
+            "openai.com",
+            "chatgpt.com",
+            "oaistatic.com",
+            "oaiusercontent.com",
+            "sora.com",
+        ],
+    ),
+    ("claude", &["claude.ai", "anthropic.com"]),
+    (
+        "gemini",
+        &[
+            "gemini.google.com",
+            "aistudio.google.com",
+            "generativelanguage.googleapis.com",
+        ],
+    ),
+    (
+        "netflix",
+        &[
+            "netflix.com",
+            "netflix.net",
+            "nflxext.com",
+            "nflximg.net",
+            "nflxso.net",
+            "nflxvideo.net",
+        ],
+    ),
+    (
+        "disney",
+        &[
+            "disneyplus.com",
+            "disney-plus.net",
+            "dssott.com",
+            "bamgrid.com",
+        ],
+    ),
+];
+
+/// WARP 出口：WireGuard 端点及经其转发的路由规则
+struct WarpEgress {
+    endpoint: WireGuardEndpoint,
+    rules: Vec<RouteRule>,
+    /// 是否全部流量经 WARP 转发（EZ_WARP_ALL）
+    all: bool,
+}
+
+/// 解析 WireGuard 保留字节：`1,2,3` 或 base64 编码的 WARP client_id
+fn parse_warp_reserved(raw: &str) -> Result<[u8; 3], String> {
+    let invalid = || {
+        format!(
+            "EZ_WARP_RESERVED 无效: {}（格式 1,2,3 或 base64 client_id）",
+            raw
+        )
+    };
+    let bytes: Vec<u8> = if raw.contains(',') {
+        raw.split(',')
+            .map(|b| b.trim().parse::<u8>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(raw.trim())
+            .map_err(|_| invalid())?
+    };
+    bytes.try_into().map_err(|_| invalid())
+}
+
+/// 读取 WARP 出口设置（EZ_WARP_PRIVATE_KEY 未设置时不启用）
+/// EZ_WARP_DOMAINS / EZ_WARP_SERVICES 中的域名经 WARP 转发，EZ_WARP_ALL 时全部流量经 WARP 转发
+fn warp_from_env() -> Result<Option<WarpEgress>, String> {
+    let Some(private_key) = env_string("EZ_WARP_PRIVATE_KEY") else {
+        return Ok(None);
+    };
+    let key_ok = base64::engine::general_purpose::STANDARD
+        .decode(private_key.trim())
+        .is_ok_and(|k| k.len() == 32);
+    if !key_ok {
+        return Err("EZ_WARP_PRIVATE_KEY 必须是 base64 编码的 32 字节 WireGuard 私钥".to_string());
+    }
+    let split = |raw: String| -> Vec<String> {
+        raw.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    };
+
+    let address = env_string("EZ_WARP_ADDRESS")
+        .map(split)
+        .unwrap_or_else(|| vec!["172.16.0.2/32".to_string()]);
+    if let Some(bad) = address.iter().find(|a| !is_valid_cidr(a)) {
+        return Err(format!("EZ_WARP_ADDRESS 中的地址无效: {}", bad));
+    }
+    let mut endpoint = WireGuardEndpoint::cloudflare_warp(WARP_TAG, private_key.trim(), address);
+    let peer = &mut endpoint.peers[0];
+    if let Some(raw) = env_string("EZ_WARP_PEER") {
+        let (host, port) = raw
+            .rsplit_once(':')
+            .and_then(|(h, p)| Some((h.trim_matches(['[', ']']), p.parse::<u16>().ok()?)))
+            .filter(|(h, _)| !h.is_empty())
+            .ok_or_else(|| format!("EZ_WARP_PEER 无效: {}（格式 host:port）", raw))?;
+        peer.address = host.to_string();
+        peer.port = port;
+    }
+    if let Some(key) = env_string("EZ_WARP_PEER_PUBLIC_KEY") {
+        peer.public_key = key;
+    }
+    if let Some(raw) = env_string("EZ_WARP_RESERVED") {
+        peer.reserved = Some(parse_warp_reserved(&raw)?);
+    }
+    if let Some(mtu) = env_u16("EZ_WARP_MTU") {
+        endpoint = endpoint.with_mtu(mtu.into());
+    }
+
+    let mut suffixes = env_string("EZ_WARP_DOMAINS").map(split).unwrap_or_default();
+    for name in env_string("EZ_WARP_SERVICES")
+        .map(split)
+        .unwrap_or_default()
+    {
+        let (_, domains) = WARP_SERVICES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = WARP_SERVICES.iter().map(|(n, _)| *n).collect();
+                format!(
+                    "EZ_WARP_SERVICES 中的服务未知: {}（可选 {}）",
+                    name,
+                    names.join(" / ")
+                )
+            })?;
+        suffixes.extend(domains.iter().map(|d| d.to_string()));
+    }
+    suffixes.sort();
+    suffixes.dedup();
+    let all = env_bool("EZ_WARP_ALL", false);
+    if suffixes.is_empty() && !all {
+        warn!(
+            "已配置 WARP 出口，但未设置 EZ_WARP_DOMAINS / EZ_WARP_SERVICES / EZ_WARP_ALL，没有流量经 WARP 转发"
+        );
+    }
+    let rules = if suffixes.is_empty() {
+        Vec::new()
+    } else {
+        vec![
+            RouteRule::new()
+                .with_domain_suffixes(suffixes)
+                .with_action(RuleAction::route(WARP_TAG)),
+        ]
+    };
+    Ok(Some(WarpEgress {
+        endpoint,
+        rules,
+        all,
+    }))
+}
+
+/// 校验 IP 或 CIDR 格式
+fn is_valid_cidr(raw: &str) -> bool {
+    let (ip, prefix) = match raw.split_once('/') {
+        Some((ip, prefix)) => (ip, Some(prefix)),
+        None => (raw, None),
+    };
+    let Ok(ip) = ip.parse::<std::net::IpAddr>() else {
+        return false;
+    };
+    let max = if ip.is_ipv4() { 32 } else { 128 };
+    prefix.is_none_or(|p| p.parse::<u8>().is_ok_and(|p| p <= max))
+}
+
+/// 单个用户的分享链接
+pub struct ShareLink {
+    /// 协议
+    pub protocol: Protocol,
+    /// 端口
+    pub port: u16,
+    /// 用户名
+    pub user: String,
+    /// 分享链接
+    pub link: String,
+}
+
+/// 协议显示名称
+pub fn protocol_label(protocol: Protocol) -> &'static str {
+    match protocol {
+        Protocol::AnyTls => "AnyTLS",
+        Protocol::Hysteria2 => "Hysteria2",
+        Protocol::Tuic => "TUIC",
+        Protocol::VlessReality => "VLESS Reality",
+        Protocol::VlessWs => "VLESS WebSocket",
+        Protocol::VlessGrpc => "VLESS gRPC",
+    }
+}
+
+/// 生成所有已启用协议、所有用户的分享链接
+pub fn share_links(result: &MultiProtocolResult) -> Vec<ShareLink> {
     // 获取 TUIC 拥塞控制算法
     let tuic_cc = env_string("EZ_TUIC_CC")
         .map(|s| s.trim().to_ascii_lowercase())
@@ -355,142 +2111,286 @@ pub fn print_details(result: &MultiProtocolResult) {
             _ => None,
         });
 
-    // 获取 Hysteria2 混淆密码
-    let hy2_obfs_enabled = env_bool("EZ_HY2_OBFS", false);
+    // 获取 VLESS 链接的 uTLS 指纹（取值已在启动时校验）
+    let fingerprint = client_utls_fingerprint().unwrap_or_default();
 
-    println!("\n==== 分享链接 ====");
+    let mut links = Vec::new();
+    let mut push = |protocol: Protocol, port: u16, user: &str, link: String| {
+        links.push(ShareLink {
+            protocol,
+            port,
+            user: user.to_string(),
+            link,
+        });
+    };
 
     // AnyTLS 分享链接
     if let Some(ref anytls) = result.anytls {
-        println!("\n[AnyTLS] 端口: {}", anytls.info.port);
+        let (server, sni) = (
+            client_server(result, Protocol::AnyTls),
+            client_sni(result, Protocol::AnyTls),
+        );
         for u in &anytls.info.users {
             let link =
-                generate_anytls_share_link(domain, anytls.info.port, &u.password, domain, &u.name);
-            println!("用户 {}: {}", u.name, link);
+                generate_anytls_share_link(&server, anytls.info.port, &u.password, &sni, &u.name);
+            push(Protocol::AnyTls, anytls.info.port, &u.name, link);
         }
     }
 
     // Hysteria2 分享链接
     if let Some(ref hy2) = result.hysteria2 {
-        println!("\n[Hysteria2] 端口: {}", hy2.info.port);
-        let obfs_pwd = if hy2_obfs_enabled {
-            hy2.obfs_password.as_deref()
-        } else {
-            None
-        };
+        let obfs_pwd = hy2.obfs_password.as_deref();
+        let (server, sni) = (
+            client_server(result, Protocol::Hysteria2),
+            client_sni(result, Protocol::Hysteria2),
+        );
         for u in &hy2.info.users {
             let link = generate_hysteria2_share_link(
-                domain,
+                &server,
                 hy2.info.port,
                 &u.password,
-                domain,
+                &sni,
                 &u.name,
                 obfs_pwd,
             );
-            println!("  用户 {}: {}", u.name, link);
+            push(Protocol::Hysteria2, hy2.info.port, &u.name, link);
         }
     }
 
     // TUIC 分享链接
     if let Some(ref tuic) = result.tuic {
-        println!("\n[TUIC] 端口: {}", tuic.info.port);
+        let options = TuicLinkOptions {
+            congestion_control: tuic_cc.as_deref(),
+            udp_relay_mode: Some(tuic.udp_relay_mode.as_str()),
+            zero_rtt: tuic.inbound.zero_rtt_handshake == Some(true),
+        };
+        let (server, sni) = (
+            client_server(result, Protocol::Tuic),
+            client_sni(result, Protocol::Tuic),
+        );
         for u in &tuic.info.users {
             if let Some(ref uuid) = u.uuid {
                 let link = generate_tuic_share_link(
-                    domain,
+                    &server,
                     tuic.info.port,
                     uuid,
                     &u.password,
-                    domain,
+                    &sni,
                     &u.name,
-                    tuic_cc.as_deref(),
+                    &options,
                 );
-                println!("  用户 {}: {}", u.name, link);
+                push(Protocol::Tuic, tuic.info.port, &u.name, link);
             }
         }
     }
 
     // VLESS Reality 分享链接
     if let Some(ref vless) = result.vless_reality {
-        println!("\n[VLESS Reality] 端口: {}", vless.info.port);
-        println!(
-            "  握手服务器: {}:{}",
-            vless.handshake_server, vless.handshake_port
-        );
-        println!("  公钥: {}", vless.public_key);
-        println!("  短ID: {}", vless.short_id);
+        let server = client_server(result, Protocol::VlessReality);
         for u in &vless.info.users {
             if let Some(ref uuid) = u.uuid {
                 let link = generate_vless_reality_share_link(
-                    &public_ip_str,
+                    &server,
                     vless.info.port,
                     uuid,
-                    &vless.public_key,
-                    &vless.short_id,
                     &vless.handshake_server,
                     &u.name,
+                    &RealityLinkParams {
+                        public_key: &vless.public_key,
+                        short_id: &vless.short_id,
+                        fingerprint: fingerprint.as_str(),
+                    },
                 );
-                println!("  用户 {}: {}", u.name, link);
+                push(Protocol::VlessReality, vless.info.port, &u.name, link);
             }
         }
     }
 
-    println!("\n==== 详细配置 ====");
-
-    let print_users = |proto: ClientProtocol, port: u16, users: &[GeneratedUser]| {
-        println!("\n[{}] 端口: {}", proto.as_str(), port);
-        for u in users {
-            println!("- 用户: {}", u.name);
-            println!("  密码: {}", u.password);
+    // VLESS WebSocket / gRPC 分享链接
+    if let Some(ref vless) = result.vless_ws {
+        let (server, sni) = (
+            client_server(result, Protocol::VlessWs),
+            client_sni(result, Protocol::VlessWs),
+        );
+        for u in &vless.info.users {
             if let Some(ref uuid) = u.uuid {
-                println!("  UUID: {}", uuid);
+                let link = generate_vless_ws_share_link(
+                    &server,
+                    vless.info.port,
+                    uuid,
+                    &sni,
+                    &u.name,
+                    &WsLinkParams {
+                        path: &vless.path,
+                        host: vless.host.as_deref().unwrap_or(&sni),
+                        fingerprint: fingerprint.as_str(),
+                    },
+                );
+                push(Protocol::VlessWs, vless.info.port, &u.name, link);
             }
-            if let Ok(outbound) = build_proxy_outbound_json(result, proto, u) {
-                if let Ok(s) = serde_json::to_string_pretty(&outbound) {
-                    println!("  sing-box outbound:\n{}", s);
-                }
+        }
+    }
+    if let Some(ref vless) = result.vless_grpc {
+        let (server, sni) = (
+            client_server(result, Protocol::VlessGrpc),
+            client_sni(result, Protocol::VlessGrpc),
+        );
+        for u in &vless.info.users {
+            if let Some(ref uuid) = u.uuid {
+                let link = generate_vless_grpc_share_link(
+                    &server,
+                    vless.info.port,
+                    uuid,
+                    &sni,
+                    &vless.path,
+                    &u.name,
+                    fingerprint.as_str(),
+                );
+                push(Protocol::VlessGrpc, vless.info.port, &u.name, link);
             }
         }
-    };
+    }
+
+    links
+}
+
+/// 打印详细信息（EZ_REDACT_SECRETS 启用时脱敏）
+pub fn print_details(result: &MultiProtocolResult, redactor: &ConsoleRedactor) {
+    let mut out = String::new();
+    if write_details(&mut out, result).is_ok() {
+        print!("{}", redactor.text(&out));
+    }
+}
+
+/// 写入详细信息
+fn write_details(out: &mut String, result: &MultiProtocolResult) -> fmt::Result {
+    writeln!(out, "{}", t(Msg::DetailsHeader))?;
+    writeln!(out, "{}", tf(Msg::PublicIp, &[&result.public_ip]))?;
+    writeln!(out, "{}", tf(Msg::Domain, &[&result.domain]))?;
+
+    writeln!(out, "{}", t(Msg::ShareLinksHeader))?;
+
+    let mut current = None;
+    for link in share_links(result) {
+        if current != Some(link.protocol) {
+            current = Some(link.protocol);
+            writeln!(
+                out,
+                "{}",
+                tf(
+                    Msg::ProtocolPort,
+                    &[&protocol_label(link.protocol), &link.port]
+                )
+            )?;
+            if let (Protocol::VlessReality, Some(vless)) = (link.protocol, &result.vless_reality) {
+                writeln!(
+                    out,
+                    "{}",
+                    tf(
+                        Msg::HandshakeServer,
+                        &[&vless.handshake_server, &vless.handshake_port]
+                    )
+                )?;
+                writeln!(out, "{}", tf(Msg::PublicKey, &[&vless.public_key]))?;
+                writeln!(out, "{}", tf(Msg::ShortIds, &[&vless.short_ids.join(", ")]))?;
+            }
+            if let (Protocol::VlessWs, Some(vless)) = (link.protocol, &result.vless_ws) {
+                writeln!(out, "{}", tf(Msg::WsPath, &[&vless.path]))?;
+            }
+            if let (Protocol::VlessGrpc, Some(vless)) = (link.protocol, &result.vless_grpc) {
+                writeln!(out, "{}", tf(Msg::GrpcServiceName, &[&vless.path]))?;
+            }
+        }
+        writeln!(out, "{}", tf(Msg::UserLink, &[&link.user, &link.link]))?;
+    }
+
+    writeln!(out, "{}", t(Msg::ConfigHeader))?;
+
+    let print_users =
+        |out: &mut String, proto: Protocol, port: u16, users: &[GeneratedUser]| -> fmt::Result {
+            writeln!(out, "{}", tf(Msg::ProtocolPort, &[&proto.as_str(), &port]))?;
+            for u in users {
+                writeln!(out, "{}", tf(Msg::UserItem, &[&u.name]))?;
+                writeln!(out, "{}", tf(Msg::Password, &[&u.password]))?;
+                if let Some(ref uuid) = u.uuid {
+                    writeln!(out, "  UUID: {}", uuid)?;
+                }
+                if let Some(expires) = u.expires_at {
+                    writeln!(out, "{}", tf(Msg::ExpiresAt, &[&format_utc(expires)]))?;
+                }
+                if let Ok(outbound) = build_proxy_outbound_json(result, proto, u) {
+                    if let Ok(s) = serde_json::to_string_pretty(&outbound) {
+                        writeln!(out, "  sing-box outbound:\n{}", s)?;
+                    }
+                }
+            }
+            Ok(())
+        };
 
     if let Some(ref anytls) = result.anytls {
-        print_users(ClientProtocol::AnyTls, anytls.info.port, &anytls.info.users);
+        print_users(out, Protocol::AnyTls, anytls.info.port, &anytls.info.users)?;
     }
     if let Some(ref hy2) = result.hysteria2 {
-        print_users(ClientProtocol::Hysteria2, hy2.info.port, &hy2.info.users);
+        print_users(out, Protocol::Hysteria2, hy2.info.port, &hy2.info.users)?;
     }
     if let Some(ref tuic) = result.tuic {
-        print_users(ClientProtocol::Tuic, tuic.info.port, &tuic.info.users);
+        print_users(out, Protocol::Tuic, tuic.info.port, &tuic.info.users)?;
     }
     if let Some(ref vless) = result.vless_reality {
-        println!("\n[vless-reality] 端口: {}", vless.info.port);
-        println!(
-            "  握手服务器: {}:{}",
-            vless.handshake_server, vless.handshake_port
-        );
-        println!("  公钥 (客户端使用): {}", vless.public_key);
-        println!("  短ID: {}", vless.short_id);
-        println!("  私钥 (服务端): {}", vless.private_key);
+        writeln!(
+            out,
+            "{}",
+            tf(Msg::ProtocolPort, &[&"vless-reality", &vless.info.port])
+        )?;
+        writeln!(
+            out,
+            "{}",
+            tf(
+                Msg::HandshakeServer,
+                &[&vless.handshake_server, &vless.handshake_port]
+            )
+        )?;
+        writeln!(out, "{}", tf(Msg::PublicKeyClient, &[&vless.public_key]))?;
+        writeln!(out, "{}", tf(Msg::ShortIdClient, &[&vless.short_id]))?;
+        if vless.short_ids.len() > 1 {
+            writeln!(
+                out,
+                "{}",
+                tf(Msg::AllShortIds, &[&vless.short_ids.join(", ")])
+            )?;
+        }
+        writeln!(out, "{}", tf(Msg::PrivateKeyServer, &[&vless.private_key]))?;
         for u in &vless.info.users {
-            println!("- 用户: {}", u.name);
+            writeln!(out, "{}", tf(Msg::UserItem, &[&u.name]))?;
             if let Some(ref uuid) = u.uuid {
-                println!("  UUID: {}", uuid);
+                writeln!(out, "  UUID: {}", uuid)?;
             }
-            if let Ok(outbound) = build_proxy_outbound_json(result, ClientProtocol::VlessReality, u)
-            {
+            if let Ok(outbound) = build_proxy_outbound_json(result, Protocol::VlessReality, u) {
                 if let Ok(s) = serde_json::to_string_pretty(&outbound) {
-                    println!("  sing-box outbound:\n{}", s);
+                    writeln!(out, "  sing-box outbound:\n{}", s)?;
                 }
             }
         }
     }
 
+    if let Some(ref vless) = result.vless_ws {
+        print_users(out, Protocol::VlessWs, vless.info.port, &vless.info.users)?;
+    }
+    if let Some(ref vless) = result.vless_grpc {
+        print_users(out, Protocol::VlessGrpc, vless.info.port, &vless.info.users)?;
+    }
+
     if let Some(url) = env_string("EZ_REMOTE_PROFILE_URL") {
         let name = env_string("EZ_REMOTE_PROFILE_NAME").unwrap_or_else(|| "ezsingbox".to_string());
-        println!("\n订阅链接: {}", url);
-        println!(
-            "URI 链接: {}",
-            sing_box_import_remote_profile_uri(&url, &name)
-        );
+        writeln!(out, "{}", tf(Msg::SubscriptionUrl, &[&url]))?;
+        writeln!(
+            out,
+            "{}",
+            tf(
+                Msg::ImportUri,
+                &[&sing_box_import_remote_profile_uri(&url, &name)]
+            )
+        )?;
     }
+    Ok(())
 }
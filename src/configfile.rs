@@ -0,0 +1,1618 @@
+//! 声明式配置文件模块
+//!
+//! 支持 `ezsingbox.toml` / `ezsingbox.yaml`，通过 `--config` 或 `EZ_FILE` 加载。
+//! 文件中的每一项都映射到对应的环境变量，作为最低优先级的默认值
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use ezsingbox::autoconfig::Protocol;
+
+use crate::fleet::FleetNode;
+use crate::users::{parse_expiry, validate_user_name};
+
+//============================================================================
+// 文件模型
+//============================================================================
+
+/// 配置文件根结构
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// 服务端配置输出路径
+    pub config_path: Option<String>,
+    /// 覆盖服务端配置前保留的备份数量
+    pub config_backups: Option<u16>,
+    /// 公网 IP
+    pub public_ip: Option<IpAddr>,
+    /// 公网 IP 探测方式（按顺序尝试）
+    pub ip_detector: Vec<String>,
+    /// HTTP 公网 IP 服务列表
+    pub ip_services: Vec<String>,
+    /// 日志级别
+    pub log_level: Option<String>,
+    /// ezsingbox 自身日志格式（text / json）
+    pub log_format: Option<String>,
+    /// 是否打印配置内容
+    pub print_config: Option<bool>,
+    /// 是否打印连接详情
+    pub print_details: Option<bool>,
+    /// 配置字段按固定顺序输出
+    pub canonical_output: Option<bool>,
+    /// 去掉与 sing-box 默认值相同的字段
+    pub minimal_config: Option<bool>,
+    /// 写入带注释的配置
+    pub annotate_config: Option<bool>,
+    /// 控制台输出中脱敏敏感信息
+    pub redact_secrets: Option<bool>,
+    /// 错误输出格式（text / json）
+    pub error_format: Option<String>,
+    /// generate 结果输出格式（text / json）
+    pub output: Option<String>,
+    /// 输出语言（zh / en）
+    pub lang: Option<String>,
+    /// 中转上游（分享链接字符串，或 sing-box 出站对象）
+    pub relay_upstream: Option<serde_json::Value>,
+    /// 协议预设（minimal / balanced / stealth / full / cdn）
+    pub preset: Option<String>,
+    /// 服务器列表（fleet 子命令使用）
+    pub servers: Vec<FleetNode>,
+    /// fleet 模式下服务端配置的输出目录
+    pub fleet_dir: Option<String>,
+    /// TLS 设置
+    pub tls: TlsSection,
+    /// 入站监听选项（作用于全部协议）
+    pub listen: ListenSection,
+    /// 协议设置（存在时仅启用列出的协议）
+    pub protocols: Option<ProtocolsSection>,
+    /// 用户列表
+    pub users: Vec<UserEntry>,
+    /// 凭证轮换设置
+    pub rotation: RotationSection,
+    /// DNS 设置
+    pub dns: DnsSection,
+    /// 滥用防护设置
+    pub limits: LimitsSection,
+    /// 客户端配置导出设置
+    pub client: ClientSection,
+    /// HTTP 订阅服务设置
+    pub serve: ServeSection,
+    /// sing-box 相关设置
+    pub sing_box: SingBoxSection,
+    /// 健康检查设置
+    pub health: HealthSection,
+    /// 延迟测试设置
+    pub urltest: UrlTestSection,
+    /// 远程部署设置
+    pub deploy: DeploySection,
+    /// 流量统计设置
+    pub stats: StatsSection,
+    /// WARP 出口设置
+    pub warp: WarpSection,
+    /// 通知设置
+    pub notify: NotifySection,
+}
+
+/// TLS 设置（ACME）
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsSection {
+    /// 域名（不指定时由域名提供方生成）
+    pub domain: Option<String>,
+    /// 域名提供方（sslip / nip / duckdns / custom）
+    pub domain_provider: Option<String>,
+    /// 自定义泛解析域名模式
+    pub domain_pattern: Option<String>,
+    /// DuckDNS 子域名
+    pub duckdns_domain: Option<String>,
+    /// DuckDNS token
+    pub duckdns_token: Option<String>,
+    /// ACME 邮箱
+    pub acme_email: Option<String>,
+    /// ACME CA 提供商（letsencrypt / zerossl / 自定义 URL）
+    pub acme_provider: Option<String>,
+    /// ACME 数据目录
+    pub acme_data_dir: Option<String>,
+    /// 写入配置前检查域名解析与验证端口
+    pub acme_preflight: Option<bool>,
+    /// ACME 外部账户绑定（EAB）
+    pub eab: Option<EabSection>,
+    /// ACME DNS-01 挑战
+    pub dns01: Option<Dns01Section>,
+}
+
+/// ACME 外部账户绑定设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EabSection {
+    /// Key ID
+    pub key_id: Option<String>,
+    /// MAC Key
+    pub mac_key: Option<String>,
+}
+
+/// ACME DNS-01 挑战设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Dns01Section {
+    /// 提供商（cloudflare / alidns）
+    pub provider: Option<String>,
+    /// Cloudflare API Token
+    pub api_token: Option<String>,
+    /// 阿里云 AccessKey ID
+    pub access_key_id: Option<String>,
+    /// 阿里云 AccessKey Secret
+    pub access_key_secret: Option<String>,
+    /// 阿里云区域 ID
+    pub region_id: Option<String>,
+}
+
+/// 入站监听选项
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListenSection {
+    /// 启用 TCP 快速打开
+    pub tcp_fast_open: Option<bool>,
+    /// UDP NAT 过期时间
+    pub udp_timeout: Option<String>,
+    /// 启用协议嗅探
+    pub sniff: Option<bool>,
+    /// 使用嗅探到的域名覆盖目标地址
+    pub sniff_override_destination: Option<bool>,
+    /// 入站与出站绑定的网络接口
+    pub bind_interface: Option<String>,
+    /// netfilter 路由标记（整数或 0x 开头的十六进制）
+    pub routing_mark: Option<String>,
+    /// 网络命名空间
+    pub netns: Option<String>,
+}
+
+/// 协议设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtocolsSection {
+    /// AnyTLS
+    pub anytls: Option<AnyTlsSection>,
+    /// Hysteria2
+    pub hysteria2: Option<Hysteria2Section>,
+    /// TUIC
+    pub tuic: Option<TuicSection>,
+    /// VLESS-Reality
+    pub vless_reality: Option<VlessRealitySection>,
+    /// VLESS over WebSocket
+    pub vless_ws: Option<VlessWsSection>,
+    /// VLESS over gRPC
+    pub vless_grpc: Option<VlessGrpcSection>,
+    /// VLESS WS / gRPC 的 TCP Brutal 带宽
+    pub tcp_brutal: Option<TcpBrutalSection>,
+    /// VLESS WS / gRPC 的多路复用设置
+    pub multiplex: Option<MultiplexSection>,
+    /// VLESS WS / gRPC 客户端连接的 CDN 地址
+    pub cdn_host: Option<String>,
+}
+
+/// 多路复用设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MultiplexSection {
+    /// 多路复用协议（smux / yamux / h2mux）
+    pub protocol: Option<String>,
+    /// 单个连接的最大流数
+    pub max_streams: Option<u32>,
+    /// 启用填充
+    pub padding: Option<bool>,
+}
+
+/// TCP Brutal 设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TcpBrutalSection {
+    /// 上行带宽 (Mbps)
+    pub up_mbps: Option<u32>,
+    /// 下行带宽 (Mbps)
+    pub down_mbps: Option<u32>,
+}
+
+/// AnyTLS 设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnyTlsSection {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监听端口
+    pub port: Option<u16>,
+    /// 监听地址
+    pub listen: Option<String>,
+    /// 是否启用协议嗅探（覆盖 [listen] 中的设置）
+    pub sniff: Option<bool>,
+    /// 客户端连接的服务器地址（覆盖 [client] 中的设置）
+    pub server_override: Option<String>,
+    /// 客户端使用的 SNI（覆盖 [client] 中的设置）
+    pub sni_override: Option<String>,
+}
+
+impl Default for AnyTlsSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: None,
+            listen: None,
+            sniff: None,
+            server_override: None,
+            sni_override: None,
+        }
+    }
+}
+
+/// Hysteria2 设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hysteria2Section {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监听端口
+    pub port: Option<u16>,
+    /// 监听地址
+    pub listen: Option<String>,
+    /// 是否启用协议嗅探（覆盖 [listen] 中的设置）
+    pub sniff: Option<bool>,
+    /// 客户端连接的服务器地址（覆盖 [client] 中的设置）
+    pub server_override: Option<String>,
+    /// 客户端使用的 SNI（覆盖 [client] 中的设置）
+    pub sni_override: Option<String>,
+    /// 是否启用 Salamander 混淆
+    pub obfs: Option<bool>,
+    /// 上行带宽 (Mbps)
+    pub up_mbps: Option<u32>,
+    /// 下行带宽 (Mbps)
+    pub down_mbps: Option<u32>,
+    /// 忽略客户端带宽（服务端改用 BBR）
+    pub ignore_client_bandwidth: Option<bool>,
+    /// 伪装
+    pub masquerade: Option<MasqueradeSection>,
+}
+
+impl Default for Hysteria2Section {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: None,
+            listen: None,
+            sniff: None,
+            server_override: None,
+            sni_override: None,
+            obfs: None,
+            up_mbps: None,
+            down_mbps: None,
+            ignore_client_bandwidth: None,
+            masquerade: None,
+        }
+    }
+}
+
+/// Hysteria2 伪装设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MasqueradeSection {
+    /// 伪装类型（proxy / file / string）
+    #[serde(rename = "type")]
+    pub masquerade_type: Option<String>,
+    /// 反向代理目标 URL
+    pub url: Option<String>,
+    /// 文件服务器目录
+    pub dir: Option<String>,
+    /// 固定响应状态码
+    pub status: Option<u16>,
+}
+
+/// TUIC 设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuicSection {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监听端口
+    pub port: Option<u16>,
+    /// 监听地址
+    pub listen: Option<String>,
+    /// 是否启用协议嗅探（覆盖 [listen] 中的设置）
+    pub sniff: Option<bool>,
+    /// 客户端连接的服务器地址（覆盖 [client] 中的设置）
+    pub server_override: Option<String>,
+    /// 客户端使用的 SNI（覆盖 [client] 中的设置）
+    pub sni_override: Option<String>,
+    /// 拥塞控制算法
+    pub congestion_control: Option<String>,
+    /// 认证超时（如 3s）
+    pub auth_timeout: Option<String>,
+    /// 启用 0-RTT 握手
+    pub zero_rtt_handshake: Option<bool>,
+    /// 心跳间隔（如 10s）
+    pub heartbeat: Option<String>,
+    /// 客户端 UDP 中继模式（native / quic）
+    pub udp_relay_mode: Option<String>,
+}
+
+impl Default for TuicSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: None,
+            listen: None,
+            sniff: None,
+            server_override: None,
+            sni_override: None,
+            congestion_control: None,
+            auth_timeout: None,
+            zero_rtt_handshake: None,
+            heartbeat: None,
+            udp_relay_mode: None,
+        }
+    }
+}
+
+/// VLESS-Reality 设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VlessRealitySection {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监听端口
+    pub port: Option<u16>,
+    /// 监听地址
+    pub listen: Option<String>,
+    /// 是否启用协议嗅探（覆盖 [listen] 中的设置）
+    pub sniff: Option<bool>,
+    /// 客户端连接的服务器地址（覆盖 [client] 中的设置）
+    pub server_override: Option<String>,
+    /// 握手服务器
+    pub handshake_server: Option<String>,
+    /// 握手端口
+    pub handshake_port: Option<u16>,
+    /// 检测握手目标
+    pub check_target: Option<bool>,
+    /// 自动选择握手目标
+    pub auto_target: Option<bool>,
+    /// 指定的短 ID（第一个用于客户端）
+    pub short_ids: Vec<String>,
+    /// 自动生成的短 ID 数量
+    pub short_id_count: Option<u16>,
+    /// 自动生成的短 ID 字节长度
+    pub short_id_len: Option<u16>,
+    /// 复用的 REALITY 私钥
+    pub private_key: Option<String>,
+    /// 密钥对生成方式（internal / sing-box）
+    pub keygen: Option<String>,
+}
+
+impl Default for VlessRealitySection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: None,
+            listen: None,
+            sniff: None,
+            server_override: None,
+            handshake_server: None,
+            handshake_port: None,
+            check_target: None,
+            auto_target: None,
+            short_ids: Vec::new(),
+            short_id_count: None,
+            short_id_len: None,
+            private_key: None,
+            keygen: None,
+        }
+    }
+}
+
+/// VLESS over WebSocket 设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VlessWsSection {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监听端口
+    pub port: Option<u16>,
+    /// 监听地址
+    pub listen: Option<String>,
+    /// 是否启用协议嗅探（覆盖 [listen] 中的设置）
+    pub sniff: Option<bool>,
+    /// 客户端连接的服务器地址（覆盖 [client] 中的设置）
+    pub server_override: Option<String>,
+    /// 客户端使用的 SNI（覆盖 [client] 中的设置）
+    pub sni_override: Option<String>,
+    /// WebSocket 路径（不指定时随机生成）
+    pub path: Option<String>,
+    /// 客户端 Host 请求头（不指定时与 SNI 相同）
+    pub host: Option<String>,
+}
+
+impl Default for VlessWsSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: None,
+            listen: None,
+            sniff: None,
+            server_override: None,
+            sni_override: None,
+            path: None,
+            host: None,
+        }
+    }
+}
+
+/// VLESS over gRPC 设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VlessGrpcSection {
+    /// 是否启用
+    pub enabled: bool,
+    /// 监听端口
+    pub port: Option<u16>,
+    /// 监听地址
+    pub listen: Option<String>,
+    /// 是否启用协议嗅探（覆盖 [listen] 中的设置）
+    pub sniff: Option<bool>,
+    /// 客户端连接的服务器地址（覆盖 [client] 中的设置）
+    pub server_override: Option<String>,
+    /// 客户端使用的 SNI（覆盖 [client] 中的设置）
+    pub sni_override: Option<String>,
+    /// gRPC 服务名（不指定时随机生成）
+    pub service_name: Option<String>,
+}
+
+impl Default for VlessGrpcSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port: None,
+            listen: None,
+            sniff: None,
+            server_override: None,
+            sni_override: None,
+            service_name: None,
+        }
+    }
+}
+
+/// 用户条目
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserEntry {
+    /// 用户名
+    pub name: String,
+    /// 密码（不指定时随机生成）
+    #[serde(default)]
+    pub password: Option<String>,
+    /// 过期时间（UTC，如 2025-12-31）
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// 凭证轮换设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RotationSection {
+    /// 轮换间隔（如 30d）
+    pub interval: Option<String>,
+    /// 状态文件路径
+    pub state_path: Option<String>,
+    /// 轮换日志路径
+    pub log: Option<String>,
+    /// 同时轮换 REALITY 短 ID
+    pub short_ids: Option<bool>,
+    /// 状态文件加密密钥（age 口令或私钥）
+    pub encryption_key: Option<String>,
+}
+
+/// DNS 设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DnsSection {
+    /// 上游服务器（预设名称或 URL）
+    pub upstream: Option<String>,
+    /// 域名解析策略
+    pub strategy: Option<String>,
+    /// 在 DNS 层屏蔽的域名
+    pub block_domains: Vec<String>,
+    /// 屏蔽动作
+    pub block_action: Option<String>,
+}
+
+/// 滥用防护设置（生成服务端路由规则）
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsSection {
+    /// 规则作用的协议（为空时作用于全部入站）
+    pub protocols: Vec<Protocol>,
+    /// 拒绝的来源 IP / CIDR
+    pub blocked_sources: Vec<String>,
+    /// 嗅探并拒绝 BitTorrent 流量
+    pub block_bittorrent: Option<bool>,
+    /// 拒绝 SMTP 端口（25/465/587）
+    pub block_smtp: Option<bool>,
+    /// 拒绝访问私有地址
+    pub block_private: Option<bool>,
+    /// 拒绝访问的目标国家（GeoIP 国家代码）
+    pub geoip_block: Vec<String>,
+    /// 直连的目标国家
+    pub geoip_direct: Vec<String>,
+    /// 拒绝访问中国大陆的 IP 与域名
+    pub block_cn_inbound: Option<bool>,
+    /// 规则集下载地址前缀
+    pub rule_set_mirror: Option<String>,
+}
+
+/// 客户端配置导出设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientSection {
+    /// 客户端配置输出路径（可为目录或含 {protocol} / {user} 的模式）
+    pub config_path: Option<String>,
+    /// 客户端使用的协议
+    pub protocol: Option<Protocol>,
+    /// 客户端使用的用户
+    pub user: Option<String>,
+    /// mixed 入站监听地址
+    pub mixed_listen: Option<String>,
+    /// mixed 入站端口
+    pub mixed_port: Option<u16>,
+    /// 链式代理的前置节点（socks:// 或分享链接）
+    pub chain_front: Option<String>,
+    /// DNS 方案（simple / split / fakeip）
+    pub dns_mode: Option<String>,
+    /// 屏蔽广告与跟踪域名
+    pub adblock: Option<bool>,
+    /// 目标平台（desktop / ios / android / router）
+    pub platform: Option<String>,
+    /// 只代理的 Android 应用包名
+    pub include_packages: Vec<String>,
+    /// 不代理的 Android 应用包名
+    pub exclude_packages: Vec<String>,
+    /// uTLS 指纹（AnyTLS / VLESS）
+    pub utls_fingerprint: Option<String>,
+    /// 服务器地址（如 CDN 地址或与检测结果不同的入口 IP）
+    pub server_override: Option<String>,
+    /// SNI（VLESS Reality 除外）
+    pub sni_override: Option<String>,
+    /// 对等出站导出路径
+    pub export_path: Option<String>,
+    /// 自定义客户端模板路径
+    pub template_path: Option<String>,
+}
+
+/// HTTP 订阅服务设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServeSection {
+    /// 监听地址（逗号分隔多个，或 dual / dual:<端口>，unix://<路径> 为 Unix 套接字）
+    pub listen: Option<String>,
+    /// 订阅路径
+    pub path: Option<String>,
+    /// 公开访问地址
+    pub public_url: Option<String>,
+    /// 订阅名称
+    pub name: Option<String>,
+    /// Basic 认证用户名
+    pub basic_user: Option<String>,
+    /// Basic 认证密码
+    pub basic_pass: Option<String>,
+    /// 允许访问的来源地址段
+    pub allow_cidrs: Vec<String>,
+    /// 受信任的反向代理地址段
+    pub trusted_proxies: Vec<String>,
+    /// 路由匹配前去除的路径前缀
+    pub path_prefix: Option<String>,
+    /// 每个来源每分钟的请求数（0 为不限流）
+    pub rate_limit: Option<u32>,
+    /// 突发请求数
+    pub rate_burst: Option<u32>,
+    /// 签名订阅链接的服务端密钥
+    pub signing_key: Option<String>,
+    /// 审计日志路径
+    pub audit_log: Option<String>,
+    /// 远程配置订阅地址
+    pub remote_profile_url: Option<String>,
+    /// 远程配置名称
+    pub remote_profile_name: Option<String>,
+}
+
+/// sing-box 相关设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SingBoxSection {
+    /// sing-box 二进制文件路径
+    pub bin: Option<String>,
+    /// 运行前是否执行 sing-box check
+    pub check: Option<bool>,
+    /// 目标 sing-box 版本（低于 1.12 时输出旧版格式）
+    pub version: Option<String>,
+    /// run 模式下定期检查新版本
+    pub upgrade_check: Option<bool>,
+    /// 新版本检查间隔
+    pub upgrade_check_interval: Option<String>,
+    /// 发现新版本时自动升级
+    pub auto_upgrade: Option<bool>,
+    /// 自动升级的维护时间窗口（UTC）
+    pub upgrade_window: Option<String>,
+}
+
+/// 流量统计设置（Clash API）
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsSection {
+    /// Clash API 监听地址
+    pub clash_api: Option<String>,
+    /// Clash API 密钥
+    pub clash_api_secret: Option<String>,
+    /// 轮询间隔
+    pub interval: Option<String>,
+    /// 统计文件路径
+    pub path: Option<String>,
+    /// /metrics 监听地址
+    pub metrics_listen: Option<String>,
+}
+
+/// 通知设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifySection {
+    /// Telegram 机器人令牌
+    pub telegram_bot_token: Option<String>,
+    /// Telegram 会话 ID
+    pub telegram_chat_id: Option<String>,
+    /// Webhook 地址
+    pub webhook_url: Option<String>,
+    /// Webhook 签名密钥
+    pub webhook_secret: Option<String>,
+    /// 只发送这些事件
+    pub webhook_events: Vec<String>,
+}
+
+/// WARP 出口设置（WireGuard 端点）
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WarpSection {
+    /// WireGuard 私钥
+    pub private_key: Option<String>,
+    /// 本地地址（CIDR）
+    pub address: Vec<String>,
+    /// 对端地址（host:port）
+    pub peer: Option<String>,
+    /// 对端公钥
+    pub peer_public_key: Option<String>,
+    /// 保留字节（1,2,3 或 base64 client_id）
+    pub reserved: Option<String>,
+    /// MTU
+    pub mtu: Option<u16>,
+    /// 经 WARP 转发的域名后缀
+    pub domains: Vec<String>,
+    /// 经 WARP 转发的内置服务
+    pub services: Vec<String>,
+    /// 全部流量经 WARP 转发
+    pub all: Option<bool>,
+}
+
+/// 健康检查设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthSection {
+    /// 连接的地址
+    pub host: Option<IpAddr>,
+    /// 单个入站的超时
+    pub timeout: Option<String>,
+    /// 检查 Hysteria2 / TUIC 的 UDP 端口是否可达
+    pub udp_selftest: Option<bool>,
+    /// 外部 UDP 探测服务地址模板
+    pub udp_probe_url: Option<String>,
+}
+
+/// 延迟测试设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UrlTestSection {
+    /// 测试地址（只支持 http://）
+    pub url: Option<String>,
+    /// 单个协议的超时
+    pub timeout: Option<String>,
+}
+
+/// 远程部署设置
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeploySection {
+    /// 远程配置目录
+    pub dir: Option<String>,
+    /// 远程 sing-box 二进制文件路径
+    pub remote_bin: Option<String>,
+    /// 上传的本地 sing-box 二进制文件
+    pub binary: Option<String>,
+    /// systemd 服务名
+    pub service: Option<String>,
+    /// SSH 私钥文件
+    pub ssh_key: Option<String>,
+}
+
+//============================================================================
+// 加载与转换
+//============================================================================
+
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+}
+
+impl FileFormat {
+    /// 根据扩展名判断格式（未知扩展名按 TOML 处理）
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("yaml" | "yml") => FileFormat::Yaml,
+            _ => FileFormat::Toml,
+        }
+    }
+}
+
+impl FileConfig {
+    /// 解析指定格式的配置内容
+    pub fn parse(content: &str, format: FileFormat) -> Result<Self, String> {
+        match format {
+            FileFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            FileFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 从文件加载配置
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件失败 {}: {}", path.display(), e))?;
+        Self::parse(&content, FileFormat::from_path(path))
+            .map_err(|e| format!("解析配置文件失败 {}: {}", path.display(), e))
+    }
+
+    /// 转换为环境变量默认值表
+    pub fn to_env_values(&self) -> Result<HashMap<String, String>, String> {
+        let mut map = HashMap::new();
+        let mut put = |key: &str, value: Option<String>| {
+            if let Some(v) = value {
+                map.insert(key.to_string(), v);
+            }
+        };
+
+        put("EZ_CONFIG_PATH", self.config_path.clone());
+        put(
+            "EZ_CONFIG_BACKUPS",
+            self.config_backups.map(|v| v.to_string()),
+        );
+        put("EZ_PUBLIC_IP", self.public_ip.map(|v| v.to_string()));
+        put("EZ_PRESET", self.preset.clone());
+        if !self.servers.is_empty() {
+            let servers = serde_json::to_string(&self.servers).map_err(|e| e.to_string())?;
+            put("EZ_FLEET", Some(servers));
+        }
+        put("EZ_FLEET_DIR", self.fleet_dir.clone());
+        if !self.ip_detector.is_empty() {
+            put("EZ_IP_DETECTOR", Some(self.ip_detector.join(",")));
+        }
+        if !self.ip_services.is_empty() {
+            put("EZ_IP_SERVICES", Some(self.ip_services.join(",")));
+        }
+        put("EZ_LOG_LEVEL", self.log_level.clone());
+        put("EZ_LOG_FORMAT", self.log_format.clone());
+        put("EZ_PRINT_CONFIG", self.print_config.map(|v| v.to_string()));
+        put(
+            "EZ_CANONICAL_OUTPUT",
+            self.canonical_output.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_MINIMAL_CONFIG",
+            self.minimal_config.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_ANNOTATE_CONFIG",
+            self.annotate_config.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_PRINT_DETAILS",
+            self.print_details.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_REDACT_SECRETS",
+            self.redact_secrets.map(|v| v.to_string()),
+        );
+        put("EZ_ERROR_FORMAT", self.error_format.clone());
+        put("EZ_OUTPUT", self.output.clone());
+        put("EZ_LANG", self.lang.clone());
+        put(
+            "EZ_RELAY_UPSTREAM",
+            self.relay_upstream.as_ref().map(|v| match v {
+                serde_json::Value::String(link) => link.clone(),
+                outbound => outbound.to_string(),
+            }),
+        );
+        put("EZ_DOMAIN", self.tls.domain.clone());
+        put("EZ_DOMAIN_PROVIDER", self.tls.domain_provider.clone());
+        put("EZ_DOMAIN_PATTERN", self.tls.domain_pattern.clone());
+        put("EZ_DUCKDNS_DOMAIN", self.tls.duckdns_domain.clone());
+        put("EZ_DUCKDNS_TOKEN", self.tls.duckdns_token.clone());
+        put("EZ_ACME_EMAIL", self.tls.acme_email.clone());
+        put("EZ_ACME_PROVIDER", self.tls.acme_provider.clone());
+        put("EZ_ACME_DATA_DIR", self.tls.acme_data_dir.clone());
+        put(
+            "EZ_ACME_PREFLIGHT",
+            self.tls.acme_preflight.map(|v| v.to_string()),
+        );
+        if let Some(ref eab) = self.tls.eab {
+            put("EZ_ACME_EAB_KEY_ID", eab.key_id.clone());
+            put("EZ_ACME_EAB_MAC_KEY", eab.mac_key.clone());
+        }
+        if let Some(ref dns01) = self.tls.dns01 {
+            put("EZ_ACME_DNS01_PROVIDER", dns01.provider.clone());
+            put("EZ_CF_API_TOKEN", dns01.api_token.clone());
+            put("EZ_ALIDNS_ACCESS_KEY_ID", dns01.access_key_id.clone());
+            put(
+                "EZ_ALIDNS_ACCESS_KEY_SECRET",
+                dns01.access_key_secret.clone(),
+            );
+            put("EZ_ALIDNS_REGION_ID", dns01.region_id.clone());
+        }
+        put(
+            "EZ_TCP_FAST_OPEN",
+            self.listen.tcp_fast_open.map(|v| v.to_string()),
+        );
+        put("EZ_UDP_TIMEOUT", self.listen.udp_timeout.clone());
+        put("EZ_SNIFF", self.listen.sniff.map(|v| v.to_string()));
+        put(
+            "EZ_SNIFF_OVERRIDE_DESTINATION",
+            self.listen
+                .sniff_override_destination
+                .map(|v| v.to_string()),
+        );
+        put("EZ_BIND_INTERFACE", self.listen.bind_interface.clone());
+        put("EZ_ROUTING_MARK", self.listen.routing_mark.clone());
+        put("EZ_NETNS", self.listen.netns.clone());
+
+        if let Some(ref p) = self.protocols {
+            let anytls = p.anytls.as_ref().filter(|s| s.enabled);
+            put("EZ_ENABLE_ANYTLS", Some(anytls.is_some().to_string()));
+            if let Some(s) = anytls {
+                put("EZ_ANYTLS_PORT", s.port.map(|v| v.to_string()));
+                put("EZ_ANYTLS_LISTEN", s.listen.clone());
+                put("EZ_SNIFF_ANYTLS", s.sniff.map(|v| v.to_string()));
+                put(
+                    "EZ_CLIENT_SERVER_OVERRIDE_ANYTLS",
+                    s.server_override.clone(),
+                );
+                put("EZ_SNI_OVERRIDE_ANYTLS", s.sni_override.clone());
+            }
+
+            let hy2 = p.hysteria2.as_ref().filter(|s| s.enabled);
+            put("EZ_ENABLE_HYSTERIA2", Some(hy2.is_some().to_string()));
+            if let Some(s) = hy2 {
+                put("EZ_HYSTERIA2_PORT", s.port.map(|v| v.to_string()));
+                put("EZ_HYSTERIA2_LISTEN", s.listen.clone());
+                put("EZ_SNIFF_HYSTERIA2", s.sniff.map(|v| v.to_string()));
+                put(
+                    "EZ_CLIENT_SERVER_OVERRIDE_HYSTERIA2",
+                    s.server_override.clone(),
+                );
+                put("EZ_SNI_OVERRIDE_HYSTERIA2", s.sni_override.clone());
+                put("EZ_HY2_OBFS", s.obfs.map(|v| v.to_string()));
+                put("EZ_HY2_UP_MBPS", s.up_mbps.map(|v| v.to_string()));
+                put("EZ_HY2_DOWN_MBPS", s.down_mbps.map(|v| v.to_string()));
+                put(
+                    "EZ_HY2_IGNORE_CLIENT_BW",
+                    s.ignore_client_bandwidth.map(|v| v.to_string()),
+                );
+                if let Some(ref m) = s.masquerade {
+                    put("EZ_HY2_MASQUERADE_TYPE", m.masquerade_type.clone());
+                    put("EZ_HY2_MASQUERADE_URL", m.url.clone());
+                    put("EZ_HY2_MASQUERADE_DIR", m.dir.clone());
+                    put("EZ_HY2_MASQUERADE_STATUS", m.status.map(|v| v.to_string()));
+                }
+            }
+
+            let tuic = p.tuic.as_ref().filter(|s| s.enabled);
+            put("EZ_ENABLE_TUIC", Some(tuic.is_some().to_string()));
+            if let Some(s) = tuic {
+                put("EZ_TUIC_PORT", s.port.map(|v| v.to_string()));
+                put("EZ_TUIC_LISTEN", s.listen.clone());
+                put("EZ_SNIFF_TUIC", s.sniff.map(|v| v.to_string()));
+                put("EZ_CLIENT_SERVER_OVERRIDE_TUIC", s.server_override.clone());
+                put("EZ_SNI_OVERRIDE_TUIC", s.sni_override.clone());
+                put("EZ_TUIC_CC", s.congestion_control.clone());
+                put("EZ_TUIC_AUTH_TIMEOUT", s.auth_timeout.clone());
+                put(
+                    "EZ_TUIC_ZERO_RTT",
+                    s.zero_rtt_handshake.map(|v| v.to_string()),
+                );
+                put("EZ_TUIC_HEARTBEAT", s.heartbeat.clone());
+                put("EZ_TUIC_UDP_RELAY_MODE", s.udp_relay_mode.clone());
+            }
+
+            let vless = p.vless_reality.as_ref().filter(|s| s.enabled);
+            put("EZ_ENABLE_VLESS_REALITY", Some(vless.is_some().to_string()));
+            if let Some(s) = vless {
+                put("EZ_VLESS_REALITY_PORT", s.port.map(|v| v.to_string()));
+                put("EZ_VLESS_REALITY_LISTEN", s.listen.clone());
+                put("EZ_SNIFF_VLESS_REALITY", s.sniff.map(|v| v.to_string()));
+                put(
+                    "EZ_CLIENT_SERVER_OVERRIDE_VLESS_REALITY",
+                    s.server_override.clone(),
+                );
+                put("EZ_VLESS_HANDSHAKE_SERVER", s.handshake_server.clone());
+                put(
+                    "EZ_VLESS_HANDSHAKE_PORT",
+                    s.handshake_port.map(|v| v.to_string()),
+                );
+                put(
+                    "EZ_REALITY_CHECK_TARGET",
+                    s.check_target.map(|v| v.to_string()),
+                );
+                put(
+                    "EZ_REALITY_AUTO_TARGET",
+                    s.auto_target.map(|v| v.to_string()),
+                );
+                if !s.short_ids.is_empty() {
+                    put("EZ_REALITY_SHORT_IDS", Some(s.short_ids.join(",")));
+                }
+                put(
+                    "EZ_REALITY_SHORT_ID_COUNT",
+                    s.short_id_count.map(|v| v.to_string()),
+                );
+                put(
+                    "EZ_REALITY_SHORT_ID_LEN",
+                    s.short_id_len.map(|v| v.to_string()),
+                );
+                put("EZ_REALITY_PRIVATE_KEY", s.private_key.clone());
+                put("EZ_REALITY_KEYGEN", s.keygen.clone());
+            }
+
+            let ws = p.vless_ws.as_ref().filter(|s| s.enabled);
+            put("EZ_ENABLE_VLESS_WS", Some(ws.is_some().to_string()));
+            if let Some(s) = ws {
+                put("EZ_VLESS_WS_PORT", s.port.map(|v| v.to_string()));
+                put("EZ_VLESS_WS_LISTEN", s.listen.clone());
+                put("EZ_VLESS_WS_PATH", s.path.clone());
+                put("EZ_VLESS_WS_HOST", s.host.clone());
+                put("EZ_SNIFF_VLESS_WS", s.sniff.map(|v| v.to_string()));
+                put(
+                    "EZ_CLIENT_SERVER_OVERRIDE_VLESS_WS",
+                    s.server_override.clone(),
+                );
+                put("EZ_SNI_OVERRIDE_VLESS_WS", s.sni_override.clone());
+            }
+
+            let grpc = p.vless_grpc.as_ref().filter(|s| s.enabled);
+            put("EZ_ENABLE_VLESS_GRPC", Some(grpc.is_some().to_string()));
+            if let Some(s) = grpc {
+                put("EZ_VLESS_GRPC_PORT", s.port.map(|v| v.to_string()));
+                put("EZ_VLESS_GRPC_LISTEN", s.listen.clone());
+                put("EZ_VLESS_GRPC_SERVICE", s.service_name.clone());
+                put("EZ_SNIFF_VLESS_GRPC", s.sniff.map(|v| v.to_string()));
+                put(
+                    "EZ_CLIENT_SERVER_OVERRIDE_VLESS_GRPC",
+                    s.server_override.clone(),
+                );
+                put("EZ_SNI_OVERRIDE_VLESS_GRPC", s.sni_override.clone());
+            }
+
+            if let Some(ref b) = p.tcp_brutal {
+                put("EZ_BRUTAL_UP_MBPS", b.up_mbps.map(|v| v.to_string()));
+                put("EZ_BRUTAL_DOWN_MBPS", b.down_mbps.map(|v| v.to_string()));
+            }
+            if let Some(ref m) = p.multiplex {
+                put("EZ_MUX_PROTOCOL", m.protocol.clone());
+                put("EZ_MUX_MAX_STREAMS", m.max_streams.map(|v| v.to_string()));
+                put("EZ_MUX_PADDING", m.padding.map(|v| v.to_string()));
+            }
+            put("EZ_CDN_HOST", p.cdn_host.clone());
+        }
+
+        if self.users.iter().any(|u| u.expires_at.is_some()) {
+            put("EZ_USERS_JSON", Some(encode_users_json(&self.users)?));
+        } else if !self.users.is_empty() {
+            put("EZ_USERS", Some(encode_users(&self.users)?));
+        }
+        put("EZ_ROTATE_INTERVAL", self.rotation.interval.clone());
+        put("EZ_STATE_PATH", self.rotation.state_path.clone());
+        put("EZ_ROTATION_LOG", self.rotation.log.clone());
+        put(
+            "EZ_ROTATE_SHORT_IDS",
+            self.rotation.short_ids.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_STATE_ENCRYPTION_KEY",
+            self.rotation.encryption_key.clone(),
+        );
+
+        put("EZ_DNS_UPSTREAM", self.dns.upstream.clone());
+        put("EZ_DNS_STRATEGY", self.dns.strategy.clone());
+        if !self.dns.block_domains.is_empty() {
+            put(
+                "EZ_DNS_BLOCK_DOMAINS",
+                Some(self.dns.block_domains.join(",")),
+            );
+        }
+        put("EZ_DNS_BLOCK_ACTION", self.dns.block_action.clone());
+
+        if !self.limits.protocols.is_empty() {
+            let names: Vec<&str> = self.limits.protocols.iter().map(|p| p.as_str()).collect();
+            put("EZ_LIMIT_PROTOCOLS", Some(names.join(",")));
+        }
+        if !self.limits.blocked_sources.is_empty() {
+            put(
+                "EZ_LIMIT_BLOCKED_SOURCES",
+                Some(self.limits.blocked_sources.join(",")),
+            );
+        }
+        put(
+            "EZ_LIMIT_BLOCK_BITTORRENT",
+            self.limits.block_bittorrent.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_LIMIT_BLOCK_SMTP",
+            self.limits.block_smtp.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_LIMIT_BLOCK_PRIVATE",
+            self.limits.block_private.map(|v| v.to_string()),
+        );
+        if !self.limits.geoip_block.is_empty() {
+            put("EZ_GEOIP_BLOCK", Some(self.limits.geoip_block.join(",")));
+        }
+        if !self.limits.geoip_direct.is_empty() {
+            put("EZ_GEOIP_DIRECT", Some(self.limits.geoip_direct.join(",")));
+        }
+        put(
+            "EZ_BLOCK_CN_INBOUND",
+            self.limits.block_cn_inbound.map(|v| v.to_string()),
+        );
+        put("EZ_RULE_SET_MIRROR", self.limits.rule_set_mirror.clone());
+
+        put("EZ_CLIENT_CONFIG_PATH", self.client.config_path.clone());
+        put(
+            "EZ_CLIENT_PROTOCOL",
+            self.client.protocol.map(|v| v.to_string()),
+        );
+        put("EZ_CLIENT_USER", self.client.user.clone());
+        put("EZ_CLIENT_MIXED_LISTEN", self.client.mixed_listen.clone());
+        put(
+            "EZ_CLIENT_MIXED_PORT",
+            self.client.mixed_port.map(|v| v.to_string()),
+        );
+        put("EZ_CLIENT_CHAIN_FRONT", self.client.chain_front.clone());
+        put("EZ_CLIENT_DNS_MODE", self.client.dns_mode.clone());
+        put(
+            "EZ_CLIENT_ADBLOCK",
+            self.client.adblock.map(|v| v.to_string()),
+        );
+        put("EZ_CLIENT_PLATFORM", self.client.platform.clone());
+        if !self.client.include_packages.is_empty() {
+            put(
+                "EZ_CLIENT_INCLUDE_PACKAGES",
+                Some(self.client.include_packages.join(",")),
+            );
+        }
+        if !self.client.exclude_packages.is_empty() {
+            put(
+                "EZ_CLIENT_EXCLUDE_PACKAGES",
+                Some(self.client.exclude_packages.join(",")),
+            );
+        }
+        put(
+            "EZ_CLIENT_UTLS_FINGERPRINT",
+            self.client.utls_fingerprint.clone(),
+        );
+        put(
+            "EZ_CLIENT_SERVER_OVERRIDE",
+            self.client.server_override.clone(),
+        );
+        put("EZ_SNI_OVERRIDE", self.client.sni_override.clone());
+        put("EZ_EXPORT_PATH", self.client.export_path.clone());
+        put("EZ_CLIENT_TEMPLATE_PATH", self.client.template_path.clone());
+
+        put("EZ_SUBSCRIBE_LISTEN", self.serve.listen.clone());
+        put("EZ_SUBSCRIBE_PATH", self.serve.path.clone());
+        put("EZ_SUBSCRIBE_PUBLIC_URL", self.serve.public_url.clone());
+        put("EZ_SUBSCRIBE_NAME", self.serve.name.clone());
+        put("EZ_SUBSCRIBE_BASIC_USER", self.serve.basic_user.clone());
+        put("EZ_SUBSCRIBE_BASIC_PASS", self.serve.basic_pass.clone());
+        if !self.serve.allow_cidrs.is_empty() {
+            put(
+                "EZ_SUBSCRIBE_ALLOW_CIDRS",
+                Some(self.serve.allow_cidrs.join(",")),
+            );
+        }
+        if !self.serve.trusted_proxies.is_empty() {
+            put(
+                "EZ_SUBSCRIBE_TRUSTED_PROXIES",
+                Some(self.serve.trusted_proxies.join(",")),
+            );
+        }
+        put("EZ_SUBSCRIBE_PATH_PREFIX", self.serve.path_prefix.clone());
+        put(
+            "EZ_SUBSCRIBE_RATE_LIMIT",
+            self.serve.rate_limit.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_SUBSCRIBE_RATE_BURST",
+            self.serve.rate_burst.map(|v| v.to_string()),
+        );
+        put("EZ_SUBSCRIBE_SIGNING_KEY", self.serve.signing_key.clone());
+        put("EZ_AUDIT_LOG", self.serve.audit_log.clone());
+        put(
+            "EZ_REMOTE_PROFILE_URL",
+            self.serve.remote_profile_url.clone(),
+        );
+        put(
+            "EZ_REMOTE_PROFILE_NAME",
+            self.serve.remote_profile_name.clone(),
+        );
+
+        put("SING_BOX_BIN", self.sing_box.bin.clone());
+        put(
+            "EZ_SINGBOX_CHECK",
+            self.sing_box.check.map(|v| v.to_string()),
+        );
+        put("EZ_SINGBOX_VERSION", self.sing_box.version.clone());
+        put(
+            "EZ_AUTO_UPGRADE_CHECK",
+            self.sing_box.upgrade_check.map(|v| v.to_string()),
+        );
+        put(
+            "EZ_UPGRADE_CHECK_INTERVAL",
+            self.sing_box.upgrade_check_interval.clone(),
+        );
+        put(
+            "EZ_AUTO_UPGRADE",
+            self.sing_box.auto_upgrade.map(|v| v.to_string()),
+        );
+        put("EZ_UPGRADE_WINDOW", self.sing_box.upgrade_window.clone());
+        put("EZ_CLASH_API", self.stats.clash_api.clone());
+        put("EZ_CLASH_API_SECRET", self.stats.clash_api_secret.clone());
+        put("EZ_STATS_INTERVAL", self.stats.interval.clone());
+        put("EZ_STATS_PATH", self.stats.path.clone());
+        put("EZ_METRICS_LISTEN", self.stats.metrics_listen.clone());
+        put("EZ_WARP_PRIVATE_KEY", self.warp.private_key.clone());
+        if !self.warp.address.is_empty() {
+            put("EZ_WARP_ADDRESS", Some(self.warp.address.join(",")));
+        }
+        put("EZ_WARP_PEER", self.warp.peer.clone());
+        put("EZ_WARP_PEER_PUBLIC_KEY", self.warp.peer_public_key.clone());
+        put("EZ_WARP_RESERVED", self.warp.reserved.clone());
+        put("EZ_WARP_MTU", self.warp.mtu.map(|v| v.to_string()));
+        if !self.warp.domains.is_empty() {
+            put("EZ_WARP_DOMAINS", Some(self.warp.domains.join(",")));
+        }
+        if !self.warp.services.is_empty() {
+            put("EZ_WARP_SERVICES", Some(self.warp.services.join(",")));
+        }
+        put("EZ_WARP_ALL", self.warp.all.map(|v| v.to_string()));
+        put(
+            "EZ_TELEGRAM_BOT_TOKEN",
+            self.notify.telegram_bot_token.clone(),
+        );
+        put("EZ_TELEGRAM_CHAT_ID", self.notify.telegram_chat_id.clone());
+        put("EZ_WEBHOOK_URL", self.notify.webhook_url.clone());
+        put("EZ_WEBHOOK_SECRET", self.notify.webhook_secret.clone());
+        if !self.notify.webhook_events.is_empty() {
+            put(
+                "EZ_WEBHOOK_EVENTS",
+                Some(self.notify.webhook_events.join(",")),
+            );
+        }
+        put("EZ_HEALTH_HOST", self.health.host.map(|v| v.to_string()));
+        put("EZ_HEALTH_TIMEOUT", self.health.timeout.clone());
+        put(
+            "EZ_UDP_SELFTEST",
+            self.health.udp_selftest.map(|v| v.to_string()),
+        );
+        put("EZ_UDP_PROBE_URL", self.health.udp_probe_url.clone());
+        put("EZ_URLTEST_URL", self.urltest.url.clone());
+        put("EZ_URLTEST_TIMEOUT", self.urltest.timeout.clone());
+
+        put("EZ_DEPLOY_DIR", self.deploy.dir.clone());
+        put("EZ_DEPLOY_REMOTE_BIN", self.deploy.remote_bin.clone());
+        put("EZ_DEPLOY_BINARY", self.deploy.binary.clone());
+        put("EZ_DEPLOY_SERVICE", self.deploy.service.clone());
+        put("EZ_DEPLOY_SSH_KEY", self.deploy.ssh_key.clone());
+
+        Ok(map)
+    }
+}
+
+/// 将用户列表编码为 EZ_USERS 格式（`name[:password],...`）
+fn encode_users(users: &[UserEntry]) -> Result<String, String> {
+    let mut parts = Vec::with_capacity(users.len());
+    for u in users {
+        validate_user_name(&u.name)?;
+        let name = u.name.trim();
+        match u.password.as_deref() {
+            Some(pwd) if pwd.contains(',') => {
+                return Err(format!("用户 {} 的密码不能包含 ','", name));
+            }
+            Some(pwd) => parts.push(format!("{}:{}", name, pwd)),
+            None => parts.push(name.to_string()),
+        }
+    }
+    Ok(parts.join(","))
+}
+
+/// 将用户列表编码为 EZ_USERS_JSON 格式（存在带过期时间的用户时使用）
+fn encode_users_json(users: &[UserEntry]) -> Result<String, String> {
+    let mut entries = Vec::with_capacity(users.len());
+    for u in users {
+        validate_user_name(&u.name)?;
+        let mut entry = serde_json::json!({ "name": u.name.trim() });
+        if let Some(ref pwd) = u.password {
+            entry["password"] = pwd.clone().into();
+        }
+        if let Some(ref expires_at) = u.expires_at {
+            parse_expiry(expires_at).map_err(|e| format!("用户 {}: {}", u.name.trim(), e))?;
+            entry["expires_at"] = expires_at.clone().into();
+        }
+        entries.push(entry);
+    }
+    Ok(serde_json::Value::Array(entries).to_string())
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_TOML: &str = r#"
+config_path = "/etc/sing-box/config.json"
+public_ip = "203.0.113.1"
+preset = "stealth"
+fleet_dir = "/srv/fleet"
+lang = "en"
+redact_secrets = true
+canonical_output = true
+minimal_config = true
+annotate_config = true
+relay_upstream = { type = "socks", server = "10.0.0.2", server_port = 1080 }
+
+[tls]
+domain = "example.com"
+domain_provider = "duckdns"
+duckdns_domain = "myhost"
+acme_email = "admin@example.com"
+acme_provider = "zerossl"
+acme_preflight = false
+
+[tls.eab]
+key_id = "kid"
+mac_key = "hmac"
+
+[listen]
+sniff = true
+udp_timeout = "1m"
+bind_interface = "eth1"
+
+[protocols.hysteria2]
+port = 8443
+obfs = true
+server_override = "203.0.113.9"
+
+[protocols.hysteria2.masquerade]
+type = "proxy"
+url = "https://www.bing.com"
+
+[protocols.tuic]
+listen = "203.0.113.9"
+congestion_control = "cubic"
+sniff = false
+heartbeat = "15s"
+udp_relay_mode = "quic"
+
+[protocols.anytls]
+enabled = false
+
+[[users]]
+name = "alice"
+password = "secret"
+
+[[users]]
+name = "bob"
+
+[limits]
+protocols = ["anytls", "tuic"]
+blocked_sources = ["198.51.100.0/24"]
+block_bittorrent = true
+geoip_block = ["ir", "kp"]
+block_cn_inbound = true
+
+[client]
+protocol = "hy2"
+dns_mode = "fakeip"
+adblock = true
+platform = "android"
+include_packages = ["org.telegram.messenger", "com.android.chrome"]
+utls_fingerprint = "firefox"
+server_override = "cdn.example.net"
+
+[serve]
+listen = "127.0.0.1:8080"
+allow_cidrs = ["10.0.0.0/8", "2001:db8::/32"]
+trusted_proxies = ["127.0.0.1/32", "::1/128"]
+path_prefix = "/ezsub"
+rate_limit = 20
+signing_key = "sign-secret"
+audit_log = "/var/log/ezsingbox/audit.log"
+
+[sing_box]
+version = "1.10"
+upgrade_check = true
+upgrade_window = "03:00-05:00"
+
+[rotation]
+interval = "30d"
+short_ids = true
+encryption_key = "correct horse battery staple"
+
+[health]
+udp_probe_url = "https://probe.example.com/quic?host={host}&port={port}"
+
+[stats]
+clash_api = "127.0.0.1:9090"
+metrics_listen = "127.0.0.1:9100"
+
+[warp]
+private_key = "key"
+address = ["172.16.0.2/32", "2606:4700:110:8a36::1/128"]
+services = ["openai", "netflix"]
+
+[notify]
+telegram_bot_token = "123456:ABC"
+telegram_chat_id = "-1001234567890"
+webhook_url = "https://hooks.example.com/ezsingbox"
+webhook_events = ["config.generated", "health.failed"]
+
+[deploy]
+binary = "./sing-box"
+ssh_key = "~/.ssh/id_ed25519"
+
+[[servers]]
+name = "jp-1"
+public_ip = "203.0.113.1"
+ssh = "root@203.0.113.1"
+
+[[servers]]
+name = "us-2"
+public_ip = "198.51.100.2"
+domain = "us.example.com"
+"#;
+
+    #[test]
+    fn test_parse_toml() {
+        let cfg = FileConfig::parse(SAMPLE_TOML, FileFormat::Toml).unwrap();
+        assert_eq!(cfg.tls.domain.as_deref(), Some("example.com"));
+        assert_eq!(cfg.users.len(), 2);
+        assert_eq!(cfg.client.protocol, Some(Protocol::Hysteria2));
+
+        let env = cfg.to_env_values().unwrap();
+        assert_eq!(env["EZ_CONFIG_PATH"], "/etc/sing-box/config.json");
+        assert_eq!(env["EZ_PUBLIC_IP"], "203.0.113.1");
+        assert_eq!(env["EZ_PRESET"], "stealth");
+        assert_eq!(env["EZ_FLEET_DIR"], "/srv/fleet");
+        assert_eq!(env["EZ_LANG"], "en");
+        assert_eq!(env["EZ_REDACT_SECRETS"], "true");
+        assert_eq!(env["EZ_CANONICAL_OUTPUT"], "true");
+        assert_eq!(env["EZ_MINIMAL_CONFIG"], "true");
+        assert_eq!(env["EZ_ANNOTATE_CONFIG"], "true");
+        assert_eq!(env["EZ_DEPLOY_BINARY"], "./sing-box");
+        assert_eq!(env["EZ_DEPLOY_SSH_KEY"], "~/.ssh/id_ed25519");
+        assert!(!env.contains_key("EZ_DEPLOY_DIR"));
+        let fleet = crate::fleet::parse_fleet(&env["EZ_FLEET"]).unwrap();
+        assert_eq!(fleet, cfg.servers);
+        assert_eq!(fleet[1].domain.as_deref(), Some("us.example.com"));
+        assert_eq!(env["EZ_DOMAIN"], "example.com");
+        assert_eq!(env["EZ_DOMAIN_PROVIDER"], "duckdns");
+        assert_eq!(env["EZ_DUCKDNS_DOMAIN"], "myhost");
+        assert_eq!(env["EZ_ACME_PROVIDER"], "zerossl");
+        assert_eq!(env["EZ_ACME_EAB_KEY_ID"], "kid");
+        assert_eq!(env["EZ_ACME_EAB_MAC_KEY"], "hmac");
+        assert_eq!(env["EZ_ACME_PREFLIGHT"], "false");
+        assert_eq!(env["EZ_SNIFF"], "true");
+        assert_eq!(env["EZ_UDP_TIMEOUT"], "1m");
+        assert_eq!(env["EZ_BIND_INTERFACE"], "eth1");
+        assert!(!env.contains_key("EZ_TCP_FAST_OPEN"));
+        assert_eq!(env["EZ_ENABLE_ANYTLS"], "false");
+        assert_eq!(env["EZ_ENABLE_HYSTERIA2"], "true");
+        assert_eq!(env["EZ_ENABLE_TUIC"], "true");
+        assert_eq!(env["EZ_ENABLE_VLESS_REALITY"], "false");
+        assert_eq!(env["EZ_HYSTERIA2_PORT"], "8443");
+        assert_eq!(env["EZ_TUIC_LISTEN"], "203.0.113.9");
+        assert_eq!(env["EZ_HY2_OBFS"], "true");
+        assert_eq!(env["EZ_HY2_MASQUERADE_TYPE"], "proxy");
+        assert_eq!(env["EZ_HY2_MASQUERADE_URL"], "https://www.bing.com");
+        assert!(!env.contains_key("EZ_HY2_MASQUERADE_STATUS"));
+        assert_eq!(env["EZ_TUIC_CC"], "cubic");
+        assert_eq!(env["EZ_TUIC_HEARTBEAT"], "15s");
+        assert_eq!(env["EZ_TUIC_UDP_RELAY_MODE"], "quic");
+        assert_eq!(env["EZ_SNIFF_TUIC"], "false");
+        assert!(!env.contains_key("EZ_SNIFF_HYSTERIA2"));
+        assert!(!env.contains_key("EZ_TUIC_ZERO_RTT"));
+        assert_eq!(env["EZ_USERS"], "alice:secret,bob");
+        assert_eq!(env["EZ_ROTATE_INTERVAL"], "30d");
+        assert_eq!(env["EZ_ROTATE_SHORT_IDS"], "true");
+        assert_eq!(
+            env["EZ_STATE_ENCRYPTION_KEY"],
+            "correct horse battery staple"
+        );
+        assert!(!env.contains_key("EZ_STATE_PATH"));
+        assert_eq!(env["EZ_CLIENT_PROTOCOL"], "hysteria2");
+        assert_eq!(env["EZ_CLIENT_DNS_MODE"], "fakeip");
+        assert_eq!(env["EZ_CLIENT_ADBLOCK"], "true");
+        assert_eq!(env["EZ_CLIENT_PLATFORM"], "android");
+        assert_eq!(
+            env["EZ_CLIENT_INCLUDE_PACKAGES"],
+            "org.telegram.messenger,com.android.chrome"
+        );
+        assert!(!env.contains_key("EZ_CLIENT_EXCLUDE_PACKAGES"));
+        assert_eq!(env["EZ_CLIENT_UTLS_FINGERPRINT"], "firefox");
+        assert_eq!(env["EZ_CLIENT_SERVER_OVERRIDE"], "cdn.example.net");
+        assert_eq!(env["EZ_CLIENT_SERVER_OVERRIDE_HYSTERIA2"], "203.0.113.9");
+        assert!(!env.contains_key("EZ_SNI_OVERRIDE"));
+        assert_eq!(env["EZ_SINGBOX_VERSION"], "1.10");
+        assert_eq!(env["EZ_AUTO_UPGRADE_CHECK"], "true");
+        assert_eq!(env["EZ_UPGRADE_WINDOW"], "03:00-05:00");
+        assert!(!env.contains_key("EZ_AUTO_UPGRADE"));
+        assert_eq!(
+            env["EZ_UDP_PROBE_URL"],
+            "https://probe.example.com/quic?host={host}&port={port}"
+        );
+        assert!(!env.contains_key("EZ_UDP_SELFTEST"));
+        assert_eq!(env["EZ_CLASH_API"], "127.0.0.1:9090");
+        assert_eq!(env["EZ_METRICS_LISTEN"], "127.0.0.1:9100");
+        assert_eq!(
+            env["EZ_WARP_ADDRESS"],
+            "172.16.0.2/32,2606:4700:110:8a36::1/128"
+        );
+        assert_eq!(env["EZ_WARP_SERVICES"], "openai,netflix");
+        assert_eq!(env["EZ_TELEGRAM_CHAT_ID"], "-1001234567890");
+        assert_eq!(env["EZ_WEBHOOK_EVENTS"], "config.generated,health.failed");
+        assert!(!env.contains_key("EZ_STATS_INTERVAL"));
+        assert_eq!(env["EZ_SUBSCRIBE_LISTEN"], "127.0.0.1:8080");
+        assert_eq!(env["EZ_SUBSCRIBE_ALLOW_CIDRS"], "10.0.0.0/8,2001:db8::/32");
+        assert_eq!(env["EZ_SUBSCRIBE_TRUSTED_PROXIES"], "127.0.0.1/32,::1/128");
+        assert_eq!(env["EZ_SUBSCRIBE_PATH_PREFIX"], "/ezsub");
+        assert_eq!(env["EZ_SUBSCRIBE_RATE_LIMIT"], "20");
+        assert!(!env.contains_key("EZ_SUBSCRIBE_RATE_BURST"));
+        assert_eq!(env["EZ_SUBSCRIBE_SIGNING_KEY"], "sign-secret");
+        assert_eq!(env["EZ_AUDIT_LOG"], "/var/log/ezsingbox/audit.log");
+        assert_eq!(env["EZ_LIMIT_PROTOCOLS"], "anytls,tuic");
+        assert_eq!(env["EZ_GEOIP_BLOCK"], "ir,kp");
+        assert_eq!(env["EZ_BLOCK_CN_INBOUND"], "true");
+        assert_eq!(env["EZ_LIMIT_BLOCKED_SOURCES"], "198.51.100.0/24");
+        assert_eq!(env["EZ_LIMIT_BLOCK_BITTORRENT"], "true");
+        assert!(!env.contains_key("EZ_LIMIT_BLOCK_SMTP"));
+        assert!(!env.contains_key("EZ_TUIC_PORT"));
+        assert!(!env.contains_key("EZ_ANYTLS_PORT"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&env["EZ_RELAY_UPSTREAM"]).unwrap()["server_port"],
+            1080
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let yaml = r#"
+tls:
+  domain: example.com
+protocols:
+  vless_reality:
+    port: 2096
+    handshake_server: www.apple.com
+    keygen: sing-box
+    short_ids: ["0123abcd", "ab"]
+  vless_ws:
+    path: /ray
+    host: ws.example.com
+  cdn_host: cdn.example.net
+  tcp_brutal:
+    up_mbps: 50
+    down_mbps: 200
+  multiplex:
+    protocol: smux
+    max_streams: 8
+users:
+  - name: alice
+    expires_at: "2025-12-31"
+dns:
+  upstream: tls://1.1.1.1
+  block_domains: [telemetry.example.com, ads.example.com]
+"#;
+        let cfg = FileConfig::parse(yaml, FileFormat::Yaml).unwrap();
+        let env = cfg.to_env_values().unwrap();
+        assert_eq!(env["EZ_ENABLE_VLESS_REALITY"], "true");
+        assert_eq!(env["EZ_ENABLE_HYSTERIA2"], "false");
+        assert_eq!(env["EZ_VLESS_HANDSHAKE_SERVER"], "www.apple.com");
+        assert_eq!(env["EZ_REALITY_KEYGEN"], "sing-box");
+        assert_eq!(env["EZ_REALITY_SHORT_IDS"], "0123abcd,ab");
+        assert_eq!(env["EZ_ENABLE_VLESS_WS"], "true");
+        assert_eq!(env["EZ_VLESS_WS_PATH"], "/ray");
+        assert_eq!(env["EZ_VLESS_WS_HOST"], "ws.example.com");
+        assert_eq!(env["EZ_CDN_HOST"], "cdn.example.net");
+        assert_eq!(env["EZ_BRUTAL_UP_MBPS"], "50");
+        assert_eq!(env["EZ_BRUTAL_DOWN_MBPS"], "200");
+        assert_eq!(env["EZ_MUX_PROTOCOL"], "smux");
+        assert_eq!(env["EZ_MUX_MAX_STREAMS"], "8");
+        assert!(!env.contains_key("EZ_MUX_PADDING"));
+        assert_eq!(env["EZ_ENABLE_VLESS_GRPC"], "false");
+        assert!(!env.contains_key("EZ_USERS"));
+        assert_eq!(
+            env["EZ_USERS_JSON"],
+            r#"[{"expires_at":"2025-12-31","name":"alice"}]"#
+        );
+        assert_eq!(env["EZ_DNS_UPSTREAM"], "tls://1.1.1.1");
+        assert_eq!(
+            env["EZ_DNS_BLOCK_DOMAINS"],
+            "telemetry.example.com,ads.example.com"
+        );
+    }
+
+    #[test]
+    fn test_empty_and_invalid() {
+        let cfg = FileConfig::parse("", FileFormat::Toml).unwrap();
+        assert_eq!(cfg, FileConfig::default());
+        assert!(cfg.to_env_values().unwrap().is_empty());
+
+        assert!(FileConfig::parse("unknown_key = 1", FileFormat::Toml).is_err());
+        assert!(FileConfig::parse("[client]\nprotocol = \"trojan\"", FileFormat::Toml).is_err());
+
+        let cfg = FileConfig {
+            users: vec![UserEntry {
+                name: "a:b".to_string(),
+                password: None,
+                expires_at: None,
+            }],
+            ..Default::default()
+        };
+        assert!(cfg.to_env_values().is_err());
+        let cfg = FileConfig {
+            users: vec![UserEntry {
+                name: "a".to_string(),
+                password: None,
+                expires_at: Some("soon".to_string()),
+            }],
+            ..Default::default()
+        };
+        assert!(cfg.to_env_values().is_err());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            FileFormat::from_path(Path::new("ezsingbox.yml")),
+            FileFormat::Yaml
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("ezsingbox.YAML")),
+            FileFormat::Yaml
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("ezsingbox.toml")),
+            FileFormat::Toml
+        );
+    }
+}
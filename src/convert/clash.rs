@@ -0,0 +1,816 @@
+//! Clash.Meta（mihomo）配置导入
+//!
+//! 把 Clash.Meta YAML 中 `proxies` 列表的节点转换为 sing-box 出站，标签为节点名：
+//! - ss / vmess / vless / trojan / hysteria2 / tuic / socks5 / http / anytls 转换为对应的出站
+//! - `network` 为 ws / grpc / h2 时转换为对应的 V2Ray 传输（`ws-opts.v2ray-http-upgrade` → httpupgrade）
+//! - `tls` / `servername` / `sni` / `skip-cert-verify` / `alpn` / `client-fingerprint` / `reality-opts` → 出站 TLS
+//! - `udp` 未开启的节点只启用 TCP，`smux` → 多路复用，`tfo` / `mptcp` / `interface-name` → 拨号字段
+//!
+//! 代理组、分流规则与 proxy-providers 不做转换；生成客户端配置时由 selector 出站选择全部节点
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use super::{Change, OutboundConversion};
+use crate::singboxconfig::inbound::{CongestionControl, UdpRelayMode};
+use crate::singboxconfig::outbound::{
+    AnyTlsOutbound, HttpOutbound, Hysteria2Outbound, ShadowsocksOutbound, SocksOutbound,
+    TrojanOutbound, TuicOutbound, VlessOutbound, VmessOutbound,
+};
+use crate::singboxconfig::shared::{
+    DialFields, GrpcTransport, HttpTransport, HttpUpgradeTransport, MultiplexOutbound,
+    MultiplexProtocol, OutboundTlsConfig, RealityOutboundConfig, UtlsConfig, UtlsFingerprint,
+    V2RayTransport, WebSocketTransport,
+};
+use crate::singboxconfig::strict::pointer_push;
+use crate::singboxconfig::types::{Duration, PortRange, PortRangeList};
+
+/// 节点共用的部分（地址、网络、拨号字段）
+struct Common {
+    tag: String,
+    server: String,
+    port: Option<u16>,
+    /// 未开启 udp 时为 Some("tcp")
+    network: Option<String>,
+    dial: DialFields,
+}
+
+/// 转换 Clash.Meta 配置中的 proxies
+///
+/// `reserved` 为生成配置中已占用的出站标签，与之重名的节点名追加序号
+/// 没有可转换的节点时返回错误
+pub fn convert_clash(config: &Value, reserved: &[&str]) -> Result<OutboundConversion, String> {
+    let proxies = config
+        .get("proxies")
+        .and_then(Value::as_array)
+        .ok_or("Clash 配置中没有 proxies 列表")?;
+    let mut skipped = Vec::new();
+    let mut tags: Vec<String> = reserved.iter().map(|t| t.to_string()).collect();
+    let mut outbounds = Vec::new();
+    for (i, proxy) in proxies.iter().enumerate() {
+        let pointer = format!("/proxies/{}", i);
+        match convert_proxy(proxy, &pointer, i, &tags, &mut skipped) {
+            Ok((tag, value)) => {
+                tags.push(tag);
+                outbounds.push(value);
+            }
+            Err(reason) => skipped.push(Change::new(pointer, reason)),
+        }
+    }
+    if outbounds.is_empty() {
+        let reasons: Vec<String> = skipped.iter().map(Change::to_string).collect();
+        return Err(format!("没有可转换的节点: {}", reasons.join("; ")));
+    }
+    collect_ignored(config, &mut skipped);
+    Ok(OutboundConversion { outbounds, skipped })
+}
+
+/// 转换单个节点，返回标签与 sing-box 出站；返回错误时整个节点被跳过
+fn convert_proxy(
+    proxy: &Value,
+    pointer: &str,
+    index: usize,
+    tags: &[String],
+    skipped: &mut Vec<Change>,
+) -> Result<(String, Value), String> {
+    let proxy_type = proxy["type"].as_str().unwrap_or_default();
+    if !matches!(
+        proxy_type,
+        "ss" | "vmess" | "vless" | "trojan" | "hysteria2" | "tuic" | "socks5" | "http" | "anytls"
+    ) {
+        return Err(format!("不支持的代理类型 \"{}\"", proxy_type));
+    }
+    let common = common_fields(proxy, pointer, index, tags, skipped)?;
+    let tag = common.tag.clone();
+    let value = match proxy_type {
+        "ss" => serde_json::to_value(shadowsocks(proxy, common)?),
+        "vmess" => serde_json::to_value(vmess(proxy, pointer, common, skipped)?),
+        "vless" => serde_json::to_value(vless(proxy, pointer, common, skipped)?),
+        "trojan" => serde_json::to_value(trojan(proxy, pointer, common, skipped)?),
+        "hysteria2" => serde_json::to_value(hysteria2(proxy, pointer, common, skipped)?),
+        "tuic" => serde_json::to_value(tuic(proxy, pointer, common, skipped)?),
+        "socks5" => serde_json::to_value(socks(proxy, common)?),
+        "http" => serde_json::to_value(http(proxy, pointer, common, skipped)?),
+        _ => serde_json::to_value(anytls(proxy, pointer, common, skipped)?),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok((tag, value))
+}
+
+/// 读取必填的字符串字段
+fn required<'a>(proxy: &'a Value, key: &str) -> Result<&'a str, String> {
+    proxy[key]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| format!("缺少 {}", key))
+}
+
+/// 读取数字字段（也接受数字字符串）
+fn number(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn common_fields(
+    proxy: &Value,
+    pointer: &str,
+    index: usize,
+    tags: &[String],
+    skipped: &mut Vec<Change>,
+) -> Result<Common, String> {
+    let server = required(proxy, "server")?.to_string();
+    let port = match proxy.get("port") {
+        None => None,
+        Some(port) => Some(
+            number(port)
+                .and_then(|p| u16::try_from(p).ok())
+                .filter(|p| *p != 0)
+                .ok_or_else(|| format!("端口无效: {}", port))?,
+        ),
+    };
+    let base = proxy["name"]
+        .as_str()
+        .filter(|n| !n.is_empty())
+        .map_or_else(|| format!("proxy-{}", index + 1), str::to_string);
+    let mut tag = base.clone();
+    let mut n = 2;
+    while tags.contains(&tag) {
+        tag = format!("{}-{}", base, n);
+        n += 1;
+    }
+    let network = (proxy["udp"] != true).then(|| "tcp".to_string());
+
+    let mut dial = DialFields::default();
+    if proxy["tfo"] == true {
+        dial.tcp_fast_open = Some(true);
+    }
+    if proxy["mptcp"] == true {
+        dial.tcp_multi_path = Some(true);
+    }
+    if let Some(interface) = proxy["interface-name"].as_str() {
+        dial.bind_interface = Some(interface.to_string());
+    }
+    if proxy.get("dialer-proxy").is_some() {
+        skipped.push(Change::new(
+            pointer_push(pointer, "dialer-proxy"),
+            "dialer-proxy 未转换，链式代理可通过 EZ_CLIENT_CHAIN_FRONT 设置",
+        ));
+    }
+    Ok(Common {
+        tag,
+        server,
+        port,
+        network,
+        dial,
+    })
+}
+
+/// 除 hysteria2 外的节点都需要 port
+fn required_port(common: &Common) -> Result<u16, String> {
+    common.port.ok_or_else(|| "缺少 port".to_string())
+}
+
+fn shadowsocks(proxy: &Value, common: Common) -> Result<ShadowsocksOutbound, String> {
+    let port = required_port(&common)?;
+    let mut outbound = ShadowsocksOutbound::new(
+        common.tag,
+        common.server,
+        port,
+        required(proxy, "cipher")?,
+        proxy["password"].as_str().unwrap_or_default(),
+    )
+    .with_dial(common.dial);
+    if let Some(network) = common.network {
+        outbound = outbound.with_network(network);
+    }
+    if proxy["udp-over-tcp"] == true {
+        outbound = outbound.with_udp_over_tcp(true);
+    }
+    if let Some(multiplex) = multiplex(proxy)? {
+        outbound = outbound.with_multiplex(multiplex);
+    }
+    if let Some(plugin) = proxy["plugin"].as_str() {
+        let opts = &proxy["plugin-opts"];
+        let mut args = Vec::new();
+        let plugin = match plugin {
+            "obfs" => {
+                args.push(format!("obfs={}", opts["mode"].as_str().unwrap_or("http")));
+                if let Some(host) = opts["host"].as_str() {
+                    args.push(format!("obfs-host={}", host));
+                }
+                "obfs-local"
+            }
+            "v2ray-plugin" => {
+                let mode = opts["mode"].as_str().unwrap_or("websocket");
+                if mode != "websocket" {
+                    return Err(format!("sing-box 的 v2ray-plugin 不支持 mode \"{}\"", mode));
+                }
+                if opts["tls"] == true {
+                    args.push("tls".to_string());
+                }
+                if let Some(host) = opts["host"].as_str() {
+                    args.push(format!("host={}", host));
+                }
+                if let Some(path) = opts["path"].as_str() {
+                    args.push(format!("path={}", path));
+                }
+                "v2ray-plugin"
+            }
+            plugin => return Err(format!("sing-box 不支持 Shadowsocks 插件 \"{}\"", plugin)),
+        };
+        outbound = outbound.with_plugin(plugin, args.join(";"));
+    }
+    Ok(outbound)
+}
+
+fn vmess(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<VmessOutbound, String> {
+    let port = required_port(&common)?;
+    let mut outbound =
+        VmessOutbound::new(common.tag, common.server, port, required(proxy, "uuid")?)
+            .with_security(proxy["cipher"].as_str().unwrap_or("auto"))
+            .with_dial(common.dial);
+    outbound.network = common.network;
+    if let Some(alter_id) = number(&proxy["alterId"]).filter(|id| *id > 0) {
+        let alter_id = u32::try_from(alter_id).map_err(|_| "alterId 无效".to_string())?;
+        outbound = outbound.with_alter_id(alter_id);
+    }
+    if proxy["global-padding"] == true {
+        outbound.global_padding = Some(true);
+    }
+    if proxy["authenticated-length"] == true {
+        outbound.authenticated_length = Some(true);
+    }
+    if let Some(encoding) = proxy["packet-encoding"].as_str() {
+        outbound = outbound.with_packet_encoding(encoding);
+    }
+    if proxy["tls"] == true {
+        outbound = outbound.with_tls(tls_config(proxy, pointer, "servername", skipped));
+    }
+    if let Some(transport) = transport(proxy)? {
+        outbound = outbound.with_transport(transport);
+    }
+    if let Some(multiplex) = multiplex(proxy)? {
+        outbound = outbound.with_multiplex(multiplex);
+    }
+    Ok(outbound)
+}
+
+fn vless(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<VlessOutbound, String> {
+    if let Some(encryption) = proxy["encryption"]
+        .as_str()
+        .filter(|e| !e.is_empty() && *e != "none")
+    {
+        return Err(format!(
+            "sing-box 不支持 VLESS encryption \"{}\"",
+            encryption
+        ));
+    }
+    let port = required_port(&common)?;
+    let mut outbound =
+        VlessOutbound::new(common.tag, common.server, port, required(proxy, "uuid")?)
+            .with_dial(common.dial);
+    outbound.network = common.network;
+    match proxy["flow"].as_str().unwrap_or_default() {
+        "" => {}
+        "xtls-rprx-vision" => outbound = outbound.with_xtls_vision(),
+        flow => return Err(format!("sing-box 不支持 flow \"{}\"", flow)),
+    }
+    if let Some(encoding) = proxy["packet-encoding"].as_str() {
+        outbound = outbound.with_packet_encoding(encoding);
+    }
+    if proxy["tls"] == true || proxy.get("reality-opts").is_some() {
+        outbound = outbound.with_tls(tls_config(proxy, pointer, "servername", skipped));
+    }
+    if let Some(transport) = transport(proxy)? {
+        outbound = outbound.with_transport(transport);
+    }
+    if let Some(multiplex) = multiplex(proxy)? {
+        outbound = outbound.with_multiplex(multiplex);
+    }
+    Ok(outbound)
+}
+
+fn trojan(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<TrojanOutbound, String> {
+    if proxy["ss-opts"]["enabled"] == true {
+        return Err("sing-box 不支持 Trojan-Go 的 Shadowsocks 加密（ss-opts）".to_string());
+    }
+    let port = required_port(&common)?;
+    let mut outbound = TrojanOutbound::new(
+        common.tag,
+        common.server,
+        port,
+        required(proxy, "password")?,
+    )
+    .with_tls(tls_config(proxy, pointer, "sni", skipped))
+    .with_dial(common.dial);
+    outbound.network = common.network;
+    if let Some(transport) = transport(proxy)? {
+        outbound = outbound.with_transport(transport);
+    }
+    if let Some(multiplex) = multiplex(proxy)? {
+        outbound = outbound.with_multiplex(multiplex);
+    }
+    Ok(outbound)
+}
+
+fn hysteria2(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<Hysteria2Outbound, String> {
+    let ports = match &proxy["ports"] {
+        Value::Null => None,
+        Value::Number(_) => match number(&proxy["ports"]).and_then(|p| u16::try_from(p).ok()) {
+            Some(port) => Some(PortRangeList::from(PortRange::single(port))),
+            None => return Err(format!("ports 无效: {}", proxy["ports"])),
+        },
+        Value::String(ports) => Some(port_ranges(ports)?),
+        ports => return Err(format!("ports 无效: {}", ports)),
+    };
+    if common.port.is_none() && ports.is_none() {
+        return Err("缺少 port 或 ports".to_string());
+    }
+    let password = proxy["password"]
+        .as_str()
+        .or_else(|| proxy["auth"].as_str())
+        .unwrap_or_default();
+    let mut outbound = Hysteria2Outbound::new(common.tag, common.server, 0, password)
+        .with_tls(tls_config(proxy, pointer, "sni", skipped))
+        .with_dial(common.dial);
+    outbound.server_port = common.port;
+    if let Some(ports) = ports {
+        outbound = outbound.with_server_ports(ports);
+    }
+    if let Some(secs) = number(&proxy["hop-interval"]) {
+        outbound.hop_interval = Some(Duration::from_secs(secs));
+    }
+    outbound.up_mbps = bandwidth(proxy, pointer, "up", skipped);
+    outbound.down_mbps = bandwidth(proxy, pointer, "down", skipped);
+    match proxy["obfs"].as_str().unwrap_or_default() {
+        "" => {}
+        "salamander" => {
+            outbound = outbound.with_obfs(proxy["obfs-password"].as_str().unwrap_or_default())
+        }
+        obfs => return Err(format!("sing-box 不支持混淆类型 \"{}\"", obfs)),
+    }
+    Ok(outbound)
+}
+
+/// Clash 的端口跳跃范围（`20000-30000,443`）→ sing-box 的 server_ports
+fn port_ranges(ports: &str) -> Result<PortRangeList, String> {
+    let ranges: PortRangeList = ports.parse().map_err(|e| format!("ports 无效: {}", e))?;
+    if ranges.as_slice().iter().any(|range| range.start == 0) {
+        return Err(format!("ports 无效: {}", ports));
+    }
+    Ok(ranges)
+}
+
+/// 带宽（`100`、`100 Mbps`、`1 Gbps`）→ Mbps；无法识别或超出范围的值记入跳过清单
+fn bandwidth(proxy: &Value, pointer: &str, key: &str, skipped: &mut Vec<Change>) -> Option<u32> {
+    let value = proxy.get(key)?;
+    let mbps = match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => {
+            let s = s.trim().to_ascii_lowercase();
+            let (digits, scale) = if let Some(n) = s.strip_suffix("gbps") {
+                (n, 1000)
+            } else {
+                (s.strip_suffix("mbps").unwrap_or(&s), 1)
+            };
+            digits
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .and_then(|n| n.checked_mul(scale))
+        }
+        _ => None,
+    };
+    let mbps = mbps.and_then(|n| u32::try_from(n).ok());
+    if mbps.is_none() {
+        skipped.push(Change::new(
+            pointer_push(pointer, key),
+            format!("无法识别的带宽 {}，已忽略", value),
+        ));
+    }
+    mbps
+}
+
+fn tuic(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<TuicOutbound, String> {
+    if proxy.get("token").is_some() {
+        return Err("sing-box 不支持 TUIC v4（token）".to_string());
+    }
+    let port = required_port(&common)?;
+    let mut outbound = TuicOutbound::new(
+        common.tag,
+        common.server,
+        port,
+        required(proxy, "uuid")?,
+        proxy["password"].as_str().unwrap_or_default(),
+    )
+    .with_tls(tls_config(proxy, pointer, "sni", skipped))
+    .with_dial(common.dial);
+    if let Some(cc) = proxy["congestion-controller"].as_str() {
+        let cc: CongestionControl = serde_json::from_value(json!(cc))
+            .map_err(|_| format!("sing-box 不支持拥塞控制算法 \"{}\"", cc))?;
+        outbound = outbound.with_congestion_control(cc);
+    }
+    if let Some(mode) = proxy["udp-relay-mode"].as_str() {
+        outbound = outbound.with_udp_relay_mode(mode.parse::<UdpRelayMode>()?);
+    }
+    if proxy["udp-over-stream"] == true {
+        outbound.udp_over_stream = Some(true);
+    }
+    if proxy["reduce-rtt"] == true {
+        outbound.zero_rtt_handshake = Some(true);
+    }
+    if let Some(millis) = number(&proxy["heartbeat-interval"]) {
+        outbound.heartbeat = Some(Duration::from_millis(millis));
+    }
+    Ok(outbound)
+}
+
+fn socks(proxy: &Value, common: Common) -> Result<SocksOutbound, String> {
+    if proxy["tls"] == true {
+        return Err("sing-box 的 SOCKS 出站不支持 TLS".to_string());
+    }
+    let port = required_port(&common)?;
+    let mut outbound = SocksOutbound::new(common.tag, common.server, port).with_dial(common.dial);
+    if let Some(network) = common.network {
+        outbound = outbound.with_network(network);
+    }
+    if let Some(username) = proxy["username"].as_str() {
+        outbound = outbound.with_auth(username, proxy["password"].as_str().unwrap_or_default());
+    }
+    Ok(outbound)
+}
+
+fn http(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<HttpOutbound, String> {
+    let port = required_port(&common)?;
+    let mut outbound = HttpOutbound::new(common.tag, common.server, port).with_dial(common.dial);
+    if let Some(username) = proxy["username"].as_str() {
+        outbound = outbound.with_auth(username, proxy["password"].as_str().unwrap_or_default());
+    }
+    if let Some(headers) = proxy["headers"].as_object() {
+        let headers: BTreeMap<String, String> = headers
+            .iter()
+            .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+            .collect();
+        outbound = outbound.with_headers(headers);
+    }
+    if proxy["tls"] == true {
+        outbound = outbound.with_tls(tls_config(proxy, pointer, "sni", skipped));
+    }
+    Ok(outbound)
+}
+
+fn anytls(
+    proxy: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<AnyTlsOutbound, String> {
+    let port = required_port(&common)?;
+    let mut outbound = AnyTlsOutbound::new(
+        common.tag,
+        common.server,
+        port,
+        required(proxy, "password")?,
+    )
+    .with_tls(tls_config(proxy, pointer, "sni", skipped))
+    .with_dial(common.dial);
+    if let Some(secs) = number(&proxy["idle-session-check-interval"]) {
+        outbound = outbound.with_idle_session_check_interval(Duration::from_secs(secs));
+    }
+    if let Some(secs) = number(&proxy["idle-session-timeout"]) {
+        outbound = outbound.with_idle_session_timeout(Duration::from_secs(secs));
+    }
+    if let Some(min) = number(&proxy["min-idle-session"]).and_then(|n| u32::try_from(n).ok()) {
+        outbound = outbound.with_min_idle_session(min);
+    }
+    Ok(outbound)
+}
+
+/// 节点的 TLS 字段 → 出站 TLS；`sni_key` 为服务器名称字段（VMess / VLESS 为 servername，其他为 sni）
+fn tls_config(
+    proxy: &Value,
+    pointer: &str,
+    sni_key: &str,
+    skipped: &mut Vec<Change>,
+) -> OutboundTlsConfig {
+    let mut tls = OutboundTlsConfig {
+        enabled: Some(true),
+        server_name: proxy[sni_key].as_str().map(str::to_string),
+        ..Default::default()
+    };
+    if proxy["skip-cert-verify"] == true {
+        tls.insecure = Some(true);
+    }
+    if proxy["disable-sni"] == true {
+        tls.disable_sni = Some(true);
+    }
+    if let Some(alpn) = proxy["alpn"].as_array() {
+        tls.alpn = Some(
+            alpn.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+        );
+    }
+    if let Some(fingerprint) = proxy["client-fingerprint"].as_str() {
+        match fingerprint.parse::<UtlsFingerprint>() {
+            Ok(fingerprint) => tls.utls = Some(UtlsConfig::new(fingerprint)),
+            Err(_) => skipped.push(Change::new(
+                pointer_push(pointer, "client-fingerprint"),
+                format!("sing-box 不支持 uTLS 指纹 \"{}\"，已忽略", fingerprint),
+            )),
+        }
+    }
+    if proxy.get("fingerprint").is_some() {
+        skipped.push(Change::new(
+            pointer_push(pointer, "fingerprint"),
+            "证书指纹固定未转换",
+        ));
+    }
+    if let Some(reality) = proxy["reality-opts"].as_object() {
+        tls.reality = Some(RealityOutboundConfig {
+            enabled: Some(true),
+            public_key: reality
+                .get("public-key")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            short_id: reality
+                .get("short-id")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        });
+        // REALITY 需要 uTLS，Clash.Meta 未设置指纹时同样使用 chrome
+        tls.utls
+            .get_or_insert_with(|| UtlsConfig::new(UtlsFingerprint::Chrome));
+    }
+    tls
+}
+
+/// network 与对应的 *-opts → V2Ray 传输
+fn transport(proxy: &Value) -> Result<Option<V2RayTransport>, String> {
+    let transport = match proxy["network"].as_str().unwrap_or("tcp") {
+        "tcp" => return Ok(None),
+        "ws" => {
+            let opts = &proxy["ws-opts"];
+            let path = opts["path"].as_str().unwrap_or("/");
+            let headers: BTreeMap<String, String> = opts["headers"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect();
+            if opts["v2ray-http-upgrade"] == true {
+                let mut transport = HttpUpgradeTransport::new().with_path(path);
+                if let Some(host) = headers.get("Host") {
+                    transport = transport.with_host(host.as_str());
+                }
+                V2RayTransport::HttpUpgrade(transport)
+            } else {
+                let mut transport = WebSocketTransport::new().with_path(path);
+                if !headers.is_empty() {
+                    transport = transport.with_headers(headers);
+                }
+                if let Some(size) = number(&opts["max-early-data"]).filter(|n| *n > 0) {
+                    let size = u32::try_from(size).map_err(|_| "max-early-data 无效")?;
+                    transport = transport.with_max_early_data(size);
+                }
+                if let Some(name) = opts["early-data-header-name"].as_str() {
+                    transport = transport.with_early_data_header_name(name);
+                }
+                V2RayTransport::Ws(transport)
+            }
+        }
+        "grpc" => V2RayTransport::Grpc(
+            GrpcTransport::new().with_service_name(
+                proxy["grpc-opts"]["grpc-service-name"]
+                    .as_str()
+                    .unwrap_or_default(),
+            ),
+        ),
+        "h2" => {
+            let opts = &proxy["h2-opts"];
+            let mut transport = HttpTransport::new();
+            if let Some(path) = opts["path"].as_str() {
+                transport = transport.with_path(path);
+            }
+            if let Some(hosts) = opts["host"].as_array() {
+                let hosts = hosts.iter().filter_map(Value::as_str).map(str::to_string);
+                transport = transport.with_host(hosts.collect());
+            }
+            V2RayTransport::Http(transport)
+        }
+        "http" => return Err("sing-box 不支持 TCP HTTP 伪装（network: http）".to_string()),
+        network => return Err(format!("sing-box 不支持传输层 \"{}\"", network)),
+    };
+    Ok(Some(transport))
+}
+
+/// smux → 多路复用
+fn multiplex(proxy: &Value) -> Result<Option<MultiplexOutbound>, String> {
+    let smux = &proxy["smux"];
+    if smux["enabled"] != true {
+        return Ok(None);
+    }
+    let mut multiplex = MultiplexOutbound::new().enabled();
+    if let Some(protocol) = smux["protocol"].as_str() {
+        multiplex = multiplex.with_protocol(protocol.parse::<MultiplexProtocol>()?);
+    }
+    let count = |key: &str| number(&smux[key]).and_then(|n| u32::try_from(n).ok());
+    if let Some(max) = count("max-connections") {
+        multiplex = multiplex.with_max_connections(max);
+    }
+    if let Some(min) = count("min-streams") {
+        multiplex = multiplex.with_min_streams(min);
+    }
+    if let Some(max) = count("max-streams") {
+        multiplex = multiplex.with_max_streams(max);
+    }
+    if smux["padding"] == true {
+        multiplex = multiplex.with_padding(true);
+    }
+    Ok(Some(multiplex))
+}
+
+/// 不做转换的代理组、分流规则与 proxy-providers
+fn collect_ignored(config: &Value, skipped: &mut Vec<Change>) {
+    let non_empty = |key: &str| match &config[key] {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(items) => !items.is_empty(),
+        _ => false,
+    };
+    if non_empty("proxy-providers") {
+        skipped.push(Change::new(
+            "/proxy-providers",
+            "proxy-providers 未转换，订阅中的节点需先展开到 proxies",
+        ));
+    }
+    if non_empty("proxy-groups") {
+        skipped.push(Change::new(
+            "/proxy-groups",
+            "代理组未转换，生成的配置由 selector 出站 proxy 选择全部节点",
+        ));
+    }
+    if non_empty("rules") {
+        skipped.push(Change::new(
+            "/rules",
+            "分流规则未转换，需要时在 route.rules 中手动改写",
+        ));
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singboxconfig::strict::strict_diagnostics;
+
+    const SAMPLE: &str = r#"
+proxies:
+  - { name: "ss", type: ss, server: 1.2.3.4, port: 8388, cipher: aes-256-gcm, password: pass, udp: true,
+      plugin: obfs, plugin-opts: { mode: tls, host: bing.com } }
+  - name: "vless-reality"
+    type: vless
+    server: example.com
+    port: 443
+    uuid: bf000d23-0752-40b4-affe-68f7707a9661
+    flow: xtls-rprx-vision
+    servername: www.microsoft.com
+    reality-opts: { public-key: pubkey, short-id: 6ba85179e30d4fc2 }
+    client-fingerprint: chrome
+  - name: "vmess-ws"
+    type: vmess
+    server: example.com
+    port: "8443"
+    uuid: bf000d23-0752-40b4-affe-68f7707a9662
+    alterId: 0
+    cipher: auto
+    tls: true
+    network: ws
+    ws-opts: { path: /ray, headers: { Host: example.com } }
+  - { name: "hy2", type: hysteria2, server: example.com, ports: "20000-30000,443", password: p,
+      up: "30 Mbps", down: 200, obfs: salamander, obfs-password: o, sni: example.com }
+  - { name: "proxy", type: tuic, server: example.com, port: 443, uuid: bf000d23-0752-40b4-affe-68f7707a9663,
+      password: p, congestion-controller: bbr, udp-relay-mode: quic, heartbeat-interval: 10000, alpn: [h3] }
+  - { name: "trojan", type: trojan, server: example.com, port: 443, password: p, sni: example.com,
+      network: grpc, grpc-opts: { grpc-service-name: grpc } }
+  - { name: "ssr", type: ssr, server: example.com, port: 443 }
+  - { name: "vmess-http", type: vmess, server: example.com, port: 80, uuid: x, network: http }
+proxy-groups:
+  - { name: auto, type: url-test, proxies: [ss, hy2] }
+rules:
+  - MATCH,auto
+"#;
+
+    #[test]
+    fn test_convert_clash() {
+        let config: Value = serde_yaml::from_str(SAMPLE).unwrap();
+        let conversion = convert_clash(&config, &["proxy", "direct"]).unwrap();
+        let outbounds = &conversion.outbounds;
+        assert_eq!(outbounds.len(), 6);
+
+        assert_eq!(outbounds[0]["type"], "shadowsocks");
+        assert_eq!(outbounds[0]["plugin"], "obfs-local");
+        assert_eq!(outbounds[0]["plugin_opts"], "obfs=tls;obfs-host=bing.com");
+        assert!(outbounds[0].get("network").is_none());
+
+        assert_eq!(outbounds[1]["flow"], "xtls-rprx-vision");
+        assert_eq!(outbounds[1]["network"], "tcp");
+        assert_eq!(outbounds[1]["tls"]["server_name"], "www.microsoft.com");
+        assert_eq!(outbounds[1]["tls"]["reality"]["public_key"], "pubkey");
+        assert_eq!(outbounds[1]["tls"]["utls"]["fingerprint"], "chrome");
+
+        assert_eq!(outbounds[2]["server_port"], 8443);
+        assert_eq!(outbounds[2]["transport"]["type"], "ws");
+        assert_eq!(outbounds[2]["transport"]["headers"]["Host"], "example.com");
+        assert!(outbounds[2].get("alter_id").is_none());
+
+        assert!(outbounds[3].get("server_port").is_none());
+        assert_eq!(
+            outbounds[3]["server_ports"],
+            json!(["20000:30000", "443:443"])
+        );
+        assert_eq!(outbounds[3]["up_mbps"], 30);
+        assert_eq!(outbounds[3]["down_mbps"], 200);
+        assert_eq!(outbounds[3]["obfs"]["type"], "salamander");
+
+        // 与保留标签重名时追加序号
+        assert_eq!(outbounds[4]["tag"], "proxy-2");
+        assert_eq!(outbounds[4]["congestion_control"], "bbr");
+        assert_eq!(outbounds[4]["heartbeat"], "10s");
+
+        assert_eq!(outbounds[5]["tls"]["enabled"], true);
+        assert_eq!(outbounds[5]["transport"]["service_name"], "grpc");
+
+        for outbound in outbounds {
+            let config = json!({ "outbounds": [outbound] });
+            assert!(strict_diagnostics(&config).is_empty(), "{}", outbound);
+        }
+
+        let skipped: Vec<&str> = conversion.skipped.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            skipped,
+            ["/proxies/6", "/proxies/7", "/proxy-groups", "/rules"]
+        );
+    }
+
+    #[test]
+    fn test_convert_clash_errors() {
+        assert!(convert_clash(&json!({}), &[]).is_err());
+        let config =
+            json!({ "proxies": [{ "name": "a", "type": "snell", "server": "x", "port": 1 }] });
+        let err = convert_clash(&config, &[]).unwrap_err();
+        assert!(err.contains("snell"), "{}", err);
+        assert_eq!(
+            port_ranges("1000-2000, 3000").unwrap().as_slice(),
+            [PortRange::new(1000, 2000), PortRange::single(3000)]
+        );
+        assert!(port_ranges("2000-1000").is_err());
+        assert!(port_ranges("0-1000").is_err());
+        assert!(port_ranges("").is_err());
+
+        let proxy = json!({ "up": "18446744073709552 Gbps", "down": "1 Gbps" });
+        let mut skipped = Vec::new();
+        assert_eq!(bandwidth(&proxy, "/proxies/0", "up", &mut skipped), None);
+        assert_eq!(
+            bandwidth(&proxy, "/proxies/0", "down", &mut skipped),
+            Some(1000)
+        );
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, "/proxies/0/up");
+    }
+}
@@ -0,0 +1,66 @@
+//! 外部配置导入
+//!
+//! 把其他代理工具的配置转换为 sing-box 配置，入站 / 出站均经强类型结构生成：
+//! - [`xray`] - Xray 服务端配置中的 VLESS / VMess / Trojan 入站
+//! - [`clash`] - Clash.Meta 配置 proxies 中的节点（生成客户端配置）
+//!
+//! 无法转换的入站、节点或字段不中断转换，而是列入结果的跳过清单
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::singboxconfig::full::SingBoxConfig;
+pub use crate::singboxconfig::migrate::Change;
+
+pub mod clash;
+pub mod xray;
+
+/// 导入来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertSource {
+    /// Xray 服务端 JSON 配置
+    Xray,
+    /// Clash.Meta（mihomo）YAML 配置
+    Clash,
+}
+
+impl fmt::Display for ConvertSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertSource::Xray => write!(f, "xray"),
+            ConvertSource::Clash => write!(f, "clash"),
+        }
+    }
+}
+
+impl FromStr for ConvertSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "xray" => Ok(ConvertSource::Xray),
+            "clash" | "mihomo" => Ok(ConvertSource::Clash),
+            _ => Err(format!("无效的导入来源: {}（可选 xray / clash）", s)),
+        }
+    }
+}
+
+/// 转换结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    /// 转换得到的 sing-box 配置
+    pub config: SingBoxConfig,
+    /// 未转换的项目（位置为原配置中的 JSON Pointer）
+    pub skipped: Vec<Change>,
+}
+
+/// 出站转换结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboundConversion {
+    /// 转换得到的 sing-box 出站，标签互不重复
+    pub outbounds: Vec<Value>,
+    /// 未转换的项目（位置为原配置中的 JSON Pointer）
+    pub skipped: Vec<Change>,
+}
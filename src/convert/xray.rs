@@ -0,0 +1,738 @@
+//! Xray 服务端配置导入
+//!
+//! 把 Xray 服务端 JSON 中的 VLESS / VMess / Trojan 入站转换为 sing-box 入站：
+//! - `settings.clients` → `users`（`email` 作为用户名），Trojan `fallbacks` → `fallback` / `fallback_for_alpn`
+//! - `streamSettings.network` 为 tcp / raw / ws / grpc / httpupgrade / h2 时转换为对应的 V2Ray 传输
+//! - `security` 为 reality / tls 时转换为入站 TLS（REALITY 的 `target` / `dest` → `handshake`）
+//! - `sniffing.enabled` → 路由规则开头的 sniff 动作
+//!
+//! 生成的配置只有一个直连出站，Xray 的出站与路由规则不做转换
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use super::{Change, Conversion};
+use crate::singboxconfig::full::SingBoxConfig;
+use crate::singboxconfig::inbound::{
+    TrojanFallback, TrojanInbound, VlessInbound, VlessUser, VmessInbound,
+};
+use crate::singboxconfig::outbound::DirectOutbound;
+use crate::singboxconfig::route::{RouteRule, RuleAction};
+use crate::singboxconfig::shared::{
+    GrpcTransport, HttpTransport, HttpUpgradeTransport, InboundTlsConfig, ListenFields,
+    RealityHandshake, RealityInboundConfig, TlsVersion, V2RayTransport, WebSocketTransport,
+};
+use crate::singboxconfig::strict::pointer_push;
+use crate::singboxconfig::types::{Duration, StringOrArray, VMessUser};
+
+/// 直连出站标签
+const DIRECT_TAG: &str = "direct";
+
+/// 入站共用的部分（监听、TLS、传输层）
+struct Common {
+    tag: String,
+    listen: ListenFields,
+    tls: Option<InboundTlsConfig>,
+    transport: Option<V2RayTransport>,
+}
+
+/// 转换 Xray 服务端配置
+///
+/// 没有可转换的入站、或生成的配置未通过结构校验（如端口重复）时返回错误
+pub fn convert_xray(config: &Value) -> Result<Conversion, String> {
+    let inbounds = config
+        .get("inbounds")
+        .and_then(Value::as_array)
+        .ok_or("Xray 配置中没有 inbounds 数组")?;
+    let mut skipped = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut sniff_tags: Vec<String> = Vec::new();
+    let mut converted = Vec::new();
+    for (i, inbound) in inbounds.iter().enumerate() {
+        let pointer = format!("/inbounds/{}", i);
+        match convert_inbound(inbound, &pointer, &tags, &mut skipped) {
+            Ok((tag, value)) => {
+                if inbound["sniffing"]["enabled"] == true {
+                    sniff_tags.push(tag.clone());
+                }
+                tags.push(tag);
+                converted.push(value);
+            }
+            Err(reason) => skipped.push(Change::new(pointer, reason)),
+        }
+    }
+    if converted.is_empty() {
+        let reasons: Vec<String> = skipped.iter().map(Change::to_string).collect();
+        return Err(format!(
+            "没有可转换的 VLESS / VMess / Trojan 入站: {}",
+            reasons.join("; ")
+        ));
+    }
+    collect_ignored(config, &mut skipped);
+
+    let mut builder = SingBoxConfig::builder();
+    if let Some(level) = config.pointer("/log/loglevel").and_then(Value::as_str) {
+        builder = match level {
+            "none" => builder.log(json!({ "disabled": true })),
+            "warning" => builder.log_level("warn"),
+            level => builder.log_level(level),
+        };
+    }
+    for inbound in converted {
+        builder = builder.add_inbound(inbound);
+    }
+    let mut route = json!({ "final": DIRECT_TAG });
+    if !sniff_tags.is_empty() {
+        let rule = RouteRule::new()
+            .with_inbounds(sniff_tags)
+            .with_action(RuleAction::sniff());
+        route["rules"] = json!([rule]);
+    }
+    let config = builder
+        .add_outbound(DirectOutbound::new(DIRECT_TAG))
+        .route(route)
+        .build()
+        .map_err(|diagnostics| {
+            let messages: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+            messages.join("; ")
+        })?;
+    Ok(Conversion { config, skipped })
+}
+
+/// 转换单个入站，返回标签与 sing-box 入站；返回错误时整个入站被跳过
+fn convert_inbound(
+    inbound: &Value,
+    pointer: &str,
+    tags: &[String],
+    skipped: &mut Vec<Change>,
+) -> Result<(String, Value), String> {
+    let protocol = inbound["protocol"].as_str().unwrap_or_default();
+    if !matches!(protocol, "vless" | "vmess" | "trojan") {
+        return Err(format!("不支持的入站协议 \"{}\"", protocol));
+    }
+    let common = common_fields(inbound, pointer, protocol, tags, skipped)?;
+    let tag = common.tag.clone();
+    let settings = &inbound["settings"];
+    let settings_pointer = pointer_push(pointer, "settings");
+    let value = match protocol {
+        "vless" => {
+            serde_json::to_value(vless_inbound(settings, &settings_pointer, common, skipped)?)
+        }
+        "vmess" => serde_json::to_value(vmess_inbound(settings, common)?),
+        _ => serde_json::to_value(trojan_inbound(
+            settings,
+            &settings_pointer,
+            common,
+            skipped,
+        )?),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok((tag, value))
+}
+
+/// 用户名：Xray 的 email，未设置时按序号生成
+fn user_name(client: &Value, index: usize) -> String {
+    client["email"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map_or_else(|| format!("user{}", index + 1), str::to_string)
+}
+
+/// VLESS 入站
+fn vless_inbound(
+    settings: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<VlessInbound, String> {
+    if let Some(decryption) = settings["decryption"].as_str().filter(|d| *d != "none") {
+        return Err(format!(
+            "sing-box 不支持 VLESS 加密（decryption: {}）",
+            decryption
+        ));
+    }
+    if settings["fallbacks"]
+        .as_array()
+        .is_some_and(|f| !f.is_empty())
+    {
+        skipped.push(Change::new(
+            pointer_push(pointer, "fallbacks"),
+            "sing-box 的 VLESS 入站没有回落，需要回落时改用 Trojan 或在前端放置 Web 服务器",
+        ));
+    }
+    let mut inbound = VlessInbound::new(common.tag).with_listen_fields(common.listen);
+    for (j, client) in clients(settings)?.iter().enumerate() {
+        let uuid = client["id"]
+            .as_str()
+            .ok_or_else(|| format!("第 {} 个用户缺少 id", j + 1))?;
+        let mut user = VlessUser::new(user_name(client, j), uuid);
+        match client["flow"].as_str().unwrap_or_default() {
+            "" => {}
+            "xtls-rprx-vision" => user = user.with_xtls_vision(),
+            flow => skipped.push(Change::new(
+                format!("{}/clients/{}/flow", pointer, j),
+                format!("sing-box 不支持流控 \"{}\"，已去掉", flow),
+            )),
+        }
+        inbound = inbound.add_user(user);
+    }
+    if let Some(tls) = common.tls {
+        inbound = inbound.with_tls(tls);
+    }
+    if let Some(transport) = common.transport {
+        inbound = inbound.with_transport(transport);
+    }
+    Ok(inbound)
+}
+
+/// VMess 入站
+fn vmess_inbound(settings: &Value, common: Common) -> Result<VmessInbound, String> {
+    let mut inbound = VmessInbound::new(common.tag).with_listen_fields(common.listen);
+    for (j, client) in clients(settings)?.iter().enumerate() {
+        let uuid = client["id"]
+            .as_str()
+            .ok_or_else(|| format!("第 {} 个用户缺少 id", j + 1))?;
+        let alter_id = client["alterId"].as_u64().unwrap_or(0) as u32;
+        inbound = inbound.add_user(VMessUser::with_alter_id(
+            user_name(client, j),
+            uuid,
+            alter_id,
+        ));
+    }
+    if let Some(tls) = common.tls {
+        inbound = inbound.with_tls(tls);
+    }
+    if let Some(transport) = common.transport {
+        inbound = inbound.with_transport(transport);
+    }
+    Ok(inbound)
+}
+
+/// Trojan 入站
+fn trojan_inbound(
+    settings: &Value,
+    pointer: &str,
+    common: Common,
+    skipped: &mut Vec<Change>,
+) -> Result<TrojanInbound, String> {
+    let mut inbound = TrojanInbound::new(common.tag).with_listen_fields(common.listen);
+    for (j, client) in clients(settings)?.iter().enumerate() {
+        let password = client["password"]
+            .as_str()
+            .ok_or_else(|| format!("第 {} 个用户缺少 password", j + 1))?;
+        inbound = inbound.add_user(user_name(client, j), password);
+    }
+    let fallbacks = settings["fallbacks"].as_array().map(Vec::as_slice);
+    for (j, fallback) in fallbacks.unwrap_or_default().iter().enumerate() {
+        let fallback_pointer = format!("{}/fallbacks/{}", pointer, j);
+        if fallback.get("path").is_some() || fallback.get("name").is_some() {
+            skipped.push(Change::new(
+                fallback_pointer,
+                "sing-box 只能按 ALPN 选择回落，按 path / SNI 的回落未转换",
+            ));
+            continue;
+        }
+        let Some(target) = fallback_target(&fallback["dest"]) else {
+            skipped.push(Change::new(
+                fallback_pointer,
+                "回落目标不是 TCP 地址（Unix 套接字不支持）",
+            ));
+            continue;
+        };
+        match fallback["alpn"].as_str().filter(|a| !a.is_empty()) {
+            Some(alpn) => {
+                inbound
+                    .fallback_for_alpn
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert(alpn.to_string(), target);
+            }
+            None => inbound.fallback = Some(target),
+        }
+    }
+    if let Some(tls) = common.tls {
+        inbound = inbound.with_tls(tls);
+    }
+    if let Some(transport) = common.transport {
+        inbound = inbound.with_transport(transport);
+    }
+    Ok(inbound)
+}
+
+/// settings.clients，为空时返回错误
+fn clients(settings: &Value) -> Result<&Vec<Value>, String> {
+    settings["clients"]
+        .as_array()
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| "没有用户（settings.clients 为空）".to_string())
+}
+
+/// 回落目标：端口号（本机）或 地址:端口
+fn fallback_target(dest: &Value) -> Option<TrojanFallback> {
+    let (server, server_port) = match dest {
+        Value::Number(port) => ("127.0.0.1".to_string(), port.as_u64()?),
+        Value::String(dest) => match dest.parse::<u64>() {
+            Ok(port) => ("127.0.0.1".to_string(), port),
+            Err(_) => {
+                let (host, port) = split_host_port(dest)?;
+                (host, port as u64)
+            }
+        },
+        _ => return None,
+    };
+    Some(TrojanFallback {
+        server,
+        server_port: u16::try_from(server_port).ok()?,
+    })
+}
+
+/// 拆分 `host:port` / `[v6]:port`
+fn split_host_port(address: &str) -> Option<(String, u16)> {
+    let (host, port) = address.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Some((host.to_string(), port.parse().ok()?))
+}
+
+/// 标签、监听、TLS 与传输层
+fn common_fields(
+    inbound: &Value,
+    pointer: &str,
+    protocol: &str,
+    tags: &[String],
+    skipped: &mut Vec<Change>,
+) -> Result<Common, String> {
+    let listen = match inbound["listen"].as_str().filter(|l| !l.is_empty()) {
+        Some(path) if path.starts_with('/') || path.starts_with('@') => {
+            return Err("sing-box 入站不支持监听 Unix 套接字".to_string());
+        }
+        Some(listen) => listen.to_string(),
+        None => "::".to_string(),
+    };
+    let port = match &inbound["port"] {
+        Value::Number(port) => port.as_u64(),
+        Value::String(port) => port.parse().ok(),
+        _ => None,
+    }
+    .and_then(|p| u16::try_from(p).ok())
+    .ok_or_else(|| format!("端口 {} 无法转换（只支持单个端口）", inbound["port"]))?;
+
+    let base = inbound["tag"]
+        .as_str()
+        .filter(|t| !t.is_empty())
+        .map_or_else(|| format!("{}-in", protocol), str::to_string);
+    let mut tag = base.clone();
+    let mut n = 2;
+    while tags.contains(&tag) {
+        tag = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let stream = &inbound["streamSettings"];
+    let stream_pointer = pointer_push(pointer, "streamSettings");
+    Ok(Common {
+        tag,
+        listen: ListenFields {
+            listen,
+            listen_port: Some(port),
+            ..Default::default()
+        },
+        tls: tls_config(stream, &stream_pointer, skipped)?,
+        transport: transport(stream, &stream_pointer, skipped)?,
+    })
+}
+
+/// streamSettings.network → V2Ray 传输，tcp / raw 为不使用传输层
+fn transport(
+    stream: &Value,
+    pointer: &str,
+    skipped: &mut Vec<Change>,
+) -> Result<Option<V2RayTransport>, String> {
+    let network = stream["network"].as_str().unwrap_or("tcp");
+    let settings = |key: &str| &stream[format!("{}Settings", key)];
+    let transport = match network {
+        "tcp" | "raw" => {
+            let settings = if stream.get("rawSettings").is_some() {
+                settings("raw")
+            } else {
+                settings("tcp")
+            };
+            if settings["header"]["type"] == "http" {
+                return Err("sing-box 不支持 TCP HTTP 伪装头".to_string());
+            }
+            if settings["acceptProxyProtocol"] == true {
+                skipped.push(Change::new(
+                    pointer.to_string(),
+                    "sing-box 入站不支持 PROXY protocol，acceptProxyProtocol 已忽略",
+                ));
+            }
+            return Ok(None);
+        }
+        "ws" => {
+            let ws = settings("ws");
+            let mut transport = WebSocketTransport::new()
+                .with_path(ws["path"].as_str().unwrap_or("/"))
+                // Xray 客户端的 0-RTT 通过 Sec-WebSocket-Protocol 头携带早期数据
+                .xray_compatible();
+            if let Some(host) = ws["host"]
+                .as_str()
+                .or_else(|| ws["headers"]["Host"].as_str())
+                .filter(|h| !h.is_empty())
+            {
+                transport = transport.add_header("Host", host);
+            }
+            V2RayTransport::Ws(transport)
+        }
+        "grpc" => V2RayTransport::Grpc(
+            GrpcTransport::new()
+                .with_service_name(settings("grpc")["serviceName"].as_str().unwrap_or("")),
+        ),
+        "httpupgrade" => {
+            let upgrade = settings("httpupgrade");
+            let mut transport =
+                HttpUpgradeTransport::new().with_path(upgrade["path"].as_str().unwrap_or("/"));
+            if let Some(host) = upgrade["host"].as_str().filter(|h| !h.is_empty()) {
+                transport = transport.with_host(host);
+            }
+            V2RayTransport::HttpUpgrade(transport)
+        }
+        "h2" | "http" => {
+            let http = settings("http");
+            let mut transport = HttpTransport::new();
+            if let Some(path) = http["path"].as_str() {
+                transport = transport.with_path(path);
+            }
+            if let Some(hosts) = http["host"].as_array() {
+                let hosts = hosts.iter().filter_map(Value::as_str).map(str::to_string);
+                transport = transport.with_host(hosts.collect());
+            }
+            V2RayTransport::Http(transport)
+        }
+        network => return Err(format!("sing-box 不支持传输层 \"{}\"", network)),
+    };
+    Ok(Some(transport))
+}
+
+/// streamSettings.security → 入站 TLS
+fn tls_config(
+    stream: &Value,
+    pointer: &str,
+    skipped: &mut Vec<Change>,
+) -> Result<Option<InboundTlsConfig>, String> {
+    match stream["security"].as_str().unwrap_or("none") {
+        "none" | "" => Ok(None),
+        "reality" => reality_tls(
+            &stream["realitySettings"],
+            &pointer_push(pointer, "realitySettings"),
+            skipped,
+        )
+        .map(Some),
+        "tls" => Ok(Some(certificate_tls(
+            &stream["tlsSettings"],
+            &pointer_push(pointer, "tlsSettings"),
+            skipped,
+        ))),
+        security => Err(format!("不支持的传输安全类型 \"{}\"", security)),
+    }
+}
+
+/// realitySettings → REALITY
+fn reality_tls(
+    reality: &Value,
+    pointer: &str,
+    skipped: &mut Vec<Change>,
+) -> Result<InboundTlsConfig, String> {
+    let private_key = reality["privateKey"]
+        .as_str()
+        .ok_or("REALITY 缺少 privateKey")?;
+    // 新版 Xray 使用 target，旧版为 dest
+    let target = reality.get("target").unwrap_or(&reality["dest"]);
+    let (server, server_port) = match target {
+        Value::Number(port) => ("127.0.0.1".to_string(), port.as_u64().map(|p| p as u16)),
+        Value::String(target) => match split_host_port(target) {
+            Some((host, port)) => (host, Some(port)),
+            None => (target.clone(), Some(443)),
+        },
+        _ => return Err("REALITY 缺少握手目标（target / dest）".to_string()),
+    };
+    if reality["xver"].as_u64().is_some_and(|x| x > 0) {
+        skipped.push(Change::new(
+            pointer_push(pointer, "xver"),
+            "sing-box 的 REALITY 握手不发送 PROXY protocol，xver 已忽略",
+        ));
+    }
+    let server_names: Vec<&str> = reality["serverNames"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if server_names.len() > 1 {
+        skipped.push(Change::new(
+            pointer_push(pointer, "serverNames"),
+            format!(
+                "sing-box 只接受一个 server_name，已使用 {}，其余的需要另建入站",
+                server_names[0]
+            ),
+        ));
+    }
+    let short_id = reality["shortIds"].as_array().map(|ids| {
+        ids.iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    });
+    let max_time_difference = reality["maxTimeDiff"]
+        .as_u64()
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis);
+    Ok(InboundTlsConfig {
+        enabled: Some(true),
+        server_name: server_names.first().map(|s| s.to_string()),
+        reality: Some(RealityInboundConfig {
+            enabled: Some(true),
+            handshake: Some(RealityHandshake {
+                server,
+                server_port,
+                bind_interface: None,
+                inet4_bind_address: None,
+                inet6_bind_address: None,
+                routing_mark: None,
+                reuse_addr: None,
+                netns: None,
+                connect_timeout: None,
+                tcp_fast_open: None,
+                tcp_multi_path: None,
+                udp_fragment: None,
+                domain_strategy: None,
+                fallback_delay: None,
+            }),
+            private_key: Some(private_key.to_string()),
+            short_id,
+            max_time_difference,
+        }),
+        ..Default::default()
+    })
+}
+
+/// tlsSettings → 证书 TLS
+fn certificate_tls(tls: &Value, pointer: &str, skipped: &mut Vec<Change>) -> InboundTlsConfig {
+    let mut config = InboundTlsConfig {
+        enabled: Some(true),
+        server_name: tls["serverName"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        alpn: tls["alpn"].as_array().map(|alpn| {
+            alpn.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        }),
+        ..Default::default()
+    };
+    let version = |key: &str| {
+        tls.get(key)
+            .and_then(|v| serde_json::from_value::<TlsVersion>(v.clone()).ok())
+    };
+    config.min_version = version("minVersion");
+    config.max_version = version("maxVersion");
+
+    let certificates = tls["certificates"].as_array().map(Vec::as_slice);
+    let certificates = certificates.unwrap_or_default();
+    match certificates.first() {
+        Some(certificate) => {
+            let lines = |value: &Value| match value {
+                Value::String(s) => Some(StringOrArray::single(s.as_str())),
+                Value::Array(lines) => {
+                    Some(StringOrArray::array(lines.iter().filter_map(Value::as_str)))
+                }
+                _ => None,
+            };
+            config.certificate_path = certificate["certificateFile"].as_str().map(str::to_string);
+            config.key_path = certificate["keyFile"].as_str().map(str::to_string);
+            config.certificate = lines(&certificate["certificate"]);
+            config.key = lines(&certificate["key"]);
+        }
+        None => skipped.push(Change::new(
+            pointer_push(pointer, "certificates"),
+            "没有证书，需要手动设置 tls.certificate_path / key_path 或 ACME",
+        )),
+    }
+    if certificates.len() > 1 {
+        skipped.push(Change::new(
+            pointer_push(pointer, "certificates"),
+            "sing-box 入站只使用一张证书，已使用第一张",
+        ));
+    }
+    config
+}
+
+/// 不做转换的 Xray 出站与路由规则
+fn collect_ignored(config: &Value, skipped: &mut Vec<Change>) {
+    let outbounds = config["outbounds"].as_array().map(Vec::as_slice);
+    for (i, outbound) in outbounds.unwrap_or_default().iter().enumerate() {
+        let protocol = outbound["protocol"].as_str().unwrap_or_default();
+        if !matches!(protocol, "freedom" | "blackhole") {
+            skipped.push(Change::new(
+                format!("/outbounds/{}", i),
+                format!("出站 \"{}\" 未转换，生成的配置只有直连出站", protocol),
+            ));
+        }
+    }
+    if config["routing"]["rules"]
+        .as_array()
+        .is_some_and(|rules| !rules.is_empty())
+    {
+        skipped.push(Change::new(
+            "/routing/rules",
+            "路由规则未转换，需要时在 route.rules 中手动改写",
+        ));
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singboxconfig::strict::strict_diagnostics;
+
+    fn sample() -> Value {
+        json!({
+            "log": { "loglevel": "warning" },
+            "inbounds": [{
+                "port": 443,
+                "protocol": "vless",
+                "settings": {
+                    "clients": [{ "id": "bf000d23-0752-40b4-affe-68f7707a9661", "flow": "xtls-rprx-vision", "email": "alice" }],
+                    "decryption": "none"
+                },
+                "streamSettings": {
+                    "network": "tcp",
+                    "security": "reality",
+                    "realitySettings": {
+                        "dest": "www.microsoft.com:443",
+                        "serverNames": ["www.microsoft.com", "microsoft.com"],
+                        "privateKey": "key",
+                        "shortIds": ["", "6ba85179e30d4fc2"]
+                    }
+                },
+                "sniffing": { "enabled": true, "destOverride": ["http", "tls"] }
+            }, {
+                "port": "8443",
+                "protocol": "vmess",
+                "tag": "vmess-ws",
+                "settings": { "clients": [{ "id": "bf000d23-0752-40b4-affe-68f7707a9662" }] },
+                "streamSettings": {
+                    "network": "ws",
+                    "security": "tls",
+                    "wsSettings": { "path": "/ray", "headers": { "Host": "example.com" } },
+                    "tlsSettings": {
+                        "alpn": ["http/1.1"],
+                        "minVersion": "1.2",
+                        "certificates": [{ "certificateFile": "/etc/ssl/cert.pem", "keyFile": "/etc/ssl/key.pem" }]
+                    }
+                }
+            }, {
+                "port": 2053,
+                "protocol": "trojan",
+                "settings": {
+                    "clients": [{ "password": "secret" }],
+                    "fallbacks": [{ "dest": 80 }, { "alpn": "h2", "dest": "127.0.0.1:81" }, { "path": "/ws", "dest": 1234 }]
+                },
+                "streamSettings": { "network": "grpc", "grpcSettings": { "serviceName": "tr" } }
+            }, {
+                "port": "1000-2000",
+                "protocol": "vless",
+                "settings": { "clients": [{ "id": "x" }] }
+            }, {
+                "port": 10085,
+                "listen": "127.0.0.1",
+                "protocol": "dokodemo-door",
+                "tag": "api"
+            }],
+            "outbounds": [{ "protocol": "freedom" }, { "protocol": "vmess" }],
+            "routing": { "rules": [{ "type": "field", "inboundTag": ["api"], "outboundTag": "api" }] }
+        })
+    }
+
+    #[test]
+    fn test_convert_xray() {
+        let conversion = convert_xray(&sample()).unwrap();
+        let config = serde_json::to_value(&conversion.config).unwrap();
+        assert!(strict_diagnostics(&config).is_empty());
+        assert_eq!(config["log"]["level"], "warn");
+
+        let inbounds = config["inbounds"].as_array().unwrap();
+        assert_eq!(inbounds.len(), 3);
+        let vless = &inbounds[0];
+        assert_eq!(vless["tag"], "vless-in");
+        assert_eq!(vless["listen"], "::");
+        assert_eq!(vless["users"][0]["name"], "alice");
+        assert_eq!(vless["users"][0]["flow"], "xtls-rprx-vision");
+        assert_eq!(vless["tls"]["server_name"], "www.microsoft.com");
+        let reality = &vless["tls"]["reality"];
+        assert_eq!(reality["handshake"]["server"], "www.microsoft.com");
+        assert_eq!(reality["handshake"]["server_port"], 443);
+        assert_eq!(reality["short_id"], json!(["", "6ba85179e30d4fc2"]));
+
+        let vmess = &inbounds[1];
+        assert_eq!(vmess["tag"], "vmess-ws");
+        assert_eq!(vmess["listen_port"], 8443);
+        assert_eq!(vmess["users"][0]["name"], "user1");
+        assert_eq!(vmess["transport"]["path"], "/ray");
+        assert_eq!(vmess["transport"]["headers"]["Host"], "example.com");
+        assert_eq!(vmess["tls"]["certificate_path"], "/etc/ssl/cert.pem");
+        assert_eq!(vmess["tls"]["min_version"], "1.2");
+
+        let trojan = &inbounds[2];
+        assert_eq!(
+            trojan["fallback"],
+            json!({ "server": "127.0.0.1", "server_port": 80 })
+        );
+        assert_eq!(trojan["fallback_for_alpn"]["h2"]["server_port"], 81);
+        assert_eq!(trojan["transport"]["service_name"], "tr");
+
+        assert_eq!(
+            config["route"]["rules"],
+            json!([{ "inbound": ["vless-in"], "action": "sniff" }])
+        );
+        assert_eq!(
+            config["outbounds"],
+            json!([{ "type": "direct", "tag": "direct" }])
+        );
+
+        let paths: Vec<&str> = conversion.skipped.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/inbounds/0/streamSettings/realitySettings/serverNames",
+                "/inbounds/2/settings/fallbacks/2",
+                "/inbounds/3",
+                "/inbounds/4",
+                "/outbounds/1",
+                "/routing/rules",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_xray_errors() {
+        assert!(convert_xray(&json!({})).is_err());
+        let err = convert_xray(&json!({
+            "inbounds": [{ "port": 443, "protocol": "vless", "settings": { "clients": [] } }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("/inbounds/0"));
+
+        // 重复标签自动加序号，重复端口由结构校验报告
+        let inbound = json!({ "port": 443, "protocol": "trojan", "tag": "t", "settings": { "clients": [{ "password": "p" }] } });
+        let mut other = inbound.clone();
+        other["port"] = json!(444);
+        let conversion = convert_xray(&json!({ "inbounds": [inbound.clone(), other] })).unwrap();
+        assert_eq!(conversion.config.inbounds[1]["tag"], "t-2");
+        assert!(convert_xray(&json!({ "inbounds": [inbound.clone(), inbound] })).is_err());
+    }
+}
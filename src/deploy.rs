@@ -0,0 +1,405 @@
+//! 远程部署模块
+//!
+//! 通过 ssh / scp 将生成的服务端配置（以及可选的 sing-box 二进制文件）复制到远程主机，
+//! 安装 systemd 服务单元并重启服务。每台主机依次执行，逐台报告结果
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use crate::env::env_string;
+
+/// 远程配置目录默认值
+pub const DEFAULT_REMOTE_DIR: &str = "/etc/sing-box";
+/// 远程 sing-box 二进制文件默认路径
+pub const DEFAULT_REMOTE_BIN: &str = "/usr/local/bin/sing-box";
+/// systemd 服务名默认值
+pub const DEFAULT_SERVICE: &str = "sing-box";
+
+//============================================================================
+// 部署选项
+//============================================================================
+
+/// 部署选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployOptions {
+    /// 远程配置目录（同时作为服务的工作目录，相对路径的 ACME 数据目录位于其中）
+    pub remote_dir: String,
+    /// 远程 sing-box 二进制文件路径
+    pub remote_bin: String,
+    /// 要上传的本地 sing-box 二进制文件（不指定时使用远程已安装的版本）
+    pub upload_bin: Option<String>,
+    /// systemd 服务名
+    pub service: String,
+    /// SSH 私钥文件
+    pub identity: Option<String>,
+}
+
+impl Default for DeployOptions {
+    fn default() -> Self {
+        Self {
+            remote_dir: DEFAULT_REMOTE_DIR.to_string(),
+            remote_bin: DEFAULT_REMOTE_BIN.to_string(),
+            upload_bin: None,
+            service: DEFAULT_SERVICE.to_string(),
+            identity: None,
+        }
+    }
+}
+
+impl DeployOptions {
+    /// 从环境变量读取部署选项
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            remote_dir: env_string("EZ_DEPLOY_DIR").unwrap_or(defaults.remote_dir),
+            remote_bin: env_string("EZ_DEPLOY_REMOTE_BIN").unwrap_or(defaults.remote_bin),
+            upload_bin: env_string("EZ_DEPLOY_BINARY"),
+            service: env_string("EZ_DEPLOY_SERVICE").unwrap_or(defaults.service),
+            identity: env_string("EZ_DEPLOY_SSH_KEY"),
+        }
+    }
+
+    /// 远程配置文件路径
+    pub fn remote_config(&self) -> String {
+        format!("{}/config.json", self.remote_dir.trim_end_matches('/'))
+    }
+
+    /// 远程 systemd 单元文件路径
+    pub fn unit_path(&self) -> String {
+        format!("/etc/systemd/system/{}.service", self.service)
+    }
+}
+
+/// 渲染 systemd 服务单元
+pub fn render_unit(opts: &DeployOptions) -> String {
+    format!(
+        "[Unit]
+Description=sing-box service (ezsingbox)
+Documentation=https://sing-box.sagernet.org
+After=network.target nss-lookup.target network-online.target
+Wants=network-online.target
+
+[Service]
+WorkingDirectory={dir}
+ExecStart={bin} run -c {config}
+ExecReload=/bin/kill -HUP $MAINPID
+Restart=on-failure
+RestartSec=10s
+LimitNOFILE=infinity
+CapabilityBoundingSet=CAP_NET_ADMIN CAP_NET_BIND_SERVICE
+AmbientCapabilities=CAP_NET_ADMIN CAP_NET_BIND_SERVICE
+
+[Install]
+WantedBy=multi-user.target
+",
+        dir = opts.remote_dir,
+        bin = opts.remote_bin,
+        config = opts.remote_config(),
+    )
+}
+
+//============================================================================
+// 部署步骤
+//============================================================================
+
+/// 单个部署步骤（一次 ssh 或 scp 调用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployStep {
+    /// 步骤说明
+    pub label: &'static str,
+    /// 程序（ssh / scp）
+    pub program: &'static str,
+    /// 参数
+    pub args: Vec<String>,
+    /// 写入标准输入的内容
+    pub stdin: Option<String>,
+}
+
+impl DeployStep {
+    /// 以可读形式显示命令（用于试运行）
+    pub fn display(&self) -> String {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|a| {
+                if a.contains(' ') {
+                    shell_quote(a)
+                } else {
+                    a.clone()
+                }
+            })
+            .collect();
+        let mut out = format!("{} {}", self.program, args.join(" "));
+        if self.stdin.is_some() {
+            out.push_str(" < (stdin)");
+        }
+        out
+    }
+}
+
+/// 单引号转义，用于拼接远程 shell 命令
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// ssh / scp 共用参数（非交互，私钥可选）
+fn common_args(opts: &DeployOptions) -> Vec<String> {
+    let mut args = vec!["-o".to_string(), "BatchMode=yes".to_string()];
+    if let Some(ref key) = opts.identity {
+        args.push("-i".to_string());
+        args.push(key.clone());
+    }
+    args
+}
+
+fn ssh_step(
+    label: &'static str,
+    host: &str,
+    opts: &DeployOptions,
+    command: String,
+    stdin: Option<String>,
+) -> DeployStep {
+    let mut args = common_args(opts);
+    args.push(host.to_string());
+    args.push(command);
+    DeployStep {
+        label,
+        program: "ssh",
+        args,
+        stdin,
+    }
+}
+
+fn scp_step(
+    label: &'static str,
+    host: &str,
+    opts: &DeployOptions,
+    local: &str,
+    remote: &str,
+) -> DeployStep {
+    let mut args = common_args(opts);
+    args.push(local.to_string());
+    args.push(format!("{}:{}", host, remote));
+    DeployStep {
+        label,
+        program: "scp",
+        args,
+        stdin: None,
+    }
+}
+
+/// 生成部署到单台主机的步骤
+/// 配置与二进制先上传到临时文件，sing-box check 通过后才替换，避免留下损坏的配置
+pub fn deploy_steps(host: &str, local_config: &str, opts: &DeployOptions) -> Vec<DeployStep> {
+    let config = opts.remote_config();
+    let config_tmp = format!("{}.new", config);
+    let mut steps = vec![ssh_step(
+        "创建远程目录",
+        host,
+        opts,
+        format!("mkdir -p {}", shell_quote(&opts.remote_dir)),
+        None,
+    )];
+    steps.push(scp_step("上传配置", host, opts, local_config, &config_tmp));
+    if let Some(ref bin) = opts.upload_bin {
+        let bin_tmp = format!("{}.new", opts.remote_bin);
+        steps.push(scp_step("上传 sing-box", host, opts, bin, &bin_tmp));
+        // 先写临时文件再 mv，避免覆盖正在运行的二进制文件（Text file busy）
+        steps.push(ssh_step(
+            "安装 sing-box",
+            host,
+            opts,
+            format!(
+                "chmod 0755 {tmp} && mv -f {tmp} {bin}",
+                tmp = shell_quote(&bin_tmp),
+                bin = shell_quote(&opts.remote_bin)
+            ),
+            None,
+        ));
+    }
+    steps.push(ssh_step(
+        "检查配置",
+        host,
+        opts,
+        format!(
+            "{bin} check -c {tmp} && mv -f {tmp} {config}",
+            bin = shell_quote(&opts.remote_bin),
+            tmp = shell_quote(&config_tmp),
+            config = shell_quote(&config)
+        ),
+        None,
+    ));
+    steps.push(ssh_step(
+        "安装 systemd 单元",
+        host,
+        opts,
+        format!("cat > {}", shell_quote(&opts.unit_path())),
+        Some(render_unit(opts)),
+    ));
+    let service = shell_quote(&opts.service);
+    steps.push(ssh_step(
+        "重启服务",
+        host,
+        opts,
+        format!(
+            "systemctl daemon-reload && systemctl enable {service} && systemctl restart {service}"
+        ),
+        None,
+    ));
+    steps
+}
+
+//============================================================================
+// 执行与报告
+//============================================================================
+
+/// 执行单个步骤，失败时返回包含标准错误输出的说明
+pub fn run_step(step: &DeployStep) -> Result<(), String> {
+    let mut child = Command::new(step.program)
+        .args(&step.args)
+        .stdin(if step.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("{}: 无法执行 {}: {}", step.label, step.program, e))?;
+    if let (Some(input), Some(mut stdin)) = (&step.stdin, child.stdin.take()) {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("{}: 写入标准输入失败: {}", step.label, e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("{}: {}", step.label, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!(
+            "{}失败 ({}): {}",
+            step.label,
+            output.status,
+            stderr.trim()
+        ))
+    }
+}
+
+/// 单台主机的部署结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployReport {
+    /// 节点名称（直接指定主机时为主机本身）
+    pub name: String,
+    /// SSH 目标
+    pub host: String,
+    /// 部署结果
+    pub result: Result<(), String>,
+}
+
+/// 渲染部署报告
+pub fn render_report(reports: &[DeployReport]) -> String {
+    let mut out = String::new();
+    for report in reports {
+        let target = if report.name == report.host {
+            report.host.clone()
+        } else {
+            format!("{} ({})", report.name, report.host)
+        };
+        let _ = match &report.result {
+            Ok(()) => writeln!(out, "✅ {} 部署成功", target),
+            Err(reason) => writeln!(out, "❌ {} {}", target, reason),
+        };
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deploy_steps() {
+        let opts = DeployOptions {
+            identity: Some("~/.ssh/id_ed25519".to_string()),
+            ..Default::default()
+        };
+        let steps = deploy_steps("root@1.2.3.4", "./config.json", &opts);
+        let labels: Vec<&str> = steps.iter().map(|s| s.label).collect();
+        assert_eq!(
+            labels,
+            [
+                "创建远程目录",
+                "上传配置",
+                "检查配置",
+                "安装 systemd 单元",
+                "重启服务"
+            ]
+        );
+        assert_eq!(
+            steps[1].args,
+            [
+                "-o",
+                "BatchMode=yes",
+                "-i",
+                "~/.ssh/id_ed25519",
+                "./config.json",
+                "root@1.2.3.4:/etc/sing-box/config.json.new"
+            ]
+        );
+        assert_eq!(
+            steps[2].args.last().unwrap(),
+            "'/usr/local/bin/sing-box' check -c '/etc/sing-box/config.json.new' && mv -f '/etc/sing-box/config.json.new' '/etc/sing-box/config.json'"
+        );
+        let unit = steps[3].stdin.as_deref().unwrap();
+        assert!(
+            unit.contains("ExecStart=/usr/local/bin/sing-box run -c /etc/sing-box/config.json")
+        );
+        assert!(unit.contains("WorkingDirectory=/etc/sing-box"));
+
+        let opts = DeployOptions {
+            upload_bin: Some("./sing-box".to_string()),
+            service: "it's".to_string(),
+            ..Default::default()
+        };
+        let steps = deploy_steps("vps", "c.json", &opts);
+        assert_eq!(steps.len(), 7);
+        assert_eq!(
+            steps[2].args.last().unwrap(),
+            "vps:/usr/local/bin/sing-box.new"
+        );
+        assert!(
+            steps[6]
+                .args
+                .last()
+                .unwrap()
+                .ends_with("restart 'it'\\''s'")
+        );
+    }
+
+    #[test]
+    fn test_render_report() {
+        let reports = vec![
+            DeployReport {
+                name: "jp-1".to_string(),
+                host: "root@203.0.113.1".to_string(),
+                result: Ok(()),
+            },
+            DeployReport {
+                name: "vps".to_string(),
+                host: "vps".to_string(),
+                result: Err("重启服务失败".to_string()),
+            },
+        ];
+        assert_eq!(
+            render_report(&reports),
+            "✅ jp-1 (root@203.0.113.1) 部署成功\n❌ vps 重启服务失败\n"
+        );
+    }
+}
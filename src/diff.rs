@@ -0,0 +1,194 @@
+//! 配置差异模块
+//!
+//! 比较已有配置与新生成的配置，按 JSON 路径列出新增、删除与修改的字段；
+//! 带 `tag` 的对象数组（入站、出站、DNS 服务器）按标签匹配，不受顺序影响；
+//! 值为 `null` 的字段与缺失的字段视为相同（与 `compare::value_equal` 共用同一套比较）。
+//! 敏感字段只提示已变更，不输出取值
+
+use std::fmt::Write;
+
+use ezsingbox::singboxconfig::compare::{field_equal, value_equal};
+use serde_json::Value;
+
+use crate::redact::{REDACTED, is_secret_key, redact_json};
+
+/// 单个字段的差异
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffEntry {
+    /// 新增字段
+    Added(String, Value),
+    /// 删除字段
+    Removed(String, Value),
+    /// 修改字段（旧值、新值）
+    Changed(String, Value, Value),
+}
+
+/// 比较两份配置，返回按路径排列的差异
+pub fn json_diff(old: &Value, new: &Value) -> Vec<DiffEntry> {
+    let mut out = Vec::new();
+    diff_into("", old, new, &mut out);
+    out
+}
+
+fn diff_into(path: &str, old: &Value, new: &Value, out: &mut Vec<DiffEntry>) {
+    if value_equal(old, new) {
+        return;
+    }
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let added = b.keys().filter(|key| !a.contains_key(*key));
+            for key in a.keys().chain(added) {
+                let (va, vb) = (a.get(key), b.get(key));
+                if field_equal(va, vb) {
+                    continue;
+                }
+                let child = join(path, key);
+                match (va, vb) {
+                    (Some(va), Some(vb)) => diff_into(&child, va, vb, out),
+                    (Some(va), None) => out.push(DiffEntry::Removed(child, va.clone())),
+                    (None, Some(vb)) => out.push(DiffEntry::Added(child, vb.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if is_tagged(a) && is_tagged(b) => {
+            for va in a {
+                let tag = &va["tag"];
+                let child = format!("{}[{}]", path, tag.as_str().unwrap_or_default());
+                match b.iter().find(|vb| &vb["tag"] == tag) {
+                    Some(vb) => diff_into(&child, va, vb, out),
+                    None => out.push(DiffEntry::Removed(child, va.clone())),
+                }
+            }
+            for vb in b
+                .iter()
+                .filter(|vb| !a.iter().any(|va| va["tag"] == vb["tag"]))
+            {
+                let child = format!("{}[{}]", path, vb["tag"].as_str().unwrap_or_default());
+                out.push(DiffEntry::Added(child, vb.clone()));
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(va), Some(vb)) => diff_into(&child, va, vb, out),
+                    (Some(va), None) => out.push(DiffEntry::Removed(child, va.clone())),
+                    (None, Some(vb)) => out.push(DiffEntry::Added(child, vb.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => out.push(DiffEntry::Changed(
+            path.to_string(),
+            old.clone(),
+            new.clone(),
+        )),
+    }
+}
+
+/// 拼接对象字段路径
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// 数组中的元素是否都是带字符串 tag 的对象
+fn is_tagged(items: &[Value]) -> bool {
+    !items.is_empty() && items.iter().all(|v| v["tag"].is_string())
+}
+
+/// 路径的最后一个字段名是否为敏感字段
+fn is_secret_path(path: &str) -> bool {
+    let last = path.rsplit('.').next().unwrap_or(path);
+    let key = last.split('[').next().unwrap_or(last);
+    is_secret_key(key)
+}
+
+/// 渲染单个取值（敏感字段替换为占位符）
+fn render_value(path: &str, value: &Value) -> String {
+    if is_secret_path(path) {
+        return REDACTED.to_string();
+    }
+    let mut value = value.clone();
+    redact_json(&mut value);
+    value.to_string()
+}
+
+/// 以统一差异格式渲染（`-` 为旧值，`+` 为新值）
+pub fn render_diff(old_label: &str, new_label: &str, entries: &[DiffEntry]) -> String {
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for entry in entries {
+        match entry {
+            DiffEntry::Added(path, v) => {
+                let _ = writeln!(out, "+ {}: {}", path, render_value(path, v));
+            }
+            DiffEntry::Removed(path, v) => {
+                let _ = writeln!(out, "- {}: {}", path, render_value(path, v));
+            }
+            DiffEntry::Changed(path, ..) if is_secret_path(path) => {
+                let _ = writeln!(out, "~ {}: 已变更（{}）", path, REDACTED);
+            }
+            DiffEntry::Changed(path, old, new) => {
+                let _ = writeln!(out, "- {}: {}", path, render_value(path, old));
+                let _ = writeln!(out, "+ {}: {}", path, render_value(path, new));
+            }
+        }
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_diff() {
+        let old = json!({
+            "log": { "level": "info" },
+            "inbounds": [
+                { "type": "anytls", "tag": "anytls-in", "listen_port": 443,
+                  "users": [{ "name": "alice", "password": "old-secret" }] },
+                { "type": "tuic", "tag": "tuic-in", "listen_port": 2083 }
+            ]
+        });
+        let new = json!({
+            "log": { "level": "warn" },
+            "inbounds": [
+                { "type": "hysteria2", "tag": "hy2-in", "listen_port": 2053 },
+                { "type": "anytls", "tag": "anytls-in", "listen_port": 443,
+                  "users": [{ "name": "alice", "password": "new-secret" }] }
+            ]
+        });
+        let entries = json_diff(&old, &new);
+        assert_eq!(entries.len(), 4);
+        assert!(entries.contains(&DiffEntry::Changed(
+            "log.level".to_string(),
+            json!("info"),
+            json!("warn")
+        )));
+
+        let text = render_diff("./config.json", "新配置", &entries);
+        assert!(text.starts_with("--- ./config.json\n+++ 新配置\n"));
+        assert!(text.contains("~ inbounds[anytls-in].users[0].password: 已变更"));
+        assert!(text.contains("- inbounds[tuic-in]: {"));
+        assert!(text.contains("+ inbounds[hy2-in]: {"));
+        assert!(!text.contains("secret"));
+        assert!(json_diff(&old, &old).is_empty());
+
+        // null 与缺失的字段相同
+        let entries = json_diff(
+            &json!({ "log": { "level": "info", "output": null } }),
+            &json!({ "log": { "level": "info" }, "ntp": null }),
+        );
+        assert!(entries.is_empty());
+    }
+}
@@ -3,10 +3,10 @@
 //! 此模块包含 sing-box DNS 配置的完整类型定义
 //! 参考文档: https://sing-box.sagernet.org/configuration/dns/
 
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 
-use crate::singboxconfig::types::{DomainStrategy, StringOrArray};
+use crate::singboxconfig::types::{DomainStrategy, PortList, PortRangeList, StringOrArray};
 
 // ============================================================================
 // DNS 主配置
@@ -33,7 +33,7 @@ use crate::singboxconfig::types::{DomainStrategy, StringOrArray};
 ///   }
 /// }
 /// ```
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Dns {
     /// DNS 服务器列表
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,7 +94,7 @@ pub struct Dns {
 ///
 /// 使用 `type` 字段区分不同类型的 DNS 服务器
 /// Since sing-box 1.12.0 引入新的类型化服务器配置
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum DnsServer {
     /// 本地 DNS 服务器
@@ -134,14 +134,14 @@ pub enum DnsServer {
     /// Resolved DNS 服务器
     Resolved(ResolvedDnsServer),
 
-    /// Legacy DNS 服务器配置 (空类型或未指定类型)
-    #[serde(other)]
-    Legacy,
+    /// Legacy DNS 服务器配置（未指定类型的 `address` 格式，原样保留）
+    #[serde(untagged)]
+    Legacy(LegacyDnsServer),
 }
 
 /// Legacy DNS 服务器配置
 /// 用于兼容旧版本配置格式
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct LegacyDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -178,7 +178,7 @@ pub struct LegacyDnsServer {
 
 /// 本地 DNS 服务器
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct LocalDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -196,7 +196,7 @@ pub struct LocalDnsServer {
 
 /// Hosts DNS 服务器
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct HostsDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -208,12 +208,12 @@ pub struct HostsDnsServer {
 
     /// 预定义的 hosts 映射
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub predefined: Option<HashMap<String, StringOrArray>>,
+    pub predefined: Option<BTreeMap<String, StringOrArray>>,
 }
 
 ///远程 DNS 服务器 (TCP/UDP/TLS/QUIC/HTTPS/H3)
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct RemoteDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -227,6 +227,10 @@ pub struct RemoteDnsServer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub server_port: Option<u16>,
 
+    /// HTTP 请求路径（仅 HTTPS / H3，默认 /dns-query）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
     /// 地址解析器
     #[serde(skip_serializing_if = "Option::is_none")]
     pub address_resolver: Option<String>,
@@ -250,7 +254,7 @@ pub struct RemoteDnsServer {
 
 /// DHCP DNS 服务器
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DhcpDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -267,7 +271,7 @@ pub struct DhcpDnsServer {
 
 /// FakeIP DNS 服务器
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct FakeIPDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -284,29 +288,50 @@ pub struct FakeIPDnsServer {
 
 /// Tailscale DNS 服务器
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct TailscaleDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<String>,
+
+    /// Tailscale 端点标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// 将 Tailscale 下发的全局 DNS 作为默认解析器
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_default_resolvers: Option<bool>,
 }
 
 /// Resolved DNS 服务器
 /// Since sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct ResolvedDnsServer {
     /// 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<String>,
+
+    /// resolved 服务标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+
+    /// 将链路的全局 DNS 作为默认解析器
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_default_resolvers: Option<bool>,
 }
 
 /// DNS拨号字段
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DnsDialFields {
     /// 出站代理
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detour: Option<String>,
 
+    /// 解析服务器域名所用的 DNS 服务器标签
+    /// Since sing-box 1.12.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_resolver: Option<String>,
+
     /// 绑定接口
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bind_interface: Option<String>,
@@ -350,7 +375,7 @@ pub struct DnsDialFields {
 
 /// Legacy FakeIP 配置
 /// Deprecated in sing-box 1.12.0
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct LegacyFakeIP {
     /// 启用 FakeIP
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -374,16 +399,20 @@ pub struct LegacyFakeIP {
 /// 支持两种类型：
 /// - 默认规则：使用各种匹配条件
 /// - 逻辑规则：使用 `and` 或 `or` 组合多个规则
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+// 规则数量很少，保持与 JSON 结构一致、便于构造，不为默认规则装箱
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum DnsRule {
-    /// 逻辑规则Logical(LogicalDnsRule),
+    /// 逻辑规则（须在默认规则之前尝试，否则会被当作忽略未知字段的默认规则）
+    Logical(LogicalDnsRule),
     /// 默认规则
     Default(DefaultDnsRule),
 }
 
 /// 默认 DNS 规则
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(remote = "Self")]
 pub struct DefaultDnsRule {
     //==========匹配条件 ==========
     /// 入站标签
@@ -452,19 +481,19 @@ pub struct DefaultDnsRule {
 
     /// 源端口
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub source_port: Option<PortOrArray>,
+    pub source_port: Option<PortList>,
 
     /// 源端口范围
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub source_port_range: Option<StringOrArray>,
+    pub source_port_range: Option<PortRangeList>,
 
     /// 目标端口
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub port: Option<PortOrArray>,
+    pub port: Option<PortList>,
 
     /// 目标端口范围
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub port_range: Option<StringOrArray>,
+    pub port_range: Option<PortRangeList>,
 
     /// 进程名称
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -513,12 +542,12 @@ pub struct DefaultDnsRule {
     /// 接口地址
     /// Since sing-box 1.13.0
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub interface_address: Option<HashMap<String, StringOrArray>>,
+    pub interface_address: Option<BTreeMap<String, StringOrArray>>,
 
     /// 网络接口地址
     /// Since sing-box 1.13.0
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub network_interface_address: Option<HashMap<String, StringOrArray>>,
+    pub network_interface_address: Option<BTreeMap<String, StringOrArray>>,
 
     /// 默认接口地址
     /// Since sing-box 1.13.0
@@ -559,8 +588,8 @@ pub struct DefaultDnsRule {
 
     // ========== 动作 ==========
     /// 规则动作
-    /// Since sing-box 1.11.0
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Since sing-box 1.11.0，字段与规则平铺（如 `"action": "reject"`）
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub action: Option<DnsRuleAction>,
 
     /// DNS 服务器标签
@@ -603,7 +632,8 @@ pub struct DefaultDnsRule {
 }
 
 /// 逻辑DNS 规则
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(remote = "Self")]
 pub struct LogicalDnsRule {
     /// 规则类型，必须为 "logical"
     #[serde(rename = "type")]
@@ -616,18 +646,33 @@ pub struct LogicalDnsRule {
     pub rules: Vec<DefaultDnsRule>,
 
     /// 规则动作
-    /// Since sing-box 1.11.0
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Since sing-box 1.11.0，字段与规则平铺（如 `"action": "reject"`）
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub action: Option<DnsRuleAction>,
 
     /// DNS 服务器标签
-    /// Deprecated in sing-box 1.11.0
+    /// Deprecated in sing-box 1.11.0, moved to action
     #[serde(skip_serializing_if = "Option::is_none")]
     pub server: Option<String>,
+
+    /// 禁用缓存
+    /// Deprecated in sing-box 1.11.0, moved to action
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_cache: Option<bool>,
+
+    /// 重写TTL
+    /// Deprecated in sing-box 1.11.0, moved to action
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewrite_ttl: Option<u32>,
+
+    /// Client Subnet
+    /// Deprecated in sing-box 1.11.0, moved to action
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_subnet: Option<String>,
 }
 
 /// 逻辑模式
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogicalMode {
     /// 所有条件都必须匹配
@@ -648,7 +693,7 @@ impl Default for LogicalMode {
 
 /// DNS 规则动作
 /// Since sing-box 1.11.0
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(tag = "action", rename_all = "kebab-case")]
 pub enum DnsRuleAction {
     /// 路由到指定 DNS 服务器
@@ -666,7 +711,7 @@ pub enum DnsRuleAction {
 }
 
 /// DNS 路由动作
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DnsRouteAction {
     /// DNS 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -686,7 +731,7 @@ pub struct DnsRouteAction {
 }
 
 /// DNS 路由选项动作
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DnsRouteOptionsAction {
     /// 禁用缓存
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -702,7 +747,7 @@ pub struct DnsRouteOptionsAction {
 }
 
 /// DNS 拒绝动作
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DnsRejectAction {
     /// 拒绝方法
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -714,7 +759,7 @@ pub struct DnsRejectAction {
 }
 
 /// DNS 拒绝方法
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DnsRejectMethod {
     /// 返回默认响应
@@ -724,7 +769,7 @@ pub enum DnsRejectMethod {
 }
 
 /// DNS 预定义响应动作
-#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DnsPredefinedAction {
     /// 预定义的RCode
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -741,7 +786,7 @@ pub struct DnsPredefinedAction {
 
 /// DNS 查询类型
 /// 可以是整数或字符串类型名称
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum QueryType {
     /// 单个查询类型
@@ -751,7 +796,7 @@ pub enum QueryType {
 }
 
 /// 查询类型值
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum QueryTypeValue {
     /// 整数类型 (如 1, 28, 32768)
@@ -760,18 +805,11 @@ pub enum QueryTypeValue {
     Name(String),
 }
 
-/// 端口或端口数组
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(untagged)]
-pub enum PortOrArray {
-    /// 单个端口
-    Single(u16),
-    /// 多个端口
-    Array(Vec<u16>),
-}
+/// 端口或端口数组（旧名称，请使用 `PortList`）
+pub type PortOrArray = PortList;
 
 /// 用户ID 或用户 ID 数组
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum UidOrArray {
     /// 单个用户 ID
@@ -872,7 +910,7 @@ impl HostsDnsServer {
 
     /// 添加 hosts 文件路径
     pub fn path<S: Into<String>>(mut self, path: S) -> Self {
-        self.path = Some(StringOrArray::Single(path.into()));
+        self.path = Some(StringOrArray::single(path));
         self
     }
 
@@ -882,9 +920,7 @@ impl HostsDnsServer {
         I: IntoIterator<Item = S>,
         S: Into<String>,
     {
-        self.path = Some(StringOrArray::Array(
-            paths.into_iter().map(|s| s.into()).collect(),
-        ));
+        self.path = Some(paths.into_iter().collect());
         self
     }
 }
@@ -895,6 +931,41 @@ impl RemoteDnsServer {
         Self::default()
     }
 
+    /// 创建指定地址与端口的远程服务器
+    fn with_endpoint<S: Into<String>>(server: S, port: u16) -> Self {
+        Self::new().server(server).server_port(port)
+    }
+
+    /// UDP 服务器（端口 53），配合 [`DnsServer::Udp`] 使用
+    pub fn udp<S: Into<String>>(server: S) -> Self {
+        Self::with_endpoint(server, 53)
+    }
+
+    /// TCP 服务器（端口 53），配合 [`DnsServer::Tcp`] 使用
+    pub fn tcp<S: Into<String>>(server: S) -> Self {
+        Self::with_endpoint(server, 53)
+    }
+
+    /// DNS over TLS 服务器（端口 853），配合 [`DnsServer::Tls`] 使用
+    pub fn tls<S: Into<String>>(server: S) -> Self {
+        Self::with_endpoint(server, 853)
+    }
+
+    /// DNS over QUIC 服务器（端口 853），配合 [`DnsServer::Quic`] 使用
+    pub fn quic<S: Into<String>>(server: S) -> Self {
+        Self::with_endpoint(server, 853)
+    }
+
+    /// DNS over HTTPS 服务器（端口 443，路径 /dns-query），配合 [`DnsServer::Https`] 使用
+    pub fn https<S: Into<String>>(server: S) -> Self {
+        Self::with_endpoint(server, 443).path("/dns-query")
+    }
+
+    /// DNS over HTTP/3 服务器（端口 443，路径 /dns-query），配合 [`DnsServer::H3`] 使用
+    pub fn h3<S: Into<String>>(server: S) -> Self {
+        Self::with_endpoint(server, 443).path("/dns-query")
+    }
+
     /// 设置标签
     pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
         self.tag = Some(tag.into());
@@ -913,12 +984,32 @@ impl RemoteDnsServer {
         self
     }
 
+    /// 设置 HTTP 请求路径
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
     /// 设置地址解析器
     pub fn address_resolver<S: Into<String>>(mut self, resolver: S) -> Self {
         self.address_resolver = Some(resolver.into());
         self
     }
 
+    /// 设置出站代理
+    pub fn detour<S: Into<String>>(mut self, detour: S) -> Self {
+        self.dial.get_or_insert_with(Default::default).detour = Some(detour.into());
+        self
+    }
+
+    /// 设置解析服务器域名所用的 DNS 服务器
+    pub fn domain_resolver<S: Into<String>>(mut self, resolver: S) -> Self {
+        self.dial
+            .get_or_insert_with(Default::default)
+            .domain_resolver = Some(resolver.into());
+        self
+    }
+
     /// 设置域名解析策略
     pub fn strategy(mut self, strategy: DomainStrategy) -> Self {
         self.strategy = Some(strategy);
@@ -926,6 +1017,90 @@ impl RemoteDnsServer {
     }
 }
 
+impl DhcpDnsServer {
+    /// 创建新的 DHCP DNS 服务器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置标签
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// 设置监听的网络接口
+    pub fn interface<S: Into<String>>(mut self, name: S) -> Self {
+        self.interface_name = Some(name.into());
+        self
+    }
+}
+
+impl TailscaleDnsServer {
+    /// 创建使用指定 Tailscale 端点的 DNS 服务器
+    pub fn new<S: Into<String>>(endpoint: S) -> Self {
+        Self {
+            endpoint: Some(endpoint.into()),
+            ..Default::default()
+        }
+    }
+
+    /// 设置标签
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// 设置是否接受 Tailscale 下发的默认解析器
+    pub fn accept_default_resolvers(mut self, accept: bool) -> Self {
+        self.accept_default_resolvers = Some(accept);
+        self
+    }
+}
+
+impl ResolvedDnsServer {
+    /// 创建使用指定 resolved 服务的 DNS 服务器
+    pub fn new<S: Into<String>>(service: S) -> Self {
+        Self {
+            service: Some(service.into()),
+            ..Default::default()
+        }
+    }
+
+    /// 设置标签
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// 设置是否接受链路的默认解析器
+    pub fn accept_default_resolvers(mut self, accept: bool) -> Self {
+        self.accept_default_resolvers = Some(accept);
+        self
+    }
+}
+
+impl DnsServer {
+    /// 服务器标签
+    pub fn tag(&self) -> Option<&str> {
+        match self {
+            DnsServer::Local(s) => s.tag.as_deref(),
+            DnsServer::Hosts(s) => s.tag.as_deref(),
+            DnsServer::Tcp(s)
+            | DnsServer::Udp(s)
+            | DnsServer::Tls(s)
+            | DnsServer::Quic(s)
+            | DnsServer::Https(s)
+            | DnsServer::H3(s) => s.tag.as_deref(),
+            DnsServer::Dhcp(s) => s.tag.as_deref(),
+            DnsServer::FakeIP(s) => s.tag.as_deref(),
+            DnsServer::Tailscale(s) => s.tag.as_deref(),
+            DnsServer::Resolved(s) => s.tag.as_deref(),
+            DnsServer::Legacy(s) => s.tag.as_deref(),
+        }
+    }
+}
+
 impl FakeIPDnsServer {
     /// 创建新的 FakeIP DNS 服务器
     pub fn new() -> Self {
@@ -959,7 +1134,7 @@ impl DefaultDnsRule {
 
     /// 设置域名匹配
     pub fn domain<S: Into<String>>(mut self, domain: S) -> Self {
-        self.domain = Some(StringOrArray::Single(domain.into()));
+        self.domain = Some(StringOrArray::single(domain));
         self
     }
 
@@ -969,15 +1144,60 @@ impl DefaultDnsRule {
         I: IntoIterator<Item = S>,
         S: Into<String>,
     {
-        self.domain = Some(StringOrArray::Array(
-            domains.into_iter().map(|s| s.into()).collect(),
-        ));
+        self.domain = Some(domains.into_iter().collect());
         self
     }
 
     /// 设置域名后缀匹配
     pub fn domain_suffix<S: Into<String>>(mut self, suffix: S) -> Self {
-        self.domain_suffix = Some(StringOrArray::Single(suffix.into()));
+        self.domain_suffix = Some(StringOrArray::single(suffix));
+        self
+    }
+
+    /// 设置多个域名后缀匹配
+    pub fn domain_suffixes<I, S>(mut self, suffixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.domain_suffix = Some(suffixes.into_iter().collect());
+        self
+    }
+
+    /// 设置目标端口匹配
+    pub fn ports(mut self, ports: impl Into<PortList>) -> Self {
+        self.port = Some(ports.into());
+        self
+    }
+
+    /// 设置目标端口范围匹配（如 `"1000:2000".parse::<PortRangeList>()`）
+    pub fn port_ranges(mut self, ranges: impl Into<PortRangeList>) -> Self {
+        self.port_range = Some(ranges.into());
+        self
+    }
+
+    /// 设置规则集匹配（引用 `route.rule_set` 中的标签）
+    pub fn rule_sets<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rule_set = Some(tags.into_iter().collect());
+        self
+    }
+
+    /// 设置查询类型匹配
+    pub fn query_types<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.query_type = Some(QueryType::Array(
+            types
+                .into_iter()
+                .map(|t| QueryTypeValue::Name(t.into()))
+                .collect(),
+        ));
         self
     }
 
@@ -1000,6 +1220,139 @@ impl DefaultDnsRule {
     }
 }
 
+/// 规则动作与 1.11 之前平铺在规则上的动作字段
+pub(crate) struct RuleActionFields<'a> {
+    pub action: &'a mut Option<DnsRuleAction>,
+    pub server: &'a mut Option<String>,
+    pub disable_cache: &'a mut Option<bool>,
+    pub rewrite_ttl: &'a mut Option<u32>,
+    pub client_subnet: &'a mut Option<String>,
+}
+
+impl DnsRule {
+    /// 取得规则的动作字段（默认规则与逻辑规则相同）
+    pub(crate) fn action_fields(&mut self) -> RuleActionFields<'_> {
+        match self {
+            DnsRule::Logical(rule) => rule.action_fields(),
+            DnsRule::Default(rule) => rule.action_fields(),
+        }
+    }
+}
+
+impl DefaultDnsRule {
+    fn action_fields(&mut self) -> RuleActionFields<'_> {
+        RuleActionFields {
+            action: &mut self.action,
+            server: &mut self.server,
+            disable_cache: &mut self.disable_cache,
+            rewrite_ttl: &mut self.rewrite_ttl,
+            client_subnet: &mut self.client_subnet,
+        }
+    }
+}
+
+impl LogicalDnsRule {
+    fn action_fields(&mut self) -> RuleActionFields<'_> {
+        RuleActionFields {
+            action: &mut self.action,
+            server: &mut self.server,
+            disable_cache: &mut self.disable_cache,
+            rewrite_ttl: &mut self.rewrite_ttl,
+            client_subnet: &mut self.client_subnet,
+        }
+    }
+}
+
+impl RuleActionFields<'_> {
+    /// 动作字段与 1.11 之前的规则字段同名，派生的反序列化把它们交给规则字段；
+    /// 有 route / route-options 动作时移回动作
+    fn move_into_action(self) {
+        let (disable_cache, rewrite_ttl, client_subnet) = match self.action {
+            Some(DnsRuleAction::Route(route)) => {
+                if route.server.is_none() {
+                    route.server = self.server.take();
+                }
+                (
+                    &mut route.disable_cache,
+                    &mut route.rewrite_ttl,
+                    &mut route.client_subnet,
+                )
+            }
+            Some(DnsRuleAction::RouteOptions(options)) => (
+                &mut options.disable_cache,
+                &mut options.rewrite_ttl,
+                &mut options.client_subnet,
+            ),
+            _ => return,
+        };
+        if disable_cache.is_none() {
+            *disable_cache = self.disable_cache.take();
+        }
+        if rewrite_ttl.is_none() {
+            *rewrite_ttl = self.rewrite_ttl.take();
+        }
+        if client_subnet.is_none() {
+            *client_subnet = self.client_subnet.take();
+        }
+    }
+}
+
+impl Serialize for DefaultDnsRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DefaultDnsRule::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DefaultDnsRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut rule = DefaultDnsRule::deserialize(deserializer)?;
+        rule.action_fields().move_into_action();
+        Ok(rule)
+    }
+}
+
+impl Serialize for LogicalDnsRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LogicalDnsRule::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LogicalDnsRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut rule = LogicalDnsRule::deserialize(deserializer)?;
+        rule.action_fields().move_into_action();
+        Ok(rule)
+    }
+}
+
+impl DnsRuleAction {
+    /// 创建路由到指定服务器的动作
+    pub fn route<S: Into<String>>(server: S) -> Self {
+        DnsRuleAction::Route(DnsRouteAction {
+            server: Some(server.into()),
+            disable_cache: None,
+            rewrite_ttl: None,
+            client_subnet: None,
+        })
+    }
+
+    /// 创建拒绝动作
+    pub fn reject(method: DnsRejectMethod) -> Self {
+        DnsRuleAction::Reject(DnsRejectAction {
+            method: Some(method),
+            no_drop: None,
+        })
+    }
+
+    /// 创建仅返回指定 RCode 的预定义响应动作
+    pub fn predefined_rcode<S: Into<String>>(rcode: S) -> Self {
+        DnsRuleAction::Predefined(DnsPredefinedAction {
+            rcode: Some(rcode.into()),
+            answer: None,
+        })
+    }
+}
+
 // ============================================================================
 // 单元测试
 // ============================================================================
@@ -1058,10 +1411,7 @@ mod tests {
     fn test_hosts_dns_server_serialize() {
         let server = HostsDnsServer {
             tag: Some("hosts".to_string()),
-            path: Some(StringOrArray::Array(vec![
-                "/etc/hosts".to_string(),
-                "$HOME/.hosts".to_string(),
-            ])),
+            path: Some(["/etc/hosts", "$HOME/.hosts"].into()),
             predefined: None,
         };
 
@@ -1083,6 +1433,90 @@ mod tests {
         assert_eq!(server.server_port, Some(53));
     }
 
+    #[test]
+    fn test_typed_server_serialize() {
+        use serde_json::json;
+
+        let cases = [
+            (
+                DnsServer::Https(RemoteDnsServer::https("dns.google").tag("google")),
+                json!({
+                    "type": "https",
+                    "tag": "google",
+                    "server": "dns.google",
+                    "server_port": 443,
+                    "path": "/dns-query"
+                }),
+            ),
+            (
+                DnsServer::H3(RemoteDnsServer::h3("1.1.1.1").tag("cf-h3")),
+                json!({
+                    "type": "h3",
+                    "tag": "cf-h3",
+                    "server": "1.1.1.1",
+                    "server_port": 443,
+                    "path": "/dns-query"
+                }),
+            ),
+            (
+                DnsServer::Tls(RemoteDnsServer::tls("1.1.1.1").tag("dot")),
+                json!({ "type": "tls", "tag": "dot", "server": "1.1.1.1", "server_port": 853 }),
+            ),
+            (
+                DnsServer::Quic(
+                    RemoteDnsServer::quic("dns.adguard-dns.com")
+                        .tag("doq")
+                        .domain_resolver("local"),
+                ),
+                json!({
+                    "type": "quic",
+                    "tag": "doq",
+                    "server": "dns.adguard-dns.com",
+                    "server_port": 853,
+                    "domain_resolver": "local"
+                }),
+            ),
+            (
+                DnsServer::Udp(RemoteDnsServer::udp("8.8.8.8").tag("udp").detour("proxy")),
+                json!({
+                    "type": "udp",
+                    "tag": "udp",
+                    "server": "8.8.8.8",
+                    "server_port": 53,
+                    "detour": "proxy"
+                }),
+            ),
+            (
+                DnsServer::Dhcp(DhcpDnsServer::new().tag("dhcp").interface("eth0")),
+                json!({ "type": "dhcp", "tag": "dhcp", "interface": "eth0" }),
+            ),
+            (
+                DnsServer::Tailscale(
+                    TailscaleDnsServer::new("ts-ep")
+                        .tag("ts")
+                        .accept_default_resolvers(true),
+                ),
+                json!({
+                    "type": "tailscale",
+                    "tag": "ts",
+                    "endpoint": "ts-ep",
+                    "accept_default_resolvers": true
+                }),
+            ),
+            (
+                DnsServer::Resolved(ResolvedDnsServer::new("resolved").tag("resolved")),
+                json!({ "type": "resolved", "tag": "resolved", "service": "resolved" }),
+            ),
+        ];
+        for (server, expected) in cases {
+            assert_eq!(serde_json::to_value(&server).unwrap(), expected);
+            // 拨号字段为平铺的 Option，反序列化后比较 JSON 表示
+            let parsed: DnsServer = serde_json::from_value(expected.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&parsed).unwrap(), expected);
+            assert!(server.tag().is_some());
+        }
+    }
+
     #[test]
     fn test_fakeip_dns_server() {
         let server = FakeIPDnsServer::new()
@@ -1111,20 +1545,18 @@ mod tests {
     fn test_default_dns_rule() {
         let rule = DefaultDnsRule::new().domain("google.com").server("google");
 
-        assert_eq!(
-            rule.domain,
-            Some(StringOrArray::Single("google.com".to_string()))
-        );
+        assert_eq!(rule.domain, Some(StringOrArray::from("google.com")));
         assert_eq!(rule.server, Some("google".to_string()));
+
+        let rule = DefaultDnsRule::new().rule_sets(["geosite-category-ads-all"]);
+        let value = serde_json::to_value(&rule).unwrap();
+        assert_eq!(value["rule_set"][0], "geosite-category-ads-all");
     }
 
     #[test]
     fn test_dns_rule_serialize() {
         let rule = DefaultDnsRule {
-            domain: Some(StringOrArray::Array(vec![
-                "google.com".to_string(),
-                "youtube.com".to_string(),
-            ])),
+            domain: Some(["google.com", "youtube.com"].into()),
             server: Some("proxy".to_string()),
             ..Default::default()
         };
@@ -1140,15 +1572,71 @@ mod tests {
             rule_type: "logical".to_string(),
             mode: LogicalMode::And,
             rules: vec![DefaultDnsRule {
-                domain_suffix: Some(StringOrArray::Single(".cn".to_string())),
+                domain_suffix: Some(".cn".into()),
                 ..Default::default()
             }],
             action: None,
             server: Some("local".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&rule).unwrap();
         assert!(json.contains("\"mode\":\"and\""));
+
+        // 逻辑规则不被当作默认规则解析
+        let parsed: DnsRule = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, DnsRule::Logical(rule));
+    }
+
+    #[test]
+    fn test_dns_rule_action_fields() {
+        // 动作的 server 等字段不落入同名的 legacy 规则字段
+        let rule: DefaultDnsRule = serde_json::from_value(serde_json::json!({
+            "domain": ["a.com"],
+            "action": "route",
+            "server": "remote",
+            "disable_cache": true
+        }))
+        .unwrap();
+        assert_eq!(rule.server, None);
+        assert_eq!(rule.disable_cache, None);
+        assert_eq!(
+            rule.action,
+            Some(DnsRuleAction::Route(DnsRouteAction {
+                server: Some("remote".to_string()),
+                disable_cache: Some(true),
+                ..Default::default()
+            }))
+        );
+        let again: DefaultDnsRule =
+            serde_json::from_value(serde_json::to_value(&rule).unwrap()).unwrap();
+        assert_eq!(again, rule);
+
+        let rule: DnsRule = serde_json::from_value(serde_json::json!({
+            "type": "logical",
+            "mode": "and",
+            "rules": [{ "domain": ["a.com"] }],
+            "action": "route-options",
+            "rewrite_ttl": 60
+        }))
+        .unwrap();
+        let DnsRule::Logical(logical) = &rule else {
+            panic!("应解析为逻辑规则");
+        };
+        assert_eq!(logical.rewrite_ttl, None);
+        assert_eq!(
+            logical.action,
+            Some(DnsRuleAction::RouteOptions(DnsRouteOptionsAction {
+                rewrite_ttl: Some(60),
+                ..Default::default()
+            }))
+        );
+
+        // 没有动作的旧版规则保留规则字段
+        let rule: DefaultDnsRule =
+            serde_json::from_str(r#"{ "domain": ["a.com"], "server": "local" }"#).unwrap();
+        assert_eq!(rule.server.as_deref(), Some("local"));
+        assert!(rule.action.is_none());
     }
 
     #[test]
@@ -1179,6 +1667,17 @@ mod tests {
         // 端口数组
         let ports: PortOrArray = serde_json::from_str("[80, 443]").unwrap();
         assert_eq!(ports, PortOrArray::Array(vec![80, 443]));
+
+        // 规则中的端口与端口范围
+        let rule = DefaultDnsRule::new()
+            .ports(53)
+            .port_ranges("1000:2000,3000-3100".parse::<PortRangeList>().unwrap());
+        let json = serde_json::to_value(&rule).unwrap();
+        assert_eq!(json["port"], 53);
+        assert_eq!(
+            json["port_range"],
+            serde_json::json!(["1000:2000", "3000:3100"])
+        );
     }
 
     #[test]
@@ -1221,4 +1720,23 @@ mod tests {
         let json = serde_json::to_string(&action).unwrap();
         assert!(json.contains("\"method\":\"drop\""));
     }
+
+    #[test]
+    fn test_rule_action_flatten() {
+        let rule = DefaultDnsRule::new()
+            .domain_suffixes(["telemetry.example.com"])
+            .action(DnsRuleAction::predefined_rcode("NXDOMAIN"));
+        let value = serde_json::to_value(&rule).unwrap();
+        assert_eq!(value["action"], "predefined");
+        assert_eq!(value["rcode"], "NXDOMAIN");
+        assert_eq!(value["domain_suffix"][0], "telemetry.example.com");
+
+        let rule: DefaultDnsRule =
+            serde_json::from_str(r#"{"domain":"a.com","action":"reject","method":"drop"}"#)
+                .unwrap();
+        assert_eq!(
+            rule.action,
+            Some(DnsRuleAction::reject(DnsRejectMethod::Drop))
+        );
+    }
 }
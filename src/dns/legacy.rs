@@ -0,0 +1,555 @@
+//! Legacy DNS 配置转换
+//!
+//! sing-box 1.12 之前不支持类型化 DNS 服务器，1.11 之前不支持规则动作；
+//! 此模块将新格式的 DNS 配置转换为旧版本可识别的 `address` 格式与规则字段，
+//! 也可反过来把旧版配置改写为新格式（migrate 命令）
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::dns::{
+    DhcpDnsServer, Dns, DnsDialFields, DnsRejectAction, DnsRejectMethod, DnsRouteAction,
+    DnsRuleAction, DnsServer, FakeIPDnsServer, LegacyDnsServer, LegacyFakeIP, LocalDnsServer,
+    RemoteDnsServer,
+};
+use crate::singboxconfig::types::SingBoxVersion;
+
+/// 将 RCode 名称映射为 legacy `rcode://` 地址中使用的名称
+fn legacy_rcode(rcode: &str) -> Option<&'static str> {
+    match rcode.to_ascii_uppercase().as_str() {
+        "NOERROR" => Some("success"),
+        "FORMERR" => Some("format_error"),
+        "SERVFAIL" => Some("server_failure"),
+        "NXDOMAIN" => Some("name_error"),
+        "NOTIMP" => Some("not_implemented"),
+        "REFUSED" => Some("refused"),
+        _ => None,
+    }
+}
+
+/// 将 legacy `rcode://` 地址中的名称映射回 RCode 名称（[`legacy_rcode`] 的逆映射）
+fn modern_rcode(name: &str) -> Option<&'static str> {
+    [
+        "NOERROR", "FORMERR", "SERVFAIL", "NXDOMAIN", "NOTIMP", "REFUSED",
+    ]
+    .into_iter()
+    .find(|rcode| legacy_rcode(rcode) == Some(name))
+}
+
+/// 拆分 legacy 地址中的 `host[:port]`（IPv6 地址可带方括号）
+fn split_host_port(authority: &str) -> Result<(String, Option<u16>), String> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest
+            .split_once(']')
+            .ok_or_else(|| format!("DNS 服务器地址无效: {}", authority))?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.split_once(':') {
+            // 不带方括号的 IPv6 地址不含端口
+            Some((host, port)) if !port.contains(':') => (host, Some(port)),
+            _ => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return Err(format!("DNS 服务器地址无效: {}", authority));
+    }
+    let port = port
+        .map(|p| {
+            p.parse::<u16>()
+                .map_err(|_| format!("DNS 服务器端口无效: {}", authority))
+        })
+        .transpose()?;
+    Ok((host.to_string(), port))
+}
+
+/// 拼接远程服务器的 legacy 地址（如 `https://1.1.1.1/dns-query`）
+fn remote_address(scheme: &str, server: &RemoteDnsServer) -> Result<String, String> {
+    let host = server
+        .server
+        .as_deref()
+        .ok_or_else(|| format!("{} DNS 服务器缺少 server 字段", scheme))?;
+    let mut address = if host.contains(':') {
+        format!("{}://[{}]", scheme, host)
+    } else {
+        format!("{}://{}", scheme, host)
+    };
+    if let Some(port) = server.server_port {
+        address.push_str(&format!(":{}", port));
+    }
+    if matches!(scheme, "https" | "h3") {
+        address.push_str(server.path.as_deref().unwrap_or("/dns-query"));
+    }
+    Ok(address)
+}
+
+impl DnsServer {
+    /// 转换为 sing-box 1.12 之前使用的 legacy 服务器
+    pub fn to_legacy(&self) -> Result<LegacyDnsServer, String> {
+        let remote = |scheme: &str, s: &RemoteDnsServer| -> Result<LegacyDnsServer, String> {
+            let dial = s.dial.as_ref();
+            Ok(LegacyDnsServer {
+                tag: s.tag.clone(),
+                address: Some(remote_address(scheme, s)?),
+                address_resolver: dial
+                    .and_then(|d| d.domain_resolver.clone())
+                    .or_else(|| s.address_resolver.clone()),
+                address_strategy: s.address_strategy.clone(),
+                address_fallback_delay: None,
+                strategy: s.strategy.clone(),
+                detour: dial.and_then(|d| d.detour.clone()),
+                client_subnet: s.client_subnet.clone(),
+            })
+        };
+        let simple =
+            |tag: &Option<String>, address: String, detour: Option<String>| LegacyDnsServer {
+                tag: tag.clone(),
+                address: Some(address),
+                detour,
+                ..Default::default()
+            };
+
+        match self {
+            DnsServer::Udp(s) => remote("udp", s),
+            DnsServer::Tcp(s) => remote("tcp", s),
+            DnsServer::Tls(s) => remote("tls", s),
+            DnsServer::Quic(s) => remote("quic", s),
+            DnsServer::Https(s) => remote("https", s),
+            DnsServer::H3(s) => remote("h3", s),
+            DnsServer::Local(s) => Ok(simple(
+                &s.tag,
+                "local".to_string(),
+                s.dial.as_ref().and_then(|d| d.detour.clone()),
+            )),
+            DnsServer::Dhcp(s) => Ok(simple(
+                &s.tag,
+                format!("dhcp://{}", s.interface_name.as_deref().unwrap_or("auto")),
+                s.dial.as_ref().and_then(|d| d.detour.clone()),
+            )),
+            DnsServer::FakeIP(s) => Ok(simple(&s.tag, "fakeip".to_string(), None)),
+            DnsServer::Hosts(_) => Err("hosts DNS 服务器需要 sing-box 1.12 及以上版本".to_string()),
+            DnsServer::Tailscale(_) => {
+                Err("tailscale DNS 服务器需要 sing-box 1.12 及以上版本".to_string())
+            }
+            DnsServer::Resolved(_) => {
+                Err("resolved DNS 服务器需要 sing-box 1.12 及以上版本".to_string())
+            }
+            DnsServer::Legacy(s) => Ok(s.clone()),
+        }
+    }
+}
+
+impl LegacyDnsServer {
+    /// 转换为 sing-box 1.12 的类型化服务器（[`DnsServer::to_legacy`] 的逆操作）
+    ///
+    /// `address_resolver` 与 `detour` 改写为拨号字段；`address_strategy`、`strategy`、
+    /// `client_subnet` 与 `address_fallback_delay` 在类型化服务器中没有对应字段，不予保留。
+    /// `fakeip` 为顶层 `dns.fakeip`，提供 fakeip 服务器的地址范围；`rcode://` 服务器没有对应的类型，返回错误
+    pub fn to_typed(&self, fakeip: Option<&LegacyFakeIP>) -> Result<DnsServer, String> {
+        let address = self.address.as_deref().ok_or_else(|| {
+            format!(
+                "DNS 服务器 {} 缺少 address 字段",
+                self.tag.as_deref().unwrap_or_default()
+            )
+        })?;
+        let tag = self.tag.clone();
+        let dial =
+            (self.detour.is_some() || self.address_resolver.is_some()).then(|| DnsDialFields {
+                detour: self.detour.clone(),
+                domain_resolver: self.address_resolver.clone(),
+                ..Default::default()
+            });
+        match address {
+            "local" => {
+                return Ok(DnsServer::Local(LocalDnsServer {
+                    tag,
+                    prefer_go: None,
+                    dial,
+                }));
+            }
+            "fakeip" => {
+                return Ok(DnsServer::FakeIP(FakeIPDnsServer {
+                    tag,
+                    inet4_range: fakeip.and_then(|f| f.inet4_range.clone()),
+                    inet6_range: fakeip.and_then(|f| f.inet6_range.clone()),
+                }));
+            }
+            _ => {}
+        }
+
+        // 不带协议的地址为 UDP 服务器
+        let (scheme, rest) = address.split_once("://").unwrap_or(("udp", address));
+        match scheme {
+            "dhcp" => Ok(DnsServer::Dhcp(DhcpDnsServer {
+                tag,
+                interface_name: Some(rest)
+                    .filter(|name| !name.is_empty() && *name != "auto")
+                    .map(str::to_string),
+                dial,
+            })),
+            "rcode" => Err(format!(
+                "{} 没有对应的类型化服务器，请改用 reject / predefined 规则动作",
+                address
+            )),
+            "udp" | "tcp" | "tls" | "quic" | "https" | "h3" => {
+                let (authority, path) = match rest.find('/') {
+                    Some(i) => (&rest[..i], Some(&rest[i..])),
+                    None => (rest, None),
+                };
+                let (host, port) = split_host_port(authority)?;
+                let mut server = RemoteDnsServer::new().server(host);
+                server.tag = tag;
+                server.server_port = port;
+                server.dial = dial;
+                if matches!(scheme, "https" | "h3") {
+                    server.path = path.filter(|p| *p != "/dns-query").map(str::to_string);
+                }
+                Ok(match scheme {
+                    "udp" => DnsServer::Udp(server),
+                    "tcp" => DnsServer::Tcp(server),
+                    "tls" => DnsServer::Tls(server),
+                    "quic" => DnsServer::Quic(server),
+                    "https" => DnsServer::Https(server),
+                    _ => DnsServer::H3(server),
+                })
+            }
+            _ => Err(format!("无法识别的 DNS 服务器地址: {}", address)),
+        }
+    }
+}
+
+impl Dns {
+    /// 将旧版 DNS 配置改写为 sing-box 1.12 的格式（[`Dns::to_legacy_value`] 的逆操作）
+    ///
+    /// - legacy 服务器改写为类型化服务器，`dns.fakeip` 的地址范围并入 fakeip 服务器
+    /// - 规则上的 `server`、`disable_cache` 等字段改写为 route 动作；
+    ///   指向 `rcode://` 服务器的规则改写为 reject（refused）或 predefined 动作，这些服务器随之移除
+    pub fn to_typed(&self) -> Result<Dns, String> {
+        let mut rcodes = BTreeMap::new();
+        let mut servers = Vec::new();
+        for server in self.servers.iter().flatten() {
+            let DnsServer::Legacy(legacy) = server else {
+                servers.push(server.clone());
+                continue;
+            };
+            if let Some(name) = legacy
+                .address
+                .as_deref()
+                .and_then(|a| a.strip_prefix("rcode://"))
+            {
+                let rcode =
+                    modern_rcode(name).ok_or_else(|| format!("无法识别的 RCode: {}", name))?;
+                rcodes.insert(legacy.tag.clone().unwrap_or_default(), rcode);
+                continue;
+            }
+            servers.push(legacy.to_typed(self.fakeip.as_ref())?);
+        }
+        if let Some(tag) = self
+            .final_server
+            .as_deref()
+            .filter(|tag| rcodes.contains_key(*tag))
+        {
+            return Err(format!(
+                "dns.final 指向 rcode 服务器 {}，新格式中没有对应写法",
+                tag
+            ));
+        }
+
+        let mut rules = self.rules.clone();
+        for rule in rules.iter_mut().flatten() {
+            let fields = rule.action_fields();
+            if fields.action.is_none() && fields.server.is_none() {
+                continue;
+            }
+            let mut route = match fields.action.take() {
+                None => DnsRouteAction::default(),
+                Some(DnsRuleAction::Route(action)) => action,
+                Some(other) => {
+                    *fields.action = Some(other);
+                    continue;
+                }
+            };
+            // 没有动作的旧版规则：server 等字段移入 route 动作
+            route.server = route.server.or_else(|| fields.server.take());
+            route.disable_cache = route.disable_cache.or(fields.disable_cache.take());
+            route.rewrite_ttl = route.rewrite_ttl.or(fields.rewrite_ttl.take());
+            route.client_subnet = route.client_subnet.or_else(|| fields.client_subnet.take());
+            *fields.action = Some(match route.server.as_deref().and_then(|s| rcodes.get(s)) {
+                Some(&"REFUSED") => DnsRuleAction::Reject(DnsRejectAction::default()),
+                Some(rcode) => DnsRuleAction::predefined_rcode(*rcode),
+                None => DnsRuleAction::Route(route),
+            });
+        }
+
+        let mut dns = self.clone();
+        dns.servers = self.servers.as_ref().map(|_| servers);
+        dns.rules = rules;
+        dns.fakeip = None;
+        Ok(dns)
+    }
+
+    /// 转换为指定 sing-box 版本（1.12 之前）可识别的 DNS 配置
+    ///
+    /// - 服务器改写为 `address` 格式，FakeIP 服务器改写为顶层 `fakeip` 配置
+    /// - 版本低于 1.11 时，规则动作改写为 `server` 字段，拒绝 / 预定义响应改写为 `rcode://` 服务器
+    pub fn to_legacy_value(&self, version: SingBoxVersion) -> Result<Value, String> {
+        let mut servers = Vec::new();
+        let mut fakeip = self.fakeip.clone();
+        for server in self.servers.iter().flatten() {
+            if let DnsServer::FakeIP(f) = server {
+                fakeip = Some(LegacyFakeIP {
+                    enabled: Some(true),
+                    inet4_range: f.inet4_range.clone(),
+                    inet6_range: f.inet6_range.clone(),
+                });
+            }
+            servers.push(server.to_legacy()?);
+        }
+
+        let mut rules = self.rules.clone().unwrap_or_default();
+        if !version.supports_rule_actions() {
+            for rule in rules.iter_mut() {
+                let fields = rule.action_fields();
+                let target = match fields.action.take() {
+                    None => continue,
+                    Some(DnsRuleAction::Route(action)) => {
+                        *fields.disable_cache = action.disable_cache;
+                        *fields.rewrite_ttl = action.rewrite_ttl;
+                        *fields.client_subnet = action.client_subnet;
+                        action
+                            .server
+                            .ok_or_else(|| "DNS route 动作缺少 server 字段".to_string())?
+                    }
+                    Some(DnsRuleAction::Reject(action)) => {
+                        if action.method == Some(DnsRejectMethod::Drop) {
+                            return Err(format!(
+                                "DNS 拒绝方式 drop 需要 sing-box 1.11 及以上版本（当前目标 {}）",
+                                version
+                            ));
+                        }
+                        rcode_server(&mut servers, "refused")
+                    }
+                    Some(DnsRuleAction::Predefined(action)) => {
+                        let rcode = action
+                            .rcode
+                            .as_deref()
+                            .filter(|_| action.answer.is_none())
+                            .and_then(legacy_rcode)
+                            .ok_or_else(|| {
+                                format!(
+                                    "该 DNS 预定义响应需要 sing-box 1.11 及以上版本（当前目标 {}）",
+                                    version
+                                )
+                            })?;
+                        rcode_server(&mut servers, rcode)
+                    }
+                    Some(DnsRuleAction::RouteOptions(_)) => {
+                        return Err(format!(
+                            "DNS route-options 动作需要 sing-box 1.11 及以上版本（当前目标 {}）",
+                            version
+                        ));
+                    }
+                };
+                *fields.server = Some(target);
+            }
+        }
+
+        let mut legacy = self.clone();
+        legacy.servers = None;
+        legacy.rules = None;
+        legacy.fakeip = fakeip;
+        if !version.supports_rule_actions() {
+            // cache_capacity 自 1.11.0 起可用
+            legacy.cache_capacity = None;
+        }
+
+        let mut value = serde_json::to_value(&legacy).map_err(|e| e.to_string())?;
+        value["servers"] = serde_json::to_value(&servers).map_err(|e| e.to_string())?;
+        if !rules.is_empty() {
+            value["rules"] = serde_json::to_value(&rules).map_err(|e| e.to_string())?;
+        }
+        Ok(value)
+    }
+}
+
+/// 返回指定 RCode 的 legacy 服务器标签，不存在时追加该服务器
+fn rcode_server(servers: &mut Vec<LegacyDnsServer>, rcode: &str) -> String {
+    let tag = format!("rcode-{}", rcode);
+    if !servers
+        .iter()
+        .any(|s| s.tag.as_deref() == Some(tag.as_str()))
+    {
+        servers.push(LegacyDnsServer {
+            tag: Some(tag.clone()),
+            address: Some(format!("rcode://{}", rcode)),
+            ..Default::default()
+        });
+    }
+    tag
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns::{ClientDnsBuilder, ClientDnsMode, DefaultDnsRule, DnsRule, ServerDnsBuilder};
+    use serde_json::json;
+
+    #[test]
+    fn test_server_to_legacy() {
+        let server = DnsServer::Https(
+            RemoteDnsServer::https("dns.google")
+                .tag("google")
+                .domain_resolver("local")
+                .detour("proxy"),
+        );
+        assert_eq!(
+            serde_json::to_value(server.to_legacy().unwrap()).unwrap(),
+            json!({
+                "tag": "google",
+                "address": "https://dns.google:443/dns-query",
+                "address_resolver": "local",
+                "detour": "proxy"
+            })
+        );
+
+        let server = DnsServer::Tls(RemoteDnsServer::new().tag("dot").server("2606:4700::1111"));
+        assert_eq!(
+            server.to_legacy().unwrap().address.as_deref(),
+            Some("tls://[2606:4700::1111]")
+        );
+        assert!(DnsServer::Resolved(Default::default()).to_legacy().is_err());
+    }
+
+    #[test]
+    fn test_dns_to_legacy_value() {
+        let v1_10 = SingBoxVersion::new(1, 10);
+        let dns = ClientDnsBuilder::new()
+            .mode(ClientDnsMode::FakeIp)
+            .build()
+            .dns
+            .add_rule(DnsRule::Default(
+                DefaultDnsRule::new()
+                    .domain_suffix("ads.example.com")
+                    .action(DnsRuleAction::predefined_rcode("NXDOMAIN")),
+            ));
+        // 生成配置时 DNS 以 JSON 形式传递，转换前先经过一次序列化往返
+        let dns: Dns = serde_json::from_value(serde_json::to_value(&dns).unwrap()).unwrap();
+        let value = dns.to_legacy_value(v1_10).unwrap();
+        assert_eq!(value["servers"][0]["address"], "local");
+        assert_eq!(value["servers"][1]["address"], "https://1.1.1.1/dns-query");
+        assert_eq!(value["servers"][1]["detour"], "proxy");
+        assert_eq!(value["servers"][2]["address"], "fakeip");
+        assert_eq!(value["servers"][3]["address"], "rcode://name_error");
+        assert_eq!(value["fakeip"]["enabled"], true);
+        assert_eq!(value["rules"][0]["server"], "local");
+        assert!(value["rules"][0].get("action").is_none());
+        assert_eq!(value["rules"][1]["server"], "fakeip");
+        assert_eq!(value["rules"][2]["server"], "rcode-name_error");
+
+        // 1.11 保留规则动作
+        let value = dns.to_legacy_value(SingBoxVersion::new(1, 11)).unwrap();
+        assert_eq!(value["rules"][0]["action"], "route");
+        assert_eq!(value["servers"].as_array().unwrap().len(), 3);
+
+        let value = ServerDnsBuilder::new()
+            .build()
+            .to_legacy_value(v1_10)
+            .unwrap();
+        assert!(value.get("cache_capacity").is_none());
+        assert!(value.get("rules").is_none());
+
+        let dns = Dns::new().add_rule(DnsRule::Default(
+            DefaultDnsRule::new().action(DnsRuleAction::reject(DnsRejectMethod::Drop)),
+        ));
+        assert!(dns.to_legacy_value(v1_10).is_err());
+
+        // 逻辑规则的动作同样改写为 server 字段
+        let dns: Dns = serde_json::from_value(json!({
+            "rules": [{
+                "type": "logical",
+                "mode": "or",
+                "rules": [{ "domain": ["a.com"] }, { "domain_suffix": ["b.com"] }],
+                "action": "predefined",
+                "rcode": "NXDOMAIN"
+            }]
+        }))
+        .unwrap();
+        let value = dns.to_legacy_value(v1_10).unwrap();
+        assert_eq!(value["rules"][0]["type"], "logical");
+        assert_eq!(value["rules"][0]["server"], "rcode-name_error");
+        assert!(value["rules"][0].get("action").is_none());
+    }
+
+    #[test]
+    fn test_legacy_to_typed() {
+        let dns: Dns = serde_json::from_value(json!({
+            "servers": [
+                { "tag": "google", "address": "https://dns.google/dns-query", "address_resolver": "local", "detour": "proxy" },
+                { "tag": "dot", "address": "tls://[2606:4700::1111]:853" },
+                { "tag": "plain", "address": "223.5.5.5" },
+                { "tag": "local", "address": "local" },
+                { "tag": "dhcp", "address": "dhcp://auto" },
+                { "tag": "fakeip", "address": "fakeip" },
+                { "tag": "block", "address": "rcode://refused" },
+                { "tag": "nx", "address": "rcode://name_error" }
+            ],
+            "rules": [
+                { "domain_suffix": ["cn"], "server": "local", "disable_cache": true },
+                { "query_type": ["A"], "server": "fakeip" },
+                { "domain": ["ads.example.com"], "server": "block" },
+                { "domain": ["x.example.com"], "server": "nx" },
+                { "domain": ["y.example.com"], "action": "route", "server": "dot" }
+            ],
+            "final": "google",
+            "fakeip": { "enabled": true, "inet4_range": "198.18.0.0/15" }
+        }))
+        .unwrap();
+        let value = serde_json::to_value(dns.to_typed().unwrap()).unwrap();
+        assert_eq!(
+            value["servers"],
+            json!([
+                { "type": "https", "tag": "google", "server": "dns.google", "domain_resolver": "local", "detour": "proxy" },
+                { "type": "tls", "tag": "dot", "server": "2606:4700::1111", "server_port": 853 },
+                { "type": "udp", "tag": "plain", "server": "223.5.5.5" },
+                { "type": "local", "tag": "local" },
+                { "type": "dhcp", "tag": "dhcp" },
+                { "type": "fakeip", "tag": "fakeip", "inet4_range": "198.18.0.0/15" }
+            ])
+        );
+        assert_eq!(
+            value["rules"],
+            json!([
+                { "domain_suffix": ["cn"], "action": "route", "server": "local", "disable_cache": true },
+                { "query_type": ["A"], "action": "route", "server": "fakeip" },
+                { "domain": ["ads.example.com"], "action": "reject" },
+                { "domain": ["x.example.com"], "action": "predefined", "rcode": "NXDOMAIN" },
+                { "domain": ["y.example.com"], "action": "route", "server": "dot" }
+            ])
+        );
+        assert!(value.get("fakeip").is_none());
+
+        // 与 to_legacy 互逆
+        let legacy = DnsServer::Https(RemoteDnsServer::https("1.1.1.1").tag("cf"))
+            .to_legacy()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(legacy.to_typed(None).unwrap()).unwrap(),
+            json!({ "type": "https", "tag": "cf", "server": "1.1.1.1", "server_port": 443 })
+        );
+        let bad = LegacyDnsServer {
+            address: Some("sdns://abc".to_string()),
+            ..Default::default()
+        };
+        assert!(bad.to_typed(None).is_err());
+        let dns: Dns = serde_json::from_value(json!({
+            "servers": [{ "tag": "block", "address": "rcode://refused" }],
+            "final": "block"
+        }))
+        .unwrap();
+        assert!(dns.to_typed().is_err());
+    }
+}
@@ -4,5 +4,8 @@
 //! 参考文档: https://sing-box.sagernet.org/configuration/dns/
 
 mod dns;
+mod legacy;
+mod preset;
 
 pub use dns::*;
+pub use preset::*;
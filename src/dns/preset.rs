@@ -0,0 +1,634 @@
+//! DNS 预设配置
+//!
+//! 提供生成配置时使用的开箱即用 DNS 方案：
+//! - 服务端：本地解析器 + 加密上游，并启用缓存
+//! - 客户端：经代理的加密上游 + 直连流量使用的本地解析器，可选 FakeIP
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use super::dns::{
+    DefaultDnsRule, Dns, DnsRejectMethod, DnsRule, DnsRuleAction, DnsServer, FakeIPDnsServer,
+    LocalDnsServer, RemoteDnsServer,
+};
+use crate::singboxconfig::outbound::DirectOutbound;
+use crate::singboxconfig::route::{RouteRule, RuleAction, RuleSet};
+use crate::singboxconfig::types::DomainStrategy;
+
+/// 本地解析器标签
+pub const LOCAL_DNS_TAG: &str = "local";
+
+/// 远程上游标签
+pub const REMOTE_DNS_TAG: &str = "remote";
+
+/// FakeIP 服务器标签
+pub const FAKEIP_DNS_TAG: &str = "fakeip";
+
+/// FakeIP IPv4 地址范围
+pub const FAKEIP_INET4_RANGE: &str = "198.18.0.0/15";
+
+/// FakeIP IPv6 地址范围
+pub const FAKEIP_INET6_RANGE: &str = "fc00::/18";
+
+/// 局域网域名后缀（始终使用本地解析、直连）
+const PRIVATE_DOMAIN_SUFFIXES: &[&str] = &["lan", "local", "localhost", "home.arpa"];
+
+/// 默认 DNS 缓存容量
+pub const DEFAULT_DNS_CACHE_CAPACITY: u32 = 4096;
+
+/// 内置上游预设（名称、DoH 服务器地址）
+const UPSTREAM_PRESETS: &[(&str, &str)] = &[
+    ("cloudflare", "1.1.1.1"),
+    ("google", "8.8.8.8"),
+    ("quad9", "9.9.9.9"),
+    ("alidns", "223.5.5.5"),
+];
+
+// ============================================================================
+// 上游服务器
+// ============================================================================
+
+/// 上游 DNS 传输协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsProtocol {
+    /// 明文 UDP
+    Udp,
+    /// 明文 TCP
+    Tcp,
+    /// DNS over TLS
+    Tls,
+    /// DNS over QUIC
+    Quic,
+    /// DNS over HTTPS
+    Https,
+    /// DNS over HTTP/3
+    H3,
+}
+
+impl DnsProtocol {
+    /// URL scheme
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            DnsProtocol::Udp => "udp",
+            DnsProtocol::Tcp => "tcp",
+            DnsProtocol::Tls => "tls",
+            DnsProtocol::Quic => "quic",
+            DnsProtocol::Https => "https",
+            DnsProtocol::H3 => "h3",
+        }
+    }
+
+    fn from_scheme(scheme: &str) -> Option<Self> {
+        match scheme.to_ascii_lowercase().as_str() {
+            "udp" => Some(DnsProtocol::Udp),
+            "tcp" => Some(DnsProtocol::Tcp),
+            "tls" => Some(DnsProtocol::Tls),
+            "quic" => Some(DnsProtocol::Quic),
+            "https" => Some(DnsProtocol::Https),
+            "h3" => Some(DnsProtocol::H3),
+            _ => None,
+        }
+    }
+
+    /// 是否基于 HTTP（支持请求路径）
+    fn is_http(&self) -> bool {
+        matches!(self, DnsProtocol::Https | DnsProtocol::H3)
+    }
+}
+
+/// 上游 DNS 服务器
+///
+/// 支持的写法：
+/// - 预设名称: `cloudflare`、`google`、`quad9`、`alidns`（均为 DoH）
+/// - URL: `https://dns.google/dns-query`、`tls://1.1.1.1`、`quic://dns.adguard-dns.com`、`udp://8.8.8.8:53`
+/// - 裸 IP: `8.8.8.8`（UDP）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsUpstream {
+    /// 传输协议
+    pub protocol: DnsProtocol,
+    /// 服务器地址（IP 或域名）
+    pub server: String,
+    /// 服务器端口（为空时使用协议默认端口）
+    pub port: Option<u16>,
+    /// HTTP 请求路径（仅 HTTPS / H3）
+    pub path: Option<String>,
+}
+
+impl DnsUpstream {
+    /// 使用 DoH 的上游
+    pub fn https<S: Into<String>>(server: S) -> Self {
+        Self {
+            protocol: DnsProtocol::Https,
+            server: server.into(),
+            port: None,
+            path: None,
+        }
+    }
+
+    /// 服务器地址是否为域名（需要先由其他 DNS 服务器解析）
+    pub fn needs_resolver(&self) -> bool {
+        self.server.parse::<IpAddr>().is_err()
+    }
+
+    /// 转换为 sing-box DNS 服务器
+    /// 服务器地址为域名时使用 `resolver` 解析，指定 `detour` 时经该出站查询
+    pub fn to_server(&self, tag: &str, resolver: &str, detour: Option<&str>) -> DnsServer {
+        let mut server = RemoteDnsServer::new().tag(tag).server(self.server.as_str());
+        if let Some(detour) = detour {
+            server = server.detour(detour);
+        }
+        if let Some(port) = self.port {
+            server = server.server_port(port);
+        }
+        if let Some(ref path) = self.path {
+            server = server.path(path.as_str());
+        }
+        if self.needs_resolver() {
+            server = server.domain_resolver(resolver);
+        }
+        match self.protocol {
+            DnsProtocol::Udp => DnsServer::Udp(server),
+            DnsProtocol::Tcp => DnsServer::Tcp(server),
+            DnsProtocol::Tls => DnsServer::Tls(server),
+            DnsProtocol::Quic => DnsServer::Quic(server),
+            DnsProtocol::Https => DnsServer::Https(server),
+            DnsProtocol::H3 => DnsServer::H3(server),
+        }
+    }
+}
+
+impl Default for DnsUpstream {
+    fn default() -> Self {
+        Self::https(UPSTREAM_PRESETS[0].1)
+    }
+}
+
+impl fmt::Display for DnsUpstream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://", self.protocol.scheme())?;
+        if self.server.contains(':') {
+            write!(f, "[{}]", self.server)?;
+        } else {
+            write!(f, "{}", self.server)?;
+        }
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        if let Some(ref path) = self.path {
+            write!(f, "{}", path)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DnsUpstream {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, ip)) = UPSTREAM_PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return Ok(Self::https(*ip));
+        }
+
+        let invalid = || format!("无效的 DNS 上游: {}", s);
+        let (protocol, rest) = match s.split_once("://") {
+            Some((scheme, rest)) => (
+                DnsProtocol::from_scheme(scheme)
+                    .ok_or_else(|| format!("不支持的 DNS 上游协议: {}", scheme))?,
+                rest,
+            ),
+            None if s.parse::<IpAddr>().is_ok() => {
+                return Ok(Self {
+                    protocol: DnsProtocol::Udp,
+                    server: s.to_string(),
+                    port: None,
+                    path: None,
+                });
+            }
+            None => return Err(invalid()),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], Some(&rest[i..])),
+            None => (rest, None),
+        };
+        if path.is_some() && !protocol.is_http() {
+            return Err(format!("{} 上游不支持请求路径: {}", protocol.scheme(), s));
+        }
+
+        // [IPv6]:port / host:port / host
+        let (server, port) = if let Some(inner) = authority.strip_prefix('[') {
+            let (host, tail) = inner.split_once(']').ok_or_else(invalid)?;
+            let port = match tail {
+                "" => None,
+                t => Some(t.strip_prefix(':').ok_or_else(invalid)?),
+            };
+            (host, port)
+        } else if authority.matches(':').count() == 1 {
+            let (host, port) = authority.split_once(':').ok_or_else(invalid)?;
+            (host, Some(port))
+        } else {
+            (authority, None)
+        };
+        if server.is_empty() {
+            return Err(invalid());
+        }
+        let port = port
+            .map(|p| p.parse::<u16>().map_err(|_| invalid()))
+            .transpose()?;
+
+        Ok(Self {
+            protocol,
+            server: server.to_string(),
+            port,
+            path: path.filter(|p| *p != "/").map(str::to_string),
+        })
+    }
+}
+
+// ============================================================================
+// 服务端 DNS
+// ============================================================================
+
+/// 服务端 DNS 配置构建器
+///
+/// 生成的配置包含本地解析器（`local`）与加密上游（`remote`），
+/// 默认经由上游解析，并启用缓存
+#[derive(Debug, Clone)]
+pub struct ServerDnsBuilder {
+    upstream: DnsUpstream,
+    strategy: Option<DomainStrategy>,
+    cache_capacity: u32,
+}
+
+impl Default for ServerDnsBuilder {
+    fn default() -> Self {
+        Self {
+            upstream: DnsUpstream::default(),
+            strategy: None,
+            cache_capacity: DEFAULT_DNS_CACHE_CAPACITY,
+        }
+    }
+}
+
+impl ServerDnsBuilder {
+    /// 创建新的构建器（上游默认为 Cloudflare DoH）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置上游服务器
+    pub fn upstream(mut self, upstream: DnsUpstream) -> Self {
+        self.upstream = upstream;
+        self
+    }
+
+    /// 设置域名解析策略
+    pub fn strategy(mut self, strategy: DomainStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// 设置缓存容量
+    pub fn cache_capacity(mut self, capacity: u32) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// 构建 DNS 配置
+    pub fn build(self) -> Dns {
+        let mut dns = Dns::new()
+            .add_server(DnsServer::Local(LocalDnsServer::new().tag(LOCAL_DNS_TAG)))
+            .add_server(self.upstream.to_server(REMOTE_DNS_TAG, LOCAL_DNS_TAG, None))
+            .final_server(REMOTE_DNS_TAG)
+            .cache_capacity(self.cache_capacity);
+        if let Some(strategy) = self.strategy {
+            dns = dns.strategy(strategy);
+        }
+        dns
+    }
+}
+
+// ============================================================================
+// 客户端 DNS
+// ============================================================================
+
+/// 客户端 DNS 方案
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientDnsMode {
+    /// 仅使用经代理的远程上游
+    Simple,
+    /// 远程上游 + 局域网域名走本地解析，并添加防泄漏路由规则
+    #[default]
+    Split,
+    /// 在 split 基础上为 A / AAAA 查询返回 FakeIP
+    FakeIp,
+}
+
+impl ClientDnsMode {
+    /// 环境变量中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientDnsMode::Simple => "simple",
+            ClientDnsMode::Split => "split",
+            ClientDnsMode::FakeIp => "fakeip",
+        }
+    }
+}
+
+impl fmt::Display for ClientDnsMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ClientDnsMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "simple" => Ok(ClientDnsMode::Simple),
+            "split" => Ok(ClientDnsMode::Split),
+            "fakeip" | "fake-ip" => Ok(ClientDnsMode::FakeIp),
+            other => Err(format!(
+                "未知的客户端 DNS 方案: {}（可选 simple / split / fakeip）",
+                other
+            )),
+        }
+    }
+}
+
+/// 客户端 DNS 配置与配套的路由规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientDnsProfile {
+    /// DNS 配置
+    pub dns: Dns,
+    /// 需追加到 `route.rules` 的规则
+    pub route_rules: Vec<RouteRule>,
+    /// 规则引用的规则集，需写入 `route.rule_set`
+    pub rule_sets: Vec<RuleSet>,
+}
+
+/// 客户端 DNS 配置构建器
+///
+/// 远程上游经代理出站查询，本地解析器（`local`）用于解析代理服务器地址与直连流量，
+/// 因此生成配置时应将 `route.default_domain_resolver` 指向 [`LOCAL_DNS_TAG`]
+#[derive(Debug, Clone)]
+pub struct ClientDnsBuilder {
+    mode: ClientDnsMode,
+    upstream: DnsUpstream,
+    proxy_tag: String,
+    direct_tag: String,
+    block_rule_sets: Vec<RuleSet>,
+}
+
+impl Default for ClientDnsBuilder {
+    fn default() -> Self {
+        Self {
+            mode: ClientDnsMode::default(),
+            upstream: DnsUpstream::default(),
+            proxy_tag: "proxy".to_string(),
+            direct_tag: DirectOutbound::DEFAULT_TAG.to_string(),
+            block_rule_sets: Vec::new(),
+        }
+    }
+}
+
+impl ClientDnsBuilder {
+    /// 创建新的构建器（split 方案，上游为经代理的 Cloudflare DoH）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置 DNS 方案
+    pub fn mode(mut self, mode: ClientDnsMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 设置远程上游
+    pub fn upstream(mut self, upstream: DnsUpstream) -> Self {
+        self.upstream = upstream;
+        self
+    }
+
+    /// 设置代理出站标签
+    pub fn proxy_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.proxy_tag = tag.into();
+        self
+    }
+
+    /// 设置直连出站标签
+    pub fn direct_tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.direct_tag = tag.into();
+        self
+    }
+
+    /// 屏蔽匹配规则集的域名（如广告 / 跟踪域名）：DNS 查询直接拒绝，连接同样拒绝
+    pub fn block_rule_sets(mut self, rule_sets: Vec<RuleSet>) -> Self {
+        self.block_rule_sets = rule_sets;
+        self
+    }
+
+    /// 构建 DNS 配置与路由规则
+    pub fn build(self) -> ClientDnsProfile {
+        let mut dns = Dns::new()
+            .add_server(DnsServer::Local(LocalDnsServer::new().tag(LOCAL_DNS_TAG)))
+            .add_server(self.upstream.to_server(
+                REMOTE_DNS_TAG,
+                LOCAL_DNS_TAG,
+                Some(&self.proxy_tag),
+            ))
+            .final_server(REMOTE_DNS_TAG);
+        // 屏蔽规则必须位于 FakeIP 规则之前，否则 A / AAAA 查询会先得到假地址
+        let block_tags: Vec<String> = self.block_rule_sets.iter().map(|r| r.tag.clone()).collect();
+        let mut block_rules = Vec::new();
+        if !block_tags.is_empty() {
+            dns = dns.add_rule(DnsRule::Default(
+                DefaultDnsRule::new()
+                    .rule_sets(block_tags.iter().cloned())
+                    .action(DnsRuleAction::reject(DnsRejectMethod::Default)),
+            ));
+            block_rules.push(
+                RouteRule::new()
+                    .with_rule_sets(block_tags)
+                    .with_action(RuleAction::reject()),
+            );
+        }
+        if self.mode == ClientDnsMode::Simple {
+            return ClientDnsProfile {
+                dns,
+                route_rules: block_rules,
+                rule_sets: self.block_rule_sets,
+            };
+        }
+
+        dns = dns.add_rule(DnsRule::Default(
+            DefaultDnsRule::new()
+                .domain_suffixes(PRIVATE_DOMAIN_SUFFIXES.iter().copied())
+                .action(DnsRuleAction::route(LOCAL_DNS_TAG)),
+        ));
+        if self.mode == ClientDnsMode::FakeIp {
+            dns = dns
+                .add_server(DnsServer::FakeIP(
+                    FakeIPDnsServer::new()
+                        .tag(FAKEIP_DNS_TAG)
+                        .inet4_range(FAKEIP_INET4_RANGE)
+                        .inet6_range(FAKEIP_INET6_RANGE),
+                ))
+                .add_rule(DnsRule::Default(
+                    DefaultDnsRule::new()
+                        .query_types(["A", "AAAA"])
+                        .action(DnsRuleAction::route(FAKEIP_DNS_TAG)),
+                ));
+        }
+
+        // 防泄漏：嗅探后劫持所有 DNS 请求交由上面的服务器处理，局域网流量直连
+        let mut route_rules = vec![
+            RouteRule::new().with_action(RuleAction::sniff()),
+            RouteRule::new()
+                .with_protocols(["dns"])
+                .with_action(RuleAction::HijackDns),
+        ];
+        route_rules.extend(block_rules);
+        route_rules.push(
+            RouteRule::new()
+                .with_ip_is_private()
+                .with_action(RuleAction::route(self.direct_tag.as_str())),
+        );
+        route_rules.push(
+            RouteRule::new()
+                .with_domain_suffixes(PRIVATE_DOMAIN_SUFFIXES.iter().copied())
+                .with_action(RuleAction::route(self.direct_tag.as_str())),
+        );
+        ClientDnsProfile {
+            dns,
+            route_rules,
+            rule_sets: self.block_rule_sets,
+        }
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_upstream() {
+        let up: DnsUpstream = "Google".parse().unwrap();
+        assert_eq!(up, DnsUpstream::https("8.8.8.8"));
+
+        let up: DnsUpstream = "https://dns.example.com:8443/resolve".parse().unwrap();
+        assert_eq!(up.protocol, DnsProtocol::Https);
+        assert_eq!(up.server, "dns.example.com");
+        assert_eq!(up.port, Some(8443));
+        assert_eq!(up.path.as_deref(), Some("/resolve"));
+        assert!(up.needs_resolver());
+
+        let up: DnsUpstream = "tls://[2606:4700:4700::1111]".parse().unwrap();
+        assert_eq!(up.protocol, DnsProtocol::Tls);
+        assert_eq!(up.server, "2606:4700:4700::1111");
+        assert_eq!(up.to_string(), "tls://[2606:4700:4700::1111]");
+
+        let up: DnsUpstream = "8.8.4.4".parse().unwrap();
+        assert_eq!(up.protocol, DnsProtocol::Udp);
+        assert!(!up.needs_resolver());
+
+        assert!("dns.example.com".parse::<DnsUpstream>().is_err());
+        assert!("ftp://1.1.1.1".parse::<DnsUpstream>().is_err());
+        assert!("tls://1.1.1.1/dns-query".parse::<DnsUpstream>().is_err());
+        assert!("udp://1.1.1.1:dns".parse::<DnsUpstream>().is_err());
+    }
+
+    #[test]
+    fn test_server_dns_builder() {
+        let dns = ServerDnsBuilder::new().build();
+        let value = serde_json::to_value(&dns).unwrap();
+        assert_eq!(value["final"], "remote");
+        assert_eq!(value["cache_capacity"], 4096);
+        assert!(value.get("strategy").is_none());
+        assert_eq!(value["servers"][0]["type"], "local");
+        assert_eq!(value["servers"][1]["type"], "https");
+        assert_eq!(value["servers"][1]["server"], "1.1.1.1");
+        assert!(value["servers"][1].get("domain_resolver").is_none());
+
+        let dns = ServerDnsBuilder::new()
+            .upstream("quic://dns.adguard-dns.com".parse().unwrap())
+            .strategy(DomainStrategy::Ipv4Only)
+            .build();
+        let value = serde_json::to_value(&dns).unwrap();
+        assert_eq!(value["strategy"], "ipv4_only");
+        assert_eq!(value["servers"][1]["type"], "quic");
+        assert_eq!(value["servers"][1]["domain_resolver"], "local");
+    }
+
+    #[test]
+    fn test_client_dns_builder() {
+        assert_eq!("Fake-IP".parse(), Ok(ClientDnsMode::FakeIp));
+        assert!("tun".parse::<ClientDnsMode>().is_err());
+
+        let profile = ClientDnsBuilder::new().mode(ClientDnsMode::Simple).build();
+        let value = serde_json::to_value(&profile.dns).unwrap();
+        assert_eq!(value["final"], "remote");
+        assert_eq!(value["servers"][1]["detour"], "proxy");
+        assert!(value.get("rules").is_none());
+        assert!(profile.route_rules.is_empty());
+
+        let profile = ClientDnsBuilder::new().build();
+        let value = serde_json::to_value(&profile.dns).unwrap();
+        assert_eq!(value["servers"].as_array().unwrap().len(), 2);
+        assert_eq!(value["rules"][0]["server"], "local");
+        assert_eq!(value["rules"][0]["domain_suffix"][0], "lan");
+        assert_eq!(profile.route_rules.len(), 4);
+        let rules = serde_json::to_value(&profile.route_rules).unwrap();
+        assert_eq!(rules[1]["action"], "hijack-dns");
+        assert_eq!(rules[2]["outbound"], "direct");
+
+        let profile = ClientDnsBuilder::new()
+            .mode(ClientDnsMode::FakeIp)
+            .direct_tag("out-direct")
+            .build();
+        let value = serde_json::to_value(&profile.dns).unwrap();
+        assert_eq!(value["servers"][2]["type"], "fakeip");
+        assert_eq!(value["servers"][2]["inet4_range"], "198.18.0.0/15");
+        assert_eq!(
+            value["rules"][1]["query_type"],
+            serde_json::json!(["A", "AAAA"])
+        );
+        assert_eq!(value["rules"][1]["server"], "fakeip");
+        let rules = serde_json::to_value(&profile.route_rules).unwrap();
+        assert_eq!(rules[3]["outbound"], "out-direct");
+    }
+
+    #[test]
+    fn test_client_dns_block_rule_sets() {
+        let ads = RuleSet::geosite("category-ads-all", "https://example.com/geosite");
+        let profile = ClientDnsBuilder::new()
+            .mode(ClientDnsMode::FakeIp)
+            .block_rule_sets(vec![ads.clone()])
+            .build();
+        let value = serde_json::to_value(&profile.dns).unwrap();
+        assert_eq!(value["rules"][0]["rule_set"][0], "geosite-category-ads-all");
+        assert_eq!(value["rules"][0]["action"], "reject");
+        assert_eq!(value["rules"][2]["server"], "fakeip");
+        let rules = serde_json::to_value(&profile.route_rules).unwrap();
+        assert_eq!(rules[2]["rule_set"][0], "geosite-category-ads-all");
+        assert_eq!(rules[2]["action"], "reject");
+        assert_eq!(profile.rule_sets, vec![ads.clone()]);
+
+        let profile = ClientDnsBuilder::new()
+            .mode(ClientDnsMode::Simple)
+            .block_rule_sets(vec![ads])
+            .build();
+        assert_eq!(profile.route_rules.len(), 1);
+        assert_eq!(profile.dns.rules.map(|r| r.len()), Some(1));
+    }
+}
@@ -1,22 +1,103 @@
 //! 环境变量读取工具模块
+//!
+//! 取值优先级：命令行参数 > 环境变量 > 配置文件（ezsingbox.toml / yaml）；
+//! 敏感变量未设置时读取 `<变量名>_FILE` 指向的文件（Docker / Kubernetes secrets）
 
+use std::collections::{BTreeMap, HashMap};
 use std::net::IpAddr;
+use std::sync::OnceLock;
+
+use crate::redact::is_secret_env;
+
+/// 命令行参数覆盖表（键为环境变量名）
+static OVERRIDES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// 配置文件提供的默认值（键为环境变量名）
+static FILE_VALUES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// 设置命令行参数覆盖值（仅首次调用生效）
+pub fn set_overrides(values: HashMap<String, String>) {
+    let _ = OVERRIDES.set(values);
+}
+
+/// 设置配置文件提供的默认值（仅首次调用生效）
+pub fn set_file_values(values: HashMap<String, String>) {
+    let _ = FILE_VALUES.set(values);
+}
+
+/// 读取原始值
+fn lookup(key: &str) -> Option<String> {
+    resolve_layers(key).or_else(|| secret_from_file(key))
+}
+
+/// 按优先级读取各来源中的值
+fn resolve_layers(key: &str) -> Option<String> {
+    resolve(
+        key,
+        OVERRIDES.get(),
+        std::env::var(key).ok(),
+        FILE_VALUES.get(),
+    )
+}
+
+/// 从 `<KEY>_FILE` 指向的文件读取敏感变量（读取失败由变量校验报告）
+fn secret_from_file(key: &str) -> Option<String> {
+    if !is_secret_env(key) {
+        return None;
+    }
+    let path = resolve_layers(&format!("{}_FILE", key))?;
+    read_secret_file(path.trim()).ok()
+}
+
+/// 读取密钥文件（去掉末尾换行）
+pub fn read_secret_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim_end_matches(['\r', '\n']).to_string())
+        .map_err(|e| format!("读取 {} 失败: {}", path, e))
+}
+
+/// 按优先级合并各来源：命令行 > 环境变量 > 配置文件
+fn resolve(
+    key: &str,
+    overrides: Option<&HashMap<String, String>>,
+    env: Option<String>,
+    file: Option<&HashMap<String, String>>,
+) -> Option<String> {
+    overrides
+        .and_then(|m| m.get(key).cloned())
+        .or(env)
+        .or_else(|| file.and_then(|m| m.get(key).cloned()))
+}
+
+/// 获取所有已设置的 ezsingbox 相关变量（合并命令行、环境变量与配置文件）
+pub fn env_snapshot() -> BTreeMap<String, String> {
+    let is_ours = |k: &str| k.starts_with("EZ_") || k == "SING_BOX_BIN";
+    let mut keys: Vec<String> = std::env::vars_os()
+        .filter_map(|(k, _)| k.into_string().ok())
+        .collect();
+    for layer in [OVERRIDES.get(), FILE_VALUES.get()].into_iter().flatten() {
+        keys.extend(layer.keys().cloned());
+    }
+    keys.into_iter()
+        .filter(|k| is_ours(k))
+        .filter_map(|k| lookup(&k).map(|v| (k, v)))
+        .collect()
+}
 
 /// 从环境变量读取布尔值
 pub fn env_bool(key: &str, default: bool) -> bool {
-    match std::env::var(key) {
-        Ok(raw) => {
+    match lookup(key) {
+        Some(raw) => {
             let v = raw.trim().to_ascii_lowercase();
             matches!(v.as_str(), "1" | "true" | "yes" | "y" | "on")
         }
-        Err(_) => default,
+        None => default,
     }
 }
 
 /// 从环境变量读取字符串
 pub fn env_string(key: &str) -> Option<String> {
-    std::env::var(key)
-        .ok()
+    lookup(key)
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
 }
@@ -35,3 +116,50 @@ pub fn env_u32(key: &str) -> Option<u32> {
 pub fn env_ip(key: &str) -> Option<IpAddr> {
     env_string(key).and_then(|s| s.parse::<IpAddr>().ok())
 }
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_precedence() {
+        let cli = HashMap::from([("EZ_DOMAIN".to_string(), "cli.example.com".to_string())]);
+        let file = HashMap::from([
+            ("EZ_DOMAIN".to_string(), "file.example.com".to_string()),
+            ("EZ_USER".to_string(), "file-user".to_string()),
+        ]);
+
+        let env = Some("env.example.com".to_string());
+        assert_eq!(
+            resolve("EZ_DOMAIN", Some(&cli), env.clone(), Some(&file)).as_deref(),
+            Some("cli.example.com")
+        );
+        assert_eq!(
+            resolve("EZ_DOMAIN", None, env, Some(&file)).as_deref(),
+            Some("env.example.com")
+        );
+        assert_eq!(
+            resolve("EZ_DOMAIN", None, None, Some(&file)).as_deref(),
+            Some("file.example.com")
+        );
+        assert_eq!(
+            resolve("EZ_USER", Some(&cli), None, Some(&file)).as_deref(),
+            Some("file-user")
+        );
+        assert_eq!(resolve("EZ_PASSWORD", Some(&cli), None, Some(&file)), None);
+    }
+
+    #[test]
+    fn test_read_secret_file() {
+        let path = std::env::temp_dir().join(format!("ez-secret-{}", std::process::id()));
+        std::fs::write(&path, "s3cret pass\n").unwrap();
+        let value = read_secret_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(value.as_deref(), Ok("s3cret pass"));
+        assert!(read_secret_file("/nonexistent/ez-secret").is_err());
+    }
+}
@@ -0,0 +1,596 @@
+//! 环境变量校验模块
+//!
+//! 定义所有 `EZ_*` 变量的取值类型，启动时校验拼写与取值，
+//! 避免错误的值被静默回退为默认值
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::env::{env_snapshot, read_secret_file};
+use crate::i18n::{Msg, t, tf};
+use crate::listen::parse_listen_list;
+use crate::redact::is_secret_env;
+use ezsingbox::autoconfig::{IpDetector, Protocol};
+use ezsingbox::dns::DnsUpstream;
+use ezsingbox::singboxconfig::shared::AcmeProvider;
+use ezsingbox::singboxconfig::types::{Duration, SingBoxVersion};
+
+//============================================================================
+// 变量定义
+//============================================================================
+
+/// 变量取值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarKind {
+    /// 任意字符串
+    Str,
+    /// 布尔值
+    Bool,
+    /// 16 位无符号整数
+    U16,
+    /// 32 位无符号整数
+    U32,
+    /// IP 地址
+    Ip,
+    /// 套接字地址（ip:port）
+    SocketAddr,
+    /// 逗号分隔的监听地址列表或 `dual` 简写
+    ListenList,
+    /// 单个协议名
+    Protocol,
+    /// 逗号分隔的协议名列表
+    ProtocolList,
+    /// 逗号分隔的公网 IP 探测方式列表
+    IpDetectorList,
+    /// ACME 提供商（letsencrypt / zerossl / 自定义 URL）
+    AcmeProvider,
+    /// 时间间隔（如 3s、1m30s）
+    Duration,
+    /// DNS 上游（预设名称或 URL）
+    DnsUpstream,
+    /// sing-box 版本（如 1.10）
+    Version,
+    /// 敏感变量的密钥文件路径（`<KEY>_FILE`）
+    SecretFile,
+    /// 枚举值之一
+    OneOf(&'static [&'static str]),
+}
+
+impl fmt::Display for VarKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarKind::Str => f.write_str(t(Msg::KindStr)),
+            VarKind::Bool => f.write_str(t(Msg::KindBool)),
+            VarKind::U16 => write!(f, "u16"),
+            VarKind::U32 => write!(f, "u32"),
+            VarKind::Ip => f.write_str(t(Msg::KindIp)),
+            VarKind::SocketAddr => f.write_str(t(Msg::KindSocketAddr)),
+            VarKind::ListenList => f.write_str(t(Msg::KindListenList)),
+            VarKind::Protocol => f.write_str(t(Msg::KindProtocol)),
+            VarKind::ProtocolList => f.write_str(t(Msg::KindProtocolList)),
+            VarKind::IpDetectorList => f.write_str(t(Msg::KindIpDetectorList)),
+            VarKind::AcmeProvider => f.write_str(t(Msg::KindAcmeProvider)),
+            VarKind::Duration => f.write_str(t(Msg::KindDuration)),
+            VarKind::Version => f.write_str(t(Msg::KindVersion)),
+            VarKind::DnsUpstream => f.write_str(t(Msg::KindDnsUpstream)),
+            VarKind::SecretFile => f.write_str(t(Msg::KindSecretFile)),
+            VarKind::OneOf(values) => f.write_str(&tf(Msg::KindOneOf, &[&values.join("/")])),
+        }
+    }
+}
+
+impl VarKind {
+    /// 校验取值是否合法
+    pub fn accepts(&self, raw: &str) -> bool {
+        let v = raw.trim();
+        match self {
+            VarKind::Str => true,
+            VarKind::Bool => matches!(
+                v.to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "y" | "on" | "0" | "false" | "no" | "n" | "off"
+            ),
+            VarKind::U16 => v.parse::<u16>().is_ok(),
+            VarKind::U32 => v.parse::<u32>().is_ok(),
+            VarKind::Ip => v.parse::<IpAddr>().is_ok(),
+            VarKind::SocketAddr => v.parse::<SocketAddr>().is_ok(),
+            VarKind::ListenList => parse_listen_list(v).is_ok(),
+            VarKind::Protocol => v.parse::<Protocol>().is_ok(),
+            VarKind::ProtocolList => v
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .all(|s| s.parse::<Protocol>().is_ok()),
+            VarKind::IpDetectorList => v
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .all(|s| s.parse::<IpDetector>().is_ok()),
+            VarKind::AcmeProvider => v.parse::<AcmeProvider>().is_ok(),
+            VarKind::Duration => v.parse::<Duration>().is_ok(),
+            VarKind::DnsUpstream => v.parse::<DnsUpstream>().is_ok(),
+            VarKind::Version => v.parse::<SingBoxVersion>().is_ok(),
+            VarKind::SecretFile => read_secret_file(v).is_ok(),
+            VarKind::OneOf(values) => values.iter().any(|x| x.eq_ignore_ascii_case(v)),
+        }
+    }
+}
+
+/// 日志级别取值
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "fatal", "panic"];
+
+/// 所有已知变量及其类型
+pub const SCHEMA: &[(&str, VarKind)] = &[
+    ("EZ_FILE", VarKind::Str),
+    ("EZ_CONFIG_PATH", VarKind::Str),
+    ("EZ_CONFIG_BACKUPS", VarKind::U16),
+    ("EZ_ROLLBACK_RELOAD", VarKind::Bool),
+    ("EZ_PUBLIC_IP", VarKind::Ip),
+    ("EZ_IP_DETECTOR", VarKind::IpDetectorList),
+    ("EZ_IP_SERVICES", VarKind::Str),
+    ("EZ_DOMAIN", VarKind::Str),
+    (
+        "EZ_DOMAIN_PROVIDER",
+        VarKind::OneOf(&["sslip", "nip", "duckdns", "custom"]),
+    ),
+    ("EZ_DOMAIN_PATTERN", VarKind::Str),
+    ("EZ_DUCKDNS_DOMAIN", VarKind::Str),
+    ("EZ_DUCKDNS_TOKEN", VarKind::Str),
+    ("EZ_ACME_EMAIL", VarKind::Str),
+    ("EZ_ACME_PROVIDER", VarKind::AcmeProvider),
+    ("EZ_ACME_DATA_DIR", VarKind::Str),
+    ("EZ_ACME_PREFLIGHT", VarKind::Bool),
+    ("EZ_ACME_EAB_KEY_ID", VarKind::Str),
+    ("EZ_ACME_EAB_MAC_KEY", VarKind::Str),
+    (
+        "EZ_ACME_DNS01_PROVIDER",
+        VarKind::OneOf(&["cloudflare", "alidns"]),
+    ),
+    ("EZ_CF_API_TOKEN", VarKind::Str),
+    ("EZ_ALIDNS_ACCESS_KEY_ID", VarKind::Str),
+    ("EZ_ALIDNS_ACCESS_KEY_SECRET", VarKind::Str),
+    ("EZ_ALIDNS_REGION_ID", VarKind::Str),
+    ("EZ_FLEET", VarKind::Str),
+    ("EZ_FLEET_DIR", VarKind::Str),
+    (
+        "EZ_PRESET",
+        VarKind::OneOf(&["minimal", "balanced", "stealth", "full", "cdn"]),
+    ),
+    ("EZ_ENABLE_ANYTLS", VarKind::Bool),
+    ("EZ_ENABLE_HYSTERIA2", VarKind::Bool),
+    ("EZ_ENABLE_TUIC", VarKind::Bool),
+    ("EZ_ENABLE_VLESS_REALITY", VarKind::Bool),
+    ("EZ_ENABLE_VLESS_WS", VarKind::Bool),
+    ("EZ_ENABLE_VLESS_GRPC", VarKind::Bool),
+    ("EZ_ANYTLS_PORT", VarKind::U16),
+    ("EZ_HYSTERIA2_PORT", VarKind::U16),
+    ("EZ_TUIC_PORT", VarKind::U16),
+    ("EZ_VLESS_REALITY_PORT", VarKind::U16),
+    ("EZ_VLESS_WS_PORT", VarKind::U16),
+    ("EZ_VLESS_GRPC_PORT", VarKind::U16),
+    ("EZ_VLESS_WS_PATH", VarKind::Str),
+    ("EZ_VLESS_WS_HOST", VarKind::Str),
+    ("EZ_CDN_HOST", VarKind::Str),
+    ("EZ_VLESS_GRPC_SERVICE", VarKind::Str),
+    ("EZ_USER", VarKind::Str),
+    ("EZ_USERS", VarKind::Str),
+    ("EZ_USERS_JSON", VarKind::Str),
+    ("EZ_PASSWORD", VarKind::Str),
+    ("EZ_ROTATE_INTERVAL", VarKind::Duration),
+    ("EZ_STATE_PATH", VarKind::Str),
+    ("EZ_ROTATION_LOG", VarKind::Str),
+    ("EZ_ROTATE_SHORT_IDS", VarKind::Bool),
+    ("EZ_STATE_ENCRYPTION_KEY", VarKind::Str),
+    ("EZ_HY2_OBFS", VarKind::Bool),
+    (
+        "EZ_HY2_MASQUERADE_TYPE",
+        VarKind::OneOf(&["proxy", "file", "string"]),
+    ),
+    ("EZ_HY2_MASQUERADE_URL", VarKind::Str),
+    ("EZ_HY2_MASQUERADE_DIR", VarKind::Str),
+    ("EZ_HY2_MASQUERADE_STATUS", VarKind::U16),
+    ("EZ_HY2_UP_MBPS", VarKind::U32),
+    ("EZ_HY2_DOWN_MBPS", VarKind::U32),
+    ("EZ_HY2_IGNORE_CLIENT_BW", VarKind::Bool),
+    ("EZ_BRUTAL_UP_MBPS", VarKind::U32),
+    ("EZ_BRUTAL_DOWN_MBPS", VarKind::U32),
+    (
+        "EZ_MUX_PROTOCOL",
+        VarKind::OneOf(&["smux", "yamux", "h2mux"]),
+    ),
+    ("EZ_MUX_MAX_STREAMS", VarKind::U32),
+    ("EZ_MUX_PADDING", VarKind::Bool),
+    (
+        "EZ_TUIC_CC",
+        VarKind::OneOf(&["bbr", "cubic", "new_reno", "newreno"]),
+    ),
+    ("EZ_TUIC_AUTH_TIMEOUT", VarKind::Duration),
+    ("EZ_TUIC_ZERO_RTT", VarKind::Bool),
+    ("EZ_TUIC_HEARTBEAT", VarKind::Duration),
+    (
+        "EZ_TUIC_UDP_RELAY_MODE",
+        VarKind::OneOf(&["native", "quic"]),
+    ),
+    ("EZ_TCP_FAST_OPEN", VarKind::Bool),
+    ("EZ_UDP_TIMEOUT", VarKind::Duration),
+    ("EZ_SNIFF", VarKind::Bool),
+    ("EZ_SNIFF_OVERRIDE_DESTINATION", VarKind::Bool),
+    ("EZ_BIND_INTERFACE", VarKind::Str),
+    ("EZ_ROUTING_MARK", VarKind::Str),
+    ("EZ_NETNS", VarKind::Str),
+    ("EZ_SNIFF_ANYTLS", VarKind::Bool),
+    ("EZ_SNIFF_HYSTERIA2", VarKind::Bool),
+    ("EZ_SNIFF_TUIC", VarKind::Bool),
+    ("EZ_SNIFF_VLESS_REALITY", VarKind::Bool),
+    ("EZ_SNIFF_VLESS_WS", VarKind::Bool),
+    ("EZ_SNIFF_VLESS_GRPC", VarKind::Bool),
+    ("EZ_ANYTLS_LISTEN", VarKind::Ip),
+    ("EZ_HYSTERIA2_LISTEN", VarKind::Ip),
+    ("EZ_TUIC_LISTEN", VarKind::Ip),
+    ("EZ_VLESS_REALITY_LISTEN", VarKind::Ip),
+    ("EZ_VLESS_WS_LISTEN", VarKind::Ip),
+    ("EZ_VLESS_GRPC_LISTEN", VarKind::Ip),
+    ("EZ_VLESS_HANDSHAKE_SERVER", VarKind::Str),
+    ("EZ_VLESS_HANDSHAKE_PORT", VarKind::U16),
+    ("EZ_REALITY_CHECK_TARGET", VarKind::Bool),
+    ("EZ_REALITY_AUTO_TARGET", VarKind::Bool),
+    ("EZ_REALITY_SHORT_IDS", VarKind::Str),
+    ("EZ_REALITY_SHORT_ID_COUNT", VarKind::U16),
+    ("EZ_REALITY_SHORT_ID_LEN", VarKind::U16),
+    ("EZ_REALITY_PRIVATE_KEY", VarKind::Str),
+    (
+        "EZ_REALITY_KEYGEN",
+        VarKind::OneOf(&["internal", "sing-box"]),
+    ),
+    ("EZ_DNS_UPSTREAM", VarKind::DnsUpstream),
+    (
+        "EZ_DNS_STRATEGY",
+        VarKind::OneOf(&["prefer_ipv4", "prefer_ipv6", "ipv4_only", "ipv6_only"]),
+    ),
+    ("EZ_DNS_BLOCK_DOMAINS", VarKind::Str),
+    (
+        "EZ_DNS_BLOCK_ACTION",
+        VarKind::OneOf(&["reject", "drop", "nxdomain", "refused"]),
+    ),
+    ("EZ_RELAY_UPSTREAM", VarKind::Str),
+    ("EZ_GEOIP_BLOCK", VarKind::Str),
+    ("EZ_GEOIP_DIRECT", VarKind::Str),
+    ("EZ_BLOCK_CN_INBOUND", VarKind::Bool),
+    ("EZ_RULE_SET_MIRROR", VarKind::Str),
+    ("EZ_WARP_PRIVATE_KEY", VarKind::Str),
+    ("EZ_WARP_ADDRESS", VarKind::Str),
+    ("EZ_WARP_PEER", VarKind::Str),
+    ("EZ_WARP_PEER_PUBLIC_KEY", VarKind::Str),
+    ("EZ_WARP_RESERVED", VarKind::Str),
+    ("EZ_WARP_MTU", VarKind::U16),
+    ("EZ_WARP_DOMAINS", VarKind::Str),
+    ("EZ_WARP_SERVICES", VarKind::Str),
+    ("EZ_WARP_ALL", VarKind::Bool),
+    ("EZ_LIMIT_PROTOCOLS", VarKind::ProtocolList),
+    ("EZ_LIMIT_BLOCKED_SOURCES", VarKind::Str),
+    ("EZ_LIMIT_BLOCK_BITTORRENT", VarKind::Bool),
+    ("EZ_LIMIT_BLOCK_SMTP", VarKind::Bool),
+    ("EZ_LIMIT_BLOCK_PRIVATE", VarKind::Bool),
+    ("EZ_LOG_LEVEL", VarKind::OneOf(LOG_LEVELS)),
+    ("EZ_LOG_FORMAT", VarKind::OneOf(&["text", "json"])),
+    ("EZ_PRINT_CONFIG", VarKind::Bool),
+    ("EZ_PRINT_DETAILS", VarKind::Bool),
+    ("EZ_REDACT_SECRETS", VarKind::Bool),
+    ("EZ_DRY_RUN", VarKind::Bool),
+    ("EZ_DIFF", VarKind::Bool),
+    ("EZ_CANONICAL_OUTPUT", VarKind::Bool),
+    ("EZ_MINIMAL_CONFIG", VarKind::Bool),
+    ("EZ_ANNOTATE_CONFIG", VarKind::Bool),
+    ("EZ_ERROR_FORMAT", VarKind::OneOf(&["text", "json"])),
+    ("EZ_OUTPUT", VarKind::OneOf(&["text", "json"])),
+    ("EZ_LANG", VarKind::OneOf(&["zh", "en"])),
+    ("EZ_CLIENT_CONFIG_PATH", VarKind::Str),
+    ("EZ_CLIENT_PROTOCOL", VarKind::Protocol),
+    ("EZ_CLIENT_USER", VarKind::Str),
+    ("EZ_CLIENT_MIXED_LISTEN", VarKind::Ip),
+    ("EZ_CLIENT_MIXED_PORT", VarKind::U16),
+    ("EZ_CLIENT_CHAIN_FRONT", VarKind::Str),
+    (
+        "EZ_CLIENT_DNS_MODE",
+        VarKind::OneOf(&["simple", "split", "fakeip", "fake-ip"]),
+    ),
+    ("EZ_CLIENT_ADBLOCK", VarKind::Bool),
+    (
+        "EZ_CLIENT_PLATFORM",
+        VarKind::OneOf(&["desktop", "ios", "android", "router", "openwrt"]),
+    ),
+    ("EZ_CLIENT_INCLUDE_PACKAGES", VarKind::Str),
+    ("EZ_CLIENT_EXCLUDE_PACKAGES", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE_ANYTLS", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE_HYSTERIA2", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE_TUIC", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE_VLESS_REALITY", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE_VLESS_WS", VarKind::Str),
+    ("EZ_CLIENT_SERVER_OVERRIDE_VLESS_GRPC", VarKind::Str),
+    ("EZ_SNI_OVERRIDE", VarKind::Str),
+    ("EZ_SNI_OVERRIDE_ANYTLS", VarKind::Str),
+    ("EZ_SNI_OVERRIDE_HYSTERIA2", VarKind::Str),
+    ("EZ_SNI_OVERRIDE_TUIC", VarKind::Str),
+    ("EZ_SNI_OVERRIDE_VLESS_WS", VarKind::Str),
+    ("EZ_SNI_OVERRIDE_VLESS_GRPC", VarKind::Str),
+    (
+        "EZ_CLIENT_UTLS_FINGERPRINT",
+        VarKind::OneOf(&[
+            "chrome",
+            "firefox",
+            "edge",
+            "safari",
+            "360",
+            "qq",
+            "ios",
+            "android",
+            "random",
+            "randomized",
+        ]),
+    ),
+    ("EZ_EXPORT_PATH", VarKind::Str),
+    ("EZ_CLIENT_TEMPLATE_PATH", VarKind::Str),
+    ("EZ_REMOTE_PROFILE_URL", VarKind::Str),
+    ("EZ_REMOTE_PROFILE_NAME", VarKind::Str),
+    ("EZ_SUBSCRIBE_LISTEN", VarKind::ListenList),
+    ("EZ_SUBSCRIBE_PATH", VarKind::Str),
+    ("EZ_SUBSCRIBE_PUBLIC_URL", VarKind::Str),
+    ("EZ_SUBSCRIBE_NAME", VarKind::Str),
+    ("EZ_SUBSCRIBE_BASIC_USER", VarKind::Str),
+    ("EZ_SUBSCRIBE_BASIC_PASS", VarKind::Str),
+    ("EZ_SUBSCRIBE_ALLOW_CIDRS", VarKind::Str),
+    ("EZ_SUBSCRIBE_TRUSTED_PROXIES", VarKind::Str),
+    ("EZ_SUBSCRIBE_PATH_PREFIX", VarKind::Str),
+    ("EZ_SUBSCRIBE_RATE_LIMIT", VarKind::U32),
+    ("EZ_SUBSCRIBE_RATE_BURST", VarKind::U32),
+    ("EZ_SUBSCRIBE_SIGNING_KEY", VarKind::Str),
+    ("EZ_AUDIT_LOG", VarKind::Str),
+    ("EZ_BUNDLE_PATH", VarKind::Str),
+    ("EZ_SUPPORT_LOG", VarKind::Str),
+    ("EZ_HEALTH_HOST", VarKind::Ip),
+    ("EZ_HEALTH_TIMEOUT", VarKind::Duration),
+    ("EZ_UDP_SELFTEST", VarKind::Bool),
+    ("EZ_UDP_PROBE_URL", VarKind::Str),
+    ("EZ_INSTALL_PREFIX", VarKind::Str),
+    ("EZ_COMPOSE_IMAGE", VarKind::Str),
+    ("EZ_DEPLOY_DIR", VarKind::Str),
+    ("EZ_DEPLOY_REMOTE_BIN", VarKind::Str),
+    ("EZ_DEPLOY_BINARY", VarKind::Str),
+    ("EZ_DEPLOY_SERVICE", VarKind::Str),
+    ("EZ_DEPLOY_SSH_KEY", VarKind::Str),
+    ("EZ_URLTEST_URL", VarKind::Str),
+    ("EZ_URLTEST_TIMEOUT", VarKind::Duration),
+    ("EZ_SINGBOX_CHECK", VarKind::Bool),
+    ("EZ_SINGBOX_VERSION", VarKind::Version),
+    ("EZ_AUTO_UPGRADE_CHECK", VarKind::Bool),
+    ("EZ_UPGRADE_CHECK_INTERVAL", VarKind::Duration),
+    ("EZ_AUTO_UPGRADE", VarKind::Bool),
+    ("EZ_UPGRADE_WINDOW", VarKind::Str),
+    ("EZ_CLASH_API", VarKind::SocketAddr),
+    ("EZ_CLASH_API_SECRET", VarKind::Str),
+    ("EZ_STATS_INTERVAL", VarKind::Duration),
+    ("EZ_STATS_PATH", VarKind::Str),
+    ("EZ_METRICS_LISTEN", VarKind::SocketAddr),
+    ("EZ_TELEGRAM_BOT_TOKEN", VarKind::Str),
+    ("EZ_TELEGRAM_CHAT_ID", VarKind::Str),
+    ("EZ_WEBHOOK_URL", VarKind::Str),
+    ("EZ_WEBHOOK_SECRET", VarKind::Str),
+    ("EZ_WEBHOOK_EVENTS", VarKind::Str),
+    ("SING_BOX_BIN", VarKind::Str),
+];
+
+/// 查找变量类型
+/// 已知敏感变量加 `_FILE` 后缀时为密钥文件路径
+pub fn lookup_kind(key: &str) -> Option<VarKind> {
+    let known = |key: &str| {
+        SCHEMA
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, kind)| *kind)
+    };
+    known(key).or_else(|| {
+        let base = key.strip_suffix("_FILE")?;
+        (is_secret_env(base) && known(base).is_some()).then_some(VarKind::SecretFile)
+    })
+}
+
+//============================================================================
+// 校验
+//============================================================================
+
+/// 变量校验问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvIssue {
+    /// 未知变量（可能拼写错误）
+    Unknown {
+        /// 变量名
+        key: String,
+        /// 最接近的已知变量
+        suggestion: Option<&'static str>,
+    },
+    /// 取值无法解析
+    Invalid {
+        /// 变量名
+        key: String,
+        /// 原始值
+        value: String,
+        /// 期望的类型
+        expected: VarKind,
+    },
+}
+
+impl EnvIssue {
+    /// 是否为错误（未知变量仅为警告）
+    pub fn is_error(&self) -> bool {
+        matches!(self, EnvIssue::Invalid { .. })
+    }
+}
+
+impl fmt::Display for EnvIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvIssue::Unknown {
+                key,
+                suggestion: Some(s),
+            } => f.write_str(&tf(Msg::UnknownVarSuggest, &[key, s])),
+            EnvIssue::Unknown {
+                key,
+                suggestion: None,
+            } => f.write_str(&tf(Msg::UnknownVar, &[key])),
+            EnvIssue::Invalid {
+                key,
+                value,
+                expected,
+            } => f.write_str(&tf(Msg::InvalidValue, &[key, value, expected])),
+        }
+    }
+}
+
+/// 校验给定的变量集合
+pub fn validate_vars(vars: &BTreeMap<String, String>) -> Vec<EnvIssue> {
+    let mut issues = Vec::new();
+    for (key, value) in vars {
+        match lookup_kind(key) {
+            Some(kind) => {
+                // 空值视为未设置
+                if !value.trim().is_empty() && !kind.accepts(value) {
+                    issues.push(EnvIssue::Invalid {
+                        key: key.clone(),
+                        value: value.clone(),
+                        expected: kind,
+                    });
+                }
+            }
+            None if key.starts_with("EZ_") => issues.push(EnvIssue::Unknown {
+                key: key.clone(),
+                suggestion: suggest(key),
+            }),
+            None => {}
+        }
+    }
+    issues
+}
+
+/// 校验当前进程的全部变量（命令行、环境变量、配置文件）
+pub fn validate_env() -> Vec<EnvIssue> {
+    validate_vars(&env_snapshot())
+}
+
+/// 查找编辑距离最近的已知变量
+fn suggest(key: &str) -> Option<&'static str> {
+    SCHEMA
+        .iter()
+        .map(|(k, _)| (*k, edit_distance(key, k)))
+        .filter(|(_, d)| *d <= 3)
+        .min_by_key(|(_, d)| *d)
+        .map(|(k, _)| k)
+}
+
+/// Levenshtein 编辑距离
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_values() {
+        let issues = validate_vars(&vars(&[
+            ("EZ_TUIC_PORT", "2083"),
+            ("EZ_ENABLE_TUIC", "off"),
+            ("EZ_PUBLIC_IP", "2001:db8::1"),
+            ("EZ_CLIENT_PROTOCOL", "hy2"),
+            ("EZ_LIMIT_PROTOCOLS", "anytls, tuic"),
+            ("EZ_TUIC_CC", "BBR"),
+            ("EZ_ACME_PROVIDER", "https://acme.example.com/directory"),
+            ("EZ_HY2_UP_MBPS", ""),
+            ("PATH", "/usr/bin"),
+        ]));
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let issues = validate_vars(&vars(&[
+            ("EZ_TUIC_PORT", "eighty"),
+            ("EZ_ENABLE_TUIC", "maybe"),
+            ("EZ_SUBSCRIBE_LISTEN", "0.0.0.0"),
+            ("EZ_ACME_PROVIDER", "buypass"),
+        ]));
+        assert_eq!(issues.len(), 4);
+        assert!(issues.iter().all(EnvIssue::is_error));
+        let port = issues
+            .iter()
+            .find(|i| matches!(i, EnvIssue::Invalid { key, .. } if key == "EZ_TUIC_PORT"))
+            .unwrap();
+        assert_eq!(port.to_string(), "EZ_TUIC_PORT=eighty 不是有效的 u16");
+    }
+
+    #[test]
+    fn test_secret_file_vars() {
+        assert_eq!(lookup_kind("EZ_PASSWORD_FILE"), Some(VarKind::SecretFile));
+        assert_eq!(
+            lookup_kind("EZ_CF_API_TOKEN_FILE"),
+            Some(VarKind::SecretFile)
+        );
+        assert_eq!(lookup_kind("EZ_DOMAIN_FILE"), None);
+        let issues = validate_vars(&vars(&[(
+            "EZ_SUBSCRIBE_BASIC_PASS_FILE",
+            "/nonexistent/ez-secret",
+        )]));
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+    }
+
+    #[test]
+    fn test_unknown_with_suggestion() {
+        let issues = validate_vars(&vars(&[("EZ_TUIC_PROT", "1"), ("EZ_WHATEVER_XYZ", "1")]));
+        assert_eq!(
+            issues[0],
+            EnvIssue::Unknown {
+                key: "EZ_TUIC_PROT".to_string(),
+                suggestion: Some("EZ_TUIC_PORT"),
+            }
+        );
+        assert_eq!(
+            issues[1],
+            EnvIssue::Unknown {
+                key: "EZ_WHATEVER_XYZ".to_string(),
+                suggestion: None,
+            }
+        );
+        assert!(!issues[0].is_error());
+    }
+
+    #[test]
+    fn test_schema_unique() {
+        let mut keys: Vec<&str> = SCHEMA.iter().map(|(k, _)| *k).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), SCHEMA.len());
+    }
+}
@@ -0,0 +1,153 @@
+//! 命令行错误类型
+//!
+//! 所有子命令返回 [`EzError`]，按类别映射到不同的退出码，
+//! 并可通过 `--error-format json` 输出机器可读的错误信息
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::json;
+use thiserror::Error;
+
+use crate::i18n::{Msg, tf};
+
+/// 命令行错误
+#[derive(Debug, Error)]
+pub enum EzError {
+    /// 环境变量 / 参数 / 配置文件取值无效
+    #[error("{0}")]
+    Env(String),
+    /// 配置生成或校验失败
+    #[error("{0}")]
+    Build(String),
+    /// 文件读写失败
+    #[error("{}", tf(Msg::IoFailed, &[.path, .source]))]
+    Io {
+        /// 文件路径
+        path: String,
+        /// 底层 I/O 错误
+        #[source]
+        source: std::io::Error,
+    },
+    /// 启动 sing-box 进程失败
+    #[error("{}", tf(Msg::SpawnFailed, &[.bin, .source]))]
+    SingBoxSpawn {
+        /// sing-box 二进制文件路径
+        bin: String,
+        /// 底层 I/O 错误
+        #[source]
+        source: std::io::Error,
+    },
+    /// 订阅服务运行失败
+    #[error("{0}")]
+    Serve(String),
+}
+
+impl EzError {
+    /// 构造文件读写错误（用于 `map_err`）
+    pub fn io(path: &str) -> impl FnOnce(std::io::Error) -> EzError + '_ {
+        move |source| EzError::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    /// 机器可读的错误代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            EzError::Env(_) => "env",
+            EzError::Build(_) => "build",
+            EzError::Io { .. } => "io",
+            EzError::SingBoxSpawn { .. } => "singbox_spawn",
+            EzError::Serve(_) => "serve",
+        }
+    }
+
+    /// 进程退出码
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            EzError::Env(_) => 2,
+            EzError::Build(_) => 3,
+            EzError::Io { .. } => 4,
+            EzError::SingBoxSpawn { .. } => 5,
+            EzError::Serve(_) => 6,
+        }
+    }
+
+    /// 按指定格式渲染错误信息
+    pub fn render(&self, format: ErrorFormat) -> String {
+        match format {
+            ErrorFormat::Text => format!("❌ {}", self),
+            ErrorFormat::Json => json!({
+                "error": {
+                    "code": self.code(),
+                    "exit_code": self.exit_code(),
+                    "message": self.to_string(),
+                }
+            })
+            .to_string(),
+        }
+    }
+}
+
+//============================================================================
+// 错误输出格式
+//============================================================================
+
+/// 错误输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// 人类可读文本
+    #[default]
+    Text,
+    /// 单行 JSON（便于自动化脚本解析）
+    Json,
+}
+
+impl fmt::Display for ErrorFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorFormat::Text => write!(f, "text"),
+            ErrorFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(ErrorFormat::Text),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("无效的错误输出格式: {}（可选 text/json）", s)),
+        }
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_json() {
+        let err = EzError::Build("没有可用协议".to_string());
+        assert_eq!(err.exit_code(), 3);
+        let value: serde_json::Value =
+            serde_json::from_str(&err.render(ErrorFormat::Json)).unwrap();
+        assert_eq!(value["error"]["code"], "build");
+        assert_eq!(value["error"]["exit_code"], 3);
+        assert_eq!(value["error"]["message"], "没有可用协议");
+        assert_eq!(err.render(ErrorFormat::Text), "❌ 没有可用协议");
+
+        let err = EzError::io("/tmp/config.json")(std::io::Error::other("disk full"));
+        assert_eq!(err.code(), "io");
+        assert_eq!(err.to_string(), "读写 /tmp/config.json 失败: disk full");
+        assert_eq!("JSON".parse::<ErrorFormat>(), Ok(ErrorFormat::Json));
+        assert!("yaml".parse::<ErrorFormat>().is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! 防火墙规则生成模块
+//!
+//! 根据已启用的协议输出 nftables / iptables / ufw 放行规则，
+//! Hysteria2 / TUIC 基于 QUIC，规则使用 udp
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use ezsingbox::autoconfig::{MultiProtocolResult, Transport};
+
+/// 防火墙规则格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FirewallFormat {
+    /// nftables（`nft add rule inet filter input ...`）
+    #[default]
+    Nft,
+    /// iptables / ip6tables
+    Iptables,
+    /// ufw
+    Ufw,
+}
+
+impl fmt::Display for FirewallFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FirewallFormat::Nft => write!(f, "nft"),
+            FirewallFormat::Iptables => write!(f, "iptables"),
+            FirewallFormat::Ufw => write!(f, "ufw"),
+        }
+    }
+}
+
+impl FromStr for FirewallFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "nft" | "nftables" => Ok(FirewallFormat::Nft),
+            "iptables" => Ok(FirewallFormat::Iptables),
+            "ufw" => Ok(FirewallFormat::Ufw),
+            _ => Err(format!(
+                "无效的防火墙规则格式: {}（可选 nft/iptables/ufw）",
+                s
+            )),
+        }
+    }
+}
+
+/// 单条放行规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirewallRule {
+    /// 端口
+    pub port: u16,
+    /// 传输层
+    pub transport: Transport,
+    /// 规则说明（协议名或用途）
+    pub comment: String,
+}
+
+/// 收集已启用入站与订阅服务的放行规则
+pub fn firewall_rules(result: &MultiProtocolResult, subscribe_ports: &[u16]) -> Vec<FirewallRule> {
+    let mut rules: Vec<FirewallRule> = result
+        .enabled_protocols()
+        .into_iter()
+        .filter_map(|protocol| {
+            Some(FirewallRule {
+                port: result.port(protocol)?,
+                transport: protocol.transport(),
+                comment: protocol.as_str().to_string(),
+            })
+        })
+        .collect();
+    for &port in subscribe_ports {
+        rules.push(FirewallRule {
+            port,
+            transport: Transport::Tcp,
+            comment: "subscribe".to_string(),
+        });
+    }
+    rules
+}
+
+/// 按指定格式渲染规则（每行一条可直接执行的命令）
+pub fn render_rules(rules: &[FirewallRule], format: FirewallFormat) -> String {
+    let mut out = format!("# 由 ezsingbox firewall 生成（{}）\n", format);
+    for rule in rules {
+        let (port, proto, comment) = (rule.port, rule.transport.as_str(), &rule.comment);
+        match format {
+            FirewallFormat::Nft => {
+                let _ = writeln!(
+                    out,
+                    "nft add rule inet filter input {proto} dport {port} accept comment \\\"ezsingbox {comment}\\\""
+                );
+            }
+            FirewallFormat::Iptables => {
+                for bin in ["iptables", "ip6tables"] {
+                    let _ = writeln!(
+                        out,
+                        "{bin} -I INPUT -p {proto} --dport {port} -m comment --comment \"ezsingbox {comment}\" -j ACCEPT"
+                    );
+                }
+            }
+            FirewallFormat::Ufw => {
+                let _ = writeln!(
+                    out,
+                    "ufw allow {port}/{proto} comment 'ezsingbox {comment}'"
+                );
+            }
+        }
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezsingbox::autoconfig::MultiProtocolBuilder;
+
+    #[test]
+    fn test_render_rules() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip("1.2.3.4".parse().unwrap())
+            .enable_anytls(443)
+            .enable_hysteria2(443)
+            .build()
+            .unwrap();
+        let rules = firewall_rules(&result, &[8080]);
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[1].transport, Transport::Udp);
+
+        let nft = render_rules(&rules, FirewallFormat::Nft);
+        assert!(nft.contains(
+            "nft add rule inet filter input udp dport 443 accept comment \\\"ezsingbox hysteria2\\\"\n"
+        ));
+        let iptables = render_rules(&rules, FirewallFormat::Iptables);
+        assert!(iptables.contains(
+            "ip6tables -I INPUT -p tcp --dport 8080 -m comment --comment \"ezsingbox subscribe\" -j ACCEPT\n"
+        ));
+        let ufw = render_rules(&rules, FirewallFormat::Ufw);
+        assert!(ufw.contains("ufw allow 443/tcp comment 'ezsingbox anytls'\n"));
+        assert_eq!(
+            "nftables".parse::<FirewallFormat>(),
+            Ok(FirewallFormat::Nft)
+        );
+        assert!("pf".parse::<FirewallFormat>().is_err());
+    }
+}
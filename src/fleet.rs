@@ -0,0 +1,126 @@
+//! 多服务器编排模块
+//!
+//! 从一份服务器列表（EZ_FLEET，通常来自配置文件的 `[[servers]]`）为每台服务器生成服务端配置，
+//! 并生成一份包含全部节点的客户端配置：每个节点一个出站（标签为节点名，如 jp-1、us-2），
+//! 由 selector 出站统一选择
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::env::env_string;
+
+/// 默认输出目录
+pub const DEFAULT_FLEET_DIR: &str = "./fleet";
+
+/// 服务器节点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FleetNode {
+    /// 节点名称（客户端出站标签，如 jp-1）
+    pub name: String,
+    /// 公网 IP
+    pub public_ip: IpAddr,
+    /// 域名（不指定时使用基于公网 IP 的 sslip.io）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// SSH 目标（如 root@203.0.113.1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<String>,
+    /// 服务端配置输出路径（默认 `<EZ_FLEET_DIR>/<name>.json`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_path: Option<String>,
+}
+
+impl FleetNode {
+    /// 服务端配置输出路径
+    pub fn config_path(&self, dir: &str) -> String {
+        self.config_path.clone().unwrap_or_else(|| {
+            Path::new(dir)
+                .join(format!("{}.json", self.name))
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+}
+
+/// 解析服务器列表（JSON 数组），检查节点名称
+pub fn parse_fleet(raw: &str) -> Result<Vec<FleetNode>, String> {
+    let nodes: Vec<FleetNode> =
+        serde_json::from_str(raw).map_err(|e| format!("EZ_FLEET 不是有效的服务器列表: {}", e))?;
+    let mut seen = HashSet::new();
+    for node in &nodes {
+        let valid = !node.name.is_empty()
+            && node
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(format!(
+                "无效的节点名称: \"{}\"（只能包含字母、数字和 - _ .）",
+                node.name
+            ));
+        }
+        // 与客户端内置出站标签冲突会导致路由错乱
+        if matches!(node.name.as_str(), "proxy" | "direct" | "block") {
+            return Err(format!("节点名称 {} 与内置出站标签冲突", node.name));
+        }
+        if !seen.insert(node.name.as_str()) {
+            return Err(format!("节点名称重复: {}", node.name));
+        }
+    }
+    Ok(nodes)
+}
+
+/// 读取 EZ_FLEET 指定的服务器列表
+pub fn fleet_from_env() -> Result<Vec<FleetNode>, String> {
+    let raw = env_string("EZ_FLEET")
+        .ok_or_else(|| "未设置服务器列表（EZ_FLEET 或配置文件 [[servers]]）".to_string())?;
+    let nodes = parse_fleet(&raw)?;
+    if nodes.is_empty() {
+        return Err("服务器列表为空".to_string());
+    }
+    Ok(nodes)
+}
+
+/// 服务端配置输出目录（EZ_FLEET_DIR）
+pub fn fleet_dir_from_env() -> String {
+    env_string("EZ_FLEET_DIR").unwrap_or_else(|| DEFAULT_FLEET_DIR.to_string())
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fleet() {
+        let nodes = parse_fleet(
+            r#"[
+                { "name": "jp-1", "public_ip": "203.0.113.1", "ssh": "root@203.0.113.1" },
+                { "name": "us-2", "public_ip": "2001:db8::2", "domain": "us.example.com",
+                  "config_path": "/srv/us.json" }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].ssh.as_deref(), Some("root@203.0.113.1"));
+        assert_eq!(
+            nodes[0].config_path("out"),
+            Path::new("out").join("jp-1.json").to_string_lossy()
+        );
+        assert_eq!(nodes[1].config_path("out"), "/srv/us.json");
+
+        let dup =
+            r#"[{ "name": "a", "public_ip": "1.1.1.1" }, { "name": "a", "public_ip": "1.0.0.1" }]"#;
+        assert!(parse_fleet(dup).is_err());
+        assert!(parse_fleet(r#"[{ "name": "proxy", "public_ip": "1.1.1.1" }]"#).is_err());
+        assert!(parse_fleet(r#"[{ "name": "a b", "public_ip": "1.1.1.1" }]"#).is_err());
+        assert!(parse_fleet(r#"[{ "name": "a" }]"#).is_err());
+    }
+}
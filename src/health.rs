@@ -0,0 +1,435 @@
+//! 健康检查模块
+//!
+//! 从本机连接已生成配置中的每个入站端口：
+//! TCP 入站建立连接（启用 TLS 时完成握手并校验证书与 SNI），
+//! QUIC 入站（Hysteria2 / TUIC）发送版本协商探测包，适合作为 Docker HEALTHCHECK；
+//! 也可请求外部探测服务（EZ_UDP_PROBE_URL）从外网检查 UDP 端口是否可达
+
+use std::fmt::Write;
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, TcpStream, UdpSocket};
+use std::time::{Duration as StdDuration, Instant};
+
+use serde_json::Value;
+
+/// 默认探测超时
+pub const DEFAULT_HEALTH_TIMEOUT: StdDuration = StdDuration::from_secs(3);
+
+/// QUIC 服务器只响应不小于该长度的未知版本数据包
+const QUIC_MIN_PACKET_SIZE: usize = 1200;
+
+/// 探测使用的保留 QUIC 版本（形如 0x?a?a?a?a，服务器必须回复版本协商）
+const QUIC_PROBE_VERSION: u32 = 0x1a2a_3a4a;
+
+//============================================================================
+// 探测目标
+//============================================================================
+
+/// 探测方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeTransport {
+    /// TCP 连接
+    Tcp,
+    /// QUIC 版本协商
+    Quic,
+}
+
+/// 单个入站的探测目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeTarget {
+    /// 入站类型
+    pub protocol: String,
+    /// 入站标签
+    pub tag: String,
+    /// 入站监听地址（未指定或为通配地址时为 None）
+    pub listen: Option<IpAddr>,
+    /// 入站端口
+    pub port: u16,
+    /// 探测方式
+    pub transport: ProbeTransport,
+    /// 需要校验的 TLS SNI（REALITY 与 QUIC 入站不校验）
+    pub sni: Option<String>,
+    /// 是否启用了混淆（Hysteria2 salamander 不响应版本协商）
+    pub obfuscated: bool,
+}
+
+/// 从配置中收集所有带监听端口的入站
+pub fn probe_targets(config: &Value) -> Vec<ProbeTarget> {
+    let inbounds = config["inbounds"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    inbounds
+        .iter()
+        .filter_map(|inbound| {
+            let port = inbound["listen_port"].as_u64()?;
+            let protocol = inbound["type"].as_str().unwrap_or("unknown").to_string();
+            let transport = match protocol.as_str() {
+                "hysteria" | "hysteria2" | "tuic" => ProbeTransport::Quic,
+                _ => ProbeTransport::Tcp,
+            };
+            let tls = &inbound["tls"];
+            let sni = tls["server_name"]
+                .as_str()
+                .filter(|_| tls["enabled"] == true && tls["reality"]["enabled"] != true)
+                .filter(|_| transport == ProbeTransport::Tcp)
+                .map(str::to_string);
+            Some(ProbeTarget {
+                tag: inbound["tag"].as_str().unwrap_or(&protocol).to_string(),
+                protocol,
+                listen: inbound["listen"]
+                    .as_str()
+                    .and_then(|s| s.parse::<IpAddr>().ok())
+                    .filter(|ip| !ip.is_unspecified()),
+                port: u16::try_from(port).ok()?,
+                transport,
+                sni,
+                obfuscated: inbound.get("obfs").is_some_and(|o| !o.is_null()),
+            })
+        })
+        .collect()
+}
+
+//============================================================================
+// 探测
+//============================================================================
+
+/// 单个入站的探测结果
+#[derive(Debug)]
+pub struct ProbeReport {
+    /// 探测目标
+    pub target: ProbeTarget,
+    /// 成功时为说明，失败时为原因
+    pub result: Result<String, String>,
+    /// 探测耗时
+    pub latency: StdDuration,
+}
+
+impl ProbeReport {
+    /// 是否通过
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// 探测入站（入站绑定了具体地址时使用该地址，否则使用 host）
+pub fn probe(target: &ProbeTarget, host: IpAddr, timeout: StdDuration) -> ProbeReport {
+    let addr = SocketAddr::new(target.listen.unwrap_or(host), target.port);
+    let start = Instant::now();
+    let result = match target.transport {
+        ProbeTransport::Tcp => probe_tcp(addr, target.sni.as_deref(), timeout),
+        ProbeTransport::Quic => probe_quic(addr, target.obfuscated, timeout),
+    };
+    ProbeReport {
+        target: target.clone(),
+        result,
+        latency: start.elapsed(),
+    }
+}
+
+/// TCP 探测：建立连接，指定 SNI 时完成 TLS 握手并校验证书
+fn probe_tcp(addr: SocketAddr, sni: Option<&str>, timeout: StdDuration) -> Result<String, String> {
+    let stream =
+        TcpStream::connect_timeout(&addr, timeout).map_err(|e| format!("无法连接: {}", e))?;
+    match sni {
+        None => Ok("TCP 端口可连接".to_string()),
+        Some(sni) => tls_handshake(stream, sni, timeout),
+    }
+}
+
+/// 完成 TLS 握手并校验证书链与 SNI
+#[cfg(feature = "rustls")]
+fn tls_handshake(mut stream: TcpStream, sni: &str, timeout: StdDuration) -> Result<String, String> {
+    use std::sync::Arc;
+
+    use rustls::pki_types::ServerName;
+    use rustls::{ClientConfig, ClientConnection, RootCertStore};
+
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| e.to_string())?;
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    let name = ServerName::try_from(sni.to_string()).map_err(|e| e.to_string())?;
+    let mut conn = ClientConnection::new(Arc::new(config), name).map_err(|e| e.to_string())?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut stream)
+            .map_err(|e| format!("TLS 握手失败 (SNI {}): {}", sni, e))?;
+    }
+    Ok(format!("TLS 证书有效 (SNI {})", sni))
+}
+
+/// 未启用 rustls 特性时只检查 TCP 连接
+#[cfg(not(feature = "rustls"))]
+fn tls_handshake(_stream: TcpStream, _sni: &str, _timeout: StdDuration) -> Result<String, String> {
+    Ok("TCP 端口可连接（未启用 rustls 特性，跳过证书校验）".to_string())
+}
+
+/// QUIC 探测：发送未知版本的 Initial 包，期望收到版本协商响应
+fn probe_quic(addr: SocketAddr, obfuscated: bool, timeout: StdDuration) -> Result<String, String> {
+    let bind: SocketAddr = if addr.is_ipv4() {
+        "0.0.0.0:0".parse().unwrap()
+    } else {
+        "[::]:0".parse().unwrap()
+    };
+    let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+    socket.connect(addr).map_err(|e| e.to_string())?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| e.to_string())?;
+
+    let dcid: [u8; 8] = rand::random();
+    let scid: [u8; 8] = rand::random();
+    socket
+        .send(&quic_probe_packet(&dcid, &scid))
+        .map_err(|e| e.to_string())?;
+
+    let mut buf = [0u8; 1500];
+    match socket.recv(&mut buf) {
+        Ok(n) => {
+            let versions = parse_version_negotiation(&buf[..n], &scid)?;
+            let versions: Vec<String> = versions.iter().map(|v| format!("0x{:08x}", v)).collect();
+            Ok(format!("QUIC 响应版本协商 ({})", versions.join(", ")))
+        }
+        // 本机 UDP 端口未监听时内核返回 ICMP 端口不可达
+        Err(e) if e.kind() == ErrorKind::ConnectionRefused => Err("UDP 端口未监听".to_string()),
+        Err(_) if obfuscated => Ok("UDP 端口未拒绝（已启用混淆，无法确认 QUIC 响应）".to_string()),
+        Err(e) => Err(format!("QUIC 无响应: {}", e)),
+    }
+}
+
+/// 构造 QUIC 版本协商探测包（长包头 + 保留版本号，填充至 1200 字节）
+fn quic_probe_packet(dcid: &[u8], scid: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(QUIC_MIN_PACKET_SIZE);
+    packet.push(0xc0);
+    packet.extend_from_slice(&QUIC_PROBE_VERSION.to_be_bytes());
+    packet.push(dcid.len() as u8);
+    packet.extend_from_slice(dcid);
+    packet.push(scid.len() as u8);
+    packet.extend_from_slice(scid);
+    packet.resize(QUIC_MIN_PACKET_SIZE, 0);
+    packet
+}
+
+/// 解析版本协商包，返回服务器支持的版本列表
+/// 响应的目标连接 ID 必须等于探测包的源连接 ID
+fn parse_version_negotiation(packet: &[u8], scid: &[u8]) -> Result<Vec<u32>, String> {
+    let invalid = || "收到无法识别的 QUIC 响应".to_string();
+    if packet.len() < 7 || packet[0] & 0x80 == 0 || packet[1..5] != [0, 0, 0, 0] {
+        return Err(invalid());
+    }
+    let dcid_len = packet[5] as usize;
+    let dcid = packet.get(6..6 + dcid_len).ok_or_else(invalid)?;
+    if dcid != scid {
+        return Err(invalid());
+    }
+    let scid_len = *packet.get(6 + dcid_len).ok_or_else(invalid)? as usize;
+    let versions = packet.get(7 + dcid_len + scid_len..).ok_or_else(invalid)?;
+    if versions.is_empty() || versions.len() % 4 != 0 {
+        return Err(invalid());
+    }
+    Ok(versions
+        .chunks_exact(4)
+        .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+        .collect())
+}
+
+//============================================================================
+// 外部探测
+//============================================================================
+
+/// 展开外部探测服务地址模板中的 {host} / {port}
+pub fn expand_probe_url(template: &str, host: IpAddr, port: u16) -> String {
+    template
+        .replace("{host}", &host.to_string())
+        .replace("{port}", &port.to_string())
+}
+
+/// 解析外部探测服务的响应（`{"reachable": true, "detail": "..."}`），返回是否可达与说明
+pub fn parse_probe_response(body: &str) -> Result<(bool, String), String> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| format!("外部探测服务响应无效: {}", e))?;
+    let reachable = value["reachable"]
+        .as_bool()
+        .ok_or_else(|| "外部探测服务响应缺少 reachable 字段".to_string())?;
+    Ok((
+        reachable,
+        value["detail"].as_str().unwrap_or("").to_string(),
+    ))
+}
+
+/// 请求外部探测服务从外网向 host:port 发送 QUIC 探测包
+#[cfg(feature = "probe")]
+pub fn probe_external(
+    template: &str,
+    host: IpAddr,
+    port: u16,
+    timeout: StdDuration,
+) -> Result<(bool, String), String> {
+    use ezsingbox::autoconfig::http_agent;
+
+    let url = expand_probe_url(template, host, port);
+    // 探测服务自身也要等待 QUIC 响应，留出额外时间
+    let body = http_agent(timeout + StdDuration::from_secs(10))
+        .get(&url)
+        .call()
+        .map_err(|e| format!("请求 {} 失败: {}", url, e))?
+        .into_body()
+        .read_to_string()
+        .map_err(|e| format!("读取 {} 响应失败: {}", url, e))?;
+    parse_probe_response(&body)
+}
+
+/// 未启用 probe 特性时的占位实现
+#[cfg(not(feature = "probe"))]
+pub fn probe_external(
+    _template: &str,
+    _host: IpAddr,
+    _port: u16,
+    _timeout: StdDuration,
+) -> Result<(bool, String), String> {
+    Err("外部探测需要 probe 特性".to_string())
+}
+
+//============================================================================
+// 报告
+//============================================================================
+
+/// 渲染探测报告
+pub fn render_report(reports: &[ProbeReport]) -> String {
+    let mut out = String::new();
+    for report in reports {
+        let target = &report.target;
+        let (mark, detail) = match &report.result {
+            Ok(detail) => ("✅", detail),
+            Err(reason) => ("❌", reason),
+        };
+        let _ = writeln!(
+            out,
+            "{} {} (tag={}, 端口 {}) {}，耗时 {} ms",
+            mark,
+            target.protocol,
+            target.tag,
+            target.port,
+            detail,
+            report.latency.as_millis()
+        );
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::net::{Ipv4Addr, TcpListener};
+
+    #[test]
+    fn test_probe_targets() {
+        let config = json!({
+            "inbounds": [
+                { "type": "anytls", "tag": "anytls-in", "listen": "::", "listen_port": 443,
+                  "tls": { "enabled": true, "server_name": "example.com" } },
+                { "type": "vless", "tag": "vless-in", "listen": "127.0.0.2", "listen_port": 2096,
+                  "tls": { "enabled": true, "server_name": "www.microsoft.com",
+                           "reality": { "enabled": true } } },
+                { "type": "hysteria2", "tag": "hy2-in", "listen_port": 2053,
+                  "obfs": { "type": "salamander", "password": "x" },
+                  "tls": { "enabled": true, "server_name": "example.com" } },
+                { "type": "direct", "tag": "no-port" }
+            ]
+        });
+        let targets = probe_targets(&config);
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0].sni.as_deref(), Some("example.com"));
+        assert_eq!(targets[0].listen, None);
+        assert_eq!(targets[1].sni, None);
+        assert_eq!(targets[1].listen, Some("127.0.0.2".parse().unwrap()));
+        assert_eq!(targets[2].transport, ProbeTransport::Quic);
+        assert_eq!(targets[2].sni, None);
+        assert!(targets[2].obfuscated);
+    }
+
+    #[test]
+    fn test_external_probe_response() {
+        let host: IpAddr = "203.0.113.1".parse().unwrap();
+        assert_eq!(
+            expand_probe_url(
+                "https://probe.example.com/quic?host={host}&port={port}",
+                host,
+                2053
+            ),
+            "https://probe.example.com/quic?host=203.0.113.1&port=2053"
+        );
+        assert_eq!(
+            parse_probe_response(r#"{"reachable": false, "detail": "timeout"}"#),
+            Ok((false, "timeout".to_string()))
+        );
+        assert_eq!(
+            parse_probe_response(r#"{"reachable": true}"#),
+            Ok((true, String::new()))
+        );
+        assert!(parse_probe_response(r#"{"ok": true}"#).is_err());
+        assert!(parse_probe_response("<html>").is_err());
+    }
+
+    #[test]
+    fn test_version_negotiation() {
+        let packet = quic_probe_packet(&[1; 8], &[2; 8]);
+        assert_eq!(packet.len(), QUIC_MIN_PACKET_SIZE);
+        assert_eq!(packet[1..5], QUIC_PROBE_VERSION.to_be_bytes());
+
+        let mut response = vec![0x80, 0, 0, 0, 0, 8];
+        response.extend_from_slice(&[2; 8]);
+        response.push(8);
+        response.extend_from_slice(&[1; 8]);
+        response.extend_from_slice(&1u32.to_be_bytes());
+        response.extend_from_slice(&0x6b33_43cfu32.to_be_bytes());
+        assert_eq!(
+            parse_version_negotiation(&response, &[2; 8]),
+            Ok(vec![1, 0x6b33_43cf])
+        );
+        assert!(parse_version_negotiation(&response, &[3; 8]).is_err());
+        assert!(parse_version_negotiation(&packet, &[2; 8]).is_err());
+    }
+
+    #[test]
+    fn test_probe_local_ports() {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let listener = TcpListener::bind((localhost, 0)).unwrap();
+        let target = ProbeTarget {
+            protocol: "vless".to_string(),
+            tag: "vless-in".to_string(),
+            listen: None,
+            port: listener.local_addr().unwrap().port(),
+            transport: ProbeTransport::Tcp,
+            sni: None,
+            obfuscated: false,
+        };
+        let report = probe(&target, localhost, DEFAULT_HEALTH_TIMEOUT);
+        assert!(report.is_ok());
+        assert!(render_report(&[report]).starts_with("✅ vless (tag=vless-in"));
+
+        // 端口释放后 TCP 与 UDP 探测都应失败
+        drop(listener);
+        assert!(!probe(&target, localhost, DEFAULT_HEALTH_TIMEOUT).is_ok());
+        let quic = ProbeTarget {
+            transport: ProbeTransport::Quic,
+            ..target
+        };
+        let report = probe(&quic, localhost, StdDuration::from_millis(500));
+        assert!(!report.is_ok());
+    }
+}
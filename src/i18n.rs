@@ -0,0 +1,350 @@
+//! 控制台输出语言
+//!
+//! 消息目录覆盖连接详情、命令行帮助与错误信息；语言由 `--lang` / `EZ_LANG` 指定，
+//! 未指定时按 `LC_ALL` / `LC_MESSAGES` / `LANG` 判断，均未设置时使用中文
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::env::env_string;
+
+/// 输出语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    /// 中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lang::Zh => write!(f, "zh"),
+            Lang::En => write!(f, "en"),
+        }
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    /// 解析 `zh`、`en` 以及 `zh_CN.UTF-8`、`en-US` 等区域设置写法
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let primary = lower.split(['_', '-', '.']).next().unwrap_or_default();
+        match primary {
+            "zh" => Ok(Lang::Zh),
+            "en" => Ok(Lang::En),
+            _ => Err(format!("无效的语言: {}（可选 zh/en）", s)),
+        }
+    }
+}
+
+/// 当前语言（0: 中文，1: 英文）
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// 设置当前语言
+pub fn set_lang(lang: Lang) {
+    CURRENT.store(lang as u8, Ordering::Relaxed);
+}
+
+/// 当前语言
+pub fn lang() -> Lang {
+    match CURRENT.load(Ordering::Relaxed) {
+        0 => Lang::Zh,
+        _ => Lang::En,
+    }
+}
+
+/// 按区域设置变量判断语言：非中文的区域设置使用英文，未设置或为 C / POSIX 时使用中文
+fn lang_from_locale(locale: Option<String>) -> Lang {
+    match locale.as_deref().map(str::trim) {
+        None | Some("") | Some("C") | Some("POSIX") => Lang::Zh,
+        Some(value) => value.parse().unwrap_or(Lang::En),
+    }
+}
+
+/// 读取区域设置变量（按 POSIX 优先级）
+fn locale_from_env() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.trim().is_empty()))
+}
+
+/// 从环境变量确定语言（EZ_LANG，其次为区域设置）
+pub fn lang_from_env() -> Lang {
+    match env_string("EZ_LANG").map(|v| v.parse::<Lang>()) {
+        Some(Ok(lang)) => lang,
+        _ => lang_from_locale(locale_from_env()),
+    }
+}
+
+/// 解析命令行参数前确定语言（`--lang` 优先，用于本地化帮助信息）
+pub fn lang_from_args(args: &[String]) -> Lang {
+    let from_args = args
+        .iter()
+        .enumerate()
+        .find_map(|(i, arg)| match arg.strip_prefix("--lang") {
+            Some(rest) if rest.starts_with('=') => Some(rest[1..].to_string()),
+            Some("") => args.get(i + 1).cloned(),
+            _ => None,
+        });
+    match from_args.map(|v| v.parse::<Lang>()) {
+        Some(Ok(lang)) => lang,
+        _ => lang_from_env(),
+    }
+}
+
+//============================================================================
+// 消息目录
+//============================================================================
+
+/// 消息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // 连接详情
+    DetailsHeader,
+    PublicIp,
+    Domain,
+    ShareLinksHeader,
+    ProtocolPort,
+    HandshakeServer,
+    PublicKey,
+    ShortIds,
+    WsPath,
+    GrpcServiceName,
+    UserLink,
+    ConfigHeader,
+    UserItem,
+    Password,
+    ExpiresAt,
+    PublicKeyClient,
+    ShortIdClient,
+    AllShortIds,
+    PrivateKeyServer,
+    SubscriptionUrl,
+    ImportUri,
+    // 用户管理
+    UserName,
+    UserPassword,
+    UserExpiresAt,
+    NeverExpires,
+    UserToken,
+    // 配置检查
+    LintSummary,
+    LintMigrateHint,
+    // 错误
+    IoFailed,
+    SpawnFailed,
+    InvalidVars,
+    UnknownVar,
+    UnknownVarSuggest,
+    InvalidValue,
+    // 变量类型
+    KindStr,
+    KindBool,
+    KindIp,
+    KindSocketAddr,
+    KindListenList,
+    KindProtocol,
+    KindProtocolList,
+    KindIpDetectorList,
+    KindAcmeProvider,
+    KindDuration,
+    KindVersion,
+    KindDnsUpstream,
+    KindSecretFile,
+    KindOneOf,
+    // 命令行帮助
+    AppAbout,
+    ArgFallback,
+}
+
+impl Msg {
+    /// 指定语言的消息文本，`{}` 为占位符
+    pub fn text(self, lang: Lang) -> &'static str {
+        let (zh, en) = match self {
+            Msg::DetailsHeader => (
+                "\n==== 详细信息 (包含敏感信息) ====",
+                "\n==== Details (contains secrets) ====",
+            ),
+            Msg::PublicIp => ("公网 IP: {}", "Public IP: {}"),
+            Msg::Domain => ("域名: {}", "Domain: {}"),
+            Msg::ShareLinksHeader => ("\n==== 分享链接 ====", "\n==== Share links ===="),
+            Msg::ProtocolPort => ("\n[{}] 端口: {}", "\n[{}] Port: {}"),
+            Msg::HandshakeServer => ("  握手服务器: {}:{}", "  Handshake server: {}:{}"),
+            Msg::PublicKey => ("  公钥: {}", "  Public key: {}"),
+            Msg::ShortIds => ("  短ID: {}", "  Short IDs: {}"),
+            Msg::WsPath => ("  路径: {}", "  Path: {}"),
+            Msg::GrpcServiceName => ("  服务名: {}", "  Service name: {}"),
+            Msg::UserLink => ("  用户 {}: {}", "  User {}: {}"),
+            Msg::ConfigHeader => ("\n==== 详细配置 ====", "\n==== Configuration ===="),
+            Msg::UserItem => ("- 用户: {}", "- User: {}"),
+            Msg::Password => ("  密码: {}", "  Password: {}"),
+            Msg::ExpiresAt => ("  过期时间: {}", "  Expires at: {}"),
+            Msg::PublicKeyClient => ("  公钥 (客户端使用): {}", "  Public key (client): {}"),
+            Msg::ShortIdClient => ("  短ID (客户端使用): {}", "  Short ID (client): {}"),
+            Msg::AllShortIds => ("  全部短ID: {}", "  All short IDs: {}"),
+            Msg::PrivateKeyServer => ("  私钥 (服务端): {}", "  Private key (server): {}"),
+            Msg::SubscriptionUrl => ("\n订阅链接: {}", "\nSubscription URL: {}"),
+            Msg::ImportUri => ("URI 链接: {}", "Import URI: {}"),
+            Msg::UserName => ("用户: {}", "User: {}"),
+            Msg::UserPassword => ("密码: {}", "Password: {}"),
+            Msg::UserExpiresAt => ("过期时间: {}", "Expires at: {}"),
+            Msg::NeverExpires => ("永不过期", "never"),
+            Msg::UserToken => ("令牌: {}", "Token: {}"),
+            Msg::LintSummary => ("{}: {} 个错误，{} 个警告", "{}: {} error(s), {} warning(s)"),
+            Msg::LintMigrateHint => (
+                "弃用字段可用 ezsingbox migrate {} 自动改写",
+                "Deprecated fields can be rewritten with ezsingbox migrate {}",
+            ),
+            Msg::IoFailed => ("读写 {} 失败: {}", "failed to read/write {}: {}"),
+            Msg::SpawnFailed => (
+                "启动 sing-box 失败({}): {}",
+                "failed to start sing-box ({}): {}",
+            ),
+            Msg::InvalidVars => ("{} 个变量取值无效", "{} variable(s) have invalid values"),
+            Msg::UnknownVar => ("未知的变量 {}", "unknown variable {}"),
+            Msg::UnknownVarSuggest => (
+                "未知的变量 {}，是否为 {}?",
+                "unknown variable {}, did you mean {}?",
+            ),
+            Msg::InvalidValue => ("{}={} 不是有效的 {}", "{}={} is not a valid {}"),
+            Msg::KindStr => ("字符串", "string"),
+            Msg::KindBool => (
+                "布尔值 (true/false/1/0/yes/no/on/off)",
+                "boolean (true/false/1/0/yes/no/on/off)",
+            ),
+            Msg::KindIp => ("IP 地址", "IP address"),
+            Msg::KindSocketAddr => ("监听地址 (ip:port)", "listen address (ip:port)"),
+            Msg::KindListenList => (
+                "逗号分隔的监听地址 (ip:port / unix://路径) 或 dual",
+                "comma-separated listen addresses (ip:port / unix://path) or dual",
+            ),
+            Msg::KindProtocol => (
+                "协议名 (anytls/hysteria2/tuic/vless-reality/vless-ws/vless-grpc)",
+                "protocol name (anytls/hysteria2/tuic/vless-reality/vless-ws/vless-grpc)",
+            ),
+            Msg::KindProtocolList => ("逗号分隔的协议名列表", "comma-separated protocol names"),
+            Msg::KindIpDetectorList => (
+                "逗号分隔的探测方式列表 (http/dns/metadata/interface:<网卡>)",
+                "comma-separated detectors (http/dns/metadata/interface:<name>)",
+            ),
+            Msg::KindAcmeProvider => (
+                "ACME 提供商 (letsencrypt/zerossl/自定义 URL)",
+                "ACME provider (letsencrypt/zerossl/custom URL)",
+            ),
+            Msg::KindDuration => (
+                "时间间隔 (如 3s/500ms/1m30s/1.5h,纯数字为秒)",
+                "duration (e.g. 3s/500ms/1m30s/1.5h, bare number = seconds)",
+            ),
+            Msg::KindVersion => (
+                "sing-box 版本 (如 1.10/1.11/1.12)",
+                "sing-box version (e.g. 1.10/1.11/1.12)",
+            ),
+            Msg::KindDnsUpstream => (
+                "DNS 上游 (cloudflare/google/quad9/alidns、IP 或 https:// tls:// quic:// 等 URL)",
+                "DNS upstream (cloudflare/google/quad9/alidns, an IP, or an https:// tls:// quic:// URL)",
+            ),
+            Msg::KindSecretFile => ("可读取的密钥文件路径", "readable secret file path"),
+            Msg::KindOneOf => ("{} 之一", "one of {}"),
+            Msg::AppAbout => (
+                "简易 sing-box 配置生成器和运行器",
+                "Easy sing-box config generator and runner",
+            ),
+            Msg::ArgFallback => ("{}", "Sets {} (see IFLOW.md)"),
+        };
+        match lang {
+            Lang::Zh => zh,
+            Lang::En => en,
+        }
+    }
+}
+
+/// 当前语言的消息文本
+pub fn t(msg: Msg) -> &'static str {
+    msg.text(lang())
+}
+
+/// 当前语言的消息文本，依次替换 `{}` 占位符
+pub fn tf(msg: Msg, args: &[&dyn fmt::Display]) -> String {
+    fill(t(msg), args)
+}
+
+/// 依次替换模板中的 `{}` 占位符（多余的占位符保留原样）
+pub fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lang() {
+        assert_eq!("zh_CN.UTF-8".parse(), Ok(Lang::Zh));
+        assert_eq!("en-US".parse(), Ok(Lang::En));
+        assert!("fr".parse::<Lang>().is_err());
+
+        assert_eq!(lang_from_locale(None), Lang::Zh);
+        assert_eq!(lang_from_locale(Some("C".to_string())), Lang::Zh);
+        assert_eq!(lang_from_locale(Some("de_DE.UTF-8".to_string())), Lang::En);
+        assert_eq!(lang_from_locale(Some("zh_TW.UTF-8".to_string())), Lang::Zh);
+
+        let args: Vec<String> = ["ezsingbox", "--lang=en", "--help"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(lang_from_args(&args), Lang::En);
+    }
+
+    #[test]
+    fn test_messages() {
+        assert_eq!(
+            fill(Msg::IoFailed.text(Lang::En), &[&"/tmp/a", &"disk full"]),
+            "failed to read/write /tmp/a: disk full"
+        );
+        assert_eq!(
+            fill(Msg::ProtocolPort.text(Lang::Zh), &[&"TUIC"]),
+            "\n[TUIC] 端口: {}"
+        );
+        assert_eq!(
+            fill(Msg::ArgFallback.text(Lang::En), &[&"EZ_DOMAIN"]),
+            "Sets EZ_DOMAIN (see IFLOW.md)"
+        );
+        // 两种语言的占位符数量一致
+        for msg in [
+            Msg::HandshakeServer,
+            Msg::InvalidValue,
+            Msg::UnknownVarSuggest,
+            Msg::UserName,
+            Msg::UserExpiresAt,
+            Msg::LintSummary,
+            Msg::LintMigrateHint,
+        ] {
+            assert_eq!(
+                msg.text(Lang::Zh).matches("{}").count(),
+                msg.text(Lang::En).matches("{}").count()
+            );
+        }
+    }
+}
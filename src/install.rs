@@ -0,0 +1,288 @@
+//! sing-box 安装模块
+//!
+//! 从 GitHub Releases 下载与当前系统 / 架构匹配的 sing-box 发布包，
+//! 按 GitHub 为发布资产提供的 SHA-256 摘要校验后解压，安装到 `<prefix>/bin/sing-box`
+
+// 未启用 install 特性时只保留占位实现，发布包解析函数仅供测试使用
+#![cfg_attr(not(feature = "install"), allow(dead_code))]
+
+/// 默认安装前缀
+pub const DEFAULT_INSTALL_PREFIX: &str = "/usr/local";
+
+/// 找不到 sing-box 时给出的安装提示
+pub const INSTALL_HINT: &str = "可运行 ezsingbox install-singbox 安装";
+
+/// sing-box 发布信息接口
+#[cfg(feature = "install")]
+const RELEASES_API: &str = "https://api.github.com/repos/SagerNet/sing-box/releases";
+
+/// 去掉版本号前的 v（v1.12.8 → 1.12.8）
+pub fn normalize_version(raw: &str) -> String {
+    let raw = raw.trim();
+    raw.strip_prefix('v').unwrap_or(raw).to_string()
+}
+
+/// 发布包文件名（sing-box-<版本>-<系统>-<架构>.tar.gz）
+/// os / arch 取值同 `std::env::consts`
+pub fn release_asset_name(version: &str, os: &str, arch: &str) -> Result<String, String> {
+    let os = match os {
+        "linux" | "android" | "freebsd" => os,
+        "macos" => "darwin",
+        _ => {
+            return Err(format!(
+                "不支持的系统: {}（仅支持 tar.gz 发布包的系统）",
+                os
+            ));
+        }
+    };
+    let arch = match arch {
+        "x86_64" => "amd64",
+        "x86" => "386",
+        "aarch64" => "arm64",
+        "arm" => "armv7",
+        "riscv64" => "riscv64",
+        "s390x" => "s390x",
+        "loongarch64" => "loong64",
+        "mips64" => "mips64",
+        _ => return Err(format!("不支持的架构: {}", arch)),
+    };
+    Ok(format!("sing-box-{}-{}-{}.tar.gz", version, os, arch))
+}
+
+/// 发布资产
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAsset {
+    /// 下载地址
+    pub url: String,
+    /// SHA-256 摘要（小写十六进制）
+    pub sha256: String,
+}
+
+/// 在 GitHub 发布信息中查找指定资产及其 SHA-256 摘要
+pub fn find_asset(release: &serde_json::Value, name: &str) -> Result<ReleaseAsset, String> {
+    let asset = release["assets"]
+        .as_array()
+        .and_then(|assets| assets.iter().find(|a| a["name"] == name))
+        .ok_or_else(|| format!("发布中没有 {}", name))?;
+    let url = asset["browser_download_url"]
+        .as_str()
+        .ok_or_else(|| format!("{} 缺少下载地址", name))?;
+    let sha256 = asset["digest"]
+        .as_str()
+        .and_then(|d| d.strip_prefix("sha256:"))
+        .filter(|d| d.len() == 64 && d.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("{} 缺少 SHA-256 摘要，无法校验", name))?;
+    Ok(ReleaseAsset {
+        url: url.to_string(),
+        sha256: sha256.to_ascii_lowercase(),
+    })
+}
+
+/// 计算 SHA-256（小写十六进制）
+#[cfg(feature = "install")]
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write as _;
+
+    Sha256::digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut out, b| {
+            let _ = write!(out, "{:02x}", b);
+            out
+        })
+}
+
+/// 从 tar.gz 发布包中取出 sing-box 可执行文件
+#[cfg(feature = "install")]
+pub fn extract_binary(archive: &[u8]) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    let entries = tar.entries().map_err(|e| format!("解压失败: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("解压失败: {}", e))?;
+        let is_binary = entry
+            .path()
+            .map(|p| p.file_name().is_some_and(|n| n == "sing-box"))
+            .unwrap_or(false);
+        if is_binary && entry.header().entry_type().is_file() {
+            let mut data = Vec::new();
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("解压失败: {}", e))?;
+            return Ok(data);
+        }
+    }
+    Err("发布包中没有 sing-box 可执行文件".to_string())
+}
+
+/// 获取发布信息（version 为 None 时为最新正式版）
+#[cfg(feature = "install")]
+fn fetch_release(agent: &ureq::Agent, version: Option<&str>) -> Result<serde_json::Value, String> {
+    let url = match version {
+        Some(v) => format!("{}/tags/v{}", RELEASES_API, normalize_version(v)),
+        None => format!("{}/latest", RELEASES_API),
+    };
+    let body = agent
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|e| format!("获取发布信息失败 ({}): {}", url, e))?
+        .into_body()
+        .read_to_string()
+        .map_err(|e| format!("获取发布信息失败: {}", e))?;
+    serde_json::from_str(&body).map_err(|e| format!("解析发布信息失败: {}", e))
+}
+
+/// 发布信息中的版本号
+#[cfg(feature = "install")]
+fn release_version(release: &serde_json::Value) -> Result<String, String> {
+    release["tag_name"]
+        .as_str()
+        .map(normalize_version)
+        .ok_or_else(|| "发布信息缺少 tag_name".to_string())
+}
+
+/// 查询 sing-box 最新正式版的版本号
+#[cfg(feature = "install")]
+pub fn latest_version() -> Result<String, String> {
+    use ezsingbox::autoconfig::http_agent;
+
+    let agent = http_agent(std::time::Duration::from_secs(30));
+    release_version(&fetch_release(&agent, None)?)
+}
+
+/// 下载并安装 sing-box，返回安装的版本与路径
+/// version 为 None 时安装最新正式版
+#[cfg(feature = "install")]
+pub fn install_singbox(version: Option<&str>, prefix: &str) -> Result<(String, String), String> {
+    use crate::utils::{temp_sibling, write_synced};
+    use ezsingbox::autoconfig::http_agent;
+    use std::path::Path;
+    use tracing::info;
+
+    // 发布包约 20 MB，慢速网络下需要较长的超时
+    let agent = http_agent(std::time::Duration::from_secs(300));
+    let release = fetch_release(&agent, version)?;
+    let version = release_version(&release)?;
+
+    let name = release_asset_name(&version, std::env::consts::OS, std::env::consts::ARCH)?;
+    let asset = find_asset(&release, &name)?;
+    info!(asset = %name, "下载 sing-box");
+    let archive = agent
+        .get(&asset.url)
+        .call()
+        .map_err(|e| format!("下载 {} 失败: {}", name, e))?
+        .into_body()
+        .with_config()
+        .limit(256 * 1024 * 1024)
+        .read_to_vec()
+        .map_err(|e| format!("下载 {} 失败: {}", name, e))?;
+    let actual = sha256_hex(&archive);
+    if actual != asset.sha256 {
+        return Err(format!(
+            "{} 校验失败: SHA-256 应为 {}，实际为 {}",
+            name, asset.sha256, actual
+        ));
+    }
+    let binary = extract_binary(&archive)?;
+
+    let dir = Path::new(prefix).join("bin");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建 {} 失败: {}", dir.display(), e))?;
+    let target = dir.join("sing-box").to_string_lossy().into_owned();
+    // 写入临时文件后重命名，避免覆盖正在运行的二进制文件
+    let tmp = temp_sibling(&target);
+    let write = || -> std::io::Result<()> {
+        write_synced(&tmp, &binary, &target)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o755))?;
+        }
+        std::fs::rename(&tmp, &target)
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("写入 {} 失败: {}", target, e)
+    })?;
+    Ok((version, target))
+}
+
+/// 未启用 install 特性时的占位实现
+#[cfg(not(feature = "install"))]
+pub fn latest_version() -> Result<String, String> {
+    Err("查询 sing-box 版本需要 install 特性".to_string())
+}
+
+/// 未启用 install 特性时的占位实现
+#[cfg(not(feature = "install"))]
+pub fn install_singbox(_version: Option<&str>, _prefix: &str) -> Result<(String, String), String> {
+    Err("install-singbox 需要 install 特性".to_string())
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_asset_name() {
+        assert_eq!(normalize_version(" v1.12.8 "), "1.12.8");
+        assert_eq!(
+            release_asset_name("1.12.8", "linux", "x86_64").unwrap(),
+            "sing-box-1.12.8-linux-amd64.tar.gz"
+        );
+        assert_eq!(
+            release_asset_name("1.12.8", "macos", "aarch64").unwrap(),
+            "sing-box-1.12.8-darwin-arm64.tar.gz"
+        );
+        assert!(release_asset_name("1.12.8", "windows", "x86_64").is_err());
+        assert!(release_asset_name("1.12.8", "linux", "sparc64").is_err());
+    }
+
+    #[test]
+    fn test_find_asset() {
+        let digest = "ab".repeat(32);
+        let release = serde_json::json!({
+            "tag_name": "v1.12.8",
+            "assets": [
+                { "name": "a.tar.gz", "browser_download_url": "https://x/a", "digest": format!("sha256:{}", digest) },
+                { "name": "b.tar.gz", "browser_download_url": "https://x/b", "digest": null }
+            ]
+        });
+        let asset = find_asset(&release, "a.tar.gz").unwrap();
+        assert_eq!(asset.url, "https://x/a");
+        assert_eq!(asset.sha256, digest);
+        assert!(find_asset(&release, "b.tar.gz").is_err());
+        assert!(find_asset(&release, "c.tar.gz").is_err());
+    }
+
+    #[cfg(feature = "install")]
+    #[test]
+    fn test_extract_binary() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::fast(),
+        ));
+        for (path, data) in [
+            ("sing-box-1.12.8-linux-amd64/LICENSE", &b"license"[..]),
+            ("sing-box-1.12.8-linux-amd64/sing-box", &b"\x7fELF"[..]),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, data).unwrap();
+        }
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+        assert_eq!(extract_binary(&archive).unwrap(), b"\x7fELF");
+        assert!(extract_binary(b"not a tarball").is_err());
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
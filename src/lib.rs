@@ -0,0 +1,15 @@
+//! ezsingbox - sing-box 配置数据模型与自动化生成库
+//!
+//! - [`singboxconfig`] - sing-box 配置文件的数据结构（入站、出站、路由、完整配置与校验）
+//! - [`dns`] - DNS 配置数据结构与服务端 / 客户端预设
+//! - [`autoconfig`] - 自动生成多协议入站、用户与 TLS 配置
+//! - [`sharelink`] - 各协议分享链接生成与解析
+//! - [`convert`] - 从 Xray 等其他代理工具的配置导入
+//!
+//! 命令行程序（`main.rs`）只是此库的一个使用者，环境变量、配置文件与子命令均不属于库的公开接口
+
+pub mod autoconfig;
+pub mod convert;
+pub mod dns;
+pub mod sharelink;
+pub mod singboxconfig;
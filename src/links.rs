@@ -0,0 +1,237 @@
+//! 代理列表格式模块
+//!
+//! 将生成的代理转换为分享链接或 iOS 客户端（Surge、Quantumult X、Loon）的代理行，
+//! 客户端不支持的协议会被跳过
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::template::template_context;
+use ezsingbox::autoconfig::MultiProtocolResult;
+
+/// 代理列表格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkFormat {
+    /// 分享链接（每行一个 URI）
+    #[default]
+    Uri,
+    /// Surge `[Proxy]` 代理行（Hysteria2、TUIC）
+    Surge,
+    /// Quantumult X `[server_local]` 服务器条目（VLESS Reality / WebSocket）
+    QuantumultX,
+    /// Loon `[Proxy]` 代理行（Hysteria2、VLESS Reality / WebSocket）
+    Loon,
+    /// Base64 编码的分享链接列表（v2rayN / v2rayNG 等客户端的订阅格式）
+    Base64,
+}
+
+impl fmt::Display for LinkFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkFormat::Uri => write!(f, "uri"),
+            LinkFormat::Surge => write!(f, "surge"),
+            LinkFormat::QuantumultX => write!(f, "quantumult-x"),
+            LinkFormat::Loon => write!(f, "loon"),
+            LinkFormat::Base64 => write!(f, "base64"),
+        }
+    }
+}
+
+impl FromStr for LinkFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "uri" | "link" | "links" => Ok(LinkFormat::Uri),
+            "surge" => Ok(LinkFormat::Surge),
+            "quantumult-x" | "quantumultx" | "qx" => Ok(LinkFormat::QuantumultX),
+            "loon" => Ok(LinkFormat::Loon),
+            "base64" | "v2rayn" => Ok(LinkFormat::Base64),
+            _ => Err(format!(
+                "无效的代理列表格式: {}（可选 uri/surge/quantumult-x/loon/base64）",
+                s
+            )),
+        }
+    }
+}
+
+//============================================================================
+// 各客户端的代理行
+//============================================================================
+
+/// 取字符串字段（缺失时为空字符串）
+fn field<'a>(proxy: &'a Value, key: &str) -> &'a str {
+    proxy[key].as_str().unwrap_or_default()
+}
+
+/// 代理名称（协议-用户），去掉各客户端用作分隔符的逗号和等号
+fn proxy_name(proxy: &Value) -> String {
+    format!("{}-{}", field(proxy, "label"), field(proxy, "user")).replace([',', '='], " ")
+}
+
+/// Surge 代理行
+fn surge_line(proxy: &Value) -> Option<String> {
+    let (name, server, port) = (proxy_name(proxy), field(proxy, "server"), &proxy["port"]);
+    let (password, sni) = (field(proxy, "password"), field(proxy, "sni"));
+    match field(proxy, "protocol") {
+        // Surge 的 Hysteria2 不支持 salamander 混淆
+        "hysteria2" if proxy["obfs_password"].is_null() => Some(format!(
+            "{name} = hysteria2, {server}, {port}, password={password}, sni={sni}"
+        )),
+        "tuic" => Some(format!(
+            "{name} = tuic-v5, {server}, {port}, password={password}, uuid={}, sni={sni}, alpn=h3",
+            field(proxy, "uuid")
+        )),
+        _ => None,
+    }
+}
+
+/// Quantumult X 服务器条目
+fn quantumult_x_line(proxy: &Value) -> Option<String> {
+    let (name, server, port) = (proxy_name(proxy), field(proxy, "server"), &proxy["port"]);
+    let (uuid, sni) = (field(proxy, "uuid"), field(proxy, "sni"));
+    match field(proxy, "protocol") {
+        "vless-reality" => Some(format!(
+            "vless={server}:{port}, method=none, password={uuid}, obfs=over-tls, obfs-host={sni}, \
+             reality-base64-pubkey={}, reality-hex-shortid={}, vless-flow=xtls-rprx-vision, \
+             udp-relay=true, tag={name}",
+            field(proxy, "public_key"),
+            field(proxy, "short_id")
+        )),
+        "vless-ws" => Some(format!(
+            "vless={server}:{port}, method=none, password={uuid}, obfs=wss, obfs-host={sni}, \
+             obfs-uri={}, tls-verification=true, udp-relay=true, tag={name}",
+            field(proxy, "path")
+        )),
+        _ => None,
+    }
+}
+
+/// Loon 代理行
+fn loon_line(proxy: &Value) -> Option<String> {
+    let (name, server, port) = (proxy_name(proxy), field(proxy, "server"), &proxy["port"]);
+    let (uuid, sni) = (field(proxy, "uuid"), field(proxy, "sni"));
+    match field(proxy, "protocol") {
+        "hysteria2" => {
+            let mut line = format!(
+                "{name} = Hysteria2,{server},{port},\"{}\",sni={sni},udp=true",
+                field(proxy, "password")
+            );
+            if let Some(obfs) = proxy["obfs_password"].as_str() {
+                line.push_str(&format!(",salamander-password={}", obfs));
+            }
+            Some(line)
+        }
+        "vless-reality" => Some(format!(
+            "{name} = VLESS,{server},{port},\"{uuid}\",transport=tcp,flow=xtls-rprx-vision,\
+             public-key=\"{}\",short-id={},udp=true,over-tls=true,sni={sni}",
+            field(proxy, "public_key"),
+            field(proxy, "short_id")
+        )),
+        "vless-ws" => Some(format!(
+            "{name} = VLESS,{server},{port},\"{uuid}\",transport=ws,path={},host={sni},\
+             udp=true,over-tls=true,sni={sni}",
+            field(proxy, "path")
+        )),
+        _ => None,
+    }
+}
+
+//============================================================================
+// 渲染
+//============================================================================
+
+/// 按指定格式渲染代理列表（每行一个代理），user 不为空时只包含该用户
+/// Base64 格式为全部分享链接（每行一个）整体编码后的结果
+pub fn render_links(
+    result: &MultiProtocolResult,
+    format: LinkFormat,
+    user: Option<&str>,
+) -> Result<String, String> {
+    if format == LinkFormat::Base64 {
+        let links = render_links(result, LinkFormat::Uri, user)?;
+        return Ok(base64::engine::general_purpose::STANDARD.encode(links));
+    }
+    let context = template_context(result, user)?;
+    let mut out = String::new();
+    for proxy in context["proxies"].as_array().into_iter().flatten() {
+        let line = match format {
+            LinkFormat::Uri | LinkFormat::Base64 => proxy["link"].as_str().map(str::to_string),
+            LinkFormat::Surge => surge_line(proxy),
+            LinkFormat::QuantumultX => quantumult_x_line(proxy),
+            LinkFormat::Loon => loon_line(proxy),
+        };
+        match line {
+            Some(line) => {
+                out.push_str(&line);
+                out.push('\n');
+            }
+            None => warn!(
+                %format,
+                protocol = field(proxy, "protocol"),
+                "客户端不支持该协议，已跳过"
+            ),
+        }
+    }
+    if out.is_empty() {
+        return Err(format!("没有 {} 格式支持的协议", format));
+    }
+    Ok(out)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezsingbox::autoconfig::MultiProtocolBuilder;
+
+    #[test]
+    fn test_render_links() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip("1.2.3.4".parse().unwrap())
+            .domain("example.com")
+            .enable_anytls(443)
+            .enable_hysteria2(2053)
+            .enable_vless_reality(2096)
+            .build()
+            .unwrap();
+        let password = &result.hysteria2.as_ref().unwrap().info.users[0].password;
+
+        let uri = render_links(&result, LinkFormat::Uri, None).unwrap();
+        assert_eq!(uri.lines().count(), 3);
+        assert!(uri.starts_with("anytls://"));
+
+        let surge = render_links(&result, LinkFormat::Surge, None).unwrap();
+        assert_eq!(
+            surge,
+            format!(
+                "Hysteria2-default = hysteria2, example.com, 2053, password={}, sni=example.com\n",
+                password
+            )
+        );
+
+        let qx = render_links(&result, LinkFormat::QuantumultX, None).unwrap();
+        assert!(qx.starts_with("vless=1.2.3.4:2096, method=none, password="));
+        assert!(qx.trim_end().ends_with("tag=VLESS Reality-default"));
+
+        let loon = render_links(&result, LinkFormat::Loon, Some("default")).unwrap();
+        assert_eq!(loon.lines().count(), 2);
+        assert!(loon.contains("VLESS Reality-default = VLESS,1.2.3.4,2096,\""));
+
+        let encoded = render_links(&result, LinkFormat::Base64, None).unwrap();
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        assert_eq!(String::from_utf8(decoded).unwrap(), uri);
+
+        assert_eq!("qx".parse::<LinkFormat>(), Ok(LinkFormat::QuantumultX));
+        assert!("clash".parse::<LinkFormat>().is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! 订阅服务监听地址
+//!
+//! EZ_SUBSCRIBE_LISTEN 接受逗号分隔的多个地址（如 `0.0.0.0:8080,[::]:8080`），
+//! 或 `dual` / `dual:<端口>` 简写（同时监听全部 IPv4 与 IPv6 地址，默认端口 8080）。
+//! 系统默认让 `[::]` 同时接受 IPv4 连接（Linux 的 bindv6only=0）时，同端口的 `0.0.0.0` 无需再单独监听。
+//! `unix:///run/ezsingbox.sock` 形式的地址监听 Unix 套接字，便于放在 nginx / Caddy 之后而不额外暴露 TCP 端口
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use tracing::info;
+
+use crate::env::env_string;
+
+/// 默认监听地址
+pub const DEFAULT_LISTEN: &str = "0.0.0.0:8080";
+
+/// `dual` 简写的默认端口
+const DEFAULT_PORT: u16 = 8080;
+
+/// Unix 套接字地址前缀
+const UNIX_PREFIX: &str = "unix://";
+
+/// Unix 套接字文件权限（属组内的反向代理可连接）
+#[cfg(unix)]
+const UNIX_SOCKET_MODE: u32 = 0o660;
+
+/// 监听地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    /// TCP 地址
+    Tcp(SocketAddr),
+    /// Unix 套接字路径
+    Unix(PathBuf),
+}
+
+impl ListenAddr {
+    /// TCP 端口（Unix 套接字为 None）
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            ListenAddr::Tcp(addr) => Some(addr.port()),
+            ListenAddr::Unix(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddr::Tcp(addr) => write!(f, "{}", addr),
+            ListenAddr::Unix(path) => write!(f, "{}{}", UNIX_PREFIX, path.display()),
+        }
+    }
+}
+
+/// 第一个 TCP 监听端口
+pub fn first_tcp_port(addrs: &[ListenAddr]) -> Option<u16> {
+    addrs.iter().find_map(ListenAddr::port)
+}
+
+/// 解析监听地址列表（逗号分隔的 ip:port、`unix://<路径>`，或 `dual` / `dual:<端口>`），去除重复地址
+pub fn parse_listen_list(raw: &str) -> Result<Vec<ListenAddr>, String> {
+    let mut addrs = Vec::new();
+    for item in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if let Some(path) = item.strip_prefix(UNIX_PREFIX) {
+            if path.is_empty() {
+                return Err(format!("无效的监听地址: {}", item));
+            }
+            let addr = ListenAddr::Unix(PathBuf::from(path));
+            if !addrs.contains(&addr) {
+                addrs.push(addr);
+            }
+            continue;
+        }
+        let parsed = match item.strip_prefix("dual") {
+            Some("") => dual_stack(DEFAULT_PORT),
+            Some(port) => {
+                let port = port
+                    .strip_prefix(':')
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(|| format!("无效的监听地址: {}", item))?;
+                dual_stack(port)
+            }
+            None => vec![
+                item.parse()
+                    .map_err(|_| format!("无效的监听地址: {}", item))?,
+            ],
+        };
+        for addr in parsed.into_iter().map(ListenAddr::Tcp) {
+            if !addrs.contains(&addr) {
+                addrs.push(addr);
+            }
+        }
+    }
+    if addrs.is_empty() {
+        return Err("未指定监听地址".to_string());
+    }
+    Ok(addrs)
+}
+
+/// 同一端口上的 IPv6 与 IPv4 全部地址
+fn dual_stack(port: u16) -> Vec<SocketAddr> {
+    vec![
+        SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port),
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port),
+    ]
+}
+
+/// 从环境变量读取监听地址（EZ_SUBSCRIBE_LISTEN，默认 0.0.0.0:8080）
+pub fn listen_addrs_from_env() -> Result<Vec<ListenAddr>, String> {
+    let raw = env_string("EZ_SUBSCRIBE_LISTEN").unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+    parse_listen_list(&raw).map_err(|e| format!("EZ_SUBSCRIBE_LISTEN 无效: {}", e))
+}
+
+/// 依次监听全部地址（IPv6 优先），返回实际监听的地址与服务
+/// `[::]` 已同时接受 IPv4 连接导致同端口的 `0.0.0.0` 被占用时跳过该地址
+pub fn bind_all(addrs: &[ListenAddr]) -> Result<Vec<(ListenAddr, tiny_http::Server)>, String> {
+    let mut ordered = addrs.to_vec();
+    ordered.sort_by_key(|a| matches!(a, ListenAddr::Tcp(addr) if addr.is_ipv4()));
+    let mut servers: Vec<(ListenAddr, tiny_http::Server)> = Vec::new();
+    for listen in ordered {
+        let addr = match &listen {
+            ListenAddr::Tcp(addr) => *addr,
+            ListenAddr::Unix(path) => {
+                let server = bind_unix(path).map_err(|e| format!("监听 {} 失败: {}", listen, e))?;
+                servers.push((listen, server));
+                continue;
+            }
+        };
+        match tiny_http::Server::http(addr) {
+            Ok(server) => servers.push((listen, server)),
+            Err(e) => {
+                let in_use = e
+                    .downcast_ref::<std::io::Error>()
+                    .is_some_and(|e| e.kind() == std::io::ErrorKind::AddrInUse);
+                let covered = addr.ip() == IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+                    && servers.iter().any(|(bound, _)| {
+                        matches!(bound, ListenAddr::Tcp(b)
+                            if b.ip() == IpAddr::V6(Ipv6Addr::UNSPECIFIED) && b.port() == addr.port())
+                    });
+                if in_use && covered {
+                    info!(%addr, "[::]:{} 已同时接受 IPv4 连接，跳过", addr.port());
+                    continue;
+                }
+                return Err(format!("监听 {} 失败: {}", addr, e));
+            }
+        }
+    }
+    Ok(servers)
+}
+
+/// 监听 Unix 套接字（删除上次运行遗留的套接字文件，权限设为 0660）
+#[cfg(unix)]
+fn bind_unix(path: &std::path::Path) -> Result<tiny_http::Server, String> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => {
+            std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        }
+        Ok(_) => return Err("路径已存在且不是套接字".to_string()),
+        Err(_) => {}
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let server = tiny_http::Server::http_unix(path).map_err(|e| e.to_string())?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(UNIX_SOCKET_MODE))
+        .map_err(|e| e.to_string())?;
+    Ok(server)
+}
+
+/// 非 Unix 系统不支持 Unix 套接字
+#[cfg(not(unix))]
+fn bind_unix(_path: &std::path::Path) -> Result<tiny_http::Server, String> {
+    Err("Unix 套接字仅支持 Unix 系统".to_string())
+}
+
+/// 把多个服务收到的请求合并到同一个通道（每个服务一个接收线程）
+pub fn merge_incoming(servers: Vec<tiny_http::Server>) -> mpsc::Receiver<tiny_http::Request> {
+    let (tx, rx) = mpsc::channel();
+    for server in servers {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for req in server.incoming_requests() {
+                if tx.send(req).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    rx
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_list() {
+        let tcp = |s: &str| ListenAddr::Tcp(s.parse().unwrap());
+        let addrs = parse_listen_list("0.0.0.0:8080, [::]:8080").unwrap();
+        assert_eq!(addrs, vec![tcp("0.0.0.0:8080"), tcp("[::]:8080")]);
+        assert_eq!(
+            parse_listen_list("dual").unwrap(),
+            vec![tcp("[::]:8080"), tcp("0.0.0.0:8080")]
+        );
+        // 与 dual 重复的地址只保留一个
+        let addrs = parse_listen_list("dual:8443,127.0.0.1:9090,0.0.0.0:8443").unwrap();
+        assert_eq!(addrs.len(), 3);
+        assert_eq!(addrs[2], tcp("127.0.0.1:9090"));
+
+        let addrs = parse_listen_list("unix:///run/ezsingbox.sock,127.0.0.1:9090").unwrap();
+        assert_eq!(
+            addrs[0],
+            ListenAddr::Unix(PathBuf::from("/run/ezsingbox.sock"))
+        );
+        assert_eq!(addrs[0].to_string(), "unix:///run/ezsingbox.sock");
+        assert_eq!(first_tcp_port(&addrs), Some(9090));
+        assert_eq!(first_tcp_port(&addrs[..1]), None);
+
+        assert!(parse_listen_list("0.0.0.0").is_err());
+        assert!(parse_listen_list("dual:x").is_err());
+        assert!(parse_listen_list("dualstack").is_err());
+        assert!(parse_listen_list(" , ").is_err());
+        assert!(parse_listen_list("unix://").is_err());
+    }
+
+    #[test]
+    fn test_bind_all() {
+        let servers = bind_all(&[ListenAddr::Tcp("127.0.0.1:0".parse().unwrap())]).unwrap();
+        assert_eq!(servers.len(), 1);
+        let port = servers[0].1.server_addr().to_ip().unwrap().port();
+        // 端口已被占用且没有覆盖它的 [::] 监听时报错
+        let taken = ListenAddr::Tcp(format!("127.0.0.1:{}", port).parse().unwrap());
+        assert!(bind_all(&[taken]).is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let dir = std::env::temp_dir().join(format!("ezsingbox-listen-{}", std::process::id()));
+            let socket = ListenAddr::Unix(dir.join("sub.sock"));
+            drop(bind_all(std::slice::from_ref(&socket)).unwrap());
+            // 遗留的套接字文件在下次监听时被替换
+            let servers = bind_all(std::slice::from_ref(&socket)).unwrap();
+            let mode = std::fs::metadata(dir.join("sub.sock"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, UNIX_SOCKET_MODE);
+            drop(servers);
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+    }
+}
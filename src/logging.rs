@@ -0,0 +1,83 @@
+//! 日志模块
+//!
+//! 使用 tracing 输出结构化日志到标准错误，标准输出只保留配置、分享链接等命令结果；
+//! `EZ_LOG_FORMAT=json` 时每条日志输出为一行 JSON，便于容器日志采集
+
+use std::fmt;
+use std::str::FromStr;
+
+use tracing::level_filters::LevelFilter;
+
+/// 日志输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// 人类可读文本
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("无效的日志格式: {}（可选 text/json）", s)),
+        }
+    }
+}
+
+/// 将 sing-box 日志级别映射为 tracing 级别
+/// sing-box 的 fatal / panic 对应 error，无法识别时使用 info
+fn level_filter(level: &str) -> LevelFilter {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => LevelFilter::TRACE,
+        "debug" => LevelFilter::DEBUG,
+        "warn" => LevelFilter::WARN,
+        "error" | "fatal" | "panic" => LevelFilter::ERROR,
+        _ => LevelFilter::INFO,
+    }
+}
+
+/// 初始化全局日志订阅器（重复调用时忽略）
+pub fn init(format: LogFormat, level: &str) {
+    let builder = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(level_filter(level))
+        .with_target(false);
+    let _ = match format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).try_init(),
+    };
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_level() {
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!("Text".parse::<LogFormat>(), Ok(LogFormat::Text));
+        assert!("logfmt".parse::<LogFormat>().is_err());
+        assert_eq!(level_filter("debug"), LevelFilter::DEBUG);
+        assert_eq!(level_filter("fatal"), LevelFilter::ERROR);
+        assert_eq!(level_filter("unknown"), LevelFilter::INFO);
+    }
+}
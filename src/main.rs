@@ -1,49 +1,174 @@
 //! ezsingbox - 简易sing-box 配置生成器和运行器
 
-mod autoconfig;
+mod annotate;
+mod audit;
+mod backup;
+mod bundle;
+mod cert;
+mod check;
+mod cli;
 mod commands;
+mod compose;
+mod compress;
 mod config;
-mod dns;
+mod configfile;
+mod deploy;
+mod diff;
 mod env;
-mod protocol;
-mod sharelink;
-mod singboxconfig;
+mod envschema;
+mod error;
+mod firewall;
+mod fleet;
+mod health;
+mod i18n;
+mod install;
+mod links;
+mod listen;
+mod logging;
+mod notify;
+mod preflight;
+mod proxy;
+mod ratelimit;
+mod readme;
+mod redact;
+mod signedlink;
+mod state;
+mod stats;
+mod subauth;
+mod summary;
+mod template;
+mod upgrade;
+mod urltest;
+mod users;
 mod utils;
 
 use std::process::ExitCode;
 
-use commands::{cmd_generate, cmd_run, print_usage};
+use clap::FromArgMatches;
+
+use cli::{CertAction, Cli, Commands, ExportTarget, UserAction};
+use commands::{
+    cmd_audit, cmd_cert_status, cmd_check, cmd_compose, cmd_convert, cmd_deploy,
+    cmd_export_peer_outbound, cmd_export_readme, cmd_export_template, cmd_firewall, cmd_fleet,
+    cmd_generate, cmd_health, cmd_install_singbox, cmd_links, cmd_lint, cmd_migrate, cmd_rollback,
+    cmd_run, cmd_serve, cmd_stats, cmd_support_bundle, cmd_test, cmd_user_add, cmd_user_token,
+};
+use configfile::FileConfig;
+use error::{ErrorFormat, EzError};
+use i18n::{Msg, tf};
+use logging::LogFormat;
+use tracing::{error, warn};
 
 fn main() -> ExitCode {
-    let mut args = std::env::args();
-    let _exe = args.next();
-    let sub = args.next().unwrap_or_else(|| "generate".to_string());
-
-    match sub.as_str() {
-        "generate" => match cmd_generate() {
-            Ok(_) => ExitCode::SUCCESS,
-            Err(e) => {
-                eprintln!("❌ {}", e);
-                ExitCode::from(1)
-            }
+    // 解析前确定语言，帮助信息按语言输出
+    let args: Vec<String> = std::env::args().collect();
+    i18n::set_lang(i18n::lang_from_args(&args));
+    let matches = cli::localized_command(i18n::lang()).get_matches_from(args);
+    let cli = match Cli::from_arg_matches(&matches) {
+        Ok(cli) => cli,
+        Err(e) => e.exit(),
+    };
+    env::set_overrides(cli.options.to_overrides());
+    match run(cli.command.unwrap_or(Commands::Generate)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e.render(error_format()));
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+/// 加载配置并执行子命令
+fn run(command: Commands) -> Result<ExitCode, EzError> {
+    load_file_config().map_err(EzError::Env)?;
+    // 配置文件可能指定 EZ_LANG
+    i18n::set_lang(i18n::lang_from_env());
+    init_logging();
+    check_env()?;
+
+    match command {
+        Commands::Generate => cmd_generate().map(|_| ExitCode::SUCCESS),
+        Commands::Run => cmd_run(),
+        Commands::Serve => cmd_serve(),
+        Commands::Check => cmd_check().map(|_| ExitCode::SUCCESS),
+        Commands::Lint { path } => cmd_lint(&path),
+        Commands::Migrate { source } => cmd_migrate(&source).map(|_| ExitCode::SUCCESS),
+        Commands::Convert { from, input } => cmd_convert(from, &input).map(|_| ExitCode::SUCCESS),
+        Commands::SupportBundle => cmd_support_bundle().map(|_| ExitCode::SUCCESS),
+        Commands::Health => cmd_health(),
+        Commands::Test => cmd_test(),
+        Commands::Rollback => cmd_rollback().map(|_| ExitCode::SUCCESS),
+        Commands::Compose => cmd_compose().map(|_| ExitCode::SUCCESS),
+        Commands::Fleet => cmd_fleet().map(|_| ExitCode::SUCCESS),
+        Commands::Deploy { hosts } => cmd_deploy(&hosts),
+        Commands::InstallSingbox { version } => {
+            cmd_install_singbox(version.as_deref()).map(|_| ExitCode::SUCCESS)
+        }
+        Commands::Firewall { format } => cmd_firewall(format).map(|_| ExitCode::SUCCESS),
+        Commands::Links { format, expire } => {
+            cmd_links(format, expire.as_ref()).map(|_| ExitCode::SUCCESS)
+        }
+        Commands::Cert { action } => match action {
+            CertAction::Status => cmd_cert_status().map(|_| ExitCode::SUCCESS),
         },
-        "run" => match cmd_run() {
-            Ok(code) => code,
-            Err(e) => {
-                eprintln!("❌ {}", e);
-                ExitCode::from(1)
+        Commands::Export { target } => match target {
+            ExportTarget::PeerOutbound => cmd_export_peer_outbound().map(|_| ExitCode::SUCCESS),
+            ExportTarget::Readme => cmd_export_readme().map(|_| ExitCode::SUCCESS),
+            ExportTarget::Template => cmd_export_template().map(|_| ExitCode::SUCCESS),
+        },
+        Commands::Stats => cmd_stats().map(|_| ExitCode::SUCCESS),
+        Commands::Audit { user, since, limit } => {
+            cmd_audit(user.as_deref(), since.as_ref(), limit).map(|_| ExitCode::SUCCESS)
+        }
+        Commands::User { action } => match action {
+            UserAction::Add { name, expires } => {
+                cmd_user_add(&name, expires.as_deref()).map(|_| ExitCode::SUCCESS)
+            }
+            UserAction::Token { name, revoke } => {
+                cmd_user_token(&name, revoke).map(|_| ExitCode::SUCCESS)
             }
         },
-        // "serve" => match cmd_serve() {
-        //     Ok(code) => code,
-        //     Err(e) => {
-        //         eprintln!("❌ {}", e);
-        //         ExitCode::from(1)
-        //     }
-        // },
-        _ => {
-            print_usage();
-            ExitCode::from(2)
+    }
+}
+
+/// 错误输出格式（EZ_ERROR_FORMAT，无效取值时回退为文本）
+fn error_format() -> ErrorFormat {
+    env::env_string("EZ_ERROR_FORMAT")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default()
+}
+
+/// 初始化日志（EZ_LOG_FORMAT，级别沿用 EZ_LOG_LEVEL）
+fn init_logging() {
+    let format = env::env_string("EZ_LOG_FORMAT")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(LogFormat::Text);
+    let level = env::env_string("EZ_LOG_LEVEL").unwrap_or_else(|| "info".to_string());
+    logging::init(format, &level);
+}
+
+/// 加载声明式配置文件（--config / EZ_FILE）
+fn load_file_config() -> Result<(), String> {
+    if let Some(path) = env::env_string("EZ_FILE") {
+        let values = FileConfig::load(&path)?.to_env_values()?;
+        env::set_file_values(values);
+    }
+    Ok(())
+}
+
+/// 校验变量拼写与取值，存在无效取值时返回错误
+fn check_env() -> Result<(), EzError> {
+    let issues = envschema::validate_env();
+    for issue in &issues {
+        if issue.is_error() {
+            error!("{}", issue);
+        } else {
+            warn!("{}", issue);
         }
     }
+    let errors = issues.iter().filter(|i| i.is_error()).count();
+    if errors > 0 {
+        return Err(EzError::Env(tf(Msg::InvalidVars, &[&errors])));
+    }
+    Ok(())
 }
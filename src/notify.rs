@@ -0,0 +1,458 @@
+//! 生命周期通知（Telegram 与 Webhook）
+//!
+//! 设置 EZ_TELEGRAM_BOT_TOKEN 与 EZ_TELEGRAM_CHAT_ID 后，`generate` 写入配置后把分享链接与订阅导入链接
+//! 发送到指定会话，run 模式在 sing-box 异常退出或升级重启时发送告警。
+//! 设置 EZ_WEBHOOK_URL 后，配置生成、凭证轮换、sing-box 重启 / 异常退出、证书续期、健康检查失败等事件
+//! 以 JSON POST 发送到该地址（EZ_WEBHOOK_EVENTS 可只选部分事件），设置 EZ_WEBHOOK_SECRET 时附带 HMAC-SHA256 签名。
+//! 发送失败只记录警告，不影响主流程
+
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tracing::{info, warn};
+
+use crate::cert::unix_now;
+use crate::config::ShareLink;
+use crate::env::env_string;
+use ezsingbox::autoconfig::MultiProtocolResult;
+use ezsingbox::sharelink::sing_box_import_remote_profile_uri;
+
+/// Webhook 签名请求头
+pub const SIGNATURE_HEADER: &str = "X-Ezsingbox-Signature";
+
+/// Webhook 事件名请求头
+pub const EVENT_HEADER: &str = "X-Ezsingbox-Event";
+
+/// Telegram 单条消息的最大长度
+const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+
+/// 发送通知的超时
+#[cfg(feature = "probe")]
+const NOTIFY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Telegram 机器人通知
+#[derive(Clone)]
+pub struct TelegramNotifier {
+    /// 机器人令牌
+    token: String,
+    /// 会话 ID（用户、群组或频道）
+    chat_id: String,
+}
+
+impl fmt::Debug for TelegramNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TelegramNotifier")
+            .field("chat_id", &self.chat_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TelegramNotifier {
+    /// 创建通知器
+    pub fn new(token: impl Into<String>, chat_id: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            chat_id: chat_id.into(),
+        }
+    }
+
+    /// 从环境变量读取（EZ_TELEGRAM_BOT_TOKEN / EZ_TELEGRAM_CHAT_ID 都未设置时为 None，只设置其一时报错）
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match (
+            env_string("EZ_TELEGRAM_BOT_TOKEN"),
+            env_string("EZ_TELEGRAM_CHAT_ID"),
+        ) {
+            (Some(token), Some(chat_id)) => Ok(Some(Self::new(token, chat_id))),
+            (None, None) => Ok(None),
+            _ => Err("EZ_TELEGRAM_BOT_TOKEN 与 EZ_TELEGRAM_CHAT_ID 需要同时设置".to_string()),
+        }
+    }
+
+    /// 发送消息（超过长度限制时按行拆分为多条）
+    pub fn send(&self, text: &str) -> Result<(), String> {
+        for chunk in split_message(text, TELEGRAM_MESSAGE_LIMIT) {
+            self.send_one(&chunk)?;
+        }
+        Ok(())
+    }
+
+    /// 发送消息，失败时只记录警告
+    pub fn notify(&self, text: &str) {
+        match self.send(text) {
+            Ok(()) => info!(chat_id = %self.chat_id, "Telegram 通知已发送"),
+            Err(e) => warn!(chat_id = %self.chat_id, "发送 Telegram 通知失败: {}", e),
+        }
+    }
+
+    /// 调用 sendMessage 接口
+    #[cfg(feature = "probe")]
+    fn send_one(&self, text: &str) -> Result<(), String> {
+        use ezsingbox::autoconfig::http_agent;
+
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.token);
+        let body = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": text,
+            "disable_web_page_preview": true,
+        });
+        // 错误信息中的 URL 含机器人令牌，不直接输出
+        http_agent(NOTIFY_TIMEOUT)
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .send(body.to_string())
+            .map_err(|e| match e {
+                ureq::Error::StatusCode(code) => format!("Telegram 接口返回 HTTP {}", code),
+                _ => "无法连接 Telegram 接口".to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// 未启用 probe 特性时的占位实现
+    #[cfg(not(feature = "probe"))]
+    fn send_one(&self, _text: &str) -> Result<(), String> {
+        Err("Telegram 通知需要 probe 特性".to_string())
+    }
+}
+
+//============================================================================
+// Webhook
+//============================================================================
+
+/// 生命周期事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// generate 写入了配置
+    ConfigGenerated,
+    /// 凭证已轮换
+    CredentialsRotated,
+    /// sing-box 已重启（升级）或重新加载配置
+    SingBoxRestarted,
+    /// sing-box 异常退出
+    SingBoxExited,
+    /// ACME 证书已续期
+    CertificateRenewed,
+    /// 健康检查失败
+    HealthCheckFailed,
+}
+
+impl Event {
+    /// 全部事件
+    pub const ALL: [Event; 6] = [
+        Event::ConfigGenerated,
+        Event::CredentialsRotated,
+        Event::SingBoxRestarted,
+        Event::SingBoxExited,
+        Event::CertificateRenewed,
+        Event::HealthCheckFailed,
+    ];
+
+    /// 事件名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Event::ConfigGenerated => "config.generated",
+            Event::CredentialsRotated => "credentials.rotated",
+            Event::SingBoxRestarted => "singbox.restarted",
+            Event::SingBoxExited => "singbox.exited",
+            Event::CertificateRenewed => "certificate.renewed",
+            Event::HealthCheckFailed => "health.failed",
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Event {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Event::ALL
+            .into_iter()
+            .find(|e| e.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let names: Vec<_> = Event::ALL.iter().map(Event::as_str).collect();
+                format!("未知的事件: {}（可选 {}）", s, names.join(" / "))
+            })
+    }
+}
+
+/// Webhook 通知
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    /// 接收地址
+    url: String,
+    /// 签名密钥
+    secret: Option<String>,
+    /// 发送的事件（None 时发送全部事件）
+    events: Option<Vec<Event>>,
+}
+
+impl fmt::Debug for WebhookNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookNotifier")
+            .field("url", &self.url)
+            .field("events", &self.events)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WebhookNotifier {
+    /// 创建 Webhook 通知
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+            events: None,
+        }
+    }
+
+    /// 设置签名密钥
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// 只发送指定的事件
+    pub fn with_events(mut self, events: Vec<Event>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// 从环境变量读取（EZ_WEBHOOK_URL 未设置时为 None）
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Some(url) = env_string("EZ_WEBHOOK_URL") else {
+            return Ok(None);
+        };
+        let mut webhook = Self::new(url);
+        if let Some(secret) = env_string("EZ_WEBHOOK_SECRET") {
+            webhook = webhook.with_secret(secret);
+        }
+        if let Some(raw) = env_string("EZ_WEBHOOK_EVENTS") {
+            let events = raw
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::parse)
+                .collect::<Result<Vec<Event>, _>>()
+                .map_err(|e| format!("EZ_WEBHOOK_EVENTS: {}", e))?;
+            webhook = webhook.with_events(events);
+        }
+        Ok(Some(webhook))
+    }
+
+    /// 是否发送该事件
+    pub fn wants(&self, event: Event) -> bool {
+        self.events.as_ref().is_none_or(|e| e.contains(&event))
+    }
+
+    /// 事件负载：`{ event, timestamp, data }`
+    pub fn payload(event: Event, data: Value, now: i64) -> String {
+        json!({
+            "event": event.as_str(),
+            "timestamp": now,
+            "data": data,
+        })
+        .to_string()
+    }
+
+    /// 负载签名（`sha256=<十六进制 HMAC>`），未设置密钥时为 None
+    pub fn signature(&self, body: &str) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC 接受任意长度的密钥");
+        mac.update(body.as_bytes());
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Some(format!("sha256={}", hex))
+    }
+
+    /// 发送事件，失败时只记录警告
+    pub fn emit(&self, event: Event, data: Value) {
+        if !self.wants(event) {
+            return;
+        }
+        let body = Self::payload(event, data, unix_now());
+        match self.post(event, &body) {
+            Ok(()) => info!(%event, "Webhook 通知已发送"),
+            Err(e) => warn!(%event, url = %self.url, "发送 Webhook 通知失败: {}", e),
+        }
+    }
+
+    /// 发送 JSON POST 请求
+    #[cfg(feature = "probe")]
+    fn post(&self, event: Event, body: &str) -> Result<(), String> {
+        use ezsingbox::autoconfig::http_agent;
+
+        let mut request = http_agent(NOTIFY_TIMEOUT)
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header(EVENT_HEADER, event.as_str());
+        if let Some(signature) = self.signature(body) {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        request.send(body).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// 未启用 probe 特性时的占位实现
+    #[cfg(not(feature = "probe"))]
+    fn post(&self, _event: Event, _body: &str) -> Result<(), String> {
+        Err("Webhook 通知需要 probe 特性".to_string())
+    }
+}
+
+//============================================================================
+// 通知分发
+//============================================================================
+
+/// 已配置的全部通知方式
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    /// Telegram 通知
+    pub telegram: Option<TelegramNotifier>,
+    /// Webhook 通知
+    pub webhook: Option<WebhookNotifier>,
+}
+
+impl Notifier {
+    /// 从环境变量读取全部通知方式
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self {
+            telegram: TelegramNotifier::from_env()?,
+            webhook: WebhookNotifier::from_env()?,
+        })
+    }
+
+    /// 发送事件：Webhook 收到 data，Telegram 收到 text（text 为 None 时不发送到 Telegram）
+    pub fn emit(&self, event: Event, text: Option<&str>, data: Value) {
+        if let (Some(telegram), Some(text)) = (&self.telegram, text) {
+            telegram.notify(text);
+        }
+        if let Some(webhook) = &self.webhook {
+            webhook.emit(event, data);
+        }
+    }
+}
+
+/// 读取环境变量中的通知设置并发送事件（用于没有持有 Notifier 的调用方）
+pub fn notify_event(event: Event, text: Option<&str>, data: Value) {
+    match Notifier::from_env() {
+        Ok(notifier) => notifier.emit(event, text, data),
+        Err(e) => warn!(%event, "通知设置无效: {}", e),
+    }
+}
+
+/// 按行把文本拆分为不超过 limit 个字符的若干段（单行过长时按字符截断）
+pub fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let mut line = line;
+        while line.chars().count() > limit {
+            let cut = line
+                .char_indices()
+                .nth(limit)
+                .map_or(line.len(), |(i, _)| i);
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+            chunks.push(line[..cut].to_string());
+            line = &line[cut..];
+        }
+        if current.chars().count() + line.chars().count() + 1 > limit && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// generate 完成后发送的消息：服务器信息、订阅导入链接（设置了 EZ_SUBSCRIBE_PUBLIC_URL 时）与分享链接
+pub fn generate_message(
+    result: &MultiProtocolResult,
+    subscription: Option<(&str, &str)>,
+    links: &[ShareLink],
+) -> String {
+    let mut out = format!(
+        "ezsingbox 配置已生成\n服务器: {} ({})\n",
+        result.domain, result.public_ip
+    );
+    if let Some((url, name)) = subscription {
+        let _ = write!(
+            out,
+            "\n订阅链接: {}\nsing-box 导入: {}\n",
+            url,
+            sing_box_import_remote_profile_uri(url, name)
+        );
+    }
+    let mut current = None;
+    for link in links {
+        if current != Some(link.user.as_str()) {
+            current = Some(link.user.as_str());
+            let _ = write!(out, "\n用户 {}:\n", link.user);
+        }
+        let _ = writeln!(out, "{}", link.link);
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("a\nb\nc", 10), vec!["a\nb\nc"]);
+        assert_eq!(split_message("aaaa\nbbbb\ncc", 9), vec!["aaaa\nbbbb", "cc"]);
+        assert_eq!(
+            split_message("短\n链接链接链接", 3),
+            vec!["短", "链接链", "接链接"]
+        );
+        assert!(split_message("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_webhook() {
+        assert_eq!("health.failed".parse(), Ok(Event::HealthCheckFailed));
+        assert!("config.deleted".parse::<Event>().is_err());
+
+        let body = WebhookNotifier::payload(Event::ConfigGenerated, json!({"domain": "a.b"}), 1000);
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["event"], "config.generated");
+        assert_eq!(value["timestamp"], 1000);
+        assert_eq!(value["data"]["domain"], "a.b");
+
+        let webhook = WebhookNotifier::new("https://hooks.example.com/ez")
+            .with_events(vec![Event::HealthCheckFailed]);
+        assert!(webhook.wants(Event::HealthCheckFailed));
+        assert!(!webhook.wants(Event::ConfigGenerated));
+        assert_eq!(webhook.signature(&body), None);
+
+        // RFC 4231 测试用例 2
+        let signed = WebhookNotifier::new("https://hooks.example.com/ez").with_secret("Jefe");
+        assert_eq!(
+            signed.signature("what do ya want for nothing?").as_deref(),
+            Some("sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+}
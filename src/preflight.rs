@@ -0,0 +1,270 @@
+//! ACME 预检模块
+//!
+//! 写入使用 ACME 证书的配置前检查：域名是否解析到本机公网 IP，
+//! HTTP-01 / TLS-ALPN-01 验证需要的 TCP 端口是否已被其他进程占用
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, TcpListener, ToSocketAddrs};
+
+use serde_json::Value;
+use tracing::warn;
+
+//============================================================================
+// 检查项
+//============================================================================
+
+/// 配置中 ACME 证书的检查项
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AcmePlan {
+    /// 证书域名
+    pub domains: BTreeSet<String>,
+    /// 验证需要监听的 TCP 端口及用途
+    pub ports: BTreeMap<u16, String>,
+}
+
+/// 从配置中收集 ACME 域名与验证端口
+/// 使用 DNS-01 的入站不需要端口；QUIC 入站（Hysteria2 / TUIC）不参与 TLS-ALPN-01 验证
+pub fn acme_plan(config: &Value) -> AcmePlan {
+    let mut plan = AcmePlan::default();
+    for inbound in config["inbounds"].as_array().into_iter().flatten() {
+        let acme = &inbound["tls"]["acme"];
+        if !acme.is_object() {
+            continue;
+        }
+        match &acme["domain"] {
+            Value::String(d) => {
+                plan.domains.insert(d.clone());
+            }
+            Value::Array(ds) => {
+                plan.domains
+                    .extend(ds.iter().filter_map(|d| d.as_str().map(str::to_string)));
+            }
+            _ => {}
+        }
+        if !acme["dns01_challenge"].is_null() {
+            continue;
+        }
+        if acme["disable_http_challenge"] != true {
+            let port = acme["alternative_http_port"].as_u64().unwrap_or(80);
+            if let Ok(port) = u16::try_from(port) {
+                plan.ports
+                    .entry(port)
+                    .or_insert_with(|| "ACME HTTP-01 验证".to_string());
+            }
+        }
+        let quic = matches!(
+            inbound["type"].as_str(),
+            Some("hysteria" | "hysteria2" | "tuic")
+        );
+        if acme["disable_tls_alpn_challenge"] != true && !quic {
+            let tag = inbound["tag"].as_str().unwrap_or("unknown");
+            let port = acme["alternative_tls_port"]
+                .as_u64()
+                .or_else(|| inbound["listen_port"].as_u64());
+            if let Some(port) = port.and_then(|p| u16::try_from(p).ok()) {
+                plan.ports
+                    .entry(port)
+                    .or_insert_with(|| format!("入站 {} 的 ACME TLS-ALPN-01 验证", tag));
+            }
+        }
+    }
+    plan
+}
+
+//============================================================================
+// 域名解析
+//============================================================================
+
+/// 检查域名是否解析到公网 IP
+pub fn check_domain(domain: &str, public_ip: IpAddr) -> Result<(), String> {
+    let resolved: BTreeSet<IpAddr> = (domain, 0)
+        .to_socket_addrs()
+        .map_err(|e| format!("无法解析域名 {}: {}", domain, e))?
+        .map(|a| a.ip())
+        .collect();
+    if resolved.contains(&public_ip) {
+        return Ok(());
+    }
+    let resolved: Vec<String> = resolved.iter().map(ToString::to_string).collect();
+    Err(format!(
+        "域名 {} 解析到 {}，但服务器公网 IP 为 {}：请将域名的 A / AAAA 记录指向 {}",
+        domain,
+        resolved.join(", "),
+        public_ip,
+        public_ip
+    ))
+}
+
+//============================================================================
+// 端口占用
+//============================================================================
+
+/// 占用端口的进程
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortOwner {
+    /// 进程 ID
+    pub pid: u32,
+    /// 进程名
+    pub name: String,
+}
+
+/// 解析 /proc/net/tcp(6)，返回处于监听状态且端口匹配的套接字 inode
+fn listening_inodes(table: &str, port: u16) -> Vec<String> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit_once(':')?.1;
+            let local_port = u16::from_str_radix(local_port, 16).ok()?;
+            // 0A = TCP_LISTEN
+            if local_port == port && *fields.get(3)? == "0A" {
+                fields.get(9).map(|s| s.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 查找监听指定 TCP 端口的进程（仅 Linux，需要读取其他进程 /proc 的权限）
+#[cfg(target_os = "linux")]
+fn port_owner(port: u16) -> Option<PortOwner> {
+    let sockets: Vec<String> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|table| listening_inodes(&table, port))
+        .map(|inode| format!("socket:[{}]", inode))
+        .collect();
+    if sockets.is_empty() {
+        return None;
+    }
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let owns = fds.flatten().any(|fd| {
+            std::fs::read_link(fd.path())
+                .is_ok_and(|link| sockets.iter().any(|s| link.as_os_str() == s.as_str()))
+        });
+        if owns {
+            let name = std::fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+            return Some(PortOwner {
+                pid,
+                name: name.trim().to_string(),
+            });
+        }
+    }
+    None
+}
+
+/// 非 Linux 系统无法查找占用端口的进程
+#[cfg(not(target_os = "linux"))]
+fn port_owner(_port: u16) -> Option<PortOwner> {
+    None
+}
+
+/// 检查 TCP 端口是否可被 sing-box 监听（已由 sing-box 自身占用时视为可用）
+/// 没有权限监听低端口时返回 Ok(Some(说明))，由调用方给出提示
+pub fn check_port(port: u16) -> Result<Option<String>, String> {
+    match TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)) {
+        Ok(_) => Ok(None),
+        Err(e) if e.kind() == ErrorKind::AddrInUse => match port_owner(port) {
+            Some(owner) if owner.name == "sing-box" => Ok(None),
+            Some(owner) => Err(format!(
+                "端口 {} 已被 {} (pid {}) 占用",
+                port, owner.name, owner.pid
+            )),
+            None => Err(format!("端口 {} 已被其他进程占用", port)),
+        },
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => Ok(Some(format!(
+            "当前用户无权监听端口 {}，无法检查占用（sing-box 需要 root 或 CAP_NET_BIND_SERVICE）",
+            port
+        ))),
+        Err(e) => Err(format!("无法检查端口 {}: {}", port, e)),
+    }
+}
+
+//============================================================================
+// 预检
+//============================================================================
+
+/// 执行 ACME 预检，返回全部问题（为空表示通过）
+/// `trusted` 为已知解析到公网 IP 的域名（泛解析域名或刚更新的动态域名），不再查询 DNS
+pub fn acme_preflight(config: &Value, public_ip: IpAddr, trusted: Option<&str>) -> Vec<String> {
+    let plan = acme_plan(config);
+    let mut issues = Vec::new();
+    for domain in plan.domains.iter().filter(|d| Some(d.as_str()) != trusted) {
+        if let Err(e) = check_domain(domain, public_ip) {
+            issues.push(e);
+        }
+    }
+    for (port, purpose) in &plan.ports {
+        match check_port(*port) {
+            Ok(None) => {}
+            Ok(Some(note)) => warn!("{}", note),
+            Err(e) => issues.push(format!("{}，{}需要该端口", e, purpose)),
+        }
+    }
+    issues
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_acme_plan() {
+        let config = json!({
+            "inbounds": [
+                { "type": "anytls", "tag": "anytls-in", "listen_port": 443,
+                  "tls": { "acme": { "domain": ["a.example.com"] } } },
+                { "type": "hysteria2", "tag": "hy2-in", "listen_port": 2053,
+                  "tls": { "acme": { "domain": ["a.example.com"], "alternative_tls_port": 2053 } } },
+                { "type": "vless", "tag": "vless-ws-in", "listen_port": 8443,
+                  "tls": { "acme": { "domain": "b.example.com", "alternative_http_port": 8080,
+                                     "disable_tls_alpn_challenge": true } } },
+                { "type": "tuic", "tag": "tuic-in", "listen_port": 2083,
+                  "tls": { "acme": { "domain": ["c.example.com"],
+                                     "dns01_challenge": { "provider": "cloudflare" } } } },
+                { "type": "vless", "tag": "reality-in", "listen_port": 2096, "tls": {} }
+            ]
+        });
+        let plan = acme_plan(&config);
+        let domains: Vec<&str> = plan.domains.iter().map(String::as_str).collect();
+        assert_eq!(domains, ["a.example.com", "b.example.com", "c.example.com"]);
+        let ports: Vec<u16> = plan.ports.keys().copied().collect();
+        assert_eq!(ports, [80, 443, 8080]);
+        assert_eq!(plan.ports[&443], "入站 anytls-in 的 ACME TLS-ALPN-01 验证");
+    }
+
+    #[test]
+    fn test_check_domain_and_port() {
+        assert!(check_domain("localhost", IpAddr::V4(Ipv4Addr::LOCALHOST)).is_ok());
+        let err = check_domain("localhost", "203.0.113.1".parse().unwrap()).unwrap_err();
+        assert!(err.contains("服务器公网 IP 为 203.0.113.1"), "{}", err);
+
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let err = check_port(port).unwrap_err();
+        assert!(err.starts_with(&format!("端口 {} 已被", port)), "{}", err);
+        drop(listener);
+        assert_eq!(check_port(port), Ok(None));
+    }
+
+    #[test]
+    fn test_listening_inodes() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 00000000:0050 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 12345 1 0000000000000000 100 0 0 10 0\n   1: 0100007F:0050 0100007F:9C40 01 00000000:00000000 00:00000000 00000000     0        0 23456 1 0000000000000000 20 4 30 10 -1\n";
+        assert_eq!(listening_inodes(table, 80), ["12345"]);
+        assert!(listening_inodes(table, 443).is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+//! 反向代理支持
+//!
+//! 订阅服务位于 nginx / Caddy 之后时，来自受信任代理（EZ_SUBSCRIBE_TRUSTED_PROXIES 中的地址段，
+//! 以及经 Unix 套接字到达的请求）的 X-Forwarded-For / X-Real-IP 用于日志、审计与限流，
+//! X-Forwarded-Proto / X-Forwarded-Host 用于推断外部访问地址。
+//! 其他来源的这些请求头一律忽略，避免伪造来源地址绕过限流与白名单。
+//! EZ_SUBSCRIBE_PATH_PREFIX 在路由匹配前从请求路径中去除（代理按前缀转发而不改写路径时使用）
+
+use std::net::IpAddr;
+
+use crate::env::env_string;
+use crate::ratelimit::{IpCidr, parse_cidrs};
+
+/// 受信任的反向代理
+#[derive(Debug, Clone, Default)]
+pub struct ProxyTrust {
+    /// 受信任代理的地址段
+    proxies: Vec<IpCidr>,
+}
+
+impl ProxyTrust {
+    /// 信任这些地址段中的代理
+    pub fn new(proxies: Vec<IpCidr>) -> Self {
+        Self { proxies }
+    }
+
+    /// 从环境变量读取（EZ_SUBSCRIBE_TRUSTED_PROXIES，逗号分隔的地址段）
+    pub fn from_env() -> Result<Self, String> {
+        let proxies = env_string("EZ_SUBSCRIBE_TRUSTED_PROXIES")
+            .map(|raw| parse_cidrs(&raw))
+            .transpose()
+            .map_err(|e| format!("EZ_SUBSCRIBE_TRUSTED_PROXIES: {}", e))?
+            .unwrap_or_default();
+        Ok(Self::new(proxies))
+    }
+
+    /// 是否信任该连接的转发请求头（peer 为 None 表示经 Unix 套接字到达，总是信任）
+    pub fn trusts(&self, peer: Option<IpAddr>) -> bool {
+        peer.is_none_or(|ip| self.proxies.iter().any(|c| c.contains(ip)))
+    }
+
+    /// 客户端地址：受信任代理转发的请求取 X-Forwarded-For 中从右往左第一个非受信任代理的地址，
+    /// 没有时取 X-Real-IP；其他请求为连接的来源地址
+    pub fn client_ip(
+        &self,
+        peer: Option<IpAddr>,
+        forwarded_for: Option<&str>,
+        real_ip: Option<&str>,
+    ) -> Option<IpAddr> {
+        if !self.trusts(peer) {
+            return peer;
+        }
+        let forwarded = forwarded_for.and_then(|header| {
+            let hops: Vec<IpAddr> = header
+                .split(',')
+                .filter_map(|hop| parse_forwarded_ip(hop.trim()))
+                .collect();
+            hops.iter()
+                .rev()
+                .find(|ip| !self.trusts(Some(**ip)))
+                .or(hops.first())
+                .copied()
+        });
+        forwarded
+            .or_else(|| real_ip.and_then(|ip| parse_forwarded_ip(ip.trim())))
+            .or(peer)
+    }
+
+    /// 外部访问的协议（受信任代理的 X-Forwarded-Proto，否则为 http）
+    pub fn scheme<'a>(&self, peer: Option<IpAddr>, forwarded_proto: Option<&'a str>) -> &'a str {
+        match forwarded_proto.map(|p| p.split(',').next().unwrap_or_default().trim()) {
+            Some(proto @ ("http" | "https")) if self.trusts(peer) => proto,
+            _ => "http",
+        }
+    }
+}
+
+/// 解析转发头中的地址（允许带端口与 IPv6 方括号，如 `[2001:db8::1]:443`、`203.0.113.7:5000`）
+fn parse_forwarded_ip(s: &str) -> Option<IpAddr> {
+    if let Ok(ip) = s.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = s.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    s.rsplit_once(':')?.0.parse().ok()
+}
+
+/// 规范化路径前缀（补全开头的 `/`，去掉结尾的 `/`；空或 `/` 为不使用前缀）
+pub fn normalize_path_prefix(raw: &str) -> String {
+    let trimmed = raw.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else if trimmed.starts_with('/') {
+        trimmed.to_string()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+/// 路径前缀（EZ_SUBSCRIBE_PATH_PREFIX，未设置时为空）
+pub fn path_prefix_from_env() -> String {
+    env_string("EZ_SUBSCRIBE_PATH_PREFIX")
+        .map(|raw| normalize_path_prefix(&raw))
+        .unwrap_or_default()
+}
+
+/// 去除请求 URL 的路径前缀（保留查询参数），不以该前缀开头时为 None
+pub fn strip_path_prefix<'a>(prefix: &str, url: &'a str) -> Option<&'a str> {
+    if prefix.is_empty() {
+        return Some(url);
+    }
+    let rest = url.strip_prefix(prefix)?;
+    if rest.is_empty() {
+        Some("/")
+    } else if rest.starts_with('/') {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let trust = ProxyTrust::new(parse_cidrs("127.0.0.1/32,10.0.0.0/8").unwrap());
+        let xff = Some("198.51.100.9, 203.0.113.7, 10.0.0.5");
+
+        // 受信任代理：跳过右侧的代理地址
+        assert_eq!(
+            trust.client_ip(Some(ip("127.0.0.1")), xff, None),
+            Some(ip("203.0.113.7"))
+        );
+        // Unix 套接字：总是信任
+        assert_eq!(
+            trust.client_ip(None, None, Some("[2001:db8::1]:443")),
+            Some(ip("2001:db8::1"))
+        );
+        assert_eq!(
+            trust.client_ip(None, Some("10.0.0.1, 10.0.0.2"), None),
+            Some(ip("10.0.0.1"))
+        );
+        // 非受信任来源：忽略转发头
+        assert_eq!(
+            trust.client_ip(Some(ip("192.0.2.1")), xff, Some("1.1.1.1")),
+            Some(ip("192.0.2.1"))
+        );
+        assert_eq!(
+            trust.client_ip(Some(ip("127.0.0.1")), Some("garbage"), None),
+            Some(ip("127.0.0.1"))
+        );
+
+        assert_eq!(trust.scheme(Some(ip("127.0.0.1")), Some("https")), "https");
+        assert_eq!(trust.scheme(Some(ip("192.0.2.1")), Some("https")), "http");
+        assert_eq!(trust.scheme(None, Some("https,http")), "https");
+        assert_eq!(trust.scheme(None, Some("ftp")), "http");
+    }
+
+    #[test]
+    fn test_path_prefix() {
+        assert_eq!(normalize_path_prefix("ezsub/"), "/ezsub");
+        assert_eq!(normalize_path_prefix("/"), "");
+        assert_eq!(
+            strip_path_prefix("/ezsub", "/ezsub/config.json?format=base64"),
+            Some("/config.json?format=base64")
+        );
+        assert_eq!(strip_path_prefix("/ezsub", "/ezsub"), Some("/"));
+        assert_eq!(strip_path_prefix("/ezsub", "/ezsubx/config.json"), None);
+        assert_eq!(strip_path_prefix("/ezsub", "/config.json"), None);
+        assert_eq!(strip_path_prefix("", "/config.json"), Some("/config.json"));
+    }
+}
@@ -0,0 +1,274 @@
+//! 订阅服务访问控制
+//!
+//! 订阅地址携带全部凭据，暴露在公网时需要防止暴力破解 Basic 认证与批量抓取：
+//! 按来源 IP 的令牌桶限流（超出时返回 429），以及可选的来源地址段白名单（不在其中时返回 403）
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::env::{env_string, env_u32};
+
+/// 默认每分钟允许的请求数
+pub const DEFAULT_RATE_PER_MINUTE: u32 = 30;
+
+/// 默认突发请求数（令牌桶容量）
+pub const DEFAULT_RATE_BURST: u32 = 10;
+
+/// 记录的来源 IP 超过该数量时清理已回满的令牌桶
+const MAX_TRACKED_IPS: usize = 10_000;
+
+//============================================================================
+// 地址段白名单
+//============================================================================
+
+/// IP 地址段（CIDR）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    /// 网络地址
+    pub addr: IpAddr,
+    /// 前缀长度
+    pub prefix: u8,
+}
+
+impl IpCidr {
+    /// 判断地址是否在地址段内（IPv4 映射的 IPv6 地址按 IPv4 处理）
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    /// 解析 `10.0.0.0/8`、`2001:db8::/32`，不带前缀时表示单个地址
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix) = s.split_once('/').unwrap_or((s, ""));
+        let addr: IpAddr = addr.parse().map_err(|_| format!("无效的地址段: {}", s))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = if prefix.is_empty() {
+            max
+        } else {
+            prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("无效的地址段前缀: {}", s))?
+        };
+        Ok(Self { addr, prefix })
+    }
+}
+
+/// 解析逗号分隔的地址段列表
+pub fn parse_cidrs(raw: &str) -> Result<Vec<IpCidr>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+//============================================================================
+// 令牌桶限流
+//============================================================================
+
+/// 单个来源的令牌桶
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// 剩余令牌
+    tokens: f64,
+    /// 上次补充时间
+    updated: Instant,
+}
+
+/// 按来源 IP 的令牌桶限流器
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// 每秒补充的令牌数
+    rate: f64,
+    /// 令牌桶容量
+    burst: f64,
+    buckets: HashMap<IpAddr, Bucket>,
+}
+
+impl RateLimiter {
+    /// 创建限流器（每分钟请求数、突发请求数）
+    pub fn new(per_minute: u32, burst: u32) -> Self {
+        Self {
+            rate: f64::from(per_minute) / 60.0,
+            burst: f64::from(burst.max(1)),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// 消耗一个令牌；令牌不足时返回需要等待的秒数
+    pub fn check(&mut self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        if self.buckets.len() >= MAX_TRACKED_IPS {
+            self.evict_idle(now);
+        }
+        let (rate, burst) = (self.rate, self.burst);
+        let bucket = self.buckets.entry(ip.to_canonical()).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / rate).ceil() as u64)
+        }
+    }
+
+    /// 移除已回满的令牌桶（这些来源重新出现时与新来源无异）
+    fn evict_idle(&mut self, now: Instant) {
+        let (rate, burst) = (self.rate, self.burst);
+        self.buckets.retain(|_, b| {
+            let elapsed = now.saturating_duration_since(b.updated).as_secs_f64();
+            b.tokens + elapsed * rate < burst
+        });
+    }
+}
+
+//============================================================================
+// 访问控制
+//============================================================================
+
+/// 请求被拒绝的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// 来源不在白名单内（403）
+    Forbidden,
+    /// 超出频率限制（429），附带建议的重试等待秒数
+    TooManyRequests(u64),
+}
+
+impl Rejection {
+    /// HTTP 状态码
+    pub fn status(&self) -> u16 {
+        match self {
+            Rejection::Forbidden => 403,
+            Rejection::TooManyRequests(_) => 429,
+        }
+    }
+}
+
+/// 订阅服务访问控制
+#[derive(Debug)]
+pub struct AccessGuard {
+    /// 允许的来源地址段（None 时不限制）
+    allow: Option<Vec<IpCidr>>,
+    /// 限流器（None 时不限流）
+    limiter: Option<RateLimiter>,
+}
+
+impl AccessGuard {
+    /// 创建访问控制
+    pub fn new(allow: Option<Vec<IpCidr>>, limiter: Option<RateLimiter>) -> Self {
+        Self { allow, limiter }
+    }
+
+    /// 检查来源地址（无法获取来源地址时只在未设置白名单时放行）
+    pub fn check(&mut self, remote: Option<IpAddr>, now: Instant) -> Result<(), Rejection> {
+        if let Some(ref allow) = self.allow
+            && !remote.is_some_and(|ip| allow.iter().any(|c| c.contains(ip)))
+        {
+            return Err(Rejection::Forbidden);
+        }
+        match (self.limiter.as_mut(), remote) {
+            (Some(limiter), Some(ip)) => limiter.check(ip, now).map_err(Rejection::TooManyRequests),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 从环境变量读取访问控制设置
+/// EZ_SUBSCRIBE_ALLOW_CIDRS 为允许的来源地址段；EZ_SUBSCRIBE_RATE_LIMIT 为每个来源每分钟的请求数（0 为不限流），
+/// EZ_SUBSCRIBE_RATE_BURST 为突发请求数
+pub fn access_guard_from_env() -> Result<AccessGuard, String> {
+    let allow = env_string("EZ_SUBSCRIBE_ALLOW_CIDRS")
+        .map(|raw| parse_cidrs(&raw))
+        .transpose()
+        .map_err(|e| format!("EZ_SUBSCRIBE_ALLOW_CIDRS: {}", e))?;
+    let per_minute = env_u32("EZ_SUBSCRIBE_RATE_LIMIT").unwrap_or(DEFAULT_RATE_PER_MINUTE);
+    let burst = env_u32("EZ_SUBSCRIBE_RATE_BURST").unwrap_or(DEFAULT_RATE_BURST);
+    let limiter = (per_minute > 0).then(|| RateLimiter::new(per_minute, burst));
+    Ok(AccessGuard::new(allow, limiter))
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cidr() {
+        let cidrs = parse_cidrs("10.0.0.0/8, 2001:db8::/32,203.0.113.7").unwrap();
+        assert!(cidrs[0].contains("10.1.2.3".parse().unwrap()));
+        assert!(cidrs[0].contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!cidrs[0].contains("11.0.0.1".parse().unwrap()));
+        assert!(cidrs[1].contains("2001:db8:1::1".parse().unwrap()));
+        assert!(cidrs[2].contains("203.0.113.7".parse().unwrap()));
+        assert!(!cidrs[2].contains("203.0.113.8".parse().unwrap()));
+        assert!(
+            "0.0.0.0/0"
+                .parse::<IpCidr>()
+                .unwrap()
+                .contains("8.8.8.8".parse().unwrap())
+        );
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+        assert!("example.com/8".parse::<IpCidr>().is_err());
+    }
+
+    #[test]
+    fn test_access_guard() {
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+        let now = Instant::now();
+        let mut guard = AccessGuard::new(
+            Some(parse_cidrs("10.0.0.0/24").unwrap()),
+            Some(RateLimiter::new(60, 2)),
+        );
+        assert_eq!(
+            guard.check(Some("192.0.2.1".parse().unwrap()), now),
+            Err(Rejection::Forbidden)
+        );
+        assert_eq!(guard.check(None, now), Err(Rejection::Forbidden));
+
+        // 突发 2 次后限流，每秒恢复一个令牌；各来源独立计数
+        assert!(guard.check(Some(ip), now).is_ok());
+        assert!(guard.check(Some(ip), now).is_ok());
+        assert_eq!(
+            guard.check(Some(ip), now),
+            Err(Rejection::TooManyRequests(1))
+        );
+        assert!(guard.check(Some(other), now).is_ok());
+        assert!(guard.check(Some(ip), now + Duration::from_secs(1)).is_ok());
+        assert_eq!(Rejection::TooManyRequests(1).status(), 429);
+
+        let mut open = AccessGuard::new(None, None);
+        assert!(open.check(None, now).is_ok());
+    }
+}
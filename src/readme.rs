@@ -0,0 +1,318 @@
+//! 部署说明文档模块
+//!
+//! 根据生成结果渲染一份 Markdown 文档（各平台连接步骤、分享链接、
+//! 二维码、常见问题），运维人员可直接交给最终用户；
+//! 订阅服务启动时的客户端导入命令也在此渲染
+
+use std::fmt::Write;
+
+use base64::Engine;
+use qrcode::QrCode;
+use qrcode::render::svg;
+
+use crate::config::{ShareLink, protocol_label, share_links};
+use crate::env::env_string;
+use ezsingbox::autoconfig::{MultiProtocolResult, Protocol, Transport};
+use ezsingbox::sharelink::sing_box_import_remote_profile_uri;
+
+/// 二维码最小边长（像素）
+const QR_MIN_SIZE: u32 = 240;
+
+/// 各平台推荐客户端及导入步骤
+const PLATFORMS: &[(&str, &str, &str)] = &[
+    (
+        "Android",
+        "sing-box (SFA)",
+        "复制分享链接后在 App 中选择「从剪贴板导入」，或扫描二维码",
+    ),
+    (
+        "iOS / iPadOS",
+        "sing-box (SFI)",
+        "复制分享链接后在 App 中导入，或使用系统相机扫描二维码",
+    ),
+    (
+        "macOS",
+        "sing-box (SFM)",
+        "复制分享链接后在 App 中选择「从剪贴板导入」",
+    ),
+    (
+        "Windows",
+        "sing-box / v2rayN",
+        "复制分享链接，在客户端中选择「从剪贴板导入」",
+    ),
+    (
+        "Linux",
+        "sing-box 命令行",
+        "使用 `ezsingbox export peer-outbound` 导出的出站配置，或由管理员提供 client.json",
+    ),
+];
+
+/// 生成指定内容的二维码 SVG，并编码为 data URI
+pub fn qr_data_uri(text: &str) -> Result<String, String> {
+    let code = QrCode::new(text.as_bytes()).map_err(|e| format!("生成二维码失败: {}", e))?;
+    let image = code
+        .render::<svg::Color>()
+        .min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE)
+        .build();
+    let encoded = base64::engine::general_purpose::STANDARD.encode(image);
+    Ok(format!("data:image/svg+xml;base64,{}", encoded))
+}
+
+/// 订阅信息（名称、URL）
+pub struct Subscription<'a> {
+    /// 订阅名称
+    pub name: &'a str,
+    /// 订阅 URL
+    pub url: &'a str,
+}
+
+/// 渲染部署说明文档
+pub fn render_markdown(
+    server: &str,
+    links: &[ShareLink],
+    subscription: Option<&Subscription>,
+) -> Result<String, String> {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {} 连接说明\n", server);
+
+    // 支持的客户端
+    let _ = writeln!(out, "## 客户端\n");
+    let _ = writeln!(out, "| 平台 | 推荐客户端 | 导入方式 |");
+    let _ = writeln!(out, "| --- | --- | --- |");
+    for (platform, client, steps) in PLATFORMS {
+        let _ = writeln!(out, "| {} | {} | {} |", platform, client, steps);
+    }
+    out.push('\n');
+
+    // 订阅
+    if let Some(sub) = subscription {
+        let uri = sing_box_import_remote_profile_uri(sub.url, sub.name);
+        let _ = writeln!(out, "## 订阅\n");
+        let _ = writeln!(
+            out,
+            "已安装 sing-box 客户端时，点击或扫描下面的链接即可导入订阅，之后配置会自动更新。\n"
+        );
+        let _ = writeln!(out, "- 订阅地址: `{}`", sub.url);
+        let _ = writeln!(out, "- 一键导入: [{}]({})\n", sub.name, uri);
+        let _ = writeln!(out, "![订阅二维码]({})\n", qr_data_uri(&uri)?);
+    }
+
+    // 按用户分组的分享链接
+    let mut users: Vec<&str> = Vec::new();
+    for link in links {
+        if !users.contains(&link.user.as_str()) {
+            users.push(&link.user);
+        }
+    }
+    for user in users {
+        let _ = writeln!(out, "## 用户 {}\n", user);
+        for link in links.iter().filter(|l| l.user == user) {
+            let _ = writeln!(
+                out,
+                "### {} (端口 {})\n",
+                protocol_label(link.protocol),
+                link.port
+            );
+            let _ = writeln!(out, "```\n{}\n```\n", link.link);
+            let _ = writeln!(
+                out,
+                "![{} 二维码]({})\n",
+                protocol_label(link.protocol),
+                qr_data_uri(&link.link)?
+            );
+        }
+    }
+
+    // 常见问题
+    let _ = writeln!(out, "## 常见问题\n");
+    let _ = writeln!(
+        out,
+        "- **无法连接**：确认设备时间准确（误差不超过 1 分钟），TLS 与 Reality 握手对时间敏感。"
+    );
+    let _ = writeln!(
+        out,
+        "- **导入失败**：请将客户端升级到最新版本，旧版本可能不支持 AnyTLS 等较新的协议。"
+    );
+    let mut udp_ports: Vec<String> = Vec::new();
+    for l in links
+        .iter()
+        .filter(|l| l.protocol.transport() == Transport::Udp)
+    {
+        let port = l.port.to_string();
+        if !udp_ports.contains(&port) {
+            udp_ports.push(port);
+        }
+    }
+    if !udp_ports.is_empty() {
+        let _ = writeln!(
+            out,
+            "- **Hysteria2 / TUIC 不可用**：这两个协议基于 UDP（端口 {}），部分网络会限制 UDP，请改用其他协议。",
+            udp_ports.join(", ")
+        );
+    }
+    if links.iter().any(|l| l.protocol == Protocol::VlessReality) {
+        let _ = writeln!(
+            out,
+            "- **VLESS Reality 连接后无法访问**：确认客户端已启用 uTLS 指纹，且未修改链接中的 SNI、公钥与短 ID。"
+        );
+    }
+    let _ = writeln!(
+        out,
+        "- **仍有问题**：将客户端日志截图发给管理员，请勿在公开场合分享本文档中的链接。"
+    );
+
+    Ok(out)
+}
+
+/// 渲染订阅服务启动横幅：可直接复制的各客户端导入命令
+pub fn render_serve_banner(
+    subscription: &Subscription,
+    basic_auth: Option<(&str, &str)>,
+    links: &[ShareLink],
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "==== 客户端导入命令 ====");
+
+    let auth = basic_auth
+        .map(|(u, p)| format!("-u '{}:{}' ", u, p))
+        .unwrap_or_default();
+    let _ = writeln!(out, "\n[sing-box 命令行]");
+    let _ = writeln!(
+        out,
+        "  curl -fsSL {}'{}' -o config.json && sing-box run -c config.json",
+        auth, subscription.url
+    );
+
+    let _ = writeln!(out, "\n[sing-box App (SFA / SFI / SFM)]");
+    let _ = writeln!(
+        out,
+        "  {}",
+        sing_box_import_remote_profile_uri(subscription.url, subscription.name)
+    );
+    if basic_auth.is_some() {
+        let _ = writeln!(
+            out,
+            "  (订阅启用了 Basic 认证，App 导入时需使用 http://用户名:密码@主机/路径 形式的 URL)"
+        );
+    }
+
+    if !links.is_empty() {
+        let _ = writeln!(out, "\n[NekoBox / v2rayN 等 (剪贴板导入)]");
+    }
+    let mut current = None;
+    for link in links {
+        if current != Some(link.user.as_str()) {
+            current = Some(link.user.as_str());
+            let _ = writeln!(out, "  用户 {}:", link.user);
+        }
+        let _ = writeln!(out, "    {}", link.link);
+    }
+    out
+}
+
+/// 根据生成结果渲染部署说明文档
+/// 设置了 EZ_CLIENT_USER 时只包含该用户的链接
+pub fn render_readme(result: &MultiProtocolResult) -> Result<String, String> {
+    let mut links = share_links(result);
+    if let Some(user) = env_string("EZ_CLIENT_USER") {
+        links.retain(|l| l.user == user);
+        if links.is_empty() {
+            return Err(format!("未找到用户: {}", user));
+        }
+    }
+
+    let name = env_string("EZ_REMOTE_PROFILE_NAME").unwrap_or_else(|| "ezsingbox".to_string());
+    let url = env_string("EZ_REMOTE_PROFILE_URL");
+    let subscription = url.as_deref().map(|url| Subscription { name: &name, url });
+
+    render_markdown(&result.domain, &links, subscription.as_ref())
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(protocol: Protocol, port: u16, user: &str) -> ShareLink {
+        ShareLink {
+            protocol,
+            port,
+            user: user.to_string(),
+            link: format!("{}://{}@example.com:{}", protocol.as_str(), user, port),
+        }
+    }
+
+    #[test]
+    fn test_qr_data_uri() {
+        let uri = qr_data_uri("hysteria2://pw@example.com:2053").unwrap();
+        let encoded = uri.strip_prefix("data:image/svg+xml;base64,").unwrap();
+        let svg = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains("<svg"));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let links = vec![
+            link(Protocol::AnyTls, 443, "alice"),
+            link(Protocol::Hysteria2, 2053, "alice"),
+            link(Protocol::AnyTls, 443, "bob"),
+        ];
+        let sub = Subscription {
+            name: "demo",
+            url: "https://example.com/sub",
+        };
+        let md = render_markdown("example.com", &links, Some(&sub)).unwrap();
+
+        assert!(md.starts_with("# example.com 连接说明"));
+        assert!(md.contains("| Android |"));
+        assert!(md.contains("## 订阅"));
+        assert!(md.contains("sing-box://import-remote-profile?url="));
+        // 用户按首次出现顺序分组
+        let alice = md.find("## 用户 alice").unwrap();
+        let bob = md.find("## 用户 bob").unwrap();
+        assert!(alice < bob);
+        assert!(md.contains("### Hysteria2 (端口 2053)"));
+        assert!(md.contains("anytls://bob@example.com:443"));
+        assert_eq!(md.matches("data:image/svg+xml;base64,").count(), 4);
+        // UDP 提示只在启用 UDP 协议时出现
+        assert!(md.contains("UDP（端口 2053）"));
+        assert!(!md.contains("VLESS Reality 连接后"));
+
+        let md = render_markdown("example.com", &links[..1], None).unwrap();
+        assert!(!md.contains("## 订阅"));
+        assert!(!md.contains("UDP"));
+    }
+
+    #[test]
+    fn test_render_serve_banner() {
+        let sub = Subscription {
+            name: "demo",
+            url: "http://1.2.3.4:8080/config.json",
+        };
+        let links = vec![
+            link(Protocol::AnyTls, 443, "alice"),
+            link(Protocol::Tuic, 2083, "alice"),
+            link(Protocol::AnyTls, 443, "bob"),
+        ];
+
+        let banner = render_serve_banner(&sub, None, &links);
+        assert!(banner.contains(
+            "curl -fsSL 'http://1.2.3.4:8080/config.json' -o config.json && sing-box run -c config.json"
+        ));
+        assert!(banner.contains("sing-box://import-remote-profile?url="));
+        assert_eq!(banner.matches("用户 alice:").count(), 1);
+        assert!(banner.contains("    tuic://alice@example.com:2083"));
+        assert!(!banner.contains("Basic"));
+
+        let banner = render_serve_banner(&sub, Some(("u", "p")), &[]);
+        assert!(banner.contains("curl -fsSL -u 'u:p' 'http://1.2.3.4:8080/config.json'"));
+        assert!(banner.contains("Basic 认证"));
+        assert!(!banner.contains("NekoBox"));
+    }
+}
@@ -0,0 +1,183 @@
+//! 签名的限时订阅链接
+//!
+//! `ezsingbox links --expire 24h` 为每个用户生成 `/sub/<用户>?exp=<过期时间>&sig=<签名>` 形式的订阅地址，
+//! 签名为服务端密钥（EZ_SUBSCRIBE_SIGNING_KEY）对用户名与过期时间的 HMAC-SHA256；
+//! 订阅服务校验签名与过期时间后返回该用户的配置，无需 Basic 认证，过期后链接自动失效
+
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::env::env_string;
+use ezsingbox::sharelink::{percent_decode, percent_encode};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 签名订阅链接的路径前缀
+pub const SIGNED_PATH_PREFIX: &str = "/sub/";
+
+/// 签名链接校验失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkError {
+    /// 缺少参数或签名不匹配
+    BadSignature,
+    /// 链接已过期
+    Expired,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::BadSignature => write!(f, "订阅链接签名无效"),
+            LinkError::Expired => write!(f, "订阅链接已过期"),
+        }
+    }
+}
+
+/// 订阅链接签名器
+#[derive(Clone)]
+pub struct LinkSigner {
+    key: Vec<u8>,
+}
+
+impl fmt::Debug for LinkSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LinkSigner(<redacted>)")
+    }
+}
+
+impl LinkSigner {
+    /// 使用密钥创建签名器
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.as_bytes().to_vec(),
+        }
+    }
+
+    /// 从环境变量读取签名密钥（EZ_SUBSCRIBE_SIGNING_KEY，未设置时为 None）
+    pub fn from_env() -> Option<Self> {
+        env_string("EZ_SUBSCRIBE_SIGNING_KEY").map(|key| Self::new(&key))
+    }
+
+    fn mac(&self, user: &str, expires_at: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC 接受任意长度的密钥");
+        mac.update(user.as_bytes());
+        mac.update(b"\n");
+        mac.update(expires_at.to_string().as_bytes());
+        mac
+    }
+
+    /// 计算签名（十六进制）
+    pub fn sign(&self, user: &str, expires_at: i64) -> String {
+        self.mac(user, expires_at)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// 生成签名链接的路径与查询参数
+    pub fn signed_path(&self, user: &str, expires_at: i64) -> String {
+        format!(
+            "{}{}?exp={}&sig={}",
+            SIGNED_PATH_PREFIX,
+            percent_encode(user),
+            expires_at,
+            self.sign(user, expires_at)
+        )
+    }
+
+    /// 校验签名链接（segment 为 `/sub/` 之后的路径段），返回用户名
+    /// 签名比较为常数时间
+    pub fn verify(
+        &self,
+        segment: &str,
+        exp: Option<&str>,
+        sig: Option<&str>,
+        now: i64,
+    ) -> Result<String, LinkError> {
+        let user = percent_decode(segment);
+        let expires_at: i64 = exp
+            .and_then(|e| e.parse().ok())
+            .ok_or(LinkError::BadSignature)?;
+        let sig = sig.and_then(decode_hex).ok_or(LinkError::BadSignature)?;
+        self.mac(&user, expires_at)
+            .verify_slice(&sig)
+            .map_err(|_| LinkError::BadSignature)?;
+        if now >= expires_at {
+            return Err(LinkError::Expired);
+        }
+        Ok(user)
+    }
+}
+
+/// 解码十六进制字符串
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query<'a>(path: &'a str, key: &str) -> Option<&'a str> {
+        path.split_once('?')?
+            .1
+            .split('&')
+            .filter_map(|p| p.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    #[test]
+    fn test_signed_link() {
+        let signer = LinkSigner::new("server-secret");
+        let path = signer.signed_path("张三 alice", 2000);
+        let segment = path
+            .strip_prefix(SIGNED_PATH_PREFIX)
+            .and_then(|p| p.split('?').next())
+            .unwrap();
+        let (exp, sig) = (query(&path, "exp"), query(&path, "sig"));
+        assert_eq!(exp, Some("2000"));
+        assert_eq!(
+            signer.verify(segment, exp, sig, 1000),
+            Ok("张三 alice".to_string())
+        );
+        assert_eq!(
+            signer.verify(segment, exp, sig, 2000),
+            Err(LinkError::Expired)
+        );
+
+        // 改动用户、过期时间或使用其他密钥时签名无效
+        assert_eq!(
+            signer.verify("bob", exp, sig, 1000),
+            Err(LinkError::BadSignature)
+        );
+        assert_eq!(
+            signer.verify(segment, Some("9999"), sig, 1000),
+            Err(LinkError::BadSignature)
+        );
+        assert_eq!(
+            LinkSigner::new("other").verify(segment, exp, sig, 1000),
+            Err(LinkError::BadSignature)
+        );
+        assert_eq!(
+            signer.verify(segment, exp, None, 1000),
+            Err(LinkError::BadSignature)
+        );
+        assert_eq!(decode_hex("0aff"), Some(vec![0x0a, 0xff]));
+        assert_eq!(decode_hex("0g"), None);
+    }
+}
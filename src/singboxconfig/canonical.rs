@@ -0,0 +1,156 @@
+//! 规范化 JSON 输出
+//!
+//! 默认输出中顶层字段跟随结构体声明顺序，版本升级调整声明顺序时，纳入 git 管理的配置会出现无意义的差异。
+//! 规范化输出的字段顺序与结构体无关：完整配置的顶层字段按 sing-box 文档顺序，
+//! 对象中 `type`、`tag` 在前，其他字段按字母顺序；缩进与 `serde_json::to_string_pretty` 相同
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use super::strict::{TOP_LEVEL_KEYS, pointer_push};
+
+/// 对象中排在最前的字段
+const LEADING_KEYS: [&str; 2] = ["type", "tag"];
+
+/// 缩进单位
+const INDENT: &str = "  ";
+
+/// 以规范化字段顺序输出带缩进的 JSON
+pub fn to_canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, 0, "", &BTreeMap::new());
+    out
+}
+
+/// 以规范化字段顺序输出，并在注释表中有条目的字段 / 数组元素前加 `//` 注释
+/// 注释表以 JSON Pointer 为键，空 Pointer 的注释写在文件开头
+pub(crate) fn to_commented_string(value: &Value, notes: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    if let Some(note) = notes.get("") {
+        write_note(&mut out, note, 0);
+    }
+    write_value(&mut out, value, 0, "", notes);
+    out
+}
+
+/// 对象字段的输出顺序（最外层对象另按顶层字段顺序）
+fn ordered_keys(map: &Map<String, Value>, top_level: bool) -> Vec<&String> {
+    let mut leading = LEADING_KEYS.to_vec();
+    if top_level {
+        leading.extend(TOP_LEVEL_KEYS);
+    }
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort_by_key(|key| {
+        let rank = leading
+            .iter()
+            .position(|k| k == key)
+            .unwrap_or(leading.len());
+        (rank, *key)
+    });
+    keys
+}
+
+fn write_value(
+    out: &mut String,
+    value: &Value,
+    depth: usize,
+    pointer: &str,
+    notes: &BTreeMap<String, String>,
+) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push_str("{\n");
+            let keys = ordered_keys(map, depth == 0);
+            for (i, key) in keys.iter().enumerate() {
+                let child = pointer_push(pointer, key);
+                if let Some(note) = notes.get(&child) {
+                    write_note(out, note, depth + 1);
+                }
+                out.push_str(&INDENT.repeat(depth + 1));
+                out.push_str(&Value::String(key.to_string()).to_string());
+                out.push_str(": ");
+                write_value(out, &map[key.as_str()], depth + 1, &child, notes);
+                out.push_str(if i + 1 < keys.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&INDENT.repeat(depth));
+            out.push('}');
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                let child = pointer_push(pointer, &i.to_string());
+                if let Some(note) = notes.get(&child) {
+                    write_note(out, note, depth + 1);
+                }
+                out.push_str(&INDENT.repeat(depth + 1));
+                write_value(out, item, depth + 1, &child, notes);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&INDENT.repeat(depth));
+            out.push(']');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+/// 输出注释（每行一条 `//` 注释）
+fn write_note(out: &mut String, note: &str, depth: usize) {
+    for line in note.lines() {
+        out.push_str(&INDENT.repeat(depth));
+        out.push_str("// ");
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_order() {
+        let value = json!({
+            "route": { "final": "direct" },
+            "outbounds": [{ "tag": "direct", "domain_strategy": "ipv4_only", "type": "direct" }],
+            "log": { "level": "info" },
+            "experimental": {},
+            "inbounds": []
+        });
+        let text = to_canonical_string(&value);
+        let keys: Vec<&str> = text
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix('"')?.split('"').next())
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "log",
+                "level",
+                "inbounds",
+                "outbounds",
+                "type",
+                "tag",
+                "domain_strategy",
+                "route",
+                "final",
+                "experimental"
+            ]
+        );
+        // 与 serde_json 的缩进格式一致，内容不变
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed, value);
+        let sorted = json!({ "a": [1, { "b": "x\"y" }], "c": [] });
+        assert_eq!(
+            to_canonical_string(&sorted["a"]),
+            serde_json::to_string_pretty(&sorted["a"]).unwrap()
+        );
+        assert_eq!(to_canonical_string(&json!("s")), "\"s\"");
+        assert!(to_canonical_string(&value["outbounds"][0]).starts_with("{\n  \"type\""));
+    }
+}
@@ -0,0 +1,87 @@
+//! 配置语义比较
+//!
+//! 强类型结构的 `PartialEq` 区分 `None` 与显式的 `null`，也无法跨类型比较；
+//! 这里把两边序列化为 JSON 后比较：对象字段顺序无关，值为 `null` 的字段与缺失的字段视为相同。
+//! 数组仍按顺序比较（规则、服务器等的顺序有意义）
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// 两份配置在语义上是否相同（任一方序列化失败时为 false）
+pub fn config_equal<A, B>(a: &A, b: &B) -> bool
+where
+    A: Serialize + ?Sized,
+    B: Serialize + ?Sized,
+{
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => value_equal(&a, &b),
+        _ => false,
+    }
+}
+
+/// 两个 JSON 值在语义上是否相同（`null` 字段等同于缺失）
+pub fn value_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => a
+            .keys()
+            .chain(b.keys())
+            .all(|key| field_equal(a.get(key), b.get(key))),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| value_equal(x, y))
+        }
+        _ => a == b,
+    }
+}
+
+/// 对象中的同名字段在语义上是否相同（缺失等同于 `null`）
+pub fn field_equal(a: Option<&Value>, b: Option<&Value>) -> bool {
+    value_equal(a.unwrap_or(&Value::Null), b.unwrap_or(&Value::Null))
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singboxconfig::outbound::DirectOutbound;
+    use serde_json::json;
+
+    #[test]
+    fn test_value_equal() {
+        assert!(value_equal(
+            &json!({ "a": 1, "b": null, "c": { "d": [1, 2] } }),
+            &json!({ "c": { "d": [1, 2], "e": null }, "a": 1 })
+        ));
+        assert!(!value_equal(&json!({ "a": 1 }), &json!({ "a": 1, "b": 0 })));
+        assert!(!value_equal(&json!([1, 2]), &json!([2, 1])));
+        assert!(!value_equal(&json!({ "a": [] }), &json!({})));
+    }
+
+    #[test]
+    fn test_field_equal() {
+        assert!(field_equal(None, None));
+        assert!(field_equal(Some(&Value::Null), None));
+        assert!(!field_equal(None, Some(&json!({ "a": null }))));
+        assert!(!field_equal(Some(&json!(0)), None));
+        assert!(field_equal(
+            Some(&json!({ "a": 1 })),
+            Some(&json!({ "a": 1, "b": null }))
+        ));
+    }
+
+    #[test]
+    fn test_config_equal() {
+        let typed = DirectOutbound::new("direct");
+        assert!(config_equal(
+            &typed,
+            &json!({ "tag": "direct", "type": "direct", "detour": null })
+        ));
+        assert!(!config_equal(
+            &typed,
+            &json!({ "type": "direct", "tag": "out" })
+        ));
+        assert!(config_equal(&typed, &typed.clone()));
+    }
+}
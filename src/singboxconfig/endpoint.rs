@@ -0,0 +1,227 @@
+//! sing-box 端点配置
+//!
+//! 端点（endpoint）自 sing-box 1.11.0 起可用，同时具备入站与出站能力，
+//! 路由规则与 `route.final` 可像出站一样引用端点标签
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::singboxconfig::shared::DialFields;
+
+//============================================================================
+// WireGuard 端点配置
+//============================================================================
+
+/// Cloudflare WARP 对端地址
+pub const WARP_PEER_ADDRESS: &str = "engage.cloudflareclient.com";
+
+/// Cloudflare WARP 对端端口
+pub const WARP_PEER_PORT: u16 = 2408;
+
+/// Cloudflare WARP 对端公钥
+pub const WARP_PEER_PUBLIC_KEY: &str = "bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=";
+
+/// Cloudflare WARP 推荐的 MTU
+pub const WARP_MTU: u32 = 1280;
+
+/// WireGuard 端点配置
+/// 文档: https://sing-box.sagernet.org/configuration/endpoint/wireguard/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct WireGuardEndpoint {
+    /// 端点类型，固定为 "wireguard"
+    #[serde(rename = "type")]
+    pub endpoint_type: String,
+
+    /// 端点标签
+    pub tag: String,
+
+    /// 使用系统网卡（默认使用 gVisor 用户态协议栈）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<bool>,
+
+    /// MTU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u32>,
+
+    /// 本地地址（CIDR）
+    pub address: Vec<String>,
+
+    /// 本地私钥（base64）
+    pub private_key: String,
+
+    /// 对端列表
+    pub peers: Vec<WireGuardPeer>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+/// WireGuard 对端
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct WireGuardPeer {
+    /// 对端地址
+    pub address: String,
+
+    /// 对端端口
+    pub port: u16,
+
+    /// 对端公钥（base64）
+    pub public_key: String,
+
+    /// 预共享密钥（base64）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_shared_key: Option<String>,
+
+    /// 经此对端路由的地址段
+    pub allowed_ips: Vec<String>,
+
+    /// 保活间隔（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistent_keepalive_interval: Option<u16>,
+
+    /// WireGuard 保留字节（WARP 用于标识客户端）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserved: Option<[u8; 3]>,
+}
+
+impl WireGuardPeer {
+    /// 创建转发全部流量的对端
+    pub fn new(address: impl Into<String>, port: u16, public_key: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            port,
+            public_key: public_key.into(),
+            pre_shared_key: None,
+            allowed_ips: vec!["0.0.0.0/0".to_string(), "::/0".to_string()],
+            persistent_keepalive_interval: None,
+            reserved: None,
+        }
+    }
+
+    /// 设置保留字节
+    pub fn with_reserved(mut self, reserved: [u8; 3]) -> Self {
+        self.reserved = Some(reserved);
+        self
+    }
+}
+
+impl WireGuardEndpoint {
+    /// 创建 WireGuard 端点
+    pub fn new(
+        tag: impl Into<String>,
+        private_key: impl Into<String>,
+        address: Vec<String>,
+        peer: WireGuardPeer,
+    ) -> Self {
+        Self {
+            endpoint_type: "wireguard".to_string(),
+            tag: tag.into(),
+            system: None,
+            mtu: None,
+            address,
+            private_key: private_key.into(),
+            peers: vec![peer],
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 创建连接 Cloudflare WARP 的端点（使用默认对端与 MTU）
+    pub fn cloudflare_warp(
+        tag: impl Into<String>,
+        private_key: impl Into<String>,
+        address: Vec<String>,
+    ) -> Self {
+        let peer = WireGuardPeer::new(WARP_PEER_ADDRESS, WARP_PEER_PORT, WARP_PEER_PUBLIC_KEY);
+        Self::new(tag, private_key, address, peer).with_mtu(WARP_MTU)
+    }
+
+    /// 设置 MTU
+    pub fn with_mtu(mut self, mtu: u32) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+
+    /// 转换为 sing-box 1.11 之前的 WireGuard 出站格式（只支持单个对端）
+    pub fn to_legacy_outbound(&self) -> Result<Value, String> {
+        let [peer] = self.peers.as_slice() else {
+            return Err(format!(
+                "WireGuard 端点 {} 有多个对端，sing-box 1.11 之前的出站格式只支持一个",
+                self.tag
+            ));
+        };
+        let mut outbound = json!({
+            "type": "wireguard",
+            "tag": self.tag,
+            "server": peer.address,
+            "server_port": peer.port,
+            "local_address": self.address,
+            "private_key": self.private_key,
+            "peer_public_key": peer.public_key,
+        });
+        if let Some(ref psk) = peer.pre_shared_key {
+            outbound["pre_shared_key"] = json!(psk);
+        }
+        if let Some(reserved) = peer.reserved {
+            outbound["reserved"] = json!(reserved);
+        }
+        if let Some(mtu) = self.mtu {
+            outbound["mtu"] = json!(mtu);
+        }
+        if let Some(system) = self.system {
+            outbound["system_interface"] = json!(system);
+        }
+        if let (Some(obj), Value::Object(dial)) = (
+            outbound.as_object_mut(),
+            serde_json::to_value(&self.dial).map_err(|e| e.to_string())?,
+        ) {
+            obj.extend(dial);
+        }
+        Ok(outbound)
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let mut endpoint = WireGuardEndpoint::cloudflare_warp(
+            "warp",
+            "cHJpdmF0ZQ==",
+            vec!["172.16.0.2/32".to_string()],
+        );
+        endpoint.peers[0] = endpoint.peers[0].clone().with_reserved([1, 2, 3]);
+        let json = serde_json::to_value(&endpoint).unwrap();
+        assert_eq!(json["type"], "wireguard");
+        assert_eq!(json["mtu"], 1280);
+        assert_eq!(json["peers"][0]["address"], WARP_PEER_ADDRESS);
+        assert_eq!(
+            json["peers"][0]["allowed_ips"],
+            json!(["0.0.0.0/0", "::/0"])
+        );
+        assert_eq!(json["peers"][0]["reserved"], json!([1, 2, 3]));
+        assert!(json.get("system").is_none());
+
+        let legacy = endpoint.to_legacy_outbound().unwrap();
+        assert_eq!(legacy["server"], WARP_PEER_ADDRESS);
+        assert_eq!(legacy["server_port"], 2408);
+        assert_eq!(legacy["local_address"], json!(["172.16.0.2/32"]));
+        assert_eq!(legacy["peer_public_key"], WARP_PEER_PUBLIC_KEY);
+        assert_eq!(legacy["reserved"], json!([1, 2, 3]));
+
+        endpoint.peers.push(endpoint.peers[0].clone());
+        assert!(endpoint.to_legacy_outbound().is_err());
+    }
+}
@@ -1,7 +1,16 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
-#[derive(Debug, Clone, Serialize)]
+use super::canonical::to_canonical_string;
+use super::endpoint::WireGuardEndpoint;
+use super::jsonc::from_jsonc_str;
+use super::minimal::minimize_config;
+use super::strict::strict_diagnostics;
+use super::types::SingBoxVersion;
+use super::validate::{Diagnostic, has_errors, validate_config};
+use crate::dns::Dns;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SingBoxConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log: Option<Value>,
@@ -9,103 +18,671 @@ pub struct SingBoxConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dns: Option<Value>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ntp: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<Value>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub inbounds: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub outbounds: Vec<Value>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<Value>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub route: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub services: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<Value>,
 }
 
 impl SingBoxConfig {
-    fn default_dns_https() -> Value {
-        json!({
-            "servers": [
-                {
-                    "type": "https",
-                    "tag": "cloudflare",
-                    "server": "1.1.1.1",
-                    "server_port": 443,
-                    "path": "/dns-query"
-                },
-                {
-                    "type": "https",
-                    "tag": "google",
-                    "server": "8.8.8.8",
-                    "server_port": 443,
-                    "path": "/dns-query"
-                }
-            ],
-            "final": "cloudflare"
-        })
+    /// 创建完整配置构建器
+    pub fn builder() -> SingBoxConfigBuilder {
+        SingBoxConfigBuilder::new()
     }
 
-    pub fn server_default(inbounds: Vec<Value>, log_level: &str) -> Self {
-        let log = Some(json!({
-            "level": log_level,
-            "timestamp": true
-        }));
+    /// 替换 DNS 配置
+    pub fn with_dns(mut self, dns: Value) -> Self {
+        self.dns = Some(dns);
+        self
+    }
+
+    /// 设置路由的默认域名解析器（出站解析服务器地址时使用的 DNS 服务器标签）
+    pub fn with_default_domain_resolver(mut self, tag: &str) -> Self {
+        let route = self.route.get_or_insert_with(|| json!({}));
+        route["default_domain_resolver"] = json!(tag);
+        self
+    }
 
-        // DNS module using sing-box 1.12+ "new dns servers" format.
-        // (Avoid legacy `address` field which is deprecated since 1.12.0.)
-        let dns = Some(Self::default_dns_https());
+    /// 追加 DNS 规则（按顺序添加到 `dns.rules` 末尾）
+    pub fn with_dns_rules(mut self, rules: Vec<Value>) -> Self {
+        if rules.is_empty() {
+            return self;
+        }
+        let dns = self.dns.get_or_insert_with(|| json!({}));
+        if !dns.get("rules").is_some_and(Value::is_array) {
+            dns["rules"] = json!([]);
+        }
+        if let Some(existing) = dns["rules"].as_array_mut() {
+            existing.extend(rules);
+        }
+        self
+    }
 
-        let outbounds = vec![
-            json!({ "type": "direct", "tag": "direct" }),
-            json!({ "type": "block", "tag": "block" }),
-        ];
+    /// 追加路由规则（按顺序添加到 `route.rules` 末尾）
+    pub fn with_route_rules(mut self, rules: Vec<Value>) -> Self {
+        if rules.is_empty() {
+            return self;
+        }
+        let route = self.route.get_or_insert_with(|| json!({}));
+        if !route.get("rules").is_some_and(Value::is_array) {
+            route["rules"] = json!([]);
+        }
+        if let Some(existing) = route["rules"].as_array_mut() {
+            existing.extend(rules);
+        }
+        self
+    }
+
+    /// 追加路由规则集（添加到 `route.rule_set`，已存在的标签不重复添加）
+    pub fn with_rule_sets(mut self, rule_sets: Vec<Value>) -> Self {
+        if rule_sets.is_empty() {
+            return self;
+        }
+        let route = self.route.get_or_insert_with(|| json!({}));
+        if !route.get("rule_set").is_some_and(Value::is_array) {
+            route["rule_set"] = json!([]);
+        }
+        if let Some(existing) = route["rule_set"].as_array_mut() {
+            for rule_set in rule_sets {
+                if !existing.iter().any(|r| r["tag"] == rule_set["tag"]) {
+                    existing.push(rule_set);
+                }
+            }
+        }
+        self
+    }
 
-        let route = Some(json!({
-            "rules": [],
-            "default_domain_resolver": "cloudflare",
-            "final": "direct"
-        }));
+    /// 将入站上的嗅探字段（sing-box 1.11 起弃用）改写为路由 sniff 动作
+    ///
+    /// 按嗅探超时分组插入到 `route.rules` 开头；`sniff_override_destination`
+    /// 在路由动作中没有对应选项，随之移除
+    pub fn with_inbound_sniff_rules(mut self) -> Self {
+        let mut groups: Vec<(Option<Value>, Vec<Value>)> = Vec::new();
+        for inbound in &mut self.inbounds {
+            let Some(obj) = inbound.as_object_mut() else {
+                continue;
+            };
+            let sniff = obj.remove("sniff").and_then(|v| v.as_bool());
+            let timeout = obj.remove("sniff_timeout");
+            obj.remove("sniff_override_destination");
+            if sniff != Some(true) {
+                continue;
+            }
+            let tag = obj.get("tag").cloned().unwrap_or(Value::Null);
+            match groups.iter_mut().find(|(t, _)| *t == timeout) {
+                Some((_, tags)) => tags.push(tag),
+                None => groups.push((timeout, vec![tag])),
+            }
+        }
+        if groups.is_empty() {
+            return self;
+        }
 
-        Self {
-            log,
-            dns,
-            inbounds,
-            outbounds,
-            route,
+        let rules: Vec<Value> = groups
+            .into_iter()
+            .map(|(timeout, tags)| {
+                let mut rule = json!({ "action": "sniff" });
+                // 未设置标签的入站无法单独匹配，退化为对全部入站嗅探
+                if !tags.contains(&Value::Null) {
+                    rule["inbound"] = Value::Array(tags);
+                }
+                if let Some(timeout) = timeout {
+                    rule["timeout"] = timeout;
+                }
+                rule
+            })
+            .collect();
+        let route = self.route.get_or_insert_with(|| json!({}));
+        if !route.get("rules").is_some_and(Value::is_array) {
+            route["rules"] = json!([]);
+        }
+        if let Some(existing) = route["rules"].as_array_mut() {
+            existing.splice(0..0, rules);
         }
+        self
     }
 
-    pub fn client_default(proxy_outbound: Value, log_level: &str, mixed_listen: &str, mixed_port: u16) -> Self {
-        let log = Some(json!({
-            "level": log_level,
-            "timestamp": true
-        }));
+    /// 转换为指定 sing-box 版本可识别的格式
+    ///
+    /// - 低于 1.12：DNS 服务器改写为 legacy `address` 格式，移除 `default_domain_resolver`
+    /// - 低于 1.11：路由规则动作改写为 `outbound` 字段，嗅探改为入站 `sniff` 字段，
+    ///   拒绝改为路由到 `block` 出站，DNS 劫持改为路由到 `dns` 出站，WireGuard 端点改写为出站
+    /// - 1.11 及以上：入站上的嗅探字段改写为路由 sniff 动作
+    pub fn for_version(mut self, version: SingBoxVersion) -> Result<Self, String> {
+        if !version.supports_anytls() {
+            let uses_anytls = self
+                .inbounds
+                .iter()
+                .chain(self.outbounds.iter())
+                .any(|v| v["type"] == "anytls");
+            if uses_anytls {
+                return Err(format!(
+                    "AnyTLS 需要 sing-box 1.12 及以上版本（当前目标 {}），请关闭 AnyTLS",
+                    version
+                ));
+            }
+        }
 
-        let dns = Some(Self::default_dns_https());
+        if !version.supports_tun_address() && self.inbounds.iter().any(|v| v["type"] == "tun") {
+            return Err(format!(
+                "TUN 入站需要 sing-box 1.10 及以上版本（当前目标 {}）",
+                version
+            ));
+        }
 
-        let inbounds = vec![json!({
-            "type": "mixed",
-            "tag": "mixed-in",
-            "listen": mixed_listen,
-            "listen_port": mixed_port
-        })];
+        if !version.supports_typed_dns_servers() {
+            if let Some(dns) = self.dns.take() {
+                let dns: Dns = serde_json::from_value(dns).map_err(|e| e.to_string())?;
+                self.dns = Some(dns.to_legacy_value(version)?);
+            }
+            if let Some(route) = self.route.as_mut().and_then(Value::as_object_mut) {
+                route.remove("default_domain_resolver");
+            }
+        }
+
+        if !version.supports_endpoints() {
+            for endpoint in std::mem::take(&mut self.endpoints) {
+                if endpoint["type"] != "wireguard" {
+                    return Err(format!(
+                        "端点 {} 需要 sing-box 1.11 及以上版本",
+                        endpoint["tag"]
+                    ));
+                }
+                let endpoint: WireGuardEndpoint =
+                    serde_json::from_value(endpoint).map_err(|e| e.to_string())?;
+                self.outbounds.push(endpoint.to_legacy_outbound()?);
+            }
+        }
 
-        let outbounds = vec![
-            proxy_outbound,
-            json!({ "type": "direct", "tag": "direct" }),
-            json!({ "type": "block", "tag": "block" }),
-        ];
+        if version.supports_rule_actions() {
+            self = self.with_inbound_sniff_rules();
+        } else {
+            self.legacy_route_rules()?;
+        }
+        Ok(self)
+    }
 
-        let route = Some(json!({
-            "rules": [],
-            "default_domain_resolver": "cloudflare",
-            "final": "proxy"
-        }));
+    /// 将路由规则动作改写为 sing-box 1.11 之前的格式
+    fn legacy_route_rules(&mut self) -> Result<(), String> {
+        let rules = match self
+            .route
+            .as_mut()
+            .and_then(|r| r.get_mut("rules"))
+            .and_then(Value::as_array_mut)
+        {
+            Some(rules) => std::mem::take(rules),
+            None => return Ok(()),
+        };
+
+        let mut legacy = Vec::with_capacity(rules.len());
+        let mut needs_dns_out = false;
+        let mut needs_block = false;
+        for mut rule in rules {
+            let Some(obj) = rule.as_object_mut() else {
+                continue;
+            };
+            let outbound = match obj.remove("action").as_ref().and_then(Value::as_str) {
+                None => {
+                    legacy.push(rule);
+                    continue;
+                }
+                Some("route") => obj.remove("outbound").unwrap_or(Value::Null),
+                Some("reject") => {
+                    obj.remove("method");
+                    obj.remove("no_drop");
+                    needs_block = true;
+                    json!("block")
+                }
+                Some("hijack-dns") => {
+                    needs_dns_out = true;
+                    json!("dns-out")
+                }
+                Some("sniff") => {
+                    // 旧版本在入站上开启嗅探
+                    let tags: Option<Vec<String>> = obj.get("inbound").map(|v| match v {
+                        Value::String(s) => vec![s.clone()],
+                        Value::Array(a) => a
+                            .iter()
+                            .filter_map(|t| t.as_str().map(str::to_string))
+                            .collect(),
+                        _ => Vec::new(),
+                    });
+                    for inbound in &mut self.inbounds {
+                        let matched = match (&tags, inbound["tag"].as_str()) {
+                            (None, _) => true,
+                            (Some(tags), Some(tag)) => tags.iter().any(|t| t == tag),
+                            (Some(_), None) => false,
+                        };
+                        if matched {
+                            inbound["sniff"] = json!(true);
+                        }
+                    }
+                    continue;
+                }
+                Some(other) => {
+                    return Err(format!("路由动作 {} 需要 sing-box 1.11 及以上版本", other));
+                }
+            };
+            obj.insert("outbound".to_string(), outbound);
+            legacy.push(rule);
+        }
 
-        Self {
-            log,
-            dns,
-            inbounds,
-            outbounds,
-            route,
+        if needs_dns_out && !self.outbounds.iter().any(|o| o["tag"] == "dns-out") {
+            self.outbounds
+                .push(json!({ "type": "dns", "tag": "dns-out" }));
         }
+        if needs_block && !self.outbounds.iter().any(|o| o["tag"] == "block") {
+            self.outbounds
+                .push(json!({ "type": "block", "tag": "block" }));
+        }
+        if let Some(route) = self.route.as_mut() {
+            route["rules"] = Value::Array(legacy);
+        }
+        Ok(())
     }
 
     pub fn to_pretty_json_string(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
     }
+
+    /// 去掉与 sing-box 默认值相同的字段，得到等价的最小配置
+    pub fn minimized(&self) -> Result<Self, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        minimize_config(&mut value);
+        serde_json::from_value(value)
+    }
+
+    /// 以规范化字段顺序输出（与结构体声明顺序无关，适合纳入版本管理）
+    pub fn to_canonical_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_value(self).map(|value| to_canonical_string(&value))
+    }
+
+    /// 解析配置 JSON（忽略未知字段）
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 解析带注释的配置（注释输出模式生成的 JSONC，也接受普通 JSON）
+    pub fn from_jsonc(text: &str) -> Result<Self, serde_json::Error> {
+        from_jsonc_str(text)
+    }
+
+    /// 严格模式解析配置 JSON：顶层、DNS 与有强类型定义的入站 / 出站 / 端点中
+    /// 存在未知字段（多为拼写错误）或类型错误时，返回以 JSON Pointer 标注位置的诊断
+    pub fn from_json_strict(json: &str) -> Result<Self, Vec<Diagnostic>> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| vec![Diagnostic::error("", e.to_string())])?;
+        let diagnostics = strict_diagnostics(&value);
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+        serde_json::from_value(value).map_err(|e| vec![Diagnostic::error("", e.to_string())])
+    }
+
+    /// 对配置执行结构校验
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        match serde_json::to_value(self) {
+            Ok(v) => validate_config(&v),
+            Err(e) => vec![Diagnostic::error("$", e.to_string())],
+        }
+    }
+}
+
+//============================================================================
+// 构建器
+//============================================================================
+
+/// 完整配置构建器
+///
+/// 各段接受任意可序列化的值（强类型结构体或 `serde_json::Value`），
+/// `build()` 时统一执行结构校验
+#[derive(Debug, Clone, Default)]
+pub struct SingBoxConfigBuilder {
+    log: Option<Value>,
+    dns: Option<Value>,
+    inbounds: Vec<Value>,
+    outbounds: Vec<Value>,
+    endpoints: Vec<Value>,
+    route: Option<Value>,
+    experimental: Option<Value>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl SingBoxConfigBuilder {
+    /// 创建空构建器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 序列化一段配置，失败时记录诊断并返回 None
+    fn serialize(&mut self, path: String, value: impl Serialize) -> Option<Value> {
+        match serde_json::to_value(value) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.diagnostics
+                    .push(Diagnostic::error(path, e.to_string()));
+                None
+            }
+        }
+    }
+
+    /// 设置日志配置
+    pub fn log(mut self, log: impl Serialize) -> Self {
+        self.log = self.serialize("log".to_string(), log);
+        self
+    }
+
+    /// 设置日志级别（带时间戳）
+    pub fn log_level(self, level: &str) -> Self {
+        self.log(json!({ "level": level, "timestamp": true }))
+    }
+
+    /// 设置 DNS 配置
+    pub fn dns(mut self, dns: impl Serialize) -> Self {
+        self.dns = self.serialize("dns".to_string(), dns);
+        self
+    }
+
+    /// 追加入站
+    pub fn add_inbound(mut self, inbound: impl Serialize) -> Self {
+        let path = format!("inbounds[{}]", self.inbounds.len());
+        if let Some(v) = self.serialize(path, inbound) {
+            self.inbounds.push(v);
+        }
+        self
+    }
+
+    /// 追加出站
+    pub fn add_outbound(mut self, outbound: impl Serialize) -> Self {
+        let path = format!("outbounds[{}]", self.outbounds.len());
+        if let Some(v) = self.serialize(path, outbound) {
+            self.outbounds.push(v);
+        }
+        self
+    }
+
+    /// 追加端点（sing-box 1.11 起可用，路由规则可像出站一样引用其标签）
+    pub fn add_endpoint(mut self, endpoint: impl Serialize) -> Self {
+        let path = format!("endpoints[{}]", self.endpoints.len());
+        if let Some(v) = self.serialize(path, endpoint) {
+            self.endpoints.push(v);
+        }
+        self
+    }
+
+    /// 设置路由配置
+    pub fn route(mut self, route: impl Serialize) -> Self {
+        self.route = self.serialize("route".to_string(), route);
+        self
+    }
+
+    /// 设置实验性配置（Clash API、缓存文件等）
+    pub fn experimental(mut self, experimental: impl Serialize) -> Self {
+        self.experimental = self.serialize("experimental".to_string(), experimental);
+        self
+    }
+
+    /// 组装并校验配置
+    ///
+    /// 存在错误级诊断（序列化失败、重复标签 / 端口、`route.final` 指向不存在的出站等）时返回全部诊断
+    pub fn build(self) -> Result<SingBoxConfig, Vec<Diagnostic>> {
+        let mut diagnostics = self.diagnostics;
+        let config = SingBoxConfig {
+            log: self.log,
+            dns: self.dns,
+            ntp: None,
+            certificate: None,
+            inbounds: self.inbounds,
+            outbounds: self.outbounds,
+            endpoints: self.endpoints,
+            route: self.route,
+            services: None,
+            experimental: self.experimental,
+        };
+
+        let final_tag = config
+            .route
+            .as_ref()
+            .and_then(|r| r.get("final"))
+            .and_then(Value::as_str);
+        let exists = |tag: &str| {
+            config
+                .outbounds
+                .iter()
+                .chain(config.endpoints.iter())
+                .any(|o| o["tag"] == tag)
+        };
+        if let Some(tag) = final_tag.filter(|t| !exists(t)) {
+            diagnostics.push(Diagnostic::error(
+                "route.final",
+                format!("出站 \"{}\" 不存在", tag),
+            ));
+        }
+        diagnostics.extend(config.validate());
+
+        if has_errors(&diagnostics) {
+            Err(diagnostics)
+        } else {
+            Ok(config)
+        }
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SingBoxConfig {
+        SingBoxConfig::builder()
+            .log_level("info")
+            .dns(json!({
+                "servers": [{
+                    "type": "https",
+                    "tag": "cloudflare",
+                    "server": "1.1.1.1",
+                    "server_port": 443,
+                    "path": "/dns-query"
+                }],
+                "final": "cloudflare"
+            }))
+            .add_inbound(json!({ "type": "hysteria2", "tag": "hy2-in", "listen_port": 2053 }))
+            .add_inbound(json!({ "type": "tuic", "tag": "tuic-in", "listen_port": 2083 }))
+            .add_outbound(json!({ "type": "direct", "tag": "direct" }))
+            .route(json!({ "default_domain_resolver": "cloudflare", "final": "direct" }))
+            .build()
+            .unwrap()
+            .with_route_rules(vec![
+                json!({ "inbound": ["tuic-in"], "action": "sniff" }),
+                json!({ "protocol": ["bittorrent"], "action": "reject", "method": "drop" }),
+                json!({ "protocol": ["dns"], "action": "hijack-dns" }),
+                json!({ "ip_is_private": true, "action": "route", "outbound": "direct" }),
+            ])
+    }
+
+    #[test]
+    fn test_builder() {
+        let cfg = sample();
+        let value = serde_json::to_value(&cfg).unwrap();
+        assert_eq!(value["log"]["level"], "info");
+        assert_eq!(value["inbounds"].as_array().unwrap().len(), 2);
+        assert!(value.get("experimental").is_none());
+
+        let cfg = SingBoxConfig::builder()
+            .add_outbound(json!({ "type": "direct", "tag": "direct" }))
+            .experimental(json!({ "cache_file": { "enabled": true } }))
+            .build()
+            .unwrap();
+        assert_eq!(cfg.experimental.unwrap()["cache_file"]["enabled"], true);
+
+        let geoip = json!({ "type": "remote", "tag": "geoip-cn" });
+        let cfg = sample().with_rule_sets(vec![geoip.clone(), geoip]);
+        assert_eq!(cfg.route.unwrap()["rule_set"].as_array().unwrap().len(), 1);
+
+        let errors = SingBoxConfig::builder()
+            .add_inbound(json!({ "type": "tuic", "tag": "in", "listen_port": 443 }))
+            .add_inbound(json!({ "type": "hysteria2", "tag": "in", "listen_port": 8443 }))
+            .route(json!({ "final": "proxy" }))
+            .build()
+            .unwrap_err();
+        let paths: Vec<&str> = errors.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, ["route.final", "inbounds[1].tag"]);
+    }
+
+    #[test]
+    fn test_for_version_legacy() {
+        let cfg = sample().for_version(SingBoxVersion::new(1, 10)).unwrap();
+        let dns = cfg.dns.as_ref().unwrap();
+        assert_eq!(
+            dns["servers"][0]["address"],
+            "https://1.1.1.1:443/dns-query"
+        );
+        let route = cfg.route.as_ref().unwrap();
+        assert!(route.get("default_domain_resolver").is_none());
+        assert_eq!(
+            route["rules"],
+            json!([
+                { "protocol": ["bittorrent"], "outbound": "block" },
+                { "protocol": ["dns"], "outbound": "dns-out" },
+                { "ip_is_private": true, "outbound": "direct" }
+            ])
+        );
+        assert!(cfg.inbounds[0].get("sniff").is_none());
+        assert_eq!(cfg.inbounds[1]["sniff"], true);
+        assert!(cfg.outbounds.iter().any(|o| o["type"] == "dns"));
+        assert!(cfg.outbounds.iter().any(|o| o["type"] == "block"));
+
+        // 1.11 保留规则动作，仅改写 DNS 服务器
+        let cfg = sample().for_version(SingBoxVersion::new(1, 11)).unwrap();
+        assert_eq!(cfg.route.as_ref().unwrap()["rules"][0]["action"], "sniff");
+        assert!(
+            cfg.dns.as_ref().unwrap()["servers"][0]
+                .get("type")
+                .is_none()
+        );
+
+        // 最新版本不做改动
+        let cfg = sample().for_version(SingBoxVersion::new(1, 12)).unwrap();
+        assert_eq!(cfg.dns, sample().dns);
+        assert_eq!(cfg.route, sample().route);
+
+        // 1.11 之前 WireGuard 端点改写为出站
+        let warp = SingBoxConfig::builder()
+            .add_endpoint(WireGuardEndpoint::cloudflare_warp(
+                "warp",
+                "key",
+                vec!["172.16.0.2/32".to_string()],
+            ))
+            .route(json!({ "final": "warp" }))
+            .build()
+            .unwrap();
+        assert!(
+            warp.clone()
+                .for_version(SingBoxVersion::new(1, 11))
+                .unwrap()
+                .outbounds
+                .is_empty()
+        );
+        let legacy = warp.for_version(SingBoxVersion::new(1, 10)).unwrap();
+        assert!(legacy.endpoints.is_empty());
+        assert_eq!(legacy.outbounds[0]["type"], "wireguard");
+        assert_eq!(legacy.outbounds[0]["tag"], "warp");
+
+        let anytls = SingBoxConfig::builder()
+            .add_inbound(json!({ "type": "anytls" }))
+            .build()
+            .unwrap();
+        assert!(anytls.for_version(SingBoxVersion::new(1, 11)).is_err());
+
+        let tun = SingBoxConfig::builder()
+            .add_inbound(json!({ "type": "tun", "tag": "tun-in" }))
+            .build()
+            .unwrap();
+        assert!(tun.clone().for_version(SingBoxVersion::new(1, 9)).is_err());
+        assert!(tun.for_version(SingBoxVersion::new(1, 10)).is_ok());
+    }
+
+    #[test]
+    fn test_inbound_sniff_rules() {
+        let cfg = SingBoxConfig::builder()
+            .add_inbound(json!({ "type": "mixed", "tag": "a", "sniff": true }))
+            .add_inbound(json!({
+                "type": "vless",
+                "tag": "b",
+                "sniff": true,
+                "sniff_override_destination": true,
+                "sniff_timeout": "1s"
+            }))
+            .add_inbound(json!({ "type": "tuic", "tag": "c", "sniff": true }))
+            .add_inbound(json!({ "type": "hysteria2", "tag": "d", "sniff": false }))
+            .add_outbound(json!({ "type": "direct", "tag": "direct" }))
+            .route(json!({ "rules": [{ "ip_is_private": true, "action": "reject" }] }))
+            .build()
+            .unwrap();
+
+        let modern = cfg.clone().with_inbound_sniff_rules();
+        assert_eq!(
+            modern.route.as_ref().unwrap()["rules"],
+            json!([
+                { "action": "sniff", "inbound": ["a", "c"] },
+                { "action": "sniff", "inbound": ["b"], "timeout": "1s" },
+                { "ip_is_private": true, "action": "reject" }
+            ])
+        );
+        assert!(modern.inbounds.iter().all(|i| i.get("sniff").is_none()));
+        assert!(
+            modern.inbounds[1]
+                .get("sniff_override_destination")
+                .is_none()
+        );
+
+        // 旧版本保留入站字段
+        let legacy = cfg.for_version(SingBoxVersion::new(1, 10)).unwrap();
+        assert_eq!(legacy.inbounds[1]["sniff_override_destination"], true);
+        assert_eq!(legacy.inbounds[0]["sniff"], true);
+    }
+
+    #[test]
+    fn test_from_json_strict() {
+        let json = r#"{
+            "log": { "level": "warn" },
+            "inbounds": [{ "type": "tuic", "tag": "tuic-in", "listen": "::", "listen_port": 443,
+                           "users": [{ "uuid": "00000000-0000-0000-0000-000000000000" }],
+                           "tls": { "enabled": true, "server_name": "a.example" },
+                           "congestion_controll": "bbr" }],
+            "outbounds": [{ "type": "direct", "tag": "direct" }]
+        }"#;
+        let cfg = SingBoxConfig::from_json(json).unwrap();
+        assert_eq!(cfg.inbounds.len(), 1);
+        assert!(cfg.endpoints.is_empty());
+
+        let errors = SingBoxConfig::from_json_strict(json).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/inbounds/0/congestion_controll");
+
+        let fixed = json.replace("congestion_controll", "congestion_control");
+        let cfg = SingBoxConfig::from_json_strict(&fixed).unwrap();
+        assert_eq!(cfg.outbounds[0]["tag"], "direct");
+    }
 }
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::singboxconfig::shared::{InboundTlsConfig, ListenFields};
-use crate::singboxconfig::types::UserWithPassword;
+use crate::singboxconfig::types::{Duration, UserWithPassword};
 
 //============================================================================
 // AnyTLS 入站配置（服务端）
@@ -10,7 +10,7 @@ use crate::singboxconfig::types::UserWithPassword;
 /// AnyTLS 入站配置（服务端）
 /// 自sing-box 1.12.0 起可用
 /// 文档: https://sing-box.sagernet.org/configuration/inbound/anytls/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct AnyTlsInbound {
     /// 入站类型，固定为 "anytls"
     #[serde(rename = "type")]
@@ -91,6 +91,48 @@ impl AnyTlsInbound {
         self
     }
 
+    /// 设置是否启用 TCP 快速打开
+    pub fn with_tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_fast_open(enabled);
+        self
+    }
+
+    /// 设置是否启用 TCP 多路径
+    pub fn with_tcp_multi_path(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_multi_path(enabled);
+        self
+    }
+
+    /// 设置是否启用 UDP 分片
+    pub fn with_udp_fragment(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_udp_fragment(enabled);
+        self
+    }
+
+    /// 设置 UDP NAT 过期时间
+    pub fn with_udp_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_udp_timeout(timeout);
+        self
+    }
+
+    /// 设置是否启用协议嗅探
+    pub fn with_sniff(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff(enabled);
+        self
+    }
+
+    /// 设置是否使用嗅探到的域名覆盖连接目标地址
+    pub fn with_sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff_override_destination(enabled);
+        self
+    }
+
+    /// 设置嗅探超时时间
+    pub fn with_sniff_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_sniff_timeout(timeout);
+        self
+    }
+
     /// 获取默认填充方案
     /// 文档: https://sing-box.sagernet.org/configuration/inbound/anytls/
     pub fn default_padding_scheme() -> Vec<String> {
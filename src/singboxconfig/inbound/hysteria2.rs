@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::singboxconfig::shared::{InboundTlsConfig, ListenFields};
-use crate::singboxconfig::types::UserWithPassword;
+use crate::singboxconfig::types::{Duration, UserWithPassword};
 
 //============================================================================
 // Hysteria2 入站配置（服务端）
@@ -9,7 +9,7 @@ use crate::singboxconfig::types::UserWithPassword;
 
 /// Hysteria2 入站配置（服务端）
 /// 文档: https://sing-box.sagernet.org/configuration/inbound/hysteria2/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Hysteria2Inbound {
     /// 入站类型，固定为 "hysteria2"
     #[serde(rename = "type")]
@@ -61,7 +61,7 @@ pub struct Hysteria2Inbound {
 }
 
 /// Hysteria2 混淆配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Hysteria2Obfs {
     /// 混淆类型，目前只支持 "salamander"
     #[serde(rename = "type")]
@@ -73,7 +73,7 @@ pub struct Hysteria2Obfs {
 
 /// Hysteria2 伪装配置
 /// 可以是 URL 字符串或配置对象
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Hysteria2Masquerade {
     /// URL 字符串配置
@@ -84,7 +84,7 @@ pub enum Hysteria2Masquerade {
 }
 
 /// Hysteria2 伪装配置对象
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Hysteria2MasqueradeConfig {
     /// 伪装类型
     /// file: 作为文件服务器
@@ -111,7 +111,7 @@ pub struct Hysteria2MasqueradeConfig {
 
     /// 固定响应头（type = "string" 时使用）
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<std::collections::HashMap<String, String>>,
+    pub headers: Option<std::collections::BTreeMap<String, String>>,
 
     /// 固定响应内容（type = "string" 时使用）
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -119,7 +119,7 @@ pub struct Hysteria2MasqueradeConfig {
 }
 
 /// 伪装类型
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MasqueradeType {
     /// 作为文件服务器
@@ -130,6 +130,63 @@ pub enum MasqueradeType {
     String,
 }
 
+impl std::str::FromStr for MasqueradeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "file" => Ok(MasqueradeType::File),
+            "proxy" => Ok(MasqueradeType::Proxy),
+            "string" => Ok(MasqueradeType::String),
+            other => Err(format!(
+                "未知的伪装类型: {}（可选 proxy / file / string）",
+                other
+            )),
+        }
+    }
+}
+
+impl Hysteria2Masquerade {
+    /// 作为文件服务器，提供指定目录下的文件
+    pub fn file(directory: impl Into<String>) -> Self {
+        Hysteria2Masquerade::Config(Hysteria2MasqueradeConfig {
+            masquerade_type: MasqueradeType::File,
+            directory: Some(directory.into()),
+            url: None,
+            rewrite_host: None,
+            status_code: None,
+            headers: None,
+            content: None,
+        })
+    }
+
+    /// 作为反向代理，转发到指定 URL
+    pub fn proxy(url: impl Into<String>, rewrite_host: bool) -> Self {
+        Hysteria2Masquerade::Config(Hysteria2MasqueradeConfig {
+            masquerade_type: MasqueradeType::Proxy,
+            directory: None,
+            url: Some(url.into()),
+            rewrite_host: Some(rewrite_host),
+            status_code: None,
+            headers: None,
+            content: None,
+        })
+    }
+
+    /// 返回固定状态码与响应内容
+    pub fn string(status_code: u16, content: Option<String>) -> Self {
+        Hysteria2Masquerade::Config(Hysteria2MasqueradeConfig {
+            masquerade_type: MasqueradeType::String,
+            directory: None,
+            url: None,
+            rewrite_host: None,
+            status_code: Some(status_code),
+            headers: None,
+            content,
+        })
+    }
+}
+
 impl Hysteria2Inbound {
     /// 创建新的 Hysteria2 入站配置
     pub fn new(tag: impl Into<String>) -> Self {
@@ -194,32 +251,25 @@ impl Hysteria2Inbound {
         self
     }
 
-    /// 设置文件服务器伪装
-    pub fn with_masquerade_file(mut self, directory: impl Into<String>) -> Self {
-        self.masquerade = Some(Hysteria2Masquerade::Config(Hysteria2MasqueradeConfig {
-            masquerade_type: MasqueradeType::File,
-            directory: Some(directory.into()),
-            url: None,
-            rewrite_host: None,
-            status_code: None,
-            headers: None,
-            content: None,
-        }));
+    /// 设置伪装
+    pub fn with_masquerade(mut self, masquerade: Hysteria2Masquerade) -> Self {
+        self.masquerade = Some(masquerade);
         self
     }
 
+    /// 设置文件服务器伪装
+    pub fn with_masquerade_file(self, directory: impl Into<String>) -> Self {
+        self.with_masquerade(Hysteria2Masquerade::file(directory))
+    }
+
     /// 设置反向代理伪装
-    pub fn with_masquerade_proxy(mut self, url: impl Into<String>, rewrite_host: bool) -> Self {
-        self.masquerade = Some(Hysteria2Masquerade::Config(Hysteria2MasqueradeConfig {
-            masquerade_type: MasqueradeType::Proxy,
-            directory: None,
-            url: Some(url.into()),
-            rewrite_host: Some(rewrite_host),
-            status_code: None,
-            headers: None,
-            content: None,
-        }));
-        self
+    pub fn with_masquerade_proxy(self, url: impl Into<String>, rewrite_host: bool) -> Self {
+        self.with_masquerade(Hysteria2Masquerade::proxy(url, rewrite_host))
+    }
+
+    /// 设置固定响应伪装
+    pub fn with_masquerade_string(self, status_code: u16, content: Option<String>) -> Self {
+        self.with_masquerade(Hysteria2Masquerade::string(status_code, content))
     }
 
     /// 忽略客户端带宽设置
@@ -239,6 +289,48 @@ impl Hysteria2Inbound {
         self.listen = listen;
         self
     }
+
+    /// 设置是否启用 TCP 快速打开
+    pub fn with_tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_fast_open(enabled);
+        self
+    }
+
+    /// 设置是否启用 TCP 多路径
+    pub fn with_tcp_multi_path(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_multi_path(enabled);
+        self
+    }
+
+    /// 设置是否启用 UDP 分片
+    pub fn with_udp_fragment(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_udp_fragment(enabled);
+        self
+    }
+
+    /// 设置 UDP NAT 过期时间
+    pub fn with_udp_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_udp_timeout(timeout);
+        self
+    }
+
+    /// 设置是否启用协议嗅探
+    pub fn with_sniff(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff(enabled);
+        self
+    }
+
+    /// 设置是否使用嗅探到的域名覆盖连接目标地址
+    pub fn with_sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff_override_destination(enabled);
+        self
+    }
+
+    /// 设置嗅探超时时间
+    pub fn with_sniff_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_sniff_timeout(timeout);
+        self
+    }
 }
 
 impl Hysteria2Obfs {
@@ -316,6 +408,29 @@ mod tests {
         assert!(json.contains("\"salamander\""));
     }
 
+    #[test]
+    fn test_masquerade() {
+        let inbound =
+            Hysteria2Inbound::new("hy2-in").with_masquerade_proxy("https://bing.com", true);
+        let v = serde_json::to_value(&inbound).unwrap();
+        assert_eq!(v["masquerade"]["type"], "proxy");
+        assert_eq!(v["masquerade"]["url"], "https://bing.com");
+        assert_eq!(v["masquerade"]["rewrite_host"], true);
+        assert!(v["masquerade"].get("directory").is_none());
+
+        let inbound = Hysteria2Inbound::new("hy2-in").with_masquerade_string(404, None);
+        let v = serde_json::to_value(&inbound).unwrap();
+        assert_eq!(v["masquerade"]["type"], "string");
+        assert_eq!(v["masquerade"]["status_code"], 404);
+
+        let inbound = Hysteria2Inbound::new("hy2-in").with_masquerade_url("file:///var/www");
+        let v = serde_json::to_value(&inbound).unwrap();
+        assert_eq!(v["masquerade"], "file:///var/www");
+
+        assert_eq!("File".parse::<MasqueradeType>(), Ok(MasqueradeType::File));
+        assert!("redirect".parse::<MasqueradeType>().is_err());
+    }
+
     #[test]
     fn test_deserialize() {
         let json = r#"{
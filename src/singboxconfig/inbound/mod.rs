@@ -4,12 +4,18 @@
 
 mod anytls;
 mod hysteria2;
+mod trojan;
 mod tuic;
+mod tun;
 mod vless;
+mod vmess;
 
 pub use anytls::AnyTlsInbound;
 pub use hysteria2::{
     Hysteria2Inbound, Hysteria2Masquerade, Hysteria2MasqueradeConfig, Hysteria2Obfs, MasqueradeType,
 };
-pub use tuic::{CongestionControl, TuicInbound};
+pub use trojan::{TrojanFallback, TrojanInbound};
+pub use tuic::{CongestionControl, TuicInbound, UdpRelayMode};
+pub use tun::{TUN_INET4_ADDRESS, TUN_INET6_ADDRESS, TunInbound};
 pub use vless::{VlessFlow, VlessInbound, VlessUser};
+pub use vmess::VmessInbound;
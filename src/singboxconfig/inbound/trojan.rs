@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::singboxconfig::shared::{
+    InboundTlsConfig, ListenFields, MultiplexInbound, V2RayTransport,
+};
+use crate::singboxconfig::types::UserWithPassword;
+
+//============================================================================
+// Trojan 入站配置（服务端）
+//============================================================================
+
+/// Trojan 入站配置（服务端）
+/// 文档: https://sing-box.sagernet.org/configuration/inbound/trojan/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TrojanInbound {
+    /// 入站类型，固定为 "trojan"
+    #[serde(rename = "type")]
+    pub inbound_type: String,
+
+    /// 入站标签
+    pub tag: String,
+
+    /// 监听字段
+    #[serde(flatten)]
+    pub listen: ListenFields,
+
+    /// Trojan 用户列表（必填）
+    pub users: Vec<UserWithPassword>,
+
+    /// TLS 配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<InboundTlsConfig>,
+
+    /// 回落服务器
+    /// 认证失败的连接转发到此地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<TrojanFallback>,
+
+    /// 按 TLS ALPN 选择的回落服务器
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_for_alpn: Option<BTreeMap<String, TrojanFallback>>,
+
+    /// 多路复用配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<MultiplexInbound>,
+
+    /// V2Ray 传输配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<V2RayTransport>,
+}
+
+//============================================================================
+// Trojan 回落
+//============================================================================
+
+/// Trojan 回落服务器
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TrojanFallback {
+    /// 回落服务器地址
+    pub server: String,
+
+    /// 回落服务器端口
+    pub server_port: u16,
+}
+
+//============================================================================
+// TrojanInbound 实现
+//============================================================================
+
+impl TrojanInbound {
+    /// 创建新的 Trojan 入站配置
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            inbound_type: "trojan".to_string(),
+            tag: tag.into(),
+            listen: ListenFields::default(),
+            users: Vec::new(),
+            tls: None,
+            fallback: None,
+            fallback_for_alpn: None,
+            multiplex: None,
+            transport: None,
+        }
+    }
+
+    /// 添加用户
+    pub fn add_user(mut self, name: impl Into<String>, password: impl Into<String>) -> Self {
+        self.users.push(UserWithPassword::new(name, password));
+        self
+    }
+
+    /// 设置监听地址
+    pub fn with_listen(mut self, listen: impl Into<String>) -> Self {
+        self.listen.listen = listen.into();
+        self
+    }
+
+    /// 设置监听端口
+    pub fn with_listen_port(mut self, port: u16) -> Self {
+        self.listen.listen_port = Some(port);
+        self
+    }
+
+    /// 设置监听字段
+    pub fn with_listen_fields(mut self, listen: ListenFields) -> Self {
+        self.listen = listen;
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: InboundTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 设置回落服务器
+    pub fn with_fallback(mut self, server: impl Into<String>, server_port: u16) -> Self {
+        self.fallback = Some(TrojanFallback {
+            server: server.into(),
+            server_port,
+        });
+        self
+    }
+
+    /// 设置多路复用配置
+    pub fn with_multiplex(mut self, multiplex: MultiplexInbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置 V2Ray 传输配置
+    pub fn with_transport(mut self, transport: V2RayTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+}
+
+impl Default for TrojanInbound {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let inbound = TrojanInbound::new("trojan-in");
+        assert_eq!(inbound.inbound_type, "trojan");
+        assert_eq!(inbound.tag, "trojan-in");
+        assert!(inbound.users.is_empty());
+        assert!(inbound.fallback.is_none());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let inbound = TrojanInbound::new("trojan-in")
+            .with_listen("::")
+            .with_listen_port(443)
+            .add_user("sekai", "password")
+            .with_fallback("127.0.0.1", 8080);
+
+        let json = serde_json::to_value(&inbound).unwrap();
+        assert_eq!(json["type"], "trojan");
+        assert_eq!(json["users"][0]["password"], "password");
+        assert_eq!(json["fallback"]["server"], "127.0.0.1");
+        assert_eq!(json["fallback"]["server_port"], 8080);
+        assert!(json.get("fallback_for_alpn").is_none());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "trojan",
+            "tag": "trojan-in",
+            "listen": "::",
+            "listen_port": 443,
+            "users": [{ "name": "sekai", "password": "password" }],
+            "fallback_for_alpn": {
+                "h2": { "server": "127.0.0.1", "server_port": 8081 }
+            }
+        }"#;
+
+        let inbound: TrojanInbound = serde_json::from_str(json).unwrap();
+        assert_eq!(inbound.users[0].name, "sekai");
+        assert_eq!(inbound.fallback_for_alpn.unwrap()["h2"].server_port, 8081);
+    }
+}
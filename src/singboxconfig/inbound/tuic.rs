@@ -9,7 +9,7 @@ use crate::singboxconfig::types::{Duration, TuicUser};
 
 /// QUIC 拥塞控制算法
 /// 文档: https://sing-box.sagernet.org/configuration/inbound/tuic/
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum CongestionControl {
     /// Cubic 拥塞控制算法（默认）
@@ -21,13 +21,49 @@ pub enum CongestionControl {
     Bbr,
 }
 
+/// TUIC UDP 中继模式（客户端设置，服务端两种模式均接受）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UdpRelayMode {
+    /// 原生 UDP（默认）
+    #[default]
+    Native,
+    /// 通过 QUIC 流传输，无丢包但延迟更高
+    Quic,
+}
+
+impl UdpRelayMode {
+    /// 获取配置与分享链接中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UdpRelayMode::Native => "native",
+            UdpRelayMode::Quic => "quic",
+        }
+    }
+}
+
+impl std::str::FromStr for UdpRelayMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "native" => Ok(UdpRelayMode::Native),
+            "quic" => Ok(UdpRelayMode::Quic),
+            other => Err(format!(
+                "未知的 UDP 中继模式: {}（可选 native / quic）",
+                other
+            )),
+        }
+    }
+}
+
 //============================================================================
 // TUIC 入站配置（服务端）
 //============================================================================
 
 /// TUIC 入站配置（服务端）
 /// 文档: https://sing-box.sagernet.org/configuration/inbound/tuic/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct TuicInbound {
     /// 入站类型，固定为 "tuic"
     #[serde(rename = "type")]
@@ -157,6 +193,48 @@ impl TuicInbound {
         self.listen = listen;
         self
     }
+
+    /// 设置是否启用 TCP 快速打开
+    pub fn with_tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_fast_open(enabled);
+        self
+    }
+
+    /// 设置是否启用 TCP 多路径
+    pub fn with_tcp_multi_path(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_multi_path(enabled);
+        self
+    }
+
+    /// 设置是否启用 UDP 分片
+    pub fn with_udp_fragment(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_udp_fragment(enabled);
+        self
+    }
+
+    /// 设置 UDP NAT 过期时间
+    pub fn with_udp_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_udp_timeout(timeout);
+        self
+    }
+
+    /// 设置是否启用协议嗅探
+    pub fn with_sniff(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff(enabled);
+        self
+    }
+
+    /// 设置是否使用嗅探到的域名覆盖连接目标地址
+    pub fn with_sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff_override_destination(enabled);
+        self
+    }
+
+    /// 设置嗅探超时时间
+    pub fn with_sniff_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_sniff_timeout(timeout);
+        self
+    }
 }
 
 impl Default for TuicInbound {
@@ -173,6 +251,17 @@ impl Default for TuicInbound {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_udp_relay_mode() {
+        assert_eq!("QUIC".parse::<UdpRelayMode>(), Ok(UdpRelayMode::Quic));
+        assert_eq!(UdpRelayMode::default().as_str(), "native");
+        assert!("udp".parse::<UdpRelayMode>().is_err());
+        assert_eq!(
+            serde_json::to_value(UdpRelayMode::Quic).unwrap(),
+            serde_json::json!("quic")
+        );
+    }
+
     #[test]
     fn test_congestion_control_serialize() {
         assert_eq!(
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+//============================================================================
+// TUN 入站配置（客户端）
+//============================================================================
+
+/// TUN 默认 IPv4 地址
+pub const TUN_INET4_ADDRESS: &str = "172.19.0.1/30";
+
+/// TUN 默认 IPv6 地址
+pub const TUN_INET6_ADDRESS: &str = "fdfe:dcba:9876::1/126";
+
+/// TUN 入站配置（客户端）
+/// `address` / `auto_redirect` 字段自 sing-box 1.10.0 起可用
+/// 文档: https://sing-box.sagernet.org/configuration/inbound/tun/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TunInbound {
+    /// 入站类型，固定为 "tun"
+    #[serde(rename = "type")]
+    pub inbound_type: String,
+
+    /// 入站标签
+    pub tag: String,
+
+    /// 虚拟网卡名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface_name: Option<String>,
+
+    /// 虚拟网卡地址（CIDR）
+    pub address: Vec<String>,
+
+    /// MTU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u32>,
+
+    /// 自动设置默认路由
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_route: Option<bool>,
+
+    /// 严格路由（防止流量绕过 TUN）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict_route: Option<bool>,
+
+    /// 使用 nftables 自动配置重定向（仅 Linux，需要 auto_route）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_redirect: Option<bool>,
+
+    /// 协议栈：system、gvisor 或 mixed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+
+    /// 只代理指定的 Android 应用（包名）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_package: Option<Vec<String>>,
+
+    /// 不代理指定的 Android 应用（包名）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_package: Option<Vec<String>>,
+}
+
+impl TunInbound {
+    /// 创建使用默认地址的 TUN 入站配置
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            inbound_type: "tun".to_string(),
+            tag: tag.into(),
+            interface_name: None,
+            address: vec![TUN_INET4_ADDRESS.to_string(), TUN_INET6_ADDRESS.to_string()],
+            mtu: None,
+            auto_route: None,
+            strict_route: None,
+            auto_redirect: None,
+            stack: None,
+            include_package: None,
+            exclude_package: None,
+        }
+    }
+
+    /// 设置 MTU
+    pub fn with_mtu(mut self, mtu: u32) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    /// 设置自动路由与严格路由
+    pub fn with_auto_route(mut self, strict: bool) -> Self {
+        self.auto_route = Some(true);
+        self.strict_route = Some(strict);
+        self
+    }
+
+    /// 启用 nftables 自动重定向（同时启用自动路由）
+    pub fn with_auto_redirect(mut self) -> Self {
+        self.auto_route = Some(true);
+        self.auto_redirect = Some(true);
+        self
+    }
+
+    /// 设置协议栈
+    pub fn with_stack(mut self, stack: impl Into<String>) -> Self {
+        self.stack = Some(stack.into());
+        self
+    }
+
+    /// 只代理指定的 Android 应用
+    pub fn with_include_packages(mut self, packages: Vec<String>) -> Self {
+        self.include_package = (!packages.is_empty()).then_some(packages);
+        self
+    }
+
+    /// 不代理指定的 Android 应用
+    pub fn with_exclude_packages(mut self, packages: Vec<String>) -> Self {
+        self.exclude_package = (!packages.is_empty()).then_some(packages);
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize() {
+        let tun = TunInbound::new("tun-in")
+            .with_auto_redirect()
+            .with_stack("mixed")
+            .with_include_packages(vec!["com.android.chrome".to_string()])
+            .with_exclude_packages(Vec::new());
+        let json = serde_json::to_value(&tun).unwrap();
+        assert_eq!(json["type"], "tun");
+        assert_eq!(
+            json["address"],
+            json!([TUN_INET4_ADDRESS, TUN_INET6_ADDRESS])
+        );
+        assert_eq!(json["auto_route"], true);
+        assert_eq!(json["auto_redirect"], true);
+        assert_eq!(json["include_package"], json!(["com.android.chrome"]));
+        assert!(json.get("exclude_package").is_none());
+        assert!(json.get("strict_route").is_none());
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::singboxconfig::shared::{
     InboundTlsConfig, ListenFields, MultiplexInbound, V2RayTransport,
 };
+use crate::singboxconfig::types::Duration;
 
 //============================================================================
 // VLESS入站配置（服务端）
@@ -10,7 +11,7 @@ use crate::singboxconfig::shared::{
 
 /// VLESS 入站配置（服务端）
 /// 文档: https://sing-box.sagernet.org/configuration/inbound/vless/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct VlessInbound {
     /// 入站类型，固定为 "vless"
     #[serde(rename = "type")]
@@ -44,7 +45,7 @@ pub struct VlessInbound {
 //============================================================================
 
 /// VLESS 用户
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct VlessUser {
     /// 用户名
     pub name: String,
@@ -63,7 +64,7 @@ pub struct VlessUser {
 //============================================================================
 
 /// VLESS 子协议
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum VlessFlow {
     /// XTLS Vision 流控
@@ -129,6 +130,48 @@ impl VlessInbound {
         self
     }
 
+    /// 设置是否启用 TCP 快速打开
+    pub fn with_tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_fast_open(enabled);
+        self
+    }
+
+    /// 设置是否启用 TCP 多路径
+    pub fn with_tcp_multi_path(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_tcp_multi_path(enabled);
+        self
+    }
+
+    /// 设置是否启用 UDP 分片
+    pub fn with_udp_fragment(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_udp_fragment(enabled);
+        self
+    }
+
+    /// 设置 UDP NAT 过期时间
+    pub fn with_udp_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_udp_timeout(timeout);
+        self
+    }
+
+    /// 设置是否启用协议嗅探
+    pub fn with_sniff(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff(enabled);
+        self
+    }
+
+    /// 设置是否使用嗅探到的域名覆盖连接目标地址
+    pub fn with_sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.listen = self.listen.with_sniff_override_destination(enabled);
+        self
+    }
+
+    /// 设置嗅探超时时间
+    pub fn with_sniff_timeout(mut self, timeout: Duration) -> Self {
+        self.listen = self.listen.with_sniff_timeout(timeout);
+        self
+    }
+
     /// 设置 TLS 配置
     pub fn with_tls(mut self, tls: InboundTlsConfig) -> Self {
         self.tls = Some(tls);
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::{
+    InboundTlsConfig, ListenFields, MultiplexInbound, V2RayTransport,
+};
+use crate::singboxconfig::types::VMessUser;
+
+//============================================================================
+// VMess 入站配置（服务端）
+//============================================================================
+
+/// VMess 入站配置（服务端）
+/// 文档: https://sing-box.sagernet.org/configuration/inbound/vmess/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct VmessInbound {
+    /// 入站类型，固定为 "vmess"
+    #[serde(rename = "type")]
+    pub inbound_type: String,
+
+    /// 入站标签
+    pub tag: String,
+
+    /// 监听字段
+    #[serde(flatten)]
+    pub listen: ListenFields,
+
+    /// VMess 用户列表（必填）
+    pub users: Vec<VMessUser>,
+
+    /// TLS 配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<InboundTlsConfig>,
+
+    /// 多路复用配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<MultiplexInbound>,
+
+    /// V2Ray 传输配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<V2RayTransport>,
+}
+
+//============================================================================
+// VmessInbound 实现
+//============================================================================
+
+impl VmessInbound {
+    /// 创建新的 VMess 入站配置
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            inbound_type: "vmess".to_string(),
+            tag: tag.into(),
+            listen: ListenFields::default(),
+            users: Vec::new(),
+            tls: None,
+            multiplex: None,
+            transport: None,
+        }
+    }
+
+    /// 添加用户
+    pub fn add_user(mut self, user: VMessUser) -> Self {
+        self.users.push(user);
+        self
+    }
+
+    /// 添加简单用户（仅名称和 UUID，alterId 为 0）
+    pub fn add_simple_user(mut self, name: impl Into<String>, uuid: impl Into<String>) -> Self {
+        self.users.push(VMessUser::new(name, uuid));
+        self
+    }
+
+    /// 设置监听地址
+    pub fn with_listen(mut self, listen: impl Into<String>) -> Self {
+        self.listen.listen = listen.into();
+        self
+    }
+
+    /// 设置监听端口
+    pub fn with_listen_port(mut self, port: u16) -> Self {
+        self.listen.listen_port = Some(port);
+        self
+    }
+
+    /// 设置监听字段
+    pub fn with_listen_fields(mut self, listen: ListenFields) -> Self {
+        self.listen = listen;
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: InboundTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 设置多路复用配置
+    pub fn with_multiplex(mut self, multiplex: MultiplexInbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置 V2Ray 传输配置
+    pub fn with_transport(mut self, transport: V2RayTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+}
+
+impl Default for VmessInbound {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let inbound = VmessInbound::new("vmess-in");
+        assert_eq!(inbound.inbound_type, "vmess");
+        assert_eq!(inbound.tag, "vmess-in");
+        assert!(inbound.users.is_empty());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let inbound = VmessInbound::new("vmess-in")
+            .with_listen("::")
+            .with_listen_port(10086)
+            .add_simple_user("sekai", "bf000d23-0752-40b4-affe-68f7707a9661")
+            .with_transport(V2RayTransport::ws());
+
+        let json = serde_json::to_value(&inbound).unwrap();
+        assert_eq!(json["type"], "vmess");
+        assert_eq!(json["listen_port"], 10086);
+        assert_eq!(json["users"][0]["alterId"], 0);
+        assert_eq!(json["transport"]["type"], "ws");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "vmess",
+            "tag": "vmess-in",
+            "listen": "::",
+            "listen_port": 10086,
+            "users": [
+                {
+                    "name": "sekai",
+                    "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+                    "alterId": 0
+                }
+            ]
+        }"#;
+
+        let inbound: VmessInbound = serde_json::from_str(json).unwrap();
+        assert_eq!(inbound.tag, "vmess-in");
+        assert_eq!(inbound.users.len(), 1);
+        assert_eq!(inbound.users[0].alter_id, 0);
+    }
+}
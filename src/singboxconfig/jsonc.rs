@@ -0,0 +1,152 @@
+//! 带注释的 JSON（JSONC）
+//!
+//! 注释输出模式在各段配置前写入 `//` 注释，说明由哪些环境变量生成、凭证的用途。
+//! sing-box 读取配置时忽略注释，可以直接使用；本工具读取已有配置（diff、health、诊断包）时
+//! 先去掉注释与末尾多余的逗号再按普通 JSON 解析
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::canonical::to_commented_string;
+
+/// 注释表：JSON Pointer（如 `/inbounds/0/users`）→ 注释文本（可含多行），空 Pointer 为文件开头的注释
+pub type Annotations = BTreeMap<String, String>;
+
+/// 以规范化字段顺序输出带注释的 JSON
+pub fn to_jsonc_string(value: &Value, notes: &Annotations) -> String {
+    to_commented_string(value, notes)
+}
+
+/// 解析 JSONC（也接受普通 JSON）
+pub fn from_jsonc_str<T: DeserializeOwned>(text: &str) -> serde_json::Result<T> {
+    serde_json::from_str(&strip_comments(text))
+}
+
+/// 去掉字符串之外的 `//` 与 `/* */` 注释以及对象 / 数组末尾多余的逗号
+/// 被去掉的字符替换为空格（换行保留），解析错误报告的行列号与原文一致
+pub fn strip_comments(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out: Vec<char> = Vec::with_capacity(chars.len());
+    // 最近一个尚未确定是否多余的逗号在 out 中的位置
+    let mut pending_comma: Option<usize> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                pending_comma = None;
+                out.push(c);
+                i += 1;
+                while i < chars.len() {
+                    out.push(chars[i]);
+                    match chars[i] {
+                        '\\' if i + 1 < chars.len() => {
+                            out.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        '"' => {
+                            i += 1;
+                            break;
+                        }
+                        _ => i += 1,
+                    }
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(' ');
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let end = (i + 2..chars.len().saturating_sub(1))
+                    .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                    .map_or(chars.len(), |j| j + 2);
+                out.extend(
+                    chars[i..end]
+                        .iter()
+                        .map(|&c| if c == '\n' { c } else { ' ' }),
+                );
+                i = end;
+                continue;
+            }
+            ',' => pending_comma = Some(out.len()),
+            '}' | ']' => {
+                if let Some(pos) = pending_comma.take() {
+                    out[pos] = ' ';
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => pending_comma = None,
+        }
+        out.push(c);
+        i += 1;
+    }
+    out.into_iter().collect()
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singboxconfig::canonical::to_canonical_string;
+    use serde_json::json;
+
+    #[test]
+    fn test_strip_comments() {
+        let text = r#"// 头部注释
+{
+  /* 块注释
+     跨行 */
+  "a": "http://x/*y*/", // 行尾注释
+  "b": [1, 2, /* 中间 */ 3,],
+  "c": "\"//\"",
+}"#;
+        let stripped = strip_comments(text);
+        assert_eq!(stripped.lines().count(), text.lines().count());
+        let value: Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(
+            value,
+            json!({ "a": "http://x/*y*/", "b": [1, 2, 3], "c": "\"//\"" })
+        );
+        // 普通 JSON 原样保留
+        let plain = r#"{"a": [1, 2], "b": "//"}"#;
+        assert_eq!(strip_comments(plain), plain);
+        // 错误位置与原文一致
+        let err = from_jsonc_str::<Value>("// x\n{\n  \"a\": ?\n}").unwrap_err();
+        assert_eq!(err.line(), 3);
+    }
+
+    #[test]
+    fn test_annotated_round_trip() {
+        let value = json!({
+            "inbounds": [{ "type": "anytls", "tag": "anytls-in", "users": [{ "name": "u", "password": "p" }] }],
+            "log": { "level": "info" }
+        });
+        let notes = Annotations::from([
+            (String::new(), "由 ezsingbox 生成".to_string()),
+            ("/inbounds/0".to_string(), "AnyTLS 入站".to_string()),
+            (
+                "/inbounds/0/users".to_string(),
+                "用户凭证\n第二行".to_string(),
+            ),
+        ]);
+        let text = to_jsonc_string(&value, &notes);
+        assert!(text.starts_with("// 由 ezsingbox 生成\n{\n  \"log\""));
+        assert!(text.contains("  \"inbounds\": [\n    // AnyTLS 入站\n    {"));
+        assert!(text.contains("      // 用户凭证\n      // 第二行\n      \"users\""));
+        assert_eq!(from_jsonc_str::<Value>(&text).unwrap(), value);
+        // 没有注释时与规范化输出相同
+        assert_eq!(
+            to_jsonc_string(&value, &Annotations::new()),
+            to_canonical_string(&value)
+        );
+    }
+}
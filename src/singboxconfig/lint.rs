@@ -0,0 +1,456 @@
+//! 配置检查（lint）
+//!
+//! 与 validate（生成配置的结构校验）不同，lint 面向任意来源的 sing-box 配置，
+//! 按最佳实践报告可以运行但不安全或即将失效的写法：跳过证书校验的出站、低于 TLS 1.2 的版本、
+//! 以 CDN 域名为握手目标的 REALITY、未启用嗅探，以及已弃用的旧字段。
+//! 每条结果带有规则名与 JSON Pointer 位置，便于脚本处理
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::strict::{pointer_push, strict_diagnostics};
+use super::validate::Severity;
+
+/// 出站跳过证书校验（`tls.insecure: true`）
+pub const RULE_INSECURE_TLS: &str = "insecure-tls";
+/// TLS 最低 / 最高版本低于 1.2
+pub const RULE_TLS_VERSION: &str = "tls-version";
+/// REALITY 握手目标为 CDN 域名
+pub const RULE_REALITY_CDN: &str = "reality-cdn-handshake";
+/// 有入站但未启用嗅探
+pub const RULE_MISSING_SNIFF: &str = "missing-sniff";
+/// 已弃用的字段或写法
+pub const RULE_DEPRECATED: &str = "deprecated";
+/// 未知字段或类型错误（严格模式解析）
+pub const RULE_UNKNOWN_FIELD: &str = "unknown-field";
+
+/// 常见 CDN 的域名后缀：REALITY 握手目标位于 CDN 之后时，
+/// 服务器 IP 与目标域名的实际解析结果不一致，容易被识别
+const CDN_SUFFIXES: &[&str] = &[
+    "cloudflare.com",
+    "cloudflare.net",
+    "workers.dev",
+    "pages.dev",
+    "cloudfront.net",
+    "akamai.net",
+    "akamaized.net",
+    "akamaiedge.net",
+    "edgekey.net",
+    "edgesuite.net",
+    "fastly.net",
+    "fastlylb.net",
+    "azureedge.net",
+    "azurefd.net",
+    "b-cdn.net",
+    "cdn77.org",
+    "jsdelivr.net",
+];
+
+/// 入站上自 1.11 起弃用的监听字段（改用路由规则动作）
+pub(crate) const LEGACY_INBOUND_FIELDS: [&str; 5] = [
+    "sniff",
+    "sniff_override_destination",
+    "sniff_timeout",
+    "domain_strategy",
+    "udp_disable_domain_unmapping",
+];
+
+/// 单条检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// 规则名（如 `insecure-tls`）
+    pub rule: &'static str,
+    /// 严重程度
+    pub severity: Severity,
+    /// 位置（JSON Pointer）
+    pub path: String,
+    /// 描述
+    pub message: String,
+}
+
+impl Finding {
+    fn new(
+        rule: &'static str,
+        severity: Severity,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            rule,
+            severity,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} {}: {}",
+            self.severity, self.rule, self.path, self.message
+        )
+    }
+}
+
+/// 检查完整的 sing-box 配置，结果按位置排序
+pub fn lint_config(config: &Value) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = strict_diagnostics(config)
+        .into_iter()
+        .map(|d| Finding::new(RULE_UNKNOWN_FIELD, d.severity, d.path, d.message))
+        .collect();
+    check_tls(config, "", &mut findings);
+    check_reality_targets(config, &mut findings);
+    check_sniff(config, &mut findings);
+    check_deprecated(config, &mut findings);
+    findings.sort_by(|a, b| a.path.cmp(&b.path).then(a.rule.cmp(b.rule)));
+    findings
+}
+
+/// 数组段中的元素及其 Pointer
+fn items<'a>(value: &'a Value, pointer: &str) -> Vec<(String, &'a Value)> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, item)| (format!("{}/{}", pointer, i), item))
+        .collect()
+}
+
+//============================================================================
+// TLS
+//============================================================================
+
+/// 递归检查配置中所有的 `tls` 对象
+fn check_tls(value: &Value, pointer: &str, findings: &mut Vec<Finding>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_pointer = pointer_push(pointer, key);
+                if key == "tls" && child.is_object() {
+                    check_tls_options(child, &child_pointer, findings);
+                }
+                check_tls(child, &child_pointer, findings);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                check_tls(child, &format!("{}/{}", pointer, i), findings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_tls_options(tls: &Value, pointer: &str, findings: &mut Vec<Finding>) {
+    if tls["insecure"] == true {
+        findings.push(Finding::new(
+            RULE_INSECURE_TLS,
+            Severity::Error,
+            format!("{}/insecure", pointer),
+            "跳过了服务器证书校验，连接可被中间人劫持；请使用受信任的证书，或以 certificate / certificate_public_key_sha256 固定自签证书",
+        ));
+    }
+    for key in ["min_version", "max_version"] {
+        let Some(version) = tls[key].as_str() else {
+            continue;
+        };
+        if matches!(version, "1.0" | "1.1") {
+            findings.push(Finding::new(
+                RULE_TLS_VERSION,
+                Severity::Error,
+                format!("{}/{}", pointer, key),
+                format!(
+                    "TLS {} 已不安全且被主流浏览器弃用，{} 应不低于 1.2",
+                    version, key
+                ),
+            ));
+        }
+    }
+}
+
+/// 域名是否属于常见 CDN
+fn is_cdn_domain(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    CDN_SUFFIXES.iter().any(|suffix| {
+        domain == *suffix
+            || domain
+                .strip_suffix(suffix)
+                .is_some_and(|rest| rest.ends_with('.'))
+    })
+}
+
+fn check_reality_targets(config: &Value, findings: &mut Vec<Finding>) {
+    for (pointer, inbound) in items(config, "/inbounds") {
+        let reality = &inbound["tls"]["reality"];
+        if reality["enabled"] != true {
+            continue;
+        }
+        let Some(server) = reality["handshake"]["server"].as_str() else {
+            continue;
+        };
+        if is_cdn_domain(server) {
+            findings.push(Finding::new(
+                RULE_REALITY_CDN,
+                Severity::Warning,
+                format!("{}/tls/reality/handshake/server", pointer),
+                format!(
+                    "握手目标 {} 位于 CDN 之后，解析到的 IP 与本服务器不符，容易被识别；请选择自建站点或不使用 CDN 的大站",
+                    server
+                ),
+            ));
+        }
+    }
+}
+
+//============================================================================
+// 嗅探
+//============================================================================
+
+fn check_sniff(config: &Value, findings: &mut Vec<Finding>) {
+    let inbounds = items(config, "/inbounds");
+    if inbounds.is_empty() {
+        return;
+    }
+    let sniff_action = items(config, "/route/rules")
+        .iter()
+        .any(|(_, rule)| rule["action"] == "sniff");
+    let legacy_sniff = inbounds.iter().any(|(_, inbound)| inbound["sniff"] == true);
+    if !sniff_action && !legacy_sniff {
+        findings.push(Finding::new(
+            RULE_MISSING_SNIFF,
+            Severity::Warning,
+            "/route/rules",
+            "没有 sniff 路由动作：按域名匹配的路由规则只对客户端直接请求域名的连接生效，\
+             建议在规则开头加入 {\"action\": \"sniff\"}",
+        ));
+    }
+}
+
+//============================================================================
+// 弃用字段
+//============================================================================
+
+fn deprecated(findings: &mut Vec<Finding>, pointer: String, message: impl Into<String>) {
+    findings.push(Finding::new(
+        RULE_DEPRECATED,
+        Severity::Warning,
+        pointer,
+        message,
+    ));
+}
+
+fn check_deprecated(config: &Value, findings: &mut Vec<Finding>) {
+    for (pointer, server) in items(config, "/dns/servers") {
+        if server.get("address").is_some() {
+            deprecated(
+                findings,
+                format!("{}/address", pointer),
+                "旧版 DNS 服务器格式（address）自 1.12 起弃用，请改用 type + server 的类型化服务器",
+            );
+        }
+        for key in ["address_resolver", "address_strategy"] {
+            if server.get(key).is_some() {
+                deprecated(
+                    findings,
+                    pointer_push(&pointer, key),
+                    format!("{} 自 1.12 起弃用，请改用 domain_resolver", key),
+                );
+            }
+        }
+    }
+    if config.pointer("/dns/fakeip").is_some() {
+        deprecated(
+            findings,
+            "/dns/fakeip".to_string(),
+            "dns.fakeip 自 1.12 起弃用，请改用 fakeip 类型的 DNS 服务器",
+        );
+    }
+    for (pointer, rule) in items(config, "/dns/rules") {
+        if rule.get("outbound").is_some() {
+            deprecated(
+                findings,
+                format!("{}/outbound", pointer),
+                "DNS 规则的 outbound 条件自 1.12 起弃用，请改用出站的 domain_resolver",
+            );
+        }
+    }
+    for section in ["/route/rules", "/dns/rules"] {
+        for (pointer, rule) in items(config, section) {
+            for key in ["geosite", "geoip", "source_geoip"] {
+                if rule.get(key).is_some() {
+                    deprecated(
+                        findings,
+                        pointer_push(&pointer, key),
+                        format!("{} 自 1.8 起弃用、1.12 起移除，请改用 rule_set", key),
+                    );
+                }
+            }
+        }
+    }
+    for key in ["geoip", "geosite"] {
+        if config["route"].get(key).is_some() {
+            deprecated(
+                findings,
+                format!("/route/{}", key),
+                format!(
+                    "route.{} 数据库自 1.8 起弃用、1.12 起移除，请改用 rule_set",
+                    key
+                ),
+            );
+        }
+    }
+    for (pointer, inbound) in items(config, "/inbounds") {
+        for key in LEGACY_INBOUND_FIELDS {
+            if inbound.get(key).is_some() {
+                deprecated(
+                    findings,
+                    pointer_push(&pointer, key),
+                    format!(
+                        "入站的 {} 自 1.11 起弃用，请改用路由规则动作（sniff / resolve）",
+                        key
+                    ),
+                );
+            }
+        }
+    }
+    for (pointer, outbound) in items(config, "/outbounds") {
+        match outbound["type"].as_str() {
+            Some(kind @ ("block" | "dns")) => deprecated(
+                findings,
+                format!("{}/type", pointer),
+                format!(
+                    "特殊出站 {} 自 1.11 起弃用，请改用路由规则动作（{}）",
+                    kind,
+                    if kind == "block" {
+                        "reject"
+                    } else {
+                        "hijack-dns"
+                    }
+                ),
+            ),
+            Some("wireguard") => deprecated(
+                findings,
+                format!("{}/type", pointer),
+                "WireGuard 出站自 1.11 起弃用，请改用 wireguard 端点（endpoints）",
+            ),
+            _ => {}
+        }
+        if outbound.get("domain_strategy").is_some() {
+            deprecated(
+                findings,
+                format!("{}/domain_strategy", pointer),
+                "出站的 domain_strategy 自 1.12 起弃用，请改用 domain_resolver",
+            );
+        }
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules_at(findings: &[Finding]) -> Vec<(&str, &str)> {
+        findings.iter().map(|f| (f.rule, f.path.as_str())).collect()
+    }
+
+    #[test]
+    fn test_lint_config() {
+        let config = json!({
+            "dns": {
+                "servers": [
+                    { "tag": "remote", "address": "https://1.1.1.1/dns-query", "address_resolver": "local" },
+                    { "tag": "local", "type": "local" }
+                ],
+                "rules": [{ "outbound": "any", "server": "local" }]
+            },
+            "inbounds": [{
+                "type": "vless",
+                "tag": "vless-in",
+                "listen": "::",
+                "listen_port": 443,
+                "users": [{ "name": "u", "uuid": "bf000d23-0752-40b4-affe-68f7707a9661" }],
+                "sniff": true,
+                "tls": {
+                    "enabled": true,
+                    "min_version": "1.1",
+                    "reality": { "enabled": true, "handshake": { "server": "www.cloudflare.com", "server_port": 443 } }
+                }
+            }],
+            "outbounds": [
+                { "type": "anytls", "tag": "proxy", "server": "x", "server_port": 443, "password": "p", "tls": { "enabled": true, "insecure": true } },
+                { "type": "block", "tag": "block" }
+            ],
+            "route": { "rules": [{ "geosite": ["cn"], "outbound": "direct" }] }
+        });
+        assert_eq!(
+            rules_at(&lint_config(&config)),
+            [
+                ("deprecated", "/dns/rules/0/outbound"),
+                ("deprecated", "/dns/servers/0/address"),
+                ("deprecated", "/dns/servers/0/address_resolver"),
+                ("deprecated", "/inbounds/0/sniff"),
+                ("tls-version", "/inbounds/0/tls/min_version"),
+                (
+                    "reality-cdn-handshake",
+                    "/inbounds/0/tls/reality/handshake/server"
+                ),
+                ("insecure-tls", "/outbounds/0/tls/insecure"),
+                ("deprecated", "/outbounds/1/type"),
+                ("deprecated", "/route/rules/0/geosite"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_clean_config() {
+        let config = json!({
+            "dns": {
+                "servers": [{ "type": "local", "tag": "local" }],
+                "rules": [{
+                    "type": "logical",
+                    "mode": "or",
+                    "rules": [{ "domain": ["a.com"] }, { "query_type": "A" }],
+                    "action": "route",
+                    "server": "local"
+                }]
+            },
+            "inbounds": [{
+                "type": "vless",
+                "tag": "vless-in",
+                "listen": "::",
+                "listen_port": 443,
+                "users": [{ "name": "u", "uuid": "bf000d23-0752-40b4-affe-68f7707a9661" }],
+                "tls": {
+                    "enabled": true,
+                    "reality": { "enabled": true, "handshake": { "server": "www.microsoft.com" } }
+                }
+            }],
+            "outbounds": [{ "type": "direct", "tag": "direct" }],
+            "route": { "rules": [{ "action": "sniff" }] },
+            "inbound": []
+        });
+        let findings = lint_config(&config);
+        assert_eq!(rules_at(&findings), [("unknown-field", "/inbound")]);
+        assert_eq!(findings[0].severity, Severity::Error);
+
+        // 没有 sniff 动作
+        let config = json!({ "inbounds": [{ "type": "mixed", "tag": "in" }] });
+        assert_eq!(
+            rules_at(&lint_config(&config)),
+            [("missing-sniff", "/route/rules")]
+        );
+        assert!(is_cdn_domain("d1.cloudfront.net."));
+        assert!(!is_cdn_domain("notcloudflare.com"));
+    }
+}
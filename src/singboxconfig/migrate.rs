@@ -0,0 +1,786 @@
+//! 旧字段迁移
+//!
+//! 把 sing-box 1.8 ~ 1.11 时代的写法改写为 1.12 可识别的等价配置，覆盖 lint 报告的大部分弃用项：
+//! - legacy DNS 服务器（address）改写为类型化服务器，dns.fakeip 并入 fakeip 服务器
+//! - DNS 规则的 server 字段改写为 route 动作，rcode:// 服务器改写为 reject / predefined 动作
+//! - 规则中的 geosite / geoip / source_geoip 改写为官方远程规则集，移除 route.geoip / route.geosite
+//! - 路由规则补全 route 动作，指向 block / dns 出站的规则改写为 reject / hijack-dns 动作，
+//!   不再被引用的特殊出站随之移除
+//! - 入站的 sniff / domain_strategy 等监听字段改写为路由规则开头的 sniff / resolve 动作
+//!
+//! 没有等价写法的字段列入需要手动处理的清单
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use super::lint::LEGACY_INBOUND_FIELDS;
+use super::route::{RouteRule, RuleAction, RuleSet, SING_GEOIP_URL, SING_GEOSITE_URL, SniffAction};
+use super::strict::{pointer_push, unknown_field_diagnostics};
+use crate::dns::Dns;
+
+/// legacy DNS 服务器上没有类型化对应字段的属性
+const DROPPED_SERVER_FIELDS: [&str; 4] = [
+    "address_strategy",
+    "address_fallback_delay",
+    "strategy",
+    "client_subnet",
+];
+
+/// 引用出站标签的字段（路由规则、route.final、选择器、拨号 detour 等）
+const OUTBOUND_REFERENCE_KEYS: [&str; 6] = [
+    "outbound",
+    "outbounds",
+    "final",
+    "default",
+    "detour",
+    "download_detour",
+];
+
+/// 一项改写或需要手动处理的项目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// 位置（原配置中的 JSON Pointer）
+    pub path: String,
+    /// 说明
+    pub message: String,
+}
+
+impl Change {
+    pub(crate) fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// 迁移结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// 改写后的配置
+    pub config: Value,
+    /// 已完成的改写
+    pub changes: Vec<Change>,
+    /// 没有等价写法、需要手动处理的项目
+    pub manual: Vec<Change>,
+}
+
+/// 迁移配置中的旧字段，`rule_set_mirror` 为规则集下载地址前缀（可为空）
+///
+/// DNS 段含有无法识别的字段时返回错误：DNS 经强类型结构改写，未知字段会在往返中丢失
+pub fn migrate_config(config: &Value, rule_set_mirror: &str) -> Result<Migration, String> {
+    if !config.is_object() {
+        return Err("配置必须是 JSON 对象".to_string());
+    }
+    let mut migration = Migration {
+        config: config.clone(),
+        changes: Vec::new(),
+        manual: Vec::new(),
+    };
+    collect_manual(config, &mut migration.manual);
+    migrate_dns(&mut migration)?;
+    migrate_geo(&mut migration, rule_set_mirror);
+    migrate_route_rules(&mut migration);
+    migrate_inbounds(&mut migration);
+    Ok(migration)
+}
+
+/// 数组段中的元素及其 Pointer
+fn items<'a>(value: &'a Value, pointer: &str) -> Vec<(String, &'a Value)> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, item)| (format!("{}/{}", pointer, i), item))
+        .collect()
+}
+
+/// 字符串或字符串数组
+fn string_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 没有等价写法的旧字段
+fn collect_manual(config: &Value, manual: &mut Vec<Change>) {
+    for (pointer, outbound) in items(config, "/outbounds") {
+        if outbound["type"] == "wireguard" {
+            manual.push(Change::new(
+                format!("{}/type", pointer),
+                "WireGuard 出站请手动改写为 endpoints 中的 wireguard 端点",
+            ));
+        }
+        if outbound.get("domain_strategy").is_some() {
+            manual.push(Change::new(
+                format!("{}/domain_strategy", pointer),
+                "出站的 domain_strategy 请手动改写为 domain_resolver",
+            ));
+        }
+    }
+    for (pointer, rule) in items(config, "/dns/rules") {
+        if rule.get("outbound").is_some() {
+            manual.push(Change::new(
+                format!("{}/outbound", pointer),
+                "DNS 规则的 outbound 条件没有等价写法，请改用出站的 domain_resolver",
+            ));
+        }
+    }
+}
+
+//============================================================================
+// DNS
+//============================================================================
+
+/// legacy 服务器、dns.fakeip 与规则上的 server 字段
+fn migrate_dns(migration: &mut Migration) -> Result<(), String> {
+    let config = &migration.config;
+    let mut changes = Vec::new();
+    let mut manual = Vec::new();
+    for (pointer, server) in items(config, "/dns/servers") {
+        let Some(address) = server.get("address").and_then(Value::as_str) else {
+            continue;
+        };
+        if address.starts_with("rcode://") {
+            changes.push(Change::new(
+                &pointer,
+                format!("{} 服务器改写为规则动作后移除", address),
+            ));
+            continue;
+        }
+        changes.push(Change::new(
+            &pointer,
+            format!("旧版服务器 {} 改写为类型化服务器", address),
+        ));
+        for key in DROPPED_SERVER_FIELDS {
+            if server.get(key).is_some() {
+                manual.push(Change::new(
+                    pointer_push(&pointer, key),
+                    format!(
+                        "类型化服务器没有 {} 字段，已移除；需要时改用 dns.strategy 或 DNS 规则动作",
+                        key
+                    ),
+                ));
+            }
+        }
+    }
+    if config.pointer("/dns/fakeip").is_some() {
+        changes.push(Change::new(
+            "/dns/fakeip",
+            "地址范围并入 fakeip 类型的服务器",
+        ));
+    }
+    for (pointer, rule) in items(config, "/dns/rules") {
+        if rule.get("server").is_some() && rule.get("action").is_none() {
+            changes.push(Change::new(
+                format!("{}/server", pointer),
+                "改写为规则动作（route / reject / predefined）",
+            ));
+        }
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let dns = &config["dns"];
+    if let Some(d) = unknown_field_diagnostics::<Dns>(dns, "/dns").first() {
+        return Err(format!(
+            "DNS 配置中有无法识别的字段，无法安全改写（{}: {}）",
+            d.path, d.message
+        ));
+    }
+    let typed: Dns = serde_json::from_value(dns.clone()).map_err(|e| e.to_string())?;
+    migration.config["dns"] = serde_json::to_value(typed.to_typed()?).map_err(|e| e.to_string())?;
+    migration.changes.extend(changes);
+    migration.manual.extend(manual);
+    Ok(())
+}
+
+//============================================================================
+// GeoIP / Geosite
+//============================================================================
+
+/// 路由与 DNS 规则中的 geosite / geoip / source_geoip 改写为规则集
+fn migrate_geo(migration: &mut Migration, mirror: &str) {
+    let mut rule_sets = Vec::new();
+    for section in ["/route/rules", "/dns/rules"] {
+        let Some(rules) = migration
+            .config
+            .pointer_mut(section)
+            .and_then(Value::as_array_mut)
+        else {
+            continue;
+        };
+        for (i, rule) in rules.iter_mut().enumerate() {
+            let mut notes = GeoNotes {
+                rule_sets: &mut rule_sets,
+                changes: &mut migration.changes,
+                manual: &mut migration.manual,
+            };
+            geo_rule(rule, &format!("{}/{}", section, i), mirror, &mut notes);
+        }
+    }
+
+    if let Some(route) = migration
+        .config
+        .get_mut("route")
+        .and_then(Value::as_object_mut)
+    {
+        for key in ["geoip", "geosite"] {
+            if route.remove(key).is_some() {
+                migration.changes.push(Change::new(
+                    format!("/route/{}", key),
+                    "数据库配置已由规则集取代，已移除",
+                ));
+            }
+        }
+    }
+    if rule_sets.is_empty() {
+        return;
+    }
+    let route = &mut migration.config["route"];
+    if route.is_null() {
+        *route = json!({});
+    }
+    let Some(route) = route.as_object_mut() else {
+        return;
+    };
+    let Some(list) = route
+        .entry("rule_set")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+    else {
+        return;
+    };
+    for rule_set in rule_sets {
+        if list.iter().any(|s| s["tag"] == rule_set.tag.as_str()) {
+            continue;
+        }
+        migration.changes.push(Change::new(
+            "/route/rule_set",
+            format!("添加远程规则集 {}", rule_set.tag),
+        ));
+        list.push(serde_json::to_value(&rule_set).unwrap_or_default());
+    }
+}
+
+/// 改写规则时收集的规则集与说明
+struct GeoNotes<'a> {
+    rule_sets: &'a mut Vec<RuleSet>,
+    changes: &'a mut Vec<Change>,
+    manual: &'a mut Vec<Change>,
+}
+
+/// 改写单条规则（逻辑规则递归处理子规则）
+fn geo_rule(rule: &mut Value, pointer: &str, mirror: &str, notes: &mut GeoNotes<'_>) {
+    if let Some(children) = rule.get_mut("rules").and_then(Value::as_array_mut) {
+        for (i, child) in children.iter_mut().enumerate() {
+            geo_rule(child, &format!("{}/rules/{}", pointer, i), mirror, notes);
+        }
+    }
+    let Some(rule) = rule.as_object_mut() else {
+        return;
+    };
+    let mut tags = Vec::new();
+    let mut targets = Vec::new();
+    let mut add = |rule_set: RuleSet, tags: &mut Vec<String>| {
+        tags.push(rule_set.tag.clone());
+        notes.rule_sets.push(rule_set);
+    };
+
+    let mut migrated = Vec::new();
+    if let Some(names) = rule.remove("geosite") {
+        migrated.push("geosite");
+        for name in string_list(&names) {
+            add(
+                RuleSet::geosite(&name, &format!("{}{}", mirror, SING_GEOSITE_URL)),
+                &mut tags,
+            );
+        }
+    }
+    if let Some(codes) = rule.remove("geoip") {
+        migrated.push("geoip");
+        for code in string_list(&codes) {
+            if code.eq_ignore_ascii_case("private") {
+                rule.insert("ip_is_private".to_string(), Value::Bool(true));
+                targets.push("ip_is_private".to_string());
+            } else {
+                add(
+                    RuleSet::geoip(&code, &format!("{}{}", mirror, SING_GEOIP_URL)),
+                    &mut tags,
+                );
+            }
+        }
+    }
+    if let Some(codes) = rule.get("source_geoip").map(string_list) {
+        let (private, countries): (Vec<String>, Vec<String>) = codes
+            .into_iter()
+            .partition(|c| c.eq_ignore_ascii_case("private"));
+        if !countries.is_empty() && (!tags.is_empty() || rule.contains_key("rule_set")) {
+            // rule_set_ip_cidr_match_source 作用于规则中的全部规则集
+            notes.manual.push(Change::new(
+                pointer_push(pointer, "source_geoip"),
+                "与其他规则集在同一条规则中，无法单独匹配来源地址，请拆分规则后再迁移",
+            ));
+        } else {
+            rule.remove("source_geoip");
+            migrated.push("source_geoip");
+            if !private.is_empty() {
+                rule.insert("source_ip_is_private".to_string(), Value::Bool(true));
+                targets.push("source_ip_is_private".to_string());
+            }
+            if !countries.is_empty() {
+                rule.insert(
+                    "rule_set_ip_cidr_match_source".to_string(),
+                    Value::Bool(true),
+                );
+                for code in countries {
+                    add(
+                        RuleSet::geoip(&code, &format!("{}{}", mirror, SING_GEOIP_URL)),
+                        &mut tags,
+                    );
+                }
+            }
+        }
+    }
+    if migrated.is_empty() {
+        return;
+    }
+    if !tags.is_empty() {
+        append_rule_sets(rule, &tags);
+        targets.push(format!("规则集 {}", tags.join(", ")));
+    }
+    notes.changes.push(Change::new(
+        pointer,
+        format!("{} 改写为 {}", migrated.join(" / "), targets.join("、")),
+    ));
+}
+
+/// 把规则集标签追加到规则的 rule_set 中（去重）
+fn append_rule_sets(rule: &mut Map<String, Value>, tags: &[String]) {
+    let mut list = match rule.remove("rule_set") {
+        Some(Value::Array(items)) => items,
+        Some(Value::String(tag)) => vec![Value::String(tag)],
+        _ => Vec::new(),
+    };
+    for tag in tags {
+        let tag = Value::String(tag.clone());
+        if !list.contains(&tag) {
+            list.push(tag);
+        }
+    }
+    rule.insert("rule_set".to_string(), Value::Array(list));
+}
+
+//============================================================================
+// 路由规则与特殊出站
+//============================================================================
+
+/// 路由规则补全 route 动作，指向 block / dns 出站的规则改写为 reject / hijack-dns 动作
+fn migrate_route_rules(migration: &mut Migration) {
+    let special: BTreeMap<String, (usize, String)> = items(&migration.config, "/outbounds")
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, (_, outbound))| {
+            let kind = outbound["type"]
+                .as_str()
+                .filter(|t| matches!(*t, "block" | "dns"))?;
+            Some((outbound["tag"].as_str()?.to_string(), (i, kind.to_string())))
+        })
+        .collect();
+
+    if let Some(rules) = migration
+        .config
+        .pointer_mut("/route/rules")
+        .and_then(Value::as_array_mut)
+    {
+        for (i, rule) in rules.iter_mut().enumerate() {
+            let pointer = format!("/route/rules/{}", i);
+            let Some(rule) = rule.as_object_mut() else {
+                continue;
+            };
+            let Some(outbound) = rule
+                .get("outbound")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            let has_action = match rule.get("action").map(|a| a.as_str()) {
+                None => false,
+                Some(Some("route")) => true,
+                Some(_) => continue,
+            };
+            let action = match special.get(&outbound).map(|(_, kind)| kind.as_str()) {
+                Some("block") => "reject",
+                Some(_) => "hijack-dns",
+                None if has_action => continue,
+                None => {
+                    rule.insert("action".to_string(), json!("route"));
+                    migration
+                        .changes
+                        .push(Change::new(&pointer, "补全 route 动作"));
+                    continue;
+                }
+            };
+            rule.remove("outbound");
+            rule.insert("action".to_string(), json!(action));
+            migration.changes.push(Change::new(
+                &pointer,
+                format!("指向出站 {} 的规则改写为 {} 动作", outbound, action),
+            ));
+        }
+    }
+
+    let mut removed = Vec::new();
+    for (tag, (index, kind)) in &special {
+        let pointer = format!("/outbounds/{}", index);
+        if references_outbound(&migration.config, tag) {
+            migration.manual.push(Change::new(
+                pointer,
+                format!(
+                    "{} 出站 {} 仍被引用（如 route.final 或选择器），请手动改写后删除",
+                    kind, tag
+                ),
+            ));
+        } else {
+            migration.changes.push(Change::new(
+                pointer,
+                format!("{} 出站 {} 不再被引用，已移除", kind, tag),
+            ));
+            removed.push(*index);
+        }
+    }
+    if let Some(outbounds) = migration
+        .config
+        .get_mut("outbounds")
+        .and_then(Value::as_array_mut)
+    {
+        removed.sort_unstable();
+        for index in removed.into_iter().rev() {
+            outbounds.remove(index);
+        }
+    }
+}
+
+/// 配置中是否有字段引用该出站标签
+fn references_outbound(value: &Value, tag: &str) -> bool {
+    match value {
+        Value::Object(map) => map.iter().any(|(key, child)| {
+            (OUTBOUND_REFERENCE_KEYS.contains(&key.as_str())
+                && string_list(child).iter().any(|t| t == tag))
+                || references_outbound(child, tag)
+        }),
+        Value::Array(items) => items.iter().any(|item| references_outbound(item, tag)),
+        _ => false,
+    }
+}
+
+//============================================================================
+// 入站监听字段
+//============================================================================
+
+/// 入站的 sniff / domain_strategy 等字段改写为路由规则开头的动作
+fn migrate_inbounds(migration: &mut Migration) {
+    let mut sniff_rules = Vec::new();
+    let mut other_rules = Vec::new();
+    let Some(inbounds) = migration
+        .config
+        .get_mut("inbounds")
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+    for (i, inbound) in inbounds.iter_mut().enumerate() {
+        let pointer = format!("/inbounds/{}", i);
+        let Some(inbound) = inbound.as_object_mut() else {
+            continue;
+        };
+        if !LEGACY_INBOUND_FIELDS
+            .iter()
+            .any(|key| inbound.contains_key(*key))
+        {
+            continue;
+        }
+        let Some(tag) = inbound
+            .get("tag")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        else {
+            migration.manual.push(Change::new(
+                pointer,
+                "入站没有 tag，监听字段无法改写为路由规则",
+            ));
+            continue;
+        };
+        let changes = &mut migration.changes;
+
+        let sniff = inbound.remove("sniff");
+        let timeout = inbound.remove("sniff_timeout");
+        if sniff == Some(Value::Bool(true)) {
+            let action = SniffAction {
+                sniffer: None,
+                timeout: timeout.as_ref().and_then(Value::as_str).map(str::to_string),
+            };
+            sniff_rules.push(
+                RouteRule::new()
+                    .with_inbounds([tag.clone()])
+                    .with_action(RuleAction::Sniff(action)),
+            );
+            changes.push(Change::new(
+                pointer_push(&pointer, "sniff"),
+                "改写为路由规则的 sniff 动作",
+            ));
+        } else if sniff.is_some() || timeout.is_some() {
+            changes.push(Change::new(
+                pointer_push(&pointer, "sniff"),
+                "未启用嗅探，已移除",
+            ));
+        }
+        if let Some(strategy) = inbound.remove("domain_strategy") {
+            other_rules
+                .push(json!({ "inbound": [tag], "action": "resolve", "strategy": strategy }));
+            changes.push(Change::new(
+                pointer_push(&pointer, "domain_strategy"),
+                "改写为路由规则的 resolve 动作",
+            ));
+        }
+        if let Some(unmapping) = inbound.remove("udp_disable_domain_unmapping") {
+            if unmapping == true {
+                other_rules.push(json!({
+                    "inbound": [tag],
+                    "action": "route-options",
+                    "udp_disable_domain_unmapping": true
+                }));
+            }
+            changes.push(Change::new(
+                pointer_push(&pointer, "udp_disable_domain_unmapping"),
+                "改写为路由规则的 route-options 动作",
+            ));
+        }
+        if inbound.contains_key("sniff_override_destination") {
+            migration.manual.push(Change::new(
+                pointer_push(&pointer, "sniff_override_destination"),
+                "路由规则动作中没有等价写法（1.13 起移除），已保留原样",
+            ));
+        }
+    }
+
+    // 解析动作依赖嗅探得到的域名，sniff 规则在前
+    let mut front: Vec<Value> = sniff_rules
+        .iter()
+        .map(|rule| serde_json::to_value(rule).unwrap_or_default())
+        .chain(other_rules)
+        .collect();
+    if front.is_empty() {
+        return;
+    }
+    let route = &mut migration.config["route"];
+    if route.is_null() {
+        *route = json!({});
+    }
+    let Some(route) = route.as_object_mut() else {
+        return;
+    };
+    if let Some(rules) = route
+        .entry("rules")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+    {
+        front.append(rules);
+        *rules = front;
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singboxconfig::lint::{RULE_DEPRECATED, lint_config};
+
+    #[test]
+    fn test_migrate_config() {
+        let config = json!({
+            "dns": {
+                "servers": [
+                    { "tag": "remote", "address": "tls://8.8.8.8", "detour": "proxy", "strategy": "ipv4_only" },
+                    { "tag": "local", "address": "223.5.5.5" },
+                    { "tag": "block", "address": "rcode://refused" }
+                ],
+                "rules": [
+                    { "geosite": ["category-ads-all"], "server": "block" },
+                    { "geosite": "cn", "server": "local" }
+                ],
+                "final": "remote"
+            },
+            "inbounds": [{
+                "type": "mixed",
+                "tag": "mixed-in",
+                "listen": "127.0.0.1",
+                "listen_port": 2080,
+                "sniff": true,
+                "sniff_timeout": "300ms",
+                "domain_strategy": "prefer_ipv4"
+            }],
+            "outbounds": [
+                { "type": "direct", "tag": "proxy" },
+                { "type": "block", "tag": "block" },
+                { "type": "dns", "tag": "dns-out" }
+            ],
+            "route": {
+                "rules": [
+                    { "protocol": "dns", "outbound": "dns-out" },
+                    { "geoip": ["private", "cn"], "outbound": "proxy" },
+                    { "source_geoip": ["ir"], "outbound": "block" }
+                ],
+                "geoip": { "path": "geoip.db" }
+            }
+        });
+        let migration = migrate_config(&config, "").unwrap();
+        let out = &migration.config;
+
+        assert_eq!(
+            out["dns"]["servers"],
+            json!([
+                { "type": "tls", "tag": "remote", "server": "8.8.8.8", "detour": "proxy" },
+                { "type": "udp", "tag": "local", "server": "223.5.5.5" }
+            ])
+        );
+        assert_eq!(
+            out["dns"]["rules"],
+            json!([
+                { "rule_set": ["geosite-category-ads-all"], "action": "reject" },
+                { "rule_set": ["geosite-cn"], "action": "route", "server": "local" }
+            ])
+        );
+        assert_eq!(
+            out["route"]["rules"],
+            json!([
+                { "inbound": ["mixed-in"], "action": "sniff", "timeout": "300ms" },
+                { "inbound": ["mixed-in"], "action": "resolve", "strategy": "prefer_ipv4" },
+                { "protocol": "dns", "action": "hijack-dns" },
+                { "ip_is_private": true, "rule_set": ["geoip-cn"], "action": "route", "outbound": "proxy" },
+                { "rule_set": ["geoip-ir"], "rule_set_ip_cidr_match_source": true, "action": "reject" }
+            ])
+        );
+        let tags: Vec<&str> = out["route"]["rule_set"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["tag"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                "geoip-cn",
+                "geoip-ir",
+                "geosite-category-ads-all",
+                "geosite-cn"
+            ]
+        );
+        assert!(out["route"].get("geoip").is_none());
+        assert_eq!(
+            out["outbounds"],
+            json!([{ "type": "direct", "tag": "proxy" }])
+        );
+        assert!(out["inbounds"][0].get("sniff").is_none());
+
+        assert_eq!(
+            migration.manual,
+            [Change::new(
+                "/dns/servers/0/strategy",
+                "类型化服务器没有 strategy 字段，已移除；需要时改用 dns.strategy 或 DNS 规则动作"
+            )]
+        );
+        // 迁移后不再有弃用警告，且再次迁移没有变化
+        assert!(lint_config(out).iter().all(|f| f.rule != RULE_DEPRECATED));
+        let again = migrate_config(out, "").unwrap();
+        assert_eq!(&again.config, out);
+        assert!(again.changes.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_manual() {
+        let config = json!({
+            "outbounds": [
+                { "type": "direct", "tag": "direct", "domain_strategy": "ipv4_only" },
+                { "type": "block", "tag": "block" }
+            ],
+            "route": {
+                "rules": [{ "geosite": ["cn"], "source_geoip": ["cn"], "outbound": "direct" }],
+                "final": "block"
+            }
+        });
+        let migration = migrate_config(&config, "https://mirror.example.com/").unwrap();
+        let paths: Vec<&str> = migration.manual.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/outbounds/0/domain_strategy",
+                "/route/rules/0/source_geoip",
+                "/outbounds/1"
+            ]
+        );
+        // 仍被 route.final 引用的 block 出站保留
+        assert_eq!(migration.config["outbounds"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            migration.config["route"]["rule_set"][0]["url"],
+            "https://mirror.example.com/https://raw.githubusercontent.com/SagerNet/sing-geosite/rule-set/geosite-cn.srs"
+        );
+
+        // DNS 中有未知字段时拒绝改写
+        let config = json!({ "dns": { "servers": [{ "tag": "x", "address": "local", "adress_resolver": "y" }] } });
+        assert!(migrate_config(&config, "").is_err());
+        assert!(migrate_config(&json!([]), "").is_err());
+    }
+
+    #[test]
+    fn test_migrate_logical_dns_rule() {
+        let config = json!({
+            "dns": {
+                "servers": [{ "tag": "block", "address": "rcode://refused" }],
+                "rules": [
+                    { "type": "logical", "mode": "or", "rules": [{ "domain": "a.com" }], "server": "l" },
+                    { "type": "logical", "mode": "and", "rules": [{ "domain": "b.com" }, { "query_type": "A" }], "server": "block" }
+                ]
+            }
+        });
+        let migration = migrate_config(&config, "").unwrap();
+        assert_eq!(
+            migration.config["dns"]["rules"],
+            json!([
+                { "type": "logical", "mode": "or", "rules": [{ "domain": "a.com" }], "action": "route", "server": "l" },
+                { "type": "logical", "mode": "and", "rules": [{ "domain": "b.com" }, { "query_type": "A" }], "action": "reject" }
+            ])
+        );
+        let paths: Vec<&str> = migration.changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/dns/servers/0",
+                "/dns/rules/0/server",
+                "/dns/rules/1/server"
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! 最小化配置
+//!
+//! sing-box 把 Go 零值（`false`、空字符串、空数组、空对象）视为未设置，`enabled: false` 的块等同于不写；
+//! 另有一批文档列出的非零默认值（如 TUIC 的 `congestion_control: cubic`）。
+//! 去掉这些字段后得到与原配置等价的最小配置。
+//! 数组元素不会被删除（规则、用户等的位置有意义），只处理其中的字段
+
+use serde_json::{Map, Value};
+
+use super::types::Duration;
+
+/// sing-box 文档中的非零默认值：（所在字段, 对象类型, 字段, 默认值 JSON）
+/// 所在字段为对象所在的字段名（数组元素取数组的字段名），对象类型为空表示不限
+const DEFAULTS: &[(&str, &str, &str, &str)] = &[
+    ("log", "", "level", r#""info""#),
+    ("inbounds", "", "udp_timeout", r#""5m""#),
+    ("inbounds", "tuic", "congestion_control", r#""cubic""#),
+    ("inbounds", "tuic", "auth_timeout", r#""3s""#),
+    ("inbounds", "tuic", "heartbeat", r#""10s""#),
+    ("inbounds", "tun", "mtu", "9000"),
+    ("inbounds", "tun", "stack", r#""mixed""#),
+    ("outbounds", "tuic", "congestion_control", r#""cubic""#),
+    ("outbounds", "tuic", "heartbeat", r#""10s""#),
+    (
+        "outbounds",
+        "anytls",
+        "idle_session_check_interval",
+        r#""30s""#,
+    ),
+    ("outbounds", "anytls", "idle_session_timeout", r#""30s""#),
+    ("handshake", "", "server_port", "443"),
+    ("utls", "", "fingerprint", r#""chrome""#),
+    ("multiplex", "", "protocol", r#""h2mux""#),
+    ("servers", "udp", "server_port", "53"),
+    ("servers", "tcp", "server_port", "53"),
+    ("servers", "tls", "server_port", "853"),
+    ("servers", "quic", "server_port", "853"),
+    ("servers", "https", "server_port", "443"),
+    ("servers", "https", "path", r#""/dns-query""#),
+    ("servers", "h3", "server_port", "443"),
+    ("servers", "h3", "path", r#""/dns-query""#),
+    ("rule_set", "remote", "update_interval", r#""1d""#),
+];
+
+/// 去掉与 sing-box 默认值相同的字段
+pub fn minimize_config(config: &mut Value) {
+    if let Some(root) = config.as_object_mut() {
+        drop_default_final(root, "route", "outbounds");
+        drop_default_final(root, "dns", "servers");
+    }
+    strip(config, "");
+}
+
+/// `final` 与第一个出站 / DNS 服务器相同时可省略（sing-box 默认使用第一个）
+fn drop_default_final(root: &mut Map<String, Value>, section: &str, items: &str) {
+    let first = match section {
+        "route" => root.get(items),
+        _ => root.get(section).and_then(|s| s.get(items)),
+    }
+    .and_then(|items| items.get(0))
+    .and_then(|item| item.get("tag"))
+    .cloned();
+    let Some(section) = root.get_mut(section).and_then(Value::as_object_mut) else {
+        return;
+    };
+    if first.is_some() && section.get("final") == first.as_ref() {
+        section.remove("final");
+    }
+}
+
+/// 递归去掉零值与默认值字段
+fn strip(value: &mut Value, parent: &str) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, child| !is_disabled(child));
+            let kind = map
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            for (key, child) in map.iter_mut() {
+                strip(child, key);
+            }
+            map.retain(|key, child| !is_zero(child) && !is_default(parent, &kind, key, child));
+        }
+        Value::Array(items) => {
+            for item in items {
+                strip(item, parent);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `enabled: false` 的块
+fn is_disabled(value: &Value) -> bool {
+    value.get("enabled") == Some(&Value::Bool(false))
+}
+
+/// Go 零值
+fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// 是否等于文档中的默认值（时间间隔按时长比较，"1m" 与 "60s" 相同）
+fn is_default(parent: &str, kind: &str, key: &str, value: &Value) -> bool {
+    DEFAULTS
+        .iter()
+        .filter(|(p, k, f, _)| *p == parent && (k.is_empty() || *k == kind) && *f == key)
+        .any(|(.., default)| {
+            let default: Value = serde_json::from_str(default).unwrap_or_default();
+            if value == &default {
+                return true;
+            }
+            let duration = |v: &Value| v.as_str()?.parse::<Duration>().ok();
+            matches!((duration(value), duration(&default)), (Some(a), Some(b)) if a == b)
+        })
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_minimize_config() {
+        let mut config = json!({
+            "log": { "level": "info", "timestamp": true },
+            "dns": {
+                "servers": [
+                    { "type": "https", "tag": "remote", "server": "1.1.1.1", "server_port": 443, "path": "/dns-query" },
+                    { "type": "udp", "tag": "local", "server": "223.5.5.5", "server_port": 5353 }
+                ],
+                "final": "remote",
+                "rules": []
+            },
+            "inbounds": [{
+                "type": "tuic",
+                "tag": "tuic-in",
+                "listen": "::",
+                "congestion_control": "cubic",
+                "heartbeat": "10000ms",
+                "zero_rtt_handshake": false,
+                "tls": { "enabled": true, "server_name": "", "ech": { "enabled": false, "key": ["k"] } }
+            }],
+            "outbounds": [
+                { "type": "direct", "tag": "direct" },
+                { "type": "anytls", "tag": "proxy", "tls": { "enabled": true, "utls": { "enabled": true, "fingerprint": "chrome" } } }
+            ],
+            "route": { "final": "direct", "rules": [{ "invert": false, "action": "sniff" }] }
+        });
+        minimize_config(&mut config);
+        assert_eq!(
+            config,
+            json!({
+                "log": { "timestamp": true },
+                "dns": {
+                    "servers": [
+                        { "type": "https", "tag": "remote", "server": "1.1.1.1" },
+                        { "type": "udp", "tag": "local", "server": "223.5.5.5", "server_port": 5353 }
+                    ]
+                },
+                "inbounds": [{
+                    "type": "tuic",
+                    "tag": "tuic-in",
+                    "listen": "::",
+                    "tls": { "enabled": true }
+                }],
+                "outbounds": [
+                    { "type": "direct", "tag": "direct" },
+                    { "type": "anytls", "tag": "proxy", "tls": { "enabled": true, "utls": { "enabled": true } } }
+                ],
+                "route": { "rules": [{ "action": "sniff" }] }
+            })
+        );
+
+        // final 不是第一个时保留
+        let mut config = json!({
+            "outbounds": [{ "type": "direct", "tag": "direct" }, { "type": "block", "tag": "block" }],
+            "route": { "final": "block" }
+        });
+        minimize_config(&mut config);
+        assert_eq!(config["route"]["final"], "block");
+    }
+}
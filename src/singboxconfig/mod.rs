@@ -2,9 +2,19 @@
 //!
 //! 此模块提供 sing-box 配置文件的 Rust 数据结构定义
 
+pub mod endpoint;
 pub mod inbound;
 pub mod outbound;
+pub mod route;
 pub mod shared;
 pub mod types;
 
+pub mod canonical;
+pub mod compare;
 pub mod full;
+pub mod jsonc;
+pub mod lint;
+pub mod migrate;
+pub mod minimal;
+pub mod strict;
+pub mod validate;
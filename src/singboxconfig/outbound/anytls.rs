@@ -10,7 +10,7 @@ use crate::singboxconfig::types::Duration;
 /// AnyTLS 出站配置
 /// 自sing-box 1.12.0 起可用
 /// 文档: https://sing-box.sagernet.org/configuration/outbound/anytls/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct AnyTlsOutbound {
     /// 出站类型，固定为 "anytls"
     #[serde(rename = "type")]
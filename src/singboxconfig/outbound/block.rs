@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+//============================================================================
+// Block 出站配置
+// ============================================================================
+
+/// Block 出站配置
+/// 关闭所有发往此出站的连接；sing-box 1.11.0 起推荐改用路由规则的 reject 动作，
+/// 但仍作为滥用防护规则之外的兜底出站保留
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/block/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct BlockOutbound {
+    /// 出站类型，固定为 "block"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+}
+
+impl BlockOutbound {
+    /// 默认标签（路由规则引用）
+    pub const DEFAULT_TAG: &'static str = "block";
+
+    /// 创建新的 Block 出站配置
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            outbound_type: "block".to_string(),
+            tag: tag.into(),
+        }
+    }
+}
+
+impl Default for BlockOutbound {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TAG)
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_value(BlockOutbound::default()).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "block", "tag": "block" }));
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::DialFields;
+
+//============================================================================
+// Direct 出站配置
+// ============================================================================
+
+/// Direct 出站配置
+/// 直接连接目标地址，拨号字段用于绑定网卡、路由标记、网络命名空间等
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/direct/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct DirectOutbound {
+    /// 出站类型，固定为 "direct"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl DirectOutbound {
+    /// 默认标签（路由规则与 `route.final` 引用）
+    pub const DEFAULT_TAG: &'static str = "direct";
+
+    /// 创建新的 Direct 出站配置
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            outbound_type: "direct".to_string(),
+            tag: tag.into(),
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+impl Default for DirectOutbound {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TAG)
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_value(DirectOutbound::default()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "direct", "tag": "direct" })
+        );
+
+        let dial = DialFields {
+            bind_interface: Some("eth1".to_string()),
+            netns: Some("vpn".to_string()),
+            ..Default::default()
+        };
+        let json =
+            serde_json::to_value(DirectOutbound::new("direct-eth1").with_dial(dial)).unwrap();
+        assert_eq!(json["tag"], "direct-eth1");
+        assert_eq!(json["bind_interface"], "eth1");
+        assert_eq!(json["netns"], "vpn");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json =
+            r#"{ "type": "direct", "tag": "out", "routing_mark": 1234, "tcp_fast_open": true }"#;
+        let outbound: DirectOutbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.tag, "out");
+        assert_eq!(outbound.dial.tcp_fast_open, Some(true));
+        assert!(outbound.dial.routing_mark.is_some());
+    }
+}
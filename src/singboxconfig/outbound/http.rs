@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::singboxconfig::shared::{DialFields, OutboundTlsConfig};
+
+//============================================================================
+// HTTP 出站配置
+// ============================================================================
+
+/// HTTP 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/http/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct HttpOutbound {
+    /// 出站类型，固定为 "http"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// Basic 认证用户名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Basic 认证密码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// HTTP 请求路径
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// 额外的 HTTP 请求头
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+
+    /// TLS 配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<OutboundTlsConfig>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl HttpOutbound {
+    /// 创建新的 HTTP 出站配置
+    pub fn new(tag: impl Into<String>, server: impl Into<String>, server_port: u16) -> Self {
+        Self {
+            outbound_type: "http".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            username: None,
+            password: None,
+            path: None,
+            headers: None,
+            tls: None,
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置 Basic 认证信息
+    pub fn with_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    /// 设置额外的请求头
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: OutboundTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = HttpOutbound::new("http-out", "127.0.0.1", 8080).with_auth("u", "p");
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "http");
+        assert_eq!(json["password"], "p");
+        assert!(json.get("tls").is_none());
+    }
+}
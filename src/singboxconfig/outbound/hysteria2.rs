@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::inbound::Hysteria2Obfs;
+use crate::singboxconfig::shared::{DialFields, OutboundTlsConfig};
+use crate::singboxconfig::types::{Duration, PortRangeList};
+
+//============================================================================
+// Hysteria2 出站配置
+// ============================================================================
+
+/// Hysteria2 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/hysteria2/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Hysteria2Outbound {
+    /// 出站类型，固定为 "hysteria2"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_port: Option<u16>,
+
+    /// 端口跳跃的端口范围列表（如 "20000:30000"）
+    /// 自 sing-box 1.11.0 起可用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_ports: Option<PortRangeList>,
+
+    /// 端口跳跃间隔，默认 30s
+    /// 自 sing-box 1.11.0 起可用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hop_interval: Option<Duration>,
+
+    /// 上行带宽（Mbps），为空时使用 BBR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_mbps: Option<u32>,
+
+    /// 下行带宽（Mbps），为空时使用 BBR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_mbps: Option<u32>,
+
+    /// QUIC 流量混淆
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obfs: Option<Hysteria2Obfs>,
+
+    /// 认证密码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// TLS 配置（必填）
+    pub tls: OutboundTlsConfig,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl Hysteria2Outbound {
+    /// 创建新的 Hysteria2 出站配置（启用 TLS）
+    pub fn new(
+        tag: impl Into<String>,
+        server: impl Into<String>,
+        server_port: u16,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            outbound_type: "hysteria2".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port: Some(server_port),
+            server_ports: None,
+            hop_interval: None,
+            up_mbps: None,
+            down_mbps: None,
+            obfs: None,
+            password: Some(password.into()),
+            network: None,
+            tls: OutboundTlsConfig {
+                enabled: Some(true),
+                ..Default::default()
+            },
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置端口跳跃范围
+    pub fn with_server_ports(mut self, ports: impl Into<PortRangeList>) -> Self {
+        self.server_ports = Some(ports.into());
+        self
+    }
+
+    /// 设置带宽
+    pub fn with_bandwidth(mut self, up_mbps: u32, down_mbps: u32) -> Self {
+        self.up_mbps = Some(up_mbps);
+        self.down_mbps = Some(down_mbps);
+        self
+    }
+
+    /// 启用 Salamander 混淆
+    pub fn with_obfs(mut self, password: impl Into<String>) -> Self {
+        self.obfs = Some(Hysteria2Obfs {
+            obfs_type: "salamander".to_string(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: OutboundTlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::singboxconfig::types::PortRange;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = Hysteria2Outbound::new("hy2-out", "example.com", 443, "password")
+            .with_bandwidth(50, 200)
+            .with_obfs("obfs");
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "hysteria2");
+        assert_eq!(json["up_mbps"], 50);
+        assert_eq!(json["obfs"]["type"], "salamander");
+        assert_eq!(json["tls"]["enabled"], true);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "hysteria2",
+            "tag": "hy2-out",
+            "server": "127.0.0.1",
+            "server_ports": ["20000:30000"],
+            "hop_interval": "10s",
+            "password": "password",
+            "tls": { "enabled": true }
+        }"#;
+        let outbound: Hysteria2Outbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.server_port, None);
+        assert_eq!(
+            outbound.server_ports.unwrap().as_slice(),
+            [PortRange::new(20000, 30000)]
+        );
+    }
+
+    #[test]
+    fn test_server_ports() {
+        let outbound = Hysteria2Outbound::new("hy2-out", "example.com", 443, "password")
+            .with_server_ports(vec![PortRange::new(20000, 30000), PortRange::single(443)]);
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(
+            json["server_ports"],
+            serde_json::json!(["20000:30000", "443:443"])
+        );
+    }
+}
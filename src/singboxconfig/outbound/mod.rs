@@ -3,3 +3,25 @@
 //! 此模块包含各种出站协议的配置定义
 
 mod anytls;
+mod block;
+mod direct;
+mod http;
+mod hysteria2;
+mod shadowsocks;
+mod socks;
+mod trojan;
+mod tuic;
+mod vless;
+mod vmess;
+
+pub use anytls::AnyTlsOutbound;
+pub use block::BlockOutbound;
+pub use direct::DirectOutbound;
+pub use http::HttpOutbound;
+pub use hysteria2::Hysteria2Outbound;
+pub use shadowsocks::ShadowsocksOutbound;
+pub use socks::SocksOutbound;
+pub use trojan::TrojanOutbound;
+pub use tuic::TuicOutbound;
+pub use vless::VlessOutbound;
+pub use vmess::VmessOutbound;
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::{DialFields, MultiplexOutbound};
+
+//============================================================================
+// Shadowsocks 出站配置
+// ============================================================================
+
+/// Shadowsocks 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/shadowsocks/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ShadowsocksOutbound {
+    /// 出站类型，固定为 "shadowsocks"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// 加密方法（必填）
+    /// 如 2022-blake3-aes-128-gcm、aes-256-gcm、chacha20-ietf-poly1305
+    pub method: String,
+
+    /// 密码（必填）
+    pub password: String,
+
+    /// SIP003 插件，支持 obfs-local 与 v2ray-plugin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+
+    /// SIP003 插件参数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin_opts: Option<String>,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// UDP over TCP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udp_over_tcp: Option<bool>,
+
+    /// 多路复用配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<MultiplexOutbound>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl ShadowsocksOutbound {
+    /// 创建新的 Shadowsocks 出站配置
+    pub fn new(
+        tag: impl Into<String>,
+        server: impl Into<String>,
+        server_port: u16,
+        method: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            outbound_type: "shadowsocks".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            method: method.into(),
+            password: password.into(),
+            plugin: None,
+            plugin_opts: None,
+            network: None,
+            udp_over_tcp: None,
+            multiplex: None,
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置 SIP003 插件与参数
+    pub fn with_plugin(mut self, plugin: impl Into<String>, opts: impl Into<String>) -> Self {
+        self.plugin = Some(plugin.into());
+        self.plugin_opts = Some(opts.into());
+        self
+    }
+
+    /// 只启用指定网络（tcp / udp）
+    pub fn with_network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(network.into());
+        self
+    }
+
+    /// 设置是否启用 UDP over TCP
+    pub fn with_udp_over_tcp(mut self, enabled: bool) -> Self {
+        self.udp_over_tcp = Some(enabled);
+        self
+    }
+
+    /// 设置多路复用配置
+    pub fn with_multiplex(mut self, multiplex: MultiplexOutbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = ShadowsocksOutbound::new(
+            "ss-out",
+            "example.com",
+            8388,
+            "2022-blake3-aes-128-gcm",
+            "8JCsPssfgS8tiRwiMlhARg==",
+        )
+        .with_plugin("obfs-local", "obfs=http;obfs-host=www.bing.com");
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "shadowsocks");
+        assert_eq!(json["method"], "2022-blake3-aes-128-gcm");
+        assert_eq!(json["plugin"], "obfs-local");
+        assert!(json.get("network").is_none());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "shadowsocks",
+            "tag": "ss-out",
+            "server": "127.0.0.1",
+            "server_port": 8388,
+            "method": "aes-256-gcm",
+            "password": "password",
+            "udp_over_tcp": true
+        }"#;
+        let outbound: ShadowsocksOutbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.method, "aes-256-gcm");
+        assert_eq!(outbound.udp_over_tcp, Some(true));
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::DialFields;
+
+//============================================================================
+// SOCKS 出站配置
+// ============================================================================
+
+/// SOCKS 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/socks/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SocksOutbound {
+    /// 出站类型，固定为 "socks"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// SOCKS 版本（4 / 4a / 5），默认 5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// 用户名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// 密码（仅 SOCKS5）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// UDP over TCP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udp_over_tcp: Option<bool>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl SocksOutbound {
+    /// 创建新的 SOCKS5 出站配置
+    pub fn new(tag: impl Into<String>, server: impl Into<String>, server_port: u16) -> Self {
+        Self {
+            outbound_type: "socks".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            version: None,
+            username: None,
+            password: None,
+            network: None,
+            udp_over_tcp: None,
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置认证信息
+    pub fn with_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    /// 只启用指定网络（tcp / udp）
+    pub fn with_network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(network.into());
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = SocksOutbound::new("socks-out", "127.0.0.1", 1080).with_auth("u", "p");
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "socks");
+        assert_eq!(json["username"], "u");
+        assert!(json.get("version").is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::{
+    DialFields, MultiplexOutbound, OutboundTlsConfig, V2RayTransport,
+};
+
+//============================================================================
+// Trojan 出站配置
+// ============================================================================
+
+/// Trojan 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/trojan/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TrojanOutbound {
+    /// 出站类型，固定为 "trojan"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// Trojan 密码（必填）
+    pub password: String,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// TLS 配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<OutboundTlsConfig>,
+
+    /// 多路复用配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<MultiplexOutbound>,
+
+    /// V2Ray 传输配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<V2RayTransport>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl TrojanOutbound {
+    /// 创建新的 Trojan 出站配置
+    pub fn new(
+        tag: impl Into<String>,
+        server: impl Into<String>,
+        server_port: u16,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            outbound_type: "trojan".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            password: password.into(),
+            network: None,
+            tls: None,
+            multiplex: None,
+            transport: None,
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: OutboundTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 设置多路复用配置
+    pub fn with_multiplex(mut self, multiplex: MultiplexOutbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置 V2Ray 传输配置
+    pub fn with_transport(mut self, transport: V2RayTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = TrojanOutbound::new("trojan-out", "example.com", 443, "password").with_tls(
+            OutboundTlsConfig {
+                enabled: Some(true),
+                server_name: Some("example.com".to_string()),
+                ..Default::default()
+            },
+        );
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "trojan");
+        assert_eq!(json["password"], "password");
+        assert_eq!(json["tls"]["server_name"], "example.com");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "trojan",
+            "tag": "trojan-out",
+            "server": "127.0.0.1",
+            "server_port": 443,
+            "password": "password",
+            "network": "tcp"
+        }"#;
+        let outbound: TrojanOutbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.network.as_deref(), Some("tcp"));
+        assert!(outbound.tls.is_none());
+    }
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::inbound::{CongestionControl, UdpRelayMode};
+use crate::singboxconfig::shared::{DialFields, OutboundTlsConfig};
+use crate::singboxconfig::types::Duration;
+
+//============================================================================
+// TUIC 出站配置
+// ============================================================================
+
+/// TUIC 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/tuic/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TuicOutbound {
+    /// 出站类型，固定为 "tuic"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// 用户 UUID（必填）
+    pub uuid: String,
+
+    /// 用户密码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    /// QUIC 拥塞控制算法，默认 cubic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub congestion_control: Option<CongestionControl>,
+
+    /// UDP 中继模式，默认 native
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udp_relay_mode: Option<UdpRelayMode>,
+
+    /// 经 QUIC 流传输 UDP over TCP 协议，与 udp_relay_mode 冲突
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udp_over_stream: Option<bool>,
+
+    /// 启用 0-RTT 握手
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zero_rtt_handshake: Option<bool>,
+
+    /// 心跳间隔，默认 10s
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<Duration>,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// TLS 配置（必填）
+    pub tls: OutboundTlsConfig,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl TuicOutbound {
+    /// 创建新的 TUIC 出站配置（启用 TLS）
+    pub fn new(
+        tag: impl Into<String>,
+        server: impl Into<String>,
+        server_port: u16,
+        uuid: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            outbound_type: "tuic".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            uuid: uuid.into(),
+            password: Some(password.into()),
+            congestion_control: None,
+            udp_relay_mode: None,
+            udp_over_stream: None,
+            zero_rtt_handshake: None,
+            heartbeat: None,
+            network: None,
+            tls: OutboundTlsConfig {
+                enabled: Some(true),
+                ..Default::default()
+            },
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置拥塞控制算法
+    pub fn with_congestion_control(mut self, cc: CongestionControl) -> Self {
+        self.congestion_control = Some(cc);
+        self
+    }
+
+    /// 设置 UDP 中继模式
+    pub fn with_udp_relay_mode(mut self, mode: UdpRelayMode) -> Self {
+        self.udp_relay_mode = Some(mode);
+        self
+    }
+
+    /// 启用 0-RTT 握手（须与服务端一致）
+    pub fn with_zero_rtt_handshake(mut self, enabled: bool) -> Self {
+        self.zero_rtt_handshake = Some(enabled);
+        self
+    }
+
+    /// 设置心跳间隔
+    pub fn with_heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat = Some(interval);
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: OutboundTlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = TuicOutbound::new(
+            "tuic-out",
+            "example.com",
+            443,
+            "bf000d23-0752-40b4-affe-68f7707a9661",
+            "password",
+        )
+        .with_congestion_control(CongestionControl::Bbr)
+        .with_udp_relay_mode(UdpRelayMode::Quic);
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "tuic");
+        assert_eq!(json["congestion_control"], "bbr");
+        assert_eq!(json["udp_relay_mode"], "quic");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "tuic",
+            "tag": "tuic-out",
+            "server": "127.0.0.1",
+            "server_port": 443,
+            "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+            "password": "password",
+            "heartbeat": "10s",
+            "tls": { "enabled": true, "alpn": ["h3"] }
+        }"#;
+        let outbound: TuicOutbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.password.as_deref(), Some("password"));
+        assert!(outbound.heartbeat.is_some());
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::{
+    DialFields, MultiplexOutbound, OutboundTlsConfig, V2RayTransport,
+};
+use crate::singboxconfig::types::VlessFlow;
+
+//============================================================================
+// VLESS 出站配置
+// ============================================================================
+
+/// VLESS 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/vless/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct VlessOutbound {
+    /// 出站类型，固定为 "vless"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// 用户 UUID（必填）
+    pub uuid: String,
+
+    /// VLESS 子协议
+    /// 可用值: xtls-rprx-vision
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flow: Option<VlessFlow>,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// TLS 配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<OutboundTlsConfig>,
+
+    /// UDP 包编码（packetaddr / xudp），默认 xudp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packet_encoding: Option<String>,
+
+    /// 多路复用配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<MultiplexOutbound>,
+
+    /// V2Ray 传输配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<V2RayTransport>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl VlessOutbound {
+    /// 创建新的 VLESS 出站配置
+    pub fn new(
+        tag: impl Into<String>,
+        server: impl Into<String>,
+        server_port: u16,
+        uuid: impl Into<String>,
+    ) -> Self {
+        Self {
+            outbound_type: "vless".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            uuid: uuid.into(),
+            flow: None,
+            network: None,
+            tls: None,
+            packet_encoding: None,
+            multiplex: None,
+            transport: None,
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 启用 XTLS Vision
+    pub fn with_xtls_vision(mut self) -> Self {
+        self.flow = Some(VlessFlow::XtlsRprxVision);
+        self
+    }
+
+    /// 设置 UDP 包编码
+    pub fn with_packet_encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.packet_encoding = Some(encoding.into());
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: OutboundTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 设置多路复用配置
+    pub fn with_multiplex(mut self, multiplex: MultiplexOutbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置 V2Ray 传输配置
+    pub fn with_transport(mut self, transport: V2RayTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = VlessOutbound::new(
+            "vless-out",
+            "example.com",
+            443,
+            "bf000d23-0752-40b4-affe-68f7707a9661",
+        )
+        .with_xtls_vision()
+        .with_tls(OutboundTlsConfig::default());
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "vless");
+        assert_eq!(json["flow"], "xtls-rprx-vision");
+        assert!(json.get("transport").is_none());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "vless",
+            "tag": "vless-out",
+            "server": "127.0.0.1",
+            "server_port": 443,
+            "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+            "packet_encoding": "xudp",
+            "transport": { "type": "grpc", "service_name": "grpc" }
+        }"#;
+        let outbound: VlessOutbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.packet_encoding.as_deref(), Some("xudp"));
+        assert!(matches!(outbound.transport, Some(V2RayTransport::Grpc(_))));
+    }
+}
@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::shared::{
+    DialFields, MultiplexOutbound, OutboundTlsConfig, V2RayTransport,
+};
+
+//============================================================================
+// VMess 出站配置
+// ============================================================================
+
+/// VMess 出站配置
+/// 文档: https://sing-box.sagernet.org/configuration/outbound/vmess/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct VmessOutbound {
+    /// 出站类型，固定为 "vmess"
+    #[serde(rename = "type")]
+    pub outbound_type: String,
+
+    /// 出站标签
+    pub tag: String,
+
+    /// 服务器地址（必填）
+    pub server: String,
+
+    /// 服务器端口（必填）
+    pub server_port: u16,
+
+    /// 用户 UUID（必填）
+    pub uuid: String,
+
+    /// 加密方法
+    /// 可用值: auto、none、zero、aes-128-gcm、chacha20-poly1305 等
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<String>,
+
+    /// Alter ID，0 为禁用旧版协议
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alter_id: Option<u32>,
+
+    /// 协议参数：启用时随机填充
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_padding: Option<bool>,
+
+    /// 协议参数：启用长度块加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticated_length: Option<bool>,
+
+    /// 启用的网络（tcp / udp），默认两者都启用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// TLS 配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<OutboundTlsConfig>,
+
+    /// UDP 包编码（packetaddr / xudp）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packet_encoding: Option<String>,
+
+    /// 多路复用配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiplex: Option<MultiplexOutbound>,
+
+    /// V2Ray 传输配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<V2RayTransport>,
+
+    /// 拨号字段
+    #[serde(flatten)]
+    pub dial: DialFields,
+}
+
+impl VmessOutbound {
+    /// 创建新的 VMess 出站配置
+    pub fn new(
+        tag: impl Into<String>,
+        server: impl Into<String>,
+        server_port: u16,
+        uuid: impl Into<String>,
+    ) -> Self {
+        Self {
+            outbound_type: "vmess".to_string(),
+            tag: tag.into(),
+            server: server.into(),
+            server_port,
+            uuid: uuid.into(),
+            security: None,
+            alter_id: None,
+            global_padding: None,
+            authenticated_length: None,
+            network: None,
+            tls: None,
+            packet_encoding: None,
+            multiplex: None,
+            transport: None,
+            dial: DialFields::default(),
+        }
+    }
+
+    /// 设置加密方法
+    pub fn with_security(mut self, security: impl Into<String>) -> Self {
+        self.security = Some(security.into());
+        self
+    }
+
+    /// 设置 Alter ID
+    pub fn with_alter_id(mut self, alter_id: u32) -> Self {
+        self.alter_id = Some(alter_id);
+        self
+    }
+
+    /// 设置 UDP 包编码
+    pub fn with_packet_encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.packet_encoding = Some(encoding.into());
+        self
+    }
+
+    /// 设置 TLS 配置
+    pub fn with_tls(mut self, tls: OutboundTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 设置多路复用配置
+    pub fn with_multiplex(mut self, multiplex: MultiplexOutbound) -> Self {
+        self.multiplex = Some(multiplex);
+        self
+    }
+
+    /// 设置 V2Ray 传输配置
+    pub fn with_transport(mut self, transport: V2RayTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// 设置拨号字段
+    pub fn with_dial(mut self, dial: DialFields) -> Self {
+        self.dial = dial;
+        self
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let outbound = VmessOutbound::new(
+            "vmess-out",
+            "example.com",
+            443,
+            "bf000d23-0752-40b4-affe-68f7707a9661",
+        )
+        .with_security("auto")
+        .with_transport(V2RayTransport::ws());
+        let json = serde_json::to_value(&outbound).unwrap();
+        assert_eq!(json["type"], "vmess");
+        assert_eq!(json["security"], "auto");
+        assert_eq!(json["transport"]["type"], "ws");
+        assert!(json.get("alter_id").is_none());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "type": "vmess",
+            "tag": "vmess-out",
+            "server": "127.0.0.1",
+            "server_port": 1080,
+            "uuid": "bf000d23-0752-40b4-affe-68f7707a9661",
+            "alter_id": 0,
+            "tls": { "enabled": true, "server_name": "example.com" }
+        }"#;
+        let outbound: VmessOutbound = serde_json::from_str(json).unwrap();
+        assert_eq!(outbound.alter_id, Some(0));
+        assert!(outbound.tls.is_some());
+    }
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+
+use crate::singboxconfig::types::{Duration, PortList, PortRangeList, StringOrArray};
+
+//============================================================================
+// 路由规则
+//============================================================================
+
+/// 路由规则（常用匹配字段子集）
+/// 文档: https://sing-box.sagernet.org/configuration/route/rule/
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RouteRule {
+    /// 入站标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inbound: Option<StringOrArray>,
+
+    /// 网络类型 (tcp / udp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<StringOrArray>,
+
+    /// 嗅探到的协议（需先执行 sniff 动作）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<StringOrArray>,
+
+    /// 目标端口
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<PortList>,
+
+    /// 目标端口范围
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_range: Option<PortRangeList>,
+
+    /// 来源 IP CIDR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ip_cidr: Option<StringOrArray>,
+
+    /// 目标域名后缀
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain_suffix: Option<StringOrArray>,
+
+    /// 目标 IP CIDR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_cidr: Option<StringOrArray>,
+
+    /// 目标是否为私有地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_is_private: Option<bool>,
+
+    /// 匹配的规则集标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_set: Option<StringOrArray>,
+
+    /// 反转匹配结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invert: Option<bool>,
+
+    /// 规则动作
+    /// 自 sing-box 1.11.0 起可用，字段与规则平铺
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub action: Option<RuleAction>,
+}
+
+impl RouteRule {
+    /// 创建空规则
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置入站标签
+    pub fn with_inbounds<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inbound = Some(StringOrArray::array(tags));
+        self
+    }
+
+    /// 设置网络类型
+    pub fn with_network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(StringOrArray::single(network));
+        self
+    }
+
+    /// 设置嗅探协议
+    pub fn with_protocols<I, S>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.protocol = Some(StringOrArray::array(protocols));
+        self
+    }
+
+    /// 设置目标端口
+    pub fn with_ports(mut self, ports: impl Into<PortList>) -> Self {
+        self.port = Some(ports.into());
+        self
+    }
+
+    /// 设置目标端口范围
+    pub fn with_port_ranges(mut self, ranges: impl Into<PortRangeList>) -> Self {
+        self.port_range = Some(ranges.into());
+        self
+    }
+
+    /// 设置来源 IP CIDR
+    pub fn with_source_ip_cidrs<I, S>(mut self, cidrs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.source_ip_cidr = Some(StringOrArray::array(cidrs));
+        self
+    }
+
+    /// 设置目标域名后缀
+    pub fn with_domain_suffixes<I, S>(mut self, suffixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.domain_suffix = Some(StringOrArray::array(suffixes));
+        self
+    }
+
+    /// 匹配私有目标地址
+    pub fn with_ip_is_private(mut self) -> Self {
+        self.ip_is_private = Some(true);
+        self
+    }
+
+    /// 设置匹配的规则集标签
+    pub fn with_rule_sets<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rule_set = Some(StringOrArray::array(tags));
+        self
+    }
+
+    /// 设置规则动作
+    pub fn with_action(mut self, action: RuleAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+}
+
+//============================================================================
+// 规则动作
+//============================================================================
+
+/// 路由规则动作
+/// 自 sing-box 1.11.0 起可用
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum RuleAction {
+    /// 路由到指定出站
+    Route(RouteAction),
+    /// 拒绝连接
+    Reject(RejectAction),
+    /// 嗅探协议
+    Sniff(SniffAction),
+    /// 劫持 DNS 请求
+    HijackDns,
+}
+
+impl RuleAction {
+    /// 路由到指定出站
+    pub fn route(outbound: impl Into<String>) -> Self {
+        RuleAction::Route(RouteAction {
+            outbound: outbound.into(),
+        })
+    }
+
+    /// 使用默认方式拒绝
+    pub fn reject() -> Self {
+        RuleAction::Reject(RejectAction::default())
+    }
+
+    /// 使用全部嗅探器嗅探
+    pub fn sniff() -> Self {
+        RuleAction::Sniff(SniffAction::default())
+    }
+}
+
+/// 路由动作
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RouteAction {
+    /// 出站标签
+    pub outbound: String,
+}
+
+/// 拒绝动作
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RejectAction {
+    /// 拒绝方式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<RejectMethod>,
+
+    /// 不因频繁拒绝而丢弃请求
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_drop: Option<bool>,
+}
+
+/// 拒绝方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RejectMethod {
+    /// TCP 返回 RST，UDP 返回 ICMP 不可达
+    Default,
+    /// 直接丢弃
+    Drop,
+}
+
+/// 嗅探动作
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SniffAction {
+    /// 启用的嗅探器（为空时启用全部）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sniffer: Option<Vec<String>>,
+
+    /// 嗅探超时
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
+//============================================================================
+// 规则集
+//============================================================================
+
+/// SagerNet 官方 GeoIP 规则集下载地址
+pub const SING_GEOIP_URL: &str = "https://raw.githubusercontent.com/SagerNet/sing-geoip/rule-set";
+
+/// SagerNet 官方 Geosite 规则集下载地址
+pub const SING_GEOSITE_URL: &str =
+    "https://raw.githubusercontent.com/SagerNet/sing-geosite/rule-set";
+
+/// 远程规则集
+/// 文档: https://sing-box.sagernet.org/configuration/rule-set/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RuleSet {
+    /// 规则集类型，固定为 "remote"
+    #[serde(rename = "type")]
+    pub rule_set_type: String,
+
+    /// 规则集标签（规则中的 rule_set 字段引用）
+    pub tag: String,
+
+    /// 格式 (binary / source)
+    pub format: String,
+
+    /// 下载地址
+    pub url: String,
+
+    /// 下载规则集使用的出站标签（默认使用默认出站）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_detour: Option<String>,
+
+    /// 规则集更新间隔（默认 1d）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_interval: Option<Duration>,
+}
+
+impl RuleSet {
+    /// 创建二进制格式的远程规则集
+    pub fn remote(tag: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            rule_set_type: "remote".to_string(),
+            tag: tag.into(),
+            format: "binary".to_string(),
+            url: url.into(),
+            download_detour: None,
+            update_interval: None,
+        }
+    }
+
+    /// 官方 GeoIP 规则集（标签 geoip-<国家代码>），`base_url` 为下载地址前缀
+    pub fn geoip(country: &str, base_url: &str) -> Self {
+        let tag = format!("geoip-{}", country.to_ascii_lowercase());
+        let url = format!("{}/{}.srs", base_url.trim_end_matches('/'), tag);
+        Self::remote(tag, url)
+    }
+
+    /// 官方 Geosite 规则集（标签 geosite-<名称>），`base_url` 为下载地址前缀
+    pub fn geosite(name: &str, base_url: &str) -> Self {
+        let tag = format!("geosite-{}", name.to_ascii_lowercase());
+        let url = format!("{}/{}.srs", base_url.trim_end_matches('/'), tag);
+        Self::remote(tag, url)
+    }
+
+    /// 设置下载规则集使用的出站
+    pub fn with_download_detour(mut self, tag: impl Into<String>) -> Self {
+        self.download_detour = Some(tag.into());
+        self
+    }
+
+    /// 设置规则集更新间隔
+    pub fn with_update_interval(mut self, interval: impl Into<Duration>) -> Self {
+        self.update_interval = Some(interval.into());
+        self
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rule_serialize() {
+        let rule = RouteRule::new()
+            .with_inbounds(["anytls-in"])
+            .with_action(RuleAction::sniff());
+        assert_eq!(
+            serde_json::to_value(&rule).unwrap(),
+            json!({ "inbound": ["anytls-in"], "action": "sniff" })
+        );
+
+        let rule = RouteRule::new()
+            .with_protocols(["bittorrent"])
+            .with_action(RuleAction::Reject(RejectAction {
+                method: Some(RejectMethod::Drop),
+                no_drop: None,
+            }));
+        assert_eq!(
+            serde_json::to_value(&rule).unwrap(),
+            json!({ "protocol": ["bittorrent"], "action": "reject", "method": "drop" })
+        );
+
+        let rule = RouteRule::new()
+            .with_network("udp")
+            .with_ports(vec![53])
+            .with_action(RuleAction::HijackDns);
+        assert_eq!(
+            serde_json::to_value(&rule).unwrap(),
+            json!({ "network": "udp", "port": [53], "action": "hijack-dns" })
+        );
+    }
+
+    #[test]
+    fn test_rule_set() {
+        let geoip = RuleSet::geoip("CN", SING_GEOIP_URL).with_download_detour("direct");
+        assert_eq!(
+            serde_json::to_value(&geoip).unwrap(),
+            json!({
+                "type": "remote",
+                "tag": "geoip-cn",
+                "format": "binary",
+                "url": "https://raw.githubusercontent.com/SagerNet/sing-geoip/rule-set/geoip-cn.srs",
+                "download_detour": "direct"
+            })
+        );
+        let geosite = RuleSet::geosite("cn", "https://mirror.example.com/");
+        assert_eq!(geosite.url, "https://mirror.example.com/geosite-cn.srs");
+
+        let rule = RouteRule::new()
+            .with_rule_sets([geoip.tag, geosite.tag])
+            .with_action(RuleAction::reject());
+        assert_eq!(
+            serde_json::to_value(&rule).unwrap(),
+            json!({ "rule_set": ["geoip-cn", "geosite-cn"], "action": "reject" })
+        );
+    }
+
+    #[test]
+    fn test_rule_deserialize() {
+        let rule: RouteRule = serde_json::from_value(
+            json!({ "ip_is_private": true, "action": "route", "outbound": "block" }),
+        )
+        .unwrap();
+        assert_eq!(rule.ip_is_private, Some(true));
+        assert_eq!(rule.action, Some(RuleAction::route("block")));
+    }
+}
@@ -11,7 +11,7 @@ use crate::singboxconfig::types::{
 /// 拨号字段配置
 /// 用于出站连接的通用配置
 /// 文档: https://sing-box.sagernet.org/configuration/shared/dial/
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DialFields {
     /// 上游出站的标签
     /// 如果启用，所有其他字段将被忽略
@@ -119,13 +119,129 @@ pub struct DialFields {
     pub domain_strategy: Option<DomainStrategy>,
 }
 
+impl DialFields {
+    /// 设置上游出站
+    pub fn with_detour(mut self, detour: impl Into<String>) -> Self {
+        self.detour = Some(detour.into());
+        self
+    }
+
+    /// 设置绑定的网络接口
+    pub fn with_bind_interface(mut self, interface: impl Into<String>) -> Self {
+        self.bind_interface = Some(interface.into());
+        self
+    }
+
+    /// 设置绑定的 IPv4 地址
+    pub fn with_inet4_bind_address(mut self, address: impl Into<String>) -> Self {
+        self.inet4_bind_address = Some(address.into());
+        self
+    }
+
+    /// 设置绑定的 IPv6 地址
+    pub fn with_inet6_bind_address(mut self, address: impl Into<String>) -> Self {
+        self.inet6_bind_address = Some(address.into());
+        self
+    }
+
+    /// 设置路由标记
+    pub fn with_routing_mark(mut self, mark: RoutingMark) -> Self {
+        self.routing_mark = Some(mark);
+        self
+    }
+
+    /// 设置是否重用监听地址
+    pub fn with_reuse_addr(mut self, enabled: bool) -> Self {
+        self.reuse_addr = Some(enabled);
+        self
+    }
+
+    /// 设置网络命名空间
+    pub fn with_netns(mut self, netns: impl Into<String>) -> Self {
+        self.netns = Some(netns.into());
+        self
+    }
+
+    /// 设置连接超时
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// 设置是否启用 TCP 快速打开
+    pub fn with_tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.tcp_fast_open = Some(enabled);
+        self
+    }
+
+    /// 设置是否启用 TCP 多路径
+    pub fn with_tcp_multi_path(mut self, enabled: bool) -> Self {
+        self.tcp_multi_path = Some(enabled);
+        self
+    }
+
+    /// 设置是否禁用 TCP 保活
+    pub fn with_disable_tcp_keep_alive(mut self, disabled: bool) -> Self {
+        self.disable_tcp_keep_alive = Some(disabled);
+        self
+    }
+
+    /// 设置 TCP 保活初始周期
+    pub fn with_tcp_keep_alive(mut self, period: Duration) -> Self {
+        self.tcp_keep_alive = Some(period);
+        self
+    }
+
+    /// 设置 TCP 保活间隔
+    pub fn with_tcp_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.tcp_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// 设置是否启用 UDP 分片
+    pub fn with_udp_fragment(mut self, enabled: bool) -> Self {
+        self.udp_fragment = Some(enabled);
+        self
+    }
+
+    /// 设置域名解析器
+    pub fn with_domain_resolver(mut self, resolver: impl Into<DomainResolver>) -> Self {
+        self.domain_resolver = Some(resolver.into());
+        self
+    }
+
+    /// 设置网络策略
+    pub fn with_network_strategy(mut self, strategy: NetworkStrategy) -> Self {
+        self.network_strategy = Some(strategy);
+        self
+    }
+
+    /// 设置网络类型
+    pub fn with_network_type(mut self, types: Vec<NetworkType>) -> Self {
+        self.network_type = Some(types);
+        self
+    }
+
+    /// 设置回退网络类型
+    pub fn with_fallback_network_type(mut self, types: Vec<NetworkType>) -> Self {
+        self.fallback_network_type = Some(types);
+        self
+    }
+
+    /// 设置回退延迟
+    pub fn with_fallback_delay(mut self, delay: Duration) -> Self {
+        self.fallback_delay = Some(delay);
+        self
+    }
+}
+
 // ============================================================================
 // 域名解析器
 // ============================================================================
 
 /// 域名解析器配置
 /// 可以是简单的服务器标签字符串，或完整的配置对象
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum DomainResolver {
     /// 简单的服务器标签
@@ -135,7 +251,7 @@ pub enum DomainResolver {
 }
 
 /// 域名解析器完整配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct DomainResolverConfig {
     /// DNS 服务器标签
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 /// DNS01 Challenge 配置
 /// 用于 ACME DNS-01 验证
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(tag = "provider", rename_all = "lowercase")]
 pub enum Dns01Challenge {
     /// 阿里云 DNS
@@ -24,7 +24,7 @@ pub enum Dns01Challenge {
 //============================================================================
 
 /// 阿里云 DNS 配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct AliDnsConfig {
     /// Access Key ID
     pub access_key_id: String,
@@ -42,7 +42,7 @@ pub struct AliDnsConfig {
 //============================================================================
 
 /// Cloudflare DNS 配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct CloudflareConfig {
     /// API Token
     pub api_token: String,
@@ -54,10 +54,7 @@ pub struct CloudflareConfig {
 
 impl Dns01Challenge {
     /// 创建阿里云 DNS 配置
-    pub fn alidns(
-        access_key_id: impl Into<String>,
-        access_key_secret: impl Into<String>,
-    ) -> Self {
+    pub fn alidns(access_key_id: impl Into<String>, access_key_secret: impl Into<String>) -> Self {
         Dns01Challenge::AliDns(AliDnsConfig {
             access_key_id: access_key_id.into(),
             access_key_secret: access_key_secret.into(),
@@ -88,10 +85,7 @@ impl Dns01Challenge {
 
 impl AliDnsConfig {
     /// 创建新的阿里云 DNS 配置
-    pub fn new(
-        access_key_id: impl Into<String>,
-        access_key_secret: impl Into<String>,
-    ) -> Self {
+    pub fn new(access_key_id: impl Into<String>, access_key_secret: impl Into<String>) -> Self {
         Self {
             access_key_id: access_key_id.into(),
             access_key_secret: access_key_secret.into(),
@@ -135,8 +129,7 @@ mod tests {
 
     #[test]
     fn test_alidns_with_region_serialize() {
-        let challenge =
-            Dns01Challenge::alidns_with_region("key_id", "key_secret", "cn-hangzhou");
+        let challenge = Dns01Challenge::alidns_with_region("key_id", "key_secret", "cn-hangzhou");
 
         let json = serde_json::to_string(&challenge).unwrap();
         assert!(json.contains("\"provider\":\"alidns\""));
@@ -4,7 +4,7 @@ use crate::singboxconfig::types::{DomainStrategy, Duration, RoutingMark};
 
 /// 入站监听字段配置
 /// 文档: https://sing-box.sagernet.org/configuration/shared/listen/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct ListenFields {
     /// 监听地址（必填）
     pub listen: String,
@@ -132,3 +132,134 @@ impl Default for ListenFields {
         }
     }
 }
+
+impl ListenFields {
+    /// 创建监听字段配置
+    pub fn new(listen: impl Into<String>) -> Self {
+        Self {
+            listen: listen.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 设置监听地址
+    pub fn with_listen(mut self, listen: impl Into<String>) -> Self {
+        self.listen = listen.into();
+        self
+    }
+
+    /// 设置监听端口
+    pub fn with_listen_port(mut self, port: u16) -> Self {
+        self.listen_port = Some(port);
+        self
+    }
+
+    /// 设置绑定的网络接口
+    pub fn with_bind_interface(mut self, interface: impl Into<String>) -> Self {
+        self.bind_interface = Some(interface.into());
+        self
+    }
+
+    /// 设置路由标记
+    pub fn with_routing_mark(mut self, mark: RoutingMark) -> Self {
+        self.routing_mark = Some(mark);
+        self
+    }
+
+    /// 设置是否重用监听地址
+    pub fn with_reuse_addr(mut self, enabled: bool) -> Self {
+        self.reuse_addr = Some(enabled);
+        self
+    }
+
+    /// 设置网络命名空间
+    pub fn with_netns(mut self, netns: impl Into<String>) -> Self {
+        self.netns = Some(netns.into());
+        self
+    }
+
+    /// 设置是否启用 TCP 快速打开
+    pub fn with_tcp_fast_open(mut self, enabled: bool) -> Self {
+        self.tcp_fast_open = Some(enabled);
+        self
+    }
+
+    /// 设置是否启用 TCP 多路径
+    pub fn with_tcp_multi_path(mut self, enabled: bool) -> Self {
+        self.tcp_multi_path = Some(enabled);
+        self
+    }
+
+    /// 设置是否禁用 TCP 保活
+    pub fn with_disable_tcp_keep_alive(mut self, disabled: bool) -> Self {
+        self.disable_tcp_keep_alive = Some(disabled);
+        self
+    }
+
+    /// 设置 TCP 保活初始周期
+    pub fn with_tcp_keep_alive(mut self, period: Duration) -> Self {
+        self.tcp_keep_alive = Some(period);
+        self
+    }
+
+    /// 设置 TCP 保活间隔
+    pub fn with_tcp_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.tcp_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// 设置是否启用 UDP 分片
+    pub fn with_udp_fragment(mut self, enabled: bool) -> Self {
+        self.udp_fragment = Some(enabled);
+        self
+    }
+
+    /// 设置 UDP NAT 过期时间
+    pub fn with_udp_timeout(mut self, timeout: Duration) -> Self {
+        self.udp_timeout = Some(timeout);
+        self
+    }
+
+    /// 设置转发目标入站
+    pub fn with_detour(mut self, detour: impl Into<String>) -> Self {
+        self.detour = Some(detour.into());
+        self
+    }
+
+    /// 设置是否启用协议嗅探
+    /// sing-box 1.11 及以上版本输出时由 `SingBoxConfig` 改写为路由 sniff 动作
+    #[allow(deprecated)]
+    pub fn with_sniff(mut self, enabled: bool) -> Self {
+        self.sniff = Some(enabled);
+        self
+    }
+
+    /// 设置是否使用嗅探到的域名覆盖连接目标地址
+    /// 路由 sniff 动作没有对应选项，仅在 sing-box 1.11 以下版本生效
+    #[allow(deprecated)]
+    pub fn with_sniff_override_destination(mut self, enabled: bool) -> Self {
+        self.sniff_override_destination = Some(enabled);
+        self
+    }
+
+    /// 设置嗅探超时时间
+    #[allow(deprecated)]
+    pub fn with_sniff_timeout(mut self, timeout: Duration) -> Self {
+        self.sniff_timeout = Some(timeout);
+        self
+    }
+
+    /// 设置域名解析策略
+    #[allow(deprecated)]
+    pub fn with_domain_strategy(mut self, strategy: DomainStrategy) -> Self {
+        self.domain_strategy = Some(strategy);
+        self
+    }
+
+    /// 设置是否在 UDP 响应中发送原始数据包地址
+    #[allow(deprecated)]
+    pub fn with_udp_disable_domain_unmapping(mut self, disabled: bool) -> Self {
+        self.udp_disable_domain_unmapping = Some(disabled);
+        self
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 //============================================================================
 
 /// 入站多路复用配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct MultiplexInbound {
     /// 启用多路复用支持
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -23,7 +23,7 @@ pub struct MultiplexInbound {
 }
 
 /// 出站多路复用配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct MultiplexOutbound {
     /// 启用多路复用
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -67,7 +67,7 @@ pub struct MultiplexOutbound {
 
 /// TCP Brutal 拥塞控制配置
 /// 服务器要求: Linux + brutal拥塞控制算法内核模块
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct TcpBrutal {
     /// 启用 TCP Brutal 拥塞控制算法
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -89,7 +89,7 @@ pub struct TcpBrutal {
 //============================================================================
 
 /// 多路复用协议
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MultiplexProtocol {
     /// smux 协议
@@ -111,6 +111,22 @@ impl Default for MultiplexProtocol {
     }
 }
 
+impl std::str::FromStr for MultiplexProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "smux" => Ok(MultiplexProtocol::Smux),
+            "yamux" => Ok(MultiplexProtocol::Yamux),
+            "h2mux" => Ok(MultiplexProtocol::H2mux),
+            other => Err(format!(
+                "未知的多路复用协议: {}（可选 smux / yamux / h2mux）",
+                other
+            )),
+        }
+    }
+}
+
 //============================================================================
 // 构建器方法
 //============================================================================
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::singboxconfig::shared::Dns01Challenge;
 use crate::singboxconfig::types::{DomainStrategy, Duration, RoutingMark, StringOrArray};
 
 //============================================================================
@@ -8,7 +9,7 @@ use crate::singboxconfig::types::{DomainStrategy, Duration, RoutingMark, StringO
 
 /// 入站 TLS 配置（服务端）
 /// 文档: https://sing-box.sagernet.org/configuration/shared/tls/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct InboundTlsConfig {
     /// 启用 TLS
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -113,7 +114,7 @@ pub struct InboundTlsConfig {
 
 /// 出站 TLS 配置（客户端）
 /// 文档: https://sing-box.sagernet.org/configuration/shared/tls/
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct OutboundTlsConfig {
     /// 启用 TLS
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -224,7 +225,7 @@ pub struct OutboundTlsConfig {
 
 /// ECH 入站配置（服务端）
 /// ECH（加密客户端 Hello）是一种 TLS 扩展，允许客户端加密 ClientHello 消息的第一部分
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct EchInboundConfig {
     /// 启用 ECH
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -257,7 +258,7 @@ pub struct EchInboundConfig {
 }
 
 /// ECH 出站配置（客户端）
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct EchOutboundConfig {
     /// 启用 ECH
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -294,7 +295,7 @@ pub struct EchOutboundConfig {
 // ============================================================================
 
 /// Reality 入站配置（服务端）
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct RealityInboundConfig {
     /// 启用 Reality
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -321,7 +322,7 @@ pub struct RealityInboundConfig {
 }
 
 /// Reality 出站配置（客户端）
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct RealityOutboundConfig {
     /// 启用 Reality
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -339,7 +340,7 @@ pub struct RealityOutboundConfig {
 }
 
 /// Reality 握手配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct RealityHandshake {
     /// 握手服务器地址
     pub server: String,
@@ -404,7 +405,7 @@ pub struct RealityHandshake {
 // ============================================================================
 
 /// ACME 自动证书配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct AcmeConfig {
     ///域名列表
     /// 如果为空则禁用 ACME
@@ -454,11 +455,11 @@ pub struct AcmeConfig {
     /// ACME DNS01 挑战字段
     /// 如果配置，其他挑战方法将被禁用
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dns01_challenge: Option<serde_json::Value>,
+    pub dns01_challenge: Option<Dns01Challenge>,
 }
 
 /// ACME 外部账户绑定
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct AcmeExternalAccount {
     /// 密钥标识符
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -475,7 +476,7 @@ pub struct AcmeExternalAccount {
 
 /// uTLS 配置
 /// uTLS 是 "crypto/tls" 的分支，提供 ClientHello 指纹伪装能力
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct UtlsConfig {
     /// 启用 uTLS
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -492,7 +493,7 @@ pub struct UtlsConfig {
 // ============================================================================
 
 /// TLS 版本
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum TlsVersion {
     #[serde(rename = "1.0")]
     Tls10,
@@ -505,7 +506,7 @@ pub enum TlsVersion {
 }
 
 /// TLS 密码套件
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum CipherSuite {
     #[serde(rename = "TLS_RSA_WITH_AES_128_CBC_SHA")]
     TlsRsaWithAes128CbcSha,
@@ -545,7 +546,7 @@ pub enum CipherSuite {
 
 /// 密钥交换机制（曲线偏好）
 /// 自 sing-box 1.13.0 起可用
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum CurvePreference {
     P256,
     P384,
@@ -557,7 +558,7 @@ pub enum CurvePreference {
 
 /// 客户端认证类型
 /// 自 sing-box 1.13.0 起可用
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum ClientAuthentication {
     /// 不要求客户端证书（默认）
@@ -573,7 +574,7 @@ pub enum ClientAuthentication {
 }
 
 /// ACME 提供商
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum AcmeProvider {
     /// 预定义提供商
@@ -583,7 +584,7 @@ pub enum AcmeProvider {
 }
 
 /// 预定义 ACME 提供商
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AcmeProviderPreset {
     /// Let's Encrypt（默认）
@@ -593,9 +594,10 @@ pub enum AcmeProviderPreset {
 }
 
 /// uTLS 指纹类型
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum UtlsFingerprint {
+    #[default]
     Chrome,
     Firefox,
     Edge,
@@ -670,3 +672,148 @@ impl Default for OutboundTlsConfig {
         }
     }
 }
+
+// ============================================================================
+// ACME 辅助实现
+// ============================================================================
+
+impl std::str::FromStr for AcmeProvider {
+    type Err = String;
+
+    /// 解析 letsencrypt / zerossl 或 http(s):// 开头的自定义 ACME 目录 URL
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "letsencrypt" => Ok(AcmeProvider::Preset(AcmeProviderPreset::LetsEncrypt)),
+            "zerossl" => Ok(AcmeProvider::Preset(AcmeProviderPreset::ZeroSSL)),
+            lower if lower.starts_with("https://") || lower.starts_with("http://") => {
+                Ok(AcmeProvider::Custom(s.to_string()))
+            }
+            _ => Err(format!(
+                "无效的 ACME 提供商: {}（可选 letsencrypt/zerossl/自定义 URL）",
+                s
+            )),
+        }
+    }
+}
+
+// ============================================================================
+// uTLS 辅助实现
+// ============================================================================
+
+impl UtlsFingerprint {
+    /// 全部指纹类型
+    pub const ALL: [UtlsFingerprint; 10] = [
+        UtlsFingerprint::Chrome,
+        UtlsFingerprint::Firefox,
+        UtlsFingerprint::Edge,
+        UtlsFingerprint::Safari,
+        UtlsFingerprint::Browser360,
+        UtlsFingerprint::QQ,
+        UtlsFingerprint::IOS,
+        UtlsFingerprint::Android,
+        UtlsFingerprint::Random,
+        UtlsFingerprint::Randomized,
+    ];
+
+    /// 获取配置与分享链接（`fp` 参数）中使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UtlsFingerprint::Chrome => "chrome",
+            UtlsFingerprint::Firefox => "firefox",
+            UtlsFingerprint::Edge => "edge",
+            UtlsFingerprint::Safari => "safari",
+            UtlsFingerprint::Browser360 => "360",
+            UtlsFingerprint::QQ => "qq",
+            UtlsFingerprint::IOS => "ios",
+            UtlsFingerprint::Android => "android",
+            UtlsFingerprint::Random => "random",
+            UtlsFingerprint::Randomized => "randomized",
+        }
+    }
+}
+
+impl std::str::FromStr for UtlsFingerprint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|fp| fp.as_str() == lower)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(UtlsFingerprint::as_str).collect();
+                format!("无效的 uTLS 指纹: {}（可选 {}）", s, names.join("/"))
+            })
+    }
+}
+
+impl UtlsConfig {
+    /// 使用指定指纹启用 uTLS
+    pub fn new(fingerprint: UtlsFingerprint) -> Self {
+        Self {
+            enabled: Some(true),
+            fingerprint: Some(fingerprint),
+        }
+    }
+}
+
+impl AcmeExternalAccount {
+    /// 创建外部账户绑定
+    pub fn new(key_id: impl Into<String>, mac_key: impl Into<String>) -> Self {
+        Self {
+            key_id: Some(key_id.into()),
+            mac_key: Some(mac_key.into()),
+        }
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_utls_fingerprint_from_str() {
+        for fp in UtlsFingerprint::ALL {
+            assert_eq!(
+                serde_json::to_value(fp.clone()).unwrap(),
+                json!(fp.as_str())
+            );
+            assert_eq!(
+                fp.as_str().parse::<UtlsFingerprint>().unwrap().as_str(),
+                fp.as_str()
+            );
+        }
+        assert_eq!(
+            "Firefox".parse::<UtlsFingerprint>().unwrap().as_str(),
+            "firefox"
+        );
+        assert!("opera".parse::<UtlsFingerprint>().is_err());
+        assert_eq!(
+            serde_json::to_value(UtlsConfig::new(UtlsFingerprint::IOS)).unwrap(),
+            json!({ "enabled": true, "fingerprint": "ios" })
+        );
+    }
+
+    #[test]
+    fn test_acme_provider_from_str() {
+        let provider: AcmeProvider = "ZeroSSL".parse().unwrap();
+        assert_eq!(serde_json::to_value(&provider).unwrap(), json!("zerossl"));
+        let provider: AcmeProvider = "letsencrypt".parse().unwrap();
+        assert_eq!(
+            serde_json::to_value(&provider).unwrap(),
+            json!("letsencrypt")
+        );
+        let provider: AcmeProvider = "https://acme.example.com/directory".parse().unwrap();
+        assert_eq!(
+            serde_json::to_value(&provider).unwrap(),
+            json!("https://acme.example.com/directory")
+        );
+        assert!("buypass".parse::<AcmeProvider>().is_err());
+    }
+}
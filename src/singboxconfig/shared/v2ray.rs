@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::singboxconfig::types::Duration;
 
@@ -10,7 +10,7 @@ use crate::singboxconfig::types::Duration;
 
 /// V2Ray Transport 配置
 /// 支持的传输类型: HTTP, WebSocket, QUIC, gRPC, HTTPUpgrade
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum V2RayTransport {
     /// HTTP 传输
@@ -31,7 +31,7 @@ pub enum V2RayTransport {
 //============================================================================
 
 /// HTTP 传输配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct HttpTransport {
     /// 主机域名列表
     /// 客户端将随机选择，服务端将验证（如果非空）
@@ -51,7 +51,7 @@ pub struct HttpTransport {
     /// HTTP 请求额外头部
     /// 服务端将在响应中写入（如果非空）
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<HashMap<String, String>>,
+    pub headers: Option<BTreeMap<String, String>>,
 
     /// 空闲超时时间
     /// HTTP2 服务端: 指定空闲客户端应该被GOAWAY 帧关闭的时间
@@ -72,7 +72,7 @@ pub struct HttpTransport {
 //============================================================================
 
 /// WebSocket 传输配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct WebSocketTransport {
     /// HTTP 请求路径
     /// 服务端将验证
@@ -82,7 +82,7 @@ pub struct WebSocketTransport {
     /// HTTP 请求额外头部
     /// 服务端将在响应中写入（如果非空）
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<HashMap<String, String>>,
+    pub headers: Option<BTreeMap<String, String>>,
 
     /// 允许在请求中发送的有效载荷大小
     /// 非零时启用
@@ -103,7 +103,7 @@ pub struct WebSocketTransport {
 
 /// QUIC 传输配置
 /// 注意: 不支持额外加密（基本上是重复加密）
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct QuicTransport {
     // QUIC 传输没有额外配置字段
 }
@@ -114,7 +114,7 @@ pub struct QuicTransport {
 
 /// gRPC 传输配置
 /// 注意: 标准 gRPC 有良好的兼容性但性能较差，默认不包含
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct GrpcTransport {
     /// gRPC 服务名称
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -145,7 +145,7 @@ pub struct GrpcTransport {
 //============================================================================
 
 /// HTTPUpgrade 传输配置
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct HttpUpgradeTransport {
     /// 主机域名
     /// 服务端将验证（如果非空）
@@ -160,7 +160,7 @@ pub struct HttpUpgradeTransport {
     /// HTTP 请求额外头部
     /// 服务端将在响应中写入（如果非空）
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<HashMap<String, String>>,
+    pub headers: Option<BTreeMap<String, String>>,
 }
 
 //============================================================================
@@ -219,7 +219,7 @@ impl HttpTransport {
     }
 
     /// 设置头部
-    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
         self.headers = Some(headers);
         self
     }
@@ -227,7 +227,7 @@ impl HttpTransport {
     /// 添加单个头部
     pub fn add_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.headers
-            .get_or_insert_with(HashMap::new)
+            .get_or_insert_with(BTreeMap::new)
             .insert(key.into(), value.into());
         self
     }
@@ -258,7 +258,7 @@ impl WebSocketTransport {
     }
 
     /// 设置头部
-    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
         self.headers = Some(headers);
         self
     }
@@ -266,7 +266,7 @@ impl WebSocketTransport {
     /// 添加单个头部
     pub fn add_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.headers
-            .get_or_insert_with(HashMap::new)
+            .get_or_insert_with(BTreeMap::new)
             .insert(key.into(), value.into());
         self
     }
@@ -340,7 +340,7 @@ impl HttpUpgradeTransport {
     }
 
     /// 设置头部
-    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
         self.headers = Some(headers);
         self
     }
@@ -348,7 +348,7 @@ impl HttpUpgradeTransport {
     /// 添加单个头部
     pub fn add_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.headers
-            .get_or_insert_with(HashMap::new)
+            .get_or_insert_with(BTreeMap::new)
             .insert(key.into(), value.into());
         self
     }
@@ -0,0 +1,269 @@
+//! 严格模式解析
+//!
+//! 默认解析会静默忽略未知字段，手工编辑配置时 `"serve_name"` 之类的拼写错误因此不会被发现。
+//! 严格模式把每一段反序列化为对应的强类型结构，再与原始 JSON 比对，
+//! 原始 JSON 中有而强类型结构不认识的字段以 JSON Pointer（如 `/inbounds/0/tls/serve_name`）报告。
+//! 没有强类型定义的入站 / 出站类型不做检查
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::endpoint::WireGuardEndpoint;
+use super::inbound::{
+    AnyTlsInbound, Hysteria2Inbound, TrojanInbound, TuicInbound, TunInbound, VlessInbound,
+    VmessInbound,
+};
+use super::outbound::{
+    AnyTlsOutbound, BlockOutbound, DirectOutbound, HttpOutbound, Hysteria2Outbound,
+    ShadowsocksOutbound, SocksOutbound, TrojanOutbound, TuicOutbound, VlessOutbound, VmessOutbound,
+};
+use super::validate::Diagnostic;
+use crate::dns::Dns;
+
+/// sing-box 配置的顶层字段（按文档顺序）
+pub(crate) const TOP_LEVEL_KEYS: [&str; 10] = [
+    "log",
+    "dns",
+    "ntp",
+    "certificate",
+    "endpoints",
+    "inbounds",
+    "outbounds",
+    "route",
+    "services",
+    "experimental",
+];
+
+/// 严格模式解析：存在未知字段或类型错误时返回全部诊断
+pub fn from_value_strict<T>(value: Value) -> Result<T, Vec<Diagnostic>>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let diagnostics = unknown_field_diagnostics::<T>(&value, "");
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+    serde_json::from_value(value).map_err(|e| vec![Diagnostic::error("", e.to_string())])
+}
+
+/// 严格模式解析 JSON 字符串
+pub fn from_str_strict<T>(json: &str) -> Result<T, Vec<Diagnostic>>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let value =
+        serde_json::from_str(json).map_err(|e| vec![Diagnostic::error("", e.to_string())])?;
+    from_value_strict(value)
+}
+
+/// 检查完整配置中的未知字段（顶层、DNS、有强类型定义的入站 / 出站 / 端点）
+pub fn strict_diagnostics(config: &Value) -> Vec<Diagnostic> {
+    let Some(root) = config.as_object() else {
+        return vec![Diagnostic::error("", "配置必须是 JSON 对象")];
+    };
+    let mut diagnostics: Vec<Diagnostic> = root
+        .keys()
+        .filter(|key| !TOP_LEVEL_KEYS.contains(&key.as_str()))
+        .map(|key| unknown_field(&pointer_push("", key)))
+        .collect();
+    if let Some(dns) = root.get("dns") {
+        diagnostics.extend(unknown_field_diagnostics::<Dns>(dns, "/dns"));
+    }
+    for (section, check) in [
+        (
+            "inbounds",
+            check_inbound as fn(&Value, &str) -> Vec<Diagnostic>,
+        ),
+        ("outbounds", check_outbound),
+        ("endpoints", check_endpoint),
+    ] {
+        let Some(items) = root.get(section).and_then(Value::as_array) else {
+            continue;
+        };
+        for (i, item) in items.iter().enumerate() {
+            diagnostics.extend(check(item, &format!("/{}/{}", section, i)));
+        }
+    }
+    diagnostics
+}
+
+/// 按类型检查入站
+fn check_inbound(item: &Value, pointer: &str) -> Vec<Diagnostic> {
+    match item["type"].as_str() {
+        Some("anytls") => unknown_field_diagnostics::<AnyTlsInbound>(item, pointer),
+        Some("hysteria2") => unknown_field_diagnostics::<Hysteria2Inbound>(item, pointer),
+        Some("trojan") => unknown_field_diagnostics::<TrojanInbound>(item, pointer),
+        Some("tuic") => unknown_field_diagnostics::<TuicInbound>(item, pointer),
+        Some("tun") => unknown_field_diagnostics::<TunInbound>(item, pointer),
+        Some("vless") => unknown_field_diagnostics::<VlessInbound>(item, pointer),
+        Some("vmess") => unknown_field_diagnostics::<VmessInbound>(item, pointer),
+        _ => Vec::new(),
+    }
+}
+
+/// 按类型检查出站
+fn check_outbound(item: &Value, pointer: &str) -> Vec<Diagnostic> {
+    match item["type"].as_str() {
+        Some("anytls") => unknown_field_diagnostics::<AnyTlsOutbound>(item, pointer),
+        Some("block") => unknown_field_diagnostics::<BlockOutbound>(item, pointer),
+        Some("direct") => unknown_field_diagnostics::<DirectOutbound>(item, pointer),
+        Some("http") => unknown_field_diagnostics::<HttpOutbound>(item, pointer),
+        Some("hysteria2") => unknown_field_diagnostics::<Hysteria2Outbound>(item, pointer),
+        Some("shadowsocks") => unknown_field_diagnostics::<ShadowsocksOutbound>(item, pointer),
+        Some("socks") => unknown_field_diagnostics::<SocksOutbound>(item, pointer),
+        Some("trojan") => unknown_field_diagnostics::<TrojanOutbound>(item, pointer),
+        Some("tuic") => unknown_field_diagnostics::<TuicOutbound>(item, pointer),
+        Some("vless") => unknown_field_diagnostics::<VlessOutbound>(item, pointer),
+        Some("vmess") => unknown_field_diagnostics::<VmessOutbound>(item, pointer),
+        _ => Vec::new(),
+    }
+}
+
+/// 按类型检查端点
+fn check_endpoint(item: &Value, pointer: &str) -> Vec<Diagnostic> {
+    match item["type"].as_str() {
+        Some("wireguard") => unknown_field_diagnostics::<WireGuardEndpoint>(item, pointer),
+        _ => Vec::new(),
+    }
+}
+
+/// 把值反序列化为 T 再序列化回来，原始值中有而结果中没有的字段即为 T 不认识的字段
+/// 反序列化失败时报告类型错误
+pub fn unknown_field_diagnostics<T>(value: &Value, pointer: &str) -> Vec<Diagnostic>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let parsed = match serde_json::from_value::<T>(value.clone()) {
+        Ok(parsed) => parsed,
+        Err(e) => return vec![Diagnostic::error(pointer, e.to_string())],
+    };
+    let round_trip = match serde_json::to_value(&parsed) {
+        Ok(v) => v,
+        Err(e) => return vec![Diagnostic::error(pointer, e.to_string())],
+    };
+    let mut diagnostics = Vec::new();
+    compare(value, &round_trip, pointer, &mut diagnostics);
+    diagnostics
+}
+
+/// 递归比对原始值与往返后的值
+fn compare(original: &Value, round_trip: &Value, pointer: &str, out: &mut Vec<Diagnostic>) {
+    match (original, round_trip) {
+        (Value::Object(original), Value::Object(round_trip)) => {
+            for (key, value) in original {
+                let child = pointer_push(pointer, key);
+                match round_trip.get(key) {
+                    Some(known) => compare(value, known, &child, out),
+                    // 空值与默认值一样可能在序列化时被省略，不视为未知字段
+                    None if is_empty(value) => {}
+                    None => out.push(unknown_field(&child)),
+                }
+            }
+        }
+        (Value::Array(original), Value::Array(round_trip)) => {
+            for (i, (value, known)) in original.iter().zip(round_trip).enumerate() {
+                compare(value, known, &pointer_push(pointer, &i.to_string()), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// null、空数组与空对象
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// 未知字段诊断
+fn unknown_field(pointer: &str) -> Diagnostic {
+    let key = pointer.rsplit('/').next().unwrap_or_default();
+    Diagnostic::error(
+        pointer,
+        format!("未知字段 `{}`", key.replace("~1", "/").replace("~0", "~")),
+    )
+}
+
+/// 追加 JSON Pointer 片段（`~` 与 `/` 按 RFC 6901 转义）
+pub(crate) fn pointer_push(pointer: &str, segment: &str) -> String {
+    format!(
+        "{}/{}",
+        pointer,
+        segment.replace('~', "~0").replace('/', "~1")
+    )
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_strict_diagnostics() {
+        let config = json!({
+            "log": { "level": "info" },
+            "inbound": [],
+            "inbounds": [{
+                "type": "anytls",
+                "tag": "anytls-in",
+                "listen": "::",
+                "listen_port": 443,
+                "users": [{ "name": "u", "password": "p" }],
+                "tls": { "enabled": true, "serve_name": "example.com", "alpn": [] }
+            }, {
+                "type": "shadowsocks",
+                "tag": "ss-in",
+                "whatever": true
+            }],
+            "outbounds": [{ "type": "direct", "tag": "direct", "detuor": "x" }],
+            "dns": {
+                "servers": [],
+                "rules": [
+                    { "type": "logical", "mode": "or", "rules": [{ "domain": ["a.com"] }], "action": "route", "server": "local" },
+                    { "type": "logical", "mode": "and", "rules": [{ "domian": ["b.com"] }], "server": "local" }
+                ],
+                "finl": "local"
+            }
+        });
+        let paths: Vec<String> = strict_diagnostics(&config)
+            .into_iter()
+            .map(|d| d.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/inbound",
+                "/dns/finl",
+                "/dns/rules/1/rules/0/domian",
+                "/inbounds/0/tls/serve_name",
+                "/outbounds/0/detuor"
+            ]
+        );
+        assert_eq!(pointer_push("/a", "b/c~"), "/a/b~1c~0");
+    }
+
+    #[test]
+    fn test_from_str_strict() {
+        let dns: Dns = from_str_strict(r#"{ "final": "local" }"#).unwrap();
+        assert_eq!(dns.final_server.as_deref(), Some("local"));
+
+        let errors =
+            from_str_strict::<Dns>(r#"{ "final": "local", "stratgy": "ipv4_only" }"#).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/stratgy");
+        assert!(errors[0].message.contains("stratgy"));
+
+        // 类型错误同样报告
+        let errors = from_str_strict::<Dns>(r#"{ "servers": 1 }"#).unwrap_err();
+        assert_eq!(errors[0].path, "");
+    }
+}
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 //============================================================================
@@ -6,7 +8,7 @@ use serde::{Deserialize, Serialize};
 
 /// 域名解析策略
 /// 用于指定如何解析域名到IP 地址
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DomainStrategy {
     /// 优先使用 IPv4
@@ -43,6 +45,23 @@ impl std::fmt::Display for DomainStrategy {
     }
 }
 
+impl FromStr for DomainStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "prefer_ipv4" => Ok(DomainStrategy::PreferIpv4),
+            "prefer_ipv6" => Ok(DomainStrategy::PreferIpv6),
+            "ipv4_only" => Ok(DomainStrategy::Ipv4Only),
+            "ipv6_only" => Ok(DomainStrategy::Ipv6Only),
+            other => Err(format!(
+                "未知的域名解析策略: {}（可选 prefer_ipv4 / prefer_ipv6 / ipv4_only / ipv6_only）",
+                other
+            )),
+        }
+    }
+}
+
 // ============================================================================
 // 单元测试
 // ============================================================================
@@ -78,4 +97,13 @@ mod tests {
         assert_eq!(DomainStrategy::Ipv4Only.to_string(), "ipv4_only");
         assert_eq!(DomainStrategy::Ipv6Only.to_string(), "ipv6_only");
     }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "IPv4_Only".parse::<DomainStrategy>(),
+            Ok(DomainStrategy::Ipv4Only)
+        );
+        assert!("ipv4".parse::<DomainStrategy>().is_err());
+    }
 }
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, AddAssign};
 use std::str::FromStr;
 
 //============================================================================
@@ -7,9 +10,11 @@ use std::str::FromStr;
 // ============================================================================
 
 /// sing-box 时间间隔类型
-/// 支持格式: "1h", "30m", "5s", "300ms", "1h30m", "1m30s" 等
+/// 支持格式: "1h", "30m", "5s", "300ms", "1h30m", "1m30s", "30d", "1.5h" 等，
+/// 不带单位的数字按秒处理（如 "30"、JSON 中的 30）
+/// 比较、相等与哈希只看时长本身（"60s" 与 "1m" 相等）
 /// 文档: https://sing-box.sagernet.org/configuration/
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Duration {
     /// 内部存储的毫秒数
     millis: u64,
@@ -47,6 +52,24 @@ impl Duration {
         }
     }
 
+    /// 从天创建
+    pub fn from_days(days: u64) -> Self {
+        Self {
+            millis: days * 24 * 60 * 60 * 1000,
+            raw: None,
+        }
+    }
+
+    /// 是否为零
+    pub fn is_zero(&self) -> bool {
+        self.millis == 0
+    }
+
+    /// 相减，结果小于零时为零
+    pub fn saturating_sub(&self, other: &Duration) -> Self {
+        Duration::from_millis(self.millis.saturating_sub(other.millis))
+    }
+
     /// 获取毫秒数
     pub fn as_millis(&self) -> u64 {
         self.millis
@@ -100,44 +123,45 @@ impl FromStr for Duration {
         if s.is_empty() {
             return Err(ParseDurationError::Empty);
         }
+        // 不带单位的数字按秒处理，输出时规范为带单位的形式
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            let secs: u64 = s
+                .parse()
+                .map_err(|_| ParseDurationError::InvalidNumber(s.to_string()))?;
+            return secs
+                .checked_mul(1000)
+                .map(Duration::from_millis)
+                .ok_or(ParseDurationError::Overflow);
+        }
 
         let mut total_millis: u64 = 0;
         let mut current_num = String::new();
         let mut chars = s.chars().peekable();
 
         while let Some(c) = chars.next() {
-            if c.is_ascii_digit() {
+            if c.is_ascii_digit() || c == '.' {
                 current_num.push(c);
             } else if c.is_alphabetic() {
                 if current_num.is_empty() {
                     return Err(ParseDurationError::InvalidFormat(s.to_string()));
                 }
 
-                let num: u64 = current_num
-                    .parse()
-                    .map_err(|_| ParseDurationError::InvalidNumber(current_num.clone()))?;
-                current_num.clear();
-
                 // 检查是否是"ms"
-                let unit = if c == 'm' && chars.peek() == Some(&'s') {
+                let unit_millis: u64 = if c == 'm' && chars.peek() == Some(&'s') {
                     chars.next(); // 消费 's'
-                    "ms"
+                    1
                 } else {
                     match c {
-                        'h' => "h",
-                        'm' => "m",
-                        's' => "s",
+                        'd' => 24 * 60 * 60 * 1000,
+                        'h' => 60 * 60 * 1000,
+                        'm' => 60 * 1000,
+                        's' => 1000,
                         _ => return Err(ParseDurationError::InvalidUnit(c.to_string())),
                     }
                 };
 
-                let millis = match unit {
-                    "h" => num * 60 * 60 * 1000,
-                    "m" => num * 60 * 1000,
-                    "s" => num * 1000,
-                    "ms" => num,
-                    _ => return Err(ParseDurationError::InvalidUnit(unit.to_string())),
-                };
+                let millis = scale_number(&current_num, unit_millis)?;
+                current_num.clear();
 
                 total_millis = total_millis
                     .checked_add(millis)
@@ -159,6 +183,34 @@ impl FromStr for Duration {
     }
 }
 
+/// 把带小数的数字（如 "1.5"）乘以单位毫秒数，不足 1 毫秒的部分舍去
+fn scale_number(num: &str, unit_millis: u64) -> Result<u64, ParseDurationError> {
+    let invalid = || ParseDurationError::InvalidNumber(num.to_string());
+    let (int, frac) = num.split_once('.').unwrap_or((num, ""));
+    if (int.is_empty() && frac.is_empty()) || frac.contains('.') {
+        return Err(invalid());
+    }
+    let int: u64 = if int.is_empty() {
+        0
+    } else {
+        int.parse().map_err(|_| invalid())?
+    };
+    let mut millis = int
+        .checked_mul(unit_millis)
+        .ok_or(ParseDurationError::Overflow)?;
+    let mut scale = unit_millis;
+    for digit in frac.bytes() {
+        scale /= 10;
+        if scale == 0 {
+            break;
+        }
+        millis = millis
+            .checked_add(u64::from(digit - b'0') * scale)
+            .ok_or(ParseDurationError::Overflow)?;
+    }
+    Ok(millis)
+}
+
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.format_string())
@@ -179,8 +231,76 @@ impl<'de> Deserialize<'de> for Duration {
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Duration::from_str(&s).map_err(serde::de::Error::custom)
+        deserializer.deserialize_any(DurationVisitor)
+    }
+}
+
+/// 接受时间间隔字符串或表示秒数的整数
+struct DurationVisitor;
+
+impl serde::de::Visitor<'_> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "时间间隔字符串（如 \"5m\"）或秒数")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Duration, E> {
+        Duration::from_str(v).map_err(E::custom)
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Duration, E> {
+        v.checked_mul(1000)
+            .map(Duration::from_millis)
+            .ok_or_else(|| E::custom(ParseDurationError::Overflow))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Duration, E> {
+        let secs = u64::try_from(v)
+            .map_err(|_| E::custom(ParseDurationError::InvalidNumber(v.to_string())))?;
+        self.visit_u64(secs)
+    }
+}
+
+impl PartialEq for Duration {
+    fn eq(&self, other: &Self) -> bool {
+        self.millis == other.millis
+    }
+}
+
+impl Eq for Duration {}
+
+impl Hash for Duration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.millis.hash(state);
+    }
+}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Duration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.millis.cmp(&other.millis)
+    }
+}
+
+/// 相加，溢出时取最大值
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        Duration::from_millis(self.millis.saturating_add(rhs.millis))
+    }
+}
+
+/// 相加，溢出时取最大值
+impl AddAssign for Duration {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = Duration::from_millis(self.millis.saturating_add(rhs.millis));
     }
 }
 
@@ -190,6 +310,12 @@ impl From<std::time::Duration> for Duration {
     }
 }
 
+impl From<Duration> for std::time::Duration {
+    fn from(d: Duration) -> Self {
+        d.to_std()
+    }
+}
+
 impl Default for Duration {
     fn default() -> Self {
         Duration::from_secs(0)
@@ -270,6 +396,7 @@ mod tests {
         assert_eq!(Duration::from_str("1h30m").unwrap().as_millis(), 5400000);
         assert_eq!(Duration::from_str("1m30s").unwrap().as_millis(), 90000);
         assert_eq!(Duration::from_str("1h30m45s").unwrap().as_millis(), 5445000);
+        assert_eq!(Duration::from_str("1d12h").unwrap().as_millis(), 129600000);
     }
 
     #[test]
@@ -298,4 +425,65 @@ mod tests {
         let d = Duration::from_str("1h30m").unwrap();
         assert_eq!(d.to_string(), "1h30m");
     }
+
+    #[test]
+    fn test_parse_fraction_and_bare_seconds() {
+        assert_eq!(Duration::from_str("1.5h").unwrap(), Duration::from_mins(90));
+        assert_eq!(Duration::from_str(".5s").unwrap().as_millis(), 500);
+        assert_eq!(Duration::from_str("1.0005s").unwrap().as_millis(), 1000);
+        let d = Duration::from_str("30").unwrap();
+        assert_eq!(d.as_secs(), 30);
+        assert_eq!(d.to_string(), "30s");
+        assert!(Duration::from_str("1.2.3s").is_err());
+        assert!(Duration::from_str(".s").is_err());
+        // 整数部分恰好不溢出，加上小数部分后溢出
+        assert!(matches!(
+            Duration::from_str("18446744073709551.999s"),
+            Err(ParseDurationError::Overflow)
+        ));
+        assert!(Duration::from_str("18446744073709551.7s").is_err());
+        assert!(Duration::from_str("18446744073709551.6s").is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_seconds() {
+        let d: Duration = serde_json::from_str("300").unwrap();
+        assert_eq!(d, Duration::from_mins(5));
+        assert_eq!(serde_json::to_string(&d).unwrap(), "\"5m\"");
+        assert!(serde_json::from_str::<Duration>("-1").is_err());
+        assert!(serde_json::from_str::<Duration>("true").is_err());
+    }
+
+    #[test]
+    fn test_compare_and_add() {
+        assert_eq!(Duration::from_str("60s").unwrap(), Duration::from_mins(1));
+        assert!(Duration::from_millis(500) < Duration::from_secs(1));
+        assert_eq!(
+            [Duration::from_hours(1), Duration::from_secs(5)]
+                .iter()
+                .max(),
+            Some(&Duration::from_hours(1))
+        );
+
+        let mut d = Duration::from_str("1h").unwrap() + Duration::from_mins(30);
+        assert_eq!(d.to_string(), "90m");
+        d += Duration::from_millis(250);
+        assert_eq!(d.as_millis(), 5_400_250);
+        assert_eq!(
+            Duration::from_secs(1).saturating_sub(&Duration::from_secs(5)),
+            Duration::default()
+        );
+        assert!(Duration::from_days(1) > Duration::from_hours(23));
+        assert_eq!(
+            (Duration::from_millis(u64::MAX) + Duration::from_secs(1)).as_millis(),
+            u64::MAX
+        );
+
+        // 哈希与相等一致
+        let set: std::collections::HashSet<Duration> = ["60s", "1m", "1m0s"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(set.len(), 1);
+    }
 }
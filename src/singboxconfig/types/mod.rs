@@ -5,15 +5,19 @@
 mod domain_strategy;
 mod duration;
 mod network_strategy;
+mod port;
 mod routing_mark;
 mod string_or_array;
 mod user;
+mod version;
 
 pub use domain_strategy::DomainStrategy;
 pub use duration::{Duration, ParseDurationError};
 pub use network_strategy::{NetworkStrategy, NetworkType};
+pub use port::{ParsePortError, PortList, PortRange, PortRangeList};
 pub use routing_mark::RoutingMark;
 pub use string_or_array::StringOrArray;
 pub use user::{
     ShadowsocksDestination, TuicUser, UserWithPassword, VMessUser, VlessFlow, VlessUser,
 };
+pub use version::SingBoxVersion;
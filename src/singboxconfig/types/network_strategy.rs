@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 /// 用于选择网络接口的策略
 /// 自sing-box 1.11.0 起可用
 ///仅在 Android 和 Apple 平台的图形客户端中支持，需启用 `auto_detect_interface`
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NetworkStrategy {
     /// 默认策略
@@ -49,7 +49,7 @@ impl std::fmt::Display for NetworkStrategy {
 /// 用于指定要使用的网络类型
 /// 自 sing-box 1.11.0 起可用
 /// 仅在 Android 和 Apple 平台的图形客户端中支持，需启用 `auto_detect_interface`
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NetworkType {
     /// WiFi 网络
@@ -0,0 +1,363 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+//============================================================================
+// 端口范围类型
+// ============================================================================
+
+/// 端口范围
+/// sing-box 格式为 "1000:2000"，省略起点（":3000"）或终点（"1000:"）时分别为 0 与 65535；
+/// 解析时也接受 "1000-2000"，单个端口（"443"）视为只含该端口的范围
+/// 用于 DNS / 路由规则的 port_range 字段与 Hysteria2 端口跳跃
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PortRange {
+    /// 起始端口（含）
+    pub start: u16,
+    /// 结束端口（含）
+    pub end: u16,
+}
+
+impl PortRange {
+    /// 创建端口范围（起点大于终点时交换）
+    pub fn new(start: u16, end: u16) -> Self {
+        Self {
+            start: start.min(end),
+            end: start.max(end),
+        }
+    }
+
+    /// 只含一个端口的范围
+    pub fn single(port: u16) -> Self {
+        Self {
+            start: port,
+            end: port,
+        }
+    }
+
+    /// 是否包含该端口
+    pub fn contains(&self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+
+    /// 范围内的端口数
+    pub fn len(&self) -> usize {
+        usize::from(self.end - self.start) + 1
+    }
+
+    /// 范围总是至少包含一个端口
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// 遍历范围内的端口
+    pub fn iter(&self) -> std::ops::RangeInclusive<u16> {
+        self.start..=self.end
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = ParsePortError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParsePortError::Empty);
+        }
+        let Some((start, end)) = s.split_once(':').or_else(|| s.split_once('-')) else {
+            return parse_port(s).map(PortRange::single);
+        };
+        let start = match start.trim() {
+            "" => 0,
+            p => parse_port(p)?,
+        };
+        let end = match end.trim() {
+            "" => u16::MAX,
+            p => parse_port(p)?,
+        };
+        if start > end {
+            return Err(ParsePortError::InvalidRange(s.to_string()));
+        }
+        Ok(PortRange { start, end })
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.start, self.end)
+    }
+}
+
+impl Serialize for PortRange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PortRange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        PortRange::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<u16> for PortRange {
+    fn from(port: u16) -> Self {
+        PortRange::single(port)
+    }
+}
+
+impl From<std::ops::RangeInclusive<u16>> for PortRange {
+    fn from(range: std::ops::RangeInclusive<u16>) -> Self {
+        PortRange::new(*range.start(), *range.end())
+    }
+}
+
+//============================================================================
+// 端口列表类型
+// ============================================================================
+
+/// 单个端口或端口数组（如 DNS / 路由规则的 port、source_port 字段）
+/// 字符串解析接受逗号分隔的端口（如 "80,443,8443"）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PortList {
+    /// 单个端口
+    Single(u16),
+    /// 多个端口
+    Array(Vec<u16>),
+}
+
+impl PortList {
+    /// 所有端口
+    pub fn as_slice(&self) -> &[u16] {
+        match self {
+            PortList::Single(port) => std::slice::from_ref(port),
+            PortList::Array(ports) => ports,
+        }
+    }
+
+    /// 是否包含该端口
+    pub fn contains(&self, port: u16) -> bool {
+        self.as_slice().contains(&port)
+    }
+
+    /// 转换为端口向量
+    pub fn into_vec(self) -> Vec<u16> {
+        match self {
+            PortList::Single(port) => vec![port],
+            PortList::Array(ports) => ports,
+        }
+    }
+}
+
+impl FromStr for PortList {
+    type Err = ParsePortError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ports = split_list(s)
+            .map(parse_port)
+            .collect::<Result<Vec<_>, _>>()?;
+        match ports.as_slice() {
+            [] => Err(ParsePortError::Empty),
+            [port] => Ok(PortList::Single(*port)),
+            _ => Ok(PortList::Array(ports)),
+        }
+    }
+}
+
+impl From<u16> for PortList {
+    fn from(port: u16) -> Self {
+        PortList::Single(port)
+    }
+}
+
+impl From<Vec<u16>> for PortList {
+    fn from(ports: Vec<u16>) -> Self {
+        PortList::Array(ports)
+    }
+}
+
+impl<const N: usize> From<[u16; N]> for PortList {
+    fn from(ports: [u16; N]) -> Self {
+        PortList::Array(ports.to_vec())
+    }
+}
+
+/// 单个端口范围或端口范围数组（如 DNS / 路由规则的 port_range、Hysteria2 的 server_ports）
+/// 字符串解析接受逗号分隔的范围（如 "1000:2000,3000-3100,443"）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PortRangeList {
+    /// 单个范围
+    Single(PortRange),
+    /// 多个范围
+    Array(Vec<PortRange>),
+}
+
+impl PortRangeList {
+    /// 所有范围
+    pub fn as_slice(&self) -> &[PortRange] {
+        match self {
+            PortRangeList::Single(range) => std::slice::from_ref(range),
+            PortRangeList::Array(ranges) => ranges,
+        }
+    }
+
+    /// 是否有范围包含该端口
+    pub fn contains(&self, port: u16) -> bool {
+        self.as_slice().iter().any(|r| r.contains(port))
+    }
+
+    /// 转换为范围向量
+    pub fn into_vec(self) -> Vec<PortRange> {
+        match self {
+            PortRangeList::Single(range) => vec![range],
+            PortRangeList::Array(ranges) => ranges,
+        }
+    }
+}
+
+impl FromStr for PortRangeList {
+    type Err = ParsePortError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges = split_list(s)
+            .map(PortRange::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        match ranges.as_slice() {
+            [] => Err(ParsePortError::Empty),
+            [range] => Ok(PortRangeList::Single(*range)),
+            _ => Ok(PortRangeList::Array(ranges)),
+        }
+    }
+}
+
+impl From<PortRange> for PortRangeList {
+    fn from(range: PortRange) -> Self {
+        PortRangeList::Single(range)
+    }
+}
+
+impl From<Vec<PortRange>> for PortRangeList {
+    fn from(ranges: Vec<PortRange>) -> Self {
+        PortRangeList::Array(ranges)
+    }
+}
+
+/// 按逗号拆分，忽略空项
+fn split_list(s: &str) -> impl Iterator<Item = &str> {
+    s.split(',').map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// 解析单个端口
+fn parse_port(s: &str) -> Result<u16, ParsePortError> {
+    s.trim()
+        .parse()
+        .map_err(|_| ParsePortError::InvalidPort(s.trim().to_string()))
+}
+
+// ============================================================================
+// 解析错误类型
+// ============================================================================
+
+/// 端口解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePortError {
+    /// 空字符串
+    Empty,
+    /// 无效的端口
+    InvalidPort(String),
+    /// 起点大于终点的范围
+    InvalidRange(String),
+}
+
+impl fmt::Display for ParsePortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePortError::Empty => write!(f, "未指定端口"),
+            ParsePortError::InvalidPort(p) => write!(f, "无效的端口: {}", p),
+            ParsePortError::InvalidRange(r) => write!(f, "无效的端口范围: {}", r),
+        }
+    }
+}
+
+impl std::error::Error for ParsePortError {}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            "1000:2000".parse::<PortRange>().unwrap(),
+            PortRange::new(1000, 2000)
+        );
+        assert_eq!(
+            "1000-2000".parse::<PortRange>().unwrap(),
+            PortRange::new(1000, 2000)
+        );
+        assert_eq!(
+            ":3000".parse::<PortRange>().unwrap(),
+            PortRange::new(0, 3000)
+        );
+        assert_eq!(
+            "1000:".parse::<PortRange>().unwrap(),
+            PortRange::new(1000, 65535)
+        );
+        assert_eq!("443".parse::<PortRange>().unwrap(), PortRange::single(443));
+        assert!("2000:1000".parse::<PortRange>().is_err());
+        assert!("1000:70000".parse::<PortRange>().is_err());
+        assert!("".parse::<PortRange>().is_err());
+
+        let range = PortRange::from(20000..=20010);
+        assert_eq!(range.len(), 11);
+        assert!(range.contains(20005));
+        assert!(!range.contains(443));
+        assert_eq!(range.iter().last(), Some(20010));
+    }
+
+    #[test]
+    fn test_parse_lists() {
+        assert_eq!(
+            "80, 443,8443".parse::<PortList>().unwrap(),
+            PortList::Array(vec![80, 443, 8443])
+        );
+        assert_eq!("53".parse::<PortList>().unwrap(), PortList::Single(53));
+        assert!("80,http".parse::<PortList>().is_err());
+        assert!(" , ".parse::<PortList>().is_err());
+
+        let ranges: PortRangeList = "1000:2000,443".parse().unwrap();
+        assert!(ranges.contains(1500));
+        assert!(ranges.contains(443));
+        assert!(!ranges.contains(80));
+        assert_eq!(ranges.as_slice().len(), 2);
+    }
+
+    #[test]
+    fn test_serde() {
+        let ports: PortList = serde_json::from_str("[80, 443]").unwrap();
+        assert_eq!(ports, PortList::from([80, 443]));
+        assert_eq!(serde_json::to_string(&PortList::from(53)).unwrap(), "53");
+
+        let ranges: PortRangeList = serde_json::from_str("[\"1000:2000\", \"3000-3100\"]").unwrap();
+        assert_eq!(
+            serde_json::to_string(&ranges).unwrap(),
+            "[\"1000:2000\",\"3000:3100\"]"
+        );
+        let single: PortRangeList = serde_json::from_str("\":3000\"").unwrap();
+        assert_eq!(single, PortRangeList::Single(PortRange::new(0, 3000)));
+        assert!(serde_json::from_str::<PortRangeList>("\"x:1\"").is_err());
+    }
+}
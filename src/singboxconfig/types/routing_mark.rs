@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 /// 路由标记类型
 /// 用于设置 netfilter 路由标记（仅限 Linux）
 /// 支持整数（如 1234）和十六进制字符串（如 "0x1234"）
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum RoutingMark {
     /// 整数形式（如 1234）
@@ -24,7 +24,7 @@ use serde::{Deserialize, Serialize};
 ///     "-----END CERTIFICATE-----"
 /// ]
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum StringOrArray {
     /// 单个字符串
@@ -97,6 +97,76 @@ impl StringOrArray {
             StringOrArray::Array(arr) => arr.join(separator),
         }
     }
+
+    /// 遍历所有字符串（无论是单个还是数组）
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        match self {
+            StringOrArray::Single(s) => std::slice::from_ref(s).iter(),
+            StringOrArray::Array(arr) => arr.iter(),
+        }
+    }
+
+    /// 字符串个数
+    pub fn len(&self) -> usize {
+        match self {
+            StringOrArray::Single(_) => 1,
+            StringOrArray::Array(arr) => arr.len(),
+        }
+    }
+
+    /// 是否为空数组
+    pub fn is_empty(&self) -> bool {
+        matches!(self, StringOrArray::Array(arr) if arr.is_empty())
+    }
+
+    /// 是否包含该字符串
+    pub fn contains(&self, value: &str) -> bool {
+        self.iter().any(|s| s == value)
+    }
+
+    /// 追加一个字符串（单个字符串会转为数组）
+    pub fn push<S: Into<String>>(&mut self, value: S) {
+        match self {
+            StringOrArray::Single(s) => {
+                *self = StringOrArray::Array(vec![std::mem::take(s), value.into()]);
+            }
+            StringOrArray::Array(arr) => arr.push(value.into()),
+        }
+    }
+
+    /// 规范化：只有一个元素的数组收拢为单个字符串，序列化时输出更简洁
+    pub fn normalize(self) -> Self {
+        match self {
+            StringOrArray::Array(mut arr) if arr.len() == 1 => {
+                StringOrArray::Single(arr.pop().unwrap_or_default())
+            }
+            other => other,
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for StringOrArray {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        StringOrArray::array(iter)
+    }
+}
+
+impl IntoIterator for StringOrArray {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a StringOrArray {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl From<String> for StringOrArray {
@@ -123,6 +193,12 @@ impl From<Vec<&str>> for StringOrArray {
     }
 }
 
+impl<const N: usize> From<[&str; N]> for StringOrArray {
+    fn from(v: [&str; N]) -> Self {
+        StringOrArray::array(v)
+    }
+}
+
 impl Default for StringOrArray {
     fn default() -> Self {
         StringOrArray::Single(String::new())
@@ -142,7 +218,8 @@ mod tests {
         let s = StringOrArray::single("hello");
         assert!(s.is_single());
         assert!(!s.is_array());
-        assert_eq!(s.as_single(), Some("hello"));assert_eq!(s.as_array(), None);
+        assert_eq!(s.as_single(), Some("hello"));
+        assert_eq!(s.as_array(), None);
     }
 
     #[test]
@@ -195,6 +272,42 @@ mod tests {
         assert_eq!(s.as_single(), Some("hello"));
     }
 
+    #[test]
+    fn test_push_and_iter() {
+        let mut s = StringOrArray::from("a");
+        assert_eq!(s.len(), 1);
+        s.push("b");
+        assert!(s.is_array());
+        s.push(String::from("c"));
+        assert_eq!(
+            s.iter().map(String::as_str).collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+        assert!(s.contains("b"));
+        assert!(!s.contains("d"));
+
+        let collected: StringOrArray = ["x", "y"].into_iter().collect();
+        assert_eq!(collected, StringOrArray::from(["x", "y"]));
+        let mut count = 0;
+        for value in &collected {
+            assert!(!value.is_empty());
+            count += 1;
+        }
+        assert_eq!(count, 2);
+        assert!(StringOrArray::from(Vec::<String>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let one = StringOrArray::array(["only"]).normalize();
+        assert_eq!(one, StringOrArray::single("only"));
+        assert_eq!(serde_json::to_string(&one).unwrap(), "\"only\"");
+        let two = StringOrArray::array(["a", "b"]).normalize();
+        assert!(two.is_array());
+        let empty = StringOrArray::array(Vec::<String>::new()).normalize();
+        assert!(empty.is_empty());
+    }
+
     #[test]
     fn test_deserialize_array() {
         let s: StringOrArray = serde_json::from_str("[\"a\",\"b\"]").unwrap();
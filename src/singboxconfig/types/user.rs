@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 // ============================================================================
 
 /// VLESS 子协议流类型
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum VlessFlow {
     /// XTLS Vision 流控
@@ -18,7 +18,7 @@ pub enum VlessFlow {
 
 /// 带密码的用户
 /// 用于 AnyTLS、Trojan、Shadowsocks 等协议
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct UserWithPassword {
     /// 用户名
     pub name: String,
@@ -52,7 +52,7 @@ impl Default for UserWithPassword {
 
 /// VMess 用户
 /// VMess 协议使用 UUID 进行身份验证
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct VMessUser {
     /// 用户名
     pub name: String,
@@ -104,7 +104,7 @@ impl Default for VMessUser {
 
 /// VLESS 用户
 /// VLESS 协议使用 UUID 进行身份验证
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct VlessUser {
     /// 用户名
     pub name: String,
@@ -166,7 +166,7 @@ impl Default for VlessUser {
 
 /// TUIC 用户
 /// TUIC 协议使用 UUID 和密码进行身份验证
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct TuicUser {
     /// 用户名
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -232,7 +232,7 @@ impl Default for TuicUser {
 
 /// Shadowsocks 中继目标
 /// 用于 Shadowsocks 中继模式
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct ShadowsocksDestination {
     /// 目标名称
     pub name: String,
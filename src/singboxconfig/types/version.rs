@@ -0,0 +1,116 @@
+use std::fmt;
+use std::str::FromStr;
+
+//============================================================================
+// sing-box 版本
+// ============================================================================
+
+/// 目标 sing-box 版本（主版本.次版本）
+/// 用于决定生成配置时使用的字段格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SingBoxVersion {
+    /// 主版本号
+    pub major: u16,
+    /// 次版本号
+    pub minor: u16,
+}
+
+impl SingBoxVersion {
+    /// TUN 入站改用 `address` 字段并支持 `auto_redirect` 的版本
+    pub const TUN_ADDRESS: SingBoxVersion = SingBoxVersion::new(1, 10);
+
+    /// 引入规则动作（rule action）与端点（endpoint）的版本
+    pub const RULE_ACTIONS: SingBoxVersion = SingBoxVersion::new(1, 11);
+
+    /// 引入类型化 DNS 服务器与 AnyTLS 的版本
+    pub const TYPED_DNS_SERVERS: SingBoxVersion = SingBoxVersion::new(1, 12);
+
+    /// 创建版本
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// 是否支持 TUN 入站的 `address` / `auto_redirect` 字段
+    pub fn supports_tun_address(&self) -> bool {
+        *self >= Self::TUN_ADDRESS
+    }
+
+    /// 是否支持路由 / DNS 规则动作
+    pub fn supports_rule_actions(&self) -> bool {
+        *self >= Self::RULE_ACTIONS
+    }
+
+    /// 是否支持端点（WireGuard 等，之前为出站）
+    pub fn supports_endpoints(&self) -> bool {
+        *self >= Self::RULE_ACTIONS
+    }
+
+    /// 是否支持类型化 DNS 服务器（`"type": "https"` 等）
+    pub fn supports_typed_dns_servers(&self) -> bool {
+        *self >= Self::TYPED_DNS_SERVERS
+    }
+
+    /// 是否支持 AnyTLS 协议
+    pub fn supports_anytls(&self) -> bool {
+        *self >= Self::TYPED_DNS_SERVERS
+    }
+}
+
+impl fmt::Display for SingBoxVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for SingBoxVersion {
+    type Err = String;
+
+    /// 解析 `1.10`、`1.10.7`、`v1.11.0-beta.1` 等写法，忽略修订号
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("无效的 sing-box 版本: {}（示例: 1.10）", s);
+        let trimmed = s.trim();
+        let trimmed = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+        let mut parts = trimmed.splitn(3, '.');
+        let major = parts
+            .next()
+            .and_then(|p| p.parse::<u16>().ok())
+            .ok_or_else(invalid)?;
+        let minor = parts
+            .next()
+            .and_then(|p| p.parse::<u16>().ok())
+            .ok_or_else(invalid)?;
+        Ok(Self::new(major, minor))
+    }
+}
+
+// ============================================================================
+// 单元测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_features() {
+        let v: SingBoxVersion = "1.10".parse().unwrap();
+        assert_eq!(v, SingBoxVersion::new(1, 10));
+        assert!(!v.supports_rule_actions());
+        assert!(!v.supports_typed_dns_servers());
+        assert!(!v.supports_endpoints());
+
+        let v: SingBoxVersion = "v1.11.0-beta.1".parse().unwrap();
+        assert_eq!(v.to_string(), "1.11");
+        assert!(v.supports_rule_actions());
+        assert!(!v.supports_anytls());
+
+        assert!(
+            "1.12.4"
+                .parse::<SingBoxVersion>()
+                .unwrap()
+                .supports_anytls()
+        );
+        assert!("1".parse::<SingBoxVersion>().is_err());
+        assert!("latest".parse::<SingBoxVersion>().is_err());
+    }
+}
@@ -0,0 +1,349 @@
+//! 配置结构校验模块
+//!
+//! 不依赖 sing-box 二进制文件，对生成的配置做常见错误检查：
+//! 重复标签、重复监听端口、缺失 Reality 私钥、空 ACME 域名、空密码用户等
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+//============================================================================
+// 诊断信息
+//============================================================================
+
+/// 诊断严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 警告：配置可用但可能不符合预期
+    Warning,
+    /// 错误：sing-box 将拒绝或无法正常运行该配置
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// 单条诊断信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 严重程度
+    pub severity: Severity,
+    /// 出错位置（如 `inbounds[0].tls.acme.domain`）
+    pub path: String,
+    /// 描述
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// 创建错误诊断
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// 创建警告诊断
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// 是否为错误
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+/// 诊断列表中是否包含错误
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(Diagnostic::is_error)
+}
+
+//============================================================================
+// 校验入口
+//============================================================================
+
+/// 校验完整的 sing-box 配置文档
+pub fn validate_config(config: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let inbounds = section(config, "inbounds");
+    let outbounds = section(config, "outbounds");
+    let endpoints = section(config, "endpoints");
+
+    check_duplicate_tags("inbounds", inbounds, &mut diagnostics);
+    check_duplicate_tags("outbounds", outbounds, &mut diagnostics);
+    check_duplicate_tags("endpoints", endpoints, &mut diagnostics);
+    check_duplicate_ports(inbounds, &mut diagnostics);
+
+    for (i, inbound) in inbounds.iter().enumerate() {
+        let path = format!("inbounds[{}]", i);
+        check_inbound_tls(&path, inbound, &mut diagnostics);
+        check_inbound_users(&path, inbound, &mut diagnostics);
+    }
+
+    diagnostics
+}
+
+/// 获取配置中的数组段（不存在时返回空切片）
+fn section<'a>(config: &'a Value, key: &str) -> &'a [Value] {
+    config
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// 入站监听使用的网络
+fn inbound_network(inbound: &Value) -> &'static str {
+    match inbound.get("type").and_then(Value::as_str) {
+        Some("hysteria" | "hysteria2" | "tuic") => "udp",
+        _ => "tcp",
+    }
+}
+
+fn check_duplicate_tags(name: &str, items: &[Value], diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        let Some(tag) = item.get("tag").and_then(Value::as_str) else {
+            continue;
+        };
+        if let Some(first) = seen.insert(tag, i) {
+            diagnostics.push(Diagnostic::error(
+                format!("{}[{}].tag", name, i),
+                format!("标签 \"{}\" 与 {}[{}] 重复", tag, name, first),
+            ));
+        }
+    }
+}
+
+fn check_duplicate_ports(inbounds: &[Value], diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen: HashMap<(&str, u64), usize> = HashMap::new();
+    for (i, inbound) in inbounds.iter().enumerate() {
+        let Some(port) = inbound.get("listen_port").and_then(Value::as_u64) else {
+            continue;
+        };
+        // 端口 0 表示由系统分配，不会冲突
+        if port == 0 {
+            continue;
+        }
+        let network = inbound_network(inbound);
+        if let Some(first) = seen.insert((network, port), i) {
+            diagnostics.push(Diagnostic::error(
+                format!("inbounds[{}].listen_port", i),
+                format!("{}/{} 端口与 inbounds[{}] 重复", port, network, first),
+            ));
+        }
+    }
+}
+
+fn check_inbound_tls(path: &str, inbound: &Value, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(tls) = inbound.get("tls") else {
+        return;
+    };
+
+    if let Some(reality) = tls.get("reality") {
+        let enabled = reality
+            .get("enabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let key = reality
+            .get("private_key")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        if enabled && key.trim().is_empty() {
+            diagnostics.push(Diagnostic::error(
+                format!("{}.tls.reality.private_key", path),
+                "已启用 Reality 但缺少私钥",
+            ));
+        }
+    }
+
+    if let Some(acme) = tls.get("acme") {
+        let has_domain = acme
+            .get("domain")
+            .and_then(Value::as_array)
+            .is_some_and(|d| {
+                d.iter()
+                    .any(|v| v.as_str().is_some_and(|s| !s.trim().is_empty()))
+            });
+        if !has_domain {
+            diagnostics.push(Diagnostic::error(
+                format!("{}.tls.acme.domain", path),
+                "ACME 域名为空，证书将无法申请",
+            ));
+        }
+    }
+}
+
+fn check_inbound_users(path: &str, inbound: &Value, diagnostics: &mut Vec<Diagnostic>) {
+    let inbound_type = inbound.get("type").and_then(Value::as_str).unwrap_or("");
+    let password_required = matches!(inbound_type, "anytls" | "hysteria2" | "trojan");
+
+    let Some(users) = inbound.get("users").and_then(Value::as_array) else {
+        return;
+    };
+    if users.is_empty() {
+        diagnostics.push(Diagnostic::warning(
+            format!("{}.users", path),
+            "用户列表为空，任何客户端都无法连接",
+        ));
+    }
+
+    for (i, user) in users.iter().enumerate() {
+        let user_path = format!("{}.users[{}]", path, i);
+        match user.get("password").and_then(Value::as_str) {
+            Some(pwd) if pwd.trim().is_empty() => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}.password", user_path),
+                    "用户密码为空",
+                ));
+            }
+            None if password_required => {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}.password", user_path),
+                    "缺少用户密码",
+                ));
+            }
+            _ => {}
+        }
+        if matches!(inbound_type, "tuic" | "vless" | "vmess") {
+            let uuid = user.get("uuid").and_then(Value::as_str).unwrap_or("");
+            if uuid.trim().is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    format!("{}.uuid", user_path),
+                    "缺少用户 UUID",
+                ));
+            }
+        }
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_config() {
+        let config = json!({
+            "inbounds": [
+                {
+                    "type": "anytls",
+                    "tag": "anytls-in",
+                    "listen_port": 443,
+                    "users": [{ "name": "a", "password": "p" }],
+                    "tls": { "enabled": true, "acme": { "domain": ["example.com"] } }
+                },
+                {
+                    "type": "hysteria2",
+                    "tag": "hy2-in",
+                    "listen_port": 443,
+                    "users": [{ "name": "a", "password": "p" }],
+                    "tls": { "enabled": true }
+                }
+            ],
+            "outbounds": [{ "type": "direct", "tag": "direct" }]
+        });
+        assert!(validate_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_tags_and_ports() {
+        let config = json!({
+            "inbounds": [
+                { "type": "anytls", "tag": "in", "listen_port": 443, "users": [{ "name": "a", "password": "p" }] },
+                { "type": "vless", "tag": "in", "listen_port": 443, "users": [{ "name": "a", "uuid": "u" }] }
+            ],
+            "outbounds": [
+                { "type": "direct", "tag": "direct" },
+                { "type": "block", "tag": "direct" }
+            ]
+        });
+        let diags = validate_config(&config);
+        assert!(has_errors(&diags));
+        assert!(diags.iter().any(|d| d.path == "inbounds[1].tag"));
+        assert!(diags.iter().any(|d| d.path == "inbounds[1].listen_port"));
+        assert!(diags.iter().any(|d| d.path == "outbounds[1].tag"));
+    }
+
+    #[test]
+    fn test_reality_and_acme() {
+        let config = json!({
+            "inbounds": [
+                {
+                    "type": "vless",
+                    "tag": "vless-in",
+                    "users": [{ "name": "a", "uuid": "u" }],
+                    "tls": { "enabled": true, "reality": { "enabled": true } }
+                },
+                {
+                    "type": "tuic",
+                    "tag": "tuic-in",
+                    "users": [{ "name": "a", "uuid": "u", "password": "p" }],
+                    "tls": { "enabled": true, "acme": { "domain": [] } }
+                }
+            ]
+        });
+        let diags = validate_config(&config);
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.path == "inbounds[0].tls.reality.private_key")
+        );
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.path == "inbounds[1].tls.acme.domain")
+        );
+    }
+
+    #[test]
+    fn test_user_credentials() {
+        let config = json!({
+            "inbounds": [
+                { "type": "hysteria2", "tag": "hy2-in", "users": [{ "name": "a", "password": " " }, { "name": "b" }] },
+                { "type": "anytls", "tag": "anytls-in", "users": [] }
+            ]
+        });
+        let diags = validate_config(&config);
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.path == "inbounds[0].users[0].password" && d.is_error())
+        );
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.path == "inbounds[0].users[1].password" && d.is_error())
+        );
+        assert!(
+            diags
+                .iter()
+                .any(|d| d.path == "inbounds[1].users" && d.severity == Severity::Warning)
+        );
+    }
+}
@@ -0,0 +1,578 @@
+//! 凭证状态与定期轮换模块
+//!
+//! 设置 EZ_ROTATE_INTERVAL（或状态文件已存在，如执行过 `user add`）后，用户密码 / UUID
+//! 与 REALITY 私钥、短 ID（未通过环境变量固定时）保存在状态文件（EZ_STATE_PATH）中，
+//! 每次构建配置都复用同一份凭证；到期后重新生成并写回状态文件，被替换的旧凭证追加到轮换日志（EZ_ROTATION_LOG）。
+//! 短 ID 只在启用 EZ_ROTATE_SHORT_IDS 时随凭证轮换。
+//! 设置 EZ_STATE_ENCRYPTION_KEY 时状态文件使用 age 加密（ASCII 封装），读取时自动解密
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write as _};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::cert::{format_utc, unix_now};
+use crate::error::EzError;
+use crate::notify::{Event, notify_event};
+use crate::users::{UserSpec, merge_users};
+use crate::utils::{ensure_parent_dir, temp_sibling, write_synced};
+use ezsingbox::autoconfig::{
+    GeneratedUser, generate_password, generate_reality_keypair, generate_short_ids, generate_uuid,
+};
+
+/// 状态文件默认路径
+pub const DEFAULT_STATE_PATH: &str = "./ezsingbox-state.json";
+/// 轮换日志默认路径
+pub const DEFAULT_ROTATION_LOG: &str = "./ezsingbox-rotation.log";
+
+//============================================================================
+// 凭证状态
+//============================================================================
+
+/// 状态文件与轮换选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateOptions {
+    /// 轮换间隔（None 时不轮换，只复用状态文件中的凭证）
+    pub interval: Option<Duration>,
+    /// 状态文件路径
+    pub state_path: String,
+    /// 轮换日志路径
+    pub log_path: String,
+    /// 保存在状态文件中的 REALITY 短 ID（数量, 字节数），None 时（短 ID 已固定）不管理短 ID
+    pub short_ids: Option<(usize, usize)>,
+    /// 轮换凭证时是否同时更换短 ID
+    pub rotate_short_ids: bool,
+    /// 是否在状态文件中保存 REALITY 私钥（私钥已固定时为 false）
+    pub reality_private_key: bool,
+    /// 状态文件加密密钥（None 时明文保存）
+    pub encryption_key: Option<StateKey>,
+}
+
+impl StateOptions {
+    /// 是否使用状态文件（设置了轮换间隔或状态文件已存在）
+    pub fn enabled(&self) -> bool {
+        self.interval.is_some() || Path::new(&self.state_path).exists()
+    }
+}
+
+/// 已保存的用户凭证
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredUser {
+    /// 用户名
+    pub name: String,
+    /// 密码
+    pub password: String,
+    /// UUID
+    pub uuid: String,
+    /// 过期时间（Unix 时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl StoredUser {
+    /// 转换为构建配置使用的用户
+    pub fn to_generated(&self) -> GeneratedUser {
+        let user = GeneratedUser::with_credentials(&self.name, &self.password, &self.uuid);
+        match self.expires_at {
+            Some(t) => user.with_expires_at(t),
+            None => user,
+        }
+    }
+}
+
+/// 状态文件内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialState {
+    /// 凭证生成时间（Unix 时间戳）
+    pub rotated_at: i64,
+    /// 用户凭证
+    pub users: Vec<StoredUser>,
+    /// REALITY 短 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reality_short_ids: Vec<String>,
+    /// REALITY 私钥
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reality_private_key: Option<String>,
+    /// 通过 `user add` 添加的用户
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_users: Vec<UserSpec>,
+    /// 订阅服务访问令牌（用户名 → 令牌），不随凭证轮换
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub subscribe_tokens: BTreeMap<String, String>,
+}
+
+impl CredentialState {
+    /// 下次轮换时间（Unix 时间戳）
+    pub fn next_rotation(&self, interval: Duration) -> i64 {
+        let secs = i64::try_from(interval.as_secs()).unwrap_or(i64::MAX);
+        self.rotated_at.saturating_add(secs)
+    }
+
+    /// 按用户名查找凭证
+    pub fn user(&self, name: &str) -> Option<&StoredUser> {
+        self.users.iter().find(|u| u.name == name)
+    }
+}
+
+/// 根据上一份状态与当前用户列表计算新状态，返回新状态及是否发生了轮换
+/// 未到期时沿用已保存的凭证与 REALITY 私钥，新增用户生成新凭证，已删除的用户从状态中移除；
+/// 指定了密码的用户始终使用该密码，轮换时只更换 UUID；
+/// 短 ID 数量或长度与设置不符时重新生成
+pub fn rotate_state(
+    prev: Option<&CredentialState>,
+    users: &[UserSpec],
+    opts: &StateOptions,
+    now: i64,
+) -> Result<(CredentialState, bool), String> {
+    let due = prev
+        .zip(opts.interval)
+        .is_some_and(|(s, i)| now >= s.next_rotation(i));
+    let keep = prev.filter(|_| !due);
+    let users: Vec<StoredUser> = users
+        .iter()
+        .map(|spec| {
+            let stored = keep.and_then(|s| s.user(&spec.name));
+            StoredUser {
+                name: spec.name.clone(),
+                password: spec
+                    .password
+                    .clone()
+                    .or_else(|| stored.map(|u| u.password.clone()))
+                    .unwrap_or_else(generate_password),
+                uuid: stored.map_or_else(generate_uuid, |u| u.uuid.clone()),
+                expires_at: spec.expires_at,
+            }
+        })
+        .collect();
+    let keep_short_ids = if opts.rotate_short_ids { keep } else { prev };
+    let reality_short_ids = match (opts.short_ids, keep_short_ids) {
+        (Some((count, len)), Some(s))
+            if s.reality_short_ids.len() == count
+                && s.reality_short_ids.iter().all(|id| id.len() == len * 2) =>
+        {
+            s.reality_short_ids.clone()
+        }
+        (Some((count, len)), _) => generate_short_ids(count, len)?,
+        (None, _) => Vec::new(),
+    };
+    let reality_private_key = opts.reality_private_key.then(|| {
+        keep.and_then(|s| s.reality_private_key.clone())
+            .unwrap_or_else(|| generate_reality_keypair().private_key)
+    });
+    let subscribe_tokens = prev
+        .map(|s| s.subscribe_tokens.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(name, _)| users.iter().any(|u| &u.name == name))
+        .collect();
+    let state = CredentialState {
+        rotated_at: keep.map_or(now, |s| s.rotated_at),
+        users,
+        reality_short_ids,
+        reality_private_key,
+        added_users: prev.map(|s| s.added_users.clone()).unwrap_or_default(),
+        subscribe_tokens,
+    };
+    Ok((state, due))
+}
+
+//============================================================================
+// 状态文件加密
+//============================================================================
+
+/// age ASCII 封装的起始行
+const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// age 私钥前缀
+const AGE_SECRET_KEY_PREFIX: &str = "AGE-SECRET-KEY-";
+
+/// 状态文件加密密钥
+#[derive(Clone, PartialEq, Eq)]
+pub enum StateKey {
+    /// age 口令（scrypt）
+    Passphrase(String),
+    /// age X25519 私钥（AGE-SECRET-KEY-1...）
+    Identity(String),
+}
+
+impl fmt::Debug for StateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateKey::Passphrase(_) => f.write_str("Passphrase(<redacted>)"),
+            StateKey::Identity(_) => f.write_str("Identity(<redacted>)"),
+        }
+    }
+}
+
+impl StateKey {
+    /// 解析密钥：包含 `AGE-SECRET-KEY-` 行时（如 age-keygen 生成的密钥文件）使用该私钥，否则整体作为口令
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        if let Some(key) = raw
+            .lines()
+            .map(str::trim)
+            .find(|l| l.starts_with(AGE_SECRET_KEY_PREFIX))
+        {
+            return Ok(StateKey::Identity(key.to_string()));
+        }
+        let passphrase = raw.trim();
+        if passphrase.is_empty() {
+            return Err("状态文件加密口令不能为空".to_string());
+        }
+        Ok(StateKey::Passphrase(passphrase.to_string()))
+    }
+
+    /// 加密为 age ASCII 封装文本
+    #[cfg(feature = "encryption")]
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<String, String> {
+        use age::secrecy::SecretString;
+        let result = match self {
+            StateKey::Passphrase(p) => age::encrypt_and_armor(
+                &age::scrypt::Recipient::new(SecretString::from(p.clone())),
+                plaintext,
+            ),
+            StateKey::Identity(k) => {
+                age::encrypt_and_armor(&parse_identity(k)?.to_public(), plaintext)
+            }
+        };
+        result.map_err(|e| format!("加密状态文件失败: {}", e))
+    }
+
+    /// 解密 age 文件（二进制或 ASCII 封装）
+    #[cfg(feature = "encryption")]
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        use age::secrecy::SecretString;
+        let result = match self {
+            StateKey::Passphrase(p) => age::decrypt(
+                &age::scrypt::Identity::new(SecretString::from(p.clone())),
+                ciphertext,
+            ),
+            StateKey::Identity(k) => age::decrypt(&parse_identity(k)?, ciphertext),
+        };
+        result.map_err(|e| format!("解密状态文件失败（密钥是否正确？）: {}", e))
+    }
+
+    /// 加密为 age ASCII 封装文本（未启用 encryption 特性）
+    #[cfg(not(feature = "encryption"))]
+    pub fn encrypt(&self, _plaintext: &[u8]) -> Result<String, String> {
+        Err(ENCRYPTION_DISABLED.to_string())
+    }
+
+    /// 解密 age 文件（未启用 encryption 特性）
+    #[cfg(not(feature = "encryption"))]
+    pub fn decrypt(&self, _ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        Err(ENCRYPTION_DISABLED.to_string())
+    }
+}
+
+/// 未启用 encryption 特性时的提示
+#[cfg(not(feature = "encryption"))]
+const ENCRYPTION_DISABLED: &str = "编译时未启用 encryption 特性，无法加密或解密状态文件";
+
+/// 解析 age X25519 私钥
+#[cfg(feature = "encryption")]
+fn parse_identity(key: &str) -> Result<age::x25519::Identity, String> {
+    key.parse().map_err(|e| format!("无效的 age 私钥: {}", e))
+}
+
+/// 判断文件内容是否为 age 加密文件
+fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(b"age-encryption.org/") || data.starts_with(AGE_ARMOR_HEADER.as_bytes())
+}
+
+//============================================================================
+// 文件读写
+//============================================================================
+
+/// 读取状态文件（不存在时返回 None）
+/// 加密的状态文件用 key 解密；明文状态文件直接读取，下次写入时按 key 加密
+pub fn load_state(path: &str, key: Option<&StateKey>) -> io::Result<Option<CredentialState>> {
+    let raw = match std::fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+    let raw = match (is_encrypted(&raw), key) {
+        (true, Some(key)) => key.decrypt(&raw).map_err(invalid)?,
+        (true, None) => {
+            return Err(invalid(
+                "状态文件已加密，需要设置 EZ_STATE_ENCRYPTION_KEY".to_string(),
+            ));
+        }
+        (false, _) => raw,
+    };
+    serde_json::from_slice(&raw)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// 原子写入状态文件（指定 key 时加密），Unix 上权限设为 0600
+pub fn save_state(path: &str, state: &CredentialState, key: Option<&StateKey>) -> io::Result<()> {
+    ensure_parent_dir(path)?;
+    let mut json = serde_json::to_string_pretty(state).map_err(io::Error::other)?;
+    if let Some(key) = key {
+        json = key.encrypt(json.as_bytes()).map_err(io::Error::other)?;
+    }
+    let tmp = temp_sibling(path);
+    let write = || -> io::Result<()> {
+        write_synced(&tmp, json.as_bytes(), path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&tmp, path)
+    };
+    write().inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// 将被替换的凭证追加到轮换日志（每次轮换一行 JSON），Unix 上权限设为 0600
+pub fn append_rotation_log(path: &str, old: &CredentialState, now: i64) -> io::Result<()> {
+    ensure_parent_dir(path)?;
+    let entry = serde_json::json!({
+        "retired_at": format_utc(now),
+        "issued_at": format_utc(old.rotated_at),
+        "users": old.users,
+        "reality_short_ids": old.reality_short_ids,
+        "reality_private_key": old.reality_private_key,
+    });
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", entry)?;
+    file.sync_all()
+}
+
+/// 读取状态文件并按需轮换：到期时先记录旧凭证再写回新状态
+/// users 为环境变量中的用户，与状态文件中 `user add` 添加的用户合并；
+/// add 为本次新增（或更新过期时间）的用户
+pub fn load_rotated_state(
+    opts: &StateOptions,
+    users: Vec<UserSpec>,
+    add: Option<UserSpec>,
+) -> Result<CredentialState, EzError> {
+    let now = unix_now();
+    let key = opts.encryption_key.as_ref();
+    let prev = load_state(&opts.state_path, key).map_err(EzError::io(&opts.state_path))?;
+    let mut added = prev
+        .as_ref()
+        .map(|s| s.added_users.clone())
+        .unwrap_or_default();
+    if let Some(spec) = add {
+        added.retain(|u| u.name != spec.name);
+        added.push(spec);
+    }
+    let users = merge_users(users, &added);
+    let (mut state, rotated) =
+        rotate_state(prev.as_ref(), &users, opts, now).map_err(EzError::Env)?;
+    state.added_users = added;
+    if let (Some(old), Some(interval)) = (prev.as_ref().filter(|_| rotated), opts.interval) {
+        append_rotation_log(&opts.log_path, old, now).map_err(EzError::io(&opts.log_path))?;
+        info!(
+            users = state.users.len(),
+            reality_key = old.reality_private_key != state.reality_private_key,
+            short_ids = old.reality_short_ids != state.reality_short_ids,
+            log = %opts.log_path,
+            next = %format_utc(state.next_rotation(interval)),
+            "凭证已轮换"
+        );
+        let users: Vec<&str> = state.users.iter().map(|u| u.name.as_str()).collect();
+        notify_event(
+            Event::CredentialsRotated,
+            None,
+            serde_json::json!({
+                "users": users,
+                "next_rotation": state.next_rotation(interval),
+            }),
+        );
+    }
+    // 已有明文状态文件且设置了密钥时重新写入，转为加密保存
+    let migrate =
+        key.is_some() && std::fs::read(&opts.state_path).is_ok_and(|raw| !is_encrypted(&raw));
+    if prev.as_ref() != Some(&state) || migrate {
+        save_state(&opts.state_path, &state, key).map_err(EzError::io(&opts.state_path))?;
+    }
+    Ok(state)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(short_ids: Option<(usize, usize)>) -> StateOptions {
+        StateOptions {
+            interval: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            state_path: DEFAULT_STATE_PATH.to_string(),
+            log_path: DEFAULT_ROTATION_LOG.to_string(),
+            short_ids,
+            rotate_short_ids: true,
+            reality_private_key: true,
+            encryption_key: None,
+        }
+    }
+
+    #[test]
+    fn test_rotate_state() {
+        let rotation = opts(Some((2, 4)));
+        let users = vec![
+            UserSpec::new("alice", None),
+            UserSpec::new("bob", Some("fixed".to_string())),
+        ];
+        let (mut first, rotated) = rotate_state(None, &users, &rotation, 1000).unwrap();
+        assert!(!rotated);
+        assert_eq!(first.rotated_at, 1000);
+        assert_eq!(first.user("bob").unwrap().password, "fixed");
+        assert_eq!(first.reality_short_ids.len(), 2);
+        for name in ["alice", "bob"] {
+            first
+                .subscribe_tokens
+                .insert(name.to_string(), format!("token-{}", name));
+        }
+
+        // 未到期：沿用凭证，新增用户生成凭证，删除的用户移除
+        let users2 = vec![UserSpec::new("alice", None), UserSpec::new("carol", None)];
+        let (kept, rotated) = rotate_state(Some(&first), &users2, &rotation, 2000).unwrap();
+        assert!(!rotated);
+        assert_eq!(kept.rotated_at, 1000);
+        assert_eq!(kept.user("alice"), first.user("alice"));
+        assert!(kept.user("carol").is_some());
+        assert!(kept.user("bob").is_none());
+        assert_eq!(
+            kept.subscribe_tokens.keys().collect::<Vec<_>>(),
+            vec!["alice"]
+        );
+        assert_eq!(kept.reality_short_ids, first.reality_short_ids);
+        assert!(first.reality_private_key.is_some());
+        assert_eq!(kept.reality_private_key, first.reality_private_key);
+
+        // 到期：全部更换，指定的密码保持不变
+        let now = first.next_rotation(rotation.interval.unwrap());
+        let (next, rotated) = rotate_state(Some(&first), &users, &rotation, now).unwrap();
+        assert!(rotated);
+        assert_eq!(next.rotated_at, now);
+        assert_eq!(next.subscribe_tokens, first.subscribe_tokens);
+        let (old, new) = (first.user("alice").unwrap(), next.user("alice").unwrap());
+        assert_ne!(old.password, new.password);
+        assert_ne!(old.uuid, new.uuid);
+        assert_eq!(next.user("bob").unwrap().password, "fixed");
+        assert_ne!(
+            next.user("bob").unwrap().uuid,
+            first.user("bob").unwrap().uuid
+        );
+        assert_ne!(next.reality_short_ids, first.reality_short_ids);
+        assert_ne!(next.reality_private_key, first.reality_private_key);
+
+        // 不轮换短 ID：到期后仍沿用，数量改变时重新生成
+        let fixed_ids = StateOptions {
+            rotate_short_ids: false,
+            ..rotation.clone()
+        };
+        let (next, _) = rotate_state(Some(&first), &users, &fixed_ids, now).unwrap();
+        assert_eq!(next.reality_short_ids, first.reality_short_ids);
+        let more_ids = StateOptions {
+            short_ids: Some((3, 4)),
+            ..fixed_ids
+        };
+        let (next, _) = rotate_state(Some(&first), &users, &more_ids, 2000).unwrap();
+        assert_eq!(next.reality_short_ids.len(), 3);
+
+        let (plain, _) = rotate_state(None, &users, &opts(None), 0).unwrap();
+        assert!(plain.reality_short_ids.is_empty());
+
+        // 私钥已固定时不保存
+        let fixed_key = StateOptions {
+            reality_private_key: false,
+            ..opts(None)
+        };
+        let (unkeyed, _) = rotate_state(Some(&first), &users, &fixed_key, 2000).unwrap();
+        assert_eq!(unkeyed.reality_private_key, None);
+
+        // 不设置轮换间隔时永不到期
+        let never = StateOptions {
+            interval: None,
+            ..opts(None)
+        };
+        let (same, rotated) = rotate_state(Some(&plain), &users, &never, i64::MAX).unwrap();
+        assert!(!rotated);
+        assert_eq!(same.users, plain.users);
+    }
+
+    #[test]
+    fn test_state_files() {
+        let dir = std::env::temp_dir().join(format!("ezsingbox-state-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("state.json").to_string_lossy().into_owned();
+        let log = dir.join("rotation.log").to_string_lossy().into_owned();
+        assert_eq!(load_state(&path, None).unwrap(), None);
+
+        let (state, _) =
+            rotate_state(None, &[UserSpec::new("alice", None)], &opts(None), 1000).unwrap();
+        save_state(&path, &state, None).unwrap();
+        assert_eq!(load_state(&path, None).unwrap(), Some(state.clone()));
+
+        append_rotation_log(&log, &state, 2000).unwrap();
+        append_rotation_log(&log, &state, 3000).unwrap();
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["users"][0]["uuid"], state.users[0].uuid);
+        assert_eq!(lines[1]["retired_at"], format_utc(3000));
+        assert_eq!(
+            lines[0]["reality_private_key"].as_str(),
+            state.reality_private_key.as_deref()
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_state(&path, None).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_state() {
+        let dir = std::env::temp_dir().join(format!("ezsingbox-state-enc-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("state.json").to_string_lossy().into_owned();
+        let (state, _) =
+            rotate_state(None, &[UserSpec::new("alice", None)], &opts(None), 1000).unwrap();
+
+        let identity = age::x25519::Identity::generate();
+        let key_file = format!(
+            "# created: 2025-01-01T00:00:00Z\n{}\n",
+            age::secrecy::ExposeSecret::expose_secret(&identity.to_string())
+        );
+        let key = StateKey::parse(&key_file).unwrap();
+        assert!(matches!(key, StateKey::Identity(_)));
+        save_state(&path, &state, Some(&key)).unwrap();
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.starts_with(AGE_ARMOR_HEADER));
+        assert!(!raw.contains(&state.users[0].password));
+        assert_eq!(load_state(&path, Some(&key)).unwrap(), Some(state.clone()));
+        assert!(load_state(&path, None).is_err());
+
+        let passphrase = StateKey::parse("correct horse battery staple").unwrap();
+        assert!(load_state(&path, Some(&passphrase)).is_err());
+        let sealed = passphrase.encrypt(b"{}").unwrap();
+        assert_eq!(passphrase.decrypt(sealed.as_bytes()).unwrap(), b"{}");
+        assert!(StateKey::parse("  \n").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,462 @@
+//! 流量统计模块
+//!
+//! 启用 Clash API（EZ_CLASH_API）时，run 模式按 EZ_STATS_INTERVAL 轮询 sing-box 的 `/connections`，
+//! 按入站与用户累计上传 / 下载流量并写入统计文件（EZ_STATS_PATH），
+//! 供 `ezsingbox stats` 与 `/metrics`（EZ_METRICS_LISTEN）读取
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::cert::{format_utc, unix_now};
+use crate::utils::{ensure_parent_dir, temp_sibling, write_synced};
+
+/// 默认统计文件路径
+pub const DEFAULT_STATS_PATH: &str = "./ezsingbox-stats.json";
+
+/// 默认轮询间隔
+pub const DEFAULT_STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+//============================================================================
+// 流量计数
+//============================================================================
+
+/// 上传 / 下载字节数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Traffic {
+    /// 上传（客户端发出）
+    pub upload: u64,
+    /// 下载（客户端收到）
+    pub download: u64,
+}
+
+impl Traffic {
+    fn add(&mut self, other: Traffic) {
+        self.upload = self.upload.saturating_add(other.upload);
+        self.download = self.download.saturating_add(other.download);
+    }
+}
+
+/// 累计流量统计（写入统计文件）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficStats {
+    /// 开始统计的时间（Unix 时间戳）
+    pub since: i64,
+    /// 最近一次更新的时间
+    pub updated_at: i64,
+    /// 按入站标签统计
+    #[serde(default)]
+    pub inbounds: BTreeMap<String, Traffic>,
+    /// 按用户统计（sing-box 报告了认证用户的连接）
+    #[serde(default)]
+    pub users: BTreeMap<String, Traffic>,
+}
+
+/// 解析连接的 `metadata.type`，返回入站标签与用户
+/// sing-box 的取值形如 `hysteria2/hy2-in`，连接带认证用户时为 `hysteria2/hy2-in [alice]`
+pub fn parse_connection_type(raw: &str) -> (String, Option<String>) {
+    let (inbound, user) = match raw.strip_suffix(']').and_then(|s| s.rsplit_once(" [")) {
+        Some((inbound, user)) => (inbound, Some(user)),
+        None => (raw, None),
+    };
+    let tag = inbound.split_once('/').map_or(inbound, |(_, tag)| tag);
+    (
+        tag.to_string(),
+        user.filter(|u| !u.is_empty()).map(str::to_string),
+    )
+}
+
+/// 流量累加器：Clash API 只报告活动连接的累计字节数，按连接 ID 记录上次的值以计算增量
+#[derive(Debug, Default)]
+pub struct TrafficAccumulator {
+    stats: TrafficStats,
+    seen: HashMap<String, Traffic>,
+}
+
+impl TrafficAccumulator {
+    /// 在已有统计的基础上继续累加
+    pub fn new(stats: TrafficStats) -> Self {
+        Self {
+            stats,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// 当前统计
+    pub fn stats(&self) -> &TrafficStats {
+        &self.stats
+    }
+
+    /// 合并一次 `/connections` 响应
+    /// 两次轮询之间结束的连接，最后一段流量不计入
+    pub fn ingest(&mut self, response: &Value, now: i64) {
+        let mut seen = HashMap::new();
+        for conn in response["connections"].as_array().into_iter().flatten() {
+            let Some(id) = conn["id"].as_str() else {
+                continue;
+            };
+            let current = Traffic {
+                upload: conn["upload"].as_u64().unwrap_or(0),
+                download: conn["download"].as_u64().unwrap_or(0),
+            };
+            let prev = self.seen.get(id).copied().unwrap_or_default();
+            // 计数变小说明 sing-box 已重启、连接 ID 被复用，按新连接计算
+            let delta = if current.upload < prev.upload || current.download < prev.download {
+                current
+            } else {
+                Traffic {
+                    upload: current.upload - prev.upload,
+                    download: current.download - prev.download,
+                }
+            };
+            let (inbound, user) =
+                parse_connection_type(conn["metadata"]["type"].as_str().unwrap_or(""));
+            if !inbound.is_empty() {
+                self.stats.inbounds.entry(inbound).or_default().add(delta);
+            }
+            if let Some(user) = user {
+                self.stats.users.entry(user).or_default().add(delta);
+            }
+            seen.insert(id.to_string(), current);
+        }
+        self.seen = seen;
+        self.stats.updated_at = now;
+    }
+}
+
+//============================================================================
+// Clash API
+//============================================================================
+
+/// 获取 sing-box Clash API 的 `/connections`
+pub fn fetch_connections(
+    api: SocketAddr,
+    secret: Option<&str>,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let mut stream = TcpStream::connect_timeout(&api, timeout)
+        .map_err(|e| format!("无法连接 Clash API {}: {}", api, e))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| e.to_string())?;
+    let auth = secret
+        .map(|s| format!("Authorization: Bearer {}\r\n", s))
+        .unwrap_or_default();
+    // HTTP/1.0 请求，响应不使用分块编码
+    let request = format!("GET /connections HTTP/1.0\r\nHost: {api}\r\n{auth}\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("请求 Clash API 失败: {}", e))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| format!("读取 Clash API 响应失败: {}", e))?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| "Clash API 响应无效".to_string())?;
+    let status = head.lines().next().unwrap_or("");
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Clash API 返回 {}", status));
+    }
+    serde_json::from_str(body).map_err(|e| format!("解析 Clash API 响应失败: {}", e))
+}
+
+//============================================================================
+// 文件读写
+//============================================================================
+
+/// 读取统计文件（不存在时返回 None）
+pub fn load_stats(path: &str) -> io::Result<Option<TrafficStats>> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// 原子写入统计文件
+pub fn save_stats(path: &str, stats: &TrafficStats) -> io::Result<()> {
+    ensure_parent_dir(path)?;
+    let json = serde_json::to_string_pretty(stats).map_err(io::Error::other)?;
+    let tmp = temp_sibling(path);
+    write_synced(&tmp, json.as_bytes(), path)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp);
+        })
+}
+
+//============================================================================
+// 定期轮询
+//============================================================================
+
+/// 流量统计选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsOptions {
+    /// Clash API 监听地址
+    pub api: SocketAddr,
+    /// Clash API 密钥
+    pub secret: Option<String>,
+    /// 轮询间隔
+    pub interval: Duration,
+    /// 统计文件路径
+    pub path: String,
+    /// `/metrics` 监听地址
+    pub metrics: Option<SocketAddr>,
+}
+
+/// 流量轮询器，由 run 模式的主循环定期调用
+pub struct TrafficPoller {
+    opts: StatsOptions,
+    next_poll: Instant,
+    accumulator: TrafficAccumulator,
+}
+
+impl TrafficPoller {
+    /// 创建轮询器（读取已有统计继续累加；首次轮询在一个间隔后，等待 sing-box 启动）
+    pub fn new(opts: StatsOptions) -> Self {
+        let stats = match load_stats(&opts.path) {
+            Ok(Some(stats)) => stats,
+            Ok(None) => TrafficStats {
+                since: unix_now(),
+                ..Default::default()
+            },
+            Err(e) => {
+                warn!(path = %opts.path, "读取流量统计失败，重新开始统计: {}", e);
+                TrafficStats {
+                    since: unix_now(),
+                    ..Default::default()
+                }
+            }
+        };
+        Self {
+            next_poll: Instant::now() + opts.interval,
+            opts,
+            accumulator: TrafficAccumulator::new(stats),
+        }
+    }
+
+    /// 到期时轮询 Clash API 并写入统计文件
+    pub fn poll(&mut self) {
+        let now = Instant::now();
+        if now < self.next_poll {
+            return;
+        }
+        self.next_poll = now + self.opts.interval;
+        let response = match fetch_connections(
+            self.opts.api,
+            self.opts.secret.as_deref(),
+            Duration::from_secs(5),
+        ) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("获取流量统计失败: {}", e);
+                return;
+            }
+        };
+        self.accumulator.ingest(&response, unix_now());
+        if let Err(e) = save_stats(&self.opts.path, self.accumulator.stats()) {
+            warn!(path = %self.opts.path, "写入流量统计失败: {}", e);
+        }
+    }
+}
+
+//============================================================================
+// 输出
+//============================================================================
+
+/// 以 1024 为进制格式化字节数
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.2} {}", value, UNITS[unit])
+    }
+}
+
+/// 渲染流量统计表
+pub fn render_stats(stats: &TrafficStats) -> String {
+    let mut out = format!(
+        "流量统计（{} 起，更新于 {}）\n",
+        format_utc(stats.since),
+        format_utc(stats.updated_at)
+    );
+    for (title, rows) in [("入站", &stats.inbounds), ("用户", &stats.users)] {
+        out.push_str(&format!("\n{}:\n", title));
+        if rows.is_empty() {
+            out.push_str("  （无）\n");
+        }
+        for (name, t) in rows {
+            out.push_str(&format!(
+                "  {:<20} 上传 {:>12}  下载 {:>12}  合计 {:>12}\n",
+                name,
+                format_bytes(t.upload),
+                format_bytes(t.download),
+                format_bytes(t.upload.saturating_add(t.download))
+            ));
+        }
+    }
+    out
+}
+
+/// Prometheus 标签值转义
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 渲染 Prometheus 文本格式的指标
+pub fn render_metrics(stats: &TrafficStats) -> String {
+    let mut out = String::new();
+    for (metric, label, rows) in [
+        (
+            "ezsingbox_inbound_traffic_bytes_total",
+            "inbound",
+            &stats.inbounds,
+        ),
+        ("ezsingbox_user_traffic_bytes_total", "user", &stats.users),
+    ] {
+        out.push_str(&format!("# TYPE {} counter\n", metric));
+        for (name, t) in rows {
+            for (direction, bytes) in [("upload", t.upload), ("download", t.download)] {
+                out.push_str(&format!(
+                    "{}{{{}=\"{}\",direction=\"{}\"}} {}\n",
+                    metric,
+                    label,
+                    escape_label(name),
+                    direction,
+                    bytes
+                ));
+            }
+        }
+    }
+    out.push_str("# TYPE ezsingbox_stats_updated_timestamp_seconds gauge\n");
+    out.push_str(&format!(
+        "ezsingbox_stats_updated_timestamp_seconds {}\n",
+        stats.updated_at
+    ));
+    out
+}
+
+/// 在后台线程启动 `/metrics` 服务，每次请求读取统计文件
+pub fn spawn_metrics_server(listen: SocketAddr, path: String) -> Result<(), String> {
+    use tiny_http::{Header, Response, Server};
+
+    let server = Server::http(listen).map_err(|e| format!("无法监听 {}: {}", listen, e))?;
+    info!(listen = %listen, "指标服务已启动: http://{}/metrics", listen);
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let url = request.url().split('?').next().unwrap_or("");
+            let response = if url != "/metrics" {
+                Response::from_string("Not Found").with_status_code(404)
+            } else {
+                match load_stats(&path) {
+                    Ok(stats) => Response::from_string(render_metrics(&stats.unwrap_or_default()))
+                        .with_header(
+                            Header::from_bytes(
+                                &b"Content-Type"[..],
+                                &b"text/plain; version=0.0.4; charset=utf-8"[..],
+                            )
+                            .unwrap(),
+                        ),
+                    Err(e) => {
+                        warn!(path = %path, "读取流量统计失败: {}", e);
+                        Response::from_string("Internal Server Error").with_status_code(500)
+                    }
+                }
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(id: &str, kind: &str, upload: u64, download: u64) -> Value {
+        serde_json::json!({
+            "id": id,
+            "metadata": { "network": "tcp", "type": kind },
+            "upload": upload,
+            "download": download
+        })
+    }
+
+    #[test]
+    fn test_parse_connection_type() {
+        assert_eq!(
+            parse_connection_type("hysteria2/hy2-in"),
+            ("hy2-in".to_string(), None)
+        );
+        assert_eq!(
+            parse_connection_type("vless/vless-reality-in [alice]"),
+            ("vless-reality-in".to_string(), Some("alice".to_string()))
+        );
+        assert_eq!(parse_connection_type("tun"), ("tun".to_string(), None));
+    }
+
+    #[test]
+    fn test_accumulate_traffic() {
+        let mut acc = TrafficAccumulator::default();
+        acc.ingest(
+            &serde_json::json!({ "connections": [
+                connection("a", "hysteria2/hy2-in [alice]", 100, 1000),
+                connection("b", "tuic/tuic-in", 10, 20),
+            ]}),
+            1,
+        );
+        // a 继续传输，b 已结束，c 为新连接
+        acc.ingest(
+            &serde_json::json!({ "connections": [
+                connection("a", "hysteria2/hy2-in [alice]", 150, 3000),
+                connection("c", "hysteria2/hy2-in [bob]", 5, 5),
+            ]}),
+            2,
+        );
+        let stats = acc.stats();
+        assert_eq!(stats.updated_at, 2);
+        assert_eq!(
+            stats.inbounds["hy2-in"],
+            Traffic {
+                upload: 155,
+                download: 3005
+            }
+        );
+        assert_eq!(stats.inbounds["tuic-in"].download, 20);
+        assert_eq!(stats.users["alice"].upload, 150);
+        assert_eq!(stats.users["bob"].download, 5);
+
+        let metrics = render_metrics(stats);
+        assert!(metrics.contains(
+            "ezsingbox_user_traffic_bytes_total{user=\"alice\",direction=\"download\"} 3000\n"
+        ));
+        assert!(render_stats(stats).contains("alice"));
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.50 MiB");
+    }
+}
@@ -0,0 +1,127 @@
+//! 订阅服务认证
+//!
+//! 除 Basic 认证外，订阅服务接受按用户签发的访问令牌：`Authorization: Bearer <令牌>` 请求头，
+//! 或供无法设置请求头的客户端使用的 `?token=<令牌>` 查询参数。令牌由 `ezsingbox user token` 签发并保存在状态文件中，
+//! 通过令牌认证的请求固定为令牌所属的用户；凭据比较均为常数时间
+
+use std::collections::BTreeMap;
+
+use crate::signedlink::LinkSigner;
+use ezsingbox::autoconfig::generate_random_bytes;
+
+/// 访问令牌的随机字节数
+const TOKEN_BYTES: usize = 24;
+
+/// 订阅请求的认证方式
+#[derive(Debug, Clone, Copy)]
+pub struct SubscriptionAuth<'a> {
+    /// 期望的 Basic 认证头（None 时不接受 Basic 认证）
+    pub basic: Option<&'a str>,
+    /// 各用户的访问令牌（用户名 → 令牌）
+    pub tokens: &'a BTreeMap<String, String>,
+    /// 签名链接校验器（None 时不接受 `/sub/<用户>` 签名链接）
+    pub signer: Option<&'a LinkSigner>,
+}
+
+/// 认证失败
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unauthorized;
+
+impl SubscriptionAuth<'_> {
+    /// 是否要求认证（设置了 Basic 认证或签发过令牌）
+    pub fn required(&self) -> bool {
+        self.basic.is_some() || !self.tokens.is_empty()
+    }
+
+    /// 校验 Authorization 请求头与 `?token=` 参数
+    /// 令牌认证成功时返回令牌所属的用户；Basic 认证成功或未要求认证时返回 None
+    pub fn check(
+        &self,
+        authorization: Option<&str>,
+        query_token: Option<&str>,
+    ) -> Result<Option<String>, Unauthorized> {
+        if !self.required() {
+            return Ok(None);
+        }
+        let bearer = authorization
+            .and_then(|h| h.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+            .map(|(_, token)| token.trim());
+        if let Some(token) = bearer.or(query_token) {
+            return self.token_user(token).map(Some).ok_or(Unauthorized);
+        }
+        match (self.basic, authorization) {
+            (Some(expected), Some(provided)) if constant_time_eq(expected, provided) => Ok(None),
+            _ => Err(Unauthorized),
+        }
+    }
+
+    /// 查找令牌所属的用户
+    fn token_user(&self, token: &str) -> Option<String> {
+        self.tokens
+            .iter()
+            .find(|(_, t)| constant_time_eq(t, token))
+            .map(|(user, _)| user.clone())
+    }
+}
+
+/// 生成访问令牌（十六进制）
+pub fn generate_token() -> String {
+    generate_random_bytes(TOKEN_BYTES)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 常数时间比较（长度不同时直接返回 false）
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_auth() {
+        let tokens = BTreeMap::from([("alice".to_string(), generate_token())]);
+        let token = tokens["alice"].clone();
+        assert_eq!(token.len(), TOKEN_BYTES * 2);
+        let auth = SubscriptionAuth {
+            basic: Some("Basic YWRtaW46cGFzcw=="),
+            tokens: &tokens,
+            signer: None,
+        };
+        assert!(auth.required());
+        let bearer = format!("Bearer {}", token);
+        assert_eq!(
+            auth.check(Some(&bearer), None),
+            Ok(Some("alice".to_string()))
+        );
+        assert_eq!(
+            auth.check(None, Some(&token)),
+            Ok(Some("alice".to_string()))
+        );
+        assert_eq!(auth.check(Some("Basic YWRtaW46cGFzcw=="), None), Ok(None));
+        assert_eq!(auth.check(Some("Bearer wrong"), None), Err(Unauthorized));
+        assert_eq!(auth.check(None, Some("wrong")), Err(Unauthorized));
+        assert_eq!(auth.check(Some("Basic d3Jvbmc="), None), Err(Unauthorized));
+        assert_eq!(auth.check(None, None), Err(Unauthorized));
+
+        // 未设置 Basic 认证也未签发令牌时不要求认证
+        let empty = BTreeMap::new();
+        let open = SubscriptionAuth {
+            basic: None,
+            tokens: &empty,
+            signer: None,
+        };
+        assert!(!open.required());
+        assert_eq!(open.check(None, None), Ok(None));
+        assert_eq!(open.check(None, Some("x")), Ok(None));
+    }
+}
@@ -0,0 +1,153 @@
+//! generate 结果摘要
+//!
+//! `EZ_OUTPUT=json` 时 generate 不打印中文详情，而是向标准输出写入一个 JSON 文档，
+//! 包含公网 IP、域名、各协议端口与凭据、Reality 密钥、分享链接与写入的文件，
+//! 便于部署脚本直接解析（日志仍输出到标准错误）
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Value, json};
+
+use crate::config::{protocol_port, protocol_tag};
+use crate::env::env_string;
+use crate::template::template_context;
+use ezsingbox::autoconfig::{MultiProtocolResult, Protocol};
+
+/// generate 输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// 中文控制台文本
+    #[default]
+    Text,
+    /// 单个 JSON 文档
+    Json,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("无效的输出格式: {}（可选 text/json）", s)),
+        }
+    }
+}
+
+/// 从环境变量读取输出格式（EZ_OUTPUT，无效取值时回退为文本）
+pub fn output_format_from_env() -> OutputFormat {
+    env_string("EZ_OUTPUT")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default()
+}
+
+/// 写入的文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrittenFile {
+    /// 文件类型（server / client）
+    pub kind: &'static str,
+    /// 文件路径
+    pub path: String,
+}
+
+impl WrittenFile {
+    /// 创建写入记录
+    pub fn new(kind: &'static str, path: impl Into<String>) -> Self {
+        Self {
+            kind,
+            path: path.into(),
+        }
+    }
+}
+
+/// 构建 generate 结果摘要
+///
+/// ```text
+/// { public_ip, domain, dry_run,
+///   inbounds: [{ protocol, tag, port }],
+///   reality: { public_key, private_key, short_ids, handshake_server, handshake_port } | null,
+///   proxies: [...],   // 同模板上下文
+///   files: [{ kind, path }] }
+/// ```
+pub fn generate_summary(
+    result: &MultiProtocolResult,
+    files: &[WrittenFile],
+    dry_run: bool,
+) -> Result<Value, String> {
+    let mut summary = template_context(result, None)?;
+    let inbounds: Vec<Value> = Protocol::ALL
+        .into_iter()
+        .filter_map(|p| {
+            Some(json!({
+                "protocol": p.as_str(),
+                "tag": protocol_tag(result, p)?,
+                "port": protocol_port(result, p)?,
+            }))
+        })
+        .collect();
+    let reality = result.vless_reality.as_ref().map(|r| {
+        json!({
+            "public_key": r.public_key,
+            "private_key": r.private_key,
+            "short_ids": r.short_ids,
+            "handshake_server": r.handshake_server,
+            "handshake_port": r.handshake_port,
+        })
+    });
+    let files: Vec<Value> = files
+        .iter()
+        .map(|f| json!({ "kind": f.kind, "path": f.path }))
+        .collect();
+    summary["dry_run"] = json!(dry_run);
+    summary["inbounds"] = json!(inbounds);
+    summary["reality"] = json!(reality);
+    summary["files"] = json!(files);
+    Ok(summary)
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezsingbox::autoconfig::MultiProtocolBuilder;
+
+    #[test]
+    fn test_generate_summary() {
+        assert_eq!("JSON".parse(), Ok(OutputFormat::Json));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+
+        let result = MultiProtocolBuilder::new()
+            .public_ip("1.2.3.4".parse().unwrap())
+            .domain("example.com")
+            .enable_anytls(443)
+            .enable_vless_reality(2096)
+            .build()
+            .unwrap();
+        let files = [WrittenFile::new("server", "/etc/sing-box/config.json")];
+        let summary = generate_summary(&result, &files, false).unwrap();
+        assert_eq!(summary["public_ip"], "1.2.3.4");
+        assert_eq!(summary["domain"], "example.com");
+        assert_eq!(summary["inbounds"][0]["port"], 443);
+        assert_eq!(summary["inbounds"][1]["protocol"], "vless-reality");
+        let reality = result.vless_reality.as_ref().unwrap();
+        assert_eq!(summary["reality"]["public_key"], reality.public_key);
+        assert_eq!(summary["proxies"].as_array().unwrap().len(), 2);
+        assert!(summary["proxies"][0]["link"].is_string());
+        assert_eq!(summary["files"][0]["kind"], "server");
+        assert_eq!(summary["dry_run"], false);
+    }
+}
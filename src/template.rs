@@ -0,0 +1,117 @@
+//! 自定义模板模块
+//!
+//! 使用 Handlebars 模板渲染内置格式未覆盖的客户端配置：模板可以读取服务器信息，
+//! 以及每个协议、每个用户的连接参数（地址、端口、凭据、密钥、分享链接和完整的 sing-box 出站）
+
+use handlebars::{Handlebars, handlebars_helper, no_escape};
+use serde_json::{Value, json};
+
+use crate::config::{build_proxy_outbound_json, protocol_label, protocol_users, share_links};
+use ezsingbox::autoconfig::{MultiProtocolResult, Protocol};
+
+// `{{{json outbound}}}` 将任意取值输出为 JSON
+handlebars_helper!(json_helper: |v: Value| v.to_string());
+
+/// 构造模板上下文
+///
+/// ```text
+/// { domain, public_ip,
+///   proxies: [{ protocol, label, user, server, port, transport, password, uuid,
+///               sni, public_key, short_id, path, obfs_password, link, outbound }] }
+/// ```
+///
+/// 协议不使用的字段为 null；user 不为空时只保留该用户
+pub fn template_context(result: &MultiProtocolResult, user: Option<&str>) -> Result<Value, String> {
+    let links = share_links(result);
+    let mut proxies = Vec::new();
+    for protocol in Protocol::ALL {
+        for u in protocol_users(result, protocol) {
+            if user.is_some_and(|name| name != u.name) {
+                continue;
+            }
+            let outbound = build_proxy_outbound_json(result, protocol, &u)?;
+            let transport = &outbound["transport"];
+            let link = links
+                .iter()
+                .find(|l| l.protocol == protocol && l.user == u.name)
+                .map(|l| l.link.clone());
+            proxies.push(json!({
+                "protocol": protocol.as_str(),
+                "label": protocol_label(protocol),
+                "user": u.name,
+                "server": outbound["server"],
+                "port": outbound["server_port"],
+                "transport": protocol.transport().as_str(),
+                "password": (!outbound["password"].is_null()).then_some(&u.password),
+                "uuid": u.uuid,
+                "sni": outbound["tls"]["server_name"],
+                "public_key": outbound["tls"]["reality"]["public_key"],
+                "short_id": outbound["tls"]["reality"]["short_id"],
+                "path": transport.get("path").or(transport.get("service_name")),
+                "obfs_password": outbound["obfs"]["password"],
+                "link": link,
+                "outbound": outbound,
+            }));
+        }
+    }
+    if proxies.is_empty() {
+        return Err(match user {
+            Some(name) => format!("未找到用户: {}", name),
+            None => "没有可用协议用于渲染模板".to_string(),
+        });
+    }
+    Ok(json!({
+        "domain": result.domain,
+        "public_ip": result.public_ip.to_string(),
+        "proxies": proxies,
+    }))
+}
+
+/// 渲染模板（输出不做 HTML 转义）
+pub fn render_template(template: &str, context: &Value) -> Result<String, String> {
+    let mut registry = Handlebars::new();
+    registry.register_escape_fn(no_escape);
+    registry.register_helper("json", Box::new(json_helper));
+    registry
+        .render_template(template, context)
+        .map_err(|e| format!("渲染模板失败: {}", e))
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ezsingbox::autoconfig::MultiProtocolBuilder;
+
+    #[test]
+    fn test_render_template() {
+        let result = MultiProtocolBuilder::new()
+            .public_ip("1.2.3.4".parse().unwrap())
+            .domain("example.com")
+            .enable_anytls(443)
+            .enable_tuic(2083)
+            .build()
+            .unwrap();
+        let context = template_context(&result, None).unwrap();
+        assert_eq!(context["proxies"].as_array().unwrap().len(), 2);
+        assert_eq!(context["proxies"][1]["transport"], "udp");
+        assert!(context["proxies"][0]["uuid"].is_null());
+
+        let template = "{{#each proxies}}{{label}} = {{server}}:{{port}}, {{password}}\
+                        {{#if uuid}}, uuid={{uuid}}{{/if}} & {{{json outbound.tls}}}\n{{/each}}";
+        let text = render_template(template, &context).unwrap();
+        let anytls = &context["proxies"][0];
+        assert!(text.starts_with(&format!(
+            "AnyTLS = example.com:443, {} & {{\"enabled\":true,\"server_name\":\"example.com\",\
+             \"utls\":{{\"enabled\":true,\"fingerprint\":\"chrome\"}}}}\n",
+            anytls["password"].as_str().unwrap()
+        )));
+        assert!(text.contains(", uuid="));
+
+        assert!(template_context(&result, Some("nobody")).is_err());
+        assert!(render_template("{{#each proxies}", &context).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! sing-box 升级检查模块
+//!
+//! run 模式下按 EZ_UPGRADE_CHECK_INTERVAL 定期比较已安装的 sing-box 与最新正式版，
+//! 发现新版本时记录日志；启用 EZ_AUTO_UPGRADE 时在维护时间窗口（EZ_UPGRADE_WINDOW，UTC）内
+//! 下载安装新版本，由调用方平滑重启 sing-box
+
+use std::fmt;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+use crate::install::{install_singbox, latest_version};
+
+/// 默认检查间隔
+pub const DEFAULT_UPGRADE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+//============================================================================
+// 维护时间窗口
+//============================================================================
+
+/// 维护时间窗口（UTC，按一天内的分钟计，支持跨零点如 23:00-02:00）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceWindow {
+    /// 开始（含）
+    start: u16,
+    /// 结束（不含）
+    end: u16,
+}
+
+impl MaintenanceWindow {
+    /// 指定分钟（0..1440）是否在窗口内
+    pub fn contains(&self, minute: u16) -> bool {
+        if self.start <= self.end {
+            self.start <= minute && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// 当前时间是否在窗口内
+    pub fn contains_now(&self) -> bool {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.contains(((secs / 60) % (24 * 60)) as u16)
+    }
+}
+
+fn parse_hhmm(s: &str) -> Option<u16> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u16>().ok()?, m.parse::<u16>().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+impl FromStr for MaintenanceWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("无效的维护时间窗口: {}（格式 HH:MM-HH:MM）", s);
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let window = MaintenanceWindow {
+            start: parse_hhmm(start).ok_or_else(invalid)?,
+            end: parse_hhmm(end).ok_or_else(invalid)?,
+        };
+        if window.start == window.end {
+            return Err(invalid());
+        }
+        Ok(window)
+    }
+}
+
+impl fmt::Display for MaintenanceWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60
+        )
+    }
+}
+
+//============================================================================
+// 版本比较
+//============================================================================
+
+/// 从 `sing-box version` 输出中提取版本号（首行 `sing-box version 1.12.8`）
+pub fn parse_version_output(text: &str) -> Option<String> {
+    text.lines()
+        .next()?
+        .trim()
+        .strip_prefix("sing-box version ")
+        .map(|v| v.trim().to_string())
+}
+
+/// 解析 x.y.z 版本号（忽略 -beta.1 等预发布后缀）
+fn version_triple(version: &str) -> Option<(u32, u32, u32)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().flatten().unwrap_or(0);
+    let patch = parts.next().flatten().unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// latest 是否比 installed 新（无法解析时视为不新；同版本的正式版比预发布版新）
+pub fn is_newer(latest: &str, installed: &str) -> bool {
+    match (version_triple(latest), version_triple(installed)) {
+        (Some(l), Some(i)) if l == i => !latest.contains('-') && installed.contains('-'),
+        (Some(l), Some(i)) => l > i,
+        _ => false,
+    }
+}
+
+/// 读取已安装的 sing-box 版本
+pub fn installed_version(sing_box: &str) -> Result<String, String> {
+    let output = Command::new(sing_box)
+        .arg("version")
+        .output()
+        .map_err(|e| format!("执行 {} version 失败: {}", sing_box, e))?;
+    parse_version_output(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("无法识别 {} version 的输出", sing_box))
+}
+
+//============================================================================
+// 定期检查
+//============================================================================
+
+/// 升级检查选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeOptions {
+    /// 检查间隔
+    pub interval: Duration,
+    /// 发现新版本时是否自动升级
+    pub auto_upgrade: bool,
+    /// 自动升级的维护时间窗口（不设置时随时升级）
+    pub window: Option<MaintenanceWindow>,
+    /// 安装前缀
+    pub prefix: String,
+}
+
+/// 升级检查器，由 run 模式的主循环定期调用
+pub struct UpgradeChecker {
+    opts: UpgradeOptions,
+    next_check: Instant,
+    /// 等待维护窗口安装的新版本
+    pending: Option<String>,
+}
+
+impl UpgradeChecker {
+    /// 创建检查器（启动后立即检查一次）
+    pub fn new(opts: UpgradeOptions) -> Self {
+        Self {
+            opts,
+            next_check: Instant::now(),
+            pending: None,
+        }
+    }
+
+    /// 到期时检查新版本；已在维护窗口内安装新版本时返回新二进制文件路径
+    pub fn poll(&mut self, sing_box: &str) -> Option<String> {
+        let now = Instant::now();
+        if now >= self.next_check {
+            self.next_check = now + self.opts.interval;
+            self.check(sing_box);
+        }
+        let version = self.pending.as_ref()?;
+        if !self.opts.window.is_none_or(|w| w.contains_now()) {
+            return None;
+        }
+        info!(version = %version, "开始升级 sing-box");
+        let installed = install_singbox(Some(version), &self.opts.prefix);
+        self.pending = None;
+        match installed {
+            Ok((version, path)) => {
+                info!(version = %version, path = %path, "sing-box 已升级");
+                Some(path)
+            }
+            Err(e) => {
+                warn!("升级 sing-box 失败: {}", e);
+                None
+            }
+        }
+    }
+
+    fn check(&mut self, sing_box: &str) {
+        let installed = match installed_version(sing_box) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("检查 sing-box 更新失败: {}", e);
+                return;
+            }
+        };
+        let latest = match latest_version() {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("检查 sing-box 更新失败: {}", e);
+                return;
+            }
+        };
+        if !is_newer(&latest, &installed) {
+            info!(installed = %installed, "sing-box 已是最新版本");
+            return;
+        }
+        if self.opts.auto_upgrade {
+            match self.opts.window {
+                Some(window) => {
+                    info!(installed = %installed, latest = %latest, window = %window, "发现 sing-box 新版本，将在维护窗口内升级")
+                }
+                None => info!(installed = %installed, latest = %latest, "发现 sing-box 新版本"),
+            }
+            self.pending = Some(latest);
+        } else {
+            warn!(installed = %installed, latest = %latest, "发现 sing-box 新版本（设置 EZ_AUTO_UPGRADE=true 自动升级）");
+        }
+    }
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance_window() {
+        let w: MaintenanceWindow = "03:00-05:30".parse().unwrap();
+        assert_eq!(w.to_string(), "03:00-05:30");
+        assert!(w.contains(3 * 60));
+        assert!(w.contains(5 * 60 + 29));
+        assert!(!w.contains(5 * 60 + 30));
+        assert!(!w.contains(0));
+
+        let w: MaintenanceWindow = "23:00-02:00".parse().unwrap();
+        assert!(w.contains(23 * 60 + 30));
+        assert!(w.contains(60));
+        assert!(!w.contains(12 * 60));
+
+        for bad in ["03:00", "24:00-01:00", "03:00-03:00", "3-5"] {
+            assert!(bad.parse::<MaintenanceWindow>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_versions() {
+        let output = "sing-box version 1.12.8\n\nEnvironment: go1.24.5 linux/amd64\n";
+        assert_eq!(parse_version_output(output).as_deref(), Some("1.12.8"));
+        assert_eq!(parse_version_output("command not found"), None);
+
+        assert!(is_newer("1.12.9", "1.12.8"));
+        assert!(is_newer("1.13.0", "1.12.10"));
+        assert!(!is_newer("1.12.8", "1.12.8"));
+        assert!(!is_newer("1.12.8", "1.13.0-beta.1"));
+        assert!(is_newer("1.13.0", "1.13.0-beta.1"));
+        assert!(!is_newer("garbage", "1.12.8"));
+    }
+}
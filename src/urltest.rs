@@ -0,0 +1,239 @@
+//! 出站延迟测试模块
+//!
+//! 为每个协议的出站分配一个本地 mixed 入站，通过临时 sing-box 实例
+//! 以 HTTP 代理方式请求测试地址，记录各协议的响应耗时
+
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::time::{Duration as StdDuration, Instant};
+
+use ezsingbox::autoconfig::Protocol;
+
+/// 默认测试地址
+pub const DEFAULT_URLTEST_URL: &str = "http://www.gstatic.com/generate_204";
+
+/// 默认单次测试超时
+pub const DEFAULT_URLTEST_TIMEOUT: StdDuration = StdDuration::from_secs(5);
+
+//============================================================================
+// 测试地址
+//============================================================================
+
+/// HTTP 测试地址（只支持 http://，请求经代理转发后由服务端直接访问）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpTarget {
+    /// 主机名
+    pub host: String,
+    /// 端口
+    pub port: u16,
+    /// 请求路径（含查询参数）
+    pub path: String,
+}
+
+impl FromStr for HttpTarget {
+    type Err = String;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("测试地址只支持 http://: {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        // IPv6 地址写作 [::1]，端口位于右方括号之后
+        let split_at = match authority.rfind(']') {
+            Some(i) => authority[i..].find(':').map(|j| i + j),
+            None => authority.rfind(':'),
+        };
+        let (host, port) = match split_at {
+            Some(i) => (
+                &authority[..i],
+                authority[i + 1..]
+                    .parse::<u16>()
+                    .map_err(|_| format!("测试地址端口无效: {}", url))?,
+            ),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("测试地址缺少主机名: {}", url));
+        }
+        Ok(HttpTarget {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+//============================================================================
+// 测试
+//============================================================================
+
+/// 申请一个空闲的本地端口
+pub fn free_port() -> std::io::Result<u16> {
+    Ok(TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port())
+}
+
+/// 等待本地端口开始监听，超过 timeout 仍未监听时返回 false
+pub fn wait_for_port(addr: SocketAddr, timeout: StdDuration) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if TcpStream::connect_timeout(&addr, StdDuration::from_millis(200)).is_ok() {
+            return true;
+        }
+        std::thread::sleep(StdDuration::from_millis(100));
+    }
+    false
+}
+
+/// 通过 HTTP 代理请求测试地址，返回收到响应状态行的耗时
+pub fn url_test(
+    proxy: SocketAddr,
+    target: &HttpTarget,
+    timeout: StdDuration,
+) -> Result<StdDuration, String> {
+    let start = Instant::now();
+    let mut stream =
+        TcpStream::connect_timeout(&proxy, timeout).map_err(|e| format!("无法连接代理: {}", e))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| e.to_string())?;
+    write!(
+        stream,
+        "GET http://{host}:{port}{path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n\r\n",
+        host = target.host,
+        port = target.port,
+        path = target.path
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut status_line)
+        .map_err(|e| format!("等待响应超时: {}", e))?;
+    let elapsed = start.elapsed();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(|| "代理未返回有效的 HTTP 响应".to_string())?;
+    if (200..400).contains(&status) {
+        Ok(elapsed)
+    } else {
+        Err(format!("HTTP {}", status))
+    }
+}
+
+//============================================================================
+// 报告
+//============================================================================
+
+/// 单个协议的测试结果
+#[derive(Debug)]
+pub struct LatencyResult {
+    /// 协议
+    pub protocol: Protocol,
+    /// 成功时为延迟，失败时为原因
+    pub result: Result<StdDuration, String>,
+}
+
+/// 延迟最低的协议
+pub fn fastest(results: &[LatencyResult]) -> Option<Protocol> {
+    results
+        .iter()
+        .filter_map(|r| r.result.as_ref().ok().map(|d| (r.protocol, *d)))
+        .min_by_key(|(_, d)| *d)
+        .map(|(p, _)| p)
+}
+
+/// 渲染测试报告，并给出推荐的 EZ_CLIENT_PROTOCOL
+pub fn render_latency(results: &[LatencyResult]) -> String {
+    let best = fastest(results);
+    let mut out = String::new();
+    for r in results {
+        match &r.result {
+            Ok(latency) => {
+                let mark = if Some(r.protocol) == best { " ⭐" } else { "" };
+                let _ = writeln!(
+                    out,
+                    "✅ {:<14} {} ms{}",
+                    r.protocol.as_str(),
+                    latency.as_millis(),
+                    mark
+                );
+            }
+            Err(reason) => {
+                let _ = writeln!(out, "❌ {:<14} {}", r.protocol.as_str(), reason);
+            }
+        }
+    }
+    if let Some(best) = best {
+        let _ = writeln!(out, "\n推荐: EZ_CLIENT_PROTOCOL={}", best.as_str());
+    }
+    out
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_parse_target() {
+        let target: HttpTarget = DEFAULT_URLTEST_URL.parse().unwrap();
+        assert_eq!(target.host, "www.gstatic.com");
+        assert_eq!(target.port, 80);
+        assert_eq!(target.path, "/generate_204");
+
+        let target: HttpTarget = "http://[::1]:8080".parse().unwrap();
+        assert_eq!(target.host, "[::1]");
+        assert_eq!(target.port, 8080);
+        assert_eq!(target.path, "/");
+        assert_eq!("http://[::1]/".parse::<HttpTarget>().unwrap().port, 80);
+        assert!("https://cp.cloudflare.com".parse::<HttpTarget>().is_err());
+        assert!("http://:80/".parse::<HttpTarget>().is_err());
+    }
+
+    #[test]
+    fn test_url_test_through_proxy() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let proxy = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 512];
+            let n = conn.read(&mut buf).unwrap();
+            conn.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        });
+
+        let target: HttpTarget = DEFAULT_URLTEST_URL.parse().unwrap();
+        let result = url_test(proxy, &target, DEFAULT_URLTEST_TIMEOUT);
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET http://www.gstatic.com:80/generate_204 HTTP/1.1\r\n"));
+
+        let results = [
+            LatencyResult {
+                protocol: Protocol::Tuic,
+                result: Err("HTTP 502".to_string()),
+            },
+            LatencyResult {
+                protocol: Protocol::AnyTls,
+                result,
+            },
+        ];
+        assert_eq!(fastest(&results), Some(Protocol::AnyTls));
+        let report = render_latency(&results);
+        assert!(report.contains("❌ tuic"));
+        assert!(report.ends_with("推荐: EZ_CLIENT_PROTOCOL=anytls\n"));
+    }
+}
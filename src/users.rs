@@ -0,0 +1,195 @@
+//! 用户列表模块
+//!
+//! 用户条目来自 EZ_USER / EZ_USERS / EZ_USERS_JSON，以及 `user add` 写入状态文件的用户；
+//! 条目可带过期时间，过期用户在重新生成配置时被移除
+
+use serde::{Deserialize, Serialize};
+
+use crate::cert::days_from_civil;
+use crate::env::env_string;
+use ezsingbox::autoconfig::{GeneratedUser, generate_uuid};
+
+/// 用户条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserSpec {
+    /// 用户名
+    pub name: String,
+    /// 指定的密码（不指定时随机生成）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// 过期时间（Unix 时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl UserSpec {
+    /// 创建不过期的用户条目
+    pub fn new(name: impl Into<String>, password: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            password,
+            expires_at: None,
+        }
+    }
+
+    /// 生成用户凭证（未指定的密码与 UUID 随机生成）
+    pub fn generate(&self) -> GeneratedUser {
+        let user = match self.password {
+            Some(ref pwd) => GeneratedUser::with_credentials(&self.name, pwd, generate_uuid()),
+            None => GeneratedUser::with_uuid(&self.name),
+        };
+        match self.expires_at {
+            Some(t) => user.with_expires_at(t),
+            None => user,
+        }
+    }
+}
+
+/// 检查用户名（不能为空或包含 ':' ','，以便与 EZ_USERS 格式互通）
+pub fn validate_user_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.contains([':', ',']) {
+        return Err(format!(
+            "无效的用户名: \"{}\"（不能为空或包含 ':' ','）",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// 解析过期时间（UTC）
+/// `YYYY-MM-DD` 表示当天结束时过期；也支持 `YYYY-MM-DD HH:MM[:SS]`、`YYYY-MM-DDTHH:MM:SSZ` 与 Unix 时间戳
+pub fn parse_expiry(raw: &str) -> Result<i64, String> {
+    let invalid = || {
+        format!(
+            "无效的过期时间: {}（格式 YYYY-MM-DD 或 YYYY-MM-DD HH:MM:SS，UTC）",
+            raw
+        )
+    };
+    let s = raw.trim();
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        return s.parse().map_err(|_| invalid());
+    }
+    let s = s
+        .strip_suffix('Z')
+        .or_else(|| s.strip_suffix(" UTC"))
+        .unwrap_or(s);
+    let (date, time) = match s.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+    let mut date_parts = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (Some(Some(year)), Some(Some(month)), Some(Some(day)), None) = (
+        date_parts.next(),
+        date_parts.next(),
+        date_parts.next(),
+        date_parts.next(),
+    ) else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    let days = days_from_civil(year, month, day);
+    let Some(time) = time else {
+        return Ok((days + 1) * 86400);
+    };
+    let fields: Vec<Option<i64>> = time.split(':').map(|p| p.parse().ok()).collect();
+    let (hour, minute, second) = match fields[..] {
+        [Some(h), Some(m)] => (h, m, 0),
+        [Some(h), Some(m), Some(s)] => (h, m, s),
+        _ => return Err(invalid()),
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+    Ok(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// EZ_USERS_JSON 中的用户条目
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserJsonEntry {
+    name: String,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+/// 解析 EZ_USERS_JSON（`[{"name": "alice", "password": "...", "expires_at": "2025-12-31"}]`）
+pub fn parse_users_json(raw: &str) -> Result<Vec<UserSpec>, String> {
+    let entries: Vec<UserJsonEntry> = serde_json::from_str(raw)
+        .map_err(|e| format!("EZ_USERS_JSON 不是有效的用户列表: {}", e))?;
+    entries
+        .into_iter()
+        .map(|e| {
+            validate_user_name(&e.name)?;
+            Ok(UserSpec {
+                name: e.name.trim().to_string(),
+                password: e.password.filter(|p| !p.is_empty()),
+                expires_at: e.expires_at.as_deref().map(parse_expiry).transpose()?,
+            })
+        })
+        .collect()
+}
+
+/// 合并用户列表：环境变量中的用户优先，`user add` 添加的同名用户被忽略
+/// 环境变量未指定用户时保留 default 用户（密码取自 EZ_PASSWORD）
+pub fn merge_users(mut users: Vec<UserSpec>, added: &[UserSpec]) -> Vec<UserSpec> {
+    if users.is_empty() {
+        users.push(UserSpec::new("default", env_string("EZ_PASSWORD")));
+    }
+    for spec in added {
+        if !users.iter().any(|u| u.name == spec.name) {
+            users.push(spec.clone());
+        }
+    }
+    users
+}
+
+//============================================================================
+// 单元测试
+//============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expiry() {
+        // 2025-12-31 当天结束 = 2026-01-01 00:00:00 UTC
+        assert_eq!(parse_expiry("2025-12-31"), Ok(1767225600));
+        assert_eq!(parse_expiry("2025-12-31 12:30"), Ok(1767184200));
+        assert_eq!(parse_expiry("2025-12-31T12:30:15Z"), Ok(1767184215));
+        assert_eq!(parse_expiry("2025-12-31 12:30:15 UTC"), Ok(1767184215));
+        assert_eq!(parse_expiry("1767225600"), Ok(1767225600));
+        for bad in ["", "2025-13-01", "2025-12", "2025-12-31 24:00", "tomorrow"] {
+            assert!(parse_expiry(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_users_json() {
+        let users = parse_users_json(
+            r#"[{"name": "alice", "expires_at": "2025-12-31"}, {"name": "bob", "password": "pw"}]"#,
+        )
+        .unwrap();
+        assert_eq!(users[0].expires_at, Some(1767225600));
+        assert_eq!(users[1], UserSpec::new("bob", Some("pw".to_string())));
+        let generated = users[0].generate();
+        assert!(generated.uuid.is_some());
+        assert!(generated.is_expired(1767225600));
+
+        assert!(parse_users_json(r#"[{"name": "a:b"}]"#).is_err());
+        assert!(parse_users_json(r#"[{"name": "a", "expires_at": "soon"}]"#).is_err());
+        assert!(parse_users_json(r#"[{"name": "a", "quota": 1}]"#).is_err());
+
+        let merged = merge_users(
+            users.clone(),
+            &[UserSpec::new("bob", None), UserSpec::new("carol", None)],
+        );
+        let names: Vec<&str> = merged.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["alice", "bob", "carol"]);
+        assert_eq!(merged[1].password.as_deref(), Some("pw"));
+    }
+}
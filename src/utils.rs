@@ -1,5 +1,7 @@
 //! 文件系统和工具函数模块
 
+use std::fs::File;
+use std::io::Write;
 use std::path::Path;
 
 use crate::env::env_string;
@@ -15,6 +17,30 @@ pub fn ensure_parent_dir(path: &str) -> std::io::Result<()> {
     std::fs::create_dir_all(parent)
 }
 
+/// 与目标文件同目录的临时文件路径（保证重命名在同一文件系统内完成）
+pub fn temp_sibling(path: &str) -> String {
+    let target = Path::new(path);
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = format!(".{}.tmp-{}", name, std::process::id());
+    match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.join(tmp).to_string_lossy().into_owned(),
+        _ => tmp,
+    }
+}
+
+/// 写入临时文件并同步到磁盘，目标文件已存在时沿用其权限
+pub fn write_synced(tmp: &str, data: &[u8], target: &str) -> std::io::Result<()> {
+    let mut file = File::create(tmp)?;
+    file.write_all(data)?;
+    if let Ok(meta) = std::fs::metadata(target) {
+        file.set_permissions(meta.permissions())?;
+    }
+    file.sync_all()
+}
+
 /// 选择 sing-box 二进制文件路径
 pub fn pick_sing_box_bin() -> String {
     if let Some(v) = env_string("SING_BOX_BIN") {
@@ -0,0 +1,328 @@
+//! 序列化往返测试
+//!
+//! tests/fixtures/<版本>/ 下是按协议整理的 sing-box 示例配置。每个配置都应当：
+//! 以 SingBoxConfig 解析后原样序列化回来；严格模式下没有未知字段；
+//! 有强类型定义的每一段（DNS、入站 / 出站 / 端点、路由规则与规则集）单独往返后与原文一致，
+//! 且往返结果再次解析得到相同的强类型值。
+//! 另有基于 proptest 的属性测试，覆盖自定义序列化的基础类型
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proptest::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use ezsingbox::dns::Dns;
+use ezsingbox::singboxconfig::endpoint::WireGuardEndpoint;
+use ezsingbox::singboxconfig::full::SingBoxConfig;
+use ezsingbox::singboxconfig::inbound::{
+    AnyTlsInbound, Hysteria2Inbound, TuicInbound, TunInbound, VlessInbound,
+};
+use ezsingbox::singboxconfig::jsonc::{Annotations, from_jsonc_str, to_jsonc_string};
+use ezsingbox::singboxconfig::migrate::migrate_config;
+use ezsingbox::singboxconfig::minimal::minimize_config;
+use ezsingbox::singboxconfig::outbound::{AnyTlsOutbound, BlockOutbound, DirectOutbound};
+use ezsingbox::singboxconfig::route::{RouteRule, RuleSet};
+use ezsingbox::singboxconfig::strict::strict_diagnostics;
+use ezsingbox::singboxconfig::types::{
+    Duration, PortList, PortRange, PortRangeList, RoutingMark, StringOrArray,
+};
+
+//============================================================================
+// 示例配置
+//============================================================================
+
+/// 全部示例配置（按路径排序）
+fn fixtures() -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut files = Vec::new();
+    for version in fs::read_dir(&root).unwrap() {
+        for entry in fs::read_dir(version.unwrap().path()).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// 读取示例配置
+fn load(path: &Path) -> Value {
+    let text = fs::read_to_string(path).unwrap();
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// 解析为 T 再序列化，结果须与原值一致；再次解析须得到相同的 T
+fn assert_round_trip<T>(value: &Value, context: &str)
+where
+    T: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug,
+{
+    let parsed: T = serde_json::from_value(value.clone())
+        .unwrap_or_else(|e| panic!("{}: 解析失败: {}", context, e));
+    let round_trip = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(&round_trip, value, "{}: 往返后不一致", context);
+    let reparsed: T = serde_json::from_value(round_trip)
+        .unwrap_or_else(|e| panic!("{}: 再次解析失败: {}", context, e));
+    assert_eq!(reparsed, parsed, "{}: 再次解析后不一致", context);
+}
+
+#[test]
+fn test_fixture_corpus() {
+    let files = fixtures();
+    assert!(files.len() >= 8, "示例配置缺失");
+    for version in ["1.11", "1.12"] {
+        assert!(
+            files.iter().any(|f| f.parent().unwrap().ends_with(version)),
+            "缺少 {} 版本的示例配置",
+            version
+        );
+    }
+}
+
+#[test]
+fn test_full_config_round_trip() {
+    for path in fixtures() {
+        let value = load(&path);
+        let context = path.display().to_string();
+        assert_round_trip::<SingBoxConfig>(&value, &context);
+
+        let diagnostics = strict_diagnostics(&value);
+        assert!(diagnostics.is_empty(), "{}: {:?}", context, diagnostics);
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(
+            SingBoxConfig::from_json_strict(&text).is_ok(),
+            "{}",
+            context
+        );
+    }
+}
+
+#[test]
+fn test_typed_sections_round_trip() {
+    for path in fixtures() {
+        let value = load(&path);
+        let name = path.display().to_string();
+
+        if let Some(dns) = value.get("dns") {
+            assert_round_trip::<Dns>(dns, &format!("{}#/dns", name));
+        }
+        for (i, inbound) in items(&value, "inbounds") {
+            let context = format!("{}#/inbounds/{}", name, i);
+            match inbound["type"].as_str().unwrap() {
+                "anytls" => assert_round_trip::<AnyTlsInbound>(inbound, &context),
+                "hysteria2" => assert_round_trip::<Hysteria2Inbound>(inbound, &context),
+                "tuic" => assert_round_trip::<TuicInbound>(inbound, &context),
+                "tun" => assert_round_trip::<TunInbound>(inbound, &context),
+                "vless" => assert_round_trip::<VlessInbound>(inbound, &context),
+                other => panic!("{}: 没有强类型定义的入站类型 {}", context, other),
+            }
+        }
+        for (i, outbound) in items(&value, "outbounds") {
+            let context = format!("{}#/outbounds/{}", name, i);
+            match outbound["type"].as_str().unwrap() {
+                "anytls" => assert_round_trip::<AnyTlsOutbound>(outbound, &context),
+                "block" => assert_round_trip::<BlockOutbound>(outbound, &context),
+                "direct" => assert_round_trip::<DirectOutbound>(outbound, &context),
+                other => panic!("{}: 没有强类型定义的出站类型 {}", context, other),
+            }
+        }
+        for (i, endpoint) in items(&value, "endpoints") {
+            let context = format!("{}#/endpoints/{}", name, i);
+            assert_round_trip::<WireGuardEndpoint>(endpoint, &context);
+        }
+        let route = value.get("route").cloned().unwrap_or_default();
+        for (i, rule) in items(&route, "rules") {
+            assert_round_trip::<RouteRule>(rule, &format!("{}#/route/rules/{}", name, i));
+        }
+        for (i, rule_set) in items(&route, "rule_set") {
+            assert_round_trip::<RuleSet>(rule_set, &format!("{}#/route/rule_set/{}", name, i));
+        }
+    }
+}
+
+#[test]
+fn test_minimized_fixtures() {
+    for path in fixtures() {
+        let mut value = load(&path);
+        let context = path.display().to_string();
+        minimize_config(&mut value);
+        let diagnostics = strict_diagnostics(&value);
+        assert!(diagnostics.is_empty(), "{}: {:?}", context, diagnostics);
+        assert_round_trip::<SingBoxConfig>(&value, &context);
+
+        let mut again = value.clone();
+        minimize_config(&mut again);
+        assert_eq!(again, value, "{}: 最小化不是幂等的", context);
+    }
+}
+
+#[test]
+fn test_annotated_fixtures() {
+    for path in fixtures() {
+        let value = load(&path);
+        let context = path.display().to_string();
+        // 每个字段与数组元素前都加注释
+        let mut notes = Annotations::new();
+        collect_pointers(&value, "", &mut notes);
+        let text = to_jsonc_string(&value, &notes);
+        assert_eq!(
+            from_jsonc_str::<Value>(&text).unwrap(),
+            value,
+            "{}",
+            context
+        );
+        let parsed = SingBoxConfig::from_jsonc(&text).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), value, "{}", context);
+    }
+}
+
+#[test]
+fn test_migrated_fixtures() {
+    for path in fixtures() {
+        let value = load(&path);
+        let context = path.display().to_string();
+        let migration = migrate_config(&value, "").unwrap();
+        let diagnostics = strict_diagnostics(&migration.config);
+        assert!(diagnostics.is_empty(), "{}: {:?}", context, diagnostics);
+        assert_round_trip::<SingBoxConfig>(&migration.config, &context);
+
+        let again = migrate_config(&migration.config, "").unwrap();
+        assert!(again.changes.is_empty(), "{}: {:?}", context, again.changes);
+    }
+}
+
+/// 收集值中全部字段与数组元素的 JSON Pointer
+fn collect_pointers(value: &Value, pointer: &str, out: &mut Annotations) {
+    out.insert(pointer.to_string(), format!("注释 {} // */", pointer));
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| (k.replace('~', "~0").replace('/', "~1"), v))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i.to_string(), v))
+            .collect(),
+        _ => Vec::new(),
+    };
+    for (segment, child) in children {
+        collect_pointers(child, &format!("{}/{}", pointer, segment), out);
+    }
+}
+
+/// 数组字段中的元素及其下标
+fn items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = (usize, &'a Value)> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+}
+
+//============================================================================
+// 属性测试
+//============================================================================
+
+/// 序列化后再解析得到相同的值
+fn assert_serde_identity<T>(value: &T)
+where
+    T: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    let parsed: T =
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("{:?} -> {}: {}", value, json, e));
+    assert_eq!(&parsed, value, "{}", json);
+}
+
+/// 随机时长（毫秒），偏向整秒与整分钟
+fn millis() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        0..1000u64,
+        (0..100_000u64).prop_map(|s| s * 1000),
+        (0..10_000u64).prop_map(|m| m * 60_000),
+        0..u64::from(u32::MAX),
+    ]
+}
+
+/// 随机端口范围
+fn port_range() -> impl Strategy<Value = PortRange> {
+    (any::<u16>(), any::<u16>()).prop_map(|(start, end)| PortRange::new(start, end))
+}
+
+/// 逗号连接
+fn join<T: ToString>(items: &[T]) -> String {
+    items.iter().map(T::to_string).collect::<Vec<_>>().join(",")
+}
+
+proptest! {
+    #[test]
+    fn prop_duration_round_trip(millis in millis()) {
+        let duration = Duration::from_millis(millis);
+        let text = duration.to_string();
+        let parsed: Duration = text.parse().unwrap();
+        prop_assert_eq!(parsed.as_millis(), millis, "{}", text);
+        prop_assert_eq!(parsed.to_string(), text);
+        assert_serde_identity(&duration);
+    }
+
+    #[test]
+    fn prop_duration_compound(h in 0..48u64, m in 0..60u64, s in 0..60u64) {
+        // 多段写法与整数秒同样可以解析
+        let parsed: Duration = format!("{}h{}m{}s", h, m, s).parse().unwrap();
+        prop_assert_eq!(parsed.as_secs(), h * 3600 + m * 60 + s);
+        let secs: Duration = serde_json::from_value(serde_json::json!(s)).unwrap();
+        prop_assert_eq!(secs, Duration::from_secs(s));
+    }
+
+    #[test]
+    fn prop_port_range_round_trip(range in port_range()) {
+        prop_assert_eq!(range.to_string().parse::<PortRange>(), Ok(range));
+        prop_assert_eq!(
+            format!("{}-{}", range.start, range.end).parse::<PortRange>(),
+            Ok(range)
+        );
+        assert_serde_identity(&range);
+    }
+
+    #[test]
+    fn prop_port_list_round_trip(ports in prop::collection::vec(any::<u16>(), 1..8)) {
+        let list: PortList = join(&ports).parse().unwrap();
+        prop_assert_eq!(list.as_slice(), ports.as_slice());
+        assert_serde_identity(&list);
+        assert_serde_identity(&PortList::from(ports));
+    }
+
+    #[test]
+    fn prop_port_range_list_round_trip(ranges in prop::collection::vec(port_range(), 1..8)) {
+        let list: PortRangeList = join(&ranges).parse().unwrap();
+        prop_assert_eq!(list.as_slice(), ranges.as_slice());
+        assert_serde_identity(&list);
+        assert_serde_identity(&PortRangeList::from(ranges));
+    }
+
+    #[test]
+    fn prop_string_or_array_round_trip(values in prop::collection::vec(".{0,12}", 0..6)) {
+        let array = StringOrArray::array(values.clone());
+        assert_serde_identity(&array);
+        prop_assert_eq!(array.clone().into_vec(), values.clone());
+        prop_assert_eq!(array.normalize().into_vec(), values.clone());
+        if let Some(first) = values.first() {
+            assert_serde_identity(&StringOrArray::single(first.clone()));
+        }
+    }
+
+    #[test]
+    fn prop_routing_mark_round_trip(value in any::<u32>()) {
+        let int = RoutingMark::from_int(value);
+        let hex = RoutingMark::from_hex(format!("{:#x}", value));
+        assert_serde_identity(&int);
+        assert_serde_identity(&hex);
+        prop_assert_eq!(hex.as_u32(), Some(value));
+    }
+}